@@ -0,0 +1,389 @@
+//! Measures the compute units consumed by each instruction of the swap lifecycle, against
+//! builds of both the Anchor program and its lite variant, executed in LiteSVM.
+//!
+//! The programs are loaded from `target/deploy`, as built by `anchor build` and
+//! `cargo build-sbf --manifest-path native/solana-native-swaps-lite/Cargo.toml` respectively.
+//! Set `SWAPS_PROGRAM_SO` or `SWAPS_LITE_PROGRAM_SO` to benchmark a build elsewhere instead,
+//! e.g. that of an earlier commit.
+
+use std::path::PathBuf;
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{hash::hash, instruction::Instruction, system_program},
+    Discriminator, InstructionData, ToAccountMetas,
+};
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_native_swaps::{HashAlgorithm, RedeemWindow, SwapIncentives, MAX_EXPIRY_SLOTS};
+use solana_native_swaps_lite::SwapInstruction;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+/// The swap amount used throughout the benchmarks
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+/// The expiry used throughout the benchmarks
+const EXPIRES_IN_SLOTS: u64 = 100;
+
+/// The lifecycle instructions common to both programs
+pub const INSTRUCTIONS: [&str; 4] = ["initiate", "redeem", "refund", "instant_refund"];
+
+/// A build of the swap logic to be benchmarked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// The Anchor program under `programs/`
+    Anchor,
+    /// The raw Solana program under `native/`
+    Lite,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Anchor, Variant::Lite];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variant::Anchor => "solana-native-swaps",
+            Variant::Lite => "solana-native-swaps-lite",
+        }
+    }
+
+    /// The maximum compute units each of `INSTRUCTIONS` may consume, in order.
+    /// Lower these as optimizations land, and raise them only deliberately.
+    pub fn budgets(&self) -> [u64; 4] {
+        match self {
+            Variant::Anchor => [40_000, 35_000, 25_000, 25_000],
+            Variant::Lite => [5_000, 5_000, 5_000, 5_000],
+        }
+    }
+
+    /// The path of the build of this variant
+    pub fn program_path(&self) -> PathBuf {
+        let (env, file) = match self {
+            Variant::Anchor => ("SWAPS_PROGRAM_SO", "solana_native_swaps.so"),
+            Variant::Lite => ("SWAPS_LITE_PROGRAM_SO", "solana_native_swaps_lite.so"),
+        };
+        std::env::var_os(env).map(PathBuf::from).unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../../target/deploy")
+                .join(file)
+        })
+    }
+
+    fn program_id(&self) -> Pubkey {
+        match self {
+            Variant::Anchor => solana_native_swaps::ID,
+            Variant::Lite => solana_native_swaps_lite::ID,
+        }
+    }
+}
+
+/// The compute units consumed by an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    pub instruction: &'static str,
+    pub compute_units: u64,
+    pub budget: u64,
+}
+
+impl Measurement {
+    pub fn within_budget(&self) -> bool {
+        self.compute_units <= self.budget
+    }
+}
+
+/// Runs each of `INSTRUCTIONS` against a fresh swap of the given variant,
+/// returning the compute units consumed by each
+pub fn measure(variant: Variant) -> Result<Vec<Measurement>, String> {
+    let mut bench = Bench::new(variant)?;
+    let consumed = [
+        bench.initiate(b"initiate")?,
+        {
+            bench.initiate(b"redeem")?;
+            bench.redeem(b"redeem")?
+        },
+        {
+            bench.initiate(b"refund")?;
+            bench.refund(b"refund")?
+        },
+        {
+            bench.initiate(b"instant_refund")?;
+            bench.instant_refund(b"instant_refund")?
+        },
+    ];
+
+    Ok(INSTRUCTIONS
+        .into_iter()
+        .zip(consumed)
+        .zip(variant.budgets())
+        .map(|((instruction, compute_units), budget)| Measurement {
+            instruction,
+            compute_units,
+            budget,
+        })
+        .collect())
+}
+
+/// A LiteSVM instance with a variant of the program deployed, and funded swap parties
+struct Bench {
+    svm: LiteSVM,
+    variant: Variant,
+    initiator: Keypair,
+    redeemer: Keypair,
+}
+
+impl Bench {
+    fn new(variant: Variant) -> Result<Self, String> {
+        let path = variant.program_path();
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(variant.program_id(), &path)
+            .map_err(|e| format!("Failed to load {}: {e}", path.display()))?;
+
+        let (initiator, redeemer) = (Keypair::new(), Keypair::new());
+        for party in [&initiator, &redeemer] {
+            svm.airdrop(&party.pubkey(), 100 * AMOUNT_LAMPORTS)
+                .map_err(|e| format!("Airdrop failed: {:?}", e.err))?;
+        }
+        if variant == Variant::Anchor {
+            Self::set_up_config(&mut svm, &initiator.pubkey())?;
+        }
+
+        Ok(Bench {
+            svm,
+            variant,
+            initiator,
+            redeemer,
+        })
+    }
+
+    /// Writes the config and treasury PDAs directly, as `initialize_config` requires the
+    /// program to be deployed through the upgradeable loader
+    fn set_up_config(svm: &mut LiteSVM, admin: &Pubkey) -> Result<(), String> {
+        let config = [
+            solana_native_swaps::Config::DISCRIMINATOR,
+            admin.as_ref(),
+            &1u64.to_le_bytes(),
+            &MAX_EXPIRY_SLOTS.to_le_bytes(),
+            // A non-zero protocol fee, for redeems to exercise the transfer to the treasury
+            &10u16.to_le_bytes(),
+            &[false as u8],
+        ]
+        .concat();
+        let treasury = solana_native_swaps::Treasury::DISCRIMINATOR.to_vec();
+
+        for (seed, data) in [(&b"config"[..], config), (b"treasury", treasury)] {
+            let (address, _) = Pubkey::find_program_address(&[seed], &solana_native_swaps::ID);
+            let account = Account {
+                lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: solana_native_swaps::ID,
+                executable: false,
+                rent_epoch: 0,
+            };
+            svm.set_account(address, account)
+                .map_err(|e| format!("Failed to set {address}: {e}"))?;
+        }
+        Ok(())
+    }
+
+    fn swap_account(&self, secret: &[u8]) -> (Pubkey, u8) {
+        let (initiator, redeemer) = (self.initiator.pubkey(), self.redeemer.pubkey());
+        let secret_hash = secret_hash(secret);
+        Pubkey::find_program_address(
+            &[
+                b"swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                &secret_hash,
+            ],
+            &self.variant.program_id(),
+        )
+    }
+
+    fn initiate(&mut self, secret: &[u8]) -> Result<u64, String> {
+        let (initiator, redeemer) = (self.initiator.pubkey(), self.redeemer.pubkey());
+        let (swap_account, bump) = self.swap_account(secret);
+        let secret_hash = secret_hash(secret);
+        let instruction = match self.variant {
+            Variant::Anchor => anchor_instruction(
+                solana_native_swaps::instruction::Initiate {
+                    amount_lamports: AMOUNT_LAMPORTS,
+                    expires_in_slots: EXPIRES_IN_SLOTS,
+                    redeemer,
+                    secret_hash,
+                    hash_algorithm: HashAlgorithm::Sha256,
+                    incentives: SwapIncentives::default(),
+                    redeem_window: RedeemWindow::default(),
+                },
+                solana_native_swaps::accounts::Initiate {
+                    swap_account,
+                    config: pda(b"config"),
+                    initiator,
+                    funder: None,
+                    refund_to: None,
+                    system_program: system_program::ID,
+                    event_authority: pda(b"__event_authority"),
+                    program: solana_native_swaps::ID,
+                },
+            ),
+            Variant::Lite => Instruction::new_with_bytes(
+                solana_native_swaps_lite::ID,
+                &SwapInstruction::Initiate {
+                    amount_lamports: AMOUNT_LAMPORTS,
+                    expires_in_slots: EXPIRES_IN_SLOTS,
+                    redeemer,
+                    secret_hash,
+                    bump,
+                }
+                .pack(),
+                vec![
+                    AccountMeta::new(initiator, true),
+                    AccountMeta::new(swap_account, false),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            ),
+        };
+        self.send(instruction, &[])
+    }
+
+    fn redeem(&mut self, secret: &[u8]) -> Result<u64, String> {
+        let (initiator, redeemer) = (self.initiator.pubkey(), self.redeemer.pubkey());
+        let (swap_account, _) = self.swap_account(secret);
+        let secret: [u8; 32] = secret_preimage(secret);
+        let instruction = match self.variant {
+            Variant::Anchor => anchor_instruction(
+                solana_native_swaps::instruction::Redeem {
+                    secret,
+                    amount_lamports: AMOUNT_LAMPORTS,
+                },
+                solana_native_swaps::accounts::Redeem {
+                    swap_account,
+                    initiator,
+                    redeemer,
+                    destination: None,
+                    config: pda(b"config"),
+                    treasury: pda(b"treasury"),
+                    relayer: None,
+                    receipt: None,
+                    receipt_payer: None,
+                    system_program: system_program::ID,
+                    event_authority: pda(b"__event_authority"),
+                    program: solana_native_swaps::ID,
+                },
+            ),
+            Variant::Lite => Instruction::new_with_bytes(
+                solana_native_swaps_lite::ID,
+                &SwapInstruction::Redeem { secret }.pack(),
+                vec![
+                    AccountMeta::new(swap_account, false),
+                    AccountMeta::new(redeemer, false),
+                    AccountMeta::new(initiator, false),
+                ],
+            ),
+        };
+        self.send(instruction, &[])
+    }
+
+    fn refund(&mut self, secret: &[u8]) -> Result<u64, String> {
+        let initiator = self.initiator.pubkey();
+        let (swap_account, _) = self.swap_account(secret);
+        let slot = self.svm.get_sysvar::<anchor_lang::prelude::Clock>().slot;
+        self.svm.warp_to_slot(slot + EXPIRES_IN_SLOTS);
+        let instruction = match self.variant {
+            Variant::Anchor => anchor_instruction(
+                solana_native_swaps::instruction::Refund {},
+                solana_native_swaps::accounts::Refund {
+                    swap_account,
+                    initiator,
+                    refund_to: None,
+                    refunder: None,
+                    event_authority: pda(b"__event_authority"),
+                    program: solana_native_swaps::ID,
+                },
+            ),
+            Variant::Lite => Instruction::new_with_bytes(
+                solana_native_swaps_lite::ID,
+                &SwapInstruction::Refund.pack(),
+                vec![
+                    AccountMeta::new(swap_account, false),
+                    AccountMeta::new(initiator, false),
+                ],
+            ),
+        };
+        self.send(instruction, &[])
+    }
+
+    fn instant_refund(&mut self, secret: &[u8]) -> Result<u64, String> {
+        let (initiator, redeemer) = (self.initiator.pubkey(), self.redeemer.pubkey());
+        let (swap_account, _) = self.swap_account(secret);
+        let instruction = match self.variant {
+            Variant::Anchor => anchor_instruction(
+                solana_native_swaps::instruction::InstantRefund {},
+                solana_native_swaps::accounts::InstantRefund {
+                    swap_account,
+                    initiator,
+                    refund_to: None,
+                    redeemer,
+                    event_authority: pda(b"__event_authority"),
+                    program: solana_native_swaps::ID,
+                },
+            ),
+            Variant::Lite => Instruction::new_with_bytes(
+                solana_native_swaps_lite::ID,
+                &SwapInstruction::InstantRefund.pack(),
+                vec![
+                    AccountMeta::new(swap_account, false),
+                    AccountMeta::new(initiator, false),
+                    AccountMeta::new_readonly(redeemer, true),
+                ],
+            ),
+        };
+        let redeemer = self.redeemer.insecure_clone();
+        self.send(instruction, &[&redeemer])
+    }
+
+    /// Sends `instruction` in a transaction of its own, paid for by the initiator,
+    /// returning the compute units it consumed
+    fn send(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Result<u64, String> {
+        let signers = [&[&self.initiator][..], signers].concat();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.initiator.pubkey()),
+            &signers,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(transaction).map_err(|failed| {
+            format!(
+                "{} failed: {}\n{}",
+                self.variant.name(),
+                failed.err,
+                failed.meta.pretty_logs()
+            )
+        })?;
+        self.svm.expire_blockhash();
+        Ok(result.compute_units_consumed)
+    }
+}
+
+fn anchor_instruction(data: impl InstructionData, accounts: impl ToAccountMetas) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &data.data(),
+        accounts.to_account_metas(None),
+    )
+}
+
+fn pda(seed: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(&[seed], &solana_native_swaps::ID).0
+}
+
+/// Pads a short label into a 32-byte secret, unique per benchmarked swap
+fn secret_preimage(label: &[u8]) -> [u8; 32] {
+    let mut secret = [0; 32];
+    secret[..label.len()].copy_from_slice(label);
+    secret
+}
+
+fn secret_hash(label: &[u8]) -> [u8; 32] {
+    hash(&secret_preimage(label)).to_bytes()
+}
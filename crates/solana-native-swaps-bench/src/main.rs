@@ -0,0 +1,36 @@
+//! Prints the compute units consumed by each instruction of every program variant,
+//! alongside its budget. Exits with a non-zero status if any budget is exceeded.
+
+use solana_native_swaps_bench::{measure, Variant};
+
+fn main() {
+    let mut exceeded = false;
+    for variant in Variant::ALL {
+        println!("{} ({})", variant.name(), variant.program_path().display());
+        match measure(variant) {
+            Ok(measurements) => {
+                for measurement in measurements {
+                    exceeded |= !measurement.within_budget();
+                    println!(
+                        "  {:<16} {:>7} CU / {:>7} budget{}",
+                        measurement.instruction,
+                        measurement.compute_units,
+                        measurement.budget,
+                        if measurement.within_budget() {
+                            ""
+                        } else {
+                            "  EXCEEDED"
+                        }
+                    );
+                }
+            }
+            Err(e) => {
+                exceeded = true;
+                println!("  {e}");
+            }
+        }
+    }
+    if exceeded {
+        std::process::exit(1);
+    }
+}
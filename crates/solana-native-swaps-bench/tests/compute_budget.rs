@@ -0,0 +1,30 @@
+use solana_native_swaps_bench::{measure, Variant};
+
+fn assert_within_budget(variant: Variant) {
+    let measurements = measure(variant).unwrap();
+    for measurement in &measurements {
+        println!("{}: {:?}", variant.name(), measurement);
+    }
+    let exceeded: Vec<_> = measurements
+        .iter()
+        .filter(|measurement| !measurement.within_budget())
+        .collect();
+    assert!(
+        exceeded.is_empty(),
+        "{} exceeded its compute budget: {:?}",
+        variant.name(),
+        exceeded
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `anchor build`"]
+fn anchor_program_within_budget() {
+    assert_within_budget(Variant::Anchor);
+}
+
+#[test]
+#[ignore = "requires the lite program to be built with `cargo build-sbf`"]
+fn lite_program_within_budget() {
+    assert_within_budget(Variant::Lite);
+}
@@ -0,0 +1,17 @@
+use std::{env, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+
+fn main() -> Result<()> {
+    let idl =
+        PathBuf::from(env::var("CARGO_MANIFEST_DIR")?).join("../../idl/solana_native_swaps.json");
+    println!("cargo:rerun-if-changed={}", idl.display());
+    let idl = fs::read_to_string(&idl)
+        .with_context(|| format!("Failed to read IDL {}", idl.display()))?;
+    let bindings = solana_native_swaps_codegen::generate(&idl)?;
+    fs::write(
+        PathBuf::from(env::var("OUT_DIR")?).join("solana_native_swaps.rs"),
+        bindings,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,13 @@
+//! Types, instruction builders and event decoding for the program, generated from its IDL at
+//! `idl/solana_native_swaps.json` by `solana-native-swaps-codegen`, and depending neither on
+//! `anchor-lang` nor on the program crate.
+//!
+//! ```ignore
+//! use solana_native_swaps_bindings::{accounts, instruction, ProgramEvent, SwapAccount};
+//!
+//! let redeem = instruction::Redeem { secret }.instruction(&accounts::Redeem { .. });
+//! let swap = SwapAccount::decode(&account_data);
+//! let event = ProgramEvent::from_cpi_data(&inner_instruction_data);
+//! ```
+
+include!(concat!(env!("OUT_DIR"), "/solana_native_swaps.rs"));
@@ -0,0 +1,191 @@
+//! Checks the generated bindings against the client's builders and the program's own types,
+//! so that a stale IDL cannot go unnoticed.
+
+use anchor_lang::{Discriminator, Event};
+use solana_native_swaps_bindings as bindings;
+use solana_native_swaps_client::{
+    pda, program, HashAlgorithm, InitiateBuilder, Instruction, Pubkey, RedeemBuilder, RedeemWindow,
+    SwapAccount, SwapIncentives, ID, SWAP_SEEDS_VERSION,
+};
+
+const SECRET: [u8; 32] = [7; 32];
+
+fn key(pubkey: Pubkey) -> bindings::Pubkey {
+    bindings::Pubkey(pubkey.to_bytes())
+}
+
+fn converted(instruction: Instruction) -> bindings::Instruction {
+    bindings::Instruction {
+        program_id: key(instruction.program_id),
+        accounts: instruction
+            .accounts
+            .into_iter()
+            .map(|meta| bindings::AccountMeta {
+                pubkey: key(meta.pubkey),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: instruction.data,
+    }
+}
+
+fn swap() -> SwapAccount {
+    let mut swap: SwapAccount = bytemuck::Zeroable::zeroed();
+    swap.initiator = Pubkey::new_unique();
+    swap.redeemer = Pubkey::new_unique();
+    swap.secret_hash = HashAlgorithm::Sha256.hash(&SECRET);
+    swap.amount_lamports = 1_000_000;
+    swap.expiry_slot = 300;
+    swap.seeds_version = SWAP_SEEDS_VERSION;
+    swap
+}
+
+#[test]
+fn constants_match() {
+    assert_eq!(bindings::ID, key(ID));
+    assert_eq!(bindings::MAX_EXPIRY_SLOTS, program::MAX_EXPIRY_SLOTS);
+    assert_eq!(bindings::SWAP_SEEDS_VERSION, SWAP_SEEDS_VERSION);
+    assert_eq!(ID.to_string().parse(), Ok(bindings::ID));
+    assert_eq!(bindings::ID.to_string(), ID.to_string());
+}
+
+#[test]
+fn initiate_instructions_match() {
+    let swap = swap();
+    let (funder, refund_to) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let expected = InitiateBuilder::new(
+        swap.initiator,
+        swap.redeemer,
+        swap.secret_hash,
+        swap.amount_lamports,
+        300,
+    )
+    .hash_algorithm(HashAlgorithm::Blake3)
+    .incentives(SwapIncentives {
+        relayer_fee_lamports: 1_000,
+        refund_tip_lamports: 2_000,
+        relayer_fee_start_lamports: Some(500),
+    })
+    .redeem_window(RedeemWindow {
+        redeem_after_slot: Some(10),
+        redeem_deadline_slot: None,
+    })
+    .funder(funder)
+    .refund_to(refund_to)
+    .instruction();
+
+    let args = bindings::instruction::Initiate {
+        amount_lamports: swap.amount_lamports,
+        expires_in_slots: 300,
+        redeemer: key(swap.redeemer),
+        secret_hash: swap.secret_hash,
+        hash_algorithm: bindings::HashAlgorithm::Blake3,
+        incentives: bindings::SwapIncentives {
+            relayer_fee_lamports: 1_000,
+            refund_tip_lamports: 2_000,
+            relayer_fee_start_lamports: Some(500),
+        },
+        redeem_window: bindings::RedeemWindow {
+            redeem_after_slot: Some(10),
+            redeem_deadline_slot: None,
+        },
+    };
+    let accounts = bindings::accounts::Initiate {
+        swap_account: key(pda::swap_account(&swap.initiator, &swap.redeemer, &swap.secret_hash).0),
+        config: key(pda::config().0),
+        initiator: key(swap.initiator),
+        funder: Some(key(funder)),
+        refund_to: Some(key(refund_to)),
+        system_program: key(anchor_lang::system_program::ID),
+        event_authority: key(pda::event_authority().0),
+        program: key(ID),
+    };
+    assert_eq!(
+        bindings::ProgramInstruction::decode(&expected.data),
+        Some(bindings::ProgramInstruction::Initiate(args.clone()))
+    );
+    assert_eq!(args.instruction(&accounts), converted(expected));
+}
+
+#[test]
+fn absent_optional_accounts_match() {
+    let swap = swap();
+    let swap_account = pda::swap_account(&swap.initiator, &swap.redeemer, &swap.secret_hash).0;
+    let args = bindings::instruction::Redeem {
+        secret: SECRET,
+        amount_lamports: swap.amount_lamports,
+    };
+    let accounts = bindings::accounts::Redeem {
+        swap_account: key(swap_account),
+        initiator: key(swap.initiator),
+        redeemer: key(swap.redeemer),
+        destination: None,
+        config: key(pda::config().0),
+        treasury: key(pda::treasury().0),
+        relayer: None,
+        receipt: None,
+        receipt_payer: None,
+        system_program: key(anchor_lang::system_program::ID),
+        event_authority: key(pda::event_authority().0),
+        program: key(ID),
+    };
+    assert_eq!(
+        args.instruction(&accounts),
+        converted(RedeemBuilder::new(&swap, SECRET).instruction())
+    );
+}
+
+#[test]
+fn swap_accounts_decode() {
+    let swap = swap();
+    let mut data = SwapAccount::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&swap));
+
+    let decoded = bindings::SwapAccount::decode(&data).unwrap();
+    assert_eq!(bytemuck::bytes_of(&decoded), bytemuck::bytes_of(&swap));
+    assert_eq!(decoded.initiator, key(swap.initiator));
+    assert_eq!(decoded.expiry_slot, swap.expiry_slot);
+    assert_eq!(bindings::SwapAccount::decode(&data[..40]), None);
+    assert_eq!(bindings::SwapAccount::decode(&[0; 200]), None);
+}
+
+#[test]
+fn events_decode() {
+    let swap = swap();
+    let event = program::Redeemed {
+        swap_account: Pubkey::new_unique(),
+        initiator: swap.initiator,
+        redeemer: swap.redeemer,
+        secret: SECRET,
+        expiry_slot: swap.expiry_slot,
+        amount_lamports: swap.amount_lamports,
+        protocol_fee_lamports: 10,
+        relayer_fee_lamports: 0,
+    };
+    let mut data = bindings::EVENT_IX_TAG_LE.to_vec();
+    data.extend(event.data());
+
+    let Some(bindings::ProgramEvent::Redeemed(decoded)) =
+        bindings::ProgramEvent::from_cpi_data(&data)
+    else {
+        panic!("Failed to decode the event");
+    };
+    assert_eq!(decoded.swap_account, key(event.swap_account));
+    assert_eq!(decoded.secret, SECRET);
+    assert_eq!(decoded.protocol_fee_lamports, 10);
+    assert_eq!(bindings::ProgramEvent::from_cpi_data(&event.data()), None);
+}
+
+#[test]
+fn error_codes_match() {
+    for error in [
+        program::SwapError::InvalidInitiator,
+        program::SwapError::InvalidSecret,
+    ] {
+        let message = error.to_string();
+        let decoded = bindings::ErrorCode::from_code(error.into()).unwrap();
+        assert_eq!(decoded.message(), message);
+    }
+    assert_eq!(bindings::ErrorCode::from_code(0), None);
+}
@@ -0,0 +1,328 @@
+//! Narration of the instructions of a transaction targeting either swap program, for incident
+//! response without reading raw instruction data.
+
+use anchor_lang::{event::EVENT_IX_TAG_LE, AnchorDeserialize, Discriminator};
+use solana_native_swaps_client::{
+    events::SwapEvent,
+    program::instruction,
+    rpc::{RpcInstruction, RpcTransaction},
+    Pubkey, ID,
+};
+use solana_native_swaps_lite::SwapInstruction;
+
+/// The name of the instruction of the swap program with `discriminator`, if any
+macro_rules! instruction_name {
+    ($discriminator:expr, { $($instruction:ident => $name:literal,)* }) => {
+        match $discriminator {
+            $(discriminator if discriminator == instruction::$instruction::DISCRIMINATOR => Some($name),)*
+            _ => None,
+        }
+    };
+}
+
+/// Prints a narrative of every instruction of `transaction` targeting either swap program,
+/// including those invoked by other programs, and of the swap events they emitted
+pub fn print_transaction(transaction: &RpcTransaction) {
+    println!("Slot:             {}", transaction.slot);
+    if let Some(block_time) = transaction.block_time {
+        println!("Block time:       {block_time}");
+    }
+    if transaction.failed {
+        println!("Status:           failed, none of the instructions below took effect");
+    }
+
+    let mut narrated = 0;
+    for (index, ix) in transaction.instructions.iter().enumerate() {
+        let narrative = if ix.program_id == ID {
+            match ix.data.strip_prefix(EVENT_IX_TAG_LE) {
+                Some(event) => match SwapEvent::decode(event) {
+                    Ok(Some(event)) => format!("emitted {}", narrate_event(&event)),
+                    // Events other than those of the swap lifecycle are left to the logs
+                    Ok(None) | Err(_) => continue,
+                },
+                None => narrate_swap_instruction(ix),
+            }
+        } else if ix.program_id == solana_native_swaps_lite::ID {
+            narrate_lite_instruction(ix)
+        } else {
+            continue;
+        };
+        let invocation = match ix.stack_height {
+            1 => String::new(),
+            depth => format!(" (invoked at depth {depth})"),
+        };
+        println!("\n#{index}{invocation}: {narrative}");
+        narrated += 1;
+    }
+    if narrated == 0 {
+        println!("\nNo instruction targets either swap program");
+    }
+}
+
+/// Narrates an instruction of the Anchor swap program
+fn narrate_swap_instruction(ix: &RpcInstruction) -> String {
+    let Some((discriminator, mut args)) = ix.data.split_at_checked(8) else {
+        return "malformed swap program instruction".to_string();
+    };
+    let account = |index: usize| {
+        ix.accounts
+            .get(index)
+            .map_or("?".to_string(), ToString::to_string)
+    };
+    let narrative = match discriminator {
+        discriminator if discriminator == instruction::Initiate::DISCRIMINATOR => {
+            instruction::Initiate::deserialize(&mut args)
+                .ok()
+                .map(|initiate| {
+                    format!(
+                        "initiate: {} initiates swap {} of {} to {}, with {:?} secret hash {}, \
+                         refundable {} slots from now",
+                        account(2),
+                        account(0),
+                        lamports(initiate.amount_lamports),
+                        initiate.redeemer,
+                        initiate.hash_algorithm,
+                        hex::encode(initiate.secret_hash),
+                        initiate.expires_in_slots,
+                    )
+                })
+        }
+        discriminator if discriminator == instruction::Redeem::DISCRIMINATOR => {
+            instruction::Redeem::deserialize(&mut args)
+                .ok()
+                .map(|redeem| {
+                    format!(
+                        "redeem: swap {} from {} is redeemed to {} with secret {}, for {}",
+                        account(0),
+                        account(1),
+                        account(2),
+                        hex::encode(redeem.secret),
+                        lamports(redeem.amount_lamports),
+                    )
+                })
+        }
+        discriminator if discriminator == instruction::Refund::DISCRIMINATOR => Some(format!(
+            "refund: expired swap {} is refunded to {}",
+            account(0),
+            refund_destination(ix),
+        )),
+        discriminator if discriminator == instruction::InstantRefund::DISCRIMINATOR => {
+            Some(format!(
+                "instant_refund: swap {} is refunded to {} with the consent of {}",
+                account(0),
+                refund_destination(ix),
+                account(3),
+            ))
+        }
+        _ => None,
+    };
+    narrative.unwrap_or_else(|| {
+        let name = instruction_name!(discriminator, {
+            Initiate => "initiate",
+            InitiateWithEpochExpiry => "initiate_with_epoch_expiry",
+            InitiateWithDualExpiry => "initiate_with_dual_expiry",
+            InitiateMultiSecret => "initiate_multi_secret",
+            InitiateWithAcceptanceBond => "initiate_with_acceptance_bond",
+            Accept => "accept",
+            InitiateAnySecret => "initiate_any_secret",
+            InitiateWithNonce => "initiate_with_nonce",
+            InitiateBatch => "initiate_batch",
+            InitializeConfig => "initialize_config",
+            UpdateExpiryBounds => "update_expiry_bounds",
+            UpdateProtocolFee => "update_protocol_fee",
+            SetInitiatePaused => "set_initiate_paused",
+            SetTransferHookPermitted => "set_transfer_hook_permitted",
+            WithdrawProtocolFees => "withdraw_protocol_fees",
+            Redeem => "redeem",
+            RedeemMultiSecret => "redeem_multi_secret",
+            RedeemAnySecret => "redeem_any_secret",
+            RedeemWithSignature => "redeem_with_signature",
+            CloseRedeemReceipt => "close_redeem_receipt",
+            Refund => "refund",
+            SweepAbandoned => "sweep_abandoned",
+            RefundExpiredBatch => "refund_expired_batch",
+            InstantRefund => "instant_refund",
+            CooperativeClose => "cooperative_close",
+            ExtendExpiry => "extend_expiry",
+            Migrate => "migrate",
+            GetVersion => "get_version",
+            GetSwapState => "get_swap_state",
+            InstantRefundWithSignature => "instant_refund_with_signature",
+            InstantRefundWithSession => "instant_refund_with_session",
+            CreateSession => "create_session",
+            RotateSession => "rotate_session",
+            RevokeSession => "revoke_session",
+            InitiateToken => "initiate_token",
+            RedeemToken => "redeem_token",
+            RedeemTokenToAta => "redeem_token_to_ata",
+            RefundToken => "refund_token",
+            InitiateWsol => "initiate_wsol",
+            RedeemWsol => "redeem_wsol",
+            RefundWsol => "refund_wsol",
+            InitiateConfidential => "initiate_confidential",
+            RedeemConfidential => "redeem_confidential",
+            RefundConfidential => "refund_confidential",
+            InitiateNft => "initiate_nft",
+            RedeemNft => "redeem_nft",
+            RefundNft => "refund_nft",
+            InitiateCnft => "initiate_cnft",
+            RedeemCnft => "redeem_cnft",
+            RefundCnft => "refund_cnft",
+            InitiateBundle => "initiate_bundle",
+            RedeemBundle => "redeem_bundle",
+            RefundBundle => "refund_bundle",
+            InitiateStreamed => "initiate_streamed",
+            RevealStreamed => "reveal_streamed",
+            ClaimStreamed => "claim_streamed",
+            DisputeStreamed => "dispute_streamed",
+            RefundStreamed => "refund_streamed",
+            InitiateHashChain => "initiate_hash_chain",
+            RedeemHashChain => "redeem_hash_chain",
+            RefundHashChain => "refund_hash_chain",
+            InitiatePtlc => "initiate_ptlc",
+            RedeemPtlc => "redeem_ptlc",
+            RefundPtlc => "refund_ptlc",
+            InitiateSecp256k1 => "initiate_secp256k1",
+            RedeemSecp256k1 => "redeem_secp256k1",
+            RefundSecp256k1 => "refund_secp256k1",
+            InitiateZk => "initiate_zk",
+            RedeemZk => "redeem_zk",
+            RefundZk => "refund_zk",
+            PostOrder => "post_order",
+            FillOrder => "fill_order",
+            CancelOrder => "cancel_order",
+            InitiateOraclePriced => "initiate_oracle_priced",
+            RedeemOraclePriced => "redeem_oracle_priced",
+            RefundOraclePriced => "refund_oracle_priced",
+            InitiateWormhole => "initiate_wormhole",
+            RedeemWithVaa => "redeem_with_vaa",
+            RefundWormhole => "refund_wormhole",
+            ProvisionRelayerFees => "provision_relayer_fees",
+            DrawRelayerFees => "draw_relayer_fees",
+            CloseFeeEscrow => "close_fee_escrow",
+            NetSettle => "net_settle",
+            OpenChannel => "open_channel",
+            DepositToChannel => "deposit_to_channel",
+            LockChannelPayment => "lock_channel_payment",
+            LockChannelPaymentWithSession => "lock_channel_payment_with_session",
+            ClaimChannelPayment => "claim_channel_payment",
+            CancelChannelPayment => "cancel_channel_payment",
+            CooperativeCloseChannel => "cooperative_close_channel",
+            CloseExpiredChannel => "close_expired_channel",
+        });
+        match name {
+            Some(name) => format!("{name}, with accounts {}", accounts(&ix.accounts)),
+            None => format!(
+                "unknown swap program instruction {}",
+                hex::encode(discriminator)
+            ),
+        }
+    })
+}
+
+/// Narrates an instruction of the lite swap program
+fn narrate_lite_instruction(ix: &RpcInstruction) -> String {
+    let account = |index: usize| {
+        ix.accounts
+            .get(index)
+            .map_or("?".to_string(), ToString::to_string)
+    };
+    match SwapInstruction::unpack(&ix.data) {
+        Ok(SwapInstruction::Initiate {
+            amount_lamports,
+            expires_in_slots,
+            redeemer,
+            secret_hash,
+            ..
+        }) => format!(
+            "lite initiate: {} initiates swap {} of {} to {}, with Sha256 secret hash {}, \
+             refundable {expires_in_slots} slots from now",
+            account(0),
+            account(1),
+            lamports(amount_lamports),
+            &redeemer,
+            hex::encode(secret_hash),
+        ),
+        Ok(SwapInstruction::Redeem { secret }) => format!(
+            "lite redeem: swap {} from {} is redeemed to {} with secret {}",
+            account(0),
+            account(2),
+            account(1),
+            hex::encode(secret),
+        ),
+        Ok(SwapInstruction::Refund) => format!(
+            "lite refund: expired swap {} is refunded to {}",
+            account(0),
+            account(1),
+        ),
+        Ok(SwapInstruction::InstantRefund) => format!(
+            "lite instant_refund: swap {} is refunded to {} with the consent of {}",
+            account(0),
+            account(1),
+            account(2),
+        ),
+        Err(_) => format!(
+            "malformed lite program instruction {}",
+            hex::encode(&ix.data)
+        ),
+    }
+}
+
+fn narrate_event(event: &SwapEvent) -> String {
+    match event {
+        SwapEvent::Initiated(event) => format!(
+            "Initiated: swap {} of {} from {} to {}, expiring at slot {}",
+            event.swap_account,
+            lamports(event.swap_amount),
+            event.initiator,
+            event.redeemer,
+            event.expiry_slot,
+        ),
+        SwapEvent::Redeemed(event) => format!(
+            "Redeemed: {} of swap {} redeemed to {}, of which {} protocol fee and {} \
+             relayer fee, revealing secret {}",
+            lamports(event.amount_lamports),
+            event.swap_account,
+            event.redeemer,
+            lamports(event.protocol_fee_lamports),
+            lamports(event.relayer_fee_lamports),
+            hex::encode(event.secret),
+        ),
+        SwapEvent::Refunded(event) => format!(
+            "Refunded: {} of swap {} refunded for {}, of which {} refund tip",
+            lamports(event.amount_lamports),
+            event.swap_account,
+            event.initiator,
+            lamports(event.refund_tip_lamports),
+        ),
+        SwapEvent::InstantRefunded(event) => format!(
+            "InstantRefunded: {} of swap {} refunded for {} with the consent of {}",
+            lamports(event.amount_lamports),
+            event.swap_account,
+            event.initiator,
+            event.redeemer,
+        ),
+    }
+}
+
+/// The account refunds of the `refund` or `instant_refund` instruction `ix` are paid to
+fn refund_destination(ix: &RpcInstruction) -> String {
+    // Optional accounts which are absent are passed as the program ID
+    match (ix.accounts.get(2), ix.accounts.get(1)) {
+        (Some(refund_to), _) if *refund_to != ID => refund_to.to_string(),
+        (_, Some(initiator)) => initiator.to_string(),
+        _ => "?".to_string(),
+    }
+}
+
+fn accounts(accounts: &[Pubkey]) -> String {
+    accounts
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn lamports(lamports: u64) -> String {
+    format!("{lamports} lamports")
+}
@@ -0,0 +1,278 @@
+//! `swapcli` performs the lifecycle operations of swaps from the command line, e.g. for
+//! manual recovery of stuck swaps, signing with a keypair file against an RPC node.
+
+mod inspect;
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_native_swaps_client::{
+    fetch_swap_account, fetch_swap_accounts_by_initiator,
+    idempotency::{FileStore, IdempotencyKey, IdempotencyStore, IdempotentSubmitter, MemoryStore},
+    rpc::{Commitment, RpcClient},
+    InitiateBuilder, InstantRefundBuilder, Instruction, Pubkey, RedeemBuilder, RefundBuilder,
+    SwapAccount,
+};
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+#[derive(Parser)]
+#[command(name = "swapcli", version, about)]
+struct Cli {
+    /// The URL of the RPC node
+    #[arg(
+        long,
+        short,
+        global = true,
+        env = "SWAPCLI_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// The keypair file of the signer, which also pays the transaction fees
+    #[arg(
+        long,
+        short,
+        global = true,
+        env = "SWAPCLI_KEYPAIR",
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Initiates a swap, with the signer as the initiator
+    Initiate {
+        /// The redeemer of the swap
+        #[arg(long)]
+        redeemer: Pubkey,
+        /// The SHA-256 hash of the secret, in hex
+        #[arg(long, value_parser = parse_bytes32)]
+        secret_hash: [u8; 32],
+        /// The swap amount in lamports
+        #[arg(long)]
+        amount: u64,
+        /// The number of slots after which refunds are allowed
+        #[arg(long)]
+        expires_in_slots: u64,
+        /// The account refunds are to be paid to in place of the initiator
+        #[arg(long)]
+        refund_to: Option<Pubkey>,
+        /// The file recording submissions, so that rerunning the command after a timeout never
+        /// initiates twice. Past submissions are otherwise only found through their memo.
+        #[arg(long)]
+        idempotency_store: Option<PathBuf>,
+    },
+    /// Redeems the entire remaining funds of a swap to its redeemer
+    Redeem {
+        /// The swap account
+        swap_account: Pubkey,
+        /// The secret, in hex
+        #[arg(long, value_parser = parse_bytes32)]
+        secret: [u8; 32],
+    },
+    /// Refunds an expired swap. The signer claims the refund tip unless they are the initiator.
+    Refund {
+        /// The swap account
+        swap_account: Pubkey,
+    },
+    /// Refunds a swap before expiry, with the signer as the consenting redeemer
+    InstantRefund {
+        /// The swap account
+        swap_account: Pubkey,
+    },
+    /// Shows the state of a swap
+    Show {
+        /// The swap account
+        swap_account: Pubkey,
+    },
+    /// Lists the open swaps of an initiator
+    List {
+        #[arg(long)]
+        initiator: Pubkey,
+    },
+    /// Narrates every instruction of a transaction targeting either swap program
+    Inspect {
+        /// The signature of the transaction
+        signature: Signature,
+    },
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new_with_commitment(&cli.url, Commitment::Confirmed);
+
+    match cli.command {
+        Command::Initiate {
+            redeemer,
+            secret_hash,
+            amount,
+            expires_in_slots,
+            refund_to,
+            idempotency_store,
+        } => {
+            let signer = read_keypair(&cli.keypair)?;
+            let mut initiate = InitiateBuilder::new(
+                signer.pubkey(),
+                redeemer,
+                secret_hash,
+                amount,
+                expires_in_slots,
+            );
+            if let Some(refund_to) = refund_to {
+                initiate = initiate.refund_to(refund_to);
+            }
+            let swap_account = initiate.swap_account();
+            println!("Swap account: {swap_account}");
+            let key = IdempotencyKey::new(format!("initiate-{swap_account}"))?;
+            let instruction = initiate.instruction();
+            match idempotency_store {
+                Some(path) => {
+                    send_once(&rpc, FileStore::new(path), &key, &signer, instruction).await?
+                }
+                None => send_once(&rpc, MemoryStore::default(), &key, &signer, instruction).await?,
+            }
+        }
+        Command::Redeem {
+            swap_account,
+            secret,
+        } => {
+            let signer = read_keypair(&cli.keypair)?;
+            let swap = fetch_open_swap(&rpc, &swap_account).await?;
+            send(
+                &rpc,
+                &signer,
+                RedeemBuilder::new(&swap, secret).instruction(),
+            )
+            .await?;
+        }
+        Command::Refund { swap_account } => {
+            let signer = read_keypair(&cli.keypair)?;
+            let swap = fetch_open_swap(&rpc, &swap_account).await?;
+            let mut refund = RefundBuilder::new(&swap);
+            if signer.pubkey() != swap.initiator {
+                refund = refund.refunder(signer.pubkey());
+            }
+            send(&rpc, &signer, refund.instruction()).await?;
+        }
+        Command::InstantRefund { swap_account } => {
+            let signer = read_keypair(&cli.keypair)?;
+            let swap = fetch_open_swap(&rpc, &swap_account).await?;
+            if signer.pubkey() != swap.redeemer {
+                bail!(
+                    "Instant refunds must be signed by the redeemer {}",
+                    swap.redeemer
+                );
+            }
+            send(
+                &rpc,
+                &signer,
+                InstantRefundBuilder::new(&swap).instruction(),
+            )
+            .await?;
+        }
+        Command::Show { swap_account } => {
+            let swap = fetch_open_swap(&rpc, &swap_account).await?;
+            print_swap(&swap_account, &swap);
+        }
+        Command::List { initiator } => {
+            let swaps = fetch_swap_accounts_by_initiator(&rpc, &initiator).await?;
+            if swaps.is_empty() {
+                println!("No open swaps");
+            }
+            for (swap_account, swap) in swaps {
+                print_swap(&swap_account, &swap);
+                println!();
+            }
+        }
+        Command::Inspect { signature } => {
+            let transaction = rpc
+                .get_transaction(&signature)
+                .await?
+                .ok_or_else(|| anyhow!("Transaction {signature} not found"))?;
+            println!("Signature:        {signature}");
+            inspect::print_transaction(&transaction);
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_open_swap(rpc: &RpcClient, swap_account: &Pubkey) -> Result<SwapAccount> {
+    fetch_swap_account(rpc, swap_account)
+        .await?
+        .ok_or_else(|| anyhow!("Swap account {swap_account} does not exist, or has completed"))
+}
+
+/// Sends `instruction` in a transaction signed and paid for by `signer`, awaiting confirmation
+async fn send(rpc: &RpcClient, signer: &Keypair, instruction: Instruction) -> Result<()> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&signer.pubkey()),
+        &[signer],
+        rpc.get_latest_blockhash().await?,
+    );
+    let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+    println!("Signature: {signature}");
+    Ok(())
+}
+
+/// Sends `instruction` as [`send`] does, unless the operation identified by `key` has already
+/// been executed
+async fn send_once(
+    rpc: &RpcClient,
+    store: impl IdempotencyStore,
+    key: &IdempotencyKey,
+    signer: &Keypair,
+    instruction: Instruction,
+) -> Result<()> {
+    let signature = IdempotentSubmitter::new(rpc, store)
+        .submit(key, &[instruction], &signer.pubkey(), &[signer])
+        .await?;
+    println!("Signature: {signature}");
+    Ok(())
+}
+
+fn print_swap(swap_account: &Pubkey, swap: &SwapAccount) {
+    println!("Swap account:     {swap_account}");
+    println!("Initiator:        {}", swap.initiator);
+    println!("Redeemer:         {}", swap.redeemer);
+    println!("Secret hash:      {}", hex::encode(swap.secret_hash));
+    match swap.hash_algorithm() {
+        Ok(hash_algorithm) => println!("Hash algorithm:   {hash_algorithm:?}"),
+        Err(_) => println!("Hash algorithm:   unknown ({})", swap.hash_algorithm),
+    }
+    println!("Amount:           {} lamports", swap.amount_lamports);
+    println!("Expiry slot:      {}", swap.expiry_slot);
+    if let Some(expiry_timestamp) = swap.expiry_timestamp() {
+        println!("Expiry timestamp: {expiry_timestamp}");
+    }
+    if let Some(refund_to) = swap.refund_to() {
+        println!("Refund to:        {refund_to}");
+    }
+    println!("Created at slot:  {}", swap.created_at_slot);
+}
+
+/// Reads a keypair file, as generated by `solana-keygen`
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(relative) => PathBuf::from(std::env::var("HOME")?).join(relative),
+        None => PathBuf::from(path),
+    };
+    read_keypair_file(&path).map_err(|e| anyhow!("Failed to read keypair {}: {e}", path.display()))
+}
+
+fn parse_bytes32(hex: &str) -> Result<[u8; 32]> {
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(hex.trim_start_matches("0x"), &mut bytes)
+        .context("Expected 32 bytes in hex")?;
+    Ok(bytes)
+}
@@ -0,0 +1,427 @@
+//! A coordinator driving an entire cross-chain swap, in which the Solana leg is initiated by
+//! the party holding the secret, from a single call.
+//!
+//! The coordinator generates the secret, initiates the Solana leg, then waits for the
+//! counterparty leg to be funded, i.e. for the swap to be filled. Once filled, it redeems the
+//! counterparty leg, revealing the secret for the redeemer to redeem the Solana leg with. If the
+//! counterparty leg is never funded, or refunded before being redeemed, the Solana leg is
+//! refunded once expired. The counterparty chain is plugged in through the [`CounterpartyLeg`]
+//! hooks.
+//!
+//! Every swap is recorded in a [`SwapStore`] under a caller-chosen ID before each step is taken,
+//! and its transactions are submitted through an [`IdempotentSubmitter`], so that a coordinator
+//! restarted with [`SwapCoordinator::resume`] picks up where it left off without initiating or
+//! refunding twice. The records hold the secrets of the swaps, and must be protected accordingly.
+//!
+//! ```ignore
+//! let coordinator = SwapCoordinator::new(
+//!     &rpc,
+//!     coordinator::FileStore::new("swaps.json"),
+//!     idempotency::FileStore::new("operations.json"),
+//!     bitcoin_leg,
+//! );
+//! let order = SwapOrder::new(redeemer, 1_000_000_000, 9_000);
+//! let record = coordinator.run(&order_id, &order, &payer, &[&payer]).await?;
+//! assert_eq!(record.stage, Stage::Completed);
+//! ```
+
+use std::{collections::HashMap, future::Future, path::PathBuf, sync::Mutex, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use solana_signer::signers::Signers;
+
+use crate::{
+    fetch_clock, fetch_swap_account,
+    idempotency::{IdempotencyKey, IdempotencyStore, IdempotentSubmitter},
+    pda,
+    rpc::RpcClient,
+    ClientError, HashAlgorithm, InitiateBuilder, Pubkey, RefundBuilder,
+};
+
+/// The Solana leg of a swap, as requested of the coordinator
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapOrder {
+    #[serde(with = "pubkey_string")]
+    pub redeemer: Pubkey,
+    pub amount_lamports: u64,
+    /// The number of slots until the Solana leg may be refunded, which must outlive the
+    /// counterparty leg
+    pub expires_in_slots: u64,
+    #[serde(with = "hash_algorithm_index")]
+    pub hash_algorithm: HashAlgorithm,
+    /// The account refunds are paid to in place of the initiator, if any
+    #[serde(with = "option_pubkey_string")]
+    pub refund_to: Option<Pubkey>,
+}
+
+impl SwapOrder {
+    /// An order of a swap with SHA-256 secret hashes, refunded to the initiator
+    pub fn new(redeemer: Pubkey, amount_lamports: u64, expires_in_slots: u64) -> Self {
+        SwapOrder {
+            redeemer,
+            amount_lamports,
+            expires_in_slots,
+            hash_algorithm: HashAlgorithm::default(),
+            refund_to: None,
+        }
+    }
+}
+
+/// The progress of a coordinated swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// The secret is generated, the Solana leg yet to be initiated
+    Created,
+    /// The Solana leg is initiated, the counterparty leg yet to be funded
+    Initiated,
+    /// The counterparty leg is funded, and being redeemed with the secret
+    Revealing,
+    /// The counterparty leg is redeemed, completing the swap
+    Completed,
+    /// The counterparty leg failed to fill, and the Solana leg is to be refunded once expired
+    Refunding,
+    /// The Solana leg is refunded, or was otherwise closed without the secret being revealed
+    Refunded,
+}
+
+impl Stage {
+    /// Whether the swap has settled, the coordinator having nothing left to do
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Stage::Completed | Stage::Refunded)
+    }
+}
+
+/// A coordinated swap, as persisted across restarts
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapRecord {
+    pub order: SwapOrder,
+    #[serde(with = "pubkey_string")]
+    pub initiator: Pubkey,
+    pub secret: [u8; 32],
+    pub secret_hash: [u8; 32],
+    #[serde(with = "pubkey_string")]
+    pub swap_account: Pubkey,
+    pub stage: Stage,
+}
+
+/// The state of the counterparty leg of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterpartyStatus {
+    NotFunded,
+    /// Funded with the agreed amount and an expiry the secret can still be revealed before
+    Funded,
+    Redeemed,
+    Refunded,
+}
+
+/// The hooks through which the coordinator drives the counterparty leg of swaps.
+///
+/// Hooks may be called again for the same swap after a restart, and must be idempotent.
+pub trait CounterpartyLeg {
+    /// Called once the Solana leg of `swap` is initiated, e.g. to hand the order to the party
+    /// funding the counterparty leg
+    fn initiated(&self, swap: &SwapRecord) -> impl Future<Output = Result<(), ClientError>>;
+
+    /// The state of the counterparty leg of `swap`. Legs funded with less than the agreed
+    /// amount, or expiring too soon, must not be reported as funded.
+    fn status(
+        &self,
+        swap: &SwapRecord,
+    ) -> impl Future<Output = Result<CounterpartyStatus, ClientError>>;
+
+    /// Submits a redeem of the counterparty leg of `swap`, revealing its secret. Called on every
+    /// poll until the leg is reported as redeemed.
+    fn redeem(&self, swap: &SwapRecord) -> impl Future<Output = Result<(), ClientError>>;
+}
+
+/// Persists coordinated swaps by their ID
+pub trait SwapStore {
+    fn load(&self, id: &str) -> Result<Option<SwapRecord>, ClientError>;
+    fn save(&self, id: &str, record: &SwapRecord) -> Result<(), ClientError>;
+}
+
+/// Keeps records in memory, for swaps which need not survive a restart
+#[derive(Default)]
+pub struct MemoryStore(Mutex<HashMap<String, SwapRecord>>);
+
+impl SwapStore for MemoryStore {
+    fn load(&self, id: &str) -> Result<Option<SwapRecord>, ClientError> {
+        Ok(self.0.lock().unwrap().get(id).cloned())
+    }
+
+    fn save(&self, id: &str, record: &SwapRecord) -> Result<(), ClientError> {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), record.clone());
+        Ok(())
+    }
+}
+
+/// Keeps records in a JSON file, rewritten on every save
+pub struct FileStore {
+    path: PathBuf,
+    // Serializes the read-modify-write of saves within the process
+    lock: Mutex<()>,
+}
+
+impl FileStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileStore {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> Result<HashMap<String, SwapRecord>, ClientError> {
+        match std::fs::read(&self.path) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|e| ClientError::SwapStore(format!("{}: {e}", self.path.display()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(ClientError::SwapStore(format!(
+                "{}: {e}",
+                self.path.display()
+            ))),
+        }
+    }
+}
+
+impl SwapStore for FileStore {
+    fn load(&self, id: &str) -> Result<Option<SwapRecord>, ClientError> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.read()?.remove(id))
+    }
+
+    fn save(&self, id: &str, record: &SwapRecord) -> Result<(), ClientError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut records = self.read()?;
+        records.insert(id.to_string(), record.clone());
+        let contents = serde_json::to_vec_pretty(&records)
+            .map_err(|e| ClientError::SwapStore(e.to_string()))?;
+        // Written aside then renamed, so that a crash mid-write cannot lose the records
+        let temporary = self.path.with_extension("tmp");
+        std::fs::write(&temporary, contents)
+            .and_then(|()| std::fs::rename(&temporary, &self.path))
+            .map_err(|e| ClientError::SwapStore(format!("{}: {e}", self.path.display())))
+    }
+}
+
+/// Drives cross-chain swaps through their lifecycle, see the [module documentation](self)
+pub struct SwapCoordinator<'a, S, I, C> {
+    rpc: &'a RpcClient,
+    store: S,
+    submitter: IdempotentSubmitter<'a, I>,
+    counterparty: C,
+    /// The interval at which the legs of swaps are polled
+    pub poll_interval: Duration,
+}
+
+impl<'a, S: SwapStore, I: IdempotencyStore, C: CounterpartyLeg> SwapCoordinator<'a, S, I, C> {
+    pub fn new(rpc: &'a RpcClient, store: S, idempotency_store: I, counterparty: C) -> Self {
+        SwapCoordinator {
+            rpc,
+            store,
+            submitter: IdempotentSubmitter::new(rpc, idempotency_store),
+            counterparty,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Drives the swap `id` until settled, returning its final record. The swap is created from
+    /// `order`, with a newly generated secret, unless already recorded, in which case it is
+    /// resumed and `order` ignored.
+    ///
+    /// The Solana leg is initiated by `payer`, who must be among `signers`.
+    pub async fn run<T: Signers + ?Sized>(
+        &self,
+        id: &str,
+        order: &SwapOrder,
+        payer: &Pubkey,
+        signers: &T,
+    ) -> Result<SwapRecord, ClientError> {
+        let record = match self.store.load(id)? {
+            Some(record) => record,
+            None => {
+                let secret: [u8; 32] = rand::random();
+                let secret_hash = order.hash_algorithm.hash(&secret);
+                let record = SwapRecord {
+                    order: order.clone(),
+                    initiator: *payer,
+                    secret,
+                    secret_hash,
+                    swap_account: pda::swap_account(payer, &order.redeemer, &secret_hash).0,
+                    stage: Stage::Created,
+                };
+                // The secret is persisted before it can be committed to on-chain
+                self.store.save(id, &record)?;
+                record
+            }
+        };
+        self.drive(id, record, signers).await
+    }
+
+    /// Drives the swap `id`, as recorded by an earlier [`run`](Self::run), until settled
+    pub async fn resume<T: Signers + ?Sized>(
+        &self,
+        id: &str,
+        signers: &T,
+    ) -> Result<SwapRecord, ClientError> {
+        let record = self
+            .store
+            .load(id)?
+            .ok_or_else(|| ClientError::SwapStore(format!("no swap {id}")))?;
+        self.drive(id, record, signers).await
+    }
+
+    async fn drive<T: Signers + ?Sized>(
+        &self,
+        id: &str,
+        mut record: SwapRecord,
+        signers: &T,
+    ) -> Result<SwapRecord, ClientError> {
+        while !record.stage.is_terminal() {
+            let stage = self.step(&record, signers).await?;
+            if stage == record.stage {
+                tokio::time::sleep(self.poll_interval).await;
+            } else {
+                record.stage = stage;
+                self.store.save(id, &record)?;
+            }
+        }
+        Ok(record)
+    }
+
+    /// Takes the next step of `record`, returning its stage once taken
+    async fn step<T: Signers + ?Sized>(
+        &self,
+        record: &SwapRecord,
+        signers: &T,
+    ) -> Result<Stage, ClientError> {
+        match record.stage {
+            Stage::Created => {
+                let order = &record.order;
+                let mut initiate = InitiateBuilder::new(
+                    record.initiator,
+                    order.redeemer,
+                    record.secret_hash,
+                    order.amount_lamports,
+                    order.expires_in_slots,
+                )
+                .hash_algorithm(order.hash_algorithm);
+                if let Some(refund_to) = order.refund_to {
+                    initiate = initiate.refund_to(refund_to);
+                }
+                let key = IdempotencyKey::new(format!("initiate-{}", record.swap_account))?;
+                self.submitter
+                    .submit(&key, &[initiate.instruction()], &record.initiator, signers)
+                    .await?;
+                self.counterparty.initiated(record).await?;
+                Ok(Stage::Initiated)
+            }
+            Stage::Initiated => match self.counterparty.status(record).await? {
+                CounterpartyStatus::Funded => Ok(Stage::Revealing),
+                CounterpartyStatus::Redeemed => Ok(Stage::Completed),
+                CounterpartyStatus::Refunded => Ok(Stage::Refunding),
+                CounterpartyStatus::NotFunded => {
+                    let clock = fetch_clock(self.rpc).await?;
+                    match fetch_swap_account(self.rpc, &record.swap_account).await? {
+                        Some(swap) if !swap.has_expired(&clock) => Ok(Stage::Initiated),
+                        // Closed swaps can only have been refunded, the secret being unrevealed
+                        _ => Ok(Stage::Refunding),
+                    }
+                }
+            },
+            Stage::Revealing => match self.counterparty.status(record).await? {
+                CounterpartyStatus::Redeemed => Ok(Stage::Completed),
+                // Expired before the redeem landed
+                CounterpartyStatus::Refunded => Ok(Stage::Refunding),
+                CounterpartyStatus::NotFunded | CounterpartyStatus::Funded => {
+                    self.counterparty.redeem(record).await?;
+                    Ok(Stage::Revealing)
+                }
+            },
+            Stage::Refunding => {
+                let Some(swap) = fetch_swap_account(self.rpc, &record.swap_account).await? else {
+                    return Ok(Stage::Refunded);
+                };
+                if !swap.has_expired(&fetch_clock(self.rpc).await?) {
+                    return Ok(Stage::Refunding);
+                }
+                let key = IdempotencyKey::new(format!("refund-{}", record.swap_account))?;
+                let refund = RefundBuilder::new(&swap).instruction();
+                self.submitter
+                    .submit(&key, &[refund], &record.initiator, signers)
+                    .await?;
+                Ok(Stage::Refunded)
+            }
+            Stage::Completed | Stage::Refunded => Ok(record.stage),
+        }
+    }
+}
+
+mod pubkey_string {
+    use std::str::FromStr;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let pubkey = String::deserialize(deserializer)?;
+        Pubkey::from_str(&pubkey).map_err(D::Error::custom)
+    }
+}
+
+mod option_pubkey_string {
+    use std::str::FromStr;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::Pubkey;
+
+    pub fn serialize<S: Serializer>(
+        pubkey: &Option<Pubkey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match pubkey {
+            Some(pubkey) => serializer.collect_str(pubkey),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Pubkey>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|pubkey| Pubkey::from_str(&pubkey).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// Hash algorithms are persisted as their discriminant, as stored in swap accounts
+mod hash_algorithm_index {
+    use anchor_lang::AnchorDeserialize;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::HashAlgorithm;
+
+    pub fn serialize<S: Serializer>(
+        hash_algorithm: &HashAlgorithm,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*hash_algorithm as u8)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashAlgorithm, D::Error> {
+        let index = <u8 as Deserialize>::deserialize(deserializer)?;
+        HashAlgorithm::try_from_slice(&[index])
+            .map_err(|_| D::Error::custom(format!("unknown hash algorithm {index}")))
+    }
+}
@@ -0,0 +1,58 @@
+//! Conversions between slots and epochs, for swaps initiated through
+//! `initiate_with_epoch_expiry`, whose expiry is the first slot of an epoch.
+//!
+//! ```ignore
+//! let schedule = rpc.get_epoch_schedule().await?;
+//! let slot = rpc.get_slot().await?;
+//! let expires_in_epochs = expires_in_epochs_for_slots(&schedule, slot, 1_000_000)
+//!     .filter(|&epochs| epochs <= max_expires_in_epochs(&schedule, config.max_expiry_slots))
+//!     .expect("expiry within the configured maximum");
+//! let expiry_slot = epoch_expiry_slot(&schedule, slot, expires_in_epochs);
+//! ```
+
+use anchor_lang::prelude::EpochSchedule;
+
+/// The most epochs a swap may be initiated to expire in, given the program's configured maximum
+/// expiry, as enforced by `initiate_with_epoch_expiry`
+pub fn max_expires_in_epochs(schedule: &EpochSchedule, max_expiry_slots: u64) -> u64 {
+    max_expiry_slots / schedule.slots_per_epoch
+}
+
+/// The first slot of `epoch`, or `None` if it overflows
+pub fn first_slot_in_epoch(schedule: &EpochSchedule, epoch: u64) -> Option<u64> {
+    if epoch <= schedule.first_normal_epoch {
+        // The warmup epochs, doubling in length, are few enough not to overflow
+        return Some(schedule.get_first_slot_in_epoch(epoch));
+    }
+    (epoch - schedule.first_normal_epoch)
+        .checked_mul(schedule.slots_per_epoch)?
+        .checked_add(schedule.first_normal_slot)
+}
+
+/// The expiry slot of a swap initiated at `slot` to expire in `expires_in_epochs` epochs,
+/// i.e. the first slot of the `expires_in_epochs`-th epoch after that of `slot`, or `None`
+/// if it overflows
+pub fn epoch_expiry_slot(
+    schedule: &EpochSchedule,
+    slot: u64,
+    expires_in_epochs: u64,
+) -> Option<u64> {
+    let expiry_epoch = schedule.get_epoch(slot).checked_add(expires_in_epochs)?;
+    first_slot_in_epoch(schedule, expiry_epoch)
+}
+
+/// The fewest epochs a swap initiated at `slot` must be initiated to expire in for it to remain
+/// unrefundable for at least `expires_in_slots` slots, or `None` if that overflows
+pub fn expires_in_epochs_for_slots(
+    schedule: &EpochSchedule,
+    slot: u64,
+    expires_in_slots: u64,
+) -> Option<u64> {
+    let expiry_slot = slot.checked_add(expires_in_slots)?;
+    let epoch = schedule.get_epoch(slot);
+    let mut expiry_epoch = schedule.get_epoch(expiry_slot);
+    if first_slot_in_epoch(schedule, expiry_epoch)? < expiry_slot {
+        expiry_epoch += 1;
+    }
+    Some((expiry_epoch - epoch).max(1))
+}
@@ -0,0 +1,133 @@
+//! Decoding of the swap lifecycle events emitted by the program.
+//!
+//! The program emits events through a self-CPI, with the event as the data of an inner
+//! instruction, which is reliably retained by the RPC. Swaps initiated by earlier releases of
+//! the program may instead have emitted them as base64-encoded `Program data:` log lines,
+//! which the RPC may truncate, so both sources are supported.
+
+use anchor_lang::{event::EVENT_IX_TAG_LE, prelude::Pubkey, AnchorDeserialize, Discriminator};
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+pub use solana_native_swaps::{Initiated, InstantRefunded, Redeemed, Refunded};
+
+use crate::{ClientError, ID};
+
+/// The prefix of log lines carrying events
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// An event of the swap lifecycle
+pub enum SwapEvent {
+    Initiated(Initiated),
+    Redeemed(Redeemed),
+    Refunded(Refunded),
+    InstantRefunded(InstantRefunded),
+}
+
+impl SwapEvent {
+    /// The swap account the event pertains to
+    pub fn swap_account(&self) -> Pubkey {
+        match self {
+            SwapEvent::Initiated(event) => event.swap_account,
+            SwapEvent::Redeemed(event) => event.swap_account,
+            SwapEvent::Refunded(event) => event.swap_account,
+            SwapEvent::InstantRefunded(event) => event.swap_account,
+        }
+    }
+
+    /// Decodes an event from its discriminator followed by its fields.
+    /// Returns `None` for any other event of the program.
+    pub fn decode(data: &[u8]) -> Result<Option<Self>, ClientError> {
+        let Some((discriminator, mut fields)) = data.split_at_checked(8) else {
+            return Err(ClientError::InvalidEvent(
+                "missing discriminator".to_string(),
+            ));
+        };
+        let event = match discriminator {
+            d if d == Initiated::DISCRIMINATOR => {
+                SwapEvent::Initiated(deserialize(&mut fields, "Initiated")?)
+            }
+            d if d == Redeemed::DISCRIMINATOR => {
+                SwapEvent::Redeemed(deserialize(&mut fields, "Redeemed")?)
+            }
+            d if d == Refunded::DISCRIMINATOR => {
+                SwapEvent::Refunded(deserialize(&mut fields, "Refunded")?)
+            }
+            d if d == InstantRefunded::DISCRIMINATOR => {
+                SwapEvent::InstantRefunded(deserialize(&mut fields, "InstantRefunded")?)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+
+    /// Decodes an event from the data of a self-CPI instruction of the program.
+    /// Returns `None` for instructions other than event emissions, and for other events.
+    pub fn from_cpi_data(data: &[u8]) -> Result<Option<Self>, ClientError> {
+        match data.strip_prefix(EVENT_IX_TAG_LE) {
+            Some(event) => Self::decode(event),
+            None => Ok(None),
+        }
+    }
+
+    /// Decodes an event from a `Program data:` log line of the program.
+    /// Returns `None` for other log lines, and for other events.
+    pub fn from_log(log: &str) -> Result<Option<Self>, ClientError> {
+        let Some(encoded) = log.strip_prefix(PROGRAM_DATA) else {
+            return Ok(None);
+        };
+        let data = BASE64_STANDARD
+            .decode(encoded)
+            .map_err(|e| ClientError::InvalidEvent(format!("invalid base64: {e}")))?;
+        Self::decode(&data)
+    }
+}
+
+/// Decodes the events emitted through self-CPI, given the program ID and data of each inner
+/// instruction of a transaction in order
+pub fn parse_inner_instructions<'a>(
+    instructions: impl IntoIterator<Item = (&'a Pubkey, &'a [u8])>,
+) -> Result<Vec<SwapEvent>, ClientError> {
+    instructions
+        .into_iter()
+        .filter(|(program_id, _)| **program_id == ID)
+        .filter_map(|(_, data)| SwapEvent::from_cpi_data(data).transpose())
+        .collect()
+}
+
+/// Decodes the events logged by the program in the log messages of a transaction.
+/// Only lines logged while the program is the innermost invocation are considered,
+/// so that other programs cannot forge events by logging the same data.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Result<Vec<SwapEvent>, ClientError> {
+    let program_id = ID.to_string();
+    let mut invocations: Vec<&str> = vec![];
+    let mut events = vec![];
+
+    for log in logs.iter().map(AsRef::as_ref) {
+        let invocation = log
+            .strip_prefix("Program ")
+            .and_then(|log| log.split_once(' '));
+        match invocation {
+            Some((invoked, status)) if status.starts_with("invoke [") => {
+                invocations.push(invoked);
+            }
+            // Matching the innermost invocation rules out `Program log:` lines
+            Some((completed, status))
+                if invocations.last() == Some(&completed)
+                    && (status == "success" || status.starts_with("failed: ")) =>
+            {
+                invocations.pop();
+            }
+            _ if invocations.last() == Some(&program_id.as_str()) => {
+                if let Some(event) = SwapEvent::from_log(log)? {
+                    events.push(event);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+fn deserialize<T: AnchorDeserialize>(fields: &mut &[u8], name: &str) -> Result<T, ClientError> {
+    T::deserialize(fields).map_err(|e| ClientError::InvalidEvent(format!("{name}: {e}")))
+}
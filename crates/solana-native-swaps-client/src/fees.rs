@@ -0,0 +1,149 @@
+//! Priority fee estimation and escalation, for transactions which cannot afford to sit
+//! unconfirmed, e.g. redeems racing the swap's expiry.
+//!
+//! The initial compute unit price is estimated from the prioritization fees recently paid for
+//! the accounts the transaction writes to. Unconfirmed transactions are then rebroadcast at an
+//! escalating price until one of them confirms.
+//!
+//! ```ignore
+//! let redeem = RedeemBuilder::new(&swap, secret).instruction();
+//! let signature = FeeStrategy::default().send(&rpc, &[redeem], &payer, &[&payer]).await?;
+//! ```
+
+use std::time::Duration;
+
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_signature::Signature;
+use solana_signer::signers::Signers;
+use solana_transaction::Transaction;
+
+use crate::{
+    rpc::{RpcClient, TransactionStatus},
+    ClientError, Instruction, Pubkey,
+};
+
+/// The interval at which the statuses of broadcast transactions are polled for
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How compute unit prices are estimated and escalated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeStrategy {
+    /// The compute unit limit of transactions, bounding the priority fee paid
+    pub compute_unit_limit: u32,
+    /// The percentile of recent prioritization fees the initial price is estimated at
+    pub percentile: u8,
+    /// The bounds of the compute unit price, in micro-lamports
+    pub min_micro_lamports: u64,
+    pub max_micro_lamports: u64,
+    /// The factor the price is multiplied by on each rebroadcast
+    pub multiplier: u64,
+    /// The time a transaction is given to confirm before being rebroadcast at a higher price
+    pub rebroadcast_interval: Duration,
+    /// The maximum number of broadcasts before giving up
+    pub max_broadcasts: u32,
+}
+
+impl Default for FeeStrategy {
+    fn default() -> Self {
+        FeeStrategy {
+            compute_unit_limit: 60_000,
+            percentile: 75,
+            min_micro_lamports: 1_000,
+            max_micro_lamports: 1_000_000,
+            multiplier: 2,
+            rebroadcast_interval: Duration::from_secs(5),
+            max_broadcasts: 8,
+        }
+    }
+}
+
+impl FeeStrategy {
+    /// Estimates the compute unit price of a transaction writing to `writable_accounts`,
+    /// in micro-lamports
+    pub async fn estimate(
+        &self,
+        rpc: &RpcClient,
+        writable_accounts: &[Pubkey],
+    ) -> Result<u64, ClientError> {
+        let mut fees = rpc
+            .get_recent_prioritization_fees(writable_accounts)
+            .await?;
+        fees.sort_unstable();
+        let estimate = match fees.len() {
+            0 => 0,
+            len => fees[(len - 1) * usize::from(self.percentile.min(100)) / 100],
+        };
+        Ok(estimate.clamp(self.min_micro_lamports, self.max_micro_lamports))
+    }
+
+    /// The compute budget instructions setting the compute unit limit and `micro_lamports` as
+    /// the compute unit price, to be prepended to a transaction's instructions
+    pub fn compute_budget_instructions(&self, micro_lamports: u64) -> [Instruction; 2] {
+        [
+            ComputeBudgetInstruction::set_compute_unit_limit(self.compute_unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(micro_lamports),
+        ]
+    }
+
+    /// Submits a transaction of `instructions` paid for by `payer` and signed by `signers`,
+    /// rebroadcasting it at an escalating price until confirmed. Returns the signature of the
+    /// broadcast which confirmed.
+    pub async fn send<T: Signers + ?Sized>(
+        &self,
+        rpc: &RpcClient,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &T,
+    ) -> Result<Signature, ClientError> {
+        let writable_accounts: Vec<Pubkey> = instructions
+            .iter()
+            .flat_map(|instruction| &instruction.accounts)
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        let mut micro_lamports = self.estimate(rpc, &writable_accounts).await?;
+        // Earlier broadcasts may still confirm, so the statuses of all of them are tracked
+        let mut broadcasts = vec![];
+
+        for _ in 0..self.max_broadcasts {
+            let instructions: Vec<Instruction> = self
+                .compute_budget_instructions(micro_lamports)
+                .into_iter()
+                .chain(instructions.iter().cloned())
+                .collect();
+            let transaction = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(payer),
+                signers,
+                rpc.get_latest_blockhash().await?,
+            );
+            broadcasts.push(rpc.send_transaction(&transaction).await?);
+
+            let polls =
+                (self.rebroadcast_interval.as_millis() / STATUS_POLL_INTERVAL.as_millis()).max(1);
+            for _ in 0..polls {
+                tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+                let statuses = rpc.get_transaction_statuses(&broadcasts).await?;
+                for (signature, status) in broadcasts.iter().zip(statuses) {
+                    match status {
+                        TransactionStatus::Confirmed => return Ok(*signature),
+                        TransactionStatus::Failed(error) => {
+                            return Err(ClientError::TransactionFailed {
+                                signature: *signature,
+                                error,
+                            })
+                        }
+                        TransactionStatus::Pending => {}
+                    }
+                }
+            }
+
+            micro_lamports = micro_lamports
+                .saturating_mul(self.multiplier)
+                .min(self.max_micro_lamports);
+        }
+        Err(ClientError::Unconfirmed(
+            *broadcasts.last().expect("at least one broadcast"),
+        ))
+    }
+}
@@ -0,0 +1,401 @@
+//! Idempotent submission of transactions, so that a logical operation, e.g. initiating a given
+//! swap, is executed at most once however many times it is submitted.
+//!
+//! Each operation is identified by a caller-chosen [`IdempotencyKey`], recorded on-chain in a
+//! memo attached to every attempt, and locally in an [`IdempotencyStore`] along with the
+//! signature and blockhash expiry of every attempt. Before a new attempt is signed, previous
+//! attempts are checked for having landed, both through their recorded signatures and by
+//! scanning the payer's recent transactions for the key's memo, and any attempt whose blockhash
+//! has yet to expire is waited out, as it may still land. A confirmation timing out therefore
+//! never results in the operation being executed twice.
+//!
+//! ```ignore
+//! let submitter = IdempotentSubmitter::new(&rpc, FileStore::new("operations.json"));
+//! let key = IdempotencyKey::new(format!("initiate-{order_id}"))?;
+//! let signature = submitter.submit(&key, &[initiate], &payer, &[&payer]).await?;
+//! // Resubmitting, e.g. after a restart, returns the same signature without a new transaction
+//! assert_eq!(submitter.submit(&key, &[initiate], &payer, &[&payer]).await?, signature);
+//! ```
+
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Mutex, time::Duration};
+
+use anchor_lang::{prelude::pubkey, solana_program::instruction::Instruction};
+use serde::{Deserialize, Serialize};
+use solana_signature::Signature;
+use solana_signer::signers::Signers;
+use solana_transaction::Transaction;
+
+use crate::{
+    rpc::{RpcClient, TransactionStatus},
+    ClientError, Pubkey,
+};
+
+/// The memo program, recording the idempotency keys of attempts on-chain
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// The prefix of the memos recording idempotency keys, setting them apart from other memos
+const MEMO_PREFIX: &str = "swap-idempotency:";
+
+/// The maximum length of an idempotency key, keeping its memo cheap to include
+const MAX_KEY_LEN: usize = 64;
+
+/// The interval at which the statuses of attempts are polled for
+const STATUS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The number of polls between rebroadcasts of an attempt yet to land
+const POLLS_PER_REBROADCAST: u32 = 4;
+
+/// Identifies a logical operation across submissions, e.g. `initiate-<order id>`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    /// Fails unless `key` is made of 1 to 64 printable ASCII characters
+    pub fn new(key: impl Into<String>) -> Result<Self, ClientError> {
+        let key = key.into();
+        if key.is_empty()
+            || key.len() > MAX_KEY_LEN
+            || !key.bytes().all(|byte| byte.is_ascii_graphic())
+        {
+            return Err(ClientError::InvalidIdempotencyKey(key));
+        }
+        Ok(IdempotencyKey(key))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The memo recording this key in the attempts of its operation
+    pub fn memo(&self) -> String {
+        format!("{MEMO_PREFIX}{}", self.0)
+    }
+
+    /// The memo program instruction recording this key, appended to every attempt
+    pub fn memo_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(MEMO_PROGRAM_ID, self.memo().as_bytes(), vec![])
+    }
+
+    /// Whether `memo`, as returned by `getSignaturesForAddress`, records this key. The RPC joins
+    /// the memos of a transaction with "; ", each prefixed by its length in brackets.
+    pub fn matches_memo(&self, memo: &str) -> bool {
+        let expected = self.memo();
+        memo.split("; ").any(|memo| {
+            let memo = match memo.split_once("] ") {
+                Some((length, memo)) if length.starts_with('[') => memo,
+                _ => memo,
+            };
+            memo == expected
+        })
+    }
+}
+
+/// A transaction submitted for an operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    #[serde(with = "signature_string")]
+    pub signature: Signature,
+    /// The last block height at which the attempt may still land, past which it never will
+    pub last_valid_block_height: u64,
+}
+
+/// What is known of an operation's submissions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationRecord {
+    /// The attempts which have yet to be found to land or to have failed
+    pub attempts: Vec<Attempt>,
+    /// The signature of the attempt which executed the operation, once found
+    #[serde(with = "option_signature_string", default)]
+    pub landed: Option<Signature>,
+}
+
+/// Persists the records of operations, so that submissions are deduplicated across restarts
+pub trait IdempotencyStore {
+    fn load(&self, key: &IdempotencyKey) -> Result<Option<OperationRecord>, ClientError>;
+
+    fn save(&self, key: &IdempotencyKey, record: &OperationRecord) -> Result<(), ClientError>;
+}
+
+/// Keeps records in memory, deduplicating submissions within a process only
+#[derive(Default)]
+pub struct MemoryStore(Mutex<HashMap<IdempotencyKey, OperationRecord>>);
+
+impl IdempotencyStore for MemoryStore {
+    fn load(&self, key: &IdempotencyKey) -> Result<Option<OperationRecord>, ClientError> {
+        Ok(self.0.lock().unwrap().get(key).cloned())
+    }
+
+    fn save(&self, key: &IdempotencyKey, record: &OperationRecord) -> Result<(), ClientError> {
+        self.0.lock().unwrap().insert(key.clone(), record.clone());
+        Ok(())
+    }
+}
+
+/// Keeps records in a JSON file, rewritten on every save
+pub struct FileStore {
+    path: PathBuf,
+    // Serializes the read-modify-write of saves within the process
+    lock: Mutex<()>,
+}
+
+impl FileStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileStore {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> Result<HashMap<String, OperationRecord>, ClientError> {
+        match std::fs::read(&self.path) {
+            Ok(contents) => serde_json::from_slice(&contents).map_err(|e| {
+                ClientError::IdempotencyStore(format!("{}: {e}", self.path.display()))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(ClientError::IdempotencyStore(format!(
+                "{}: {e}",
+                self.path.display()
+            ))),
+        }
+    }
+}
+
+impl IdempotencyStore for FileStore {
+    fn load(&self, key: &IdempotencyKey) -> Result<Option<OperationRecord>, ClientError> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.read()?.remove(key.as_str()))
+    }
+
+    fn save(&self, key: &IdempotencyKey, record: &OperationRecord) -> Result<(), ClientError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut records = self.read()?;
+        records.insert(key.0.clone(), record.clone());
+        let contents = serde_json::to_vec_pretty(&records)
+            .map_err(|e| ClientError::IdempotencyStore(e.to_string()))?;
+        // Written aside then renamed, so that a crash mid-write cannot lose the records
+        let temporary = self.path.with_extension("tmp");
+        std::fs::write(&temporary, contents)
+            .and_then(|()| std::fs::rename(&temporary, &self.path))
+            .map_err(|e| ClientError::IdempotencyStore(format!("{}: {e}", self.path.display())))
+    }
+}
+
+/// Submits transactions at most once per idempotency key
+pub struct IdempotentSubmitter<'a, S> {
+    rpc: &'a RpcClient,
+    store: S,
+    /// The number of pages of the payer's recent transactions scanned for the key's memo
+    pub scan_pages: usize,
+    /// The maximum number of attempts made by a single submission, each signed against a new
+    /// blockhash once the previous one has expired
+    pub max_attempts: u32,
+}
+
+impl<'a, S: IdempotencyStore> IdempotentSubmitter<'a, S> {
+    pub fn new(rpc: &'a RpcClient, store: S) -> Self {
+        IdempotentSubmitter {
+            rpc,
+            store,
+            scan_pages: 1,
+            max_attempts: 3,
+        }
+    }
+
+    /// Executes the operation identified by `key` as a transaction of `instructions`, with the
+    /// key's memo appended, paid for by `payer` and signed by `signers`, unless it has already
+    /// been executed. Returns the signature of the transaction which executed it.
+    pub async fn submit<T: Signers + ?Sized>(
+        &self,
+        key: &IdempotencyKey,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &T,
+    ) -> Result<Signature, ClientError> {
+        let mut record = self.store.load(key)?.unwrap_or_default();
+        if let Some(signature) = record.landed {
+            return Ok(signature);
+        }
+        if let Some(signature) = self.find_landed(key, payer, &mut record).await? {
+            return Ok(signature);
+        }
+
+        let instructions: Vec<Instruction> = instructions
+            .iter()
+            .cloned()
+            .chain(std::iter::once(key.memo_instruction()))
+            .collect();
+        for _ in 0..self.max_attempts {
+            let (blockhash, last_valid_block_height) =
+                self.rpc.get_latest_blockhash_with_expiry().await?;
+            let transaction =
+                Transaction::new_signed_with_payer(&instructions, Some(payer), signers, blockhash);
+            let attempt = Attempt {
+                signature: transaction.signatures[0],
+                last_valid_block_height,
+            };
+            // Recorded before being sent, so that an attempt is never lost track of
+            record.attempts.push(attempt);
+            self.store.save(key, &record)?;
+            self.rpc.send_transaction(&transaction).await?;
+
+            if let Some(signature) = self
+                .await_attempts(key, &mut record, Some(&transaction))
+                .await?
+            {
+                return Ok(signature);
+            }
+        }
+        let last = record.attempts.last().map(|attempt| attempt.signature);
+        Err(ClientError::Unconfirmed(last.unwrap_or_default()))
+    }
+
+    /// Finds the attempt which executed the operation identified by `key`, if any, among the
+    /// recorded attempts and the recent transactions of `payer`, waiting out recorded attempts
+    /// which may still land
+    async fn find_landed(
+        &self,
+        key: &IdempotencyKey,
+        payer: &Pubkey,
+        record: &mut OperationRecord,
+    ) -> Result<Option<Signature>, ClientError> {
+        if let Some(signature) = self.await_attempts(key, record, None).await? {
+            return Ok(Some(signature));
+        }
+
+        // Attempts made by another process, or whose record was lost, are found by their memo
+        let mut before = None;
+        for _ in 0..self.scan_pages {
+            let page = self
+                .rpc
+                .get_signatures_for_address(payer, before.as_ref(), None)
+                .await?;
+            let found = page.iter().find(|info| {
+                !info.failed
+                    && info
+                        .memo
+                        .as_deref()
+                        .is_some_and(|memo| key.matches_memo(memo))
+            });
+            if let Some(info) = found {
+                record.landed = Some(info.signature);
+                self.store.save(key, record)?;
+                return Ok(Some(info.signature));
+            }
+            match page.last() {
+                Some(last) => before = Some(last.signature),
+                None => break,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Polls the statuses of the recorded attempts until one lands or every one of them has
+    /// expired, rebroadcasting `transaction`, the latest attempt, if provided. Attempts which
+    /// failed or expired are dropped from the record.
+    async fn await_attempts(
+        &self,
+        key: &IdempotencyKey,
+        record: &mut OperationRecord,
+        transaction: Option<&Transaction>,
+    ) -> Result<Option<Signature>, ClientError> {
+        let mut polls = 0u32;
+        while !record.attempts.is_empty() {
+            let signatures: Vec<Signature> = record
+                .attempts
+                .iter()
+                .map(|attempt| attempt.signature)
+                .collect();
+            let statuses = self.rpc.get_transaction_statuses(&signatures).await?;
+            // Read after the statuses, so that an attempt found expired had not landed before
+            let block_height = self.rpc.get_block_height().await?;
+
+            let mut failure = None;
+            let mut remaining = vec![];
+            for (attempt, status) in record.attempts.iter().zip(statuses) {
+                match status {
+                    TransactionStatus::Confirmed => {
+                        record.landed = Some(attempt.signature);
+                        record.attempts.clear();
+                        self.store.save(key, record)?;
+                        return Ok(record.landed);
+                    }
+                    TransactionStatus::Failed(error) => {
+                        failure = Some(ClientError::TransactionFailed {
+                            signature: attempt.signature,
+                            error,
+                        })
+                    }
+                    TransactionStatus::Pending
+                        if block_height <= attempt.last_valid_block_height =>
+                    {
+                        remaining.push(*attempt)
+                    }
+                    TransactionStatus::Pending => {}
+                }
+            }
+            if remaining.len() != record.attempts.len() {
+                record.attempts = remaining;
+                self.store.save(key, record)?;
+            }
+            // A failed attempt executed nothing, and leaves it to the caller to retry or not
+            if let Some(failure) = failure {
+                return Err(failure);
+            }
+
+            tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+            polls += 1;
+            if let Some(transaction) = transaction {
+                let signature = transaction.signatures[0];
+                let pending = record
+                    .attempts
+                    .iter()
+                    .any(|attempt| attempt.signature == signature);
+                if pending && polls.is_multiple_of(POLLS_PER_REBROADCAST) {
+                    // Resending the same transaction cannot execute it twice
+                    self.rpc.send_transaction(transaction).await?;
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+mod signature_string {
+    use super::*;
+    use serde::{de::Error, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        signature: &Signature,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(signature)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Signature, D::Error> {
+        let signature = String::deserialize(deserializer)?;
+        Signature::from_str(&signature).map_err(D::Error::custom)
+    }
+}
+
+mod option_signature_string {
+    use super::*;
+    use serde::{de::Error, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        signature: &Option<Signature>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match signature {
+            Some(signature) => serializer.collect_str(signature),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Signature>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|signature| Signature::from_str(&signature).map_err(D::Error::custom))
+            .transpose()
+    }
+}
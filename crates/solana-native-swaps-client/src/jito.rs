@@ -0,0 +1,189 @@
+//! [Jito bundles](https://docs.jito.wtf/lowlatencytxnsend/#bundles-api-v1-bundles), for
+//! submitting `initiate` together with related transactions, e.g. both legs of a swap on
+//! Solana, or an initiate and the fill of the counter-order, so that they land atomically and
+//! in order, or not at all.
+//!
+//! A bundle is only considered by the block engine if one of its transactions tips one of the
+//! Jito tip accounts. The tip is best paid by the last transaction, so that it is only paid if
+//! all of the bundle lands.
+//!
+//! ```ignore
+//! let jito = JitoClient::new(MAINNET_BLOCK_ENGINE_URL);
+//! let tip_account = jito.get_tip_accounts().await?[0];
+//! let blockhash = rpc.get_latest_blockhash().await?;
+//! let initiate = Transaction::new_signed_with_payer(
+//!     &[InitiateBuilder::new(alice, bob, secret_hash, amount, 300).instruction()],
+//!     Some(&alice),
+//!     &[&alice_keypair],
+//!     blockhash,
+//! );
+//! // ... have the counterparty sign their leg, tipping with tip_instruction(&bob, &tip_account, tip)
+//! let (bundle_id, slot) = jito.send_and_confirm_bundle(&[initiate, counter_leg]).await?;
+//! ```
+
+use std::time::Duration;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::json;
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction::Transaction;
+
+use crate::{rpc::RpcClient, ClientError, Instruction, Pubkey};
+
+/// The bundles endpoint of the mainnet block engine
+pub const MAINNET_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf/api/v1/bundles";
+
+/// The maximum number of transactions in a bundle
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// The minimum tip for a bundle to be considered, in lamports
+pub const MIN_TIP_LAMPORTS: u64 = 1_000;
+
+/// The interval at which bundle statuses are polled for
+const BUNDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of times a bundle's status is polled for before giving up,
+/// spanning roughly the lifetime of a blockhash
+const BUNDLE_POLL_ATTEMPTS: usize = 75;
+
+/// The status of a submitted bundle, as known to the block engine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleStatus {
+    /// Not yet landed, or unknown to the block engine
+    Pending,
+    /// Landed in the slot
+    Landed(u64),
+    /// Rejected by the block engine, e.g. as a transaction of the bundle failed
+    Failed,
+}
+
+/// The instruction tipping `lamports` from `payer` to `tip_account`, one of the accounts
+/// returned by [`JitoClient::get_tip_accounts`]
+pub fn tip_instruction(payer: &Pubkey, tip_account: &Pubkey, lamports: u64) -> Instruction {
+    system_instruction::transfer(payer, tip_account, lamports)
+}
+
+/// A client of the bundles API of a Jito block engine
+pub struct JitoClient {
+    rpc: RpcClient,
+}
+
+impl JitoClient {
+    /// A client of the block engine's bundles endpoint at `url`,
+    /// e.g. [`MAINNET_BLOCK_ENGINE_URL`]
+    pub fn new(url: impl Into<String>) -> Self {
+        JitoClient {
+            rpc: RpcClient::new(url),
+        }
+    }
+
+    /// Fetches the accounts bundles may tip
+    pub async fn get_tip_accounts(&self) -> Result<Vec<Pubkey>, ClientError> {
+        let accounts: Vec<String> = self.rpc.call("getTipAccounts", json!([])).await?;
+        accounts
+            .iter()
+            .map(|account| {
+                account.parse().map_err(|_| {
+                    ClientError::InvalidResponse(format!("invalid tip account {account}"))
+                })
+            })
+            .collect()
+    }
+
+    /// Submits a bundle of signed `transactions`, to be executed in order, returning the
+    /// bundle's ID without awaiting it landing
+    pub async fn send_bundle(&self, transactions: &[Transaction]) -> Result<String, ClientError> {
+        if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(ClientError::InvalidTransaction(format!(
+                "bundles must have between 1 and {MAX_BUNDLE_TRANSACTIONS} transactions, got {}",
+                transactions.len()
+            )));
+        }
+        let transactions = transactions
+            .iter()
+            .map(|transaction| {
+                bincode::serialize(transaction)
+                    .map(|serialized| BASE64_STANDARD.encode(serialized))
+                    .map_err(|e| ClientError::InvalidTransaction(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.rpc
+            .call(
+                "sendBundle",
+                json!([transactions, { "encoding": "base64" }]),
+            )
+            .await
+    }
+
+    /// Fetches the statuses of the bundles with `bundle_ids`, in order. Only bundles submitted
+    /// within the last five minutes are known to the block engine.
+    pub async fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> Result<Vec<BundleStatus>, ClientError> {
+        let response: InflightBundleStatuses = self
+            .rpc
+            .call("getInflightBundleStatuses", json!([bundle_ids]))
+            .await?;
+        Ok(bundle_ids
+            .iter()
+            .map(|bundle_id| {
+                match response
+                    .value
+                    .iter()
+                    .find(|status| status.bundle_id == *bundle_id)
+                {
+                    Some(InflightBundleStatus {
+                        status,
+                        landed_slot,
+                        ..
+                    }) => match (status.as_str(), landed_slot) {
+                        ("Landed", Some(slot)) => BundleStatus::Landed(*slot),
+                        ("Failed", _) => BundleStatus::Failed,
+                        _ => BundleStatus::Pending,
+                    },
+                    None => BundleStatus::Pending,
+                }
+            })
+            .collect())
+    }
+
+    /// Awaits the bundle with `bundle_id` landing, returning the slot it landed in
+    pub async fn confirm_bundle(&self, bundle_id: &str) -> Result<u64, ClientError> {
+        let bundle_ids = [bundle_id.to_string()];
+        for _ in 0..BUNDLE_POLL_ATTEMPTS {
+            match self.get_bundle_statuses(&bundle_ids).await?[0] {
+                BundleStatus::Landed(slot) => return Ok(slot),
+                BundleStatus::Failed => {
+                    return Err(ClientError::BundleFailed(bundle_id.to_string()))
+                }
+                BundleStatus::Pending => {}
+            }
+            tokio::time::sleep(BUNDLE_POLL_INTERVAL).await;
+        }
+        Err(ClientError::BundleUnconfirmed(bundle_id.to_string()))
+    }
+
+    /// Submits a bundle of signed `transactions` and awaits it landing, returning the bundle's
+    /// ID and the slot it landed in
+    pub async fn send_and_confirm_bundle(
+        &self,
+        transactions: &[Transaction],
+    ) -> Result<(String, u64), ClientError> {
+        let bundle_id = self.send_bundle(transactions).await?;
+        let slot = self.confirm_bundle(&bundle_id).await?;
+        Ok((bundle_id, slot))
+    }
+}
+
+#[derive(Deserialize)]
+struct InflightBundleStatuses {
+    value: Vec<InflightBundleStatus>,
+}
+
+#[derive(Deserialize)]
+struct InflightBundleStatus {
+    bundle_id: String,
+    status: String,
+    landed_slot: Option<u64>,
+}
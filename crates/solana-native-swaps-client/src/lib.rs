@@ -0,0 +1,605 @@
+//! A client for the native SOL atomic swap program, providing typed builders for the
+//! instructions of the swap lifecycle, derivation of the program's PDAs, and fetching and
+//! decoding of swap accounts.
+//!
+//! ```ignore
+//! let initiate = InitiateBuilder::new(initiator, redeemer, secret_hash, 1_000_000_000, 300)
+//!     .refund_to(cold_wallet)
+//!     .instruction();
+//! // ... once the counterpart chain's leg has been funded
+//! let swap = fetch_swap_account(&rpc, &pda::swap_account(&initiator, &redeemer, &secret_hash).0)
+//!     .await?
+//!     .expect("swap is open");
+//! let redeem = RedeemBuilder::new(&swap, secret).instruction();
+//! ```
+
+use anchor_lang::{
+    prelude::{AccountMeta, Clock},
+    solana_program::{system_program, sysvar},
+    AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use solana_transaction::Transaction;
+
+use rpc::{Memcmp, RpcClient};
+
+pub use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+pub use solana_native_swaps::{
+    self as program, pda, HashAlgorithm, ProgramVersion, RedeemWindow, SwapAccount, SwapIncentives,
+    SwapInfo, ABANDONED_SWAP_GRACE_SLOTS, ID, PROGRAM_VERSION, SWAP_ACCOUNT_VERSION,
+    SWAP_SEEDS_VERSION,
+};
+
+pub mod coordinator;
+pub mod epoch;
+pub mod events;
+pub mod fees;
+pub mod idempotency;
+pub mod jito;
+pub mod lookup_table;
+pub mod nonce;
+pub mod quote;
+pub mod rpc;
+pub mod security_txt;
+pub mod solana_pay;
+pub mod state;
+pub mod tenant;
+
+/// The size of the discriminator prefixing every account of the program
+const ANCHOR_DISCRIMINATOR: usize = 8;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("RPC error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("invalid RPC response: {0}")]
+    InvalidResponse(String),
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("transaction {signature} failed: {error}")]
+    TransactionFailed {
+        signature: solana_signature::Signature,
+        error: String,
+    },
+    #[error("simulation failed: {0}")]
+    SimulationFailed(String),
+    #[error("transaction {0} was not confirmed in time")]
+    Unconfirmed(solana_signature::Signature),
+    #[error("bundle {0} failed")]
+    BundleFailed(String),
+    #[error("bundle {0} did not land in time")]
+    BundleUnconfirmed(String),
+    #[error("malformed event: {0}")]
+    InvalidEvent(String),
+    #[error("account is not a swap account")]
+    NotSwapAccount,
+    #[error("invalid nonce account: {0}")]
+    InvalidNonceAccount(String),
+    #[error("invalid lookup table: {0}")]
+    InvalidLookupTable(String),
+    #[error("invalid security.txt: {0}")]
+    InvalidSecurityTxt(String),
+    #[error("invalid transaction request: {0}")]
+    InvalidTransactionRequest(String),
+    #[error("invalid idempotency key: {0}")]
+    InvalidIdempotencyKey(String),
+    #[error("idempotency store: {0}")]
+    IdempotencyStore(String),
+    #[error("invalid oracle price: {0}")]
+    InvalidPrice(String),
+    #[error("cannot quote: {0}")]
+    InvalidQuote(String),
+    #[error("swap store: {0}")]
+    SwapStore(String),
+    #[error("swap account has layout version {0}, expected {SWAP_ACCOUNT_VERSION}")]
+    UnsupportedSwapVersion(u8),
+    #[error("cannot {transition} from state {state}")]
+    InvalidTransition {
+        state: state::SwapState,
+        transition: state::SwapTransition,
+    },
+}
+
+/// Builds an `initiate` instruction. Swaps default to SHA-256 secret hashes, with no incentives
+/// or redeem window, funded by the initiator, and refunded to the initiator.
+#[derive(Clone)]
+pub struct InitiateBuilder {
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    secret_hash: [u8; 32],
+    amount_lamports: u64,
+    expires_in_slots: u64,
+    hash_algorithm: HashAlgorithm,
+    incentives: SwapIncentives,
+    redeem_window: RedeemWindow,
+    funder: Option<Pubkey>,
+    refund_to: Option<Pubkey>,
+    nonce: Option<u32>,
+}
+
+impl InitiateBuilder {
+    pub fn new(
+        initiator: Pubkey,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    ) -> Self {
+        InitiateBuilder {
+            initiator,
+            redeemer,
+            secret_hash,
+            amount_lamports,
+            expires_in_slots,
+            hash_algorithm: HashAlgorithm::default(),
+            incentives: SwapIncentives::default(),
+            redeem_window: RedeemWindow::default(),
+            funder: None,
+            refund_to: None,
+            nonce: None,
+        }
+    }
+
+    pub fn hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+
+    pub fn incentives(mut self, incentives: SwapIncentives) -> Self {
+        self.incentives = incentives;
+        self
+    }
+
+    pub fn redeem_window(mut self, redeem_window: RedeemWindow) -> Self {
+        self.redeem_window = redeem_window;
+        self
+    }
+
+    /// Has `funder` provide the swap amount on behalf of the initiator.
+    /// The funder must then sign the transaction too.
+    pub fn funder(mut self, funder: Pubkey) -> Self {
+        self.funder = Some(funder);
+        self
+    }
+
+    /// Has refunds paid to `refund_to` in place of the initiator
+    pub fn refund_to(mut self, refund_to: Pubkey) -> Self {
+        self.refund_to = Some(refund_to);
+        self
+    }
+
+    /// Initiates the swap through `initiate_with_nonce` with the nonzero `nonce`, allowing it
+    /// to be open alongside other swaps between the same initiator and secret hash
+    pub fn nonce(mut self, nonce: u32) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// The address of the swap account the instruction creates
+    pub fn swap_account(&self) -> Pubkey {
+        match self.nonce {
+            Some(nonce) => {
+                pda::swap_account_with_nonce(
+                    &self.initiator,
+                    &self.redeemer,
+                    &self.secret_hash,
+                    nonce,
+                )
+                .0
+            }
+            None => pda::swap_account(&self.initiator, &self.redeemer, &self.secret_hash).0,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let data = match self.nonce {
+            Some(nonce) => program::instruction::InitiateWithNonce {
+                amount_lamports: self.amount_lamports,
+                expires_in_slots: self.expires_in_slots,
+                redeemer: self.redeemer,
+                secret_hash: self.secret_hash,
+                hash_algorithm: self.hash_algorithm,
+                incentives: self.incentives,
+                redeem_window: self.redeem_window,
+                nonce,
+            }
+            .data(),
+            None => program::instruction::Initiate {
+                amount_lamports: self.amount_lamports,
+                expires_in_slots: self.expires_in_slots,
+                redeemer: self.redeemer,
+                secret_hash: self.secret_hash,
+                hash_algorithm: self.hash_algorithm,
+                incentives: self.incentives,
+                redeem_window: self.redeem_window,
+            }
+            .data(),
+        };
+        // `initiate_with_nonce` shares the accounts of `initiate`
+        Instruction::new_with_bytes(
+            ID,
+            &data,
+            program::accounts::Initiate {
+                swap_account: self.swap_account(),
+                config: pda::config().0,
+                initiator: self.initiator,
+                funder: self.funder,
+                refund_to: self.refund_to,
+                system_program: system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+/// Builds a `redeem` instruction for a swap, redeeming its entire remaining funds
+/// to the redeemer by default
+#[derive(Clone)]
+pub struct RedeemBuilder {
+    swap_account: Pubkey,
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    secret: [u8; 32],
+    amount_lamports: u64,
+    destination: Option<Pubkey>,
+    relayer: Option<Pubkey>,
+    receipt_payer: Option<Pubkey>,
+}
+
+impl RedeemBuilder {
+    pub fn new(swap: &SwapAccount, secret: [u8; 32]) -> Self {
+        RedeemBuilder {
+            swap_account: pda::swap_account_of(swap).0,
+            initiator: swap.initiator,
+            redeemer: swap.redeemer,
+            secret,
+            amount_lamports: swap.amount_lamports,
+            destination: None,
+            relayer: None,
+            receipt_payer: None,
+        }
+    }
+
+    /// Redeems a tranche of `amount_lamports` of the swap's remaining funds
+    pub fn amount_lamports(mut self, amount_lamports: u64) -> Self {
+        self.amount_lamports = amount_lamports;
+        self
+    }
+
+    /// Directs the funds to `destination` in place of the redeemer.
+    /// The redeemer must then sign the transaction.
+    pub fn destination(mut self, destination: Pubkey) -> Self {
+        self.destination = Some(destination);
+        self
+    }
+
+    /// Has `relayer` submit the instruction on the redeemer's behalf, claiming the swap's relayer fee.
+    /// The relayer must then sign the transaction.
+    pub fn relayer(mut self, relayer: Pubkey) -> Self {
+        self.relayer = Some(relayer);
+        self
+    }
+
+    /// Records the revealed secret in a redeem receipt, funded by `receipt_payer`.
+    /// The receipt payer must then sign the transaction.
+    pub fn receipt(mut self, receipt_payer: Pubkey) -> Self {
+        self.receipt_payer = Some(receipt_payer);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            ID,
+            &program::instruction::Redeem {
+                secret: self.secret,
+                amount_lamports: self.amount_lamports,
+            }
+            .data(),
+            program::accounts::Redeem {
+                swap_account: self.swap_account,
+                initiator: self.initiator,
+                redeemer: self.redeemer,
+                destination: self.destination,
+                config: pda::config().0,
+                treasury: pda::treasury().0,
+                relayer: self.relayer,
+                receipt: self
+                    .receipt_payer
+                    .map(|_| pda::redeem_receipt(&self.swap_account).0),
+                receipt_payer: self.receipt_payer,
+                system_program: system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+/// Builds a `refund` instruction for an expired swap, paying the refund destination
+/// specified at initiate, if any
+#[derive(Clone)]
+pub struct RefundBuilder {
+    swap_account: Pubkey,
+    initiator: Pubkey,
+    refund_to: Option<Pubkey>,
+    refunder: Option<Pubkey>,
+    abandoned: bool,
+}
+
+impl RefundBuilder {
+    pub fn new(swap: &SwapAccount) -> Self {
+        RefundBuilder {
+            swap_account: pda::swap_account_of(swap).0,
+            initiator: swap.initiator,
+            refund_to: swap.refund_to(),
+            refunder: None,
+            abandoned: false,
+        }
+    }
+
+    /// Has `refunder` submit the instruction, claiming the swap's refund tip.
+    /// The refunder must then sign the transaction.
+    pub fn refunder(mut self, refunder: Pubkey) -> Self {
+        self.refunder = Some(refunder);
+        self
+    }
+
+    /// Refunds the swap through `sweep_abandoned`, allowed once `ABANDONED_SWAP_GRACE_SLOTS`
+    /// slots have passed since its expiry
+    pub fn sweep_abandoned(mut self) -> Self {
+        self.abandoned = true;
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let data = match self.abandoned {
+            true => program::instruction::SweepAbandoned {}.data(),
+            false => program::instruction::Refund {}.data(),
+        };
+        // `sweep_abandoned` shares the accounts of `refund`
+        Instruction::new_with_bytes(
+            ID,
+            &data,
+            program::accounts::Refund {
+                swap_account: self.swap_account,
+                initiator: self.initiator,
+                refund_to: self.refund_to,
+                refunder: self.refunder,
+                event_authority: pda::event_authority().0,
+                program: ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+/// Builds an `instant_refund` instruction for a swap, which the redeemer must sign
+#[derive(Clone)]
+pub struct InstantRefundBuilder {
+    swap_account: Pubkey,
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    refund_to: Option<Pubkey>,
+}
+
+impl InstantRefundBuilder {
+    pub fn new(swap: &SwapAccount) -> Self {
+        InstantRefundBuilder {
+            swap_account: pda::swap_account_of(swap).0,
+            initiator: swap.initiator,
+            redeemer: swap.redeemer,
+            refund_to: swap.refund_to(),
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            ID,
+            &program::instruction::InstantRefund {}.data(),
+            program::accounts::InstantRefund {
+                swap_account: self.swap_account,
+                initiator: self.initiator,
+                refund_to: self.refund_to,
+                redeemer: self.redeemer,
+                event_authority: pda::event_authority().0,
+                program: ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+/// Builds a `refund_expired_batch` instruction, refunding multiple expired swaps at once
+#[derive(Clone, Default)]
+pub struct RefundExpiredBatchBuilder {
+    swaps: Vec<(Pubkey, Pubkey, Pubkey)>,
+    refunder: Option<Pubkey>,
+}
+
+impl RefundExpiredBatchBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `swap` to the batch, to be refunded to its refund destination
+    pub fn swap(mut self, swap: &SwapAccount) -> Self {
+        self.swaps.push((
+            pda::swap_account_of(swap).0,
+            swap.initiator,
+            swap.refund_recipient(),
+        ));
+        self
+    }
+
+    /// Has `refunder` submit the instruction, claiming the refund tips of the swaps.
+    /// The refunder must then sign the transaction.
+    pub fn refunder(mut self, refunder: Pubkey) -> Self {
+        self.refunder = Some(refunder);
+        self
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let mut accounts = program::accounts::RefundExpiredBatch {
+            refunder: self.refunder,
+            event_authority: pda::event_authority().0,
+            program: ID,
+        }
+        .to_account_metas(None);
+        for (swap_account, initiator, destination) in &self.swaps {
+            accounts.push(AccountMeta::new(*swap_account, false));
+            accounts.push(AccountMeta::new(*initiator, false));
+            accounts.push(AccountMeta::new(*destination, false));
+        }
+        Instruction::new_with_bytes(
+            ID,
+            &program::instruction::RefundExpiredBatch {}.data(),
+            accounts,
+        )
+    }
+}
+
+/// Builds a `get_version` instruction, whose return data is the program's `ProgramVersion`
+pub fn get_version_instruction() -> Instruction {
+    Instruction::new_with_bytes(
+        ID,
+        &program::instruction::GetVersion {}.data(),
+        program::accounts::GetVersion { program: ID }.to_account_metas(None),
+    )
+}
+
+/// Builds a `get_swap_state` instruction for the swap account at `address`,
+/// whose return data is the swap's `SwapInfo`
+pub fn get_swap_state_instruction(address: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        ID,
+        &program::instruction::GetSwapState {}.data(),
+        program::accounts::GetSwapState {
+            swap_account: *address,
+        }
+        .to_account_metas(None),
+    )
+}
+
+/// Decodes the data of a swap account, ensuring it is of the current layout
+pub fn decode_swap_account(data: &[u8]) -> Result<SwapAccount, ClientError> {
+    let (discriminator, swap) = data
+        .split_at_checked(ANCHOR_DISCRIMINATOR)
+        .ok_or(ClientError::NotSwapAccount)?;
+    if discriminator != SwapAccount::DISCRIMINATOR {
+        return Err(ClientError::NotSwapAccount);
+    }
+    // The version is stored first so as to be readable regardless of the layout
+    match swap.first() {
+        Some(&SWAP_ACCOUNT_VERSION) => {}
+        Some(&version) => return Err(ClientError::UnsupportedSwapVersion(version)),
+        None => return Err(ClientError::NotSwapAccount),
+    }
+    let swap = swap
+        .get(..std::mem::size_of::<SwapAccount>())
+        .ok_or(ClientError::NotSwapAccount)?;
+    Ok(bytemuck::pod_read_unaligned(swap))
+}
+
+/// Fetches the swap account at `address`, if it exists, i.e. the swap has not yet completed
+pub async fn fetch_swap_account(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<Option<SwapAccount>, ClientError> {
+    match rpc.get_account(address).await? {
+        Some(account) if account.owner == ID => decode_swap_account(&account.data).map(Some),
+        Some(_) => Err(ClientError::NotSwapAccount),
+        None => Ok(None),
+    }
+}
+
+/// Fetches the cluster's clock, against which swap expiries are evaluated on-chain
+pub async fn fetch_clock(rpc: &RpcClient) -> Result<Clock, ClientError> {
+    let account = rpc
+        .get_account(&sysvar::clock::ID)
+        .await?
+        .ok_or_else(|| ClientError::InvalidResponse("missing clock sysvar".to_string()))?;
+    bincode::deserialize(&account.data)
+        .map_err(|e| ClientError::InvalidResponse(format!("malformed clock sysvar: {e}")))
+}
+
+/// Fetches the version of the deployed program, by simulating `get_version` with `payer`,
+/// which must be an existing account, as the fee payer
+pub async fn fetch_program_version(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+) -> Result<ProgramVersion, ClientError> {
+    simulate_query(rpc, payer, get_version_instruction()).await
+}
+
+/// Fetches the state of the open swap at `address` as decoded by the deployed program,
+/// by simulating `get_swap_state` with `payer`, which must be an existing account, as the
+/// fee payer. Unlike `fetch_swap_account`, this requires no knowledge of the account layout.
+pub async fn fetch_swap_info(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    address: &Pubkey,
+) -> Result<SwapInfo, ClientError> {
+    simulate_query(rpc, payer, get_swap_state_instruction(address)).await
+}
+
+/// Simulates the query `instruction`, decoding the return data the program set
+async fn simulate_query<T: AnchorDeserialize>(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    instruction: Instruction,
+) -> Result<T, ClientError> {
+    let transaction = Transaction::new_with_payer(&[instruction], Some(payer));
+    match rpc.simulate_return_data(&transaction).await? {
+        Some((program_id, data)) if program_id == ID => T::try_from_slice(&data)
+            .map_err(|e| ClientError::InvalidResponse(format!("malformed return data: {e}"))),
+        _ => Err(ClientError::InvalidResponse(
+            "the program set no return data".to_string(),
+        )),
+    }
+}
+
+/// Fetches every open swap account of the program, along with its address
+pub async fn fetch_swap_accounts(
+    rpc: &RpcClient,
+) -> Result<Vec<(Pubkey, SwapAccount)>, ClientError> {
+    fetch_swap_accounts_matching(rpc, vec![]).await
+}
+
+/// Fetches every open swap account initiated by `initiator`, along with its address
+pub async fn fetch_swap_accounts_by_initiator(
+    rpc: &RpcClient,
+    initiator: &Pubkey,
+) -> Result<Vec<(Pubkey, SwapAccount)>, ClientError> {
+    let offset = ANCHOR_DISCRIMINATOR + std::mem::offset_of!(SwapAccount, initiator);
+    fetch_swap_accounts_matching(rpc, vec![Memcmp::new(offset, initiator.as_ref())]).await
+}
+
+/// Fetches every open swap account redeemable by `redeemer`, along with its address
+pub async fn fetch_swap_accounts_by_redeemer(
+    rpc: &RpcClient,
+    redeemer: &Pubkey,
+) -> Result<Vec<(Pubkey, SwapAccount)>, ClientError> {
+    let offset = ANCHOR_DISCRIMINATOR + std::mem::offset_of!(SwapAccount, redeemer);
+    fetch_swap_accounts_matching(rpc, vec![Memcmp::new(offset, redeemer.as_ref())]).await
+}
+
+/// Fetches every swap account of the current layout matching `filters`.
+/// Accounts of other layouts are skipped, to be migrated.
+async fn fetch_swap_accounts_matching(
+    rpc: &RpcClient,
+    mut filters: Vec<Memcmp>,
+) -> Result<Vec<(Pubkey, SwapAccount)>, ClientError> {
+    filters.push(Memcmp::new(
+        0,
+        &[SwapAccount::DISCRIMINATOR, &[SWAP_ACCOUNT_VERSION]].concat(),
+    ));
+    rpc.get_program_accounts(&ID, &filters)
+        .await?
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode_swap_account(&account.data)?)))
+        .collect()
+}
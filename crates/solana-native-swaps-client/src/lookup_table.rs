@@ -0,0 +1,135 @@
+//! Address lookup tables, for batch operations whose accounts would otherwise exceed the
+//! account limit of legacy transactions, e.g. initiating or redeeming many swaps at once.
+//!
+//! A lookup table holding the accounts shared by the swaps, i.e. the program, its PDAs and the
+//! hot counterparties, lets v0 transactions reference each of them by a one byte index.
+//!
+//! ```ignore
+//! let recent_slot = rpc.get_slot().await?;
+//! let (table, instructions) =
+//!     create_lookup_table_instructions(&authority, &payer, recent_slot, &counterparties);
+//! // ... submit each of the instructions in its own transaction, then wait a slot
+//! let table = fetch_lookup_table(&rpc, &table).await?;
+//! let transaction =
+//!     versioned_transaction(&initiates, &payer, &[table], blockhash, &[&payer_keypair])?;
+//! rpc.send_versioned_transaction(&transaction).await?;
+//! ```
+
+use anchor_lang::solana_program::{hash::Hash, system_program};
+use solana_address_lookup_table_interface::{
+    instruction as lookup_table_instruction, program as lookup_table_program,
+    state::AddressLookupTable,
+};
+use solana_message::{v0, AddressLookupTableAccount, VersionedMessage};
+use solana_signer::signers::Signers;
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::{pda, rpc::RpcClient, ClientError, Instruction, Pubkey, ID};
+
+/// The maximum number of addresses an extend instruction can add while fitting in a
+/// transaction
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// The accounts shared by every swap, followed by `counterparties`, deduplicated
+pub fn lookup_table_addresses(counterparties: &[Pubkey]) -> Vec<Pubkey> {
+    let mut addresses = vec![
+        ID,
+        system_program::ID,
+        pda::config().0,
+        pda::treasury().0,
+        pda::event_authority().0,
+    ];
+    for counterparty in counterparties {
+        if !addresses.contains(counterparty) {
+            addresses.push(*counterparty);
+        }
+    }
+    addresses
+}
+
+/// The address of the lookup table created by `authority` at `recent_slot`, and the
+/// instructions creating it and adding the accounts shared by every swap along with
+/// `counterparties`. The instructions must be submitted in order, each in its own transaction
+/// signed by `payer` and `authority`. `recent_slot` must be within the last 150 slots.
+pub fn create_lookup_table_instructions(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+    counterparties: &[Pubkey],
+) -> (Pubkey, Vec<Instruction>) {
+    let (create, table) =
+        lookup_table_instruction::create_lookup_table(*authority, *payer, recent_slot);
+    let mut instructions = vec![create];
+    instructions.extend(extend_lookup_table_instructions(
+        &table,
+        authority,
+        payer,
+        &lookup_table_addresses(counterparties),
+    ));
+    (table, instructions)
+}
+
+/// The instructions adding `addresses` to the lookup table at `table`, paid for by `payer`.
+/// The instructions must be submitted each in its own transaction signed by `payer` and
+/// `authority`. Added addresses become usable in the slot after their addition.
+pub fn extend_lookup_table_instructions(
+    table: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    addresses: &[Pubkey],
+) -> Vec<Instruction> {
+    addresses
+        .chunks(MAX_ADDRESSES_PER_EXTEND)
+        .map(|chunk| {
+            lookup_table_instruction::extend_lookup_table(
+                *table,
+                *authority,
+                Some(*payer),
+                chunk.to_vec(),
+            )
+        })
+        .collect()
+}
+
+/// Decodes the addresses of the lookup table at `address` from its data
+pub fn decode_lookup_table(
+    address: &Pubkey,
+    data: &[u8],
+) -> Result<AddressLookupTableAccount, ClientError> {
+    let table = AddressLookupTable::deserialize(data)
+        .map_err(|_| ClientError::InvalidLookupTable(format!("{address} is not a lookup table")))?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: table.addresses.to_vec(),
+    })
+}
+
+/// Fetches the addresses of the lookup table at `address`
+pub async fn fetch_lookup_table(
+    rpc: &RpcClient,
+    address: &Pubkey,
+) -> Result<AddressLookupTableAccount, ClientError> {
+    match rpc.get_account(address).await? {
+        Some(table) if table.owner == lookup_table_program::ID => {
+            decode_lookup_table(address, &table.data)
+        }
+        _ => Err(ClientError::InvalidLookupTable(format!(
+            "{address} is not a lookup table"
+        ))),
+    }
+}
+
+/// A v0 transaction of `instructions` paid for by `payer` and signed by `signers`,
+/// referencing the accounts found in `lookup_tables` through them
+pub fn versioned_transaction<T: Signers + ?Sized>(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+    signers: &T,
+) -> Result<VersionedTransaction, ClientError> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+        .map_err(|e| ClientError::InvalidTransaction(e.to_string()))?;
+    VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+        .map_err(|e| ClientError::InvalidTransaction(e.to_string()))
+}
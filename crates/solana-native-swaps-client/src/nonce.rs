@@ -0,0 +1,128 @@
+//! Durable nonce support, for transactions prepared long before being submitted, e.g.
+//! instant refunds pre-signed by the redeemer, which would otherwise expire along with their
+//! recent blockhash within about a minute.
+//!
+//! A durable transaction uses the value of a nonce account in place of a recent blockhash, and
+//! advances it as its first instruction, so that it can only be executed once.
+//!
+//! ```ignore
+//! let nonce = fetch_durable_nonce(&rpc, &nonce_account).await?;
+//! let transaction = nonce.transaction(&[InstantRefundBuilder::new(&swap).instruction()], &payer);
+//! // ... have the redeemer sign the transaction, to be submitted whenever
+//! ```
+
+use anchor_lang::solana_program::hash::Hash;
+use solana_nonce::{state::State, versions::Versions};
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+use solana_transaction::Transaction;
+
+use crate::{rpc::RpcClient, ClientError, Instruction, Pubkey};
+
+/// The durable nonce stored in a nonce account, along with its authority
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DurableNonce {
+    /// The nonce account
+    pub account: Pubkey,
+    /// The account authorized to advance the nonce, which must sign durable transactions
+    pub authority: Pubkey,
+    /// The current nonce, used in place of a recent blockhash
+    pub blockhash: Hash,
+}
+
+impl DurableNonce {
+    /// The instruction advancing the nonce, which must come first in durable transactions
+    pub fn advance_instruction(&self) -> Instruction {
+        system_instruction::advance_nonce_account(&self.account, &self.authority)
+    }
+
+    /// An unsigned durable transaction of `instructions` paid for by `payer`,
+    /// with the nonce advance instruction inserted first
+    pub fn transaction(&self, instructions: &[Instruction], payer: &Pubkey) -> Transaction {
+        let instructions: Vec<Instruction> = std::iter::once(self.advance_instruction())
+            .chain(instructions.iter().cloned())
+            .collect();
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(payer));
+        transaction.message.recent_blockhash = self.blockhash;
+        transaction
+    }
+}
+
+/// Decodes the durable nonce of the nonce account at `account` from its data
+pub fn decode_durable_nonce(account: &Pubkey, data: &[u8]) -> Result<DurableNonce, ClientError> {
+    let versions: Versions = bincode::deserialize(data).map_err(|_| {
+        ClientError::InvalidNonceAccount(format!("{account} is not a nonce account"))
+    })?;
+    let state = match versions {
+        Versions::Current(state) => state,
+        // Legacy nonces share their domain with blockhashes, and are rejected by the runtime
+        Versions::Legacy(_) => {
+            return Err(ClientError::InvalidNonceAccount(format!(
+                "{account} must be upgraded first"
+            )))
+        }
+    };
+    match *state {
+        State::Initialized(data) => Ok(DurableNonce {
+            account: *account,
+            authority: data.authority,
+            blockhash: data.blockhash(),
+        }),
+        State::Uninitialized => Err(ClientError::InvalidNonceAccount(format!(
+            "{account} is not initialized"
+        ))),
+    }
+}
+
+/// Fetches the durable nonce of the nonce account at `account`
+pub async fn fetch_durable_nonce(
+    rpc: &RpcClient,
+    account: &Pubkey,
+) -> Result<DurableNonce, ClientError> {
+    match rpc.get_account(account).await? {
+        Some(nonce) if nonce.owner == system_program::ID => {
+            decode_durable_nonce(account, &nonce.data)
+        }
+        _ => Err(ClientError::InvalidNonceAccount(format!(
+            "{account} is not a nonce account"
+        ))),
+    }
+}
+
+/// The instructions creating a nonce account at `account` funded by `payer`, with its nonce
+/// advanceable by `authority`. Both `payer` and `account` must sign.
+pub async fn create_nonce_account_instructions(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    account: &Pubkey,
+    authority: &Pubkey,
+) -> Result<Vec<Instruction>, ClientError> {
+    let lamports = rpc
+        .get_minimum_balance_for_rent_exemption(State::size())
+        .await?;
+    Ok(system_instruction::create_nonce_account(
+        payer, account, authority, lamports,
+    ))
+}
+
+/// The instruction transferring the authority over the nonce of `nonce` to `new_authority`
+pub fn authorize_nonce_account_instruction(
+    nonce: &DurableNonce,
+    new_authority: &Pubkey,
+) -> Instruction {
+    system_instruction::authorize_nonce_account(&nonce.account, &nonce.authority, new_authority)
+}
+
+/// The instruction withdrawing `lamports` from the nonce account of `nonce` to `destination`.
+/// Withdrawing its entire balance closes the nonce account.
+pub fn withdraw_nonce_account_instruction(
+    nonce: &DurableNonce,
+    destination: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    system_instruction::withdraw_nonce_account(
+        &nonce.account,
+        &nonce.authority,
+        destination,
+        lamports,
+    )
+}
@@ -0,0 +1,427 @@
+//! Quoting of cross-chain swaps from oracle prices, producing the amounts and expiries of both
+//! legs of a swap between native SOL and a counterparty asset.
+//!
+//! Prices are read from Pyth `PriceUpdateV2` accounts or Switchboard On-Demand pull feeds, and
+//! validated for staleness and confidence as the program validates those of oracle-priced
+//! swaps. The taker is quoted at the oracle rate less the spread and fees, and its leg, being
+//! funded first, expires after the maker's by a safety margin, leaving the maker time to redeem
+//! it once the secret is revealed.
+//!
+//! ```ignore
+//! let sol_usd = fetch_price(&rpc, &sol_usd_price_update, PriceSource::Pyth(PYTH_SOL_USD_FEED_ID)).await?;
+//! let btc_usd = fetch_price(&rpc, &btc_usd_feed, PriceSource::Switchboard).await?;
+//! // The taker sends 0.01 BTC, in sats, in exchange for native SOL
+//! let quote = Quoter::default().quote_lamports_out(1_000_000, 8, &sol_usd, &btc_usd, now)?;
+//! let initiate = InitiateBuilder::new(maker, taker, secret_hash, quote.amount_lamports, quote.expires_in_slots);
+//! ```
+
+use anchor_lang::prelude::pubkey;
+pub use solana_native_swaps::{PYTH_RECEIVER_PROGRAM_ID, PYTH_SOL_USD_FEED_ID};
+
+use crate::{rpc::RpcClient, ClientError, Pubkey, ANCHOR_DISCRIMINATOR};
+
+/// The Switchboard On-Demand program, owning pull feed accounts
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey =
+    pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+/// The discriminator of the Pyth receiver's `PriceUpdateV2` account
+const PYTH_PRICE_UPDATE_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// The exponent of the fixed-point values of Switchboard feeds
+const SWITCHBOARD_EXPONENT: i32 = -18;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// The decimals of native SOL, in lamports
+const SOL_DECIMALS: i32 = 9;
+
+/// An oracle account to read a price from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceSource {
+    /// A Pyth `PriceUpdateV2` account, which must be of the feed with this ID
+    Pyth([u8; 32]),
+    /// A Switchboard On-Demand pull feed
+    Switchboard,
+}
+
+/// A USD price, being `price` × 10^`exponent` USD per whole unit of an asset, give or take
+/// `conf` × 10^`exponent`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i128,
+    pub conf: u128,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Reads the fully verified price of the Pyth `PriceUpdateV2` account data `data`, of the
+    /// feed with `feed_id`
+    pub fn from_pyth(data: &[u8], feed_id: &[u8; 32]) -> Result<Self, ClientError> {
+        // Laid out as the program reads it, only fully verified updates being accepted
+        const VERIFICATION_LEVEL: usize = ANCHOR_DISCRIMINATOR + 32;
+        const VERIFICATION_LEVEL_FULL: u8 = 1;
+        const FEED_ID: usize = VERIFICATION_LEVEL + 1;
+        const PRICE: usize = FEED_ID + 32;
+
+        if data.get(..ANCHOR_DISCRIMINATOR) != Some(&PYTH_PRICE_UPDATE_DISCRIMINATOR[..]) {
+            return Err(invalid_price("not a Pyth price update"));
+        }
+        if data.get(VERIFICATION_LEVEL) != Some(&VERIFICATION_LEVEL_FULL) {
+            return Err(invalid_price("Pyth price update is not fully verified"));
+        }
+        if data.get(FEED_ID..PRICE) != Some(&feed_id[..]) {
+            return Err(invalid_price("Pyth price update is of another feed"));
+        }
+        Ok(OraclePrice {
+            price: read_i64(data, PRICE)?.into(),
+            conf: u64::from_le_bytes(read(data, PRICE + 8)?).into(),
+            exponent: i32::from_le_bytes(read(data, PRICE + 16)?),
+            publish_time: read_i64(data, PRICE + 20)?,
+        })
+    }
+
+    /// Reads the current result of the Switchboard On-Demand pull feed account data `data`,
+    /// its standard deviation standing for the confidence interval
+    pub fn from_switchboard(data: &[u8]) -> Result<Self, ClientError> {
+        // Refer: https://github.com/switchboard-xyz/solana-sdk/blob/main/rust/switchboard-on-demand/src/on_demand/accounts/pull_feed.rs
+        // The 32 oracle submissions of 64 bytes each are followed by the authority, queue, feed
+        // hash, initialization time, permissions, maximum variance, minimum responses, name,
+        // padding, historical result index and minimum sample size, then the fields below
+        const LAST_UPDATE_TIMESTAMP: usize = ANCHOR_DISCRIMINATOR + 2208;
+        // Past the LUT slot and reserved bytes, the result starts with its value and deviation
+        const RESULT: usize = LAST_UPDATE_TIMESTAMP + 48;
+
+        let price = i128::from_le_bytes(read(data, RESULT)?);
+        let std_dev = i128::from_le_bytes(read(data, RESULT + 16)?);
+        Ok(OraclePrice {
+            price,
+            conf: std_dev.unsigned_abs(),
+            exponent: SWITCHBOARD_EXPONENT,
+            publish_time: read_i64(data, LAST_UPDATE_TIMESTAMP)?,
+        })
+    }
+
+    /// Ensures the price was published within `max_staleness_secs` of `now`, with its confidence
+    /// interval within `max_confidence_bps` basis points of it, as the program requires of the
+    /// prices oracle-priced swaps are redeemed at
+    pub fn validate(
+        &self,
+        now: i64,
+        max_staleness_secs: u64,
+        max_confidence_bps: u16,
+    ) -> Result<(), ClientError> {
+        if i128::from(now) - i128::from(self.publish_time) > i128::from(max_staleness_secs) {
+            return Err(invalid_price(format!(
+                "price published at {} is stale",
+                self.publish_time
+            )));
+        }
+        let confident = u128::try_from(self.price).is_ok_and(|price| {
+            let conf = self.conf.checked_mul(BPS_DENOMINATOR);
+            let bound = price.checked_mul(u128::from(max_confidence_bps));
+            price > 0 && conf.zip(bound).is_some_and(|(conf, bound)| conf <= bound)
+        });
+        if !confident {
+            return Err(invalid_price(format!(
+                "price {} ± {} is too uncertain",
+                self.price, self.conf
+            )));
+        }
+        Ok(())
+    }
+
+    /// The lamports worth `notional_usd_micros` at this SOL/USD price, rounded down, as the
+    /// program computes the amount oracle-priced swaps release
+    pub fn lamports_for(&self, notional_usd_micros: u64) -> Option<u64> {
+        // Lamports are 10^-9 SOL, whereas micros are 10^-6 USD
+        let notional = u128::from(notional_usd_micros) * 1_000;
+        let price = u128::try_from(self.price).ok().filter(|&price| price > 0)?;
+        let scale = 10u128.checked_pow(self.exponent.unsigned_abs())?;
+        let lamports = match self.exponent {
+            ..0 => notional.checked_mul(scale)? / price,
+            _ => notional / price.checked_mul(scale)?,
+        };
+        u64::try_from(lamports).ok()
+    }
+}
+
+/// Fetches the price held by the oracle account at `address`
+pub async fn fetch_price(
+    rpc: &RpcClient,
+    address: &Pubkey,
+    source: PriceSource,
+) -> Result<OraclePrice, ClientError> {
+    let account = rpc
+        .get_account(address)
+        .await?
+        .ok_or_else(|| invalid_price(format!("oracle account {address} does not exist")))?;
+    match source {
+        PriceSource::Pyth(feed_id) if account.owner == PYTH_RECEIVER_PROGRAM_ID => {
+            OraclePrice::from_pyth(&account.data, &feed_id)
+        }
+        PriceSource::Switchboard if account.owner == SWITCHBOARD_ON_DEMAND_PROGRAM_ID => {
+            OraclePrice::from_switchboard(&account.data)
+        }
+        _ => Err(invalid_price(format!(
+            "oracle account {address} is owned by {}",
+            account.owner
+        ))),
+    }
+}
+
+/// The fees charged by the maker, deducted from the taker's proceeds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeModel {
+    /// A fixed fee per swap, covering e.g. the transaction fees of both legs, in lamports
+    pub fixed_lamports: u64,
+    /// A fee proportional to the swap's value, in basis points of its lamports
+    pub bps: u16,
+}
+
+impl FeeModel {
+    /// The fees charged on a swap worth `lamports`, if representable
+    pub fn fee(&self, lamports: u64) -> Option<u64> {
+        let proportional = u128::from(lamports) * u128::from(self.bps) / BPS_DENOMINATOR;
+        self.fixed_lamports
+            .checked_add(u64::try_from(proportional).ok()?)
+    }
+}
+
+/// The amounts and expiries of both legs of a quoted swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quote {
+    /// The native SOL swapped on Solana, in lamports
+    pub amount_lamports: u64,
+    /// The number of slots after which the Solana leg may be refunded, as passed to `initiate`
+    pub expires_in_slots: u64,
+    /// The counterparty asset swapped on the counterparty chain, in its base units
+    pub counterparty_amount: u64,
+    /// The number of seconds after which the counterparty leg may be refunded
+    pub counterparty_expires_in_secs: u64,
+    /// The fees deducted from the taker's proceeds, in lamports
+    pub fee_lamports: u64,
+}
+
+/// The parameters of an oracle-priced swap of a USD notional, as passed to
+/// `initiate_oracle_priced`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePricedQuote {
+    /// The escrow covering the notional at any price within the bounds, in lamports
+    pub amount_lamports: u64,
+    pub max_staleness_secs: u64,
+    pub max_confidence_bps: u16,
+    pub min_lamports_out: u64,
+    pub max_lamports_out: u64,
+}
+
+/// Quotes swaps between native SOL and a counterparty asset from their USD prices
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quoter {
+    /// The margin taken by the maker off the oracle rate, in basis points of the taker's proceeds
+    pub spread_bps: u16,
+    pub fees: FeeModel,
+    /// The maximum age of the prices quoted at, in seconds
+    pub max_staleness_secs: u64,
+    /// The maximum confidence interval of the prices quoted at, in basis points of the price
+    pub max_confidence_bps: u16,
+    /// The time the maker's leg, funded second, may be redeemed within, in seconds
+    pub maker_expiry_secs: u64,
+    /// The time the taker's leg outlives the maker's by, for the maker to redeem it once the
+    /// taker reveals the secret redeeming the maker's leg, in seconds
+    pub safety_margin_secs: u64,
+    /// The expected duration of a Solana slot, converting expiries to slots, in milliseconds
+    pub slot_duration_ms: u64,
+    /// The tolerance of oracle-priced swaps to price moves between quote and redeem, in basis
+    /// points of the quoted lamports
+    pub slippage_bps: u16,
+}
+
+impl Default for Quoter {
+    fn default() -> Self {
+        Quoter {
+            spread_bps: 30,
+            fees: FeeModel::default(),
+            max_staleness_secs: 60,
+            max_confidence_bps: 100,
+            maker_expiry_secs: 60 * 60,
+            safety_margin_secs: 60 * 60,
+            slot_duration_ms: 400,
+            slippage_bps: 100,
+        }
+    }
+}
+
+impl Quoter {
+    /// Quotes the native SOL a taker receives on Solana for `counterparty_amount` base units of
+    /// a counterparty asset of `counterparty_decimals` sent on the counterparty chain, whose
+    /// leg, funded first by the taker, expires after the Solana leg
+    pub fn quote_lamports_out(
+        &self,
+        counterparty_amount: u64,
+        counterparty_decimals: u8,
+        sol_usd: &OraclePrice,
+        counterparty_usd: &OraclePrice,
+        now: i64,
+    ) -> Result<Quote, ClientError> {
+        self.validate(sol_usd, counterparty_usd, now)?;
+        let value = convert(
+            counterparty_amount,
+            counterparty_usd,
+            counterparty_decimals.into(),
+            sol_usd,
+            SOL_DECIMALS,
+        )
+        .ok_or_else(|| invalid_quote("value overflows"))?;
+        let fee_lamports = self
+            .fees
+            .fee(value)
+            .ok_or_else(|| invalid_quote("fees overflow"))?;
+        let amount_lamports = less_bps(value, self.spread_bps)
+            .checked_sub(fee_lamports)
+            .filter(|&lamports| lamports > 0)
+            .ok_or_else(|| invalid_quote("amount does not cover the fees"))?;
+        Ok(Quote {
+            amount_lamports,
+            expires_in_slots: self.slots(self.maker_expiry_secs)?,
+            counterparty_amount,
+            counterparty_expires_in_secs: self.taker_expiry_secs()?,
+            fee_lamports,
+        })
+    }
+
+    /// Quotes the base units of a counterparty asset of `counterparty_decimals` a taker receives
+    /// on the counterparty chain for `amount_lamports` sent on Solana, whose leg, funded first
+    /// by the taker, expires after the counterparty leg
+    pub fn quote_counterparty_out(
+        &self,
+        amount_lamports: u64,
+        counterparty_decimals: u8,
+        sol_usd: &OraclePrice,
+        counterparty_usd: &OraclePrice,
+        now: i64,
+    ) -> Result<Quote, ClientError> {
+        self.validate(sol_usd, counterparty_usd, now)?;
+        let fee_lamports = self
+            .fees
+            .fee(amount_lamports)
+            .ok_or_else(|| invalid_quote("fees overflow"))?;
+        let proceeds = amount_lamports
+            .checked_sub(fee_lamports)
+            .ok_or_else(|| invalid_quote("amount does not cover the fees"))?;
+        let counterparty_amount = convert(
+            less_bps(proceeds, self.spread_bps),
+            sol_usd,
+            SOL_DECIMALS,
+            counterparty_usd,
+            counterparty_decimals.into(),
+        )
+        .filter(|&amount| amount > 0)
+        .ok_or_else(|| invalid_quote("amount is not representable"))?;
+        Ok(Quote {
+            amount_lamports,
+            expires_in_slots: self.slots(self.taker_expiry_secs()?)?,
+            counterparty_amount,
+            counterparty_expires_in_secs: self.maker_expiry_secs,
+            fee_lamports,
+        })
+    }
+
+    /// Quotes an oracle-priced swap of `notional_usd_micros`, bounding the lamports released
+    /// upon redeem to within the slippage of those the notional is worth at `sol_usd`, and
+    /// escrowing enough to cover the upper bound
+    pub fn quote_oracle_priced(
+        &self,
+        notional_usd_micros: u64,
+        sol_usd: &OraclePrice,
+        now: i64,
+    ) -> Result<OraclePricedQuote, ClientError> {
+        sol_usd.validate(now, self.max_staleness_secs, self.max_confidence_bps)?;
+        let lamports = sol_usd
+            .lamports_for(notional_usd_micros)
+            .ok_or_else(|| invalid_quote("notional is not representable"))?;
+        let slippage = u128::from(lamports) * u128::from(self.slippage_bps) / BPS_DENOMINATOR;
+        let slippage = u64::try_from(slippage).map_err(|_| invalid_quote("slippage overflows"))?;
+        let max_lamports_out = lamports
+            .checked_add(slippage)
+            .ok_or_else(|| invalid_quote("slippage overflows"))?;
+        Ok(OraclePricedQuote {
+            amount_lamports: max_lamports_out,
+            max_staleness_secs: self.max_staleness_secs,
+            max_confidence_bps: self.max_confidence_bps,
+            min_lamports_out: lamports.saturating_sub(slippage),
+            max_lamports_out,
+        })
+    }
+
+    fn validate(
+        &self,
+        sol_usd: &OraclePrice,
+        counterparty_usd: &OraclePrice,
+        now: i64,
+    ) -> Result<(), ClientError> {
+        sol_usd.validate(now, self.max_staleness_secs, self.max_confidence_bps)?;
+        counterparty_usd.validate(now, self.max_staleness_secs, self.max_confidence_bps)
+    }
+
+    fn taker_expiry_secs(&self) -> Result<u64, ClientError> {
+        self.maker_expiry_secs
+            .checked_add(self.safety_margin_secs)
+            .ok_or_else(|| invalid_quote("expiry overflows"))
+    }
+
+    /// The slots spanning at least `secs`
+    fn slots(&self, secs: u64) -> Result<u64, ClientError> {
+        secs.checked_mul(1_000)
+            .and_then(|ms| ms.checked_div(self.slot_duration_ms))
+            .ok_or_else(|| invalid_quote("invalid slot duration"))
+    }
+}
+
+/// The base units of an asset of `to_decimals` priced at `to_price` worth `amount` base units of
+/// an asset of `from_decimals` priced at `from_price`, rounded down
+fn convert(
+    amount: u64,
+    from_price: &OraclePrice,
+    from_decimals: i32,
+    to_price: &OraclePrice,
+    to_decimals: i32,
+) -> Option<u64> {
+    // `amount` is worth amount × from price × 10^(from exponent - from decimals) USD, and a
+    // base unit of the other asset to price × 10^(to exponent - to decimals) USD
+    let exponent = (from_price.exponent - from_decimals) - (to_price.exponent - to_decimals);
+    let value = u128::from(amount).checked_mul(u128::try_from(from_price.price).ok()?)?;
+    let to_price = u128::try_from(to_price.price)
+        .ok()
+        .filter(|&price| price > 0)?;
+    let scale = 10u128.checked_pow(exponent.unsigned_abs())?;
+    let amount = match exponent {
+        ..0 => value / to_price.checked_mul(scale)?,
+        _ => value.checked_mul(scale)? / to_price,
+    };
+    u64::try_from(amount).ok()
+}
+
+/// `amount` less `bps` basis points of it
+fn less_bps(amount: u64, bps: u16) -> u64 {
+    let bps = u128::from(bps.min(BPS_DENOMINATOR as u16));
+    (u128::from(amount) * (BPS_DENOMINATOR - bps) / BPS_DENOMINATOR) as u64
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], ClientError> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid_price("oracle account is too short"))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64, ClientError> {
+    Ok(i64::from_le_bytes(read(data, offset)?))
+}
+
+fn invalid_price(reason: impl Into<String>) -> ClientError {
+    ClientError::InvalidPrice(reason.into())
+}
+
+fn invalid_quote(reason: &str) -> ClientError {
+    ClientError::InvalidQuote(reason.to_string())
+}
@@ -0,0 +1,726 @@
+//! A minimal JSON-RPC client, covering only the methods needed to interact with the swap
+//! program, so as to spare integrators the full Solana RPC client stack
+
+use std::{str::FromStr, time::Duration};
+
+use anchor_lang::{
+    prelude::{EpochSchedule, Pubkey},
+    solana_program::hash::Hash,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_signature::Signature;
+use solana_transaction::{versioned::VersionedTransaction, Transaction};
+
+use crate::ClientError;
+
+/// The commitment level RPC requests are made at
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+/// The maximum number of signatures `getSignaturesForAddress` returns per request
+pub const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+/// The interval at which transaction confirmations are polled for
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// The number of times a transaction's confirmation is polled for before giving up,
+/// spanning roughly the lifetime of a blockhash
+const CONFIRMATION_POLL_ATTEMPTS: usize = 150;
+
+/// An account as returned by the RPC
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcAccount {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// A `getProgramAccounts` filter, matching accounts whose data contains `bytes` at `offset`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memcmp {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl Memcmp {
+    pub fn new(offset: usize, bytes: &[u8]) -> Self {
+        Memcmp {
+            offset,
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "memcmp": {
+                "offset": self.offset,
+                "bytes": BASE64_STANDARD.encode(&self.bytes),
+                "encoding": "base64",
+            }
+        })
+    }
+}
+
+/// A transaction involving an address, as returned by `getSignaturesForAddress`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureInfo {
+    pub signature: Signature,
+    pub slot: u64,
+    /// Whether the transaction failed
+    pub failed: bool,
+    pub block_time: Option<i64>,
+    /// The memos of the transaction, as recorded by the memo program, if any
+    pub memo: Option<String>,
+}
+
+/// A confirmed transaction, reduced to what decoding swap events requires
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// Whether the transaction failed
+    pub failed: bool,
+    /// The program ID and data of every inner instruction, in execution order
+    pub inner_instructions: Vec<(Pubkey, Vec<u8>)>,
+    /// Every instruction, the transaction's own followed by those each invoked, in execution
+    /// order
+    pub instructions: Vec<RpcInstruction>,
+    pub log_messages: Vec<String>,
+}
+
+/// An instruction of a confirmed transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+    /// The depth of the instruction's invocation, 1 for the transaction's own instructions
+    pub stack_height: u32,
+}
+
+/// The status of a submitted transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Not yet confirmed at the client's commitment, or unknown to the RPC
+    Pending,
+    Confirmed,
+    Failed(String),
+}
+
+pub struct RpcClient {
+    http: reqwest::Client,
+    url: String,
+    commitment: Commitment,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::new_with_commitment(url, Commitment::default())
+    }
+
+    pub fn new_with_commitment(url: impl Into<String>, commitment: Commitment) -> Self {
+        RpcClient {
+            http: reqwest::Client::new(),
+            url: url.into(),
+            commitment,
+        }
+    }
+
+    pub fn commitment(&self) -> Commitment {
+        self.commitment
+    }
+
+    /// Fetches the account at `address`, if it exists
+    pub async fn get_account(&self, address: &Pubkey) -> Result<Option<RpcAccount>, ClientError> {
+        let response: WithContext<Option<UiAccount>> = self
+            .call(
+                "getAccountInfo",
+                json!([address.to_string(), self.account_config()]),
+            )
+            .await?;
+        response.value.map(UiAccount::decode).transpose()
+    }
+
+    /// Fetches every account owned by `program_id` matching all of `filters`
+    pub async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[Memcmp],
+    ) -> Result<Vec<(Pubkey, RpcAccount)>, ClientError> {
+        let mut config = self.account_config();
+        config["filters"] = filters.iter().map(Memcmp::to_json).collect();
+        let accounts: Vec<KeyedUiAccount> = self
+            .call(
+                "getProgramAccounts",
+                json!([program_id.to_string(), config]),
+            )
+            .await?;
+
+        accounts
+            .into_iter()
+            .map(|keyed| Ok((parse_pubkey(&keyed.pubkey)?, keyed.account.decode()?)))
+            .collect()
+    }
+
+    /// Fetches the current slot
+    pub async fn get_slot(&self) -> Result<u64, ClientError> {
+        self.call("getSlot", json!([{ "commitment": self.commitment }]))
+            .await
+    }
+
+    /// Fetches the cluster's epoch schedule, for conversions between slots and epochs
+    pub async fn get_epoch_schedule(&self) -> Result<EpochSchedule, ClientError> {
+        let schedule: RpcEpochSchedule = self.call("getEpochSchedule", json!([])).await?;
+        Ok(EpochSchedule {
+            slots_per_epoch: schedule.slots_per_epoch,
+            leader_schedule_slot_offset: schedule.leader_schedule_slot_offset,
+            warmup: schedule.warmup,
+            first_normal_epoch: schedule.first_normal_epoch,
+            first_normal_slot: schedule.first_normal_slot,
+        })
+    }
+
+    /// Fetches the minimum balance of a rent-exempt account holding `data_len` bytes
+    pub async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> Result<u64, ClientError> {
+        self.call(
+            "getMinimumBalanceForRentExemption",
+            json!([data_len, { "commitment": self.commitment }]),
+        )
+        .await
+    }
+
+    /// Fetches the latest blockhash, for transactions to be signed against
+    pub async fn get_latest_blockhash(&self) -> Result<Hash, ClientError> {
+        Ok(self.get_latest_blockhash_with_expiry().await?.0)
+    }
+
+    /// Fetches the latest blockhash along with the last block height at which transactions
+    /// signed against it may still be executed
+    pub async fn get_latest_blockhash_with_expiry(&self) -> Result<(Hash, u64), ClientError> {
+        let response: WithContext<LatestBlockhash> = self
+            .call(
+                "getLatestBlockhash",
+                json!([{ "commitment": self.commitment }]),
+            )
+            .await?;
+        let blockhash = Hash::from_str(&response.value.blockhash).map_err(|_| {
+            ClientError::InvalidResponse(format!("invalid blockhash {}", response.value.blockhash))
+        })?;
+        Ok((blockhash, response.value.last_valid_block_height))
+    }
+
+    /// Fetches the current block height
+    pub async fn get_block_height(&self) -> Result<u64, ClientError> {
+        self.call("getBlockHeight", json!([{ "commitment": self.commitment }]))
+            .await
+    }
+
+    /// Fetches up to [`SIGNATURES_PAGE_LIMIT`] transactions involving `address`, newest first,
+    /// starting before `before` and stopping at `until`, both exclusive
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<&Signature>,
+        until: Option<&Signature>,
+    ) -> Result<Vec<SignatureInfo>, ClientError> {
+        let mut config = json!({ "limit": SIGNATURES_PAGE_LIMIT, "commitment": self.commitment });
+        if let Some(before) = before {
+            config["before"] = json!(before.to_string());
+        }
+        if let Some(until) = until {
+            config["until"] = json!(until.to_string());
+        }
+        let page = self
+            .call(
+                "getSignaturesForAddress",
+                json!([address.to_string(), config]),
+            )
+            .await?;
+        decode_signatures(page)
+    }
+
+    /// Fetches the confirmed transaction with `signature`, if found.
+    /// Processed transactions cannot be fetched, so the commitment is at least confirmed.
+    pub async fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<RpcTransaction>, ClientError> {
+        let transaction: Option<UiTransaction> = self
+            .call(
+                "getTransaction",
+                json!([
+                    signature.to_string(),
+                    {
+                        "encoding": "json",
+                        "commitment": self.commitment.max(Commitment::Confirmed),
+                        "maxSupportedTransactionVersion": 0,
+                    },
+                ]),
+            )
+            .await?;
+        transaction.map(UiTransaction::decode).transpose()
+    }
+
+    /// Submits a signed transaction, returning its signature without awaiting confirmation
+    pub async fn send_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Signature, ClientError> {
+        let serialized = bincode::serialize(transaction)
+            .map_err(|e| ClientError::InvalidTransaction(e.to_string()))?;
+        self.send_serialized_transaction(serialized).await
+    }
+
+    /// Submits a signed versioned transaction, e.g. one referencing address lookup tables,
+    /// returning its signature without awaiting confirmation
+    pub async fn send_versioned_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> Result<Signature, ClientError> {
+        let serialized = bincode::serialize(transaction)
+            .map_err(|e| ClientError::InvalidTransaction(e.to_string()))?;
+        self.send_serialized_transaction(serialized).await
+    }
+
+    async fn send_serialized_transaction(
+        &self,
+        serialized: Vec<u8>,
+    ) -> Result<Signature, ClientError> {
+        let signature: String = self
+            .call(
+                "sendTransaction",
+                json!([
+                    BASE64_STANDARD.encode(serialized),
+                    { "encoding": "base64", "preflightCommitment": self.commitment },
+                ]),
+            )
+            .await?;
+        parse_signature(&signature)
+    }
+
+    /// Simulates `transaction` without verifying its signatures, and with its blockhash replaced
+    /// by the latest one, returning the return data it set along with the program which set
+    /// it, if any
+    pub async fn simulate_return_data(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Option<(Pubkey, Vec<u8>)>, ClientError> {
+        let serialized = bincode::serialize(transaction)
+            .map_err(|e| ClientError::InvalidTransaction(e.to_string()))?;
+        let response: WithContext<SimulationResult> = self
+            .call(
+                "simulateTransaction",
+                json!([
+                    BASE64_STANDARD.encode(serialized),
+                    {
+                        "encoding": "base64",
+                        "commitment": self.commitment,
+                        "sigVerify": false,
+                        "replaceRecentBlockhash": true,
+                    },
+                ]),
+            )
+            .await?;
+        let simulation = response.value;
+        if let Some(error) = simulation.err {
+            let logs = simulation.logs.unwrap_or_default();
+            return Err(ClientError::SimulationFailed(format!(
+                "{error}\n{}",
+                logs.join("\n")
+            )));
+        }
+        simulation.return_data.map(UiReturnData::decode).transpose()
+    }
+
+    /// Awaits the confirmation of the transaction with `signature` at this client's commitment,
+    /// failing if the transaction failed or is not confirmed in time
+    pub async fn confirm_transaction(&self, signature: &Signature) -> Result<(), ClientError> {
+        for _ in 0..CONFIRMATION_POLL_ATTEMPTS {
+            match self.get_transaction_statuses(&[*signature]).await?[0].clone() {
+                TransactionStatus::Confirmed => return Ok(()),
+                TransactionStatus::Failed(error) => {
+                    return Err(ClientError::TransactionFailed {
+                        signature: *signature,
+                        error,
+                    })
+                }
+                TransactionStatus::Pending => {}
+            }
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+        }
+        Err(ClientError::Unconfirmed(*signature))
+    }
+
+    /// Fetches the statuses of the transactions with `signatures`, in order,
+    /// as of this client's commitment
+    pub async fn get_transaction_statuses(
+        &self,
+        signatures: &[Signature],
+    ) -> Result<Vec<TransactionStatus>, ClientError> {
+        let signatures: Vec<String> = signatures.iter().map(ToString::to_string).collect();
+        let response: WithContext<Vec<Option<SignatureStatus>>> = self
+            .call("getSignatureStatuses", json!([signatures]))
+            .await?;
+        Ok(response
+            .value
+            .into_iter()
+            .map(|status| match status {
+                Some(SignatureStatus {
+                    err: Some(error), ..
+                }) => TransactionStatus::Failed(error.to_string()),
+                Some(status) if status.satisfies(self.commitment) => TransactionStatus::Confirmed,
+                _ => TransactionStatus::Pending,
+            })
+            .collect())
+    }
+
+    /// Fetches the prioritization fees paid in recent slots by transactions locking all of
+    /// `writable_accounts` as writable, in micro-lamports per compute unit
+    pub async fn get_recent_prioritization_fees(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> Result<Vec<u64>, ClientError> {
+        let accounts: Vec<String> = writable_accounts.iter().map(ToString::to_string).collect();
+        let fees: Vec<PrioritizationFee> = self
+            .call("getRecentPrioritizationFees", json!([accounts]))
+            .await?;
+        Ok(fees.into_iter().map(|fee| fee.prioritization_fee).collect())
+    }
+
+    /// Submits a signed transaction and awaits its confirmation
+    pub async fn send_and_confirm_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Signature, ClientError> {
+        let signature = self.send_transaction(transaction).await?;
+        self.confirm_transaction(&signature).await?;
+        Ok(signature)
+    }
+
+    fn account_config(&self) -> Value {
+        json!({ "encoding": "base64", "commitment": self.commitment })
+    }
+
+    /// Invokes `method` with `params`, returning its result
+    pub(crate) async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, ClientError> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Response = self
+            .http
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        match response {
+            Response {
+                error: Some(error), ..
+            } => Err(ClientError::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+            // A null result is only valid for methods returning an optional value
+            Response { result, .. } => serde_json::from_value(result).map_err(|e| {
+                ClientError::InvalidResponse(format!("{method} returned an invalid result: {e}"))
+            }),
+        }
+    }
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, ClientError> {
+    Pubkey::from_str(pubkey)
+        .map_err(|_| ClientError::InvalidResponse(format!("invalid address {pubkey}")))
+}
+
+/// Decodes a page of signatures as returned by `getSignaturesForAddress`, e.g. from a snapshot
+/// of an archival node's history
+pub fn decode_signatures(page: Value) -> Result<Vec<SignatureInfo>, ClientError> {
+    let signatures: Vec<UiSignatureInfo> = serde_json::from_value(page)
+        .map_err(|e| ClientError::InvalidResponse(format!("invalid signatures: {e}")))?;
+    signatures
+        .into_iter()
+        .map(|info| {
+            Ok(SignatureInfo {
+                signature: parse_signature(&info.signature)?,
+                slot: info.slot,
+                failed: info.err.is_some(),
+                block_time: info.block_time,
+                memo: info.memo,
+            })
+        })
+        .collect()
+}
+
+fn parse_signature(signature: &str) -> Result<Signature, ClientError> {
+    Signature::from_str(signature)
+        .map_err(|_| ClientError::InvalidResponse(format!("invalid signature {signature}")))
+}
+
+#[derive(Deserialize)]
+struct Response {
+    #[serde(default)]
+    result: Value,
+    error: Option<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LatestBlockhash {
+    blockhash: String,
+    last_valid_block_height: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureStatus {
+    err: Option<Value>,
+    confirmation_status: Option<String>,
+}
+
+impl SignatureStatus {
+    /// Whether the transaction has been confirmed at `commitment` or a stronger level
+    fn satisfies(&self, commitment: Commitment) -> bool {
+        let reached = match self.confirmation_status.as_deref() {
+            Some("finalized") => Commitment::Finalized,
+            Some("confirmed") => Commitment::Confirmed,
+            _ => Commitment::Processed,
+        };
+        reached >= commitment
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcEpochSchedule {
+    slots_per_epoch: u64,
+    leader_schedule_slot_offset: u64,
+    warmup: bool,
+    first_normal_epoch: u64,
+    first_normal_slot: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrioritizationFee {
+    prioritization_fee: u64,
+}
+
+#[derive(Deserialize)]
+struct KeyedUiAccount {
+    pubkey: String,
+    account: UiAccount,
+}
+
+#[derive(Deserialize)]
+struct UiAccount {
+    lamports: u64,
+    owner: String,
+    /// The account data along with its encoding, always base64 as requested
+    data: (String, String),
+}
+
+impl UiAccount {
+    fn decode(self) -> Result<RpcAccount, ClientError> {
+        let data = BASE64_STANDARD
+            .decode(&self.data.0)
+            .map_err(|e| ClientError::InvalidResponse(format!("invalid account data: {e}")))?;
+        Ok(RpcAccount {
+            lamports: self.lamports,
+            owner: parse_pubkey(&self.owner)?,
+            data,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulationResult {
+    err: Option<Value>,
+    logs: Option<Vec<String>>,
+    return_data: Option<UiReturnData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiReturnData {
+    program_id: String,
+    /// The return data along with its encoding, always base64
+    data: (String, String),
+}
+
+impl UiReturnData {
+    fn decode(self) -> Result<(Pubkey, Vec<u8>), ClientError> {
+        let data = BASE64_STANDARD
+            .decode(&self.data.0)
+            .map_err(|e| ClientError::InvalidResponse(format!("invalid return data: {e}")))?;
+        Ok((parse_pubkey(&self.program_id)?, data))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiSignatureInfo {
+    signature: String,
+    slot: u64,
+    err: Option<Value>,
+    block_time: Option<i64>,
+    memo: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiTransaction {
+    slot: u64,
+    block_time: Option<i64>,
+    transaction: UiTransactionBody,
+    meta: Option<UiTransactionMeta>,
+}
+
+#[derive(Deserialize)]
+struct UiTransactionBody {
+    message: UiMessage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiMessage {
+    account_keys: Vec<String>,
+    #[serde(default)]
+    instructions: Vec<UiInstruction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiTransactionMeta {
+    err: Option<Value>,
+    #[serde(default)]
+    inner_instructions: Option<Vec<UiInnerInstructions>>,
+    #[serde(default)]
+    loaded_addresses: Option<UiLoadedAddresses>,
+    #[serde(default)]
+    log_messages: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct UiInnerInstructions {
+    /// The index of the transaction's instruction which invoked these
+    index: usize,
+    instructions: Vec<UiInstruction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiInstruction {
+    program_id_index: usize,
+    #[serde(default)]
+    accounts: Vec<usize>,
+    /// The instruction data, in base58
+    data: String,
+    #[serde(default)]
+    stack_height: Option<u32>,
+}
+
+impl UiInstruction {
+    fn decode(
+        &self,
+        account_keys: &[Pubkey],
+        stack_height: u32,
+    ) -> Result<RpcInstruction, ClientError> {
+        let account = |index: usize| {
+            account_keys.get(index).copied().ok_or_else(|| {
+                ClientError::InvalidResponse(format!("account index {index} out of bounds"))
+            })
+        };
+        let data = bs58::decode(&self.data)
+            .into_vec()
+            .map_err(|e| ClientError::InvalidResponse(format!("invalid instruction data: {e}")))?;
+        Ok(RpcInstruction {
+            program_id: account(self.program_id_index)?,
+            accounts: self
+                .accounts
+                .iter()
+                .map(|&index| account(index))
+                .collect::<Result<_, _>>()?,
+            data,
+            stack_height: self.stack_height.unwrap_or(stack_height),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct UiLoadedAddresses {
+    writable: Vec<String>,
+    readonly: Vec<String>,
+}
+
+impl UiTransaction {
+    fn decode(self) -> Result<RpcTransaction, ClientError> {
+        let meta = self.meta.ok_or_else(|| {
+            ClientError::InvalidResponse("transaction returned without its status".to_string())
+        })?;
+        // The static account keys are followed by those loaded from address lookup tables
+        let mut account_keys = self.transaction.message.account_keys;
+        if let Some(loaded) = meta.loaded_addresses {
+            account_keys.extend(loaded.writable);
+            account_keys.extend(loaded.readonly);
+        }
+        let account_keys = account_keys
+            .iter()
+            .map(|key| parse_pubkey(key))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let inner = meta.inner_instructions.unwrap_or_default();
+        let mut instructions = vec![];
+        for (index, instruction) in self.transaction.message.instructions.iter().enumerate() {
+            instructions.push(instruction.decode(&account_keys, 1)?);
+            for invoked in inner.iter().filter(|inner| inner.index == index) {
+                for instruction in &invoked.instructions {
+                    instructions.push(instruction.decode(&account_keys, 2)?);
+                }
+            }
+        }
+        let inner_instructions = inner
+            .iter()
+            .flat_map(|inner| &inner.instructions)
+            .map(|instruction| {
+                let instruction = instruction.decode(&account_keys, 2)?;
+                Ok((instruction.program_id, instruction.data))
+            })
+            .collect::<Result<_, ClientError>>()?;
+        Ok(RpcTransaction {
+            slot: self.slot,
+            block_time: self.block_time,
+            failed: meta.err.is_some(),
+            inner_instructions,
+            instructions,
+            log_messages: meta.log_messages.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,48 @@
+//! Retrieval of the `security.txt` embedded in the deployed programs, listing the contacts and
+//! disclosure policy for vulnerabilities.
+//!
+//! ```ignore
+//! let security_txt = fetch_security_txt(&rpc, &ID).await?;
+//! println!("Report vulnerabilities to {:?}", security_txt.contacts);
+//! ```
+
+use anchor_lang::solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+
+pub use solana_security_txt::{Contact, SecurityTxt};
+
+use crate::{rpc::RpcClient, ClientError, Pubkey};
+
+/// Decodes the `security.txt` embedded in the executable `data` of a program
+pub fn decode_security_txt(data: &[u8]) -> Result<SecurityTxt, ClientError> {
+    solana_security_txt::find_and_parse(data)
+        .map_err(|e| ClientError::InvalidSecurityTxt(e.to_string()))
+}
+
+/// Fetches the `security.txt` embedded in the program deployed at `program_id`.
+/// The executable of upgradeable programs is read from their program data account.
+pub async fn fetch_security_txt(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<SecurityTxt, ClientError> {
+    let not_found = || ClientError::InvalidSecurityTxt(format!("{program_id} is not a program"));
+    let program = rpc.get_account(program_id).await?.ok_or_else(not_found)?;
+    if program.owner != bpf_loader_upgradeable::ID {
+        return decode_security_txt(&program.data);
+    }
+
+    let Ok(UpgradeableLoaderState::Program {
+        programdata_address,
+    }) = bincode::deserialize(&program.data)
+    else {
+        return Err(not_found());
+    };
+    let program_data = rpc
+        .get_account(&programdata_address)
+        .await?
+        .ok_or_else(not_found)?;
+    let executable = program_data
+        .data
+        .get(UpgradeableLoaderState::size_of_programdata_metadata()..)
+        .ok_or_else(not_found)?;
+    decode_security_txt(executable)
+}
@@ -0,0 +1,302 @@
+//! [Solana Pay transaction requests](https://docs.solanapay.com/spec#specification-transaction-request)
+//! for `initiate` and `redeem`, letting mobile wallets participate in a swap by scanning a QR
+//! code generated by the counterparty.
+//!
+//! The counterparty encodes the parameters of the swap into a link to their transaction request
+//! endpoint, and shows the `solana:` URL of the link as a QR code. The scanning wallet then POSTs
+//! its account to the link, to which the endpoint responds with the transaction built for it.
+//!
+//! ```ignore
+//! let link = InitiateRequest::new(redeemer, secret_hash, 1_000_000_000, 300).link(&endpoint);
+//! show_qr_code(&transaction_request_url(&link));
+//! // ... upon the wallet's POST to the link
+//! let request = InitiateRequest::from_link(&link)?;
+//! let blockhash = rpc.get_latest_blockhash().await?;
+//! respond(request.transaction(&body.account.parse()?, blockhash)?);
+//! ```
+
+use std::str::FromStr;
+
+use anchor_lang::solana_program::hash::Hash;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_transaction::Transaction;
+use url::{form_urlencoded, Url};
+
+use crate::{
+    fetch_swap_account, pda, rpc::RpcClient, ClientError, HashAlgorithm, InitiateBuilder,
+    Instruction, Pubkey, RedeemBuilder,
+};
+
+/// The response to the GET request of a wallet, describing the requester
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionRequestMetadata {
+    pub label: String,
+    /// The URL of an SVG, PNG or WebP icon
+    pub icon: String,
+}
+
+/// The body of the POST request of a wallet
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionRequest {
+    /// The address of the wallet's account, which pays for and signs the transaction
+    pub account: String,
+}
+
+/// The response to the POST request of a wallet
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionResponse {
+    /// The unsigned transaction, serialized and base64-encoded
+    pub transaction: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl TransactionResponse {
+    /// Responds with a transaction of `instructions` paid for by `account`
+    pub fn new(
+        instructions: &[Instruction],
+        account: &Pubkey,
+        recent_blockhash: Hash,
+        message: Option<String>,
+    ) -> Result<Self, ClientError> {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(account));
+        transaction.message.recent_blockhash = recent_blockhash;
+        let serialized = bincode::serialize(&transaction)
+            .map_err(|e| ClientError::InvalidTransaction(e.to_string()))?;
+        Ok(TransactionResponse {
+            transaction: BASE64_STANDARD.encode(serialized),
+            message,
+        })
+    }
+}
+
+/// The `solana:` URL of a transaction request to `link`, to be shown as a QR code
+pub fn transaction_request_url(link: &Url) -> String {
+    // Links with query parameters must be URL-encoded, so as not to be confused with
+    // the parameters of transfer requests
+    match link.query() {
+        Some(_) => format!(
+            "solana:{}",
+            form_urlencoded::byte_serialize(link.as_str().as_bytes()).collect::<String>()
+        ),
+        None => format!("solana:{link}"),
+    }
+}
+
+/// A request for the scanning wallet to initiate a swap to the requester
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitiateRequest {
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    pub amount_lamports: u64,
+    pub expires_in_slots: u64,
+    pub hash_algorithm: HashAlgorithm,
+}
+
+impl InitiateRequest {
+    pub fn new(
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    ) -> Self {
+        InitiateRequest {
+            redeemer,
+            secret_hash,
+            amount_lamports,
+            expires_in_slots,
+            hash_algorithm: HashAlgorithm::default(),
+        }
+    }
+
+    pub fn hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+
+    /// The link to `endpoint` carrying the parameters of this request
+    pub fn link(&self, endpoint: &Url) -> Url {
+        let mut link = endpoint.clone();
+        link.query_pairs_mut()
+            .append_pair("redeemer", &self.redeemer.to_string())
+            .append_pair("secret_hash", &hex(&self.secret_hash))
+            .append_pair("amount_lamports", &self.amount_lamports.to_string())
+            .append_pair("expires_in_slots", &self.expires_in_slots.to_string())
+            .append_pair("hash_algorithm", hash_algorithm_name(self.hash_algorithm));
+        link
+    }
+
+    /// Parses the parameters of a request from its link
+    pub fn from_link(link: &Url) -> Result<Self, ClientError> {
+        let hash_algorithm = match optional_param(link, "hash_algorithm") {
+            Some(name) => parse_hash_algorithm(&name)?,
+            None => HashAlgorithm::default(),
+        };
+        Ok(InitiateRequest {
+            redeemer: parse(link, "redeemer")?,
+            secret_hash: parse_bytes32(link, "secret_hash")?,
+            amount_lamports: parse(link, "amount_lamports")?,
+            expires_in_slots: parse(link, "expires_in_slots")?,
+            hash_algorithm,
+        })
+    }
+
+    /// Responds with the transaction initiating the swap from `account`
+    pub fn transaction(
+        &self,
+        account: &Pubkey,
+        recent_blockhash: Hash,
+    ) -> Result<TransactionResponse, ClientError> {
+        let initiate = InitiateBuilder::new(
+            *account,
+            self.redeemer,
+            self.secret_hash,
+            self.amount_lamports,
+            self.expires_in_slots,
+        )
+        .hash_algorithm(self.hash_algorithm);
+        TransactionResponse::new(
+            &[initiate.instruction()],
+            account,
+            recent_blockhash,
+            Some(format!(
+                "Initiate a swap of {} lamports to {}",
+                self.amount_lamports, self.redeemer
+            )),
+        )
+    }
+}
+
+/// A request for the scanning wallet to redeem a swap with its secret, as revealed by the
+/// requester. As the link carries the secret, it must only be shared once the secret may be
+/// revealed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedeemRequest {
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    pub secret: [u8; 32],
+}
+
+impl RedeemRequest {
+    pub fn new(
+        initiator: Pubkey,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        secret: [u8; 32],
+    ) -> Self {
+        RedeemRequest {
+            initiator,
+            redeemer,
+            secret_hash,
+            secret,
+        }
+    }
+
+    /// The link to `endpoint` carrying the parameters of this request
+    pub fn link(&self, endpoint: &Url) -> Url {
+        let mut link = endpoint.clone();
+        link.query_pairs_mut()
+            .append_pair("initiator", &self.initiator.to_string())
+            .append_pair("redeemer", &self.redeemer.to_string())
+            .append_pair("secret_hash", &hex(&self.secret_hash))
+            .append_pair("secret", &hex(&self.secret));
+        link
+    }
+
+    /// Parses the parameters of a request from its link
+    pub fn from_link(link: &Url) -> Result<Self, ClientError> {
+        Ok(RedeemRequest {
+            initiator: parse(link, "initiator")?,
+            redeemer: parse(link, "redeemer")?,
+            secret_hash: parse_bytes32(link, "secret_hash")?,
+            secret: parse_bytes32(link, "secret")?,
+        })
+    }
+
+    /// Responds with the transaction redeeming the swap's entire remaining funds. Wallets other
+    /// than the redeemer's submit it as the relayer, claiming the swap's relayer fee.
+    pub async fn transaction(
+        &self,
+        rpc: &RpcClient,
+        account: &Pubkey,
+        recent_blockhash: Hash,
+    ) -> Result<TransactionResponse, ClientError> {
+        let swap_account = pda::swap_account(&self.initiator, &self.redeemer, &self.secret_hash).0;
+        let swap = fetch_swap_account(rpc, &swap_account)
+            .await?
+            .ok_or_else(|| {
+                ClientError::InvalidTransactionRequest("swap is not open".to_string())
+            })?;
+        let mut redeem = RedeemBuilder::new(&swap, self.secret);
+        if *account != swap.redeemer {
+            redeem = redeem.relayer(*account);
+        }
+        TransactionResponse::new(
+            &[redeem.instruction()],
+            account,
+            recent_blockhash,
+            Some(format!(
+                "Redeem {} lamports from swap {swap_account}",
+                swap.amount_lamports
+            )),
+        )
+    }
+}
+
+fn optional_param(link: &Url, name: &str) -> Option<String> {
+    link.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn param(link: &Url, name: &str) -> Result<String, ClientError> {
+    optional_param(link, name)
+        .ok_or_else(|| ClientError::InvalidTransactionRequest(format!("missing {name}")))
+}
+
+fn parse<T: FromStr>(link: &Url, name: &str) -> Result<T, ClientError> {
+    param(link, name)?
+        .parse()
+        .map_err(|_| ClientError::InvalidTransactionRequest(format!("invalid {name}")))
+}
+
+fn parse_bytes32(link: &Url, name: &str) -> Result<[u8; 32], ClientError> {
+    let value = param(link, name)?;
+    let invalid = || ClientError::InvalidTransactionRequest(format!("invalid {name}"));
+    if value.len() != 64 {
+        return Err(invalid());
+    }
+    let mut bytes = [0; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(value.get(2 * i..2 * i + 2).ok_or_else(invalid)?, 16)
+            .map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hash_algorithm_name(hash_algorithm: HashAlgorithm) -> &'static str {
+    match hash_algorithm {
+        HashAlgorithm::Sha256 => "sha256",
+        HashAlgorithm::Keccak256 => "keccak256",
+        HashAlgorithm::Hash160 => "hash160",
+        HashAlgorithm::Blake3 => "blake3",
+    }
+}
+
+fn parse_hash_algorithm(name: &str) -> Result<HashAlgorithm, ClientError> {
+    match name {
+        "sha256" => Ok(HashAlgorithm::Sha256),
+        "keccak256" => Ok(HashAlgorithm::Keccak256),
+        "hash160" => Ok(HashAlgorithm::Hash160),
+        "blake3" => Ok(HashAlgorithm::Blake3),
+        _ => Err(ClientError::InvalidTransactionRequest(format!(
+            "unknown hash algorithm {name}"
+        ))),
+    }
+}
@@ -0,0 +1,112 @@
+//! The swap lifecycle as an explicit state machine, for off-chain components to track swaps
+//! by the same rules the program enforces.
+//!
+//! A swap is `Initiated` with its funds escrowed, and settles into exactly one of the terminal
+//! states `Redeemed`, `Refunded` or `InstantRefunded`, from which no transition is allowed.
+//! Partial redeems leave the swap `Initiated` with the remainder of its funds, until fully
+//! redeemed. Whether the swap has expired, and whether the secret is correct, are preconditions
+//! checked by the program on top of the transitions modelled here.
+
+use std::fmt;
+
+use crate::{events::SwapEvent, ClientError};
+
+/// The state of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapState {
+    /// The swap is open, with `remaining_lamports` yet to be redeemed or refunded
+    Initiated {
+        remaining_lamports: u64,
+    },
+    Redeemed,
+    Refunded,
+    InstantRefunded,
+}
+
+/// A transition of an initiated swap, as effected by each lifecycle instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapTransition {
+    /// Redeems a tranche of `amount_lamports` of the swap's remaining funds
+    Redeem {
+        amount_lamports: u64,
+    },
+    Refund,
+    InstantRefund,
+}
+
+impl SwapState {
+    /// The state of a swap just initiated with `amount_lamports`
+    pub fn initiated(amount_lamports: u64) -> Self {
+        SwapState::Initiated {
+            remaining_lamports: amount_lamports,
+        }
+    }
+
+    /// Whether the swap has settled, allowing no further transitions
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, SwapState::Initiated { .. })
+    }
+
+    /// The state following `transition`, if allowed from this state
+    pub fn apply(self, transition: SwapTransition) -> Result<SwapState, ClientError> {
+        let invalid = || ClientError::InvalidTransition {
+            state: self,
+            transition,
+        };
+        let SwapState::Initiated { remaining_lamports } = self else {
+            return Err(invalid());
+        };
+        match transition {
+            SwapTransition::Redeem { amount_lamports } => {
+                if amount_lamports == 0 || amount_lamports > remaining_lamports {
+                    return Err(invalid());
+                }
+                Ok(match remaining_lamports - amount_lamports {
+                    0 => SwapState::Redeemed,
+                    remaining_lamports => SwapState::Initiated { remaining_lamports },
+                })
+            }
+            SwapTransition::Refund => Ok(SwapState::Refunded),
+            SwapTransition::InstantRefund => Ok(SwapState::InstantRefunded),
+        }
+    }
+}
+
+impl SwapTransition {
+    /// The transition `event` records, `None` for initiations
+    pub fn of(event: &SwapEvent) -> Option<Self> {
+        match event {
+            SwapEvent::Initiated(_) => None,
+            SwapEvent::Redeemed(event) => Some(SwapTransition::Redeem {
+                amount_lamports: event.amount_lamports,
+            }),
+            SwapEvent::Refunded(_) => Some(SwapTransition::Refund),
+            SwapEvent::InstantRefunded(_) => Some(SwapTransition::InstantRefund),
+        }
+    }
+}
+
+impl fmt::Display for SwapState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapState::Initiated { remaining_lamports } => {
+                write!(f, "initiated with {remaining_lamports} lamports remaining")
+            }
+            SwapState::Redeemed => f.write_str("redeemed"),
+            SwapState::Refunded => f.write_str("refunded"),
+            SwapState::InstantRefunded => f.write_str("instant refunded"),
+        }
+    }
+}
+
+impl fmt::Display for SwapTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapTransition::Redeem { amount_lamports } => {
+                write!(f, "redeem {amount_lamports} lamports")
+            }
+            SwapTransition::Refund => f.write_str("refund"),
+            SwapTransition::InstantRefund => f.write_str("instant refund"),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Support for daemons serving several tenants, e.g. the desks of an operator, from a single
+//! instance, with each tenant's swaps handled under its own keypair, its own limits, its own
+//! metrics and its own webhook.
+//!
+//! [`RateLimiter`] caps the number of swaps acted on per tenant over a sliding hour,
+//! [`TenantMetrics`] counts what was done per tenant, served in the Prometheus text format by
+//! [`serve_metrics`], and [`Webhook`] notifies a tenant's own endpoint of what was done on its
+//! behalf.
+//!
+//! ```ignore
+//! let metrics = Arc::new(TenantMetrics::new("swap_keeper"));
+//! tokio::spawn(serve_metrics("127.0.0.1:9100".parse()?, metrics.clone()));
+//! let granted = limiter.acquire(expired.len());
+//! metrics.add(&tenant.name, "swaps_refunded", granted as u64);
+//! webhook.notify(&WebhookEvent::new(&tenant.name, "refunded", &swap_accounts)).await?;
+//! ```
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+use crate::{ClientError, Pubkey};
+
+/// The window the limits of a tenant apply over
+const LIMIT_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// The limits a tenant is held to, none by default
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct TenantLimits {
+    /// The maximum number of swaps acted on per hour
+    pub max_swaps_per_hour: Option<usize>,
+}
+
+/// Grants a tenant the swaps it may act on under its [`TenantLimits`], over a sliding hour
+pub struct RateLimiter {
+    limit: Option<usize>,
+    granted: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: &TenantLimits) -> Self {
+        RateLimiter {
+            limit: limits.max_swaps_per_hour,
+            granted: VecDeque::new(),
+        }
+    }
+
+    /// Grants up to `wanted` swaps, returning the number granted
+    pub fn acquire(&mut self, wanted: usize) -> usize {
+        self.acquire_at(wanted, Instant::now())
+    }
+
+    /// Grants up to `wanted` swaps as of `now`, returning the number granted
+    pub fn acquire_at(&mut self, wanted: usize, now: Instant) -> usize {
+        let Some(limit) = self.limit else {
+            return wanted;
+        };
+        while self
+            .granted
+            .front()
+            .is_some_and(|granted| now.duration_since(*granted) >= LIMIT_WINDOW)
+        {
+            self.granted.pop_front();
+        }
+        let granted = wanted.min(limit.saturating_sub(self.granted.len()));
+        self.granted.extend(std::iter::repeat_n(now, granted));
+        granted
+    }
+}
+
+/// Counters of what a daemon did on behalf of each tenant
+pub struct TenantMetrics {
+    /// The prefix of the name of every counter, e.g. the daemon's name
+    prefix: String,
+    counters: Mutex<BTreeMap<(String, &'static str), u64>>,
+}
+
+impl TenantMetrics {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        TenantMetrics {
+            prefix: prefix.into(),
+            counters: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Adds `value` to the counter `name` of `tenant`
+    pub fn add(&self, tenant: &str, name: &'static str, value: u64) {
+        *self
+            .counters
+            .lock()
+            .expect("metrics lock poisoned")
+            .entry((tenant.to_string(), name))
+            .or_default() += value;
+    }
+
+    /// The value of the counter `name` of `tenant`
+    pub fn get(&self, tenant: &str, name: &'static str) -> u64 {
+        self.counters
+            .lock()
+            .expect("metrics lock poisoned")
+            .get(&(tenant.to_string(), name))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Renders every counter in the Prometheus text format, labelled with its tenant
+    pub fn render(&self) -> String {
+        let counters = self.counters.lock().expect("metrics lock poisoned");
+        let mut by_name: BTreeMap<&str, Vec<(&str, u64)>> = BTreeMap::new();
+        for ((tenant, name), value) in counters.iter() {
+            by_name.entry(name).or_default().push((tenant, *value));
+        }
+        let mut rendered = String::new();
+        for (name, values) in by_name {
+            let name = format!("{}_{name}_total", self.prefix);
+            rendered.push_str(&format!("# TYPE {name} counter\n"));
+            for (tenant, value) in values {
+                let tenant = tenant.replace('\\', "\\\\").replace('"', "\\\"");
+                rendered.push_str(&format!("{name}{{tenant=\"{tenant}\"}} {value}\n"));
+            }
+        }
+        rendered
+    }
+}
+
+/// Serves `metrics` in the Prometheus text format to every request on `addr`, until failing to
+/// accept connections
+pub async fn serve_metrics(addr: SocketAddr, metrics: Arc<TenantMetrics>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            // The request is irrelevant, every path serving the metrics
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await;
+            let body = metrics.render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+/// What was done on behalf of a tenant, as posted to its webhook
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct WebhookEvent {
+    pub tenant: String,
+    /// What happened, e.g. `refunded`, `redeemed` or `rate_limited`
+    pub event: String,
+    /// The swap accounts concerned, in base58
+    pub swap_accounts: Vec<String>,
+    /// The signature of the transaction concerned, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Why the event happened, e.g. the error a transaction failed with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl WebhookEvent {
+    pub fn new(tenant: &str, event: &str, swap_accounts: &[Pubkey]) -> Self {
+        WebhookEvent {
+            tenant: tenant.to_string(),
+            event: event.to_string(),
+            swap_accounts: swap_accounts.iter().map(Pubkey::to_string).collect(),
+            signature: None,
+            detail: None,
+        }
+    }
+
+    pub fn signature(mut self, signature: impl ToString) -> Self {
+        self.signature = Some(signature.to_string());
+        self
+    }
+
+    pub fn detail(mut self, detail: impl ToString) -> Self {
+        self.detail = Some(detail.to_string());
+        self
+    }
+}
+
+/// A tenant's endpoint, notified of what is done on its behalf by POSTing [`WebhookEvent`]s as
+/// JSON
+pub struct Webhook {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl Webhook {
+    pub fn new(url: &str) -> Self {
+        Webhook {
+            http: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+
+    pub async fn notify(&self, event: &WebhookEvent) -> Result<(), ClientError> {
+        self.http
+            .post(&self.url)
+            .json(event)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
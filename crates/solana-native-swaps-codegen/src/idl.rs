@@ -0,0 +1,142 @@
+//! The subset of the Anchor IDL format the program's IDL uses
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Idl {
+    pub address: String,
+    pub metadata: Metadata,
+    pub instructions: Vec<Instruction>,
+    #[serde(default)]
+    pub accounts: Vec<Discriminated>,
+    #[serde(default)]
+    pub events: Vec<Discriminated>,
+    #[serde(default)]
+    pub errors: Vec<Error>,
+    #[serde(default)]
+    pub types: Vec<TypeDef>,
+    #[serde(default)]
+    pub constants: Vec<Constant>,
+}
+
+#[derive(Deserialize)]
+pub struct Metadata {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Deserialize)]
+pub struct Instruction {
+    pub name: String,
+    #[serde(default)]
+    pub docs: Vec<String>,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<InstructionAccount>,
+    pub args: Vec<Field>,
+}
+
+#[derive(Deserialize)]
+pub struct InstructionAccount {
+    pub name: String,
+    #[serde(default)]
+    pub docs: Vec<String>,
+    #[serde(default)]
+    pub writable: bool,
+    #[serde(default)]
+    pub signer: bool,
+    #[serde(default)]
+    pub optional: bool,
+    /// Composite accounts, which the program does not use, carry nested `accounts` instead
+    #[serde(default)]
+    pub accounts: Option<serde_json::Value>,
+}
+
+/// An account or event, identified by its discriminator, and defined in `types`
+#[derive(Deserialize)]
+pub struct Discriminated {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+pub struct Error {
+    pub code: u32,
+    pub name: String,
+    pub msg: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(default)]
+    pub docs: Vec<String>,
+    #[serde(rename = "type")]
+    pub ty: Type,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Type {
+    Primitive(String),
+    Complex(Complex),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Complex {
+    Option(Box<Type>),
+    Vec(Box<Type>),
+    Array(Box<Type>, usize),
+    Defined { name: String },
+}
+
+#[derive(Deserialize)]
+pub struct TypeDef {
+    pub name: String,
+    #[serde(default)]
+    pub docs: Vec<String>,
+    pub serialization: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: TypeDefKind,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TypeDefKind {
+    Struct {
+        #[serde(default)]
+        fields: Fields,
+    },
+    Enum {
+        variants: Vec<Variant>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Fields {
+    Named(Vec<Field>),
+    Tuple(Vec<Type>),
+}
+
+impl Default for Fields {
+    fn default() -> Self {
+        Fields::Named(vec![])
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Option<Fields>,
+}
+
+#[derive(Deserialize)]
+pub struct Constant {
+    pub name: String,
+    #[serde(default)]
+    pub docs: Vec<String>,
+    #[serde(rename = "type")]
+    pub ty: Type,
+    pub value: String,
+}
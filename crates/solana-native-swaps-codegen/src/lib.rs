@@ -0,0 +1,624 @@
+//! Generates lightweight Rust bindings for the program from its Anchor IDL, for services which
+//! only decode its accounts and events, and build its instructions, and which would rather not
+//! depend on `anchor-lang` and the program crate's Solana dependencies.
+//!
+//! The generated module depends only on `borsh` (with `derive`), `bytemuck` (with `derive` and
+//! `min_const_generics`) and `bs58`, and provides:
+//! - a `Pubkey` type, and the program's `ID` and constants
+//! - the program's types, Borsh-serialized, or `#[repr(C)]` and `Pod` when zero-copy, with
+//!   `DISCRIMINATOR` and `decode` for those which are accounts or events
+//! - an `instruction` module of the instructions' arguments, with `DISCRIMINATOR`, `data` and
+//!   `instruction`, and an `accounts` module of their accounts, with `to_account_metas`
+//! - `ProgramInstruction`, `ProgramEvent` and `ErrorCode` to decode instructions, events and
+//!   error codes
+//!
+//! ```ignore
+//! let idl = std::fs::read_to_string("idl/solana_native_swaps.json")?;
+//! std::fs::write(out_dir.join("solana_native_swaps.rs"), solana_native_swaps_codegen::generate(&idl)?)?;
+//! ```
+
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
+
+mod idl;
+
+use idl::{Complex, Constant, Fields, Idl, TypeDefKind};
+
+/// The tag prefixed to the data of the self-CPIs events are emitted through
+const EVENT_IX_TAG_LE: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+
+/// The names the generated module defines itself, which the IDL's types may not use
+const RESERVED_NAMES: [&str; 7] = [
+    "Pubkey",
+    "AccountMeta",
+    "Instruction",
+    "ProgramInstruction",
+    "ProgramEvent",
+    "ErrorCode",
+    "ID",
+];
+
+const KEYWORDS: [&str; 51] = [
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+];
+
+/// Generates the bindings of the program described by the IDL `idl`, as Rust source meant to
+/// be `include!`d as the contents of a module
+pub fn generate(idl: &str) -> Result<String> {
+    let idl: Idl = serde_json::from_str(idl).context("Malformed IDL")?;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Generated from the IDL of {} {} by solana-native-swaps-codegen, do not edit\n",
+        idl.metadata.name, idl.metadata.version
+    )?;
+    prelude(&mut out, &idl)?;
+    constants(&mut out, &idl)?;
+    types(&mut out, &idl)?;
+    instructions(&mut out, &idl)?;
+    events(&mut out, &idl)?;
+    errors(&mut out, &idl)?;
+    Ok(out)
+}
+
+fn prelude(out: &mut String, idl: &Idl) -> Result<()> {
+    let address = pubkey_bytes(&idl.address)?;
+    write!(
+        out,
+        r#"use borsh::{{BorshDeserialize, BorshSerialize}};
+
+/// An account address
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, BorshSerialize, BorshDeserialize, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Pubkey(pub [u8; 32]);
+
+impl Pubkey {{
+    pub const fn new_from_array(bytes: [u8; 32]) -> Self {{
+        Pubkey(bytes)
+    }}
+
+    pub const fn to_bytes(self) -> [u8; 32] {{
+        self.0
+    }}
+}}
+
+impl std::fmt::Display for Pubkey {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        f.write_str(&bs58::encode(self.0).into_string())
+    }}
+}}
+
+impl std::fmt::Debug for Pubkey {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        std::fmt::Display::fmt(self, f)
+    }}
+}}
+
+impl std::str::FromStr for Pubkey {{
+    type Err = bs58::decode::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {{
+        let mut bytes = [0; 32];
+        if bs58::decode(s).onto(&mut bytes)? != 32 {{
+            return Err(bs58::decode::Error::BufferTooSmall);
+        }}
+        Ok(Pubkey(bytes))
+    }}
+}}
+
+/// An account of an instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountMeta {{
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}}
+
+/// An instruction of the program, to be converted into the Solana SDK's own
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {{
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}}
+
+/// The address of the program
+pub const ID: Pubkey = Pubkey({address:?});
+
+/// The tag prefixed to the data of the self-CPIs events are emitted through
+pub const EVENT_IX_TAG_LE: [u8; 8] = {EVENT_IX_TAG_LE:?};
+
+"#
+    )?;
+    Ok(())
+}
+
+fn constants(out: &mut String, idl: &Idl) -> Result<()> {
+    for constant in &idl.constants {
+        docs(out, "", &constant.docs)?;
+        let (ty, value) = constant_value(constant)
+            .with_context(|| format!("Unsupported constant {}", constant.name))?;
+        writeln!(out, "pub const {}: {ty} = {value};\n", constant.name)?;
+    }
+    Ok(())
+}
+
+fn constant_value(constant: &Constant) -> Result<(String, String)> {
+    Ok(match &constant.ty {
+        idl::Type::Primitive(ty) if ty == "pubkey" => (
+            "Pubkey".into(),
+            format!("Pubkey({:?})", pubkey_bytes(&constant.value)?),
+        ),
+        idl::Type::Primitive(ty) if ty == "bytes" => {
+            let bytes: Vec<u8> = serde_json::from_str(&constant.value)?;
+            ("&[u8]".into(), format!("&{bytes:?}"))
+        }
+        idl::Type::Primitive(ty) if ty == "string" => {
+            // Anchor writes string constants as their Rust literal
+            let value: String = serde_json::from_str(&constant.value)?;
+            ("&str".into(), format!("{value:?}"))
+        }
+        idl::Type::Complex(Complex::Array(..)) => {
+            let elements: Vec<serde_json::Value> = serde_json::from_str(&constant.value)?;
+            (
+                rust_type(&constant.ty, false)?,
+                serde_json::to_string(&elements)?.replace(',', ", "),
+            )
+        }
+        idl::Type::Primitive(_) => (rust_type(&constant.ty, false)?, constant.value.clone()),
+        _ => bail!("Unsupported type"),
+    })
+}
+
+fn types(out: &mut String, idl: &Idl) -> Result<()> {
+    for def in &idl.types {
+        if RESERVED_NAMES.contains(&def.name.as_str()) {
+            bail!(
+                "Type {} conflicts with the generated type of that name",
+                def.name
+            );
+        }
+    }
+    for def in &idl.types {
+        let zero_copy = def.serialization.as_deref() == Some("bytemuck");
+        if let Some(serialization) = def.serialization.as_deref() {
+            if !zero_copy && serialization != "borsh" {
+                bail!(
+                    "Type {} has unsupported serialization {serialization}",
+                    def.name
+                );
+            }
+        }
+        docs(out, "", &def.docs)?;
+        match &def.ty {
+            TypeDefKind::Struct { fields } => {
+                if zero_copy {
+                    writeln!(out, "#[repr(C)]")?;
+                    writeln!(
+                        out,
+                        "#[derive(Clone, Copy, Debug, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]"
+                    )?;
+                } else {
+                    writeln!(
+                        out,
+                        "#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]"
+                    )?;
+                }
+                match fields {
+                    Fields::Named(fields) => {
+                        writeln!(out, "pub struct {} {{", def.name)?;
+                        for field in fields {
+                            docs(out, "    ", &field.docs)?;
+                            writeln!(
+                                out,
+                                "    pub {}: {},",
+                                field_name(&field.name),
+                                rust_type(&field.ty, zero_copy)?
+                            )?;
+                        }
+                        writeln!(out, "}}\n")?;
+                    }
+                    Fields::Tuple(fields) => {
+                        let fields = fields
+                            .iter()
+                            .map(|ty| Ok(format!("pub {}", rust_type(ty, zero_copy)?)))
+                            .collect::<Result<Vec<_>>>()?;
+                        writeln!(out, "pub struct {}({});\n", def.name, fields.join(", "))?;
+                    }
+                }
+            }
+            TypeDefKind::Enum { variants } => {
+                if zero_copy {
+                    bail!("Zero-copy enum {} is unsupported", def.name);
+                }
+                let unit = variants.iter().all(|variant| variant.fields.is_none());
+                if unit {
+                    writeln!(out, "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]")?;
+                } else {
+                    writeln!(
+                        out,
+                        "#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]"
+                    )?;
+                }
+                writeln!(out, "pub enum {} {{", def.name)?;
+                for variant in variants {
+                    match &variant.fields {
+                        None => writeln!(out, "    {},", variant.name)?,
+                        Some(Fields::Named(fields)) => {
+                            writeln!(out, "    {} {{", variant.name)?;
+                            for field in fields {
+                                writeln!(
+                                    out,
+                                    "        {}: {},",
+                                    field_name(&field.name),
+                                    rust_type(&field.ty, false)?
+                                )?;
+                            }
+                            writeln!(out, "    }},")?;
+                        }
+                        Some(Fields::Tuple(fields)) => {
+                            let fields = fields
+                                .iter()
+                                .map(|ty| rust_type(ty, false))
+                                .collect::<Result<Vec<_>>>()?;
+                            writeln!(out, "    {}({}),", variant.name, fields.join(", "))?;
+                        }
+                    }
+                }
+                writeln!(out, "}}\n")?;
+            }
+        }
+    }
+
+    // Accounts are decoded past their discriminator, by casting when zero-copy
+    for account in &idl.accounts {
+        let def = idl
+            .types
+            .iter()
+            .find(|def| def.name == account.name)
+            .with_context(|| format!("Account {} is not defined", account.name))?;
+        let discriminator = discriminator(&account.discriminator)?;
+        let decode = if def.serialization.as_deref() == Some("bytemuck") {
+            "let data = data.get(8..8 + std::mem::size_of::<Self>())?;\n        \
+             bytemuck::try_pod_read_unaligned(data).ok()"
+        } else {
+            "BorshDeserialize::deserialize(&mut &data[8..]).ok()"
+        };
+        write!(
+            out,
+            r#"impl {name} {{
+    pub const DISCRIMINATOR: [u8; 8] = {discriminator:?};
+
+    /// Decodes the account from its data, if it is such an account
+    pub fn decode(data: &[u8]) -> Option<Self> {{
+        if !data.starts_with(&Self::DISCRIMINATOR) {{
+            return None;
+        }}
+        {decode}
+    }}
+}}
+
+"#,
+            name = account.name,
+        )?;
+    }
+    Ok(())
+}
+
+fn instructions(out: &mut String, idl: &Idl) -> Result<()> {
+    let mut args = String::new();
+    let mut accounts = String::new();
+    let mut decoded = String::new();
+    let mut decode = String::new();
+    for instruction in &idl.instructions {
+        let name = camel_case(&instruction.name);
+        let discriminator = discriminator(&instruction.discriminator)?;
+
+        docs(&mut args, "    ", &instruction.docs)?;
+        writeln!(
+            args,
+            "    #[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]"
+        )?;
+        if instruction.args.is_empty() {
+            writeln!(args, "    pub struct {name};\n")?;
+        } else {
+            writeln!(args, "    pub struct {name} {{")?;
+            for arg in &instruction.args {
+                docs(&mut args, "        ", &arg.docs)?;
+                writeln!(
+                    args,
+                    "        pub {}: {},",
+                    field_name(&arg.name),
+                    rust_type(&arg.ty, false)?
+                )?;
+            }
+            writeln!(args, "    }}\n")?;
+        }
+        write!(
+            args,
+            r#"    impl {name} {{
+        pub const DISCRIMINATOR: [u8; 8] = {discriminator:?};
+
+        /// The instruction's data, i.e. its discriminator followed by its arguments
+        pub fn data(&self) -> Vec<u8> {{
+            let mut data = Self::DISCRIMINATOR.to_vec();
+            BorshSerialize::serialize(self, &mut data).expect("Writing to a Vec cannot fail");
+            data
+        }}
+
+        /// The instruction with these arguments and `accounts`
+        pub fn instruction(&self, accounts: &super::accounts::{name}) -> Instruction {{
+            Instruction {{
+                program_id: ID,
+                accounts: accounts.to_account_metas(),
+                data: self.data(),
+            }}
+        }}
+    }}
+
+"#
+        )?;
+
+        writeln!(accounts, "    #[derive(Clone, Copy, Debug, PartialEq, Eq)]")?;
+        writeln!(accounts, "    pub struct {name} {{")?;
+        let mut metas = String::new();
+        for account in &instruction.accounts {
+            if account.accounts.is_some() {
+                bail!(
+                    "Composite account {} of {} is unsupported",
+                    account.name,
+                    instruction.name
+                );
+            }
+            let field = field_name(&account.name);
+            docs(&mut accounts, "        ", &account.docs)?;
+            if account.optional {
+                writeln!(accounts, "        pub {field}: Option<Pubkey>,")?;
+                // Absent optional accounts are passed as the program itself
+                writeln!(
+                    metas,
+                    "                match self.{field} {{\n                    \
+                     Some(pubkey) => AccountMeta {{ pubkey, is_signer: {}, is_writable: {} }},\n                    \
+                     None => AccountMeta {{ pubkey: ID, is_signer: false, is_writable: false }},\n                \
+                     }},",
+                    account.signer, account.writable
+                )?;
+            } else {
+                writeln!(accounts, "        pub {field}: Pubkey,")?;
+                writeln!(
+                    metas,
+                    "                AccountMeta {{ pubkey: self.{field}, is_signer: {}, is_writable: {} }},",
+                    account.signer, account.writable
+                )?;
+            }
+        }
+        write!(
+            accounts,
+            r#"    }}
+
+    impl {name} {{
+        pub fn to_account_metas(&self) -> Vec<AccountMeta> {{
+            vec![
+{metas}            ]
+        }}
+    }}
+
+"#
+        )?;
+
+        writeln!(decoded, "    {name}(instruction::{name}),")?;
+        writeln!(
+            decode,
+            "            instruction::{name}::DISCRIMINATOR => Self::{name}(BorshDeserialize::deserialize(&mut data).ok()?),"
+        )?;
+    }
+    write!(
+        out,
+        r#"/// The arguments of the program's instructions
+pub mod instruction {{
+    use super::*;
+
+{args}}}
+
+/// The accounts of the program's instructions
+pub mod accounts {{
+    use super::*;
+
+{accounts}}}
+
+/// An instruction of the program, decoded from its data
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum ProgramInstruction {{
+{decoded}}}
+
+impl ProgramInstruction {{
+    /// Decodes an instruction's data, if it is an instruction of the program
+    pub fn decode(data: &[u8]) -> Option<Self> {{
+        let (discriminator, mut data) = data.split_first_chunk::<8>()?;
+        Some(match *discriminator {{
+{decode}            _ => return None,
+        }})
+    }}
+}}
+
+"#
+    )?;
+    Ok(())
+}
+
+fn events(out: &mut String, idl: &Idl) -> Result<()> {
+    let mut variants = String::new();
+    let mut decode = String::new();
+    for event in &idl.events {
+        let discriminator = discriminator(&event.discriminator)?;
+        write!(
+            out,
+            "impl {} {{\n    pub const DISCRIMINATOR: [u8; 8] = {discriminator:?};\n}}\n\n",
+            event.name
+        )?;
+        writeln!(variants, "    {0}({0}),", event.name)?;
+        writeln!(
+            decode,
+            "            {0}::DISCRIMINATOR => Self::{0}(BorshDeserialize::deserialize(&mut data).ok()?),",
+            event.name
+        )?;
+    }
+    write!(
+        out,
+        r#"/// An event of the program
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum ProgramEvent {{
+{variants}}}
+
+impl ProgramEvent {{
+    /// Decodes an event from its discriminator and fields
+    pub fn decode(data: &[u8]) -> Option<Self> {{
+        let (discriminator, mut data) = data.split_first_chunk::<8>()?;
+        Some(match *discriminator {{
+{decode}            _ => return None,
+        }})
+    }}
+
+    /// Decodes an event from the data of the self-CPI it was emitted through
+    pub fn from_cpi_data(data: &[u8]) -> Option<Self> {{
+        Self::decode(data.strip_prefix(&EVENT_IX_TAG_LE)?)
+    }}
+}}
+
+"#
+    )?;
+    Ok(())
+}
+
+fn errors(out: &mut String, idl: &Idl) -> Result<()> {
+    let mut variants = String::new();
+    let mut from_code = String::new();
+    let mut messages = String::new();
+    for error in &idl.errors {
+        if let Some(msg) = &error.msg {
+            writeln!(variants, "    /// {msg}")?;
+            writeln!(messages, "            Self::{} => {msg:?},", error.name)?;
+        } else {
+            writeln!(messages, "            Self::{0} => {0:?},", error.name)?;
+        }
+        writeln!(variants, "    {} = {},", error.name, error.code)?;
+        writeln!(
+            from_code,
+            "            {} => Self::{},",
+            error.code, error.name
+        )?;
+    }
+    write!(
+        out,
+        r#"/// The errors of the program
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ErrorCode {{
+{variants}}}
+
+impl ErrorCode {{
+    /// The error of a custom program error code, if it is one of the program's
+    pub fn from_code(code: u32) -> Option<Self> {{
+        Some(match code {{
+{from_code}            _ => return None,
+        }})
+    }}
+
+    pub fn message(&self) -> &'static str {{
+        match self {{
+{messages}        }}
+    }}
+}}
+
+impl std::fmt::Display for ErrorCode {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        f.write_str(self.message())
+    }}
+}}
+
+impl std::error::Error for ErrorCode {{}}
+"#
+    )?;
+    Ok(())
+}
+
+/// The Rust type of an IDL type, `zero_copy` types only allowing `Pod` types
+fn rust_type(ty: &idl::Type, zero_copy: bool) -> Result<String> {
+    Ok(match ty {
+        idl::Type::Primitive(ty) => match ty.as_str() {
+            "bool" | "string" | "bytes" if zero_copy => {
+                bail!("Type {ty} is not allowed in zero-copy types")
+            }
+            "bool" | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128"
+            | "i128" => ty.clone(),
+            "pubkey" => "Pubkey".into(),
+            "string" => "String".into(),
+            "bytes" => "Vec<u8>".into(),
+            _ => bail!("Unsupported type {ty}"),
+        },
+        idl::Type::Complex(Complex::Option(_) | Complex::Vec(_)) if zero_copy => {
+            bail!("Variable-length types are not allowed in zero-copy types")
+        }
+        idl::Type::Complex(Complex::Option(ty)) => format!("Option<{}>", rust_type(ty, false)?),
+        idl::Type::Complex(Complex::Vec(ty)) => format!("Vec<{}>", rust_type(ty, false)?),
+        idl::Type::Complex(Complex::Array(ty, len)) => {
+            format!("[{}; {len}]", rust_type(ty, zero_copy)?)
+        }
+        idl::Type::Complex(Complex::Defined { name }) => name.clone(),
+    })
+}
+
+fn docs(out: &mut String, indent: &str, docs: &[String]) -> Result<()> {
+    for line in docs {
+        if line.is_empty() {
+            writeln!(out, "{indent}///")?;
+        } else {
+            writeln!(out, "{indent}/// {line}")?;
+        }
+    }
+    Ok(())
+}
+
+fn discriminator(bytes: &[u8]) -> Result<[u8; 8]> {
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Discriminators of {} bytes are unsupported", bytes.len()))
+}
+
+fn pubkey_bytes(address: &str) -> Result<[u8; 32]> {
+    let mut bytes = [0; 32];
+    let len = bs58::decode(address)
+        .onto(&mut bytes)
+        .with_context(|| format!("Malformed address {address}"))?;
+    if len != 32 {
+        bail!("Malformed address {address}");
+    }
+    Ok(bytes)
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn field_name(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,29 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+
+/// Generates Anchor-free Rust bindings for the program from its IDL
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// The IDL of the program, as built by `anchor idl build`
+    #[arg(default_value = "idl/solana_native_swaps.json")]
+    idl: PathBuf,
+    /// Where to write the bindings, standard output if not set
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let idl = fs::read_to_string(&args.idl)
+        .with_context(|| format!("Failed to read IDL {}", args.idl.display()))?;
+    let bindings = solana_native_swaps_codegen::generate(&idl)?;
+    match args.out {
+        Some(out) => fs::write(&out, bindings)
+            .with_context(|| format!("Failed to write {}", out.display()))?,
+        None => print!("{bindings}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,274 @@
+//! A Geyser plugin streaming the swap lifecycle events of the program to NATS JetStream,
+//! for consumers needing every event exactly once, which RPC log subscriptions cannot provide
+//! under load.
+//!
+//! Events are decoded from the self-CPI inner instructions of successful transactions, and
+//! buffered per slot until the slot is rooted, so that events of abandoned forks are never
+//! published. Each event is published with a `Nats-Msg-Id` of its transaction signature and
+//! position within the transaction, letting JetStream discard the duplicates of retried publishes.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use agave_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaTransactionInfoVersions, Result, SlotStatus,
+};
+use async_nats::{jetstream, HeaderMap};
+use log::{error, info};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_native_swaps_client::{
+    events::{parse_inner_instructions, SwapEvent},
+    Pubkey,
+};
+use tokio::{runtime::Runtime, sync::mpsc};
+
+/// The delay before a failed publish is retried
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize)]
+struct Config {
+    nats_url: String,
+    subject: String,
+}
+
+/// A record of an event, ready to be published
+struct Record {
+    id: String,
+    payload: Value,
+}
+
+#[derive(Default)]
+pub struct SwapEventsPlugin {
+    runtime: Option<Runtime>,
+    publisher: Option<mpsc::UnboundedSender<Vec<Record>>>,
+    /// The records of slots yet to be rooted
+    pending: Arc<Mutex<BTreeMap<u64, Vec<Record>>>>,
+}
+
+impl std::fmt::Debug for SwapEventsPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SwapEventsPlugin").finish_non_exhaustive()
+    }
+}
+
+impl GeyserPlugin for SwapEventsPlugin {
+    fn name(&self) -> &'static str {
+        "solana-native-swaps-geyser"
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let config = std::fs::read_to_string(config_file).map_err(|e| {
+            GeyserPluginError::ConfigFileReadError {
+                msg: format!("Failed to read {config_file}: {e}"),
+            }
+        })?;
+        let config: Config =
+            serde_json::from_str(&config).map_err(|e| GeyserPluginError::ConfigFileReadError {
+                msg: format!("Malformed {config_file}: {e}"),
+            })?;
+
+        let runtime = Runtime::new().map_err(|e| GeyserPluginError::Custom(Box::new(e)))?;
+        let client = runtime
+            .block_on(async_nats::connect(&config.nats_url))
+            .map_err(|e| GeyserPluginError::Custom(Box::new(e)))?;
+        let (publisher, batches) = mpsc::unbounded_channel();
+        runtime.spawn(publish(jetstream::new(client), config.subject, batches));
+        info!("Streaming swap events to {}", config.nats_url);
+
+        self.runtime = Some(runtime);
+        self.publisher = Some(publisher);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        self.publisher = None;
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_timeout(Duration::from_secs(5));
+        }
+    }
+
+    fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: u64,
+    ) -> Result<()> {
+        let (signature, transaction, meta) = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(info) => (
+                info.signature,
+                info.transaction,
+                info.transaction_status_meta,
+            ),
+            ReplicaTransactionInfoVersions::V0_0_2(info) => (
+                info.signature,
+                info.transaction,
+                info.transaction_status_meta,
+            ),
+        };
+        if meta.status.is_err() {
+            return Ok(());
+        }
+        let Some(inner_instructions) = &meta.inner_instructions else {
+            return Ok(());
+        };
+
+        let account_keys = transaction.message().account_keys();
+        let instructions: Vec<(&Pubkey, &[u8])> = inner_instructions
+            .iter()
+            .flat_map(|inner| &inner.instructions)
+            .filter_map(|inner| {
+                let program_id = account_keys.get(inner.instruction.program_id_index.into())?;
+                Some((program_id, inner.instruction.data.as_slice()))
+            })
+            .collect();
+        let events = parse_inner_instructions(instructions)
+            .map_err(|e| GeyserPluginError::Custom(Box::new(e)))?;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let records = events.iter().enumerate().map(|(position, event)| Record {
+            id: format!("{signature}:{position}"),
+            payload: record(event, &signature.to_string(), slot),
+        });
+        self.pending
+            .lock()
+            .unwrap()
+            .entry(slot)
+            .or_default()
+            .extend(records);
+        Ok(())
+    }
+
+    fn update_slot_status(
+        &self,
+        slot: u64,
+        _parent: Option<u64>,
+        status: &SlotStatus,
+    ) -> Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        match status {
+            SlotStatus::Rooted => {
+                if let (Some(records), Some(publisher)) = (pending.remove(&slot), &self.publisher) {
+                    if publisher.send(records).is_err() {
+                        error!("Publisher stopped, dropping the events of slot {slot}");
+                    }
+                }
+                // Slots below the root that were never rooted belong to abandoned forks
+                pending.retain(|&pending_slot, _| pending_slot > slot);
+            }
+            SlotStatus::Dead(_) => {
+                pending.remove(&slot);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        false
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Publishes batches of records in order, retrying each until acknowledged by JetStream
+async fn publish(
+    jetstream: jetstream::Context,
+    subject: String,
+    mut batches: mpsc::UnboundedReceiver<Vec<Record>>,
+) {
+    while let Some(records) = batches.recv().await {
+        for record in records {
+            let payload = record.payload.to_string();
+            loop {
+                let mut headers = HeaderMap::new();
+                headers.insert("Nats-Msg-Id", record.id.as_str());
+                let published = jetstream
+                    .publish_with_headers(subject.clone(), headers, payload.clone().into())
+                    .await;
+                let acknowledged = match published {
+                    Ok(ack) => ack.await.map(|_| ()).map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                match acknowledged {
+                    Ok(()) => break,
+                    Err(e) => {
+                        error!("Failed to publish event {}: {e}", record.id);
+                        tokio::time::sleep(RETRY_DELAY).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The structured record of an event
+fn record(event: &SwapEvent, signature: &str, slot: u64) -> Value {
+    let mut record = match event {
+        SwapEvent::Initiated(event) => json!({
+            "kind": "initiated",
+            "initiator": event.initiator.to_string(),
+            "redeemer": event.redeemer.to_string(),
+            "secret_hash": hex(&event.secret_hash),
+            "hash_algorithm": format!("{:?}", event.hash_algorithm),
+            "amount_lamports": event.swap_amount,
+            "expires_in_slots": event.expires_in_slots,
+            "expiry_slot": event.expiry_slot,
+            "expiry_timestamp": event.expiry_timestamp,
+            "relayer_fee_lamports": event.incentives.relayer_fee_lamports,
+            "refund_tip_lamports": event.incentives.refund_tip_lamports,
+            "created_at_unix": event.created_at_unix,
+        }),
+        SwapEvent::Redeemed(event) => json!({
+            "kind": "redeemed",
+            "initiator": event.initiator.to_string(),
+            "redeemer": event.redeemer.to_string(),
+            "secret": hex(&event.secret),
+            "amount_lamports": event.amount_lamports,
+            "expiry_slot": event.expiry_slot,
+            "protocol_fee_lamports": event.protocol_fee_lamports,
+            "relayer_fee_lamports": event.relayer_fee_lamports,
+        }),
+        SwapEvent::Refunded(event) => json!({
+            "kind": "refunded",
+            "initiator": event.initiator.to_string(),
+            "redeemer": event.redeemer.to_string(),
+            "secret_hash": hex(&event.secret_hash),
+            "amount_lamports": event.amount_lamports,
+            "expiry_slot": event.expiry_slot,
+            "refund_tip_lamports": event.refund_tip_lamports,
+        }),
+        SwapEvent::InstantRefunded(event) => json!({
+            "kind": "instant_refunded",
+            "initiator": event.initiator.to_string(),
+            "redeemer": event.redeemer.to_string(),
+            "secret_hash": hex(&event.secret_hash),
+            "amount_lamports": event.amount_lamports,
+            "expiry_slot": event.expiry_slot,
+        }),
+    };
+    record["swap_account"] = json!(event.swap_account().to_string());
+    record["signature"] = json!(signature);
+    record["slot"] = json!(slot);
+    record
+}
+
+fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// # Safety
+///
+/// The validator takes ownership of the returned plugin, loading it through this symbol.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::<SwapEventsPlugin>::default();
+    Box::into_raw(plugin)
+}
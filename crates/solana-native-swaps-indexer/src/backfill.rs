@@ -0,0 +1,113 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{Context, Result};
+use futures_util::{stream, StreamExt};
+use log::{info, warn};
+use serde_json::Value;
+use solana_native_swaps_client::{
+    rpc::{decode_signatures, RpcClient, SignatureInfo},
+    ID,
+};
+
+use crate::{db::Database, fetch_events};
+
+/// Where the signatures of the transactions to backfill are found
+pub enum Source<'a> {
+    /// Paged from the archival RPC node, back to the newest transaction indexed
+    Archival,
+    /// Read from a JSON array of `getSignaturesForAddress` pages
+    Snapshot(&'a Path),
+}
+
+/// Indexes the transactions of `source` not yet indexed, fetching them from the archival RPC
+/// node, and returns the number of transactions indexed.
+///
+/// Events are folded into the swaps in the order they were emitted, so transactions older than
+/// the newest transaction indexed are skipped rather than indexed out of order.
+pub async fn backfill(
+    archival: &RpcClient,
+    source: Source<'_>,
+    concurrency: usize,
+    database: &mut Database,
+) -> Result<usize> {
+    let signatures = match source {
+        Source::Archival => page_signatures(archival, database).await?,
+        Source::Snapshot(path) => read_snapshot(path)?,
+    };
+    let cursor_slot = database.cursor_slot()?;
+    let mut pending = vec![];
+    let mut skipped = 0;
+    for info in signatures {
+        if database.is_indexed(&info.signature)? {
+            continue;
+        }
+        if cursor_slot.is_some_and(|cursor_slot| info.slot < cursor_slot) {
+            skipped += 1;
+            continue;
+        }
+        pending.push(info);
+    }
+    if skipped > 0 {
+        warn!("Skipped {skipped} transactions older than the newest transaction indexed");
+    }
+    info!("Backfilling {} transactions", pending.len());
+
+    // Transactions are fetched concurrently, but indexed in order
+    let mut transactions = stream::iter(&pending)
+        .map(|info| async move { (info, fetch_events(archival, info).await) })
+        .buffered(concurrency.max(1));
+    let mut indexed = 0;
+    let mut position = 0;
+    while let Some((info, events)) = transactions.next().await {
+        if database.index_transaction(&info.signature, info.slot, info.block_time, &events?)? {
+            indexed += 1;
+        }
+        position += 1;
+        if position % 1000 == 0 {
+            info!("Backfilled {position} of {} transactions", pending.len());
+        }
+    }
+    Ok(indexed)
+}
+
+/// Pages the signatures of the program from the archival RPC node back to the newest
+/// transaction indexed, returning them oldest first
+async fn page_signatures(archival: &RpcClient, database: &Database) -> Result<Vec<SignatureInfo>> {
+    let cursor = database.cursor()?;
+    let mut signatures: Vec<SignatureInfo> = vec![];
+    loop {
+        let before = signatures.last().map(|info| info.signature);
+        let page = archival
+            .get_signatures_for_address(&ID, before.as_ref(), cursor.as_ref())
+            .await?;
+        if page.is_empty() {
+            break;
+        }
+        signatures.extend(page);
+        info!("Found {} transactions to backfill", signatures.len());
+    }
+    signatures.reverse();
+    Ok(signatures)
+}
+
+/// Reads the signatures of a snapshot of `getSignaturesForAddress` pages, returning them oldest
+/// first. Pages may overlap, and need not be in order.
+fn read_snapshot(path: &Path) -> Result<Vec<SignatureInfo>> {
+    let snapshot =
+        fs::read(path).with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+    let pages: Vec<Value> = serde_json::from_slice(&snapshot)
+        .with_context(|| format!("Malformed snapshot {}", path.display()))?;
+    let mut seen = HashSet::new();
+    let mut signatures = vec![];
+    for page in pages {
+        for info in decode_signatures(page)? {
+            if seen.insert(info.signature) {
+                signatures.push(info);
+            }
+        }
+    }
+    // Each page is newest first, and transactions of the same slot keep that order reversed
+    signatures.reverse();
+    signatures.sort_by_key(|info| info.slot);
+    Ok(signatures)
+}
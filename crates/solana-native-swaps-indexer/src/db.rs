@@ -0,0 +1,317 @@
+use std::{path::Path, str::FromStr};
+
+use anyhow::{anyhow, Context, Result};
+use log::warn;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use solana_native_swaps_client::events::SwapEvent;
+use solana_signature::Signature;
+
+const SCHEMA: &str = "
+-- Every swap, from its initiation to its terminal state
+CREATE TABLE IF NOT EXISTS swaps (
+    id INTEGER PRIMARY KEY,
+    swap_account TEXT NOT NULL,
+    initiator TEXT NOT NULL,
+    redeemer TEXT NOT NULL,
+    secret_hash TEXT NOT NULL,
+    hash_algorithm TEXT NOT NULL,
+    amount_lamports INTEGER NOT NULL,
+    expiry_slot INTEGER NOT NULL,
+    expiry_timestamp INTEGER,
+    relayer_fee_lamports INTEGER NOT NULL,
+    refund_tip_lamports INTEGER NOT NULL,
+    initiate_signature TEXT NOT NULL,
+    initiate_slot INTEGER NOT NULL,
+    initiated_at INTEGER NOT NULL,
+    -- One of open, redeemed, refunded or instant_refunded
+    state TEXT NOT NULL,
+    -- The funds yet to be redeemed or refunded
+    remaining_lamports INTEGER NOT NULL,
+    redeemed_lamports INTEGER NOT NULL DEFAULT 0,
+    protocol_fee_paid_lamports INTEGER NOT NULL DEFAULT 0,
+    relayer_fee_paid_lamports INTEGER NOT NULL DEFAULT 0,
+    refunded_lamports INTEGER NOT NULL DEFAULT 0,
+    refund_tip_paid_lamports INTEGER NOT NULL DEFAULT 0,
+    secret TEXT,
+    terminal_signature TEXT,
+    terminal_slot INTEGER,
+    terminal_at INTEGER,
+    -- The seconds elapsed between the initiation and the terminal state
+    duration_secs INTEGER
+);
+CREATE INDEX IF NOT EXISTS swaps_swap_account ON swaps (swap_account, state);
+CREATE INDEX IF NOT EXISTS swaps_initiator ON swaps (initiator);
+CREATE INDEX IF NOT EXISTS swaps_redeemer ON swaps (redeemer);
+
+-- Every event of every swap, in the order they were emitted
+CREATE TABLE IF NOT EXISTS swap_events (
+    signature TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    swap_id INTEGER REFERENCES swaps (id),
+    swap_account TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount_lamports INTEGER NOT NULL,
+    PRIMARY KEY (signature, position)
+);
+
+-- Every transaction indexed, so that those found both by backfilling and by following the
+-- program's history are indexed once
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL
+);
+
+-- The newest transaction indexed, from which indexing resumes
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL
+);
+";
+
+/// The SQLite database holding the swap history
+pub struct Database {
+    connection: Connection,
+}
+
+impl Database {
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("Failed to open database {}", path.display()))?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Database { connection })
+    }
+
+    /// The newest transaction indexed, if any
+    pub fn cursor(&self) -> Result<Option<Signature>> {
+        let signature: Option<String> = self
+            .connection
+            .query_row("SELECT signature FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        signature
+            .map(|signature| {
+                Signature::from_str(&signature)
+                    .map_err(|_| anyhow!("Invalid cursor signature {signature}"))
+            })
+            .transpose()
+    }
+
+    /// The slot of the newest transaction indexed, if any. Databases predating the
+    /// `transactions` table fall back to the newest slot with swap events.
+    pub fn cursor_slot(&self) -> Result<Option<u64>> {
+        let slot = self
+            .connection
+            .query_row(
+                "SELECT slot FROM transactions JOIN cursor USING (signature)",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if slot.is_some() {
+            return Ok(slot);
+        }
+        Ok(self
+            .connection
+            .query_row("SELECT MAX(slot) FROM swap_events", [], |row| row.get(0))?)
+    }
+
+    /// Whether the transaction with `signature` was already indexed
+    pub fn is_indexed(&self, signature: &Signature) -> Result<bool> {
+        is_indexed(&self.connection, &signature.to_string())
+    }
+
+    /// Records the events of a transaction, advancing the cursor to it, and returns whether it
+    /// was indexed, transactions already indexed being skipped.
+    /// Transactions must be indexed in the order they were executed.
+    pub fn index_transaction(
+        &mut self,
+        signature: &Signature,
+        slot: u64,
+        block_time: Option<i64>,
+        events: &[SwapEvent],
+    ) -> Result<bool> {
+        let transaction = self.connection.transaction()?;
+        let signature = signature.to_string();
+        if is_indexed(&transaction, &signature)? {
+            return Ok(false);
+        }
+        transaction.execute(
+            "INSERT INTO transactions (signature, slot) VALUES (?1, ?2)",
+            params![signature, slot],
+        )?;
+        for (position, event) in events.iter().enumerate() {
+            let swap_id = apply(&transaction, &signature, slot, block_time, event)?;
+            let (kind, amount_lamports) = match event {
+                SwapEvent::Initiated(event) => ("initiated", event.swap_amount),
+                SwapEvent::Redeemed(event) => ("redeemed", event.amount_lamports),
+                SwapEvent::Refunded(event) => ("refunded", event.amount_lamports),
+                SwapEvent::InstantRefunded(event) => ("instant_refunded", event.amount_lamports),
+            };
+            transaction.execute(
+                "INSERT INTO swap_events
+                 (signature, position, slot, block_time, swap_id, swap_account, kind, amount_lamports)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    position,
+                    slot,
+                    block_time,
+                    swap_id,
+                    event.swap_account().to_string(),
+                    kind,
+                    amount_lamports,
+                ],
+            )?;
+        }
+        transaction.execute(
+            "INSERT INTO cursor (id, signature) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+            params![signature],
+        )?;
+        transaction.commit()?;
+        Ok(true)
+    }
+}
+
+/// Whether the transaction with `signature` was indexed, including by releases of the indexer
+/// which only recorded the transactions with swap events
+fn is_indexed(connection: &Connection, signature: &str) -> Result<bool> {
+    Ok(connection.query_row(
+        "SELECT EXISTS (SELECT 1 FROM transactions WHERE signature = ?1)
+             OR EXISTS (SELECT 1 FROM swap_events WHERE signature = ?1)",
+        params![signature],
+        |row| row.get(0),
+    )?)
+}
+
+/// Applies an event to the swap it pertains to, returning the ID of the swap.
+/// Returns `None` if the swap's initiation was never indexed.
+fn apply(
+    transaction: &Transaction,
+    signature: &str,
+    slot: u64,
+    block_time: Option<i64>,
+    event: &SwapEvent,
+) -> Result<Option<i64>> {
+    if let SwapEvent::Initiated(event) = event {
+        transaction.execute(
+            "INSERT INTO swaps
+             (swap_account, initiator, redeemer, secret_hash, hash_algorithm, amount_lamports,
+              expiry_slot, expiry_timestamp, relayer_fee_lamports, refund_tip_lamports,
+              initiate_signature, initiate_slot, initiated_at, state, remaining_lamports)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, 'open', ?6)",
+            params![
+                event.swap_account.to_string(),
+                event.initiator.to_string(),
+                event.redeemer.to_string(),
+                hex::encode(event.secret_hash),
+                format!("{:?}", event.hash_algorithm),
+                event.swap_amount,
+                event.expiry_slot,
+                event.expiry_timestamp,
+                event.incentives.relayer_fee_lamports,
+                event.incentives.refund_tip_lamports,
+                signature,
+                slot,
+                event.created_at_unix,
+            ],
+        )?;
+        return Ok(Some(transaction.last_insert_rowid()));
+    }
+
+    // Swap accounts are closed once settled, so an address may be reused by later swaps,
+    // of which at most one is open at a time
+    let swap_account = event.swap_account().to_string();
+    let swap: Option<(i64, u64, i64)> = transaction
+        .query_row(
+            "SELECT id, remaining_lamports, initiated_at FROM swaps
+             WHERE swap_account = ?1 AND state = 'open'",
+            params![swap_account],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    let Some((id, remaining_lamports, initiated_at)) = swap else {
+        warn!("Transaction {signature} settles swap {swap_account} with no indexed initiation");
+        return Ok(None);
+    };
+    let duration_secs = block_time.map(|block_time| block_time - initiated_at);
+
+    match event {
+        SwapEvent::Initiated(_) => unreachable!(),
+        SwapEvent::Redeemed(event) => {
+            let remaining_lamports = remaining_lamports.saturating_sub(event.amount_lamports);
+            // Partial redeems leave the swap open
+            let settled = remaining_lamports == 0;
+            transaction.execute(
+                "UPDATE swaps SET
+                 remaining_lamports = ?2,
+                 redeemed_lamports = redeemed_lamports + ?3,
+                 protocol_fee_paid_lamports = protocol_fee_paid_lamports + ?4,
+                 relayer_fee_paid_lamports = relayer_fee_paid_lamports + ?5,
+                 expiry_slot = ?6,
+                 secret = ?7,
+                 state = CASE WHEN ?8 THEN 'redeemed' ELSE 'open' END,
+                 terminal_signature = CASE WHEN ?8 THEN ?9 END,
+                 terminal_slot = CASE WHEN ?8 THEN ?10 END,
+                 terminal_at = CASE WHEN ?8 THEN ?11 END,
+                 duration_secs = CASE WHEN ?8 THEN ?12 END
+                 WHERE id = ?1",
+                params![
+                    id,
+                    remaining_lamports,
+                    event.amount_lamports,
+                    event.protocol_fee_lamports,
+                    event.relayer_fee_lamports,
+                    event.expiry_slot,
+                    hex::encode(event.secret),
+                    settled,
+                    signature,
+                    slot,
+                    block_time,
+                    duration_secs,
+                ],
+            )?;
+        }
+        SwapEvent::Refunded(event) => {
+            transaction.execute(
+                "UPDATE swaps SET
+                 state = 'refunded', remaining_lamports = 0,
+                 refunded_lamports = ?2, refund_tip_paid_lamports = ?3, expiry_slot = ?4,
+                 terminal_signature = ?5, terminal_slot = ?6, terminal_at = ?7,
+                 duration_secs = ?8
+                 WHERE id = ?1",
+                params![
+                    id,
+                    event.amount_lamports,
+                    event.refund_tip_lamports,
+                    event.expiry_slot,
+                    signature,
+                    slot,
+                    block_time,
+                    duration_secs,
+                ],
+            )?;
+        }
+        SwapEvent::InstantRefunded(event) => {
+            transaction.execute(
+                "UPDATE swaps SET
+                 state = 'instant_refunded', remaining_lamports = 0, refunded_lamports = ?2,
+                 terminal_signature = ?3, terminal_slot = ?4, terminal_at = ?5,
+                 duration_secs = ?6
+                 WHERE id = ?1",
+                params![
+                    id,
+                    event.amount_lamports,
+                    signature,
+                    slot,
+                    block_time,
+                    duration_secs,
+                ],
+            )?;
+        }
+    }
+    Ok(Some(id))
+}
@@ -0,0 +1,162 @@
+//! `swap-indexer` backfills the history of every swap of the program into a SQLite database,
+//! for auditing and reconciliation.
+//!
+//! It walks every transaction of the program since its deployment through
+//! `getSignaturesForAddress`, oldest first, decodes their swap events, and folds them into a
+//! `swaps` table with a row per swap from its initiation to its terminal state, along with a
+//! `swap_events` table of the individual events. Indexing resumes from the newest transaction
+//! indexed, so it can be rerun, or kept running with `--follow`, to pick up new transactions.
+//!
+//! RPC nodes rarely retain the program's entire history, so the history before it can first be
+//! backfilled from an archival source: either an archival RPC node, e.g. one backed by Bigtable,
+//! with `--archival-url`, or a snapshot of `getSignaturesForAddress` pages with
+//! `--backfill-signatures`. Transactions are recorded as they are indexed, so those found by both
+//! the backfill and the live indexing are indexed once.
+
+mod backfill;
+mod db;
+
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use log::{error, info};
+use solana_native_swaps_client::{
+    events::{parse_inner_instructions, parse_logs, SwapEvent},
+    rpc::{Commitment, RpcClient, SignatureInfo},
+    ID,
+};
+
+use crate::db::Database;
+
+#[derive(Parser)]
+#[command(name = "swap-indexer", version, about)]
+struct Cli {
+    /// The URL of the RPC node, which must retain the program's entire transaction history
+    #[arg(
+        long,
+        short,
+        env = "SWAP_INDEXER_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// The URL of an archival RPC node, e.g. one backed by Bigtable, from which the history
+    /// before that retained by `--url` is backfilled
+    #[arg(long, env = "SWAP_INDEXER_ARCHIVAL_URL")]
+    archival_url: Option<String>,
+
+    /// A JSON array of `getSignaturesForAddress` pages of the program, whose transactions are
+    /// backfilled from the archival RPC node rather than paging its signatures
+    #[arg(long, requires = "archival_url")]
+    backfill_signatures: Option<PathBuf>,
+
+    /// The number of transactions fetched concurrently from the archival RPC node
+    #[arg(long, default_value_t = 8)]
+    concurrency: usize,
+
+    /// The path of the SQLite database
+    #[arg(long, short, env = "SWAP_INDEXER_DATABASE", default_value = "swaps.db")]
+    database: PathBuf,
+
+    /// Keeps indexing new transactions once caught up
+    #[arg(long)]
+    follow: bool,
+
+    /// The number of seconds between polls for new transactions when following
+    #[arg(long, default_value_t = 30)]
+    interval_secs: u64,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let cli = Cli::parse();
+    // Only finalized transactions are indexed, so that the history is never rolled back
+    let rpc = RpcClient::new_with_commitment(&cli.url, Commitment::Finalized);
+    let mut database = Database::open(&cli.database)?;
+
+    if let Some(archival_url) = &cli.archival_url {
+        let archival = RpcClient::new_with_commitment(archival_url, Commitment::Finalized);
+        let source = match &cli.backfill_signatures {
+            Some(path) => backfill::Source::Snapshot(path),
+            None => backfill::Source::Archival,
+        };
+        let indexed = backfill::backfill(&archival, source, cli.concurrency, &mut database).await?;
+        info!("Backfilled {indexed} transactions");
+    }
+
+    loop {
+        match index(&rpc, &mut database).await {
+            Ok(indexed) => info!("Indexed {indexed} transactions"),
+            Err(e) if cli.follow => error!("Indexing failed: {e:#}"),
+            Err(e) => return Err(e),
+        }
+        if !cli.follow {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(cli.interval_secs)).await;
+    }
+}
+
+/// Indexes every transaction of the program newer than the cursor,
+/// returning the number of transactions indexed
+async fn index(rpc: &RpcClient, database: &mut Database) -> Result<usize> {
+    let cursor = database.cursor()?;
+
+    // Signatures are returned newest first, so every page is fetched before indexing any
+    let mut signatures: Vec<SignatureInfo> = vec![];
+    loop {
+        let before = signatures.last().map(|info| info.signature);
+        let page = rpc
+            .get_signatures_for_address(&ID, before.as_ref(), cursor.as_ref())
+            .await?;
+        if page.is_empty() {
+            break;
+        }
+        signatures.extend(page);
+        info!("Found {} transactions to index", signatures.len());
+    }
+
+    let mut indexed = 0;
+    for (position, info) in signatures.iter().rev().enumerate() {
+        // Transactions already backfilled are not fetched again
+        if database.is_indexed(&info.signature)? {
+            continue;
+        }
+        let events = fetch_events(rpc, info).await?;
+        if database.index_transaction(&info.signature, info.slot, info.block_time, &events)? {
+            indexed += 1;
+        }
+        if (position + 1) % 1000 == 0 {
+            info!(
+                "Indexed {} of {} transactions",
+                position + 1,
+                signatures.len()
+            );
+        }
+    }
+    Ok(indexed)
+}
+
+/// Fetches the swap events of a transaction, failed transactions having none
+async fn fetch_events(rpc: &RpcClient, info: &SignatureInfo) -> Result<Vec<SwapEvent>> {
+    if info.failed {
+        return Ok(vec![]);
+    }
+    let transaction = rpc
+        .get_transaction(&info.signature)
+        .await?
+        .ok_or_else(|| anyhow!("Transaction {} not found", info.signature))?;
+    let events = parse_inner_instructions(
+        transaction
+            .inner_instructions
+            .iter()
+            .map(|(program_id, data)| (program_id, data.as_slice())),
+    )?;
+    // Earlier releases of the program logged their events instead
+    if events.is_empty() {
+        return Ok(parse_logs(&transaction.log_messages)?);
+    }
+    Ok(events)
+}
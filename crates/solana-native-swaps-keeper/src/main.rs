@@ -0,0 +1,229 @@
+//! `swap-keeper` refunds expired swaps on behalf of their initiators, who regularly forget to
+//! reclaim their funds. It periodically scans every open swap of the program, and refunds the
+//! expired ones in batches through `refund_expired_batch`, optionally claiming their refund tips.
+//!
+//! With a tenants file, the keeper refunds the swaps of several tenants instead, each under its
+//! own keypair, limits and webhook, and serves per-tenant metrics if `--metrics-addr` is set.
+
+mod tenant;
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use clap::Parser;
+use log::{error, info, warn};
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_native_swaps_client::{
+    fetch_clock, fetch_swap_accounts,
+    rpc::RpcClient,
+    tenant::{serve_metrics, TenantMetrics, WebhookEvent},
+    Pubkey, RefundExpiredBatchBuilder, SwapAccount,
+};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::tenant::Tenant;
+
+#[derive(Parser)]
+#[command(name = "swap-keeper", version, about)]
+struct Cli {
+    /// The URL of the RPC node
+    #[arg(
+        long,
+        short,
+        env = "SWAP_KEEPER_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// The keypair file of the keeper, which pays the transaction fees
+    #[arg(
+        long,
+        short,
+        env = "SWAP_KEEPER_KEYPAIR",
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: String,
+
+    /// Claims the refund tips of the swaps, as offered by their initiators
+    #[arg(long)]
+    claim_tips: bool,
+
+    /// Only refunds the swaps of these initiators, if any are given
+    #[arg(long = "initiator")]
+    initiators: Vec<Pubkey>,
+
+    /// The maximum number of swaps refunded per transaction
+    #[arg(long, default_value_t = 10)]
+    batch_size: usize,
+
+    /// The compute unit price of refund transactions, in micro-lamports
+    #[arg(long, default_value_t = 0)]
+    priority_fee: u64,
+
+    /// The number of seconds between scans
+    #[arg(long, default_value_t = 60)]
+    interval_secs: u64,
+
+    /// Scans once and exits
+    #[arg(long)]
+    once: bool,
+
+    /// The TOML file of the tenants to refund the swaps of, each under its own keypair, instead
+    /// of those of `--keypair` and `--initiator`
+    #[arg(long, env = "SWAP_KEEPER_TENANTS")]
+    tenants: Option<String>,
+
+    /// The address to serve the per-tenant metrics on, in the Prometheus text format
+    #[arg(long, env = "SWAP_KEEPER_METRICS_ADDR")]
+    metrics_addr: Option<SocketAddr>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let cli = Cli::parse();
+    let mut tenants = match &cli.tenants {
+        Some(path) => Tenant::read_all(path)?,
+        None => vec![Tenant::single(
+            &cli.keypair,
+            cli.initiators.clone(),
+            cli.claim_tips,
+        )?],
+    };
+    let metrics = Arc::new(TenantMetrics::new("swap_keeper"));
+    if let Some(addr) = cli.metrics_addr {
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_metrics(addr, metrics).await {
+                error!("Failed to serve metrics on {addr}: {e}");
+            }
+        });
+    }
+    let rpc = RpcClient::new(&cli.url);
+
+    loop {
+        if let Err(e) = refund_expired(&cli, &rpc, &mut tenants, &metrics).await {
+            error!("Scan failed: {e:#}");
+        }
+        if cli.once {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(cli.interval_secs)).await;
+    }
+}
+
+/// Refunds every expired swap of every tenant in batches
+async fn refund_expired(
+    cli: &Cli,
+    rpc: &RpcClient,
+    tenants: &mut [Tenant],
+    metrics: &TenantMetrics,
+) -> Result<()> {
+    let clock = fetch_clock(rpc).await?;
+    let expired: Vec<(Pubkey, SwapAccount)> = fetch_swap_accounts(rpc)
+        .await?
+        .into_iter()
+        .filter(|(_, swap)| swap.has_expired(&clock))
+        .collect();
+    info!("Found {} expired swaps", expired.len());
+
+    for tenant in tenants {
+        let mut owned: Vec<_> = expired
+            .iter()
+            .filter(|(_, swap)| tenant.owns(&swap.initiator))
+            .collect();
+        let granted = tenant.limiter.acquire(owned.len());
+        if granted < owned.len() {
+            let limited: Vec<Pubkey> = owned[granted..]
+                .iter()
+                .map(|(address, _)| *address)
+                .collect();
+            warn!(
+                "Tenant {} is limited to refunding {granted} of its {} expired swaps",
+                tenant.name,
+                owned.len()
+            );
+            metrics.add(&tenant.name, "swaps_rate_limited", limited.len() as u64);
+            notify(
+                tenant,
+                WebhookEvent::new(&tenant.name, "rate_limited", &limited),
+            )
+            .await;
+            owned.truncate(granted);
+        }
+        for batch in owned.chunks(cli.batch_size.max(1)) {
+            refund_batch(cli, rpc, tenant, batch, metrics).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Refunds a batch of the expired swaps of `tenant`, under its keypair
+async fn refund_batch(
+    cli: &Cli,
+    rpc: &RpcClient,
+    tenant: &Tenant,
+    batch: &[&(Pubkey, SwapAccount)],
+    metrics: &TenantMetrics,
+) -> Result<()> {
+    let mut refund = batch
+        .iter()
+        .fold(RefundExpiredBatchBuilder::new(), |refund, (_, swap)| {
+            refund.swap(swap)
+        });
+    if tenant.claim_tips {
+        refund = refund.refunder(tenant.keypair.pubkey());
+    }
+    let mut instructions = vec![];
+    if cli.priority_fee > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+            cli.priority_fee,
+        ));
+    }
+    instructions.push(refund.instruction());
+
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&tenant.keypair.pubkey()),
+        &[&tenant.keypair],
+        rpc.get_latest_blockhash().await?,
+    );
+    let swap_accounts: Vec<Pubkey> = batch.iter().map(|(address, _)| *address).collect();
+    // A batch fails as a whole if any of its swaps has been completed in the meantime,
+    // in which case its remaining swaps are retried on the next scan
+    let event = match rpc.send_and_confirm_transaction(&transaction).await {
+        Ok(signature) => {
+            info!(
+                "Refunded {} swaps of tenant {} in {signature}",
+                batch.len(),
+                tenant.name
+            );
+            metrics.add(&tenant.name, "swaps_refunded", batch.len() as u64);
+            WebhookEvent::new(&tenant.name, "refunded", &swap_accounts).signature(signature)
+        }
+        Err(e) => {
+            error!(
+                "Failed to refund a batch of {} swaps of tenant {}: {e}",
+                batch.len(),
+                tenant.name
+            );
+            metrics.add(&tenant.name, "refund_batches_failed", 1);
+            WebhookEvent::new(&tenant.name, "refund_failed", &swap_accounts).detail(e)
+        }
+    };
+    notify(tenant, event).await;
+    Ok(())
+}
+
+/// Posts `event` to the webhook of `tenant`, if it has one
+async fn notify(tenant: &Tenant, event: WebhookEvent) {
+    if let Some(webhook) = &tenant.webhook {
+        if let Err(e) = webhook.notify(&event).await {
+            error!(
+                "Failed to notify the webhook of tenant {}: {e}",
+                tenant.name
+            );
+        }
+    }
+}
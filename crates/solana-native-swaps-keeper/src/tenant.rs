@@ -0,0 +1,100 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_native_swaps_client::{
+    tenant::{RateLimiter, TenantLimits, Webhook},
+    Pubkey,
+};
+
+/// The tenants file, listing the tenants the keeper refunds the swaps of.
+/// See `tenants.example.toml` for a documented example.
+#[derive(Deserialize)]
+struct TenantsFile {
+    tenants: Vec<TenantConfig>,
+}
+
+#[derive(Deserialize)]
+struct TenantConfig {
+    name: String,
+    keypair: String,
+    initiators: Vec<Pubkey>,
+    #[serde(default)]
+    claim_tips: bool,
+    #[serde(default)]
+    limits: TenantLimits,
+    webhook_url: Option<String>,
+}
+
+/// A tenant whose expired swaps are refunded under its own keypair
+pub struct Tenant {
+    pub name: String,
+    /// Pays the fees of the tenant's refunds, and claims their refund tips
+    pub keypair: Keypair,
+    /// The initiators of the tenant's swaps, every initiator if empty
+    pub initiators: Vec<Pubkey>,
+    pub claim_tips: bool,
+    pub limiter: RateLimiter,
+    pub webhook: Option<Webhook>,
+}
+
+impl Tenant {
+    /// The sole tenant of a keeper not configured with a tenants file
+    pub fn single(keypair: &str, initiators: Vec<Pubkey>, claim_tips: bool) -> Result<Self> {
+        Ok(Tenant {
+            name: "default".to_string(),
+            keypair: read_keypair(keypair)?,
+            initiators,
+            claim_tips,
+            limiter: RateLimiter::new(&TenantLimits::default()),
+            webhook: None,
+        })
+    }
+
+    /// Reads the tenants of a tenants file, whose swaps must not overlap
+    pub fn read_all(path: &str) -> Result<Vec<Self>> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tenants {path}"))?;
+        let file: TenantsFile =
+            toml::from_str(&contents).with_context(|| format!("Malformed tenants {path}"))?;
+        let (mut names, mut initiators) = (HashSet::new(), HashSet::new());
+        let mut tenants = vec![];
+        for tenant in file.tenants {
+            if !names.insert(tenant.name.clone()) {
+                bail!("Tenant {} is configured twice", tenant.name);
+            }
+            // Each swap is refunded on behalf of a single tenant, under that tenant's keypair
+            if tenant.initiators.is_empty() {
+                bail!(
+                    "Tenant {} must list the initiators of its swaps",
+                    tenant.name
+                );
+            }
+            if let Some(initiator) = tenant.initiators.iter().find(|i| !initiators.insert(**i)) {
+                bail!("Initiator {initiator} belongs to several tenants");
+            }
+            tenants.push(Tenant {
+                keypair: read_keypair(&tenant.keypair)?,
+                limiter: RateLimiter::new(&tenant.limits),
+                webhook: tenant.webhook_url.as_deref().map(Webhook::new),
+                name: tenant.name,
+                initiators: tenant.initiators,
+                claim_tips: tenant.claim_tips,
+            });
+        }
+        Ok(tenants)
+    }
+
+    pub fn owns(&self, initiator: &Pubkey) -> bool {
+        self.initiators.is_empty() || self.initiators.contains(initiator)
+    }
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match path.strip_prefix("~/") {
+        Some(relative) => PathBuf::from(std::env::var("HOME")?).join(relative),
+        None => PathBuf::from(path),
+    };
+    read_keypair_file(&path).map_err(|e| anyhow!("Failed to read keypair {}: {e}", path.display()))
+}
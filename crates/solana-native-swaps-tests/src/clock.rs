@@ -0,0 +1,61 @@
+//! Deterministic control of the clock of a LiteSVM instance, for testing timelocks at their
+//! exact boundaries.
+//!
+//! LiteSVM's own `warp_to_slot` only moves the slot, leaving the unix timestamp and epoch
+//! behind. The helpers here keep them consistent with the slot, advancing the unix timestamp
+//! at the target slot time and the epoch as per the cluster's `EpochSchedule`, unless set
+//! explicitly.
+
+use anchor_lang::prelude::{Clock, EpochSchedule};
+use litesvm::LiteSVM;
+
+/// The target duration of a slot, in milliseconds
+pub const MS_PER_SLOT: u64 = 400;
+
+/// The current clock of `svm`
+pub fn clock(svm: &LiteSVM) -> Clock {
+    svm.get_sysvar::<Clock>()
+}
+
+/// Warps to `slot`, moving the unix timestamp along at [`MS_PER_SLOT`], and the epoch
+pub fn warp_to_slot(svm: &mut LiteSVM, slot: u64) {
+    let mut clock = clock(svm);
+    let elapsed_slots = i128::from(slot) - i128::from(clock.slot);
+    clock.unix_timestamp += (elapsed_slots * i128::from(MS_PER_SLOT) / 1_000) as i64;
+    clock.slot = slot;
+    let epoch = svm.get_sysvar::<EpochSchedule>().get_epoch(slot);
+    if epoch != clock.epoch {
+        clock.epoch = epoch;
+        clock.epoch_start_timestamp = clock.unix_timestamp;
+        clock.leader_schedule_epoch = epoch + 1;
+    }
+    svm.set_sysvar(&clock);
+}
+
+/// Advances the clock by `slots` slots
+pub fn advance_slots(svm: &mut LiteSVM, slots: u64) {
+    let slot = clock(svm).slot;
+    warp_to_slot(svm, slot + slots);
+}
+
+/// Warps to the first slot of `epoch`
+pub fn warp_to_epoch(svm: &mut LiteSVM, epoch: u64) {
+    let slot = svm
+        .get_sysvar::<EpochSchedule>()
+        .get_first_slot_in_epoch(epoch);
+    warp_to_slot(svm, slot);
+}
+
+/// Sets the unix timestamp alone, for wall-clock timelocks to be tested independently of
+/// slot-denominated ones
+pub fn set_unix_timestamp(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = clock(svm);
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+/// Advances the unix timestamp alone by `seconds`
+pub fn advance_seconds(svm: &mut LiteSVM, seconds: i64) {
+    let unix_timestamp = clock(svm).unix_timestamp;
+    set_unix_timestamp(svm, unix_timestamp + seconds);
+}
@@ -0,0 +1,483 @@
+//! A LiteSVM harness for integration tests of the swap programs, requiring neither a validator
+//! nor the TypeScript toolchain.
+//!
+//! The programs are loaded from `target/deploy`, as built by `anchor build` or
+//! `cargo build-sbf --manifest-path programs/solana-native-swaps/Cargo.toml`, and
+//! `cargo build-sbf --manifest-path native/solana-native-swaps-lite/Cargo.toml` respectively.
+//! Set `SWAPS_PROGRAM_SO` or `SWAPS_LITE_PROGRAM_SO` to test a build elsewhere instead.
+//!
+//! The programs cNFT swaps depend on are loaded from `target/deploy` too, as dumped from
+//! mainnet with `solana program dump -u m <program id> target/deploy/<file>`: Bubblegum as
+//! `mpl_bubblegum.so`, the account compression program as `spl_account_compression.so` and the
+//! noop program as `spl_noop.so`, or from `BUBBLEGUM_PROGRAM_SO`,
+//! `ACCOUNT_COMPRESSION_PROGRAM_SO` and `NOOP_PROGRAM_SO` respectively.
+
+pub mod clock;
+
+use std::path::PathBuf;
+
+use anchor_lang::{
+    error::ERROR_CODE_OFFSET,
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{
+        hash::hash, instruction::InstructionError, program_pack::Pack, system_instruction,
+        system_program,
+    },
+    Discriminator, Space,
+};
+use anchor_spl::token::spl_token::{
+    self,
+    state::{Account as TokenAccount, Mint},
+};
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_native_swaps::{
+    SwapError, ACCOUNT_COMPRESSION_PROGRAM_ID, BUBBLEGUM_PROGRAM_ID, MAX_EXPIRY_SLOTS,
+    NOOP_PROGRAM_ID,
+};
+use solana_native_swaps_client::{
+    decode_swap_account, pda, InitiateBuilder, InstantRefundBuilder, Instruction, RedeemBuilder,
+    RefundBuilder, SwapAccount,
+};
+use solana_native_swaps_lite::SwapInstruction;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+/// The lamports each party is funded with
+pub const PARTY_LAMPORTS: u64 = 100_000_000_000;
+/// The protocol fee configured for the Anchor program, non-zero for redeems to exercise the
+/// transfer to the treasury
+pub const PROTOCOL_FEE_BPS: u16 = 10;
+
+/// A program implementing the swap lifecycle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Program {
+    /// The Anchor program under `programs/`
+    Anchor,
+    /// The raw Solana program under `native/`
+    Lite,
+}
+
+impl Program {
+    pub const ALL: [Program; 2] = [Program::Anchor, Program::Lite];
+
+    pub fn id(&self) -> Pubkey {
+        match self {
+            Program::Anchor => solana_native_swaps::ID,
+            Program::Lite => solana_native_swaps_lite::ID,
+        }
+    }
+
+    /// The path of the build of this program
+    pub fn path(&self) -> PathBuf {
+        let (env, file) = match self {
+            Program::Anchor => ("SWAPS_PROGRAM_SO", "solana_native_swaps.so"),
+            Program::Lite => ("SWAPS_LITE_PROGRAM_SO", "solana_native_swaps_lite.so"),
+        };
+        build_path(env, file)
+    }
+
+    /// The protocol fee deducted upon redeeming `amount_lamports`, which only the Anchor
+    /// program charges
+    pub fn protocol_fee(&self, amount_lamports: u64) -> u64 {
+        match self {
+            Program::Anchor => {
+                (u128::from(amount_lamports) * u128::from(PROTOCOL_FEE_BPS) / 10_000) as u64
+            }
+            Program::Lite => 0,
+        }
+    }
+
+    /// The size of a swap account of this program
+    fn swap_account_size(&self) -> usize {
+        match self {
+            Program::Anchor => SwapAccount::DISCRIMINATOR.len() + SwapAccount::INIT_SPACE,
+            Program::Lite => solana_native_swaps_lite::SwapAccount::LEN,
+        }
+    }
+}
+
+/// A LiteSVM instance with a swap program deployed and configured, and funded swap parties.
+/// Transaction fees are paid by a separate payer, so that the balances of the parties only
+/// reflect the swaps.
+pub struct SwapTest {
+    pub svm: LiteSVM,
+    pub program: Program,
+    pub payer: Keypair,
+    pub initiator: Keypair,
+    pub redeemer: Keypair,
+}
+
+impl SwapTest {
+    /// A test of the Anchor program
+    pub fn new() -> Result<Self, String> {
+        Self::with_program(Program::Anchor)
+    }
+
+    pub fn with_program(program: Program) -> Result<Self, String> {
+        let path = program.path();
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(program.id(), &path)
+            .map_err(|e| format!("Failed to load {}: {e}", path.display()))?;
+
+        let (payer, initiator, redeemer) = (Keypair::new(), Keypair::new(), Keypair::new());
+        for party in [&payer, &initiator, &redeemer] {
+            svm.airdrop(&party.pubkey(), PARTY_LAMPORTS)
+                .map_err(|e| format!("Airdrop failed: {:?}", e.err))?;
+        }
+        if program == Program::Anchor {
+            set_up_config(&mut svm, &payer.pubkey())?;
+        }
+
+        Ok(SwapTest {
+            svm,
+            program,
+            payer,
+            initiator,
+            redeemer,
+        })
+    }
+
+    /// Loads the example program composing the Anchor program through CPI, from
+    /// `target/deploy` or `SWAPS_COMPOSER_PROGRAM_SO`
+    pub fn add_composer(&mut self) -> Result<(), String> {
+        let path = build_path(
+            "SWAPS_COMPOSER_PROGRAM_SO",
+            "solana_native_swaps_composer.so",
+        );
+        self.svm
+            .add_program_from_file(solana_native_swaps_composer::ID, &path)
+            .map_err(|e| format!("Failed to load {}: {e}", path.display()))
+    }
+
+    /// Loads Bubblegum along with the account compression and noop programs it depends on, as
+    /// dumped from mainnet to `target/deploy` or to the paths in the environment
+    pub fn add_bubblegum(&mut self) -> Result<(), String> {
+        for (program_id, env, file) in [
+            (
+                BUBBLEGUM_PROGRAM_ID,
+                "BUBBLEGUM_PROGRAM_SO",
+                "mpl_bubblegum.so",
+            ),
+            (
+                ACCOUNT_COMPRESSION_PROGRAM_ID,
+                "ACCOUNT_COMPRESSION_PROGRAM_SO",
+                "spl_account_compression.so",
+            ),
+            (NOOP_PROGRAM_ID, "NOOP_PROGRAM_SO", "spl_noop.so"),
+        ] {
+            let path = build_path(env, file);
+            self.svm
+                .add_program_from_file(program_id, &path)
+                .map_err(|e| format!("Failed to load {}: {e}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Sends `instructions` in a transaction signed by the payer and `signers`
+    #[allow(clippy::result_large_err)]
+    pub fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let signers = [&[&self.payer][..], signers].concat();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(transaction);
+        // Allows identical transactions to be sent again
+        self.svm.expire_blockhash();
+        result
+    }
+
+    /// Initiates a swap of `amount_lamports` from the initiator to the redeemer, locked to
+    /// `secret`, returning the address of its swap account
+    pub fn initiate(
+        &mut self,
+        secret: &[u8; 32],
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    ) -> Result<Pubkey, String> {
+        let secret_hash = secret_hash(secret);
+        let initiate = self.initiate_instruction(&secret_hash, amount_lamports, expires_in_slots);
+        let initiator = self.initiator.insecure_clone();
+        self.send(&[initiate], &[&initiator]).map_err(|failed| {
+            format!(
+                "initiate failed: {}\n{}",
+                failed.err,
+                failed.meta.pretty_logs()
+            )
+        })?;
+        Ok(self.swap_account(&secret_hash))
+    }
+
+    /// The address of the swap account from the initiator to the redeemer locked to
+    /// `secret_hash`
+    pub fn swap_account(&self, secret_hash: &[u8; 32]) -> Pubkey {
+        let (initiator, redeemer) = (self.initiator.pubkey(), self.redeemer.pubkey());
+        match self.program {
+            Program::Anchor => pda::swap_account(&initiator, &redeemer, secret_hash).0,
+            Program::Lite => {
+                solana_native_swaps_lite::find_swap_account(&initiator, &redeemer, secret_hash).0
+            }
+        }
+    }
+
+    /// The `initiate` instruction of a swap from the initiator to the redeemer
+    pub fn initiate_instruction(
+        &self,
+        secret_hash: &[u8; 32],
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    ) -> Instruction {
+        let (initiator, redeemer) = (self.initiator.pubkey(), self.redeemer.pubkey());
+        match self.program {
+            Program::Anchor => InitiateBuilder::new(
+                initiator,
+                redeemer,
+                *secret_hash,
+                amount_lamports,
+                expires_in_slots,
+            )
+            .instruction(),
+            Program::Lite => {
+                let (swap_account, bump) =
+                    solana_native_swaps_lite::find_swap_account(&initiator, &redeemer, secret_hash);
+                Instruction::new_with_bytes(
+                    solana_native_swaps_lite::ID,
+                    &SwapInstruction::Initiate {
+                        amount_lamports,
+                        expires_in_slots,
+                        redeemer,
+                        secret_hash: *secret_hash,
+                        bump,
+                    }
+                    .pack(),
+                    vec![
+                        AccountMeta::new(initiator, true),
+                        AccountMeta::new(swap_account, false),
+                        AccountMeta::new_readonly(system_program::ID, false),
+                    ],
+                )
+            }
+        }
+    }
+
+    /// The `redeem` instruction of the open swap locked to `secret_hash`, redeeming its entire
+    /// funds with `secret`
+    pub fn redeem_instruction(&self, secret_hash: &[u8; 32], secret: [u8; 32]) -> Instruction {
+        match self.program {
+            Program::Anchor => {
+                RedeemBuilder::new(&self.open_swap(secret_hash), secret).instruction()
+            }
+            Program::Lite => Instruction::new_with_bytes(
+                solana_native_swaps_lite::ID,
+                &SwapInstruction::Redeem { secret }.pack(),
+                vec![
+                    AccountMeta::new(self.swap_account(secret_hash), false),
+                    AccountMeta::new(self.redeemer.pubkey(), false),
+                    AccountMeta::new(self.initiator.pubkey(), false),
+                ],
+            ),
+        }
+    }
+
+    /// The `refund` instruction of the open swap locked to `secret_hash`
+    pub fn refund_instruction(&self, secret_hash: &[u8; 32]) -> Instruction {
+        match self.program {
+            Program::Anchor => RefundBuilder::new(&self.open_swap(secret_hash)).instruction(),
+            Program::Lite => Instruction::new_with_bytes(
+                solana_native_swaps_lite::ID,
+                &SwapInstruction::Refund.pack(),
+                vec![
+                    AccountMeta::new(self.swap_account(secret_hash), false),
+                    AccountMeta::new(self.initiator.pubkey(), false),
+                ],
+            ),
+        }
+    }
+
+    /// The `instant_refund` instruction of the open swap locked to `secret_hash`, which the
+    /// redeemer must sign
+    pub fn instant_refund_instruction(&self, secret_hash: &[u8; 32]) -> Instruction {
+        match self.program {
+            Program::Anchor => {
+                InstantRefundBuilder::new(&self.open_swap(secret_hash)).instruction()
+            }
+            Program::Lite => Instruction::new_with_bytes(
+                solana_native_swaps_lite::ID,
+                &SwapInstruction::InstantRefund.pack(),
+                vec![
+                    AccountMeta::new(self.swap_account(secret_hash), false),
+                    AccountMeta::new(self.initiator.pubkey(), false),
+                    AccountMeta::new_readonly(self.redeemer.pubkey(), true),
+                ],
+            ),
+        }
+    }
+
+    /// The swap account of the Anchor program at `address`, if open
+    pub fn swap(&self, address: &Pubkey) -> Option<SwapAccount> {
+        let account = self.svm.get_account(address)?;
+        decode_swap_account(&account.data).ok()
+    }
+
+    fn open_swap(&self, secret_hash: &[u8; 32]) -> SwapAccount {
+        self.swap(&self.swap_account(secret_hash))
+            .expect("swap is open")
+    }
+
+    /// The balance of `address`, zero if it does not exist
+    pub fn balance(&self, address: &Pubkey) -> u64 {
+        self.svm.get_balance(address).unwrap_or_default()
+    }
+
+    pub fn slot(&self) -> u64 {
+        clock::clock(&self.svm).slot
+    }
+
+    /// Advances the clock by `slots` slots, see [`clock::advance_slots`]
+    pub fn warp(&mut self, slots: u64) {
+        clock::advance_slots(&mut self.svm, slots);
+    }
+
+    /// The rent of a swap account, returned to the initiator once the swap completes
+    pub fn swap_rent(&self) -> u64 {
+        self.svm
+            .minimum_balance_for_rent_exemption(self.program.swap_account_size())
+    }
+
+    /// Creates an SPL token mint with `decimals` and the payer as its mint authority
+    pub fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer.pubkey();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                self.svm.minimum_balance_for_rent_exemption(Mint::LEN),
+                Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &payer,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ];
+        self.send(&instructions, &[&mint]).unwrap();
+        mint.pubkey()
+    }
+
+    /// Creates a token account of `mint` owned by `owner`
+    pub fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let instructions = [
+            system_instruction::create_account(
+                &self.payer.pubkey(),
+                &account.pubkey(),
+                self.svm
+                    .minimum_balance_for_rent_exemption(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::ID,
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap(),
+        ];
+        self.send(&instructions, &[&account]).unwrap();
+        account.pubkey()
+    }
+
+    /// Mints `amount` of `mint`, created by [`Self::create_mint`], to `token_account`
+    pub fn mint_to(&mut self, mint: &Pubkey, token_account: &Pubkey, amount: u64) {
+        let mint_to = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            mint,
+            token_account,
+            &self.payer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.send(&[mint_to], &[]).unwrap();
+    }
+
+    /// The balance of the token account at `address`
+    pub fn token_balance(&self, address: &Pubkey) -> u64 {
+        let account = self.svm.get_account(address).unwrap();
+        TokenAccount::unpack(&account.data).unwrap().amount
+    }
+}
+
+/// The path of the build of a program, `file` under `target/deploy` unless overridden by the
+/// environment variable `env`
+fn build_path(env: &str, file: &str) -> PathBuf {
+    std::env::var_os(env).map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/deploy")
+            .join(file)
+    })
+}
+
+/// The SHA-256 secret hash of `secret`
+pub fn secret_hash(secret: &[u8; 32]) -> [u8; 32] {
+    hash(secret).to_bytes()
+}
+
+/// Asserts that `result` failed with `error` of the Anchor program
+pub fn assert_swap_error(result: TransactionResult, error: SwapError) {
+    let code = ERROR_CODE_OFFSET + error as u32;
+    match result {
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::Custom(code)),
+            "{}",
+            failed.meta.pretty_logs()
+        ),
+        Ok(_) => panic!("expected the transaction to fail with {code}"),
+    }
+}
+
+/// Writes the config and treasury PDAs directly, as `initialize_config` requires the program
+/// to be deployed through the upgradeable loader
+fn set_up_config(svm: &mut LiteSVM, admin: &Pubkey) -> Result<(), String> {
+    let config = [
+        solana_native_swaps::Config::DISCRIMINATOR,
+        admin.as_ref(),
+        &1u64.to_le_bytes(),
+        &MAX_EXPIRY_SLOTS.to_le_bytes(),
+        &PROTOCOL_FEE_BPS.to_le_bytes(),
+        &[false as u8],
+        // No transfer hook programs are permitted
+        &0u32.to_le_bytes(),
+    ]
+    .concat();
+    let treasury = solana_native_swaps::Treasury::DISCRIMINATOR.to_vec();
+
+    for (address, data) in [(pda::config().0, config), (pda::treasury().0, treasury)] {
+        let account = Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: solana_native_swaps::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(address, account)
+            .map_err(|e| format!("Failed to set {address}: {e}"))?;
+    }
+    Ok(())
+}
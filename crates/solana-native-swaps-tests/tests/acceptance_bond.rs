@@ -0,0 +1,149 @@
+//! Swaps the redeemer must accept by posting a bond, forfeited to the refund destination upon
+//! refund
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_native_swaps::{
+    accounts, instruction, HashAlgorithm, RedeemWindow, SwapError, SwapIncentives,
+};
+use solana_native_swaps_client::{pda, InitiateBuilder, Instruction, RedeemBuilder, RefundBuilder};
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+const BOND_LAMPORTS: u64 = 10_000_000;
+
+/// Initiates a swap locked to `SECRET` requiring a bond of `BOND_LAMPORTS`, refunded to
+/// `refund_to` if any, returning the address of its swap account
+fn initiate(test: &mut SwapTest, refund_to: Option<Pubkey>) -> Pubkey {
+    let redeemer = test.redeemer.pubkey();
+    let mut builder = InitiateBuilder::new(
+        test.initiator.pubkey(),
+        redeemer,
+        secret_hash(&SECRET),
+        AMOUNT_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    );
+    if let Some(refund_to) = refund_to {
+        builder = builder.refund_to(refund_to);
+    }
+    let mut initiate = builder.instruction();
+    // Shares the accounts of `initiate`
+    initiate.data = instruction::InitiateWithAcceptanceBond {
+        amount_lamports: AMOUNT_LAMPORTS,
+        expires_in_slots: EXPIRES_IN_SLOTS,
+        redeemer,
+        secret_hash: secret_hash(&SECRET),
+        hash_algorithm: HashAlgorithm::Sha256,
+        incentives: SwapIncentives::default(),
+        redeem_window: RedeemWindow::default(),
+        acceptance_bond_lamports: BOND_LAMPORTS,
+    }
+    .data();
+    let initiator = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&initiator]).unwrap();
+    test.swap_account(&secret_hash(&SECRET))
+}
+
+fn accept_instruction(test: &SwapTest, swap_account: Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::Accept {}.data(),
+        accounts::Accept {
+            swap_account,
+            redeemer: test.redeemer.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn accepted_swaps_return_the_bond_upon_redeem() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = initiate(&mut test, None);
+    let redeemer = test.redeemer.insecure_clone();
+    let before = test.balance(&redeemer.pubkey());
+
+    let redeem = RedeemBuilder::new(&test.swap(&swap_account).unwrap(), SECRET).instruction();
+    assert_swap_error(
+        test.send(std::slice::from_ref(&redeem), &[]),
+        SwapError::SwapNotAccepted,
+    );
+    let accept = accept_instruction(&test, swap_account);
+    test.send(std::slice::from_ref(&accept), &[&redeemer])
+        .unwrap();
+    assert_eq!(test.balance(&redeemer.pubkey()), before - BOND_LAMPORTS);
+    assert_swap_error(
+        test.send(&[accept], &[&redeemer]),
+        SwapError::SwapAlreadyAccepted,
+    );
+
+    test.send(&[redeem], &[]).unwrap();
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(
+        test.balance(&redeemer.pubkey()),
+        before + AMOUNT_LAMPORTS - test.program.protocol_fee(AMOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn bonds_are_forfeited_to_the_initiator_upon_refund() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = initiate(&mut test, None);
+    let redeemer = test.redeemer.insecure_clone();
+    let accept = accept_instruction(&test, swap_account);
+    test.send(&[accept], &[&redeemer]).unwrap();
+
+    test.warp(EXPIRES_IN_SLOTS);
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    let refund = RefundBuilder::new(&test.swap(&swap_account).unwrap()).instruction();
+    test.send(&[refund], &[]).unwrap();
+
+    assert_eq!(
+        test.balance(&initiator),
+        before + AMOUNT_LAMPORTS + BOND_LAMPORTS + test.swap_rent()
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn bonds_are_forfeited_to_the_refund_destination_if_any() {
+    let mut test = SwapTest::new().unwrap();
+    let refund_to = Pubkey::new_unique();
+    let swap_account = initiate(&mut test, Some(refund_to));
+    let redeemer = test.redeemer.insecure_clone();
+    let accept = accept_instruction(&test, swap_account);
+    test.send(&[accept], &[&redeemer]).unwrap();
+
+    test.warp(EXPIRES_IN_SLOTS);
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    let refund = RefundBuilder::new(&test.swap(&swap_account).unwrap()).instruction();
+    test.send(&[refund], &[]).unwrap();
+
+    // The rent of the swap account alone returns to the initiator, who paid it
+    assert_eq!(test.balance(&refund_to), AMOUNT_LAMPORTS + BOND_LAMPORTS);
+    assert_eq!(test.balance(&initiator), before + test.swap_rent());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn expired_swaps_cannot_be_accepted() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = initiate(&mut test, None);
+    let redeemer = test.redeemer.insecure_clone();
+
+    test.warp(EXPIRES_IN_SLOTS);
+    let accept = accept_instruction(&test, swap_account);
+    assert_swap_error(
+        test.send(&[accept], &[&redeemer]),
+        SwapError::AcceptAfterExpiry,
+    );
+}
@@ -0,0 +1,117 @@
+//! Swaps redeemable with the secret of any one of several secret hashes
+
+use anchor_lang::InstructionData;
+use solana_native_swaps::{
+    any_secret_hash, combined_secret_hash, instruction, HashAlgorithm, RedeemWindow, SwapError,
+    SwapIncentives,
+};
+use solana_native_swaps_client::{pda, InitiateBuilder, Instruction, RedeemBuilder};
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRETS: [[u8; 32]; 3] = [[7; 32], [8; 32], [9; 32]];
+
+fn secret_hashes() -> Vec<[u8; 32]> {
+    SECRETS.map(|secret| secret_hash(&secret)).to_vec()
+}
+
+fn combined() -> [u8; 32] {
+    any_secret_hash(&secret_hashes())
+}
+
+/// The `initiate_any_secret` instruction committing to the secret hashes of `SECRETS`, with
+/// `commitment` as their commitment
+fn initiate_instruction(test: &SwapTest, commitment: [u8; 32]) -> Instruction {
+    let redeemer = test.redeemer.pubkey();
+    let mut initiate = InitiateBuilder::new(
+        test.initiator.pubkey(),
+        redeemer,
+        commitment,
+        AMOUNT_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    )
+    .instruction();
+    // Shares the accounts of `initiate`
+    initiate.data = instruction::InitiateAnySecret {
+        amount_lamports: AMOUNT_LAMPORTS,
+        expires_in_slots: EXPIRES_IN_SLOTS,
+        redeemer,
+        secret_hash: commitment,
+        hash_algorithm: HashAlgorithm::Sha256,
+        incentives: SwapIncentives::default(),
+        redeem_window: RedeemWindow::default(),
+        secret_hashes: secret_hashes(),
+    }
+    .data();
+    initiate
+}
+
+fn redeem_instruction(
+    test: &SwapTest,
+    secret: [u8; 32],
+    secret_hashes: Vec<[u8; 32]>,
+) -> Instruction {
+    let swap_account = pda::swap_account(
+        &test.initiator.pubkey(),
+        &test.redeemer.pubkey(),
+        &combined(),
+    )
+    .0;
+    let swap = test.swap(&swap_account).unwrap();
+    // Shares the accounts of `redeem`
+    let mut redeem = RedeemBuilder::new(&swap, secret).instruction();
+    redeem.data = instruction::RedeemAnySecret {
+        secret,
+        secret_hashes,
+        amount_lamports: AMOUNT_LAMPORTS,
+    }
+    .data();
+    redeem
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeems_accept_any_committed_secret() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let initiate = initiate_instruction(&test, combined());
+    test.send(&[initiate], &[&initiator]).unwrap();
+    let redeemer = test.redeemer.pubkey();
+    let before = test.balance(&redeemer);
+
+    assert_swap_error(
+        test.send(&[redeem_instruction(&test, [0; 32], secret_hashes())], &[]),
+        SwapError::InvalidSecret,
+    );
+    // Substituting a committed secret hash for one of the attacker's choosing
+    let mut forged = secret_hashes();
+    forged[0] = secret_hash(&[0; 32]);
+    assert_swap_error(
+        test.send(&[redeem_instruction(&test, [0; 32], forged)], &[]),
+        SwapError::InvalidSecret,
+    );
+    test.send(
+        &[redeem_instruction(&test, SECRETS[1], secret_hashes())],
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(
+        test.balance(&redeemer),
+        before + AMOUNT_LAMPORTS - test.program.protocol_fee(AMOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn initiates_must_commit_to_the_secret_hashes() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let initiate = initiate_instruction(&test, combined_secret_hash(&secret_hashes()));
+    assert_swap_error(
+        test.send(&[initiate], &[&initiator]),
+        SwapError::InvalidCombinedSecretHash,
+    );
+}
@@ -0,0 +1,127 @@
+//! Redeems of token swaps to the redeemer's associated token account, created if need be
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use anchor_spl::{
+    associated_token::{
+        self, get_associated_token_address,
+        spl_associated_token_account::instruction::create_associated_token_account,
+    },
+    token::spl_token,
+};
+use solana_native_swaps::{accounts, instruction, pda};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT: u64 = 5_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// Initiates a token swap of a fresh mint, returning the mint
+fn initiate_token(test: &mut SwapTest) -> Pubkey {
+    let initiator = test.initiator.insecure_clone();
+    let mint = test.create_mint(6);
+    let initiator_token_account = test.create_token_account(&mint, &initiator.pubkey());
+    test.mint_to(&mint, &initiator_token_account, AMOUNT);
+
+    let swap_account = swap_account(test);
+    let initiate = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::InitiateToken {
+            amount: AMOUNT,
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+        }
+        .data(),
+        accounts::InitiateToken {
+            swap_account,
+            token_vault: token_vault(&swap_account),
+            initiator_token_account,
+            mint,
+            config: pda::config().0,
+            initiator: initiator.pubkey(),
+            token_program: spl_token::ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    test.send(&[initiate], &[&initiator]).unwrap();
+    mint
+}
+
+fn swap_account(test: &SwapTest) -> Pubkey {
+    pda::token_swap_account(
+        &test.initiator.pubkey(),
+        &test.redeemer.pubkey(),
+        &secret_hash(&SECRET),
+    )
+    .0
+}
+
+fn token_vault(swap_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"token_vault", swap_account.as_ref()],
+        &solana_native_swaps::ID,
+    )
+    .0
+}
+
+fn redeem_instruction(test: &SwapTest, mint: &Pubkey) -> Instruction {
+    let swap_account = swap_account(test);
+    let redeemer = test.redeemer.pubkey();
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RedeemTokenToAta { secret: SECRET }.data(),
+        accounts::RedeemTokenToAta {
+            swap_account,
+            token_vault: token_vault(&swap_account),
+            redeemer_token_account: get_associated_token_address(&redeemer, mint),
+            mint: *mint,
+            initiator: test.initiator.pubkey(),
+            redeemer,
+            payer: test.payer.pubkey(),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeems_create_the_associated_token_account_of_fresh_wallets() {
+    let mut test = SwapTest::new().unwrap();
+    let mint = initiate_token(&mut test);
+    let ata = get_associated_token_address(&test.redeemer.pubkey(), &mint);
+    assert!(test.svm.get_account(&ata).is_none());
+
+    test.send(&[redeem_instruction(&test, &mint)], &[]).unwrap();
+
+    assert_eq!(test.token_balance(&ata), AMOUNT);
+    assert!(test.svm.get_account(&swap_account(&test)).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeems_reuse_an_existing_associated_token_account() {
+    let mut test = SwapTest::new().unwrap();
+    let mint = initiate_token(&mut test);
+    let redeemer = test.redeemer.pubkey();
+    let create_ata =
+        create_associated_token_account(&test.payer.pubkey(), &redeemer, &mint, &spl_token::ID);
+    test.send(&[create_ata], &[]).unwrap();
+    let ata = get_associated_token_address(&redeemer, &mint);
+    let payer_before = test.balance(&test.payer.pubkey());
+
+    test.send(&[redeem_instruction(&test, &mint)], &[]).unwrap();
+
+    assert_eq!(test.token_balance(&ata), AMOUNT);
+    // The payer only pays the transaction fee
+    assert_eq!(test.balance(&test.payer.pubkey()), payer_before - 5_000);
+}
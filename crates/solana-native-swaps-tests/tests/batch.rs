@@ -0,0 +1,111 @@
+//! Several swaps initiated in a single `initiate_batch` instruction
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    InstructionData, ToAccountMetas,
+};
+use solana_native_swaps::{
+    accounts, instruction, pda, BatchedSwap, HashAlgorithm, RedeemWindow, SwapIncentives,
+};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRETS: [[u8; 32]; 3] = [[7; 32], [8; 32], [9; 32]];
+
+/// Initiates a swap of `AMOUNT_LAMPORTS` to the redeemer per secret of `SECRETS`, returning
+/// their swap accounts along with the instruction
+fn initiate_batch(test: &SwapTest) -> (Vec<Pubkey>, Instruction) {
+    let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+    let swaps: Vec<_> = SECRETS
+        .iter()
+        .map(|secret| BatchedSwap {
+            amount_lamports: AMOUNT_LAMPORTS,
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer,
+            secret_hash: secret_hash(secret),
+            hash_algorithm: HashAlgorithm::Sha256,
+            incentives: SwapIncentives::default(),
+            refund_to: None,
+            redeem_window: RedeemWindow::default(),
+        })
+        .collect();
+    let swap_accounts: Vec<_> = swaps
+        .iter()
+        .map(|swap| pda::swap_account(&initiator, &redeemer, &swap.secret_hash).0)
+        .collect();
+    let mut account_metas = accounts::InitiateBatch {
+        config: pda::config().0,
+        initiator,
+        system_program: anchor_lang::system_program::ID,
+        event_authority: pda::event_authority().0,
+        program: solana_native_swaps::ID,
+    }
+    .to_account_metas(None);
+    account_metas.extend(
+        swap_accounts
+            .iter()
+            .map(|swap_account| AccountMeta::new(*swap_account, false)),
+    );
+    let instruction = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::InitiateBatch { swaps }.data(),
+        account_metas,
+    );
+    (swap_accounts, instruction)
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn batch_initiates_every_swap() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+
+    let (swap_accounts, initiate) = initiate_batch(&test);
+    let signer = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&signer]).unwrap();
+
+    for (swap_account, secret) in swap_accounts.iter().zip(SECRETS) {
+        let swap = test.swap(swap_account).unwrap();
+        assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+        assert_eq!(swap.secret_hash, secret_hash(&secret));
+        assert_eq!(
+            test.balance(swap_account),
+            test.swap_rent() + AMOUNT_LAMPORTS
+        );
+    }
+    assert_eq!(
+        test.balance(&initiator),
+        before - SECRETS.len() as u64 * (test.swap_rent() + AMOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn lamports_sent_to_a_swap_address_ahead_of_the_batch_count_towards_it() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+    let (swap_accounts, initiate) = initiate_batch(&test);
+    // A single lamport would otherwise fail the creation of the account, and with it the batch
+    test.svm.airdrop(&swap_accounts[1], 1).unwrap();
+    let before = test.balance(&initiator);
+
+    let signer = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&signer]).unwrap();
+
+    for swap_account in &swap_accounts {
+        let swap = test.swap(swap_account).unwrap();
+        assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+        assert_eq!(
+            test.balance(swap_account),
+            test.swap_rent() + AMOUNT_LAMPORTS
+        );
+    }
+    assert_eq!(
+        test.balance(&initiator),
+        before - SECRETS.len() as u64 * (test.swap_rent() + AMOUNT_LAMPORTS) + 1
+    );
+}
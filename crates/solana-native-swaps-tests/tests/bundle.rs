@@ -0,0 +1,214 @@
+//! Bundle swaps, escrowing native SOL along with SPL tokens under a single secret hash
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::token::spl_token;
+use solana_native_swaps::{accounts, instruction, pda, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const TOKEN_AMOUNTS: [u64; 2] = [5_000_000, 7_000_000];
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// A token of a bundle, with the token accounts of both parties and the swap's vault
+struct Token {
+    mint: Pubkey,
+    initiator_account: Pubkey,
+    redeemer_account: Pubkey,
+    vault: Pubkey,
+}
+
+struct Bundle {
+    test: SwapTest,
+    swap_account: Pubkey,
+    tokens: Vec<Token>,
+}
+
+impl Bundle {
+    fn new() -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let swap_account = Pubkey::find_program_address(
+            &[
+                b"bundle_swap_account",
+                test.initiator.pubkey().as_ref(),
+                test.redeemer.pubkey().as_ref(),
+                &secret_hash(&SECRET),
+            ],
+            &solana_native_swaps::ID,
+        )
+        .0;
+
+        let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+        let tokens = TOKEN_AMOUNTS
+            .iter()
+            .map(|&amount| {
+                let mint = test.create_mint(6);
+                let initiator_account = test.create_token_account(&mint, &initiator);
+                test.mint_to(&mint, &initiator_account, amount);
+                Token {
+                    mint,
+                    initiator_account,
+                    redeemer_account: test.create_token_account(&mint, &redeemer),
+                    vault: test.create_token_account(&mint, &swap_account),
+                }
+            })
+            .collect();
+
+        Bundle {
+            test,
+            swap_account,
+            tokens,
+        }
+    }
+
+    fn initiate(&mut self) {
+        let mut accounts = accounts::InitiateBundle {
+            swap_account: self.swap_account,
+            config: pda::config().0,
+            initiator: self.test.initiator.pubkey(),
+            token_program: spl_token::ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None);
+        for token in &self.tokens {
+            accounts.extend([
+                AccountMeta::new_readonly(token.mint, false),
+                AccountMeta::new(token.initiator_account, false),
+                AccountMeta::new(token.vault, false),
+            ]);
+        }
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateBundle {
+                amount_lamports: AMOUNT_LAMPORTS,
+                token_amounts: TOKEN_AMOUNTS.to_vec(),
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer: self.test.redeemer.pubkey(),
+                secret_hash: secret_hash(&SECRET),
+            }
+            .data(),
+            accounts,
+        );
+        let initiator = self.test.initiator.insecure_clone();
+        self.test.send(&[initiate], &[&initiator]).unwrap();
+    }
+
+    /// The accounts of each token, releasing them to the token accounts chosen by `recipient`
+    fn release_accounts(&self, recipient: impl Fn(&Token) -> Pubkey) -> Vec<AccountMeta> {
+        self.tokens
+            .iter()
+            .flat_map(|token| {
+                [
+                    AccountMeta::new(token.mint, false),
+                    AccountMeta::new(token.vault, false),
+                    AccountMeta::new(recipient(token), false),
+                ]
+            })
+            .collect()
+    }
+
+    fn redeem_instruction(&self, secret: [u8; 32]) -> Instruction {
+        let mut accounts = accounts::RedeemBundle {
+            swap_account: self.swap_account,
+            initiator: self.test.initiator.pubkey(),
+            redeemer: self.test.redeemer.pubkey(),
+            token_program: spl_token::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(self.release_accounts(|token| token.redeemer_account));
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemBundle { secret }.data(),
+            accounts,
+        )
+    }
+
+    fn refund_instruction(&self) -> Instruction {
+        let mut accounts = accounts::RefundBundle {
+            swap_account: self.swap_account,
+            initiator: self.test.initiator.pubkey(),
+            token_program: spl_token::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(self.release_accounts(|token| token.initiator_account));
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundBundle {}.data(),
+            accounts,
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn bundles_are_redeemed_together() {
+    let mut bundle = Bundle::new();
+    bundle.initiate();
+    let redeemer = bundle.test.redeemer.pubkey();
+    let before = bundle.test.balance(&redeemer);
+
+    assert_swap_error(
+        bundle.test.send(&[bundle.redeem_instruction([0; 32])], &[]),
+        SwapError::InvalidSecret,
+    );
+    bundle
+        .test
+        .send(&[bundle.redeem_instruction(SECRET)], &[])
+        .unwrap();
+
+    assert_eq!(bundle.test.balance(&redeemer), before + AMOUNT_LAMPORTS);
+    for (token, amount) in bundle.tokens.iter().zip(TOKEN_AMOUNTS) {
+        assert_eq!(bundle.test.token_balance(&token.redeemer_account), amount);
+        assert!(bundle.test.svm.get_account(&token.vault).is_none());
+    }
+    assert!(bundle.test.svm.get_account(&bundle.swap_account).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn bundles_are_refunded_together_past_expiry() {
+    let mut bundle = Bundle::new();
+    bundle.initiate();
+
+    assert_swap_error(
+        bundle.test.send(&[bundle.refund_instruction()], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+    bundle.test.warp(EXPIRES_IN_SLOTS);
+    bundle
+        .test
+        .send(&[bundle.refund_instruction()], &[])
+        .unwrap();
+
+    for (token, amount) in bundle.tokens.iter().zip(TOKEN_AMOUNTS) {
+        assert_eq!(bundle.test.token_balance(&token.initiator_account), amount);
+    }
+    assert!(bundle.test.svm.get_account(&bundle.swap_account).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn bundles_are_only_released_to_the_recipient() {
+    let mut bundle = Bundle::new();
+    bundle.initiate();
+
+    let mut redeem = bundle.redeem_instruction(SECRET);
+    // Directs the first token to the initiator's token account in place of the redeemer's
+    let offset = redeem.accounts.len() - 3 * bundle.tokens.len();
+    redeem.accounts[offset + 2].pubkey = bundle.tokens[0].initiator_account;
+    assert_swap_error(
+        bundle.test.send(&[redeem], &[]),
+        SwapError::InvalidBundleDestination,
+    );
+}
@@ -0,0 +1,310 @@
+//! cNFT swaps, escrowing the leaf of a compressed NFT by transferring it to the swap's PDA
+//! through Bubblegum, against a tree and a cNFT created through Bubblegum itself
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{keccak, system_instruction},
+    InstructionData, ToAccountMetas,
+};
+use solana_keypair::Keypair;
+use solana_native_swaps::{
+    accounts, instruction, pda, CompressedLeaf, SwapError, ACCOUNT_COMPRESSION_PROGRAM_ID,
+    BUBBLEGUM_PROGRAM_ID, NOOP_PROGRAM_ID,
+};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// The smallest tree the account compression program supports
+const MAX_DEPTH: usize = 3;
+const MAX_BUFFER_SIZE: usize = 8;
+/// The size of the header of a tree account, preceding the tree itself
+const TREE_HEADER_SIZE: usize = 56;
+/// The size of each change log of a tree, being its root, the path of the changed leaf, and
+/// the leaf's index padded to 8 bytes
+const CHANGE_LOG_SIZE: usize = 32 + 32 * MAX_DEPTH + 8;
+/// The size of a tree account without a canopy: the header, the sequence number, the active
+/// index and the size of the buffer, the change logs, and the rightmost proof
+const TREE_SIZE: usize =
+    TREE_HEADER_SIZE + 24 + MAX_BUFFER_SIZE * CHANGE_LOG_SIZE + 32 * MAX_DEPTH + 40;
+
+const CREATE_TREE_DISCRIMINATOR: [u8; 8] = [165, 83, 136, 142, 89, 202, 47, 220];
+const MINT_V1_DISCRIMINATOR: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+
+/// A cNFT minted to the initiator in a tree of its own
+struct Cnft {
+    merkle_tree: Pubkey,
+    leaf: CompressedLeaf,
+    asset_id: Pubkey,
+}
+
+/// The Borsh serialization of Bubblegum's `MetadataArgs` of the cNFT, being a non-fungible
+/// without a collection, uses or creators
+fn metadata_args() -> Vec<u8> {
+    let mut metadata = vec![];
+    for field in ["Swapped cNFT", "SWAP", "https://example.com/cnft.json"] {
+        metadata.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        metadata.extend_from_slice(field.as_bytes());
+    }
+    // No seller fee, no primary sale, mutable, no edition nonce
+    metadata.extend_from_slice(&0u16.to_le_bytes());
+    metadata.extend_from_slice(&[0, 1, 0]);
+    // A non-fungible token standard, no collection, no uses, the original token program
+    metadata.extend_from_slice(&[1, 0, 0, 0, 0]);
+    // No creators
+    metadata.extend_from_slice(&0u32.to_le_bytes());
+    metadata
+}
+
+/// Creates a tree through Bubblegum with the payer as its creator, then mints a cNFT to the
+/// initiator in it
+fn mint_cnft(test: &mut SwapTest) -> Cnft {
+    test.add_bubblegum().unwrap();
+    let (payer, merkle_tree) = (test.payer.pubkey(), Keypair::new());
+    let tree_authority = pda::cnft_tree_authority(&merkle_tree.pubkey()).0;
+
+    let mut create_tree_data = CREATE_TREE_DISCRIMINATOR.to_vec();
+    create_tree_data.extend_from_slice(&(MAX_DEPTH as u32).to_le_bytes());
+    create_tree_data.extend_from_slice(&(MAX_BUFFER_SIZE as u32).to_le_bytes());
+    // Not public, so only the creator may mint
+    create_tree_data.extend_from_slice(&[1, 0]);
+    let create_tree = Instruction::new_with_bytes(
+        BUBBLEGUM_PROGRAM_ID,
+        &create_tree_data,
+        vec![
+            AccountMeta::new(tree_authority, false),
+            AccountMeta::new(merkle_tree.pubkey(), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new_readonly(NOOP_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ACCOUNT_COMPRESSION_PROGRAM_ID, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+    );
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &merkle_tree.pubkey(),
+            test.svm.minimum_balance_for_rent_exemption(TREE_SIZE),
+            TREE_SIZE as u64,
+            &ACCOUNT_COMPRESSION_PROGRAM_ID,
+        ),
+        create_tree,
+    ];
+    test.send(&instructions, &[&merkle_tree]).unwrap();
+
+    let metadata = metadata_args();
+    let mint_v1 = Instruction::new_with_bytes(
+        BUBBLEGUM_PROGRAM_ID,
+        &[&MINT_V1_DISCRIMINATOR[..], &metadata].concat(),
+        vec![
+            AccountMeta::new(tree_authority, false),
+            AccountMeta::new_readonly(test.initiator.pubkey(), false),
+            AccountMeta::new_readonly(test.initiator.pubkey(), false),
+            AccountMeta::new(merkle_tree.pubkey(), false),
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new_readonly(NOOP_PROGRAM_ID, false),
+            AccountMeta::new_readonly(ACCOUNT_COMPRESSION_PROGRAM_ID, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+    );
+    test.send(&[mint_v1], &[]).unwrap();
+
+    // As hashed by Bubblegum, with the seller fee hashed again alongside the metadata
+    let data_hash =
+        keccak::hashv(&[&keccak::hash(&metadata).to_bytes(), &0u16.to_le_bytes()]).to_bytes();
+    let leaf = CompressedLeaf {
+        data_hash,
+        creator_hash: keccak::hashv(&[]).to_bytes(),
+        nonce: 0,
+        index: 0,
+    };
+    Cnft {
+        merkle_tree: merkle_tree.pubkey(),
+        asset_id: pda::cnft_asset_id(&merkle_tree.pubkey(), leaf.nonce).0,
+        leaf,
+    }
+}
+
+impl Cnft {
+    /// The root of the tree once the cNFT is owned by `owner`, along with the proof of its
+    /// leaf, being the empty nodes as no other leaves are minted
+    fn root_and_proof(&self, owner: &Pubkey) -> ([u8; 32], Vec<AccountMeta>) {
+        // Transfers reset the delegate of a leaf to its new owner
+        let leaf_hash = keccak::hashv(&[
+            &[1],
+            self.asset_id.as_ref(),
+            owner.as_ref(),
+            owner.as_ref(),
+            &self.leaf.nonce.to_le_bytes(),
+            &self.leaf.data_hash,
+            &self.leaf.creator_hash,
+        ])
+        .to_bytes();
+        let (mut node, mut empty_node, mut proof) = (leaf_hash, [0; 32], vec![]);
+        for _ in 0..MAX_DEPTH {
+            proof.push(AccountMeta::new_readonly(
+                Pubkey::new_from_array(empty_node),
+                false,
+            ));
+            node = keccak::hashv(&[&node, &empty_node]).to_bytes();
+            empty_node = keccak::hashv(&[&empty_node, &empty_node]).to_bytes();
+        }
+        (node, proof)
+    }
+
+    /// The current root of the tree, being that of its latest change log
+    fn root(&self, test: &SwapTest) -> [u8; 32] {
+        let data = test.svm.get_account(&self.merkle_tree).unwrap().data;
+        let active_index = u64::from_le_bytes(
+            data[TREE_HEADER_SIZE + 8..TREE_HEADER_SIZE + 16]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        let change_log = TREE_HEADER_SIZE + 24 + active_index * CHANGE_LOG_SIZE;
+        data[change_log..change_log + 32].try_into().unwrap()
+    }
+
+    fn swap_account(&self, test: &SwapTest) -> Pubkey {
+        pda::cnft_swap_account(
+            &test.initiator.pubkey(),
+            &test.redeemer.pubkey(),
+            &secret_hash(&SECRET),
+        )
+        .0
+    }
+
+    fn initiate(&self, test: &SwapTest) -> Instruction {
+        let initiator = test.initiator.pubkey();
+        let (root, proof) = self.root_and_proof(&initiator);
+        let mut accounts = accounts::InitiateCnft {
+            swap_account: self.swap_account(test),
+            config: pda::config().0,
+            initiator,
+            leaf_delegate: initiator,
+            tree_authority: pda::cnft_tree_authority(&self.merkle_tree).0,
+            merkle_tree: self.merkle_tree,
+            log_wrapper: NOOP_PROGRAM_ID,
+            compression_program: ACCOUNT_COMPRESSION_PROGRAM_ID,
+            bubblegum_program: BUBBLEGUM_PROGRAM_ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(proof);
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateCnft {
+                leaf: self.leaf,
+                root,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer: test.redeemer.pubkey(),
+                secret_hash: secret_hash(&SECRET),
+            }
+            .data(),
+            accounts,
+        )
+    }
+
+    fn redeem(&self, test: &SwapTest, secret: [u8; 32]) -> Instruction {
+        let swap_account = self.swap_account(test);
+        let (root, proof) = self.root_and_proof(&swap_account);
+        let mut accounts = accounts::RedeemCnft {
+            swap_account,
+            initiator: test.initiator.pubkey(),
+            redeemer: test.redeemer.pubkey(),
+            tree_authority: pda::cnft_tree_authority(&self.merkle_tree).0,
+            merkle_tree: self.merkle_tree,
+            log_wrapper: NOOP_PROGRAM_ID,
+            compression_program: ACCOUNT_COMPRESSION_PROGRAM_ID,
+            bubblegum_program: BUBBLEGUM_PROGRAM_ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(proof);
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemCnft { secret, root }.data(),
+            accounts,
+        )
+    }
+
+    fn refund(&self, test: &SwapTest) -> Instruction {
+        let swap_account = self.swap_account(test);
+        let (root, proof) = self.root_and_proof(&swap_account);
+        let mut accounts = accounts::RefundCnft {
+            swap_account,
+            initiator: test.initiator.pubkey(),
+            tree_authority: pda::cnft_tree_authority(&self.merkle_tree).0,
+            merkle_tree: self.merkle_tree,
+            log_wrapper: NOOP_PROGRAM_ID,
+            compression_program: ACCOUNT_COMPRESSION_PROGRAM_ID,
+            bubblegum_program: BUBBLEGUM_PROGRAM_ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(proof);
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundCnft { root }.data(),
+            accounts,
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf` and Bubblegum to be dumped"]
+fn cnft_swaps_are_redeemed_with_the_secret() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let cnft = mint_cnft(&mut test);
+    assert_eq!(cnft.root(&test), cnft.root_and_proof(&initiator.pubkey()).0);
+
+    test.send(&[cnft.initiate(&test)], &[&initiator]).unwrap();
+    let swap_account = cnft.swap_account(&test);
+    assert_eq!(cnft.root(&test), cnft.root_and_proof(&swap_account).0);
+
+    assert_swap_error(
+        test.send(&[cnft.redeem(&test, [8; 32])], &[]),
+        SwapError::InvalidSecret,
+    );
+
+    let initiator_before = test.balance(&initiator.pubkey());
+    let rent = test.balance(&swap_account);
+    test.send(&[cnft.redeem(&test, SECRET)], &[]).unwrap();
+    assert_eq!(test.balance(&swap_account), 0);
+    assert_eq!(
+        cnft.root(&test),
+        cnft.root_and_proof(&test.redeemer.pubkey()).0
+    );
+    assert_eq!(test.balance(&initiator.pubkey()), initiator_before + rent);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf` and Bubblegum to be dumped"]
+fn cnft_swaps_are_refunded_once_expired() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let cnft = mint_cnft(&mut test);
+    test.send(&[cnft.initiate(&test)], &[&initiator]).unwrap();
+
+    test.warp(EXPIRES_IN_SLOTS - 1);
+    assert_swap_error(
+        test.send(&[cnft.refund(&test)], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+
+    test.warp(1);
+    test.send(&[cnft.refund(&test)], &[]).unwrap();
+    assert_eq!(test.balance(&cnft.swap_account(&test)), 0);
+    assert_eq!(cnft.root(&test), cnft.root_and_proof(&initiator.pubkey()).0);
+}
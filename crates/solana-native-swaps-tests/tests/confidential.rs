@@ -0,0 +1,516 @@
+//! Confidential token swaps, escrowing Token-2022 tokens through confidential transfers with
+//! every proof generated off-chain and verified into a context state account beforehand
+
+use anchor_lang::{
+    prelude::Pubkey, solana_program::system_instruction, InstructionData, ToAccountMetas,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        confidential_transfer::{
+            self,
+            account_info::{ApplyPendingBalanceAccountInfo, TransferAccountInfo},
+            ConfidentialTransferAccount,
+        },
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    solana_zk_sdk::{
+        encryption::{
+            auth_encryption::AeKey,
+            elgamal::{ElGamalCiphertext, ElGamalKeypair, ElGamalPubkey},
+            grouped_elgamal::GroupedElGamalCiphertext,
+            pod::grouped_elgamal::PodGroupedElGamalCiphertext3Handles,
+        },
+        zk_elgamal_proof_program::{
+            self,
+            instruction::{ContextStateInfo, ProofInstruction},
+            proof_data::{PubkeyValidityProofData, ZeroCiphertextProofData, ZkProofData},
+            state::ProofContextState,
+        },
+    },
+    state::{Account as TokenAccount, Mint},
+};
+use bytemuck::Pod;
+use solana_keypair::Keypair;
+use solana_native_swaps::{accounts, instruction, pda, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+use spl_token_confidential_transfer_proof_extraction::instruction::{ProofData, ProofLocation};
+use spl_token_confidential_transfer_proof_generation::{
+    transfer::TransferProofData, try_combine_lo_hi_ciphertexts, TRANSFER_AMOUNT_LO_BITS,
+};
+
+const AMOUNT: u64 = 1_000_000;
+const DECIMALS: u8 = 6;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// A token account configured for confidential transfers, along with the keys its owner
+/// decrypts its balances with
+struct ConfidentialAccount {
+    address: Pubkey,
+    elgamal: ElGamalKeypair,
+    aes: AeKey,
+}
+
+/// The context state accounts of the proofs of a confidential transfer
+struct TransferProofs {
+    equality: Pubkey,
+    ciphertext_validity: Pubkey,
+    range: Pubkey,
+}
+
+/// A confidential swap from the initiator to the redeemer locked to `SECRET`, escrowed in a
+/// vault whose keys the initiator shares with the redeemer
+struct ConfidentialSwap {
+    mint: Pubkey,
+    swap_account: Pubkey,
+    token_vault: Pubkey,
+    vault_elgamal: ElGamalKeypair,
+    vault_aes: AeKey,
+    initiator_account: ConfidentialAccount,
+    redeemer_account: ConfidentialAccount,
+}
+
+/// Verifies `proof_data` with `proof_instruction` into a new context state account, returning
+/// its address. The context state account is created in a transaction of its own, as range
+/// proofs barely fit in one.
+fn verify_proof<T, U>(
+    test: &mut SwapTest,
+    proof_instruction: ProofInstruction,
+    proof_data: &T,
+) -> Pubkey
+where
+    T: Pod + ZkProofData<U>,
+    U: Pod,
+{
+    let (payer, context_state) = (test.payer.pubkey(), Keypair::new());
+    let size = std::mem::size_of::<ProofContextState<U>>();
+    let create_account = system_instruction::create_account(
+        &payer,
+        &context_state.pubkey(),
+        test.svm.minimum_balance_for_rent_exemption(size),
+        size as u64,
+        &zk_elgamal_proof_program::ID,
+    );
+    test.send(&[create_account], &[&context_state]).unwrap();
+
+    let verify = proof_instruction.encode_verify_proof(
+        Some(ContextStateInfo {
+            context_state_account: &context_state.pubkey(),
+            context_state_authority: &payer,
+        }),
+        proof_data,
+    );
+    test.send(&[verify], &[]).unwrap();
+    context_state.pubkey()
+}
+
+fn verify_transfer_proofs(test: &mut SwapTest, proof_data: &TransferProofData) -> TransferProofs {
+    TransferProofs {
+        equality: verify_proof(
+            test,
+            ProofInstruction::VerifyCiphertextCommitmentEquality,
+            &proof_data.equality_proof_data,
+        ),
+        ciphertext_validity: verify_proof(
+            test,
+            ProofInstruction::VerifyBatchedGroupedCiphertext3HandlesValidity,
+            &proof_data.ciphertext_validity_proof_data,
+        ),
+        range: verify_proof(
+            test,
+            ProofInstruction::VerifyBatchedRangeProofU128,
+            &proof_data.range_proof_data,
+        ),
+    }
+}
+
+/// The confidential transfer state of the token account at `address`
+fn confidential_state(test: &SwapTest, address: &Pubkey) -> ConfidentialTransferAccount {
+    let account = test.svm.get_account(address).unwrap();
+    *StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .unwrap()
+        .get_extension::<ConfidentialTransferAccount>()
+        .unwrap()
+}
+
+/// Creates a Token-2022 mint automatically approving new confidential accounts
+fn create_confidential_mint(test: &mut SwapTest) -> Pubkey {
+    let (payer, mint) = (test.payer.pubkey(), Keypair::new());
+    let mint_len = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::ConfidentialTransferMint,
+    ])
+    .unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            test.svm.minimum_balance_for_rent_exemption(mint_len),
+            mint_len as u64,
+            &spl_token_2022::ID,
+        ),
+        confidential_transfer::instruction::initialize_mint(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            None,
+            true,
+            None,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &payer,
+            None,
+            DECIMALS,
+        )
+        .unwrap(),
+    ];
+    test.send(&instructions, &[&mint]).unwrap();
+    mint.pubkey()
+}
+
+/// Creates a token account of `mint` owned by `owner`, configured for confidential transfers
+/// with fresh keys
+fn create_confidential_account(
+    test: &mut SwapTest,
+    mint: &Pubkey,
+    owner: &Keypair,
+) -> ConfidentialAccount {
+    let (account, elgamal, aes) = (
+        Keypair::new(),
+        ElGamalKeypair::new_rand(),
+        AeKey::new_rand(),
+    );
+    let account_len = ExtensionType::try_calculate_account_len::<TokenAccount>(&[
+        ExtensionType::ConfidentialTransferAccount,
+    ])
+    .unwrap();
+    let pubkey_validity = PubkeyValidityProofData::new(&elgamal).unwrap();
+    let mut instructions = vec![
+        system_instruction::create_account(
+            &test.payer.pubkey(),
+            &account.pubkey(),
+            test.svm.minimum_balance_for_rent_exemption(account_len),
+            account_len as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::initialize_account3(
+            &spl_token_2022::ID,
+            &account.pubkey(),
+            mint,
+            &owner.pubkey(),
+        )
+        .unwrap(),
+    ];
+    // The proof is verified by the instruction following `configure_account`
+    instructions.extend(
+        confidential_transfer::instruction::configure_account(
+            &spl_token_2022::ID,
+            &account.pubkey(),
+            mint,
+            aes.encrypt(0).into(),
+            u64::from(u16::MAX),
+            &owner.pubkey(),
+            &[],
+            ProofLocation::InstructionOffset(
+                1.try_into().unwrap(),
+                ProofData::InstructionData(&pubkey_validity),
+            ),
+        )
+        .unwrap(),
+    );
+    test.send(&instructions, &[&account, owner]).unwrap();
+    ConfidentialAccount {
+        address: account.pubkey(),
+        elgamal,
+        aes,
+    }
+}
+
+/// The balance pending at the confidential account, credited by confidential transfers
+fn pending_balance(test: &SwapTest, account: &ConfidentialAccount) -> u64 {
+    let state = confidential_state(test, &account.address);
+    let available = ApplyPendingBalanceAccountInfo::new(&state)
+        .new_decryptable_available_balance(account.elgamal.secret(), &account.aes)
+        .unwrap();
+    let available_before: u64 = account
+        .aes
+        .decrypt(&state.decryptable_available_balance.try_into().unwrap())
+        .unwrap();
+    account.aes.decrypt(&available).unwrap() - available_before
+}
+
+/// The ElGamal ciphertext, under the source's public key, of one half of a transfer amount
+fn source_ciphertext(grouped_ciphertext: PodGroupedElGamalCiphertext3Handles) -> ElGamalCiphertext {
+    GroupedElGamalCiphertext::<3>::try_from(grouped_ciphertext)
+        .unwrap()
+        .to_elgamal_ciphertext(0)
+        .unwrap()
+}
+
+impl ConfidentialSwap {
+    /// Sets up a swap of `AMOUNT`, which the initiator holds as their available confidential
+    /// balance
+    fn new(test: &mut SwapTest) -> Self {
+        let (initiator, redeemer) = (
+            test.initiator.insecure_clone(),
+            test.redeemer.insecure_clone(),
+        );
+        let mint = create_confidential_mint(test);
+        let initiator_account = create_confidential_account(test, &mint, &initiator);
+        let redeemer_account = create_confidential_account(test, &mint, &redeemer);
+
+        let fund = [
+            spl_token_2022::instruction::mint_to(
+                &spl_token_2022::ID,
+                &mint,
+                &initiator_account.address,
+                &test.payer.pubkey(),
+                &[],
+                AMOUNT,
+            )
+            .unwrap(),
+            confidential_transfer::instruction::deposit(
+                &spl_token_2022::ID,
+                &initiator_account.address,
+                &mint,
+                AMOUNT,
+                DECIMALS,
+                &initiator.pubkey(),
+                &[],
+            )
+            .unwrap(),
+            confidential_transfer::instruction::apply_pending_balance(
+                &spl_token_2022::ID,
+                &initiator_account.address,
+                1,
+                initiator_account.aes.encrypt(AMOUNT).into(),
+                &initiator.pubkey(),
+                &[],
+            )
+            .unwrap(),
+        ];
+        test.send(&fund, &[&initiator]).unwrap();
+
+        let swap_account = pda::confidential_swap_account(
+            &initiator.pubkey(),
+            &redeemer.pubkey(),
+            &secret_hash(&SECRET),
+        )
+        .0;
+        ConfidentialSwap {
+            mint,
+            swap_account,
+            token_vault: pda::confidential_vault(&swap_account).0,
+            vault_elgamal: ElGamalKeypair::new_rand(),
+            vault_aes: AeKey::new_rand(),
+            initiator_account,
+            redeemer_account,
+        }
+    }
+
+    /// Verifies the proofs of the initiator's transfer into the vault, returning the
+    /// `initiate_confidential` instruction relying on them
+    fn initiate(&self, test: &mut SwapTest) -> Instruction {
+        let pubkey_validity_proof = verify_proof(
+            test,
+            ProofInstruction::VerifyPubkeyValidity,
+            &PubkeyValidityProofData::new(&self.vault_elgamal).unwrap(),
+        );
+        let initiator_state = confidential_state(test, &self.initiator_account.address);
+        let proof_data = TransferAccountInfo::new(&initiator_state)
+            .generate_split_transfer_proof_data(
+                AMOUNT,
+                &self.initiator_account.elgamal,
+                &self.initiator_account.aes,
+                self.vault_elgamal.pubkey(),
+                None,
+            )
+            .unwrap();
+        let proofs = verify_transfer_proofs(test, &proof_data);
+
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateConfidential {
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer: test.redeemer.pubkey(),
+                secret_hash: secret_hash(&SECRET),
+                vault_decryptable_zero_balance: self.vault_aes.encrypt(0).to_bytes(),
+                vault_decryptable_balance: self.vault_aes.encrypt(AMOUNT).to_bytes(),
+                initiator_decryptable_balance: self.initiator_account.aes.encrypt(0).to_bytes(),
+            }
+            .data(),
+            accounts::InitiateConfidential {
+                swap_account: self.swap_account,
+                token_vault: self.token_vault,
+                initiator_token_account: self.initiator_account.address,
+                mint: self.mint,
+                pubkey_validity_proof,
+                equality_proof: proofs.equality,
+                ciphertext_validity_proof: proofs.ciphertext_validity,
+                range_proof: proofs.range,
+                config: pda::config().0,
+                initiator: test.initiator.pubkey(),
+                token_program: spl_token_2022::ID,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    /// Verifies the proofs of the transfer of the escrowed tokens out of the vault to
+    /// `destination`, and of the vault's resulting zero balance
+    fn verify_release_proofs(
+        &self,
+        test: &mut SwapTest,
+        destination: &ElGamalPubkey,
+    ) -> (TransferProofs, Pubkey) {
+        let vault_state = confidential_state(test, &self.token_vault);
+        let proof_data = TransferAccountInfo::new(&vault_state)
+            .generate_split_transfer_proof_data(
+                AMOUNT,
+                &self.vault_elgamal,
+                &self.vault_aes,
+                destination,
+                None,
+            )
+            .unwrap();
+
+        // The vault's available balance once the transfer is deducted from it, as Token-2022
+        // computes it from the transfer amount encrypted under the vault's public key
+        let context = proof_data.ciphertext_validity_proof_data.context;
+        let transferred = try_combine_lo_hi_ciphertexts(
+            &source_ciphertext(context.grouped_ciphertext_lo),
+            &source_ciphertext(context.grouped_ciphertext_hi),
+            TRANSFER_AMOUNT_LO_BITS,
+        )
+        .unwrap();
+        let available_balance: ElGamalCiphertext =
+            vault_state.available_balance.try_into().unwrap();
+        let remaining_balance = available_balance - transferred;
+
+        let proofs = verify_transfer_proofs(test, &proof_data);
+        let zero_balance_proof = verify_proof(
+            test,
+            ProofInstruction::VerifyZeroCiphertext,
+            &ZeroCiphertextProofData::new(&self.vault_elgamal, &remaining_balance).unwrap(),
+        );
+        (proofs, zero_balance_proof)
+    }
+
+    fn redeem(&self, test: &mut SwapTest, secret: [u8; 32]) -> Instruction {
+        let destination = *self.redeemer_account.elgamal.pubkey();
+        let (proofs, zero_balance_proof) = self.verify_release_proofs(test, &destination);
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemConfidential {
+                secret,
+                vault_decryptable_zero_balance: self.vault_aes.encrypt(0).to_bytes(),
+            }
+            .data(),
+            accounts::RedeemConfidential {
+                swap_account: self.swap_account,
+                token_vault: self.token_vault,
+                redeemer_token_account: self.redeemer_account.address,
+                mint: self.mint,
+                equality_proof: proofs.equality,
+                ciphertext_validity_proof: proofs.ciphertext_validity,
+                range_proof: proofs.range,
+                zero_balance_proof,
+                initiator: test.initiator.pubkey(),
+                token_program: spl_token_2022::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn refund(&self, test: &mut SwapTest) -> Instruction {
+        let destination = *self.initiator_account.elgamal.pubkey();
+        let (proofs, zero_balance_proof) = self.verify_release_proofs(test, &destination);
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundConfidential {
+                vault_decryptable_zero_balance: self.vault_aes.encrypt(0).to_bytes(),
+            }
+            .data(),
+            accounts::RefundConfidential {
+                swap_account: self.swap_account,
+                token_vault: self.token_vault,
+                initiator_token_account: self.initiator_account.address,
+                mint: self.mint,
+                equality_proof: proofs.equality,
+                ciphertext_validity_proof: proofs.ciphertext_validity,
+                range_proof: proofs.range,
+                zero_balance_proof,
+                initiator: test.initiator.pubkey(),
+                token_program: spl_token_2022::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn is_closed(&self, test: &SwapTest) -> bool {
+        [self.swap_account, self.token_vault].iter().all(|address| {
+            test.svm
+                .get_account(address)
+                .is_none_or(|account| account.lamports == 0)
+        })
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn confidential_swaps_are_redeemed_with_the_secret() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let swap = ConfidentialSwap::new(&mut test);
+
+    let initiate = swap.initiate(&mut test);
+    test.send(&[initiate], &[&initiator]).unwrap();
+    // The escrowed amount is only known to those holding the vault's keys
+    let vault_state = confidential_state(&test, &swap.token_vault);
+    let vault_balance = vault_state
+        .decryptable_available_balance
+        .try_into()
+        .unwrap();
+    assert_eq!(swap.vault_aes.decrypt(&vault_balance), Some(AMOUNT));
+
+    let redeem = swap.redeem(&mut test, [8; 32]);
+    assert_swap_error(test.send(&[redeem], &[]), SwapError::InvalidSecret);
+
+    let redeem = swap.redeem(&mut test, SECRET);
+    test.send(&[redeem], &[]).unwrap();
+    assert!(swap.is_closed(&test));
+    assert_eq!(pending_balance(&test, &swap.redeemer_account), AMOUNT);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn confidential_swaps_are_refunded_once_expired() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let swap = ConfidentialSwap::new(&mut test);
+    let initiate = swap.initiate(&mut test);
+    test.send(&[initiate], &[&initiator]).unwrap();
+
+    let refund = swap.refund(&mut test);
+    assert_swap_error(
+        test.send(std::slice::from_ref(&refund), &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+
+    test.warp(EXPIRES_IN_SLOTS);
+    test.send(&[refund], &[]).unwrap();
+    assert!(swap.is_closed(&test));
+    assert_eq!(pending_balance(&test, &swap.initiator_account), AMOUNT);
+    assert_eq!(pending_balance(&test, &swap.redeemer_account), 0);
+}
@@ -0,0 +1,92 @@
+//! Cooperative closes, splitting a swap between its parties as they agree
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_native_swaps::{accounts, instruction, SwapError};
+use solana_native_swaps_client::{pda, Instruction};
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+fn cooperative_close_instruction(
+    test: &SwapTest,
+    swap_account: Pubkey,
+    redeemer_share_bps: u16,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::CooperativeClose { redeemer_share_bps }.data(),
+        accounts::CooperativeClose {
+            swap_account,
+            initiator: test.initiator.pubkey(),
+            refund_to: None,
+            redeemer: test.redeemer.pubkey(),
+            config: pda::config().0,
+            treasury: pda::treasury().0,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn cooperative_closes_split_the_swap_by_the_agreed_share() {
+    let mut test = SwapTest::new().unwrap();
+    let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let (initiator_before, redeemer_before) = (test.balance(&initiator), test.balance(&redeemer));
+    let treasury_before = test.balance(&pda::treasury().0);
+
+    let close = cooperative_close_instruction(&test, swap_account, 3_000);
+    let (initiator_signer, redeemer_signer) = (
+        test.initiator.insecure_clone(),
+        test.redeemer.insecure_clone(),
+    );
+    test.send(&[close], &[&initiator_signer, &redeemer_signer])
+        .unwrap();
+
+    let redeemer_share = AMOUNT_LAMPORTS * 3 / 10;
+    let fee = test.program.protocol_fee(redeemer_share);
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(
+        test.balance(&redeemer),
+        redeemer_before + redeemer_share - fee
+    );
+    assert_eq!(test.balance(&pda::treasury().0), treasury_before + fee);
+    assert_eq!(
+        test.balance(&initiator),
+        initiator_before + AMOUNT_LAMPORTS - redeemer_share + test.swap_rent()
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn cooperative_closes_require_both_parties() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let (initiator, redeemer) = (
+        test.initiator.insecure_clone(),
+        test.redeemer.insecure_clone(),
+    );
+
+    let close = cooperative_close_instruction(&test, swap_account, 5_000);
+    assert!(test
+        .send(std::slice::from_ref(&close), &[&redeemer])
+        .is_err());
+    assert!(test.send(&[close], &[&initiator]).is_err());
+
+    let close = cooperative_close_instruction(&test, swap_account, 10_001);
+    assert_swap_error(
+        test.send(&[close], &[&initiator, &redeemer]),
+        SwapError::InvalidCloseShare,
+    );
+    assert!(test.swap(&swap_account).is_some());
+}
@@ -0,0 +1,59 @@
+//! Persistence of the swaps driven by the cross-chain swap coordinator
+
+use solana_native_swaps_client::{
+    coordinator::{FileStore, Stage, SwapOrder, SwapRecord, SwapStore},
+    pda, HashAlgorithm, Pubkey,
+};
+
+fn record(stage: Stage) -> SwapRecord {
+    let initiator = Pubkey::new_unique();
+    let secret = [7; 32];
+    let secret_hash = HashAlgorithm::Keccak256.hash(&secret);
+    let order = SwapOrder {
+        hash_algorithm: HashAlgorithm::Keccak256,
+        refund_to: Some(Pubkey::new_unique()),
+        ..SwapOrder::new(Pubkey::new_unique(), 1_000_000_000, 9_000)
+    };
+    SwapRecord {
+        swap_account: pda::swap_account(&initiator, &order.redeemer, &secret_hash).0,
+        order,
+        initiator,
+        secret,
+        secret_hash,
+        stage,
+    }
+}
+
+#[test]
+fn records_survive_a_restart() {
+    let path = std::env::temp_dir().join(format!("coordinator-{}.json", Pubkey::new_unique()));
+    let initiated = record(Stage::Initiated);
+    let refunding = record(Stage::Refunding);
+    FileStore::new(&path).save("a", &initiated).unwrap();
+    FileStore::new(&path).save("b", &refunding).unwrap();
+
+    let store = FileStore::new(&path);
+    assert_eq!(store.load("a").unwrap(), Some(initiated.clone()));
+    assert_eq!(store.load("b").unwrap(), Some(refunding));
+    assert_eq!(store.load("c").unwrap(), None);
+
+    // Records are saved as readable JSON, with addresses in base58
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains(&initiated.swap_account.to_string()));
+    assert!(contents.contains("\"stage\": \"initiated\""));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn only_settled_swaps_are_terminal() {
+    for (stage, terminal) in [
+        (Stage::Created, false),
+        (Stage::Initiated, false),
+        (Stage::Revealing, false),
+        (Stage::Completed, true),
+        (Stage::Refunding, false),
+        (Stage::Refunded, true),
+    ] {
+        assert_eq!(stage.is_terminal(), terminal, "{stage:?}");
+    }
+}
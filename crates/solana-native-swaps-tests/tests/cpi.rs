@@ -0,0 +1,185 @@
+//! The swap lifecycle driven through CPI by the example composer program
+
+use anchor_lang::{
+    prelude::Pubkey, solana_program::system_instruction, InstructionData, ToAccountMetas,
+};
+use solana_native_swaps::pda;
+use solana_native_swaps_client::{Instruction, RefundBuilder};
+use solana_native_swaps_composer::{accounts, instruction};
+use solana_native_swaps_tests::{secret_hash, SwapTest, PROTOCOL_FEE_BPS};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+fn set_up() -> SwapTest {
+    let mut test = SwapTest::new().unwrap();
+    test.add_composer().unwrap();
+    test
+}
+
+fn initiate(test: &mut SwapTest) {
+    let initiate = Instruction::new_with_bytes(
+        solana_native_swaps_composer::ID,
+        &instruction::Initiate {
+            amount_lamports: AMOUNT_LAMPORTS,
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+        }
+        .data(),
+        accounts::Initiate {
+            swap_account: test.swap_account(&secret_hash(&SECRET)),
+            config: pda::config().0,
+            initiator: test.initiator.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            swaps_program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    let initiator = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&initiator]).unwrap();
+}
+
+#[test]
+#[ignore = "requires the program and the composer to be built with `anchor build`"]
+fn initiate_through_cpi_escrows_the_swap_amount() {
+    let mut test = set_up();
+
+    initiate(&mut test);
+
+    let swap = test
+        .swap(&test.swap_account(&secret_hash(&SECRET)))
+        .unwrap();
+    assert_eq!(swap.initiator, test.initiator.pubkey());
+    assert_eq!(swap.redeemer, test.redeemer.pubkey());
+    assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+}
+
+#[test]
+#[ignore = "requires the program and the composer to be built with `anchor build`"]
+fn redeem_through_cpi_pays_the_redeemer_in_full() {
+    let mut test = set_up();
+    initiate(&mut test);
+    let redeemer = test.redeemer.pubkey();
+    let before = test.balance(&redeemer);
+
+    let redeem = Instruction::new_with_bytes(
+        solana_native_swaps_composer::ID,
+        &instruction::Redeem { secret: SECRET }.data(),
+        accounts::Redeem {
+            swap_account: test.swap_account(&secret_hash(&SECRET)),
+            initiator: test.initiator.pubkey(),
+            redeemer,
+            config: pda::config().0,
+            treasury: pda::treasury().0,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            swaps_program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    test.send(&[redeem], &[]).unwrap();
+
+    let fee = AMOUNT_LAMPORTS * u64::from(PROTOCOL_FEE_BPS) / 10_000;
+    assert_eq!(test.balance(&redeemer), before + AMOUNT_LAMPORTS - fee);
+    assert!(test
+        .swap(&test.swap_account(&secret_hash(&SECRET)))
+        .is_none());
+}
+
+#[test]
+#[ignore = "requires the program and the composer to be built with `anchor build`"]
+fn refund_through_cpi_is_only_allowed_from_expiry() {
+    let mut test = set_up();
+    initiate(&mut test);
+    let refund = Instruction::new_with_bytes(
+        solana_native_swaps_composer::ID,
+        &instruction::Refund {}.data(),
+        accounts::Refund {
+            swap_account: test.swap_account(&secret_hash(&SECRET)),
+            initiator: test.initiator.pubkey(),
+            event_authority: pda::event_authority().0,
+            swaps_program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+
+    test.warp(EXPIRES_IN_SLOTS - 1);
+    assert!(test.send(std::slice::from_ref(&refund), &[]).is_err());
+
+    test.warp(1);
+    test.send(&[refund], &[]).unwrap();
+    assert!(test
+        .swap(&test.swap_account(&secret_hash(&SECRET)))
+        .is_none());
+}
+
+#[test]
+#[ignore = "requires the program and the composer to be built with `anchor build`"]
+fn initiate_from_a_program_owned_vault_escrows_its_lamports() {
+    let mut test = set_up();
+    let authority = test.initiator.insecure_clone();
+    let vault = Pubkey::find_program_address(
+        &[b"vault", authority.pubkey().as_ref()],
+        &solana_native_swaps_composer::ID,
+    )
+    .0;
+    let create_vault = Instruction::new_with_bytes(
+        solana_native_swaps_composer::ID,
+        &instruction::CreateVault {}.data(),
+        accounts::CreateVault {
+            vault,
+            authority: authority.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+    );
+    let deposit = system_instruction::transfer(&authority.pubkey(), &vault, 2 * AMOUNT_LAMPORTS);
+    test.send(&[create_vault, deposit], &[&authority]).unwrap();
+    let before = test.balance(&vault);
+
+    let (swap_account, _) =
+        pda::swap_account(&vault, &test.redeemer.pubkey(), &secret_hash(&SECRET));
+    let initiate = Instruction::new_with_bytes(
+        solana_native_swaps_composer::ID,
+        &instruction::InitiateFromVault {
+            amount_lamports: AMOUNT_LAMPORTS,
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+        }
+        .data(),
+        accounts::InitiateFromVault {
+            vault,
+            authority: authority.pubkey(),
+            swap_account,
+            config: pda::config().0,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            swaps_program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    test.send(&[initiate], &[&authority]).unwrap();
+
+    let swap = test.swap(&swap_account).unwrap();
+    assert_eq!(swap.initiator, vault);
+    assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+    assert_eq!(
+        test.balance(&swap_account),
+        AMOUNT_LAMPORTS + test.swap_rent()
+    );
+    assert_eq!(
+        test.balance(&vault),
+        before - AMOUNT_LAMPORTS - test.swap_rent()
+    );
+
+    // The refund and rent return to the vault
+    test.warp(EXPIRES_IN_SLOTS);
+    test.send(&[RefundBuilder::new(&swap).instruction()], &[])
+        .unwrap();
+    assert_eq!(test.balance(&vault), before);
+}
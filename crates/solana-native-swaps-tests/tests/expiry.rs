@@ -0,0 +1,284 @@
+//! Refunds at the exact boundaries of each kind of timelock, with the clock warped rather than
+//! waited on.
+
+use anchor_lang::{
+    prelude::{EpochSchedule, Pubkey},
+    solana_program::clock::DEFAULT_MS_PER_SLOT,
+    InstructionData, ToAccountMetas,
+};
+use solana_native_swaps::{
+    accounts, instruction, pda, HashAlgorithm, RedeemWindow, SwapError, SwapIncentives,
+    MAX_EXPIRY_SLOTS,
+};
+use solana_native_swaps_client::{
+    epoch::{epoch_expiry_slot, max_expires_in_epochs},
+    InitiateBuilder, Instruction, RedeemBuilder,
+};
+use solana_native_swaps_tests::{assert_swap_error, clock, secret_hash, Program, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const EXPIRES_IN_SECONDS: i64 = 3_600;
+const SECRET: [u8; 32] = [7; 32];
+
+/// The `initiate` instruction of the Anchor program with its data replaced by `data`, for
+/// the variants sharing its accounts
+fn initiate_variant(test: &SwapTest, data: Vec<u8>) -> Instruction {
+    let mut instruction = InitiateBuilder::new(
+        test.initiator.pubkey(),
+        test.redeemer.pubkey(),
+        secret_hash(&SECRET),
+        AMOUNT_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    )
+    .instruction();
+    instruction.data = data;
+    instruction
+}
+
+fn extend_expiry_instruction(
+    test: &SwapTest,
+    swap_account: &Pubkey,
+    new_expiry_slot: u64,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::ExtendExpiry { new_expiry_slot }.data(),
+        accounts::ExtendExpiry {
+            swap_account: *swap_account,
+            config: pda::config().0,
+            initiator: test.initiator.pubkey(),
+            redeemer: test.redeemer.pubkey(),
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+fn initiate_with_epoch_expiry(test: &SwapTest, expires_in_epochs: u64) -> Instruction {
+    initiate_variant(
+        test,
+        instruction::InitiateWithEpochExpiry {
+            amount_lamports: AMOUNT_LAMPORTS,
+            expires_in_epochs,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+            hash_algorithm: HashAlgorithm::Sha256,
+            incentives: SwapIncentives::default(),
+            redeem_window: RedeemWindow::default(),
+        }
+        .data(),
+    )
+}
+
+fn initiate_with_dual_expiry(test: &SwapTest, expires_in_seconds: i64) -> Instruction {
+    initiate_variant(
+        test,
+        instruction::InitiateWithDualExpiry {
+            amount_lamports: AMOUNT_LAMPORTS,
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+            hash_algorithm: HashAlgorithm::Sha256,
+            incentives: SwapIncentives::default(),
+            redeem_window: RedeemWindow::default(),
+            expires_in_seconds,
+        }
+        .data(),
+    )
+}
+
+#[test]
+#[ignore = "requires both programs to be built with `cargo build-sbf`"]
+fn refund_is_allowed_from_the_exact_expiry_slot() {
+    for program in Program::ALL {
+        let mut test = SwapTest::with_program(program).unwrap();
+        test.initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+            .unwrap();
+        let expiry_slot = test.slot() + EXPIRES_IN_SLOTS;
+
+        clock::warp_to_slot(&mut test.svm, expiry_slot - 1);
+        let refund = test.refund_instruction(&secret_hash(&SECRET));
+        assert!(
+            test.send(std::slice::from_ref(&refund), &[]).is_err(),
+            "{program:?}"
+        );
+
+        clock::warp_to_slot(&mut test.svm, expiry_slot);
+        assert!(test.send(&[refund], &[]).is_ok(), "{program:?}");
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn refund_of_a_dual_expiry_swap_requires_both_timelocks() {
+    let mut test = SwapTest::new().unwrap();
+    let initiate = initiate_with_dual_expiry(&test, EXPIRES_IN_SECONDS);
+    let initiator = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&initiator]).unwrap();
+    let expiry_timestamp = clock::clock(&test.svm).unix_timestamp + EXPIRES_IN_SECONDS;
+    let refund = test.refund_instruction(&secret_hash(&SECRET));
+
+    // Slots alone pass far quicker than wall-clock time here
+    test.warp(EXPIRES_IN_SLOTS);
+    clock::set_unix_timestamp(&mut test.svm, expiry_timestamp - 1);
+    let result = test.send(std::slice::from_ref(&refund), &[]);
+    assert_swap_error(result, SwapError::RefundBeforeExpiry);
+
+    clock::set_unix_timestamp(&mut test.svm, expiry_timestamp);
+    test.send(&[refund], &[]).unwrap();
+    assert_eq!(test.balance(&test.swap_account(&secret_hash(&SECRET))), 0);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn refund_of_an_epoch_expiry_swap_is_allowed_from_the_next_epoch() {
+    let mut test = SwapTest::new().unwrap();
+    let initiate = initiate_with_epoch_expiry(&test, 1);
+    let initiator = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&initiator]).unwrap();
+    let next_epoch = clock::clock(&test.svm).epoch + 1;
+    let refund = test.refund_instruction(&secret_hash(&SECRET));
+
+    let first_slot = test
+        .svm
+        .get_sysvar::<EpochSchedule>()
+        .get_first_slot_in_epoch(next_epoch);
+    clock::warp_to_slot(&mut test.svm, first_slot - 1);
+    let result = test.send(std::slice::from_ref(&refund), &[]);
+    assert_swap_error(result, SwapError::RefundBeforeExpiry);
+
+    clock::warp_to_epoch(&mut test.svm, next_epoch);
+    assert_eq!(clock::clock(&test.svm).epoch, next_epoch);
+    test.send(&[refund], &[]).unwrap();
+    assert_eq!(test.balance(&test.swap_account(&secret_hash(&SECRET))), 0);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn expiry_of_an_expired_swap_cannot_be_extended_to_a_past_slot() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let expiry_slot = test.slot() + EXPIRES_IN_SLOTS;
+    clock::warp_to_slot(&mut test.svm, expiry_slot + 10);
+    let (initiator, redeemer) = (
+        test.initiator.insecure_clone(),
+        test.redeemer.insecure_clone(),
+    );
+
+    // Later than the swap's expiry, yet already passed
+    let extend = extend_expiry_instruction(&test, &swap_account, expiry_slot + 5);
+    assert_swap_error(
+        test.send(&[extend], &[&initiator, &redeemer]),
+        SwapError::InvalidExpiryExtension,
+    );
+
+    let new_expiry_slot = test.slot() + EXPIRES_IN_SLOTS;
+    let extend = extend_expiry_instruction(&test, &swap_account, new_expiry_slot);
+    test.send(&[extend], &[&initiator, &redeemer]).unwrap();
+    assert_eq!(
+        test.swap(&swap_account).unwrap().expiry_slot,
+        new_expiry_slot
+    );
+    let refund = test.refund_instruction(&secret_hash(&SECRET));
+    assert_swap_error(test.send(&[refund], &[]), SwapError::RefundBeforeExpiry);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn epoch_expiries_are_bounded_by_the_maximum_expiry() {
+    let mut test = SwapTest::new().unwrap();
+    let schedule = test.svm.get_sysvar::<EpochSchedule>();
+    let max_epochs = max_expires_in_epochs(&schedule, MAX_EXPIRY_SLOTS);
+    let initiator = test.initiator.insecure_clone();
+
+    for expires_in_epochs in [max_epochs + 1, u64::MAX] {
+        let initiate = initiate_with_epoch_expiry(&test, expires_in_epochs);
+        assert_swap_error(
+            test.send(&[initiate], &[&initiator]),
+            SwapError::InvalidEpochExpiry,
+        );
+    }
+
+    let initiate = initiate_with_epoch_expiry(&test, max_epochs);
+    test.send(&[initiate], &[&initiator]).unwrap();
+    let swap = test
+        .swap(&test.swap_account(&secret_hash(&SECRET)))
+        .unwrap();
+    assert_eq!(
+        Some(swap.expiry_slot),
+        epoch_expiry_slot(&schedule, swap.created_at_slot, max_epochs)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn dual_expiries_are_bounded_by_the_maximum_expiry() {
+    let mut test = SwapTest::new().unwrap();
+    let max_expiry_seconds = (MAX_EXPIRY_SLOTS * DEFAULT_MS_PER_SLOT / 1_000) as i64;
+    let initiator = test.initiator.insecure_clone();
+
+    for expires_in_seconds in [0, -1, i64::MIN] {
+        let initiate = initiate_with_dual_expiry(&test, expires_in_seconds);
+        assert_swap_error(test.send(&[initiate], &[&initiator]), SwapError::ZeroExpiry);
+    }
+    for expires_in_seconds in [max_expiry_seconds + 1, i64::MAX] {
+        let initiate = initiate_with_dual_expiry(&test, expires_in_seconds);
+        assert_swap_error(
+            test.send(&[initiate], &[&initiator]),
+            SwapError::ExpiryOutOfBounds,
+        );
+    }
+
+    let initiate = initiate_with_dual_expiry(&test, max_expiry_seconds);
+    test.send(&[initiate], &[&initiator]).unwrap();
+    let swap = test
+        .swap(&test.swap_account(&secret_hash(&SECRET)))
+        .unwrap();
+    assert_eq!(
+        swap.expiry_timestamp(),
+        Some(clock::clock(&test.svm).unix_timestamp + max_expiry_seconds)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeem_windows_take_effect_from_the_slot_after_each_bound() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let start = test.slot();
+    let redeem_window = RedeemWindow {
+        redeem_after_slot: Some(start + 10),
+        redeem_deadline_slot: Some(start + 20),
+    };
+    let secrets = [SECRET, [8; 32]];
+    for secret in &secrets {
+        let initiate = InitiateBuilder::new(
+            initiator.pubkey(),
+            test.redeemer.pubkey(),
+            secret_hash(secret),
+            AMOUNT_LAMPORTS,
+            EXPIRES_IN_SLOTS,
+        )
+        .redeem_window(redeem_window);
+        test.send(&[initiate.instruction()], &[&initiator]).unwrap();
+    }
+    let redeem = |test: &SwapTest, secret: &[u8; 32]| {
+        let swap = test.swap(&test.swap_account(&secret_hash(secret))).unwrap();
+        RedeemBuilder::new(&swap, *secret).instruction()
+    };
+
+    clock::warp_to_slot(&mut test.svm, start + 10);
+    let result = test.send(&[redeem(&test, &secrets[0])], &[]);
+    assert_swap_error(result, SwapError::RedeemOutsideWindow);
+    clock::warp_to_slot(&mut test.svm, start + 11);
+    test.send(&[redeem(&test, &secrets[0])], &[]).unwrap();
+
+    clock::warp_to_slot(&mut test.svm, start + 21);
+    let result = test.send(&[redeem(&test, &secrets[1])], &[]);
+    assert_swap_error(result, SwapError::RedeemOutsideWindow);
+}
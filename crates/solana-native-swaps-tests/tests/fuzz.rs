@@ -0,0 +1,408 @@
+//! Fuzzes the program with random sequences of lifecycle instructions, some of them with an
+//! account substituted by an attacker's, checking every outcome against a model of the swaps.
+//!
+//! Set `SWAPS_FUZZ_SEED` to replay a failing run, and `SWAPS_FUZZ_RUNS` to fuzz for longer.
+
+use anchor_lang::prelude::Pubkey;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use solana_keypair::Keypair;
+use solana_native_swaps_client::{
+    pda, InitiateBuilder, InstantRefundBuilder, Instruction, RedeemBuilder, RefundBuilder,
+    SwapAccount,
+};
+use solana_native_swaps_tests::{secret_hash, SwapTest, PARTY_LAMPORTS, PROTOCOL_FEE_BPS};
+use solana_signer::Signer;
+
+/// The number of distinct secrets swaps are locked to, few enough for operations to often
+/// target open swaps
+const SECRETS: usize = 3;
+/// The number of operations per run, each run starting from a fresh program
+const OPERATIONS_PER_RUN: usize = 50;
+
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+    Initiate {
+        secret: usize,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    },
+    Redeem {
+        secret: usize,
+        correct_secret: bool,
+        amount_lamports: u64,
+        relayed: bool,
+    },
+    Refund {
+        secret: usize,
+        tipped: bool,
+    },
+    InstantRefund {
+        secret: usize,
+        consented: bool,
+    },
+    Warp(u64),
+}
+
+/// The model of an open swap
+#[derive(Clone, Copy, Debug)]
+struct OpenSwap {
+    amount_lamports: u64,
+    expiry_slot: u64,
+}
+
+/// The balances of every party which may receive funds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Balances {
+    initiator: u64,
+    redeemer: u64,
+    attacker: u64,
+    treasury: u64,
+    swap_accounts: u64,
+}
+
+impl Balances {
+    fn total(&self) -> u64 {
+        self.initiator + self.redeemer + self.attacker + self.treasury + self.swap_accounts
+    }
+}
+
+struct Fuzzer {
+    rng: StdRng,
+    test: SwapTest,
+    attacker: Keypair,
+    secrets: [[u8; 32]; SECRETS],
+    /// The open swap locked to each secret
+    open: [Option<OpenSwap>; SECRETS],
+    /// The last state observed of the swap locked to each secret, for operations to target
+    /// swaps which have since completed
+    last_seen: [Option<SwapAccount>; SECRETS],
+}
+
+impl Fuzzer {
+    fn new(seed: u64) -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let attacker = Keypair::new();
+        test.svm
+            .airdrop(&attacker.pubkey(), PARTY_LAMPORTS)
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let secrets = std::array::from_fn(|_| rng.gen());
+        Fuzzer {
+            rng,
+            test,
+            attacker,
+            secrets,
+            open: [None; SECRETS],
+            last_seen: [None; SECRETS],
+        }
+    }
+
+    fn operation(&mut self) -> Operation {
+        let secret = self.rng.gen_range(0..SECRETS);
+        let remaining = self.open[secret].map_or(1, |swap| swap.amount_lamports);
+        match self.rng.gen_range(0..5) {
+            0 => Operation::Initiate {
+                secret,
+                amount_lamports: self.rng.gen_range(1..=10_000_000_000),
+                expires_in_slots: self.rng.gen_range(1..=200),
+            },
+            1 => Operation::Redeem {
+                secret,
+                correct_secret: self.rng.gen_bool(0.8),
+                // Mostly full redeems, then tranches, then amounts exceeding the swap's
+                amount_lamports: match self.rng.gen_range(0..4) {
+                    0 | 1 => remaining,
+                    2 => self.rng.gen_range(1..=remaining),
+                    _ => self.rng.gen_range(0..=remaining + 1),
+                },
+                relayed: self.rng.gen_bool(0.3),
+            },
+            2 => Operation::Refund {
+                secret,
+                tipped: self.rng.gen_bool(0.3),
+            },
+            3 => Operation::InstantRefund {
+                secret,
+                consented: self.rng.gen_bool(0.8),
+            },
+            _ => Operation::Warp(self.rng.gen_range(1..=100)),
+        }
+    }
+
+    fn balances(&self) -> Balances {
+        Balances {
+            initiator: self.test.balance(&self.test.initiator.pubkey()),
+            redeemer: self.test.balance(&self.test.redeemer.pubkey()),
+            attacker: self.test.balance(&self.attacker.pubkey()),
+            treasury: self.test.balance(&pda::treasury().0),
+            swap_accounts: self
+                .secrets
+                .iter()
+                .map(|secret| self.test.balance(&self.swap_account(secret)))
+                .sum(),
+        }
+    }
+
+    fn swap_account(&self, secret: &[u8; 32]) -> Pubkey {
+        pda::swap_account(
+            &self.test.initiator.pubkey(),
+            &self.test.redeemer.pubkey(),
+            &secret_hash(secret),
+        )
+        .0
+    }
+
+    /// Applies `operation`, checking its outcome and the resulting balances against the model
+    fn apply(&mut self, operation: Operation) {
+        if let Operation::Warp(slots) = operation {
+            self.test.warp(slots);
+            return;
+        }
+
+        let before = self.balances();
+        let Some((mut instruction, parties, expected)) = self.instruction(operation) else {
+            // Operations on swaps never opened cannot be built
+            return;
+        };
+        // Substituting an account with the attacker's must either fail, or have no effect
+        let substituted =
+            !matches!(operation, Operation::Initiate { .. }) && self.rng.gen_bool(0.2);
+        if substituted {
+            self.substitute(&mut instruction);
+        }
+
+        let signers: Vec<Keypair> = parties
+            .iter()
+            .map(|party| match party {
+                Party::Initiator => self.test.initiator.insecure_clone(),
+                Party::Redeemer => self.test.redeemer.insecure_clone(),
+                Party::Attacker => self.attacker.insecure_clone(),
+            })
+            .collect();
+        let result = self
+            .test
+            .send(&[instruction], &signers.iter().collect::<Vec<_>>());
+        let after = self.balances();
+
+        assert_eq!(
+            before.total(),
+            after.total(),
+            "{operation:?} created or destroyed lamports"
+        );
+        assert!(
+            after.attacker <= before.attacker,
+            "{operation:?} paid the attacker"
+        );
+        match (expected, result) {
+            (Some(expected), Ok(_)) => {
+                assert_eq!(after, expected.balances, "{operation:?} misrouted funds");
+                let secret = operation_secret(operation);
+                self.open[secret] = expected.open;
+                let swap = self.test.swap(&self.swap_account(&self.secrets[secret]));
+                assert_eq!(
+                    swap.map(|swap| swap.amount_lamports),
+                    expected.open.map(|swap| swap.amount_lamports),
+                    "{operation:?} left the swap account inconsistent"
+                );
+                if swap.is_some() {
+                    self.last_seen[secret] = swap;
+                }
+            }
+            (_, Err(failed)) => {
+                assert_eq!(after, before, "failed {operation:?} moved funds");
+                assert!(
+                    expected.is_none() || substituted,
+                    "{operation:?} unexpectedly failed: {}\n{}",
+                    failed.err,
+                    failed.meta.pretty_logs()
+                );
+            }
+            (None, Ok(_)) => panic!("{operation:?} unexpectedly succeeded"),
+        }
+    }
+
+    /// The instruction of `operation`, its signers besides the payer, and its expected outcome
+    /// if it is to succeed
+    fn instruction(
+        &self,
+        operation: Operation,
+    ) -> Option<(Instruction, Vec<Party>, Option<Expected>)> {
+        let before = self.balances();
+        let rent = self.test.swap_rent();
+        let slot = self.test.slot();
+        let secret = operation_secret(operation);
+        let open = self.open[secret];
+
+        if let Operation::Initiate {
+            amount_lamports,
+            expires_in_slots,
+            ..
+        } = operation
+        {
+            let initiate = InitiateBuilder::new(
+                self.test.initiator.pubkey(),
+                self.test.redeemer.pubkey(),
+                secret_hash(&self.secrets[secret]),
+                amount_lamports,
+                expires_in_slots,
+            );
+            let expected = open.is_none().then(|| Expected {
+                balances: Balances {
+                    initiator: before.initiator - amount_lamports - rent,
+                    swap_accounts: before.swap_accounts + amount_lamports + rent,
+                    ..before
+                },
+                open: Some(OpenSwap {
+                    amount_lamports,
+                    expiry_slot: slot + expires_in_slots,
+                }),
+            });
+            return Some((initiate.instruction(), vec![Party::Initiator], expected));
+        }
+
+        let swap = self.last_seen[secret]?;
+        let closed = |open: OpenSwap| Balances {
+            initiator: before.initiator + open.amount_lamports + rent,
+            swap_accounts: before.swap_accounts - open.amount_lamports - rent,
+            ..before
+        };
+        Some(match operation {
+            Operation::Redeem {
+                correct_secret,
+                amount_lamports,
+                relayed,
+                ..
+            } => {
+                let preimage = match correct_secret {
+                    true => self.secrets[secret],
+                    false => self.secrets[(secret + 1) % SECRETS],
+                };
+                let mut redeem =
+                    RedeemBuilder::new(&swap, preimage).amount_lamports(amount_lamports);
+                let mut signers = vec![];
+                if relayed {
+                    redeem = redeem.relayer(self.attacker.pubkey());
+                    signers.push(Party::Attacker);
+                }
+                let expected = open
+                    .filter(|open| {
+                        correct_secret
+                            && amount_lamports > 0
+                            && amount_lamports <= open.amount_lamports
+                    })
+                    .map(|open| {
+                        let fee = protocol_fee(amount_lamports);
+                        let remaining = open.amount_lamports - amount_lamports;
+                        let freed_rent = if remaining == 0 { rent } else { 0 };
+                        Expected {
+                            balances: Balances {
+                                initiator: before.initiator + freed_rent,
+                                redeemer: before.redeemer + amount_lamports - fee,
+                                treasury: before.treasury + fee,
+                                swap_accounts: before.swap_accounts - amount_lamports - freed_rent,
+                                ..before
+                            },
+                            open: (remaining > 0).then_some(OpenSwap {
+                                amount_lamports: remaining,
+                                ..open
+                            }),
+                        }
+                    });
+                (redeem.instruction(), signers, expected)
+            }
+            Operation::Refund { tipped, .. } => {
+                let mut refund = RefundBuilder::new(&swap);
+                let mut signers = vec![];
+                if tipped {
+                    refund = refund.refunder(self.attacker.pubkey());
+                    signers.push(Party::Attacker);
+                }
+                let expected = open
+                    .filter(|open| slot >= open.expiry_slot)
+                    .map(|open| Expected {
+                        balances: closed(open),
+                        open: None,
+                    });
+                (refund.instruction(), signers, expected)
+            }
+            Operation::InstantRefund { consented, .. } => {
+                let mut instant_refund = InstantRefundBuilder::new(&swap).instruction();
+                let mut signers = vec![Party::Redeemer];
+                if !consented {
+                    let redeemer = self.test.redeemer.pubkey();
+                    for account in &mut instant_refund.accounts {
+                        if account.pubkey == redeemer {
+                            account.is_signer = false;
+                        }
+                    }
+                    signers.clear();
+                }
+                let expected = open.filter(|_| consented).map(|open| Expected {
+                    balances: closed(open),
+                    open: None,
+                });
+                (instant_refund, signers, expected)
+            }
+            Operation::Initiate { .. } | Operation::Warp(_) => unreachable!(),
+        })
+    }
+
+    /// Substitutes a random account of `instruction` with the attacker's
+    fn substitute(&mut self, instruction: &mut Instruction) {
+        let attacker = self.attacker.pubkey();
+        let candidates: Vec<usize> = (0..instruction.accounts.len())
+            .filter(|&index| instruction.accounts[index].pubkey != attacker)
+            .collect();
+        let index = candidates[self.rng.gen_range(0..candidates.len())];
+        instruction.accounts[index].pubkey = attacker;
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Party {
+    Initiator,
+    Redeemer,
+    Attacker,
+}
+
+/// The expected outcome of a successful operation
+#[derive(Clone, Copy, Debug)]
+struct Expected {
+    balances: Balances,
+    /// The swap locked to the operation's secret, if still open
+    open: Option<OpenSwap>,
+}
+
+fn operation_secret(operation: Operation) -> usize {
+    match operation {
+        Operation::Initiate { secret, .. }
+        | Operation::Redeem { secret, .. }
+        | Operation::Refund { secret, .. }
+        | Operation::InstantRefund { secret, .. } => secret,
+        Operation::Warp(_) => unreachable!(),
+    }
+}
+
+fn protocol_fee(amount_lamports: u64) -> u64 {
+    (u128::from(amount_lamports) * u128::from(PROTOCOL_FEE_BPS) / 10_000) as u64
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().map(|value| value.parse().unwrap())
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn fuzz_instruction_sequences() {
+    let seed = env_u64("SWAPS_FUZZ_SEED").unwrap_or_else(rand::random);
+    let runs = env_u64("SWAPS_FUZZ_RUNS").unwrap_or(20);
+    println!("Fuzzing {runs} runs with SWAPS_FUZZ_SEED={seed}");
+
+    for run in 0..runs {
+        let mut fuzzer = Fuzzer::new(seed.wrapping_add(run));
+        for _ in 0..OPERATIONS_PER_RUN {
+            let operation = fuzzer.operation();
+            fuzzer.apply(operation);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! The secret hash algorithms other than SHA-256, checked against known vectors and through the
+//! lifecycle of a swap
+
+use solana_native_swaps::SwapError;
+use solana_native_swaps_client::{HashAlgorithm, InitiateBuilder, Pubkey, RedeemBuilder};
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// `digest` as a secret hash, padded with trailing zeroes
+fn secret_hash(digest: &str) -> [u8; 32] {
+    let mut secret_hash = [0; 32];
+    let digest = hex::decode(digest).unwrap();
+    secret_hash[..digest.len()].copy_from_slice(&digest);
+    secret_hash
+}
+
+/// Initiates a swap locked to the `hash_algorithm` hash of `SECRET`, returning its swap account
+fn initiate(test: &mut SwapTest, hash_algorithm: HashAlgorithm) -> Pubkey {
+    let initiator = test.initiator.insecure_clone();
+    let initiate = InitiateBuilder::new(
+        initiator.pubkey(),
+        test.redeemer.pubkey(),
+        hash_algorithm.hash(&SECRET),
+        AMOUNT_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    )
+    .hash_algorithm(hash_algorithm);
+    test.send(&[initiate.instruction()], &[&initiator]).unwrap();
+    initiate.swap_account()
+}
+
+/// Redeems a swap locked to the `hash_algorithm` hash of `SECRET`, which only the secret does
+fn redeems_with_the_secret_only(hash_algorithm: HashAlgorithm) {
+    let mut test = SwapTest::new().unwrap();
+    let redeemer = test.redeemer.pubkey();
+    let swap_account = initiate(&mut test, hash_algorithm);
+    let swap = test.swap(&swap_account).unwrap();
+    assert_eq!(swap.hash_algorithm, hash_algorithm as u8);
+    let redeemer_before = test.balance(&redeemer);
+
+    assert_swap_error(
+        test.send(&[RedeemBuilder::new(&swap, [8; 32]).instruction()], &[]),
+        SwapError::InvalidSecret,
+    );
+    // The SHA-256 preimage of the secret hash is no secret under another algorithm
+    assert_swap_error(
+        test.send(
+            &[RedeemBuilder::new(&swap, HashAlgorithm::Sha256.hash(&SECRET)).instruction()],
+            &[],
+        ),
+        SwapError::InvalidSecret,
+    );
+
+    test.send(&[RedeemBuilder::new(&swap, SECRET).instruction()], &[])
+        .unwrap();
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(
+        test.balance(&redeemer),
+        redeemer_before + AMOUNT_LAMPORTS - test.program.protocol_fee(AMOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+fn hash160_matches_known_vectors() {
+    // RIPEMD160(SHA256(secret)), as computed by Bitcoin's OP_HASH160
+    assert_eq!(
+        HashAlgorithm::Hash160.hash(&[0; 32]),
+        secret_hash("b8bcb07f6344b42ab04250c86a6e8b75d3fdbbc6")
+    );
+    assert_eq!(
+        HashAlgorithm::Hash160.hash(&SECRET),
+        secret_hash("b566a3eecce809896361988823cd2f423fe800e7")
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn hash160_swaps_are_redeemed_with_the_secret_only() {
+    redeems_with_the_secret_only(HashAlgorithm::Hash160);
+}
+
+#[test]
+fn blake3_matches_known_vectors() {
+    assert_eq!(
+        HashAlgorithm::Blake3.hash(&[0; 32]),
+        secret_hash("2ada83c1819a5372dae1238fc1ded123c8104fdaa15862aaee69428a1820fcda")
+    );
+    assert_eq!(
+        HashAlgorithm::Blake3.hash(&SECRET),
+        secret_hash("ebaf85b465a09de21b398fb112c1500f2cbe658c42f379e0c0f18d24b819f637")
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn blake3_swaps_are_redeemed_with_the_secret_only() {
+    redeems_with_the_secret_only(HashAlgorithm::Blake3);
+}
@@ -0,0 +1,142 @@
+//! Series of recurring swaps committed to a hash chain, each revealing the next preimage
+
+use anchor_lang::{prelude::Pubkey, solana_program::hash::hash, InstructionData, ToAccountMetas};
+use solana_native_swaps::{accounts, instruction, pda, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const SWAP_COUNT: u32 = 3;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// The hash chain of `SECRET`, from `SECRET` itself up to the tip H^SWAP_COUNT(SECRET)
+fn hash_chain() -> Vec<[u8; 32]> {
+    let mut chain = vec![SECRET];
+    for _ in 0..SWAP_COUNT {
+        chain.push(hash(chain.last().unwrap()).to_bytes());
+    }
+    chain
+}
+
+struct Series {
+    test: SwapTest,
+    swap_account: Pubkey,
+}
+
+impl Series {
+    fn initiate() -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+        let chain_tip = *hash_chain().last().unwrap();
+        let swap_account = pda::hash_chain_swap_account(&initiator, &redeemer, &chain_tip).0;
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateHashChain {
+                amount_lamports: AMOUNT_LAMPORTS,
+                swap_count: SWAP_COUNT,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer,
+                chain_tip,
+            }
+            .data(),
+            accounts::InitiateHashChain {
+                swap_account,
+                config: pda::config().0,
+                initiator,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let initiator = test.initiator.insecure_clone();
+        test.send(&[initiate], &[&initiator]).unwrap();
+        Series { test, swap_account }
+    }
+
+    fn redeem_instruction(&self, secret: [u8; 32]) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemHashChain { secret }.data(),
+            accounts::RedeemHashChain {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn refund_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundHashChain {}.data(),
+            accounts::RefundHashChain {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn each_swap_reveals_the_next_preimage_of_the_chain() {
+    let mut series = Series::initiate();
+    let redeemer = series.test.redeemer.pubkey();
+    let before = series.test.balance(&redeemer);
+    let chain = hash_chain();
+
+    // Preimages must be revealed in order, from the tip down to the secret
+    assert_swap_error(
+        series.test.send(&[series.redeem_instruction(SECRET)], &[]),
+        SwapError::InvalidSecret,
+    );
+    for (redeemed, preimage) in chain[..SWAP_COUNT as usize].iter().rev().enumerate() {
+        series
+            .test
+            .send(&[series.redeem_instruction(*preimage)], &[])
+            .unwrap();
+        assert_eq!(
+            series.test.balance(&redeemer),
+            before + AMOUNT_LAMPORTS * (redeemed as u64 + 1)
+        );
+    }
+    assert!(series.test.svm.get_account(&series.swap_account).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn remaining_swaps_are_refunded_past_expiry() {
+    let mut series = Series::initiate();
+    let initiator = series.test.initiator.pubkey();
+    let chain = hash_chain();
+    series
+        .test
+        .send(
+            &[series.redeem_instruction(chain[SWAP_COUNT as usize - 1])],
+            &[],
+        )
+        .unwrap();
+
+    assert_swap_error(
+        series.test.send(&[series.refund_instruction()], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+    series.test.warp(EXPIRES_IN_SLOTS);
+    let before = series.test.balance(&initiator);
+    let escrowed = series.test.balance(&series.swap_account);
+    series
+        .test
+        .send(&[series.refund_instruction()], &[])
+        .unwrap();
+
+    assert_eq!(series.test.balance(&initiator), before + escrowed);
+}
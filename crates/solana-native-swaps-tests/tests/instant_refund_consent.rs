@@ -0,0 +1,97 @@
+//! Instant refunds with the redeemer's consent given as a pre-signed ed25519 signature
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_native_swaps::{accounts, instruction, INSTANT_REFUND_CONSENT_PREFIX};
+use solana_native_swaps_client::{pda, Instruction, SwapAccount};
+use solana_native_swaps_tests::SwapTest;
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// The redeemer's consent to an instant refund of `swap`, verified by the ed25519 program
+fn consent(test: &SwapTest, swap_account: &Pubkey, swap: &SwapAccount) -> Instruction {
+    let message = swap.signed_message(INSTANT_REFUND_CONSENT_PREFIX, swap_account);
+    let signature = test.redeemer.sign_message(&message);
+    solana_ed25519_program::new_ed25519_instruction_with_signature(
+        &message,
+        &signature.into(),
+        &test.redeemer.pubkey().to_bytes(),
+    )
+}
+
+fn instant_refund_with_signature(test: &SwapTest, swap_account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::InstantRefundWithSignature {}.data(),
+        accounts::InstantRefundWithSignature {
+            swap_account: *swap_account,
+            initiator: test.initiator.pubkey(),
+            refund_to: None,
+            redeemer: None,
+            instructions_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn pre_signed_consent_refunds_the_swap() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+
+    test.send(
+        &[
+            consent(&test, &swap_account, &swap),
+            instant_refund_with_signature(&test, &swap_account),
+        ],
+        &[],
+    )
+    .unwrap();
+
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(test.balance(&initiator), before);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn consent_is_not_replayable_against_a_later_swap_at_the_same_address() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+    let consent = consent(&test, &swap_account, &swap);
+    test.send(
+        &[
+            consent.clone(),
+            instant_refund_with_signature(&test, &swap_account),
+        ],
+        &[],
+    )
+    .unwrap();
+
+    // The same parties and secret hash derive the same address for the next swap
+    test.warp(1);
+    let later = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    assert_eq!(later, swap_account);
+
+    assert!(test
+        .send(
+            &[consent, instant_refund_with_signature(&test, &swap_account)],
+            &[],
+        )
+        .is_err());
+    assert!(test.swap(&swap_account).is_some());
+}
@@ -0,0 +1,227 @@
+use solana_native_swaps::SwapError;
+use solana_native_swaps_client::{pda, InstantRefundBuilder, RedeemBuilder, RefundBuilder};
+use solana_native_swaps_tests::{
+    assert_swap_error, secret_hash, Program, SwapTest, PROTOCOL_FEE_BPS,
+};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+fn protocol_fee(amount_lamports: u64) -> u64 {
+    amount_lamports * u64::from(PROTOCOL_FEE_BPS) / 10_000
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn initiate_escrows_the_swap_amount() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+
+    let swap = test.swap(&swap_account).unwrap();
+    assert_eq!(swap.initiator, initiator);
+    assert_eq!(swap.redeemer, test.redeemer.pubkey());
+    assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+    assert_eq!(swap.expiry_slot, test.slot() + EXPIRES_IN_SLOTS);
+    assert_eq!(
+        test.balance(&swap_account),
+        AMOUNT_LAMPORTS + test.swap_rent()
+    );
+    assert_eq!(
+        test.balance(&initiator),
+        before - AMOUNT_LAMPORTS - test.swap_rent()
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn initiate_twice_with_the_same_secret_hash_fails() {
+    let mut test = SwapTest::new().unwrap();
+    test.initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    assert!(test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .is_err());
+}
+
+#[test]
+#[ignore = "requires both programs to be built with `cargo build-sbf`"]
+fn lamports_sent_to_the_swap_address_ahead_of_initiate_count_towards_it() {
+    for program in Program::ALL {
+        let mut test = SwapTest::with_program(program).unwrap();
+        let initiator = test.initiator.pubkey();
+        let swap_account = test.swap_account(&secret_hash(&SECRET));
+        // A single lamport would otherwise fail the creation of the account
+        test.svm.airdrop(&swap_account, 1).unwrap();
+        let before = test.balance(&initiator);
+
+        test.initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+            .unwrap();
+
+        assert_eq!(
+            test.balance(&swap_account),
+            AMOUNT_LAMPORTS + test.swap_rent(),
+            "{program:?}"
+        );
+        assert_eq!(
+            test.balance(&initiator),
+            before - AMOUNT_LAMPORTS - test.swap_rent() + 1,
+            "{program:?}"
+        );
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeem_with_the_secret_pays_the_redeemer() {
+    let mut test = SwapTest::new().unwrap();
+    let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+    let (initiator_before, redeemer_before) = (test.balance(&initiator), test.balance(&redeemer));
+    let treasury_before = test.balance(&pda::treasury().0);
+
+    test.send(&[RedeemBuilder::new(&swap, SECRET).instruction()], &[])
+        .unwrap();
+
+    let fee = protocol_fee(AMOUNT_LAMPORTS);
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(test.balance(&swap_account), 0);
+    assert_eq!(
+        test.balance(&redeemer),
+        redeemer_before + AMOUNT_LAMPORTS - fee
+    );
+    assert_eq!(test.balance(&pda::treasury().0), treasury_before + fee);
+    assert_eq!(
+        test.balance(&initiator),
+        initiator_before + test.swap_rent()
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeem_in_tranches_closes_the_swap_once_fully_redeemed() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+
+    let tranche = AMOUNT_LAMPORTS / 4;
+    test.send(
+        &[RedeemBuilder::new(&swap, SECRET)
+            .amount_lamports(tranche)
+            .instruction()],
+        &[],
+    )
+    .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+    assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS - tranche);
+
+    test.send(&[RedeemBuilder::new(&swap, SECRET).instruction()], &[])
+        .unwrap();
+    assert!(test.swap(&swap_account).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeem_with_a_wrong_secret_fails() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+
+    let result = test.send(&[RedeemBuilder::new(&swap, [8; 32]).instruction()], &[]);
+
+    assert_swap_error(result, SwapError::InvalidSecret);
+    assert_eq!(
+        test.swap(&swap_account).unwrap().amount_lamports,
+        AMOUNT_LAMPORTS
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn refund_before_expiry_fails() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+    test.warp(EXPIRES_IN_SLOTS - 1);
+
+    let result = test.send(&[RefundBuilder::new(&swap).instruction()], &[]);
+
+    assert_swap_error(result, SwapError::RefundBeforeExpiry);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn refund_at_expiry_returns_the_funds_to_the_initiator() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+    test.warp(EXPIRES_IN_SLOTS);
+
+    test.send(&[RefundBuilder::new(&swap).instruction()], &[])
+        .unwrap();
+
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(test.balance(&initiator), before);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn instant_refund_with_the_redeemers_consent_returns_the_funds() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+
+    let redeemer = test.redeemer.insecure_clone();
+    test.send(
+        &[InstantRefundBuilder::new(&swap).instruction()],
+        &[&redeemer],
+    )
+    .unwrap();
+
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(test.balance(&initiator), before);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn instant_refund_without_the_redeemers_signature_fails() {
+    let mut test = SwapTest::new().unwrap();
+    let redeemer = test.redeemer.pubkey();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+
+    let mut instant_refund = InstantRefundBuilder::new(&swap).instruction();
+    for account in &mut instant_refund.accounts {
+        if account.pubkey == redeemer {
+            account.is_signer = false;
+        }
+    }
+
+    assert!(test.send(&[instant_refund], &[]).is_err());
+    assert!(test.swap(&swap_account).is_some());
+}
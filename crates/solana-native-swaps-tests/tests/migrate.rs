@@ -0,0 +1,241 @@
+//! Upgrades of swap accounts created under previous layout versions through `migrate`
+
+use anchor_lang::{prelude::Pubkey, Discriminator, InstructionData, ToAccountMetas};
+use solana_account::Account;
+use solana_native_swaps::{accounts, instruction, SwapError};
+use solana_native_swaps_client::{
+    pda, HashAlgorithm, Instruction, RedeemBuilder, RefundBuilder, SwapAccount,
+    SWAP_ACCOUNT_VERSION,
+};
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRY_SLOT: u64 = 1_000;
+const SECRET: [u8; 32] = [7; 32];
+
+fn migrate_instruction(test: &SwapTest, swap_account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::Migrate {}.data(),
+        accounts::Migrate {
+            swap_account: *swap_account,
+            payer: test.payer.pubkey(),
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+/// Writes a swap account of `data` at the address its seeds under `SWAP_SEEDS_VERSION` 0
+/// derive, holding the swap amount on top of its rent
+fn set_legacy_swap_account(test: &mut SwapTest, data: Vec<u8>) -> Pubkey {
+    let (swap_account, _) =
+        pda::legacy_swap_account(&test.initiator.pubkey(), &secret_hash(&SECRET));
+    let account = Account {
+        lamports: test.svm.minimum_balance_for_rent_exemption(data.len()) + AMOUNT_LAMPORTS,
+        data,
+        owner: solana_native_swaps::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    test.svm.set_account(swap_account, account).unwrap();
+    swap_account
+}
+
+/// Asserts the swap at `swap_account` has been upgraded to the current layout, with the
+/// fields common to every layout carried over
+fn assert_migrated(test: &SwapTest, swap_account: &Pubkey) -> SwapAccount {
+    let swap = test
+        .swap(swap_account)
+        .expect("swap is at the current layout");
+    assert_eq!(swap.version, SWAP_ACCOUNT_VERSION);
+    assert_eq!(swap.seeds_version, 0);
+    assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+    assert_eq!(swap.expiry_slot, EXPIRY_SLOT);
+    assert_eq!(swap.initiator, test.initiator.pubkey());
+    assert_eq!(swap.redeemer, test.redeemer.pubkey());
+    assert_eq!(swap.secret_hash, secret_hash(&SECRET));
+    assert_eq!(pda::swap_account_of(&swap).0, *swap_account);
+    swap
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn borsh_encoded_swaps_are_migrated_to_the_zero_copy_layout() {
+    let mut test = SwapTest::new().unwrap();
+    let (_, bump) = pda::legacy_swap_account(&test.initiator.pubkey(), &secret_hash(&SECRET));
+    let refund_to = Pubkey::new_unique();
+    // The Borsh encoding of a version 1 swap account
+    let data = [
+        SwapAccount::DISCRIMINATOR,
+        &[1],
+        &AMOUNT_LAMPORTS.to_le_bytes(),
+        &EXPIRY_SLOT.to_le_bytes(),
+        // No expiry timestamp, leaving its 8 bytes unused
+        &[0],
+        test.initiator.pubkey().as_ref(),
+        test.redeemer.pubkey().as_ref(),
+        &secret_hash(&SECRET),
+        &[HashAlgorithm::Sha256 as u8],
+        &5_000u64.to_le_bytes(),
+        &0u64.to_le_bytes(),
+        &[1],
+        refund_to.as_ref(),
+        // No redeem window
+        &[0],
+        &[0],
+        &[bump],
+        &10u64.to_le_bytes(),
+        &1_700_000_000i64.to_le_bytes(),
+        &[0; 24],
+    ]
+    .concat();
+    assert_eq!(data.len(), 215);
+    let swap_account = set_legacy_swap_account(&mut test, data);
+
+    let migrate = migrate_instruction(&test, &swap_account);
+    test.send(std::slice::from_ref(&migrate), &[]).unwrap();
+    let swap = assert_migrated(&test, &swap_account);
+    assert_eq!(swap.bump, bump);
+    assert_eq!(swap.relayer_fee_lamports, 5_000);
+    assert_eq!(swap.relayer_fee_start_lamports, 5_000);
+    assert_eq!(swap.refund_to(), Some(refund_to));
+    assert_eq!(swap.expiry_timestamp(), None);
+    assert_eq!(swap.created_at_slot, 10);
+
+    // The rent of the larger layout is paid by the payer, leaving the swap amount intact
+    let account = test.svm.get_account(&swap_account).unwrap();
+    assert_eq!(
+        account.lamports,
+        test.svm
+            .minimum_balance_for_rent_exemption(account.data.len())
+            + AMOUNT_LAMPORTS
+    );
+    assert_swap_error(test.send(&[migrate], &[]), SwapError::SwapAlreadyMigrated);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn swaps_initiated_before_the_layout_was_versioned_are_migrated() {
+    let mut test = SwapTest::new().unwrap();
+    let (_, bump) = pda::legacy_swap_account(&test.initiator.pubkey(), &secret_hash(&SECRET));
+    // The layout of the program as first deployed, with no version byte, the first byte being
+    // that of the amount instead
+    let data = [
+        SwapAccount::DISCRIMINATOR,
+        &AMOUNT_LAMPORTS.to_le_bytes(),
+        &EXPIRY_SLOT.to_le_bytes(),
+        test.initiator.pubkey().as_ref(),
+        test.redeemer.pubkey().as_ref(),
+        &secret_hash(&SECRET),
+    ]
+    .concat();
+    assert_eq!(data.len(), 120);
+    let swap_account = set_legacy_swap_account(&mut test, data);
+
+    test.send(&[migrate_instruction(&test, &swap_account)], &[])
+        .unwrap();
+    let swap = test
+        .swap(&swap_account)
+        .expect("swap is at the current layout");
+    assert_eq!(swap.version, SWAP_ACCOUNT_VERSION);
+    assert_eq!(swap.seeds_version, 0);
+    assert_eq!(swap.bump, bump);
+    assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+    // Such swaps were refundable only after their expiry slot
+    assert_eq!(swap.expiry_slot, EXPIRY_SLOT + 1);
+    assert_eq!(swap.initiator, test.initiator.pubkey());
+    assert_eq!(swap.redeemer, test.redeemer.pubkey());
+    assert_eq!(swap.secret_hash, secret_hash(&SECRET));
+    assert_eq!(swap.hash_algorithm, HashAlgorithm::Sha256 as u8);
+    assert_eq!(swap.refund_to(), None);
+    assert_eq!(pda::swap_account_of(&swap).0, swap_account);
+
+    // The swap remains refundable to its initiator under its original seeds
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    test.warp(EXPIRY_SLOT + 1 - test.slot());
+    test.send(&[RefundBuilder::new(&swap).instruction()], &[])
+        .unwrap();
+    assert!(test.balance(&initiator) > before + AMOUNT_LAMPORTS);
+    assert!(test.svm.get_account(&swap_account).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn unversioned_swaps_are_only_migrated_at_their_own_address() {
+    let mut test = SwapTest::new().unwrap();
+    // A swap whose seeds do not derive the address it is held at
+    let data = [
+        SwapAccount::DISCRIMINATOR,
+        &AMOUNT_LAMPORTS.to_le_bytes(),
+        &EXPIRY_SLOT.to_le_bytes(),
+        test.redeemer.pubkey().as_ref(),
+        test.initiator.pubkey().as_ref(),
+        &secret_hash(&SECRET),
+    ]
+    .concat();
+    let swap_account = set_legacy_swap_account(&mut test, data);
+
+    assert_swap_error(
+        test.send(&[migrate_instruction(&test, &swap_account)], &[]),
+        SwapError::InvalidSwapAccount,
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn swaps_of_unknown_layouts_are_not_migrated() {
+    let mut test = SwapTest::new().unwrap();
+    let data = [SwapAccount::DISCRIMINATOR, &[1], &[0; 100]].concat();
+    let swap_account = set_legacy_swap_account(&mut test, data);
+
+    assert_swap_error(
+        test.send(&[migrate_instruction(&test, &swap_account)], &[]),
+        SwapError::UnsupportedSwapVersion,
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn migrated_swaps_are_redeemable() {
+    let mut test = SwapTest::new().unwrap();
+    let (_, bump) = pda::legacy_swap_account(&test.initiator.pubkey(), &secret_hash(&SECRET));
+    let data = [
+        SwapAccount::DISCRIMINATOR,
+        &[1],
+        &AMOUNT_LAMPORTS.to_le_bytes(),
+        &EXPIRY_SLOT.to_le_bytes(),
+        &[0],
+        test.initiator.pubkey().as_ref(),
+        test.redeemer.pubkey().as_ref(),
+        &secret_hash(&SECRET),
+        &[HashAlgorithm::Sha256 as u8],
+        &0u64.to_le_bytes(),
+        &0u64.to_le_bytes(),
+        &[0],
+        &[0],
+        &[0],
+        &[bump],
+        &0u64.to_le_bytes(),
+        &0i64.to_le_bytes(),
+        &[0; 56],
+    ]
+    .concat();
+    assert_eq!(data.len(), 215);
+    let swap_account = set_legacy_swap_account(&mut test, data);
+    test.send(&[migrate_instruction(&test, &swap_account)], &[])
+        .unwrap();
+
+    let swap = assert_migrated(&test, &swap_account);
+    let redeemer = test.redeemer.pubkey();
+    let before = test.balance(&redeemer);
+    test.send(&[RedeemBuilder::new(&swap, SECRET).instruction()], &[])
+        .unwrap();
+    assert_eq!(
+        test.balance(&redeemer),
+        before + AMOUNT_LAMPORTS - test.program.protocol_fee(AMOUNT_LAMPORTS)
+    );
+    assert!(test.svm.get_account(&swap_account).is_none());
+}
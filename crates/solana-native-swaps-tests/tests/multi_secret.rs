@@ -0,0 +1,103 @@
+//! Swaps committed to several secret hashes, all of whose secrets must be revealed to redeem
+
+use anchor_lang::InstructionData;
+use solana_native_swaps::{
+    combined_secret_hash, instruction, HashAlgorithm, RedeemWindow, SwapError, SwapIncentives,
+};
+use solana_native_swaps_client::{pda, InitiateBuilder, Instruction, RedeemBuilder};
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRETS: [[u8; 32]; 3] = [[7; 32], [8; 32], [9; 32]];
+
+fn combined() -> [u8; 32] {
+    combined_secret_hash(&SECRETS.map(|secret| secret_hash(&secret)))
+}
+
+/// The `initiate_multi_secret` instruction committing to the secret hashes of `SECRETS`, with
+/// `commitment` as their combined secret hash
+fn initiate_instruction(test: &SwapTest, commitment: [u8; 32]) -> Instruction {
+    let redeemer = test.redeemer.pubkey();
+    let mut initiate = InitiateBuilder::new(
+        test.initiator.pubkey(),
+        redeemer,
+        commitment,
+        AMOUNT_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    )
+    .instruction();
+    // Shares the accounts of `initiate`
+    initiate.data = instruction::InitiateMultiSecret {
+        amount_lamports: AMOUNT_LAMPORTS,
+        expires_in_slots: EXPIRES_IN_SLOTS,
+        redeemer,
+        secret_hash: commitment,
+        hash_algorithm: HashAlgorithm::Sha256,
+        incentives: SwapIncentives::default(),
+        redeem_window: RedeemWindow::default(),
+        secret_hashes: SECRETS.map(|secret| secret_hash(&secret)).to_vec(),
+    }
+    .data();
+    initiate
+}
+
+fn redeem_instruction(test: &SwapTest, secrets: &[[u8; 32]]) -> Instruction {
+    let swap_account = pda::swap_account(
+        &test.initiator.pubkey(),
+        &test.redeemer.pubkey(),
+        &combined(),
+    )
+    .0;
+    let swap = test.swap(&swap_account).unwrap();
+    // Shares the accounts of `redeem`
+    let mut redeem = RedeemBuilder::new(&swap, secrets[0]).instruction();
+    redeem.data = instruction::RedeemMultiSecret {
+        secrets: secrets.to_vec(),
+        amount_lamports: AMOUNT_LAMPORTS,
+    }
+    .data();
+    redeem
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeems_require_every_secret() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let initiate = initiate_instruction(&test, combined());
+    test.send(&[initiate], &[&initiator]).unwrap();
+    let redeemer = test.redeemer.pubkey();
+    let before = test.balance(&redeemer);
+
+    assert_swap_error(
+        test.send(&[redeem_instruction(&test, &SECRETS[..2])], &[]),
+        SwapError::InvalidSecret,
+    );
+    let mut reordered = SECRETS;
+    reordered.swap(0, 1);
+    assert_swap_error(
+        test.send(&[redeem_instruction(&test, &reordered)], &[]),
+        SwapError::InvalidSecret,
+    );
+    test.send(&[redeem_instruction(&test, &SECRETS)], &[])
+        .unwrap();
+
+    assert_eq!(
+        test.balance(&redeemer),
+        before + AMOUNT_LAMPORTS - test.program.protocol_fee(AMOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn initiates_must_commit_to_the_combined_secret_hash() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let initiate = initiate_instruction(&test, secret_hash(&SECRETS[0]));
+    assert_swap_error(
+        test.send(&[initiate], &[&initiator]),
+        SwapError::InvalidCombinedSecretHash,
+    );
+}
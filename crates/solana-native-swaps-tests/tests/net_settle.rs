@@ -0,0 +1,80 @@
+//! Net settlements of opposing swaps between the same pair of parties
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_native_swaps::{accounts, instruction};
+use solana_native_swaps_client::{pda, InitiateBuilder, Instruction};
+use solana_native_swaps_tests::{secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_A_LAMPORTS: u64 = 3_000_000_000;
+const AMOUNT_B_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+
+fn net_settle_instruction(test: &SwapTest, swap_a: Pubkey, swap_b: Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::NetSettle {}.data(),
+        accounts::NetSettle {
+            swap_a,
+            swap_b,
+            party_a: test.initiator.pubkey(),
+            party_b: test.redeemer.pubkey(),
+            config: pda::config().0,
+            treasury: pda::treasury().0,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn net_settlements_charge_the_protocol_fee_on_the_difference() {
+    let mut test = SwapTest::new().unwrap();
+    let (party_a, party_b) = (
+        test.initiator.insecure_clone(),
+        test.redeemer.insecure_clone(),
+    );
+    let swap_a = InitiateBuilder::new(
+        party_a.pubkey(),
+        party_b.pubkey(),
+        secret_hash(&[7; 32]),
+        AMOUNT_A_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    );
+    let swap_b = InitiateBuilder::new(
+        party_b.pubkey(),
+        party_a.pubkey(),
+        secret_hash(&[8; 32]),
+        AMOUNT_B_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    );
+    test.send(
+        &[swap_a.instruction(), swap_b.instruction()],
+        &[&party_a, &party_b],
+    )
+    .unwrap();
+    let (a_before, b_before) = (
+        test.balance(&party_a.pubkey()),
+        test.balance(&party_b.pubkey()),
+    );
+    let treasury_before = test.balance(&pda::treasury().0);
+
+    let settle = net_settle_instruction(&test, swap_a.swap_account(), swap_b.swap_account());
+    test.send(&[settle], &[&party_a, &party_b]).unwrap();
+
+    let difference = AMOUNT_A_LAMPORTS - AMOUNT_B_LAMPORTS;
+    let fee = test.program.protocol_fee(difference);
+    assert!(test.swap(&swap_a.swap_account()).is_none());
+    assert!(test.swap(&swap_b.swap_account()).is_none());
+    assert_eq!(test.balance(&pda::treasury().0), treasury_before + fee);
+    assert_eq!(
+        test.balance(&party_a.pubkey()),
+        a_before + AMOUNT_B_LAMPORTS + test.swap_rent()
+    );
+    assert_eq!(
+        test.balance(&party_b.pubkey()),
+        b_before + difference - fee + AMOUNT_B_LAMPORTS + test.swap_rent()
+    );
+}
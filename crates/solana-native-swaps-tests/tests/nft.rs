@@ -0,0 +1,200 @@
+//! NFT swaps, escrowing a single token of a mint verified to be non-fungible through its metadata
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::{
+    self,
+    instruction::{set_authority, AuthorityType},
+};
+use solana_account::Account;
+use solana_native_swaps::{accounts, instruction, pda, SwapError, TOKEN_METADATA_PROGRAM_ID};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// An NFT held by the initiator, with the token accounts of both parties
+struct Nft {
+    mint: Pubkey,
+    initiator_account: Pubkey,
+    redeemer_account: Pubkey,
+}
+
+/// Mints `supply` tokens of a mint with `decimals` to the initiator, then hands its mint
+/// authority to the one `mint_authority` returns for the mint, with a Metaplex metadata account
+/// standing in for its metadata
+fn mint_nft(
+    test: &mut SwapTest,
+    supply: u64,
+    decimals: u8,
+    mint_authority: impl Fn(&Pubkey) -> Option<Pubkey>,
+) -> Nft {
+    let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+    let mint = test.create_mint(decimals);
+    let initiator_account = test.create_token_account(&mint, &initiator);
+    test.mint_to(&mint, &initiator_account, supply);
+    let set_mint_authority = set_authority(
+        &spl_token::ID,
+        &mint,
+        mint_authority(&mint).as_ref(),
+        AuthorityType::MintTokens,
+        &test.payer.pubkey(),
+        &[],
+    )
+    .unwrap();
+    test.send(&[set_mint_authority], &[]).unwrap();
+
+    let metadata = Account {
+        lamports: 1_000_000_000,
+        data: vec![1; 679],
+        owner: TOKEN_METADATA_PROGRAM_ID,
+        ..Default::default()
+    };
+    test.svm
+        .set_account(pda::metaplex_metadata(&mint).0, metadata)
+        .unwrap();
+
+    Nft {
+        mint,
+        initiator_account,
+        redeemer_account: test.create_token_account(&mint, &redeemer),
+    }
+}
+
+fn swap_account(test: &SwapTest) -> Pubkey {
+    pda::nft_swap_account(
+        &test.initiator.pubkey(),
+        &test.redeemer.pubkey(),
+        &secret_hash(&SECRET),
+    )
+    .0
+}
+
+fn initiate_instruction(test: &SwapTest, nft: &Nft, metadata: Pubkey) -> Instruction {
+    let swap_account = swap_account(test);
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::InitiateNft {
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+        }
+        .data(),
+        accounts::InitiateNft {
+            swap_account,
+            nft_vault: pda::nft_vault(&swap_account).0,
+            initiator_token_account: nft.initiator_account,
+            mint: nft.mint,
+            metadata,
+            config: pda::config().0,
+            initiator: test.initiator.pubkey(),
+            token_program: spl_token::ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+fn initiate(test: &mut SwapTest, nft: &Nft) {
+    let initiate = initiate_instruction(test, nft, pda::metaplex_metadata(&nft.mint).0);
+    let initiator = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&initiator]).unwrap();
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn nfts_are_redeemed_with_the_secret() {
+    let mut test = SwapTest::new().unwrap();
+    // Metaplex NFTs keep their mint authority in their master edition
+    let nft = mint_nft(&mut test, 1, 0, |mint| {
+        Some(pda::metaplex_master_edition(mint).0)
+    });
+    initiate(&mut test, &nft);
+    let swap_account = swap_account(&test);
+    assert_eq!(test.token_balance(&pda::nft_vault(&swap_account).0), 1);
+
+    let redeem = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RedeemNft { secret: SECRET }.data(),
+        accounts::RedeemNft {
+            swap_account,
+            nft_vault: pda::nft_vault(&swap_account).0,
+            redeemer_token_account: nft.redeemer_account,
+            mint: nft.mint,
+            initiator: test.initiator.pubkey(),
+            token_program: spl_token::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    test.send(&[redeem], &[]).unwrap();
+
+    assert_eq!(test.token_balance(&nft.redeemer_account), 1);
+    assert!(test.svm.get_account(&swap_account).is_none());
+    assert!(test
+        .svm
+        .get_account(&pda::nft_vault(&swap_account).0)
+        .is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn nfts_are_refunded_past_expiry() {
+    let mut test = SwapTest::new().unwrap();
+    let nft = mint_nft(&mut test, 1, 0, |_| None);
+    initiate(&mut test, &nft);
+    let swap_account = swap_account(&test);
+
+    let refund = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RefundNft {}.data(),
+        accounts::RefundNft {
+            swap_account,
+            nft_vault: pda::nft_vault(&swap_account).0,
+            initiator_token_account: nft.initiator_account,
+            mint: nft.mint,
+            initiator: test.initiator.pubkey(),
+            token_program: spl_token::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    assert_swap_error(
+        test.send(std::slice::from_ref(&refund), &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+
+    test.warp(EXPIRES_IN_SLOTS);
+    test.send(&[refund], &[]).unwrap();
+    assert_eq!(test.token_balance(&nft.initiator_account), 1);
+    assert!(test.svm.get_account(&swap_account).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn only_nfts_are_initiated() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let payer = test.payer.pubkey();
+
+    for nft in [
+        mint_nft(&mut test, 2, 0, |_| None),
+        mint_nft(&mut test, 1, 1, |_| None),
+        mint_nft(&mut test, 1, 0, |_| Some(payer)),
+    ] {
+        let initiate = initiate_instruction(&test, &nft, pda::metaplex_metadata(&nft.mint).0);
+        assert_swap_error(test.send(&[initiate], &[&initiator]), SwapError::NotAnNft);
+    }
+
+    let nft = mint_nft(&mut test, 1, 0, |_| None);
+    let initiate = initiate_instruction(&test, &nft, Pubkey::new_unique());
+    assert_swap_error(
+        test.send(&[initiate], &[&initiator]),
+        SwapError::InvalidNftMetadata,
+    );
+}
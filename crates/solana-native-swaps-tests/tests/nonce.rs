@@ -0,0 +1,83 @@
+//! Swaps initiated with a nonce in their PDA's seeds, allowing multiple swaps between the same
+//! initiator and secret hash to be open at once.
+
+use solana_native_swaps::SwapError;
+use solana_native_swaps_client::{pda, InitiateBuilder, RedeemBuilder, RefundBuilder};
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+fn initiate_builder(test: &SwapTest, nonce: Option<u32>) -> InitiateBuilder {
+    let builder = InitiateBuilder::new(
+        test.initiator.pubkey(),
+        test.redeemer.pubkey(),
+        secret_hash(&SECRET),
+        AMOUNT_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    );
+    match nonce {
+        Some(nonce) => builder.nonce(nonce),
+        None => builder,
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn swaps_with_distinct_nonces_are_open_at_once() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let builders: Vec<_> = [None, Some(1), Some(2)]
+        .into_iter()
+        .map(|nonce| initiate_builder(&test, nonce))
+        .collect();
+    for builder in &builders {
+        test.send(&[builder.instruction()], &[&initiator]).unwrap();
+    }
+
+    let swap_accounts: Vec<_> = builders.iter().map(|b| b.swap_account()).collect();
+    assert_eq!(swap_accounts[0], test.swap_account(&secret_hash(&SECRET)));
+    for (swap_account, nonce) in swap_accounts.iter().zip([0, 1, 2]) {
+        let swap = test.swap(swap_account).unwrap();
+        assert_eq!(swap.nonce(), nonce);
+        assert_eq!(pda::swap_account_of(&swap).0, *swap_account);
+    }
+
+    // Each swap completes independently of the others
+    let nonced = test.swap(&swap_accounts[1]).unwrap();
+    test.send(&[RedeemBuilder::new(&nonced, SECRET).instruction()], &[])
+        .unwrap();
+    assert!(test.swap(&swap_accounts[1]).is_none());
+
+    test.warp(EXPIRES_IN_SLOTS);
+    for swap_account in [swap_accounts[0], swap_accounts[2]] {
+        let swap = test.swap(&swap_account).unwrap();
+        test.send(&[RefundBuilder::new(&swap).instruction()], &[])
+            .unwrap();
+        assert!(test.swap(&swap_account).is_none());
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn swaps_with_equal_nonces_are_not_open_at_once() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let initiate = initiate_builder(&test, Some(1)).instruction();
+    test.send(std::slice::from_ref(&initiate), &[&initiator])
+        .unwrap();
+
+    assert!(test.send(&[initiate], &[&initiator]).is_err());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn zero_nonces_are_rejected() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let initiate = initiate_builder(&test, Some(0)).instruction();
+
+    assert_swap_error(test.send(&[initiate], &[&initiator]), SwapError::ZeroNonce);
+}
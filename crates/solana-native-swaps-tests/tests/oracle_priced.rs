@@ -0,0 +1,199 @@
+//! Oracle-priced swaps, releasing native SOL worth a USD notional at the Pyth SOL/USD price
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_account::Account;
+use solana_native_swaps::{
+    accounts, instruction, pda, SwapError, PYTH_RECEIVER_PROGRAM_ID, PYTH_SOL_USD_FEED_ID,
+};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, clock, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 2_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+/// 150 USD
+const NOTIONAL_USD_MICROS: u64 = 150_000_000;
+const MAX_STALENESS_SECS: u64 = 60;
+const MAX_CONFIDENCE_BPS: u16 = 100;
+const MIN_LAMPORTS_OUT: u64 = 1_000_000_000;
+const MAX_LAMPORTS_OUT: u64 = 1_800_000_000;
+/// 100 USD per SOL, in Pyth's usual exponent of -8
+const PRICE: i64 = 10_000_000_000;
+const EXPONENT: i32 = -8;
+
+struct Oracle {
+    test: SwapTest,
+    swap_account: Pubkey,
+    price_update: Pubkey,
+}
+
+impl Oracle {
+    fn initiate() -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+        let swap_account = pda::oracle_swap_account(&initiator, &redeemer, &secret_hash(&SECRET)).0;
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateOraclePriced {
+                amount_lamports: AMOUNT_LAMPORTS,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer,
+                secret_hash: secret_hash(&SECRET),
+                notional_usd_micros: NOTIONAL_USD_MICROS,
+                max_staleness_secs: MAX_STALENESS_SECS,
+                max_confidence_bps: MAX_CONFIDENCE_BPS,
+                min_lamports_out: MIN_LAMPORTS_OUT,
+                max_lamports_out: MAX_LAMPORTS_OUT,
+            }
+            .data(),
+            accounts::InitiateOraclePriced {
+                swap_account,
+                config: pda::config().0,
+                initiator,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let initiator = test.initiator.insecure_clone();
+        test.send(&[initiate], &[&initiator]).unwrap();
+        Oracle {
+            test,
+            swap_account,
+            price_update: Pubkey::new_unique(),
+        }
+    }
+
+    /// Posts a fully verified SOL/USD price update of `price` published `age_secs` ago
+    fn post_price(&mut self, price: i64, conf: u64, age_secs: i64) {
+        let publish_time = clock::clock(&self.test.svm).unix_timestamp - age_secs;
+        let data = [
+            &[34, 241, 35, 99, 157, 126, 244, 205][..],
+            // The write authority
+            &[0; 32],
+            // Fully verified
+            &[1],
+            &PYTH_SOL_USD_FEED_ID,
+            &price.to_le_bytes(),
+            &conf.to_le_bytes(),
+            &EXPONENT.to_le_bytes(),
+            &publish_time.to_le_bytes(),
+            // The previous publish time, EMA price and EMA confidence
+            &publish_time.to_le_bytes(),
+            &price.to_le_bytes(),
+            &conf.to_le_bytes(),
+            // The posted slot
+            &0u64.to_le_bytes(),
+        ]
+        .concat();
+        let account = Account {
+            lamports: self.test.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: PYTH_RECEIVER_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.test
+            .svm
+            .set_account(self.price_update, account)
+            .unwrap();
+    }
+
+    fn redeem_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemOraclePriced { secret: SECRET }.data(),
+            accounts::RedeemOraclePriced {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                price_update: self.price_update,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeems_release_the_notional_at_the_oracle_price() {
+    let mut oracle = Oracle::initiate();
+    let (initiator, redeemer) = (
+        oracle.test.initiator.pubkey(),
+        oracle.test.redeemer.pubkey(),
+    );
+    oracle.post_price(PRICE, PRICE as u64 / 1_000, 0);
+    let (initiator_before, redeemer_before) = (
+        oracle.test.balance(&initiator),
+        oracle.test.balance(&redeemer),
+    );
+    let escrowed = oracle.test.balance(&oracle.swap_account);
+
+    let redeem = oracle.redeem_instruction();
+    oracle.test.send(&[redeem], &[]).unwrap();
+
+    // 150 USD at 100 USD per SOL
+    let released = 1_500_000_000;
+    assert!(oracle.test.svm.get_account(&oracle.swap_account).is_none());
+    assert_eq!(oracle.test.balance(&redeemer), redeemer_before + released);
+    assert_eq!(
+        oracle.test.balance(&initiator),
+        initiator_before + escrowed - released
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn stale_or_uncertain_prices_are_rejected() {
+    let mut oracle = Oracle::initiate();
+
+    oracle.post_price(PRICE, 0, MAX_STALENESS_SECS as i64 + 1);
+    assert_swap_error(
+        oracle.test.send(&[oracle.redeem_instruction()], &[]),
+        SwapError::StalePrice,
+    );
+    oracle.post_price(PRICE, PRICE as u64 / 50, 0);
+    assert_swap_error(
+        oracle.test.send(&[oracle.redeem_instruction()], &[]),
+        SwapError::PriceTooUncertain,
+    );
+    assert!(oracle.test.svm.get_account(&oracle.swap_account).is_some());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn redeems_outside_the_slippage_bounds_are_left_to_be_refunded() {
+    let mut oracle = Oracle::initiate();
+    let initiator = oracle.test.initiator.pubkey();
+
+    // 150 USD is 3 SOL at 50 USD per SOL and 0.75 SOL at 200 USD per SOL
+    for price in [PRICE / 2, PRICE * 2] {
+        oracle.post_price(price, 0, 0);
+        assert_swap_error(
+            oracle.test.send(&[oracle.redeem_instruction()], &[]),
+            SwapError::SlippageExceeded,
+        );
+    }
+
+    oracle.test.warp(EXPIRES_IN_SLOTS);
+    let before = oracle.test.balance(&initiator);
+    let escrowed = oracle.test.balance(&oracle.swap_account);
+    let refund = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RefundOraclePriced {}.data(),
+        accounts::RefundOraclePriced {
+            swap_account: oracle.swap_account,
+            initiator,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    oracle.test.send(&[refund], &[]).unwrap();
+
+    assert_eq!(oracle.test.balance(&initiator), before + escrowed);
+}
@@ -0,0 +1,205 @@
+//! Orders posted on-chain by makers and filled by takers, atomically initiating their swaps
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use litesvm::types::TransactionResult;
+use solana_native_swaps::{accounts, instruction, SwapError};
+use solana_native_swaps_client::{pda, Instruction, RedeemBuilder};
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const ORDER_ID: u64 = 1;
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const MIN_EXPIRES_IN_SLOTS: u64 = 100;
+const MAX_EXPIRES_IN_SLOTS: u64 = 200;
+const FILLABLE_FOR_SLOTS: u64 = 50;
+const MIN_FILL_LAMPORTS: u64 = 300_000_000;
+const SECRET: [u8; 32] = [7; 32];
+
+/// Zero-pads `identifier` into a counterpart chain or asset
+fn counterpart(identifier: &str) -> [u8; 32] {
+    let mut padded = [0; 32];
+    padded[..identifier.len()].copy_from_slice(identifier.as_bytes());
+    padded
+}
+
+struct Market {
+    test: SwapTest,
+    order: Pubkey,
+}
+
+impl Market {
+    /// Posts an order from the initiator, as its maker
+    fn post() -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let maker = test.initiator.pubkey();
+        let order = pda::order(&maker, ORDER_ID).0;
+        let post = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::PostOrder {
+                order_id: ORDER_ID,
+                amount_lamports: AMOUNT_LAMPORTS,
+                counterpart_chain: counterpart("bitcoin"),
+                counterpart_asset: counterpart("BTC"),
+                counterpart_amount: 100_000,
+                min_expires_in_slots: MIN_EXPIRES_IN_SLOTS,
+                max_expires_in_slots: MAX_EXPIRES_IN_SLOTS,
+                fillable_for_slots: FILLABLE_FOR_SLOTS,
+                min_fill_lamports: MIN_FILL_LAMPORTS,
+            }
+            .data(),
+            accounts::PostOrder {
+                order,
+                config: pda::config().0,
+                maker,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let maker = test.initiator.insecure_clone();
+        test.send(&[post], &[&maker]).unwrap();
+        Market { test, order }
+    }
+
+    /// The `fill_order` instruction of the redeemer, as the taker, locked to `secret`
+    fn fill_instruction(
+        &self,
+        secret: &[u8; 32],
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    ) -> Instruction {
+        let (maker, taker) = (self.test.initiator.pubkey(), self.test.redeemer.pubkey());
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::FillOrder {
+                expires_in_slots,
+                secret_hash: secret_hash(secret),
+                amount_lamports,
+            }
+            .data(),
+            accounts::FillOrder {
+                order: self.order,
+                swap_account: pda::swap_account(&maker, &taker, &secret_hash(secret)).0,
+                config: pda::config().0,
+                maker,
+                taker,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn fill(
+        &mut self,
+        secret: &[u8; 32],
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    ) -> TransactionResult {
+        let fill = self.fill_instruction(secret, amount_lamports, expires_in_slots);
+        let taker = self.test.redeemer.insecure_clone();
+        self.test.send(&[fill], &[&taker])
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn filling_an_order_initiates_its_swap() {
+    let mut market = Market::post();
+    let (maker, taker) = (
+        market.test.initiator.pubkey(),
+        market.test.redeemer.pubkey(),
+    );
+    let (maker_before, taker_before) = (market.test.balance(&maker), market.test.balance(&taker));
+
+    assert_swap_error(
+        market.fill(&SECRET, AMOUNT_LAMPORTS, MAX_EXPIRES_IN_SLOTS + 1),
+        SwapError::InvalidOrderExpiry,
+    );
+    market
+        .fill(&SECRET, AMOUNT_LAMPORTS, MIN_EXPIRES_IN_SLOTS)
+        .unwrap();
+
+    assert!(market.test.svm.get_account(&market.order).is_none());
+    let swap_account = market.test.swap_account(&secret_hash(&SECRET));
+    let swap = market.test.swap(&swap_account).unwrap();
+    assert_eq!((swap.initiator, swap.redeemer), (maker, taker));
+    assert_eq!(swap.amount_lamports, AMOUNT_LAMPORTS);
+    assert_eq!(swap.expiry_slot, market.test.slot() + MIN_EXPIRES_IN_SLOTS);
+    // The taker is reimbursed the swap's rent, and the maker receives the order's
+    assert_eq!(market.test.balance(&taker), taker_before);
+    assert!(market.test.balance(&maker) > maker_before);
+
+    let redeem = RedeemBuilder::new(&swap, SECRET).instruction();
+    market.test.send(&[redeem], &[]).unwrap();
+    assert_eq!(
+        market.test.balance(&taker),
+        taker_before + AMOUNT_LAMPORTS - market.test.program.protocol_fee(AMOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn orders_are_filled_partially_down_to_their_remainder() {
+    let mut market = Market::post();
+    let secrets = [[7; 32], [8; 32], [9; 32], [10; 32]];
+
+    assert_swap_error(
+        market.fill(&secrets[0], MIN_FILL_LAMPORTS - 1, MIN_EXPIRES_IN_SLOTS),
+        SwapError::InvalidFillAmount,
+    );
+    for secret in &secrets[..3] {
+        market
+            .fill(secret, MIN_FILL_LAMPORTS, MIN_EXPIRES_IN_SLOTS)
+            .unwrap();
+        let swap_account = market.test.swap_account(&secret_hash(secret));
+        let swap = market.test.swap(&swap_account).unwrap();
+        assert_eq!(swap.amount_lamports, MIN_FILL_LAMPORTS);
+    }
+    assert!(market.test.svm.get_account(&market.order).is_some());
+
+    // Fills may not exceed the remainder, but may take it despite it being below the minimum
+    let remainder = AMOUNT_LAMPORTS - 3 * MIN_FILL_LAMPORTS;
+    assert_swap_error(
+        market.fill(&secrets[3], remainder + 1, MIN_EXPIRES_IN_SLOTS),
+        SwapError::InvalidFillAmount,
+    );
+    market
+        .fill(&secrets[3], remainder, MIN_EXPIRES_IN_SLOTS)
+        .unwrap();
+    assert!(market.test.svm.get_account(&market.order).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn lapsed_orders_cannot_be_filled_but_can_be_cancelled() {
+    let mut market = Market::post();
+    let maker = market.test.initiator.insecure_clone();
+    let before = market.test.balance(&maker.pubkey());
+    let escrowed = market.test.balance(&market.order);
+
+    market.test.warp(FILLABLE_FOR_SLOTS);
+    assert_swap_error(
+        market.fill(&SECRET, AMOUNT_LAMPORTS, MIN_EXPIRES_IN_SLOTS),
+        SwapError::OrderExpired,
+    );
+
+    let cancel = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::CancelOrder {}.data(),
+        accounts::CancelOrder {
+            order: market.order,
+            maker: maker.pubkey(),
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    market.test.send(&[cancel], &[&maker]).unwrap();
+
+    assert!(market.test.svm.get_account(&market.order).is_none());
+    assert_eq!(market.test.balance(&maker.pubkey()), before + escrowed);
+}
@@ -0,0 +1,110 @@
+//! Properties of the swap lifecycle which must hold for any amount, expiry and secret, against
+//! both the Anchor program and its lite variant.
+
+use anchor_lang::prelude::Pubkey;
+use proptest::{prelude::*, sample::select};
+use solana_native_swaps_client::pda;
+use solana_native_swaps_tests::{secret_hash, Program, SwapTest};
+use solana_signer::Signer;
+
+/// The total balance of the initiator, the redeemer, the swap account and the treasury,
+/// among which the lamports of a swap move
+fn total(test: &SwapTest, swap_account: &Pubkey) -> u64 {
+    test.balance(&test.initiator.pubkey())
+        + test.balance(&test.redeemer.pubkey())
+        + test.balance(swap_account)
+        + test.balance(&pda::treasury().0)
+}
+
+fn program() -> impl Strategy<Value = Program> {
+    select(Program::ALL.to_vec())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    #[ignore = "requires both programs to be built with `cargo build-sbf`"]
+    fn redeem_conserves_lamports(
+        program in program(),
+        amount_lamports in 1..=10_000_000_000u64,
+        expires_in_slots in 1..=1_000u64,
+        secret in any::<[u8; 32]>(),
+    ) {
+        let mut test = SwapTest::with_program(program).unwrap();
+        let redeemer = test.redeemer.pubkey();
+        let swap_account = test.swap_account(&secret_hash(&secret));
+        let before = total(&test, &swap_account);
+        let redeemer_before = test.balance(&redeemer);
+
+        test.initiate(&secret, amount_lamports, expires_in_slots).unwrap();
+        prop_assert_eq!(total(&test, &swap_account), before);
+
+        let redeem = test.redeem_instruction(&secret_hash(&secret), secret);
+        test.send(&[redeem], &[]).unwrap();
+        prop_assert_eq!(total(&test, &swap_account), before);
+        prop_assert_eq!(test.balance(&swap_account), 0);
+        prop_assert_eq!(
+            test.balance(&redeemer),
+            redeemer_before + amount_lamports - program.protocol_fee(amount_lamports)
+        );
+    }
+
+    #[test]
+    #[ignore = "requires both programs to be built with `cargo build-sbf`"]
+    fn redeem_only_succeeds_with_the_preimage(
+        program in program(),
+        amount_lamports in 1..=10_000_000_000u64,
+        secret in any::<[u8; 32]>(),
+        wrong_secret in any::<[u8; 32]>(),
+    ) {
+        prop_assume!(secret != wrong_secret);
+        let mut test = SwapTest::with_program(program).unwrap();
+        let swap_account = test.initiate(&secret, amount_lamports, 100).unwrap();
+        let before = total(&test, &swap_account);
+
+        let redeem = test.redeem_instruction(&secret_hash(&secret), wrong_secret);
+        prop_assert!(test.send(&[redeem], &[]).is_err());
+        prop_assert_eq!(total(&test, &swap_account), before);
+        prop_assert_eq!(
+            test.balance(&swap_account),
+            amount_lamports + test.swap_rent()
+        );
+
+        let redeem = test.redeem_instruction(&secret_hash(&secret), secret);
+        prop_assert!(test.send(&[redeem], &[]).is_ok());
+        prop_assert_eq!(test.balance(&swap_account), 0);
+    }
+
+    #[test]
+    #[ignore = "requires both programs to be built with `cargo build-sbf`"]
+    fn refund_only_succeeds_from_expiry(
+        program in program(),
+        amount_lamports in 1..=10_000_000_000u64,
+        expires_in_slots in 1..=1_000u64,
+        elapsed_slots in 0..=2_000u64,
+        secret in any::<[u8; 32]>(),
+    ) {
+        let mut test = SwapTest::with_program(program).unwrap();
+        let initiator = test.initiator.pubkey();
+        let initiator_before = test.balance(&initiator);
+        let swap_account = test.initiate(&secret, amount_lamports, expires_in_slots).unwrap();
+        if elapsed_slots > 0 {
+            test.warp(elapsed_slots);
+        }
+
+        let refund = test.refund_instruction(&secret_hash(&secret));
+        let result = test.send(&[refund], &[]);
+        if elapsed_slots >= expires_in_slots {
+            prop_assert!(result.is_ok());
+            prop_assert_eq!(test.balance(&swap_account), 0);
+            prop_assert_eq!(test.balance(&initiator), initiator_before);
+        } else {
+            prop_assert!(result.is_err());
+            prop_assert_eq!(
+                test.balance(&swap_account),
+                amount_lamports + test.swap_rent()
+            );
+        }
+    }
+}
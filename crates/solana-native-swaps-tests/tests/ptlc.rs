@@ -0,0 +1,185 @@
+//! Point time-locked contract swaps, claimed with a signature under the swap's point
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_keypair::Keypair;
+use solana_native_swaps::{accounts, instruction, pda, PtlcSwapAccount, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+
+struct Ptlc {
+    test: SwapTest,
+    /// The keypair whose public key is the swap's point, its secret scalar being the secret
+    point: Keypair,
+    swap_account: Pubkey,
+}
+
+impl Ptlc {
+    fn new() -> Self {
+        let test = SwapTest::new().unwrap();
+        let point = Keypair::new();
+        let swap_account = pda::ptlc_swap_account(
+            &test.initiator.pubkey(),
+            &test.redeemer.pubkey(),
+            &point.pubkey(),
+        )
+        .0;
+        Ptlc {
+            test,
+            point,
+            swap_account,
+        }
+    }
+
+    fn initiate(&mut self) {
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiatePtlc {
+                amount_lamports: AMOUNT_LAMPORTS,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer: self.test.redeemer.pubkey(),
+                point: self.point.pubkey(),
+            }
+            .data(),
+            accounts::InitiatePtlc {
+                swap_account: self.swap_account,
+                config: pda::config().0,
+                initiator: self.test.initiator.pubkey(),
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let initiator = self.test.initiator.insecure_clone();
+        self.test.send(&[initiate], &[&initiator]).unwrap();
+    }
+
+    fn swap(&self) -> Option<PtlcSwapAccount> {
+        let account = self.test.svm.get_account(&self.swap_account)?;
+        PtlcSwapAccount::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    /// The signature under the point over the claim message of the swap as currently initiated,
+    /// verified by the ed25519 program
+    fn claim(&self) -> Instruction {
+        let message = self.swap().unwrap().claim_message(&self.swap_account);
+        let signature = self.point.sign_message(&message);
+        solana_ed25519_program::new_ed25519_instruction_with_signature(
+            &message,
+            &signature.into(),
+            &self.point.pubkey().to_bytes(),
+        )
+    }
+
+    fn redeem_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemPtlc {}.data(),
+            accounts::RedeemPtlc {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                instructions_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn refund_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundPtlc {}.data(),
+            accounts::RefundPtlc {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn a_signature_under_the_point_redeems_the_swap() {
+    let mut ptlc = Ptlc::new();
+    ptlc.initiate();
+    let redeemer = ptlc.test.redeemer.pubkey();
+    let before = ptlc.test.balance(&redeemer);
+
+    let claim = ptlc.claim();
+    let redeem = ptlc.redeem_instruction();
+    ptlc.test.send(&[claim, redeem], &[]).unwrap();
+
+    assert!(ptlc.swap().is_none());
+    assert_eq!(ptlc.test.balance(&redeemer), before + AMOUNT_LAMPORTS);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn a_signature_under_another_key_does_not_redeem_the_swap() {
+    let mut ptlc = Ptlc::new();
+    ptlc.initiate();
+
+    let message = ptlc.swap().unwrap().claim_message(&ptlc.swap_account);
+    let impostor = Keypair::new();
+    let claim = solana_ed25519_program::new_ed25519_instruction_with_signature(
+        &message,
+        &impostor.sign_message(&message).into(),
+        &impostor.pubkey().to_bytes(),
+    );
+    let redeem = ptlc.redeem_instruction();
+
+    assert_swap_error(
+        ptlc.test.send(&[claim, redeem], &[]),
+        SwapError::InvalidPtlcSignature,
+    );
+    assert!(ptlc.swap().is_some());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn claims_are_not_replayable_against_a_later_swap_at_the_same_address() {
+    let mut ptlc = Ptlc::new();
+    ptlc.initiate();
+    let claim = ptlc.claim();
+    let redeem = ptlc.redeem_instruction();
+    ptlc.test.send(&[claim.clone(), redeem], &[]).unwrap();
+
+    // The same parties and point derive the same address for the next swap
+    ptlc.test.warp(1);
+    ptlc.initiate();
+
+    let redeem = ptlc.redeem_instruction();
+    assert_swap_error(
+        ptlc.test.send(&[claim, redeem], &[]),
+        SwapError::InvalidPtlcSignature,
+    );
+    assert!(ptlc.swap().is_some());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn expired_swaps_are_refunded_to_the_initiator() {
+    let mut ptlc = Ptlc::new();
+    let initiator = ptlc.test.initiator.pubkey();
+    let before = ptlc.test.balance(&initiator);
+    ptlc.initiate();
+
+    assert_swap_error(
+        ptlc.test.send(&[ptlc.refund_instruction()], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+    ptlc.test.warp(EXPIRES_IN_SLOTS);
+    ptlc.test.send(&[ptlc.refund_instruction()], &[]).unwrap();
+
+    assert!(ptlc.swap().is_none());
+    assert_eq!(ptlc.test.balance(&initiator), before);
+}
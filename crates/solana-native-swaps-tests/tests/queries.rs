@@ -0,0 +1,65 @@
+use anchor_lang::AnchorDeserialize;
+use solana_native_swaps_client::{
+    get_swap_state_instruction, get_version_instruction, Instruction, ProgramVersion, SwapInfo, ID,
+    PROGRAM_VERSION, SWAP_ACCOUNT_VERSION,
+};
+use solana_native_swaps_tests::SwapTest;
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// Executes the query `instruction`, decoding the return data the program set
+fn query<T: AnchorDeserialize>(test: &mut SwapTest, instruction: Instruction) -> T {
+    let meta = test.send(&[instruction], &[]).unwrap();
+    assert_eq!(meta.return_data.program_id, ID);
+    T::try_from_slice(&meta.return_data.data).unwrap()
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn get_version_returns_the_program_and_layout_versions() {
+    let mut test = SwapTest::new().unwrap();
+
+    let version: ProgramVersion = query(&mut test, get_version_instruction());
+
+    assert_eq!(version.version, PROGRAM_VERSION);
+    assert_eq!(version.swap_account_version, SWAP_ACCOUNT_VERSION);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn get_swap_state_returns_the_decoded_swap() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+
+    let info: SwapInfo = query(&mut test, get_swap_state_instruction(&swap_account));
+    assert_eq!(info.version, SWAP_ACCOUNT_VERSION);
+    assert_eq!(info.initiator, test.initiator.pubkey());
+    assert_eq!(info.redeemer, test.redeemer.pubkey());
+    assert_eq!(info.secret_hash, swap.secret_hash);
+    assert_eq!(info.amount_lamports, AMOUNT_LAMPORTS);
+    assert_eq!(info.expiry_slot, swap.expiry_slot);
+    assert_eq!(info.expiry_timestamp, None);
+    assert_eq!(info.refund_recipient, test.initiator.pubkey());
+    assert!(!info.has_expired);
+
+    test.warp(EXPIRES_IN_SLOTS);
+    let info: SwapInfo = query(&mut test, get_swap_state_instruction(&swap_account));
+    assert!(info.has_expired);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn get_swap_state_of_a_non_swap_account_fails() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+
+    assert!(test
+        .send(&[get_swap_state_instruction(&initiator)], &[])
+        .is_err());
+}
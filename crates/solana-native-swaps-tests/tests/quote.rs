@@ -0,0 +1,124 @@
+//! Quotes of both legs of cross-chain swaps from Pyth and Switchboard prices
+
+use solana_native_swaps_client::{
+    quote::{FeeModel, OraclePrice, Quoter, PYTH_SOL_USD_FEED_ID},
+    ClientError,
+};
+
+const NOW: i64 = 1_700_000_000;
+/// 100 USD per SOL, in Pyth's usual exponent of -8
+const SOL_USD: i64 = 10_000_000_000;
+/// 60,000 USD per BTC, in Switchboard's fixed-point precision of 18 decimals
+const BTC_USD: i128 = 60_000 * 10i128.pow(18);
+const BTC_DECIMALS: u8 = 8;
+
+/// A fully verified Pyth SOL/USD price update of `price` published `age_secs` ago
+fn pyth_price_update(price: i64, conf: u64, age_secs: i64) -> Vec<u8> {
+    [
+        &[34, 241, 35, 99, 157, 126, 244, 205][..],
+        &[0; 32],
+        &[1],
+        &PYTH_SOL_USD_FEED_ID,
+        &price.to_le_bytes(),
+        &conf.to_le_bytes(),
+        &(-8i32).to_le_bytes(),
+        &(NOW - age_secs).to_le_bytes(),
+        &[0; 32],
+    ]
+    .concat()
+}
+
+/// A Switchboard On-Demand pull feed whose current result is `value` ± `std_dev`
+fn switchboard_pull_feed(value: i128, std_dev: i128) -> Vec<u8> {
+    let mut data = vec![0; 3_208];
+    data[2_216..2_224].copy_from_slice(&NOW.to_le_bytes());
+    data[2_264..2_280].copy_from_slice(&value.to_le_bytes());
+    data[2_280..2_296].copy_from_slice(&std_dev.to_le_bytes());
+    data
+}
+
+fn prices() -> (OraclePrice, OraclePrice) {
+    let sol_usd = pyth_price_update(SOL_USD, SOL_USD as u64 / 1_000, 0);
+    let btc_usd = switchboard_pull_feed(BTC_USD, BTC_USD / 1_000);
+    (
+        OraclePrice::from_pyth(&sol_usd, &PYTH_SOL_USD_FEED_ID).unwrap(),
+        OraclePrice::from_switchboard(&btc_usd).unwrap(),
+    )
+}
+
+#[test]
+fn takers_selling_the_counterparty_asset_are_quoted_lamports_less_the_spread() {
+    let (sol_usd, btc_usd) = prices();
+    // 0.01 BTC, worth 600 USD or 6 SOL
+    let quote = Quoter::default()
+        .quote_lamports_out(1_000_000, BTC_DECIMALS, &sol_usd, &btc_usd, NOW)
+        .unwrap();
+    assert_eq!(quote.amount_lamports, 5_982_000_000);
+    assert_eq!(quote.counterparty_amount, 1_000_000);
+    assert_eq!(quote.fee_lamports, 0);
+    // The Solana leg, funded second, expires an hour before the counterparty leg
+    assert_eq!(quote.expires_in_slots, 9_000);
+    assert_eq!(quote.counterparty_expires_in_secs, 2 * 60 * 60);
+}
+
+#[test]
+fn takers_selling_sol_are_quoted_the_counterparty_asset_less_fees_and_spread() {
+    let (sol_usd, btc_usd) = prices();
+    let quoter = Quoter {
+        fees: FeeModel {
+            fixed_lamports: 5_000,
+            bps: 10,
+        },
+        ..Quoter::default()
+    };
+    let quote = quoter
+        .quote_counterparty_out(6_000_000_000, BTC_DECIMALS, &sol_usd, &btc_usd, NOW)
+        .unwrap();
+    assert_eq!(quote.fee_lamports, 6_005_000);
+    // 5.976013015 SOL after fees and spread, worth 0.00996002 BTC
+    assert_eq!(quote.counterparty_amount, 996_002);
+    // The Solana leg, funded first, outlives the counterparty leg
+    assert_eq!(quote.expires_in_slots, 18_000);
+    assert_eq!(quote.counterparty_expires_in_secs, 60 * 60);
+}
+
+#[test]
+fn oracle_priced_bounds_bracket_the_lamports_the_program_releases() {
+    let (sol_usd, _) = prices();
+    // 150 USD at 100 USD per SOL
+    let quote = Quoter::default()
+        .quote_oracle_priced(150_000_000, &sol_usd, NOW)
+        .unwrap();
+    assert_eq!(sol_usd.lamports_for(150_000_000), Some(1_500_000_000));
+    assert_eq!(quote.min_lamports_out, 1_485_000_000);
+    assert_eq!(quote.max_lamports_out, 1_515_000_000);
+    assert_eq!(quote.amount_lamports, quote.max_lamports_out);
+}
+
+#[test]
+fn stale_or_uncertain_prices_are_not_quoted() {
+    let (_, btc_usd) = prices();
+    let quoter = Quoter::default();
+    for (sol_usd, age_secs) in [
+        // Published past the maximum staleness
+        (SOL_USD as u64 / 1_000, 61),
+        // Uncertain by more than the maximum confidence interval
+        (SOL_USD as u64 / 50, 0),
+    ] {
+        let data = pyth_price_update(SOL_USD, sol_usd, age_secs);
+        let sol_usd = OraclePrice::from_pyth(&data, &PYTH_SOL_USD_FEED_ID).unwrap();
+        assert!(matches!(
+            quoter.quote_lamports_out(1_000_000, BTC_DECIMALS, &sol_usd, &btc_usd, NOW),
+            Err(ClientError::InvalidPrice(_))
+        ));
+    }
+}
+
+#[test]
+fn price_updates_of_other_feeds_are_rejected() {
+    let data = pyth_price_update(SOL_USD, 0, 0);
+    assert!(matches!(
+        OraclePrice::from_pyth(&data, &[0; 32]),
+        Err(ClientError::InvalidPrice(_))
+    ));
+}
@@ -0,0 +1,120 @@
+//! Redeems to a destination of the redeemer's choosing, with their consent given as a
+//! pre-signed ed25519 signature
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_keypair::Keypair;
+use solana_native_swaps::{accounts, instruction, SwapError};
+use solana_native_swaps_client::{pda, Instruction, SwapAccount};
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// The redeemer's consent to a redeem of `swap` to `destination`, verified by the ed25519 program
+fn consent(
+    test: &SwapTest,
+    swap_account: &Pubkey,
+    swap: &SwapAccount,
+    destination: &Pubkey,
+) -> Instruction {
+    let message = swap.redeem_consent_message(swap_account, destination);
+    let signature = test.redeemer.sign_message(&message);
+    solana_ed25519_program::new_ed25519_instruction_with_signature(
+        &message,
+        &signature.into(),
+        &test.redeemer.pubkey().to_bytes(),
+    )
+}
+
+fn redeem_with_signature(
+    test: &SwapTest,
+    swap_account: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RedeemWithSignature { secret: SECRET }.data(),
+        accounts::RedeemWithSignature {
+            swap_account: *swap_account,
+            initiator: test.initiator.pubkey(),
+            destination: *destination,
+            config: pda::config().0,
+            treasury: pda::treasury().0,
+            relayer: None,
+            instructions_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+            receipt: None,
+            receipt_payer: None,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn pre_signed_consent_redeems_to_the_destination() {
+    let mut test = SwapTest::new().unwrap();
+    let destination = Keypair::new().pubkey();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+
+    test.send(
+        &[
+            consent(&test, &swap_account, &swap, &destination),
+            redeem_with_signature(&test, &swap_account, &destination),
+        ],
+        &[],
+    )
+    .unwrap();
+
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(
+        test.balance(&destination),
+        AMOUNT_LAMPORTS - test.program.protocol_fee(AMOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn consent_is_not_replayable_against_a_later_swap_at_the_same_address() {
+    let mut test = SwapTest::new().unwrap();
+    let destination = Keypair::new().pubkey();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let swap = test.swap(&swap_account).unwrap();
+    let consent = consent(&test, &swap_account, &swap, &destination);
+    test.send(
+        &[
+            consent.clone(),
+            redeem_with_signature(&test, &swap_account, &destination),
+        ],
+        &[],
+    )
+    .unwrap();
+
+    // The same parties and secret hash derive the same address for the next swap
+    test.warp(1);
+    let later = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    assert_eq!(later, swap_account);
+
+    assert_swap_error(
+        test.send(
+            &[
+                consent,
+                redeem_with_signature(&test, &swap_account, &destination),
+            ],
+            &[],
+        ),
+        SwapError::InvalidConsentSignature,
+    );
+    assert!(test.swap(&swap_account).is_some());
+}
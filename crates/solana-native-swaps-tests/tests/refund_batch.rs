@@ -0,0 +1,54 @@
+//! Refunds of expired swaps in batches through the permissionless `refund_expired_batch` crank
+
+use solana_keypair::Keypair;
+use solana_native_swaps_client::{InitiateBuilder, RefundExpiredBatchBuilder};
+use solana_native_swaps_tests::{secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn batch_refunds_pay_the_refund_destination_and_return_rent_to_the_initiator() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let refund_to = Keypair::new().pubkey();
+
+    let swap_accounts: Vec<_> = [[7; 32], [8; 32]]
+        .iter()
+        .zip([None, Some(refund_to)])
+        .map(|(secret, refund_to)| {
+            let mut initiate = InitiateBuilder::new(
+                initiator.pubkey(),
+                test.redeemer.pubkey(),
+                secret_hash(secret),
+                AMOUNT_LAMPORTS,
+                EXPIRES_IN_SLOTS,
+            );
+            if let Some(refund_to) = refund_to {
+                initiate = initiate.refund_to(refund_to);
+            }
+            test.send(&[initiate.instruction()], &[&initiator]).unwrap();
+            initiate.swap_account()
+        })
+        .collect();
+    let initiator_before = test.balance(&initiator.pubkey());
+
+    test.warp(EXPIRES_IN_SLOTS);
+    let refund = swap_accounts
+        .iter()
+        .fold(RefundExpiredBatchBuilder::new(), |refund, swap_account| {
+            refund.swap(&test.swap(swap_account).unwrap())
+        });
+    test.send(&[refund.instruction()], &[]).unwrap();
+
+    for swap_account in &swap_accounts {
+        assert!(test.swap(swap_account).is_none());
+    }
+    assert_eq!(test.balance(&refund_to), AMOUNT_LAMPORTS);
+    assert_eq!(
+        test.balance(&initiator.pubkey()),
+        initiator_before + AMOUNT_LAMPORTS + 2 * test.swap_rent()
+    );
+}
@@ -0,0 +1,78 @@
+//! Relayer fees auctioned from a starting fee upon initiation up to the full fee at expiry
+
+use solana_keypair::Keypair;
+use solana_native_swaps::{SwapError, SwapIncentives};
+use solana_native_swaps_client::{InitiateBuilder, Instruction, RedeemBuilder};
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest, PARTY_LAMPORTS};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+const RELAYER_FEE_START: u64 = 1_000_000;
+const RELAYER_FEE: u64 = 5_000_000;
+
+fn initiate_instruction(test: &SwapTest, incentives: SwapIncentives) -> Instruction {
+    InitiateBuilder::new(
+        test.initiator.pubkey(),
+        test.redeemer.pubkey(),
+        secret_hash(&SECRET),
+        AMOUNT_LAMPORTS,
+        EXPIRES_IN_SLOTS,
+    )
+    .incentives(incentives)
+    .instruction()
+}
+
+fn auction() -> SwapIncentives {
+    SwapIncentives {
+        relayer_fee_lamports: RELAYER_FEE,
+        refund_tip_lamports: 0,
+        relayer_fee_start_lamports: Some(RELAYER_FEE_START),
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn auctioned_relayer_fees_rise_linearly_toward_expiry() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let initiate = initiate_instruction(&test, auction());
+    test.send(&[initiate], &[&initiator]).unwrap();
+    let swap_account = test.swap_account(&secret_hash(&SECRET));
+    let swap = test.swap(&swap_account).unwrap();
+    assert_eq!(swap.relayer_fee_at(swap.created_at_slot), RELAYER_FEE_START);
+    assert_eq!(swap.relayer_fee_at(swap.expiry_slot), RELAYER_FEE);
+
+    let relayer = Keypair::new();
+    test.svm.airdrop(&relayer.pubkey(), PARTY_LAMPORTS).unwrap();
+    test.warp(EXPIRES_IN_SLOTS / 4);
+    let redeem = RedeemBuilder::new(&swap, SECRET)
+        .relayer(relayer.pubkey())
+        .instruction();
+    test.send(&[redeem], &[&relayer]).unwrap();
+
+    let expected_fee = RELAYER_FEE_START + (RELAYER_FEE - RELAYER_FEE_START) / 4;
+    assert_eq!(
+        test.balance(&relayer.pubkey()),
+        PARTY_LAMPORTS + expected_fee
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn auctions_starting_above_the_relayer_fee_are_rejected() {
+    let mut test = SwapTest::new().unwrap();
+    let initiate = initiate_instruction(
+        &test,
+        SwapIncentives {
+            relayer_fee_start_lamports: Some(RELAYER_FEE + 1),
+            ..auction()
+        },
+    );
+    let initiator = test.initiator.insecure_clone();
+    assert_swap_error(
+        test.send(&[initiate], &[&initiator]),
+        SwapError::InvalidRelayerFeeAuction,
+    );
+}
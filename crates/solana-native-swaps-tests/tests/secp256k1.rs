@@ -0,0 +1,179 @@
+//! Swaps claimed with a signature by a committed secp256k1 key rather than a secret
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, InstructionData, ToAccountMetas};
+use libsecp256k1::{PublicKey, SecretKey};
+use solana_native_swaps::{accounts, instruction, pda, Secp256k1SwapAccount, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_secp256k1_program::{construct_eth_pubkey, new_secp256k1_instruction};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+
+struct Secp256k1Swap {
+    test: SwapTest,
+    /// The committed key, as held by a Bitcoin or EVM-native signer
+    key: SecretKey,
+    eth_address: [u8; 20],
+    swap_account: Pubkey,
+}
+
+impl Secp256k1Swap {
+    fn new() -> Self {
+        let test = SwapTest::new().unwrap();
+        let key = SecretKey::parse(&[7; 32]).unwrap();
+        let eth_address = construct_eth_pubkey(&PublicKey::from_secret_key(&key));
+        let swap_account = pda::secp256k1_swap_account(
+            &test.initiator.pubkey(),
+            &test.redeemer.pubkey(),
+            &eth_address,
+        )
+        .0;
+        Secp256k1Swap {
+            test,
+            key,
+            eth_address,
+            swap_account,
+        }
+    }
+
+    fn initiate(&mut self) {
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateSecp256k1 {
+                amount_lamports: AMOUNT_LAMPORTS,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer: self.test.redeemer.pubkey(),
+                eth_address: self.eth_address,
+            }
+            .data(),
+            accounts::InitiateSecp256k1 {
+                swap_account: self.swap_account,
+                config: pda::config().0,
+                initiator: self.test.initiator.pubkey(),
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let initiator = self.test.initiator.insecure_clone();
+        self.test.send(&[initiate], &[&initiator]).unwrap();
+    }
+
+    fn swap(&self) -> Option<Secp256k1SwapAccount> {
+        let account = self.test.svm.get_account(&self.swap_account)?;
+        Secp256k1SwapAccount::try_deserialize(&mut account.data.as_slice()).ok()
+    }
+
+    /// The committed key's signature over the claim message of the swap as currently
+    /// initiated, verified by the secp256k1 program as the first instruction of the transaction
+    fn claim(&self) -> Instruction {
+        let message = self.swap().unwrap().claim_message(&self.swap_account);
+        new_secp256k1_instruction(&self.key, &message)
+    }
+
+    fn redeem_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemSecp256k1 {}.data(),
+            accounts::RedeemSecp256k1 {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                instructions_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn refund_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundSecp256k1 {}.data(),
+            accounts::RefundSecp256k1 {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn a_signature_by_the_committed_key_redeems_the_swap() {
+    let mut swap = Secp256k1Swap::new();
+    swap.initiate();
+    let redeemer = swap.test.redeemer.pubkey();
+    let before = swap.test.balance(&redeemer);
+
+    let claim = swap.claim();
+    let redeem = swap.redeem_instruction();
+    swap.test.send(&[claim, redeem], &[]).unwrap();
+
+    assert!(swap.swap().is_none());
+    assert_eq!(swap.test.balance(&redeemer), before + AMOUNT_LAMPORTS);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn a_signature_by_another_key_does_not_redeem_the_swap() {
+    let mut swap = Secp256k1Swap::new();
+    swap.initiate();
+
+    let message = swap.swap().unwrap().claim_message(&swap.swap_account);
+    let claim = new_secp256k1_instruction(&SecretKey::parse(&[8; 32]).unwrap(), &message);
+    let redeem = swap.redeem_instruction();
+
+    assert_swap_error(
+        swap.test.send(&[claim, redeem], &[]),
+        SwapError::InvalidSecp256k1Signature,
+    );
+    assert!(swap.swap().is_some());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn claims_are_not_replayable_against_a_later_swap_at_the_same_address() {
+    let mut swap = Secp256k1Swap::new();
+    swap.initiate();
+    let claim = swap.claim();
+    let redeem = swap.redeem_instruction();
+    swap.test.send(&[claim.clone(), redeem], &[]).unwrap();
+
+    // The same parties and committed key derive the same address for the next swap
+    swap.test.warp(1);
+    swap.initiate();
+
+    let redeem = swap.redeem_instruction();
+    assert_swap_error(
+        swap.test.send(&[claim, redeem], &[]),
+        SwapError::InvalidSecp256k1Signature,
+    );
+    assert!(swap.swap().is_some());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn expired_swaps_are_refunded_to_the_initiator() {
+    let mut swap = Secp256k1Swap::new();
+    let initiator = swap.test.initiator.pubkey();
+    let before = swap.test.balance(&initiator);
+    swap.initiate();
+
+    assert_swap_error(
+        swap.test.send(&[swap.refund_instruction()], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+    swap.test.warp(EXPIRES_IN_SLOTS);
+    swap.test.send(&[swap.refund_instruction()], &[]).unwrap();
+
+    assert!(swap.swap().is_none());
+    assert_eq!(swap.test.balance(&initiator), before);
+}
@@ -0,0 +1,17 @@
+use solana_native_swaps_client::security_txt::{decode_security_txt, Contact};
+use solana_native_swaps_tests::Program;
+
+#[test]
+#[ignore = "requires both programs to be built with `cargo build-sbf`"]
+fn programs_embed_a_security_txt() {
+    for program in Program::ALL {
+        let executable = std::fs::read(program.path()).unwrap();
+        let security_txt = decode_security_txt(&executable).unwrap();
+        assert!(security_txt.name.starts_with("Solana Native Swaps"));
+        assert!(matches!(
+            security_txt.contacts.as_slice(),
+            [Contact::Link(_), ..]
+        ));
+        assert!(!security_txt.policy.is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+//! The seeds of swap accounts, which include the redeemer for swaps initiated since
+//! `SWAP_SEEDS_VERSION` 1, while swaps initiated before remain usable under their own seeds.
+
+use anchor_lang::Discriminator;
+use solana_keypair::Keypair;
+use solana_native_swaps_client::{
+    pda, InitiateBuilder, RedeemBuilder, SwapAccount, SWAP_SEEDS_VERSION,
+};
+use solana_native_swaps_tests::{secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// The offsets of the bump and seeds version within the data of a swap account
+const BUMP_OFFSET: usize = SwapAccount::DISCRIMINATOR.len() + 1;
+const SEEDS_VERSION_OFFSET: usize = SwapAccount::DISCRIMINATOR.len() + 7;
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn swaps_with_the_same_secret_hash_are_open_with_each_redeemer() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let other_redeemer = Keypair::new();
+
+    let mut swap_accounts = vec![];
+    for redeemer in [test.redeemer.pubkey(), other_redeemer.pubkey()] {
+        let initiate = InitiateBuilder::new(
+            initiator.pubkey(),
+            redeemer,
+            secret_hash(&SECRET),
+            AMOUNT_LAMPORTS,
+            EXPIRES_IN_SLOTS,
+        );
+        test.send(&[initiate.instruction()], &[&initiator]).unwrap();
+        let (swap_account, _) =
+            pda::swap_account(&initiator.pubkey(), &redeemer, &secret_hash(&SECRET));
+        assert_eq!(initiate.swap_account(), swap_account);
+        let swap = test.swap(&swap_account).unwrap();
+        assert_eq!(swap.seeds_version, SWAP_SEEDS_VERSION);
+        assert_eq!(swap.redeemer, redeemer);
+        swap_accounts.push(swap_account);
+    }
+
+    for swap_account in swap_accounts {
+        let swap = test.swap(&swap_account).unwrap();
+        test.send(&[RedeemBuilder::new(&swap, SECRET).instruction()], &[])
+            .unwrap();
+        assert!(test.swap(&swap_account).is_none());
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn swaps_initiated_before_the_redeemer_was_seeded_remain_usable() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+
+    // Moves the swap to its address under the previous seeds, as recorded by such swaps
+    let (legacy_swap_account, bump) =
+        pda::legacy_swap_account(&test.initiator.pubkey(), &secret_hash(&SECRET));
+    let mut account = test.svm.get_account(&swap_account).unwrap();
+    account.data[BUMP_OFFSET] = bump;
+    account.data[SEEDS_VERSION_OFFSET] = 0;
+    test.svm.set_account(legacy_swap_account, account).unwrap();
+    test.svm
+        .set_account(swap_account, Default::default())
+        .unwrap();
+
+    let swap = test.swap(&legacy_swap_account).unwrap();
+    assert_eq!(pda::swap_account_of(&swap), (legacy_swap_account, bump));
+
+    // The seeds verified are those the swap records
+    test.send(&[RedeemBuilder::new(&swap, SECRET).instruction()], &[])
+        .unwrap();
+    assert!(test.swap(&legacy_swap_account).is_none());
+}
@@ -0,0 +1,196 @@
+//! Exhaustive checks of the swap lifecycle's state machine: of the model in the client's
+//! `state` module on its own, and of both programs against the model, so that no instruction
+//! is accepted from a state the model does not allow it from.
+
+use solana_keypair::Keypair;
+use solana_native_swaps_client::{
+    state::{SwapState, SwapTransition},
+    Instruction, RedeemBuilder,
+};
+use solana_native_swaps_tests::{secret_hash, Program, SwapTest};
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const TRANCHE_LAMPORTS: u64 = AMOUNT_LAMPORTS / 4;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+const TERMINAL_STATES: [SwapState; 3] = [
+    SwapState::Redeemed,
+    SwapState::Refunded,
+    SwapState::InstantRefunded,
+];
+
+fn transitions(remaining_lamports: u64) -> Vec<SwapTransition> {
+    let mut transitions = vec![SwapTransition::Refund, SwapTransition::InstantRefund];
+    for amount_lamports in [
+        0,
+        1,
+        remaining_lamports - 1,
+        remaining_lamports,
+        remaining_lamports + 1,
+        u64::MAX,
+    ] {
+        transitions.push(SwapTransition::Redeem { amount_lamports });
+    }
+    transitions
+}
+
+#[test]
+fn terminal_states_allow_no_transition() {
+    for state in TERMINAL_STATES {
+        assert!(state.is_terminal());
+        for transition in transitions(AMOUNT_LAMPORTS) {
+            assert!(
+                state.apply(transition).is_err(),
+                "{transition} allowed from {state}"
+            );
+        }
+    }
+}
+
+#[test]
+fn initiated_swaps_allow_each_transition_within_their_funds() {
+    for remaining_lamports in [1, 2, TRANCHE_LAMPORTS, AMOUNT_LAMPORTS] {
+        let state = SwapState::initiated(remaining_lamports);
+        assert!(!state.is_terminal());
+        for transition in transitions(remaining_lamports) {
+            let expected = match transition {
+                SwapTransition::Refund => Some(SwapState::Refunded),
+                SwapTransition::InstantRefund => Some(SwapState::InstantRefunded),
+                SwapTransition::Redeem { amount_lamports: 0 } => None,
+                SwapTransition::Redeem { amount_lamports }
+                    if amount_lamports == remaining_lamports =>
+                {
+                    Some(SwapState::Redeemed)
+                }
+                SwapTransition::Redeem { amount_lamports }
+                    if amount_lamports < remaining_lamports =>
+                {
+                    Some(SwapState::initiated(remaining_lamports - amount_lamports))
+                }
+                SwapTransition::Redeem { .. } => None,
+            };
+            assert_eq!(
+                state.apply(transition).ok(),
+                expected,
+                "{transition} from {state}"
+            );
+        }
+    }
+}
+
+#[test]
+fn redeeming_in_tranches_settles_once_fully_redeemed() {
+    let mut state = SwapState::initiated(AMOUNT_LAMPORTS);
+    for _ in 0..AMOUNT_LAMPORTS / TRANCHE_LAMPORTS {
+        assert!(!state.is_terminal());
+        state = state
+            .apply(SwapTransition::Redeem {
+                amount_lamports: TRANCHE_LAMPORTS,
+            })
+            .unwrap();
+    }
+    assert_eq!(state, SwapState::Redeemed);
+}
+
+/// The instructions effecting each transition of a swap, built while it is open, along with
+/// their signers beyond the payer
+struct Transitions {
+    redeem: Instruction,
+    redeem_tranche: Option<Instruction>,
+    refund: Instruction,
+    instant_refund: (Instruction, Keypair),
+}
+
+impl Transitions {
+    fn new(test: &SwapTest) -> Self {
+        let secret_hash = secret_hash(&SECRET);
+        let redeem_tranche = (test.program == Program::Anchor).then(|| {
+            let swap = test.swap(&test.swap_account(&secret_hash)).unwrap();
+            RedeemBuilder::new(&swap, SECRET)
+                .amount_lamports(TRANCHE_LAMPORTS)
+                .instruction()
+        });
+        Transitions {
+            redeem: test.redeem_instruction(&secret_hash, SECRET),
+            redeem_tranche,
+            refund: test.refund_instruction(&secret_hash),
+            instant_refund: (
+                test.instant_refund_instruction(&secret_hash),
+                test.redeemer.insecure_clone(),
+            ),
+        }
+    }
+
+    /// The instruction effecting `transition`, which must be one of those built
+    fn instruction(&self, transition: SwapTransition) -> (&Instruction, Vec<&Keypair>) {
+        match transition {
+            SwapTransition::Redeem {
+                amount_lamports: AMOUNT_LAMPORTS,
+            } => (&self.redeem, vec![]),
+            SwapTransition::Redeem {
+                amount_lamports: TRANCHE_LAMPORTS,
+            } => (self.redeem_tranche.as_ref().unwrap(), vec![]),
+            SwapTransition::Redeem { .. } => unreachable!(),
+            SwapTransition::Refund => (&self.refund, vec![]),
+            SwapTransition::InstantRefund => (&self.instant_refund.0, vec![&self.instant_refund.1]),
+        }
+    }
+
+    /// Effects `transition` on `test`, returning whether the program accepted it
+    fn send(&self, test: &mut SwapTest, transition: SwapTransition) -> bool {
+        let (instruction, signers) = self.instruction(transition);
+        test.send(std::slice::from_ref(instruction), &signers)
+            .is_ok()
+    }
+}
+
+#[test]
+#[ignore = "requires both programs to be built with `cargo build-sbf`"]
+fn programs_accept_exactly_the_transitions_of_the_model() {
+    for program in Program::ALL {
+        let mut attempts = vec![
+            SwapTransition::Redeem {
+                amount_lamports: AMOUNT_LAMPORTS,
+            },
+            SwapTransition::Refund,
+            SwapTransition::InstantRefund,
+        ];
+        // The lite program only redeems swaps in full
+        if program == Program::Anchor {
+            attempts.push(SwapTransition::Redeem {
+                amount_lamports: TRANCHE_LAMPORTS,
+            });
+        }
+
+        // Every state reachable in at most one transition, followed by every transition
+        let paths = std::iter::once(None).chain(attempts.iter().copied().map(Some));
+        for path in paths {
+            for &attempt in &attempts {
+                let mut test = SwapTest::with_program(program).unwrap();
+                test.initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+                    .unwrap();
+                let transitions = Transitions::new(&test);
+                // Past expiry, the swap's state is all that decides whether refunds are allowed
+                test.warp(EXPIRES_IN_SLOTS);
+
+                let mut state = SwapState::initiated(AMOUNT_LAMPORTS);
+                if let Some(transition) = path {
+                    assert!(
+                        transitions.send(&mut test, transition),
+                        "{program:?} rejected {transition} from {state}"
+                    );
+                    state = state.apply(transition).unwrap();
+                }
+
+                let allowed = state.apply(attempt).is_ok();
+                assert_eq!(
+                    transitions.send(&mut test, attempt),
+                    allowed,
+                    "{program:?} {} {attempt} from {state}",
+                    if allowed { "rejected" } else { "accepted" },
+                );
+            }
+        }
+    }
+}
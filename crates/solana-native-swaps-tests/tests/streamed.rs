@@ -0,0 +1,259 @@
+//! Streamed swaps, releasing native SOL to the redeemer linearly once the secret is revealed
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_native_swaps::{accounts, instruction, pda, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const STREAM_SLOTS: u64 = 40;
+const SECRET: [u8; 32] = [7; 32];
+
+struct Streamed {
+    test: SwapTest,
+    swap_account: Pubkey,
+}
+
+impl Streamed {
+    fn initiate() -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+        let swap_account =
+            pda::streamed_swap_account(&initiator, &redeemer, &secret_hash(&SECRET)).0;
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateStreamed {
+                amount_lamports: AMOUNT_LAMPORTS,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer,
+                secret_hash: secret_hash(&SECRET),
+                stream_slots: STREAM_SLOTS,
+            }
+            .data(),
+            accounts::InitiateStreamed {
+                swap_account,
+                config: pda::config().0,
+                initiator,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let initiator = test.initiator.insecure_clone();
+        test.send(&[initiate], &[&initiator]).unwrap();
+        Streamed { test, swap_account }
+    }
+
+    fn reveal_instruction(&self, secret: [u8; 32]) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RevealStreamed { secret }.data(),
+            accounts::RevealStreamed {
+                swap_account: self.swap_account,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn claim_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::ClaimStreamed {}.data(),
+            accounts::ClaimStreamed {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn dispute_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::DisputeStreamed {}.data(),
+            accounts::DisputeStreamed {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn refund_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundStreamed {}.data(),
+            accounts::RefundStreamed {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn streamed_funds_unlock_linearly_after_the_reveal() {
+    let mut streamed = Streamed::initiate();
+    let redeemer = streamed.test.redeemer.pubkey();
+    let before = streamed.test.balance(&redeemer);
+
+    assert_swap_error(
+        streamed.test.send(&[streamed.claim_instruction()], &[]),
+        SwapError::StreamNotRevealed,
+    );
+    assert_swap_error(
+        streamed
+            .test
+            .send(&[streamed.reveal_instruction([0; 32])], &[]),
+        SwapError::InvalidSecret,
+    );
+    streamed
+        .test
+        .send(&[streamed.reveal_instruction(SECRET)], &[])
+        .unwrap();
+
+    streamed.test.warp(STREAM_SLOTS / 4);
+    streamed
+        .test
+        .send(&[streamed.claim_instruction()], &[])
+        .unwrap();
+    assert_eq!(
+        streamed.test.balance(&redeemer),
+        before + AMOUNT_LAMPORTS / 4
+    );
+
+    streamed.test.warp(STREAM_SLOTS);
+    streamed
+        .test
+        .send(&[streamed.claim_instruction()], &[])
+        .unwrap();
+    assert_eq!(streamed.test.balance(&redeemer), before + AMOUNT_LAMPORTS);
+    assert!(streamed
+        .test
+        .svm
+        .get_account(&streamed.swap_account)
+        .is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn revealed_streams_can_no_longer_be_refunded() {
+    let mut streamed = Streamed::initiate();
+    streamed
+        .test
+        .send(&[streamed.reveal_instruction(SECRET)], &[])
+        .unwrap();
+    streamed.test.warp(EXPIRES_IN_SLOTS);
+
+    assert_swap_error(
+        streamed.test.send(&[streamed.refund_instruction()], &[]),
+        SwapError::StreamAlreadyRevealed,
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn unrevealed_streams_are_refunded_past_expiry() {
+    let mut streamed = Streamed::initiate();
+    let initiator = streamed.test.initiator.pubkey();
+
+    assert_swap_error(
+        streamed.test.send(&[streamed.refund_instruction()], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+    streamed.test.warp(EXPIRES_IN_SLOTS);
+    let before = streamed.test.balance(&initiator);
+    let escrowed = streamed.test.balance(&streamed.swap_account);
+    streamed
+        .test
+        .send(&[streamed.refund_instruction()], &[])
+        .unwrap();
+
+    assert_eq!(streamed.test.balance(&initiator), before + escrowed);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn disputes_halt_the_stream_returning_the_funds_yet_to_unlock() {
+    let mut streamed = Streamed::initiate();
+    let (initiator, redeemer) = (
+        streamed.test.initiator.pubkey(),
+        streamed.test.redeemer.pubkey(),
+    );
+    let signer = streamed.test.initiator.insecure_clone();
+    assert_swap_error(
+        streamed
+            .test
+            .send(&[streamed.dispute_instruction()], &[&signer]),
+        SwapError::StreamNotRevealed,
+    );
+    streamed
+        .test
+        .send(&[streamed.reveal_instruction(SECRET)], &[])
+        .unwrap();
+    streamed.test.warp(STREAM_SLOTS / 4);
+    streamed
+        .test
+        .send(&[streamed.claim_instruction()], &[])
+        .unwrap();
+    streamed.test.warp(STREAM_SLOTS / 4);
+
+    let (initiator_before, redeemer_before) = (
+        streamed.test.balance(&initiator),
+        streamed.test.balance(&redeemer),
+    );
+    let escrowed = streamed.test.balance(&streamed.swap_account);
+    streamed
+        .test
+        .send(&[streamed.dispute_instruction()], &[&signer])
+        .unwrap();
+
+    // Half of the funds unlocked, of which a quarter had already been claimed
+    assert_eq!(
+        streamed.test.balance(&redeemer),
+        redeemer_before + AMOUNT_LAMPORTS / 4
+    );
+    assert_eq!(
+        streamed.test.balance(&initiator),
+        initiator_before + escrowed - AMOUNT_LAMPORTS / 4
+    );
+    assert!(streamed
+        .test
+        .svm
+        .get_account(&streamed.swap_account)
+        .is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn fully_unlocked_streams_can_no_longer_be_disputed() {
+    let mut streamed = Streamed::initiate();
+    streamed
+        .test
+        .send(&[streamed.reveal_instruction(SECRET)], &[])
+        .unwrap();
+    streamed.test.warp(STREAM_SLOTS);
+
+    let signer = streamed.test.initiator.insecure_clone();
+    assert_swap_error(
+        streamed
+            .test
+            .send(&[streamed.dispute_instruction()], &[&signer]),
+        SwapError::StreamFullyUnlocked,
+    );
+}
@@ -0,0 +1,58 @@
+//! Sweeping of swaps abandoned long past their expiry.
+
+use solana_native_swaps::SwapError;
+use solana_native_swaps_client::{RefundBuilder, ABANDONED_SWAP_GRACE_SLOTS};
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn abandoned_swaps_are_swept_once_the_grace_period_has_passed() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let sweep = RefundBuilder::new(&test.swap(&swap_account).unwrap())
+        .sweep_abandoned()
+        .instruction();
+
+    test.warp(EXPIRES_IN_SLOTS);
+    assert_swap_error(
+        test.send(std::slice::from_ref(&sweep), &[]),
+        SwapError::SweepBeforeGracePeriod,
+    );
+
+    test.warp(ABANDONED_SWAP_GRACE_SLOTS - 1);
+    assert_swap_error(
+        test.send(std::slice::from_ref(&sweep), &[]),
+        SwapError::SweepBeforeGracePeriod,
+    );
+
+    test.warp(1);
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    test.send(&[sweep], &[]).unwrap();
+    assert!(test.swap(&swap_account).is_none());
+    assert_eq!(
+        test.balance(&initiator),
+        before + AMOUNT_LAMPORTS + test.swap_rent()
+    );
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn open_swaps_are_not_swept() {
+    let mut test = SwapTest::new().unwrap();
+    let swap_account = test
+        .initiate(&SECRET, AMOUNT_LAMPORTS, EXPIRES_IN_SLOTS)
+        .unwrap();
+    let sweep = RefundBuilder::new(&test.swap(&swap_account).unwrap())
+        .sweep_abandoned()
+        .instruction();
+
+    assert_swap_error(test.send(&[sweep], &[]), SwapError::RefundBeforeExpiry);
+}
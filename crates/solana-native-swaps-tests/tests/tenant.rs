@@ -0,0 +1,61 @@
+//! Per-tenant limits and metrics of the daemons serving several tenants
+
+use std::time::{Duration, Instant};
+
+use solana_native_swaps_client::{
+    tenant::{RateLimiter, TenantLimits, TenantMetrics, WebhookEvent},
+    Pubkey,
+};
+
+#[test]
+fn limits_apply_over_a_sliding_hour() {
+    let mut limiter = RateLimiter::new(&TenantLimits {
+        max_swaps_per_hour: Some(3),
+    });
+    let start = Instant::now();
+    assert_eq!(limiter.acquire_at(2, start), 2);
+    assert_eq!(limiter.acquire_at(2, start + Duration::from_secs(60)), 1);
+    assert_eq!(limiter.acquire_at(1, start + Duration::from_secs(120)), 0);
+    // The first two swaps leave the window an hour after being granted
+    assert_eq!(limiter.acquire_at(5, start + Duration::from_secs(3600)), 2);
+    assert_eq!(limiter.acquire_at(5, start + Duration::from_secs(3660)), 1);
+
+    let mut unlimited = RateLimiter::new(&TenantLimits::default());
+    assert_eq!(unlimited.acquire(1_000), 1_000);
+}
+
+#[test]
+fn metrics_are_labelled_by_tenant() {
+    let metrics = TenantMetrics::new("swap_keeper");
+    metrics.add("desk-a", "swaps_refunded", 3);
+    metrics.add("desk-b", "swaps_refunded", 1);
+    metrics.add("desk-a", "swaps_refunded", 2);
+    metrics.add("desk-\"c\"", "refund_batches_failed", 1);
+    assert_eq!(metrics.get("desk-a", "swaps_refunded"), 5);
+    assert_eq!(metrics.get("desk-b", "refund_batches_failed"), 0);
+    assert_eq!(
+        metrics.render(),
+        "# TYPE swap_keeper_refund_batches_failed_total counter\n\
+         swap_keeper_refund_batches_failed_total{tenant=\"desk-\\\"c\\\"\"} 1\n\
+         # TYPE swap_keeper_swaps_refunded_total counter\n\
+         swap_keeper_swaps_refunded_total{tenant=\"desk-a\"} 5\n\
+         swap_keeper_swaps_refunded_total{tenant=\"desk-b\"} 1\n"
+    );
+}
+
+#[test]
+fn webhook_events_omit_what_they_lack() {
+    let swap_account = Pubkey::new_unique();
+    let event = WebhookEvent::new("desk-a", "rate_limited", &[swap_account]);
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        serde_json::json!({
+            "tenant": "desk-a",
+            "event": "rate_limited",
+            "swap_accounts": [swap_account.to_string()],
+        })
+    );
+    let event = event.signature("5ig").detail("failed");
+    assert_eq!(event.signature.as_deref(), Some("5ig"));
+    assert_eq!(event.detail.as_deref(), Some("failed"));
+}
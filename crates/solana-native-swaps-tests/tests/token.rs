@@ -0,0 +1,370 @@
+//! The lifecycle of token swaps, escrowing tokens of a mint in a vault owned by the swap's PDA
+
+use anchor_lang::{
+    prelude::Pubkey, solana_program::system_instruction, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{self, TransferFeeConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use solana_keypair::Keypair;
+use solana_native_swaps::{accounts, instruction, pda, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT: u64 = 5_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+/// The transfer fee of the Token-2022 mint, withheld from every transfer of its tokens
+const TRANSFER_FEE_BPS: u16 = 100;
+
+/// The transfer fee withheld from a transfer of `amount` of the Token-2022 mint
+fn transfer_fee(amount: u64) -> u64 {
+    (amount * u64::from(TRANSFER_FEE_BPS)).div_ceil(10_000)
+}
+
+/// A token swap from the initiator to the redeemer locked to `SECRET`, with the token accounts
+/// of both parties
+struct TokenSwap {
+    mint: Pubkey,
+    token_program: Pubkey,
+    swap_account: Pubkey,
+    token_vault: Pubkey,
+    initiator_account: Pubkey,
+    redeemer_account: Pubkey,
+}
+
+impl TokenSwap {
+    fn new(
+        test: &SwapTest,
+        mint: Pubkey,
+        token_program: Pubkey,
+        initiator_account: Pubkey,
+        redeemer_account: Pubkey,
+    ) -> Self {
+        let swap_account = pda::token_swap_account(
+            &test.initiator.pubkey(),
+            &test.redeemer.pubkey(),
+            &secret_hash(&SECRET),
+        )
+        .0;
+        let token_vault = Pubkey::find_program_address(
+            &[b"token_vault", swap_account.as_ref()],
+            &solana_native_swaps::ID,
+        )
+        .0;
+        TokenSwap {
+            mint,
+            token_program,
+            swap_account,
+            token_vault,
+            initiator_account,
+            redeemer_account,
+        }
+    }
+
+    fn initiate(&self, test: &SwapTest) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateToken {
+                amount: AMOUNT,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer: test.redeemer.pubkey(),
+                secret_hash: secret_hash(&SECRET),
+            }
+            .data(),
+            accounts::InitiateToken {
+                swap_account: self.swap_account,
+                token_vault: self.token_vault,
+                initiator_token_account: self.initiator_account,
+                mint: self.mint,
+                config: pda::config().0,
+                initiator: test.initiator.pubkey(),
+                token_program: self.token_program,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn redeem(&self, test: &SwapTest, secret: [u8; 32]) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemToken { secret }.data(),
+            accounts::RedeemToken {
+                swap_account: self.swap_account,
+                token_vault: self.token_vault,
+                redeemer_token_account: self.redeemer_account,
+                mint: self.mint,
+                initiator: test.initiator.pubkey(),
+                token_program: self.token_program,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn refund(&self, test: &SwapTest) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundToken {}.data(),
+            accounts::RefundToken {
+                swap_account: self.swap_account,
+                token_vault: self.token_vault,
+                initiator_token_account: self.initiator_account,
+                mint: self.mint,
+                initiator: test.initiator.pubkey(),
+                token_program: self.token_program,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    /// The swap amount and the net amount escrowed after any transfer fee, as recorded by the
+    /// swap account
+    fn amounts(&self, test: &SwapTest) -> (u64, u64) {
+        let data = test.svm.get_account(&self.swap_account).unwrap().data;
+        let amount =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        (amount(8), amount(16))
+    }
+
+    fn is_closed(&self, test: &SwapTest) -> bool {
+        [self.swap_account, self.token_vault].iter().all(|address| {
+            test.svm
+                .get_account(address)
+                .is_none_or(|a| a.lamports == 0)
+        })
+    }
+}
+
+/// A swap of `AMOUNT` of an SPL token mint held by the initiator
+fn spl_token_swap(test: &mut SwapTest) -> TokenSwap {
+    let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+    let mint = test.create_mint(6);
+    let initiator_account = test.create_token_account(&mint, &initiator);
+    let redeemer_account = test.create_token_account(&mint, &redeemer);
+    test.mint_to(&mint, &initiator_account, AMOUNT);
+    TokenSwap::new(
+        test,
+        mint,
+        spl_token::ID,
+        initiator_account,
+        redeemer_account,
+    )
+}
+
+/// A swap of `AMOUNT` of a Token-2022 mint with a transfer fee of `TRANSFER_FEE_BPS`, held by
+/// the initiator
+fn token_2022_swap(test: &mut SwapTest) -> TokenSwap {
+    let payer = test.payer.pubkey();
+    let (mint, initiator_account, redeemer_account) =
+        (Keypair::new(), Keypair::new(), Keypair::new());
+    let mint_len =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+            .unwrap();
+    let account_len = ExtensionType::try_calculate_account_len::<TokenAccount>(&[
+        ExtensionType::TransferFeeAmount,
+    ])
+    .unwrap();
+    let mut instructions = vec![
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            test.svm.minimum_balance_for_rent_exemption(mint_len),
+            mint_len as u64,
+            &spl_token_2022::ID,
+        ),
+        transfer_fee::instruction::initialize_transfer_fee_config(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            None,
+            None,
+            TRANSFER_FEE_BPS,
+            u64::MAX,
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &payer,
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+    for (account, owner) in [
+        (&initiator_account, test.initiator.pubkey()),
+        (&redeemer_account, test.redeemer.pubkey()),
+    ] {
+        instructions.extend([
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                test.svm.minimum_balance_for_rent_exemption(account_len),
+                account_len as u64,
+                &spl_token_2022::ID,
+            ),
+            spl_token_2022::instruction::initialize_account3(
+                &spl_token_2022::ID,
+                &account.pubkey(),
+                &mint.pubkey(),
+                &owner,
+            )
+            .unwrap(),
+        ]);
+    }
+    instructions.push(
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &initiator_account.pubkey(),
+            &payer,
+            &[],
+            AMOUNT,
+        )
+        .unwrap(),
+    );
+    test.send(
+        &instructions,
+        &[&mint, &initiator_account, &redeemer_account],
+    )
+    .unwrap();
+    TokenSwap::new(
+        test,
+        mint.pubkey(),
+        spl_token_2022::ID,
+        initiator_account.pubkey(),
+        redeemer_account.pubkey(),
+    )
+}
+
+/// The balance of the Token-2022 token account at `address`
+fn token_2022_balance(test: &SwapTest, address: &Pubkey) -> u64 {
+    let account = test.svm.get_account(address).unwrap();
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+/// The transfer fees withheld by the Token-2022 mint at `address`, harvested from token accounts
+fn withheld_by_mint(test: &SwapTest, address: &Pubkey) -> u64 {
+    let account = test.svm.get_account(address).unwrap();
+    let mint = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    mint.get_extension::<TransferFeeConfig>()
+        .unwrap()
+        .withheld_amount
+        .into()
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn spl_token_swaps_are_redeemed_with_the_secret() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let swap = spl_token_swap(&mut test);
+
+    test.send(&[swap.initiate(&test)], &[&initiator]).unwrap();
+    assert_eq!(swap.amounts(&test), (AMOUNT, AMOUNT));
+    assert_eq!(test.token_balance(&swap.token_vault), AMOUNT);
+    assert_eq!(test.token_balance(&swap.initiator_account), 0);
+
+    assert_swap_error(
+        test.send(&[swap.redeem(&test, [8; 32])], &[]),
+        SwapError::InvalidSecret,
+    );
+
+    let initiator_before = test.balance(&initiator.pubkey());
+    let rent = test.balance(&swap.swap_account) + test.balance(&swap.token_vault);
+    test.send(&[swap.redeem(&test, SECRET)], &[]).unwrap();
+    assert!(swap.is_closed(&test));
+    assert_eq!(test.token_balance(&swap.redeemer_account), AMOUNT);
+    assert_eq!(test.balance(&initiator.pubkey()), initiator_before + rent);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn spl_token_swaps_are_refunded_once_expired() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let swap = spl_token_swap(&mut test);
+    test.send(&[swap.initiate(&test)], &[&initiator]).unwrap();
+
+    test.warp(EXPIRES_IN_SLOTS - 1);
+    assert_swap_error(
+        test.send(&[swap.refund(&test)], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+
+    test.warp(1);
+    test.send(&[swap.refund(&test)], &[]).unwrap();
+    assert!(swap.is_closed(&test));
+    assert_eq!(test.token_balance(&swap.initiator_account), AMOUNT);
+    assert_eq!(test.token_balance(&swap.redeemer_account), 0);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn token_2022_swaps_record_and_release_the_amount_net_of_transfer_fees() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let swap = token_2022_swap(&mut test);
+    let net_amount = AMOUNT - transfer_fee(AMOUNT);
+
+    test.send(&[swap.initiate(&test)], &[&initiator]).unwrap();
+    assert_eq!(swap.amounts(&test), (AMOUNT, net_amount));
+    assert_eq!(token_2022_balance(&test, &swap.token_vault), net_amount);
+
+    assert_swap_error(
+        test.send(&[swap.redeem(&test, [8; 32])], &[]),
+        SwapError::InvalidSecret,
+    );
+
+    // The fee is withheld again from the net amount released to the redeemer, with the fee
+    // withheld by the vault harvested to the mint for the vault to be closed
+    test.send(&[swap.redeem(&test, SECRET)], &[]).unwrap();
+    assert!(swap.is_closed(&test));
+    assert_eq!(
+        token_2022_balance(&test, &swap.redeemer_account),
+        net_amount - transfer_fee(net_amount)
+    );
+    assert_eq!(withheld_by_mint(&test, &swap.mint), transfer_fee(AMOUNT));
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn token_2022_swaps_are_refunded_net_of_transfer_fees_once_expired() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let swap = token_2022_swap(&mut test);
+    let net_amount = AMOUNT - transfer_fee(AMOUNT);
+    test.send(&[swap.initiate(&test)], &[&initiator]).unwrap();
+
+    assert_swap_error(
+        test.send(&[swap.refund(&test)], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+
+    test.warp(EXPIRES_IN_SLOTS);
+    test.send(&[swap.refund(&test)], &[]).unwrap();
+    assert!(swap.is_closed(&test));
+    assert_eq!(
+        token_2022_balance(&test, &swap.initiator_account),
+        net_amount - transfer_fee(net_amount)
+    );
+    assert_eq!(token_2022_balance(&test, &swap.redeemer_account), 0);
+}
@@ -0,0 +1,179 @@
+//! Token swaps of Token-2022 mints with transfer hooks, permitted per hook program by the config
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::InstructionError, system_instruction},
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_hook, ExtensionType},
+    state::{Account as TokenAccount, Mint},
+};
+use solana_keypair::Keypair;
+use solana_native_swaps::{accounts, instruction, pda, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+use solana_transaction_error::TransactionError;
+
+const AMOUNT: u64 = 5_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// Creates a Token-2022 mint whose transfer hook invokes `hook_program`, funding the initiator
+/// with `AMOUNT` of it in a token account, which is returned along with the mint
+fn create_hooked_mint(test: &mut SwapTest, hook_program: &Pubkey) -> (Pubkey, Pubkey) {
+    let (mint, token_account) = (Keypair::new(), Keypair::new());
+    let payer = test.payer.pubkey();
+    let mint_len =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+    let account_len = ExtensionType::try_calculate_account_len::<TokenAccount>(&[
+        ExtensionType::TransferHookAccount,
+    ])
+    .unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            test.svm.minimum_balance_for_rent_exemption(mint_len),
+            mint_len as u64,
+            &spl_token_2022::ID,
+        ),
+        transfer_hook::instruction::initialize(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            None,
+            Some(*hook_program),
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &payer,
+            None,
+            6,
+        )
+        .unwrap(),
+        system_instruction::create_account(
+            &payer,
+            &token_account.pubkey(),
+            test.svm.minimum_balance_for_rent_exemption(account_len),
+            account_len as u64,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::initialize_account3(
+            &spl_token_2022::ID,
+            &token_account.pubkey(),
+            &mint.pubkey(),
+            &test.initiator.pubkey(),
+        )
+        .unwrap(),
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::ID,
+            &mint.pubkey(),
+            &token_account.pubkey(),
+            &payer,
+            &[],
+            AMOUNT,
+        )
+        .unwrap(),
+    ];
+    test.send(&instructions, &[&mint, &token_account]).unwrap();
+    (mint.pubkey(), token_account.pubkey())
+}
+
+fn initiate_instruction(
+    test: &SwapTest,
+    mint: &Pubkey,
+    initiator_token_account: &Pubkey,
+) -> Instruction {
+    let swap_account = pda::token_swap_account(
+        &test.initiator.pubkey(),
+        &test.redeemer.pubkey(),
+        &secret_hash(&SECRET),
+    )
+    .0;
+    let token_vault = Pubkey::find_program_address(
+        &[b"token_vault", swap_account.as_ref()],
+        &solana_native_swaps::ID,
+    )
+    .0;
+    Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::InitiateToken {
+            amount: AMOUNT,
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+        }
+        .data(),
+        accounts::InitiateToken {
+            swap_account,
+            token_vault,
+            initiator_token_account: *initiator_token_account,
+            mint: *mint,
+            config: pda::config().0,
+            initiator: test.initiator.pubkey(),
+            token_program: spl_token_2022::ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    )
+}
+
+fn set_transfer_hook_permitted(test: &mut SwapTest, program_id: Pubkey, permitted: bool) {
+    let set_permitted = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::SetTransferHookPermitted {
+            program_id,
+            permitted,
+        }
+        .data(),
+        accounts::UpdateConfig {
+            config: pda::config().0,
+            admin: test.payer.pubkey(),
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    test.send(&[set_permitted], &[]).unwrap();
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn mints_with_transfer_hooks_are_only_swapped_if_permitted() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.insecure_clone();
+    let hook_program = Pubkey::new_unique();
+    let (mint, token_account) = create_hooked_mint(&mut test, &hook_program);
+    let initiate = initiate_instruction(&test, &mint, &token_account);
+
+    assert_swap_error(
+        test.send(std::slice::from_ref(&initiate), &[&initiator]),
+        SwapError::TransferHookNotPermitted,
+    );
+
+    // Past the permission, the transfer itself is attempted, failing for lack of the hook's
+    // accounts as no such program is deployed
+    set_transfer_hook_permitted(&mut test, hook_program, true);
+    let failed = test
+        .send(std::slice::from_ref(&initiate), &[&initiator])
+        .unwrap_err();
+    let not_permitted = InstructionError::Custom(
+        anchor_lang::error::ERROR_CODE_OFFSET + SwapError::TransferHookNotPermitted as u32,
+    );
+    assert_ne!(
+        failed.err,
+        TransactionError::InstructionError(0, not_permitted)
+    );
+
+    set_transfer_hook_permitted(&mut test, hook_program, false);
+    assert_swap_error(
+        test.send(&[initiate], &[&initiator]),
+        SwapError::TransferHookNotPermitted,
+    );
+}
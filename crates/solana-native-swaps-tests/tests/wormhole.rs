@@ -0,0 +1,176 @@
+//! Wormhole swaps, redeemed with a VAA attesting to the settlement of the counterpart leg
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_account::Account;
+use solana_native_swaps::{accounts, instruction, pda, SwapError, WORMHOLE_CORE_BRIDGE_PROGRAM_ID};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SWAP_ID: [u8; 32] = [7; 32];
+/// Ethereum, in Wormhole's chain IDs
+const EMITTER_CHAIN: u16 = 2;
+const EMITTER_ADDRESS: [u8; 32] = [9; 32];
+
+struct Wormhole {
+    test: SwapTest,
+    swap_account: Pubkey,
+    posted_vaa: Pubkey,
+}
+
+impl Wormhole {
+    fn initiate() -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+        let swap_account = pda::wormhole_swap_account(&initiator, &redeemer, &SWAP_ID).0;
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateWormhole {
+                amount_lamports: AMOUNT_LAMPORTS,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer,
+                swap_id: SWAP_ID,
+                emitter_chain: EMITTER_CHAIN,
+                emitter_address: EMITTER_ADDRESS,
+            }
+            .data(),
+            accounts::InitiateWormhole {
+                swap_account,
+                config: pda::config().0,
+                initiator,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let initiator = test.initiator.insecure_clone();
+        test.send(&[initiate], &[&initiator]).unwrap();
+        Wormhole {
+            test,
+            swap_account,
+            posted_vaa: Pubkey::new_unique(),
+        }
+    }
+
+    /// Posts a VAA from `emitter_address` with `payload`, as if verified by the core bridge
+    fn post_vaa(&mut self, emitter_address: [u8; 32], payload: &[u8]) {
+        let data = [
+            &b"vaa"[..],
+            // The version and consistency level
+            &[1, 1],
+            // The VAA time
+            &0u32.to_le_bytes(),
+            // The signature set
+            &[0; 32],
+            // The submission time and nonce
+            &0u32.to_le_bytes(),
+            &0u32.to_le_bytes(),
+            // The sequence
+            &1u64.to_le_bytes(),
+            &EMITTER_CHAIN.to_le_bytes(),
+            &emitter_address,
+            &(payload.len() as u32).to_le_bytes(),
+            payload,
+        ]
+        .concat();
+        let account = Account {
+            lamports: self.test.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: WORMHOLE_CORE_BRIDGE_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.test.svm.set_account(self.posted_vaa, account).unwrap();
+    }
+
+    fn redeem_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemWithVaa {}.data(),
+            accounts::RedeemWithVaa {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                posted_vaa: self.posted_vaa,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn vaas_attesting_to_the_swap_redeem_it() {
+    let mut wormhole = Wormhole::initiate();
+    let redeemer = wormhole.test.redeemer.pubkey();
+    let before = wormhole.test.balance(&redeemer);
+
+    let payload = wormhole.swap_account.to_bytes();
+    wormhole.post_vaa(EMITTER_ADDRESS, &payload);
+    let redeem = wormhole.redeem_instruction();
+    wormhole.test.send(&[redeem], &[]).unwrap();
+
+    assert!(wormhole
+        .test
+        .svm
+        .get_account(&wormhole.swap_account)
+        .is_none());
+    assert_eq!(wormhole.test.balance(&redeemer), before + AMOUNT_LAMPORTS);
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn vaas_of_other_emitters_or_swaps_are_rejected() {
+    let mut wormhole = Wormhole::initiate();
+    let payload = wormhole.swap_account.to_bytes();
+
+    wormhole.post_vaa([8; 32], &payload);
+    assert_swap_error(
+        wormhole.test.send(&[wormhole.redeem_instruction()], &[]),
+        SwapError::InvalidVaaEmitter,
+    );
+    wormhole.post_vaa(EMITTER_ADDRESS, &Pubkey::new_unique().to_bytes());
+    assert_swap_error(
+        wormhole.test.send(&[wormhole.redeem_instruction()], &[]),
+        SwapError::InvalidVaaPayload,
+    );
+    assert!(wormhole
+        .test
+        .svm
+        .get_account(&wormhole.swap_account)
+        .is_some());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn wormhole_swaps_are_refunded_past_expiry() {
+    let mut wormhole = Wormhole::initiate();
+    let initiator = wormhole.test.initiator.pubkey();
+    let refund = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RefundWormhole {}.data(),
+        accounts::RefundWormhole {
+            swap_account: wormhole.swap_account,
+            initiator,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+
+    assert_swap_error(
+        wormhole.test.send(std::slice::from_ref(&refund), &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+    wormhole.test.warp(EXPIRES_IN_SLOTS);
+    let before = wormhole.test.balance(&initiator);
+    let escrowed = wormhole.test.balance(&wormhole.swap_account);
+    wormhole.test.send(&[refund], &[]).unwrap();
+
+    assert_eq!(wormhole.test.balance(&initiator), before + escrowed);
+}
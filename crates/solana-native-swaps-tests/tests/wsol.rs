@@ -0,0 +1,120 @@
+//! Token swaps of wrapped SOL, wrapped from and unwrapped to native SOL
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token::{self, native_mint};
+use solana_native_swaps::{accounts, instruction, pda, SwapError};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+fn swap_account(test: &SwapTest) -> Pubkey {
+    pda::token_swap_account(
+        &test.initiator.pubkey(),
+        &test.redeemer.pubkey(),
+        &secret_hash(&SECRET),
+    )
+    .0
+}
+
+fn token_vault(test: &SwapTest) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"token_vault", swap_account(test).as_ref()],
+        &solana_native_swaps::ID,
+    )
+    .0
+}
+
+fn initiate(test: &mut SwapTest) {
+    let initiate = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::InitiateWsol {
+            amount_lamports: AMOUNT_LAMPORTS,
+            expires_in_slots: EXPIRES_IN_SLOTS,
+            redeemer: test.redeemer.pubkey(),
+            secret_hash: secret_hash(&SECRET),
+        }
+        .data(),
+        accounts::InitiateWsol {
+            swap_account: swap_account(test),
+            token_vault: token_vault(test),
+            mint: native_mint::ID,
+            config: pda::config().0,
+            initiator: test.initiator.pubkey(),
+            token_program: spl_token::ID,
+            system_program: anchor_lang::system_program::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    let initiator = test.initiator.insecure_clone();
+    test.send(&[initiate], &[&initiator]).unwrap();
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn wrapped_sol_is_unwrapped_to_the_redeemer() {
+    let mut test = SwapTest::new().unwrap();
+    let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+    let initiator_before = test.balance(&initiator);
+    initiate(&mut test);
+    assert_eq!(test.token_balance(&token_vault(&test)), AMOUNT_LAMPORTS);
+
+    let redeemer_before = test.balance(&redeemer);
+    let redeem = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RedeemWsol { secret: SECRET }.data(),
+        accounts::RedeemWsol {
+            swap_account: swap_account(&test),
+            token_vault: token_vault(&test),
+            initiator,
+            redeemer,
+            token_program: spl_token::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    test.send(&[redeem], &[]).unwrap();
+
+    assert_eq!(test.balance(&redeemer), redeemer_before + AMOUNT_LAMPORTS);
+    // The rent of the swap account and the vault is returned to the initiator
+    assert_eq!(test.balance(&initiator), initiator_before - AMOUNT_LAMPORTS);
+    assert!(test.svm.get_account(&swap_account(&test)).is_none());
+    assert!(test.svm.get_account(&token_vault(&test)).is_none());
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn wrapped_sol_is_unwrapped_back_to_the_initiator_past_expiry() {
+    let mut test = SwapTest::new().unwrap();
+    let initiator = test.initiator.pubkey();
+    let before = test.balance(&initiator);
+    initiate(&mut test);
+
+    let refund = Instruction::new_with_bytes(
+        solana_native_swaps::ID,
+        &instruction::RefundWsol {}.data(),
+        accounts::RefundWsol {
+            swap_account: swap_account(&test),
+            token_vault: token_vault(&test),
+            initiator,
+            token_program: spl_token::ID,
+            event_authority: pda::event_authority().0,
+            program: solana_native_swaps::ID,
+        }
+        .to_account_metas(None),
+    );
+    assert_swap_error(
+        test.send(std::slice::from_ref(&refund), &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+
+    test.warp(EXPIRES_IN_SLOTS);
+    test.send(&[refund], &[]).unwrap();
+    assert_eq!(test.balance(&initiator), before);
+}
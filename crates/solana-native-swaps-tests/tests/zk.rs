@@ -0,0 +1,200 @@
+//! Zero-knowledge swaps, redeemed with a Groth16 proof of knowledge of the secret
+
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use solana_native_swaps::{
+    accounts, instruction, pda, Groth16Proof, Groth16VerifyingKey, SwapError,
+};
+use solana_native_swaps_client::Instruction;
+use solana_native_swaps_tests::{assert_swap_error, secret_hash, SwapTest};
+use solana_signer::Signer;
+
+const AMOUNT_LAMPORTS: u64 = 1_000_000_000;
+const EXPIRES_IN_SLOTS: u64 = 100;
+const SECRET: [u8; 32] = [7; 32];
+
+/// The generator of BN254's G1, encoded as by the `alt_bn128` syscalls
+fn g1_generator() -> [u8; 64] {
+    let mut point = [0; 64];
+    point[31] = 1;
+    point[63] = 2;
+    point
+}
+
+/// The generator of BN254's G2, encoded as by the `alt_bn128` syscalls
+fn g2_generator() -> [u8; 128] {
+    let coordinates = [
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+        "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+        "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+        "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+    ];
+    let mut point = [0; 128];
+    for (chunk, coordinate) in point.chunks_mut(32).zip(coordinates) {
+        chunk.copy_from_slice(&hex(coordinate));
+    }
+    point
+}
+
+/// Decodes the big-endian hex `coordinate` of a point
+fn hex(coordinate: &str) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (byte, i) in bytes.iter_mut().zip((0..64).step_by(2)) {
+        *byte = u8::from_str_radix(&coordinate[i..i + 2], 16).unwrap();
+    }
+    bytes
+}
+
+/// Twice the generator of BN254's G1
+fn g1_double() -> [u8; 64] {
+    let mut point = [0; 64];
+    point[..32].copy_from_slice(&hex(
+        "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3",
+    ));
+    point[32..].copy_from_slice(&hex(
+        "15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+    ));
+    point
+}
+
+/// A well-formed verifying key, not of any actual circuit
+fn verifying_key() -> Groth16VerifyingKey {
+    Groth16VerifyingKey {
+        alpha_g1: g1_generator(),
+        beta_g2: g2_generator(),
+        gamma_g2: g2_generator(),
+        delta_g2: g2_generator(),
+        ic: [g1_generator(); 3],
+    }
+}
+
+#[test]
+fn proofs_satisfying_the_pairing_equation_are_accepted() {
+    // With every input point at infinity, vk_x is too, and the pairing equation reduces to
+    // e(-A, g2) · e(g1, g2) · e(C, g2) = 1, satisfied by A = g1 + C
+    let trivial_key = Groth16VerifyingKey {
+        ic: [[0; 64]; 3],
+        ..verifying_key()
+    };
+    let proof = Groth16Proof {
+        a: g1_double(),
+        b: g2_generator(),
+        c: g1_generator(),
+    };
+    assert!(trivial_key.verify(&proof, &secret_hash(&SECRET)));
+    assert!(!trivial_key.verify(
+        &Groth16Proof {
+            a: g1_generator(),
+            ..proof
+        },
+        &secret_hash(&SECRET)
+    ));
+    assert!(!verifying_key().verify(&proof, &secret_hash(&SECRET)));
+}
+
+struct Zk {
+    test: SwapTest,
+    swap_account: Pubkey,
+}
+
+impl Zk {
+    fn initiate() -> Self {
+        let mut test = SwapTest::new().unwrap();
+        let (initiator, redeemer) = (test.initiator.pubkey(), test.redeemer.pubkey());
+        let swap_account = pda::zk_swap_account(&initiator, &redeemer, &secret_hash(&SECRET)).0;
+        let initiate = Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::InitiateZk {
+                amount_lamports: AMOUNT_LAMPORTS,
+                expires_in_slots: EXPIRES_IN_SLOTS,
+                redeemer,
+                secret_hash: secret_hash(&SECRET),
+                verifying_key: verifying_key(),
+            }
+            .data(),
+            accounts::InitiateZk {
+                swap_account,
+                config: pda::config().0,
+                initiator,
+                system_program: anchor_lang::system_program::ID,
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        );
+        let initiator = test.initiator.insecure_clone();
+        test.send(&[initiate], &[&initiator]).unwrap();
+        Zk { test, swap_account }
+    }
+
+    fn redeem_instruction(&self, proof: Groth16Proof) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RedeemZk { proof }.data(),
+            accounts::RedeemZk {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                redeemer: self.test.redeemer.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+
+    fn refund_instruction(&self) -> Instruction {
+        Instruction::new_with_bytes(
+            solana_native_swaps::ID,
+            &instruction::RefundZk {}.data(),
+            accounts::RefundZk {
+                swap_account: self.swap_account,
+                initiator: self.test.initiator.pubkey(),
+                event_authority: pda::event_authority().0,
+                program: solana_native_swaps::ID,
+            }
+            .to_account_metas(None),
+        )
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn invalid_proofs_are_rejected() {
+    let mut zk = Zk::initiate();
+    let proofs = [
+        Groth16Proof {
+            a: [0; 64],
+            b: [0; 128],
+            c: [0; 64],
+        },
+        Groth16Proof {
+            a: g1_generator(),
+            b: g2_generator(),
+            c: g1_generator(),
+        },
+    ];
+
+    for proof in proofs {
+        assert_swap_error(
+            zk.test.send(&[zk.redeem_instruction(proof)], &[]),
+            SwapError::InvalidPreimageProof,
+        );
+    }
+}
+
+#[test]
+#[ignore = "requires the program to be built with `cargo build-sbf`"]
+fn zk_swaps_are_refunded_past_expiry() {
+    let mut zk = Zk::initiate();
+    let initiator = zk.test.initiator.pubkey();
+
+    assert_swap_error(
+        zk.test.send(&[zk.refund_instruction()], &[]),
+        SwapError::RefundBeforeExpiry,
+    );
+    zk.test.warp(EXPIRES_IN_SLOTS);
+    let before = zk.test.balance(&initiator);
+    let escrowed = zk.test.balance(&zk.swap_account);
+    zk.test.send(&[zk.refund_instruction()], &[]).unwrap();
+
+    assert_eq!(zk.test.balance(&initiator), before + escrowed);
+}
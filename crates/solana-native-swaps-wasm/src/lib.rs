@@ -0,0 +1,522 @@
+//! WebAssembly bindings for dApp frontends, exposing the derivation of the program's PDAs,
+//! the hashing of secrets, and the building of the instructions of the swap lifecycle.
+//!
+//! Addresses are passed as base58 strings, and secrets and secret hashes as 32-byte arrays.
+//! Built instructions expose their program ID, accounts and data, for conversion into a
+//! `TransactionInstruction` of `@solana/web3.js`:
+//!
+//! ```js
+//! const ix = new InitiateBuilder(initiator, redeemer, secretHash, 1_000_000_000n, 300n)
+//!   .refundTo(coldWallet)
+//!   .instruction();
+//! new TransactionInstruction({
+//!   programId: new PublicKey(ix.programId),
+//!   keys: ix.accounts.map((a) => ({ pubkey: new PublicKey(a.pubkey), isSigner: a.isSigner, isWritable: a.isWritable })),
+//!   data: Buffer.from(ix.data),
+//! });
+//! ```
+
+use std::str::FromStr;
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{hash, keccak, pubkey, system_program},
+    AnchorSerialize,
+};
+use ripemd::{Digest, Ripemd160};
+use wasm_bindgen::prelude::*;
+
+/// The ID of the swap program
+pub const PROGRAM_ID: Pubkey = pubkey!("6eksgdCnSjUaGQWZ6iYvauv1qzvYPF33RTGTM1ZuyENx");
+
+/// The algorithm used to compute a swap's secret hash from its secret,
+/// in the order of the program's `HashAlgorithm`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256 = 0,
+    Keccak256 = 1,
+    Hash160 = 2,
+    Blake3 = 3,
+}
+
+/// Computes the secret hash of `secret` using `algorithm`, as the program does
+#[wasm_bindgen(js_name = hashSecret)]
+pub fn hash_secret(algorithm: HashAlgorithm, secret: &[u8]) -> Result<Vec<u8>, JsError> {
+    let secret = bytes32(secret, "secret")?;
+    let secret_hash = match algorithm {
+        HashAlgorithm::Sha256 => hash::hash(&secret).to_bytes(),
+        HashAlgorithm::Keccak256 => keccak::hash(&secret).to_bytes(),
+        HashAlgorithm::Hash160 => {
+            let digest = Ripemd160::digest(hash::hash(&secret).to_bytes());
+            let mut secret_hash = [0; 32];
+            secret_hash[..digest.len()].copy_from_slice(&digest);
+            secret_hash
+        }
+        HashAlgorithm::Blake3 => solana_blake3_hasher::hash(&secret).to_bytes(),
+    };
+    Ok(secret_hash.to_vec())
+}
+
+/// The swap account of the swap from `initiator` to `redeemer` with `secret_hash`
+#[wasm_bindgen(js_name = swapAccountAddress)]
+pub fn swap_account_address(
+    initiator: &str,
+    redeemer: &str,
+    secret_hash: &[u8],
+) -> Result<String, JsError> {
+    let swap_account = swap_account(
+        &address(initiator)?,
+        &address(redeemer)?,
+        &bytes32(secret_hash, "secret hash")?,
+    );
+    Ok(swap_account.to_string())
+}
+
+/// The program-wide config
+#[wasm_bindgen(js_name = configAddress)]
+pub fn config_address() -> String {
+    pda(&[b"config"]).to_string()
+}
+
+/// The treasury collecting protocol fees
+#[wasm_bindgen(js_name = treasuryAddress)]
+pub fn treasury_address() -> String {
+    pda(&[b"treasury"]).to_string()
+}
+
+/// The redeem receipt of the swap account at `swap_account`
+#[wasm_bindgen(js_name = redeemReceiptAddress)]
+pub fn redeem_receipt_address(swap_account: &str) -> Result<String, JsError> {
+    Ok(redeem_receipt(&address(swap_account)?).to_string())
+}
+
+/// The authority the program emits events through, by invoking itself
+#[wasm_bindgen(js_name = eventAuthorityAddress)]
+pub fn event_authority_address() -> String {
+    event_authority().to_string()
+}
+
+/// An account of an instruction
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapAccountMeta {
+    pub pubkey: String,
+    #[wasm_bindgen(js_name = isSigner)]
+    pub is_signer: bool,
+    #[wasm_bindgen(js_name = isWritable)]
+    pub is_writable: bool,
+}
+
+/// An instruction of the swap program
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapInstruction {
+    #[wasm_bindgen(js_name = programId)]
+    pub program_id: String,
+    pub accounts: Vec<SwapAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl SwapInstruction {
+    fn new(name: &str, args: impl AnchorSerialize, accounts: Vec<AccountMeta>) -> Self {
+        // Anchor prefixes instruction data with the leading bytes of the hash of its name
+        let mut data = hash::hash(format!("global:{name}").as_bytes()).to_bytes()[..8].to_vec();
+        args.serialize(&mut data)
+            .expect("serializing to a vector cannot fail");
+        SwapInstruction {
+            program_id: PROGRAM_ID.to_string(),
+            accounts: accounts
+                .into_iter()
+                .map(|meta| SwapAccountMeta {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data,
+        }
+    }
+}
+
+/// Builds an `initiate` instruction. Swaps default to SHA-256 secret hashes, with no incentives
+/// or redeem window, funded by the initiator, and refunded to the initiator.
+#[wasm_bindgen]
+pub struct InitiateBuilder {
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    secret_hash: [u8; 32],
+    amount_lamports: u64,
+    expires_in_slots: u64,
+    hash_algorithm: HashAlgorithm,
+    relayer_fee_lamports: u64,
+    refund_tip_lamports: u64,
+    relayer_fee_start_lamports: Option<u64>,
+    redeem_after_slot: Option<u64>,
+    redeem_deadline_slot: Option<u64>,
+    funder: Option<Pubkey>,
+    refund_to: Option<Pubkey>,
+}
+
+#[wasm_bindgen]
+impl InitiateBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        initiator: &str,
+        redeemer: &str,
+        secret_hash: &[u8],
+        amount_lamports: u64,
+        expires_in_slots: u64,
+    ) -> Result<InitiateBuilder, JsError> {
+        Ok(InitiateBuilder {
+            initiator: address(initiator)?,
+            redeemer: address(redeemer)?,
+            secret_hash: bytes32(secret_hash, "secret hash")?,
+            amount_lamports,
+            expires_in_slots,
+            hash_algorithm: HashAlgorithm::default(),
+            relayer_fee_lamports: 0,
+            refund_tip_lamports: 0,
+            relayer_fee_start_lamports: None,
+            redeem_after_slot: None,
+            redeem_deadline_slot: None,
+            funder: None,
+            refund_to: None,
+        })
+    }
+
+    #[wasm_bindgen(js_name = hashAlgorithm)]
+    pub fn hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+
+    /// Offers `relayer_fee_lamports` to a relayer submitting `redeem`
+    #[wasm_bindgen(js_name = relayerFee)]
+    pub fn relayer_fee(mut self, relayer_fee_lamports: u64) -> Self {
+        self.relayer_fee_lamports = relayer_fee_lamports;
+        self
+    }
+
+    /// Auctions the relayer fee, starting at `relayer_fee_start_lamports` upon initiation and
+    /// rising linearly to the relayer fee at expiry
+    #[wasm_bindgen(js_name = relayerFeeStart)]
+    pub fn relayer_fee_start(mut self, relayer_fee_start_lamports: u64) -> Self {
+        self.relayer_fee_start_lamports = Some(relayer_fee_start_lamports);
+        self
+    }
+
+    /// Offers `refund_tip_lamports` to a third party submitting `refund` after expiry
+    #[wasm_bindgen(js_name = refundTip)]
+    pub fn refund_tip(mut self, refund_tip_lamports: u64) -> Self {
+        self.refund_tip_lamports = refund_tip_lamports;
+        self
+    }
+
+    /// Only allows redeems after `slot`
+    #[wasm_bindgen(js_name = redeemAfterSlot)]
+    pub fn redeem_after_slot(mut self, slot: u64) -> Self {
+        self.redeem_after_slot = Some(slot);
+        self
+    }
+
+    /// No longer allows redeems after `slot`
+    #[wasm_bindgen(js_name = redeemDeadlineSlot)]
+    pub fn redeem_deadline_slot(mut self, slot: u64) -> Self {
+        self.redeem_deadline_slot = Some(slot);
+        self
+    }
+
+    /// Has `funder` provide the swap amount on behalf of the initiator.
+    /// The funder must then sign the transaction too.
+    pub fn funder(mut self, funder: &str) -> Result<InitiateBuilder, JsError> {
+        self.funder = Some(address(funder)?);
+        Ok(self)
+    }
+
+    /// Has refunds paid to `refund_to` in place of the initiator
+    #[wasm_bindgen(js_name = refundTo)]
+    pub fn refund_to(mut self, refund_to: &str) -> Result<InitiateBuilder, JsError> {
+        self.refund_to = Some(address(refund_to)?);
+        Ok(self)
+    }
+
+    /// The address of the swap account the instruction creates
+    #[wasm_bindgen(js_name = swapAccount)]
+    pub fn swap_account(&self) -> String {
+        swap_account(&self.initiator, &self.redeemer, &self.secret_hash).to_string()
+    }
+
+    pub fn instruction(&self) -> SwapInstruction {
+        SwapInstruction::new(
+            "initiate",
+            (
+                self.amount_lamports,
+                self.expires_in_slots,
+                self.redeemer,
+                self.secret_hash,
+                self.hash_algorithm as u8,
+                (
+                    self.relayer_fee_lamports,
+                    self.refund_tip_lamports,
+                    self.relayer_fee_start_lamports,
+                ),
+                (self.redeem_after_slot, self.redeem_deadline_slot),
+            ),
+            vec![
+                AccountMeta::new(
+                    swap_account(&self.initiator, &self.redeemer, &self.secret_hash),
+                    false,
+                ),
+                AccountMeta::new_readonly(pda(&[b"config"]), false),
+                AccountMeta::new(self.initiator, true),
+                optional(self.funder, AccountMeta::new(PROGRAM_ID, true)),
+                optional(self.refund_to, AccountMeta::new_readonly(PROGRAM_ID, false)),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(event_authority(), false),
+                AccountMeta::new_readonly(PROGRAM_ID, false),
+            ],
+        )
+    }
+}
+
+/// Builds a `redeem` instruction for a swap, redeeming `amount_lamports` of its remaining funds
+/// to the redeemer by default
+#[wasm_bindgen]
+pub struct RedeemBuilder {
+    swap_account: Pubkey,
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    secret: [u8; 32],
+    amount_lamports: u64,
+    destination: Option<Pubkey>,
+    relayer: Option<Pubkey>,
+    receipt_payer: Option<Pubkey>,
+}
+
+#[wasm_bindgen]
+impl RedeemBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        initiator: &str,
+        redeemer: &str,
+        secret_hash: &[u8],
+        secret: &[u8],
+        amount_lamports: u64,
+    ) -> Result<RedeemBuilder, JsError> {
+        let (initiator, redeemer) = (address(initiator)?, address(redeemer)?);
+        Ok(RedeemBuilder {
+            swap_account: swap_account(
+                &initiator,
+                &redeemer,
+                &bytes32(secret_hash, "secret hash")?,
+            ),
+            initiator,
+            redeemer,
+            secret: bytes32(secret, "secret")?,
+            amount_lamports,
+            destination: None,
+            relayer: None,
+            receipt_payer: None,
+        })
+    }
+
+    /// Directs the funds to `destination` in place of the redeemer.
+    /// The redeemer must then sign the transaction.
+    pub fn destination(mut self, destination: &str) -> Result<RedeemBuilder, JsError> {
+        self.destination = Some(address(destination)?);
+        Ok(self)
+    }
+
+    /// Has `relayer` submit the instruction on the redeemer's behalf, claiming the swap's relayer fee.
+    /// The relayer must then sign the transaction.
+    pub fn relayer(mut self, relayer: &str) -> Result<RedeemBuilder, JsError> {
+        self.relayer = Some(address(relayer)?);
+        Ok(self)
+    }
+
+    /// Records the revealed secret in a redeem receipt, funded by `receipt_payer`.
+    /// The receipt payer must then sign the transaction.
+    pub fn receipt(mut self, receipt_payer: &str) -> Result<RedeemBuilder, JsError> {
+        self.receipt_payer = Some(address(receipt_payer)?);
+        Ok(self)
+    }
+
+    pub fn instruction(&self) -> SwapInstruction {
+        let receipt = self
+            .receipt_payer
+            .map(|_| redeem_receipt(&self.swap_account));
+        SwapInstruction::new(
+            "redeem",
+            (self.secret, self.amount_lamports),
+            vec![
+                AccountMeta::new(self.swap_account, false),
+                AccountMeta::new(self.initiator, false),
+                AccountMeta::new(self.redeemer, false),
+                optional(self.destination, AccountMeta::new(PROGRAM_ID, false)),
+                AccountMeta::new_readonly(pda(&[b"config"]), false),
+                AccountMeta::new(pda(&[b"treasury"]), false),
+                optional(self.relayer, AccountMeta::new(PROGRAM_ID, true)),
+                optional(receipt, AccountMeta::new(PROGRAM_ID, false)),
+                optional(self.receipt_payer, AccountMeta::new(PROGRAM_ID, true)),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(event_authority(), false),
+                AccountMeta::new_readonly(PROGRAM_ID, false),
+            ],
+        )
+    }
+}
+
+/// Builds a `refund` instruction for an expired swap
+#[wasm_bindgen]
+pub struct RefundBuilder {
+    swap_account: Pubkey,
+    initiator: Pubkey,
+    refund_to: Option<Pubkey>,
+    refunder: Option<Pubkey>,
+}
+
+#[wasm_bindgen]
+impl RefundBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        initiator: &str,
+        redeemer: &str,
+        secret_hash: &[u8],
+    ) -> Result<RefundBuilder, JsError> {
+        let initiator = address(initiator)?;
+        Ok(RefundBuilder {
+            swap_account: swap_account(
+                &initiator,
+                &address(redeemer)?,
+                &bytes32(secret_hash, "secret hash")?,
+            ),
+            initiator,
+            refund_to: None,
+            refunder: None,
+        })
+    }
+
+    /// Pays the refund destination specified at initiate, which is then required
+    #[wasm_bindgen(js_name = refundTo)]
+    pub fn refund_to(mut self, refund_to: &str) -> Result<RefundBuilder, JsError> {
+        self.refund_to = Some(address(refund_to)?);
+        Ok(self)
+    }
+
+    /// Has `refunder` submit the instruction, claiming the swap's refund tip.
+    /// The refunder must then sign the transaction.
+    pub fn refunder(mut self, refunder: &str) -> Result<RefundBuilder, JsError> {
+        self.refunder = Some(address(refunder)?);
+        Ok(self)
+    }
+
+    pub fn instruction(&self) -> SwapInstruction {
+        SwapInstruction::new(
+            "refund",
+            (),
+            vec![
+                AccountMeta::new(self.swap_account, false),
+                AccountMeta::new(self.initiator, false),
+                optional(self.refund_to, AccountMeta::new(PROGRAM_ID, false)),
+                optional(self.refunder, AccountMeta::new(PROGRAM_ID, true)),
+                AccountMeta::new_readonly(event_authority(), false),
+                AccountMeta::new_readonly(PROGRAM_ID, false),
+            ],
+        )
+    }
+}
+
+/// Builds an `instant_refund` instruction for a swap, which the redeemer must sign
+#[wasm_bindgen]
+pub struct InstantRefundBuilder {
+    swap_account: Pubkey,
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    refund_to: Option<Pubkey>,
+}
+
+#[wasm_bindgen]
+impl InstantRefundBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        initiator: &str,
+        redeemer: &str,
+        secret_hash: &[u8],
+    ) -> Result<InstantRefundBuilder, JsError> {
+        let (initiator, redeemer) = (address(initiator)?, address(redeemer)?);
+        Ok(InstantRefundBuilder {
+            swap_account: swap_account(
+                &initiator,
+                &redeemer,
+                &bytes32(secret_hash, "secret hash")?,
+            ),
+            initiator,
+            redeemer,
+            refund_to: None,
+        })
+    }
+
+    /// Pays the refund destination specified at initiate, which is then required
+    #[wasm_bindgen(js_name = refundTo)]
+    pub fn refund_to(mut self, refund_to: &str) -> Result<InstantRefundBuilder, JsError> {
+        self.refund_to = Some(address(refund_to)?);
+        Ok(self)
+    }
+
+    pub fn instruction(&self) -> SwapInstruction {
+        SwapInstruction::new(
+            "instant_refund",
+            (),
+            vec![
+                AccountMeta::new(self.swap_account, false),
+                AccountMeta::new(self.initiator, false),
+                optional(self.refund_to, AccountMeta::new(PROGRAM_ID, false)),
+                AccountMeta::new(self.redeemer, true),
+                AccountMeta::new_readonly(event_authority(), false),
+                AccountMeta::new_readonly(PROGRAM_ID, false),
+            ],
+        )
+    }
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &PROGRAM_ID).0
+}
+
+fn swap_account(initiator: &Pubkey, redeemer: &Pubkey, secret_hash: &[u8; 32]) -> Pubkey {
+    pda(&[
+        b"swap_account",
+        initiator.as_ref(),
+        redeemer.as_ref(),
+        secret_hash,
+    ])
+}
+
+fn redeem_receipt(swap_account: &Pubkey) -> Pubkey {
+    pda(&[b"redeem_receipt", swap_account.as_ref()])
+}
+
+fn event_authority() -> Pubkey {
+    pda(&[b"__event_authority"])
+}
+
+/// The account of an optional account of an instruction, taking the flags of `meta`.
+/// Anchor passes the program ID, read-only and unsigned, in place of omitted accounts.
+fn optional(account: Option<Pubkey>, meta: AccountMeta) -> AccountMeta {
+    match account {
+        Some(pubkey) => AccountMeta { pubkey, ..meta },
+        None => AccountMeta::new_readonly(PROGRAM_ID, false),
+    }
+}
+
+fn address(address: &str) -> Result<Pubkey, JsError> {
+    Pubkey::from_str(address).map_err(|_| JsError::new(&format!("invalid address {address}")))
+}
+
+fn bytes32(bytes: &[u8], name: &str) -> Result<[u8; 32], JsError> {
+    bytes
+        .try_into()
+        .map_err(|_| JsError::new(&format!("{name} must be 32 bytes, got {}", bytes.len())))
+}
@@ -0,0 +1,234 @@
+//! Checks the bindings against the client's builders, which use the program's own types,
+//! so that the mirrored layouts cannot drift from the program's.
+
+use solana_native_swaps_client::{
+    pda, HashAlgorithm, InitiateBuilder, InstantRefundBuilder, Instruction, Pubkey, RedeemBuilder,
+    RedeemWindow, RefundBuilder, SwapAccount, SwapIncentives, ID, SWAP_SEEDS_VERSION,
+};
+use solana_native_swaps_wasm as wasm;
+
+const SECRET: [u8; 32] = [7; 32];
+
+fn converted(instruction: Instruction) -> wasm::SwapInstruction {
+    wasm::SwapInstruction {
+        program_id: instruction.program_id.to_string(),
+        accounts: instruction
+            .accounts
+            .into_iter()
+            .map(|meta| wasm::SwapAccountMeta {
+                pubkey: meta.pubkey.to_string(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: instruction.data,
+    }
+}
+
+fn swap(refund_to: Pubkey) -> SwapAccount {
+    let mut swap: SwapAccount = bytemuck::Zeroable::zeroed();
+    swap.initiator = Pubkey::new_unique();
+    swap.redeemer = Pubkey::new_unique();
+    swap.secret_hash = HashAlgorithm::Sha256.hash(&SECRET);
+    swap.amount_lamports = 1_000_000;
+    swap.refund_to = refund_to;
+    swap.seeds_version = SWAP_SEEDS_VERSION;
+    swap
+}
+
+#[test]
+fn program_addresses_match() {
+    let swap = swap(Pubkey::default());
+    let swap_account = pda::swap_account(&swap.initiator, &swap.redeemer, &swap.secret_hash).0;
+    assert_eq!(wasm::PROGRAM_ID, ID);
+    assert_eq!(
+        wasm::swap_account_address(
+            &swap.initiator.to_string(),
+            &swap.redeemer.to_string(),
+            &swap.secret_hash,
+        )
+        .unwrap(),
+        swap_account.to_string()
+    );
+    assert_eq!(wasm::config_address(), pda::config().0.to_string());
+    assert_eq!(wasm::treasury_address(), pda::treasury().0.to_string());
+    assert_eq!(
+        wasm::redeem_receipt_address(&swap_account.to_string()).unwrap(),
+        pda::redeem_receipt(&swap_account).0.to_string()
+    );
+    assert_eq!(
+        wasm::event_authority_address(),
+        pda::event_authority().0.to_string()
+    );
+}
+
+#[test]
+fn secret_hashes_match() {
+    for (algorithm, program_algorithm) in [
+        (wasm::HashAlgorithm::Sha256, HashAlgorithm::Sha256),
+        (wasm::HashAlgorithm::Keccak256, HashAlgorithm::Keccak256),
+        (wasm::HashAlgorithm::Hash160, HashAlgorithm::Hash160),
+        (wasm::HashAlgorithm::Blake3, HashAlgorithm::Blake3),
+    ] {
+        assert_eq!(
+            wasm::hash_secret(algorithm, &SECRET).unwrap(),
+            program_algorithm.hash(&SECRET)
+        );
+    }
+}
+
+#[test]
+fn initiate_instructions_match() {
+    let swap = swap(Pubkey::default());
+    let (funder, refund_to) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let new = || {
+        wasm::InitiateBuilder::new(
+            &swap.initiator.to_string(),
+            &swap.redeemer.to_string(),
+            &swap.secret_hash,
+            swap.amount_lamports,
+            300,
+        )
+        .unwrap()
+    };
+    let expected = || {
+        InitiateBuilder::new(
+            swap.initiator,
+            swap.redeemer,
+            swap.secret_hash,
+            swap.amount_lamports,
+            300,
+        )
+    };
+
+    assert_eq!(new().instruction(), converted(expected().instruction()));
+    assert_eq!(
+        new()
+            .hash_algorithm(wasm::HashAlgorithm::Blake3)
+            .relayer_fee(1_000)
+            .refund_tip(2_000)
+            .relayer_fee_start(500)
+            .redeem_after_slot(10)
+            .redeem_deadline_slot(20)
+            .funder(&funder.to_string())
+            .unwrap()
+            .refund_to(&refund_to.to_string())
+            .unwrap()
+            .instruction(),
+        converted(
+            expected()
+                .hash_algorithm(HashAlgorithm::Blake3)
+                .incentives(SwapIncentives {
+                    relayer_fee_lamports: 1_000,
+                    refund_tip_lamports: 2_000,
+                    relayer_fee_start_lamports: Some(500),
+                })
+                .redeem_window(RedeemWindow {
+                    redeem_after_slot: Some(10),
+                    redeem_deadline_slot: Some(20),
+                })
+                .funder(funder)
+                .refund_to(refund_to)
+                .instruction()
+        )
+    );
+}
+
+#[test]
+fn redeem_instructions_match() {
+    let swap = swap(Pubkey::default());
+    let (destination, relayer, receipt_payer) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let new = || {
+        wasm::RedeemBuilder::new(
+            &swap.initiator.to_string(),
+            &swap.redeemer.to_string(),
+            &swap.secret_hash,
+            &SECRET,
+            swap.amount_lamports,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        new().instruction(),
+        converted(RedeemBuilder::new(&swap, SECRET).instruction())
+    );
+    assert_eq!(
+        new()
+            .destination(&destination.to_string())
+            .unwrap()
+            .relayer(&relayer.to_string())
+            .unwrap()
+            .receipt(&receipt_payer.to_string())
+            .unwrap()
+            .instruction(),
+        converted(
+            RedeemBuilder::new(&swap, SECRET)
+                .destination(destination)
+                .relayer(relayer)
+                .receipt(receipt_payer)
+                .instruction()
+        )
+    );
+}
+
+#[test]
+fn refund_instructions_match() {
+    let (refund_to, refunder) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let swap = swap(Pubkey::default());
+    let new = || {
+        wasm::RefundBuilder::new(
+            &swap.initiator.to_string(),
+            &swap.redeemer.to_string(),
+            &swap.secret_hash,
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        new().instruction(),
+        converted(RefundBuilder::new(&swap).instruction())
+    );
+    assert_eq!(
+        new().refunder(&refunder.to_string()).unwrap().instruction(),
+        converted(RefundBuilder::new(&swap).refunder(refunder).instruction())
+    );
+
+    let swap = self::swap(refund_to);
+    assert_eq!(
+        wasm::RefundBuilder::new(
+            &swap.initiator.to_string(),
+            &swap.redeemer.to_string(),
+            &swap.secret_hash,
+        )
+        .unwrap()
+        .refund_to(&refund_to.to_string())
+        .unwrap()
+        .instruction(),
+        converted(RefundBuilder::new(&swap).instruction())
+    );
+}
+
+#[test]
+fn instant_refund_instructions_match() {
+    let refund_to = Pubkey::new_unique();
+    for swap in [swap(Pubkey::default()), swap(refund_to)] {
+        let mut builder = wasm::InstantRefundBuilder::new(
+            &swap.initiator.to_string(),
+            &swap.redeemer.to_string(),
+            &swap.secret_hash,
+        )
+        .unwrap();
+        if let Some(refund_to) = swap.refund_to() {
+            builder = builder.refund_to(&refund_to.to_string()).unwrap();
+        }
+        assert_eq!(
+            builder.instruction(),
+            converted(InstantRefundBuilder::new(&swap).instruction())
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use solana_native_swaps_client::{tenant::TenantLimits, Pubkey};
+
+use crate::vault::VAULT_KEY_ENV;
+
+/// The configuration of the watcher, read from a TOML file.
+/// See `config.example.toml` for a documented example.
+#[derive(Deserialize)]
+pub struct Config {
+    pub rpc_url: String,
+    pub keypair: Option<String>,
+    #[serde(default)]
+    pub redeemers: Vec<Pubkey>,
+    #[serde(default = "default_secret_retention_secs")]
+    pub secret_retention_secs: u64,
+    pub source: SourceConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub priority_fee: PriorityFeeConfig,
+    pub reconcile: Option<ReconcileConfig>,
+    pub vault: Option<VaultConfig>,
+    /// The tenants to redeem the swaps of, instead of those of `keypair` and `redeemers`
+    #[serde(default)]
+    pub tenants: Vec<TenantConfig>,
+    pub metrics_addr: Option<SocketAddr>,
+}
+
+impl Config {
+    pub fn read(path: &Path) -> Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        toml::from_str(&config).with_context(|| format!("Malformed config {}", path.display()))
+    }
+
+    /// The tenants to redeem the swaps of, a single one named `default` unless `tenants` are
+    /// configured
+    pub fn tenants(&self) -> Result<Vec<TenantConfig>> {
+        if self.tenants.is_empty() {
+            let Some(keypair) = &self.keypair else {
+                bail!("Either a keypair or tenants must be configured");
+            };
+            return Ok(vec![TenantConfig {
+                name: "default".to_string(),
+                keypair: keypair.clone(),
+                redeemers: self.redeemers.clone(),
+                vault: self.vault.clone(),
+                limits: TenantLimits::default(),
+                webhook_url: None,
+            }]);
+        }
+        if self.keypair.is_some() || !self.redeemers.is_empty() || self.vault.is_some() {
+            bail!("The keypair, redeemers and vault are configured per tenant with tenants");
+        }
+        let mut names = HashSet::new();
+        for tenant in &self.tenants {
+            if !names.insert(&tenant.name) {
+                bail!("Tenant {} is configured twice", tenant.name);
+            }
+        }
+        Ok(self.tenants.clone())
+    }
+}
+
+/// A tenant whose swaps are redeemed under its own keypair, limits and webhook
+#[derive(Clone, Deserialize)]
+pub struct TenantConfig {
+    pub name: String,
+    pub keypair: String,
+    /// The redeemers of the tenant's swaps, defaulting to the keypair's address
+    #[serde(default)]
+    pub redeemers: Vec<Pubkey>,
+    pub vault: Option<VaultConfig>,
+    #[serde(default)]
+    pub limits: TenantLimits,
+    pub webhook_url: Option<String>,
+}
+
+/// Where revealed secrets are read from
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SourceConfig {
+    /// Polls `url` for a JSON array of hex-encoded secrets
+    Http {
+        url: String,
+        poll_interval_secs: u64,
+    },
+    /// Reads hex-encoded secrets from stdin, one per line
+    Stdin,
+}
+
+/// How the watched swaps are reconciled with their counterparty legs
+#[derive(Deserialize)]
+pub struct ReconcileConfig {
+    pub interval_secs: u64,
+    pub counterparty: CounterpartyConfig,
+}
+
+/// The chain the counterparty legs of swaps are on
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CounterpartyConfig {
+    /// Queries the logs of an HTLC contract through the JSON-RPC of an EVM node
+    Evm {
+        rpc_url: String,
+        htlc: String,
+        #[serde(default)]
+        from_block: u64,
+    },
+    /// Queries the transactions of HTLC addresses through an Esplora API, with the HTLC address
+    /// of each secret hash served by `htlc_addresses_url` as a JSON object of hex-encoded secret
+    /// hashes to addresses
+    Esplora {
+        url: String,
+        htlc_addresses_url: String,
+    },
+}
+
+/// The encrypted vault of the secrets held by the watcher, and the policy revealing them
+#[derive(Clone, Deserialize)]
+pub struct VaultConfig {
+    pub path: PathBuf,
+    /// The environment variable holding the hex-encoded 32-byte key the vault is encrypted with
+    #[serde(default = "default_vault_key_env")]
+    pub key_env: String,
+    /// The confirmations the counterparty leg of a swap must have before the swap is redeemed
+    /// with its secret
+    pub min_confirmations: u64,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 8,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct PriorityFeeConfig {
+    pub compute_unit_limit: u32,
+    pub initial_micro_lamports: u64,
+    pub multiplier: u64,
+    pub max_micro_lamports: u64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        PriorityFeeConfig {
+            compute_unit_limit: 60_000,
+            initial_micro_lamports: 1_000,
+            multiplier: 2,
+            max_micro_lamports: 1_000_000,
+        }
+    }
+}
+
+fn default_vault_key_env() -> String {
+    VAULT_KEY_ENV.to_string()
+}
+
+fn default_secret_retention_secs() -> u64 {
+    24 * 60 * 60
+}
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use solana_native_swaps_client::HashAlgorithm;
+
+use crate::config::CounterpartyConfig;
+
+/// The events of the EVM HTLC contract, the order ID and secret hash being indexed
+const EVM_INITIATED: &str = "Initiated(bytes32,bytes32,uint256)";
+const EVM_REDEEMED: &str = "Redeemed(bytes32,bytes32,bytes)";
+const EVM_REFUNDED: &str = "Refunded(bytes32)";
+
+/// The state of the counterparty leg of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterpartyLeg {
+    NotFunded,
+    /// Funded in a block with `confirmations` blocks on top of it, including its own
+    Funded {
+        confirmations: u64,
+    },
+    /// Redeemed with `secret`, if it could be read from the redeem
+    Redeemed {
+        secret: Option<[u8; 32]>,
+    },
+    Refunded,
+}
+
+/// A chain the counterparty legs of swaps are queried on
+pub enum Counterparty {
+    Evm {
+        http: reqwest::Client,
+        rpc_url: String,
+        htlc: String,
+        from_block: u64,
+    },
+    Esplora {
+        http: reqwest::Client,
+        url: String,
+        htlc_addresses_url: String,
+    },
+}
+
+impl Counterparty {
+    pub fn new(config: &CounterpartyConfig) -> Self {
+        match config {
+            CounterpartyConfig::Evm {
+                rpc_url,
+                htlc,
+                from_block,
+            } => Counterparty::Evm {
+                http: reqwest::Client::new(),
+                rpc_url: rpc_url.clone(),
+                htlc: htlc.clone(),
+                from_block: *from_block,
+            },
+            CounterpartyConfig::Esplora {
+                url,
+                htlc_addresses_url,
+            } => Counterparty::Esplora {
+                http: reqwest::Client::new(),
+                url: url.trim_end_matches('/').to_string(),
+                htlc_addresses_url: htlc_addresses_url.clone(),
+            },
+        }
+    }
+
+    /// Fetches the counterparty legs of the swaps with `secret_hashes`. Secret hashes whose leg
+    /// cannot be located, e.g. having no known HTLC address, are left out.
+    pub async fn legs(
+        &self,
+        secret_hashes: &[[u8; 32]],
+    ) -> Result<HashMap<[u8; 32], CounterpartyLeg>> {
+        if secret_hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        match self {
+            Counterparty::Evm {
+                http,
+                rpc_url,
+                htlc,
+                from_block,
+            } => evm_legs(http, rpc_url, htlc, *from_block, secret_hashes).await,
+            Counterparty::Esplora {
+                http,
+                url,
+                htlc_addresses_url,
+            } => esplora_legs(http, url, htlc_addresses_url, secret_hashes).await,
+        }
+    }
+}
+
+async fn evm_legs(
+    http: &reqwest::Client,
+    rpc_url: &str,
+    htlc: &str,
+    from_block: u64,
+    secret_hashes: &[[u8; 32]],
+) -> Result<HashMap<[u8; 32], CounterpartyLeg>> {
+    let latest: String = evm_call(http, rpc_url, "eth_blockNumber", json!([])).await?;
+    let latest = parse_quantity(&latest)?;
+    let initiated = event_topic(EVM_INITIATED);
+    let redeemed = event_topic(EVM_REDEEMED);
+    let hashes: Vec<String> = secret_hashes.iter().map(hex_word).collect();
+    let logs: Vec<EvmLog> = evm_call(
+        http,
+        rpc_url,
+        "eth_getLogs",
+        json!([{
+            "address": htlc,
+            "fromBlock": format!("{from_block:#x}"),
+            "toBlock": "latest",
+            "topics": [[&initiated, &redeemed], null, hashes],
+        }]),
+    )
+    .await?;
+
+    let mut legs = HashMap::new();
+    // The orders initiated for each secret hash, whose refunds are only indexed by order ID
+    let mut orders: HashMap<String, [u8; 32]> = HashMap::new();
+    for log in &logs {
+        let (Some(topic), Some(order_id), Some(secret_hash)) =
+            (log.topics.first(), log.topics.get(1), log.topics.get(2))
+        else {
+            continue;
+        };
+        let secret_hash = parse_word(secret_hash)?;
+        if *topic == redeemed {
+            // The secret is ABI-encoded as dynamic bytes: an offset, a length, then the bytes
+            let data = hex::decode(log.data.trim_start_matches("0x"))?;
+            let secret = data.get(64..96).and_then(|secret| secret.try_into().ok());
+            legs.insert(secret_hash, CounterpartyLeg::Redeemed { secret });
+        } else if *topic == initiated {
+            orders.insert(order_id.clone(), secret_hash);
+            // Pending logs have no block yet
+            let confirmations = match &log.block_number {
+                Some(block_number) => (latest + 1).saturating_sub(parse_quantity(block_number)?),
+                None => 0,
+            };
+            legs.entry(secret_hash)
+                .or_insert(CounterpartyLeg::Funded { confirmations });
+        }
+    }
+
+    let order_ids: Vec<&String> = orders.keys().collect();
+    if !order_ids.is_empty() {
+        let refunds: Vec<EvmLog> = evm_call(
+            http,
+            rpc_url,
+            "eth_getLogs",
+            json!([{
+                "address": htlc,
+                "fromBlock": format!("{from_block:#x}"),
+                "toBlock": "latest",
+                "topics": [event_topic(EVM_REFUNDED), order_ids],
+            }]),
+        )
+        .await?;
+        for log in refunds {
+            if let Some(secret_hash) = log.topics.get(1).and_then(|id| orders.get(id)) {
+                legs.entry(*secret_hash).and_modify(|leg| {
+                    if let CounterpartyLeg::Funded { .. } = leg {
+                        *leg = CounterpartyLeg::Refunded
+                    }
+                });
+            }
+        }
+    }
+
+    for secret_hash in secret_hashes {
+        legs.entry(*secret_hash)
+            .or_insert(CounterpartyLeg::NotFunded);
+    }
+    Ok(legs)
+}
+
+async fn esplora_legs(
+    http: &reqwest::Client,
+    url: &str,
+    htlc_addresses_url: &str,
+    secret_hashes: &[[u8; 32]],
+) -> Result<HashMap<[u8; 32], CounterpartyLeg>> {
+    let addresses: HashMap<String, String> = http
+        .get(htlc_addresses_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .with_context(|| format!("Malformed response from {htlc_addresses_url}"))?;
+
+    let tip: u64 = http
+        .get(format!("{url}/blocks/tip/height"))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?
+        .trim()
+        .parse()
+        .with_context(|| format!("Malformed tip height from {url}"))?;
+
+    let mut legs = HashMap::new();
+    for secret_hash in secret_hashes {
+        let Some(address) = addresses.get(&hex::encode(secret_hash)) else {
+            continue;
+        };
+        let transactions: Vec<EsploraTransaction> = http
+            .get(format!("{url}/address/{address}/txs"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Malformed transactions of {address} from {url}"))?;
+        let transactions = transactions.iter().filter(|tx| tx.status.confirmed);
+
+        let mut leg = CounterpartyLeg::NotFunded;
+        for transaction in transactions {
+            if transaction
+                .vout
+                .iter()
+                .any(|output| output.scriptpubkey_address.as_ref() == Some(address))
+                && leg == CounterpartyLeg::NotFunded
+            {
+                let height = transaction.status.block_height.unwrap_or(tip);
+                leg = CounterpartyLeg::Funded {
+                    confirmations: (tip + 1).saturating_sub(height),
+                };
+            }
+            for input in &transaction.vin {
+                let spent = input
+                    .prevout
+                    .as_ref()
+                    .and_then(|prevout| prevout.scriptpubkey_address.as_ref());
+                if spent != Some(address) {
+                    continue;
+                }
+                // Redeems reveal the secret in their witness, refunds do not
+                let secret = input
+                    .witness
+                    .iter()
+                    .flatten()
+                    .filter_map(|item| <[u8; 32]>::try_from(hex::decode(item).ok()?).ok())
+                    .find(|secret| HashAlgorithm::Sha256.hash(secret) == *secret_hash);
+                leg = match secret {
+                    Some(secret) => CounterpartyLeg::Redeemed {
+                        secret: Some(secret),
+                    },
+                    None => CounterpartyLeg::Refunded,
+                };
+            }
+        }
+        legs.insert(*secret_hash, leg);
+    }
+    Ok(legs)
+}
+
+async fn evm_call<T: DeserializeOwned>(
+    http: &reqwest::Client,
+    rpc_url: &str,
+    method: &str,
+    params: Value,
+) -> Result<T> {
+    let response: EvmResponse<T> = http
+        .post(rpc_url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .with_context(|| format!("Malformed response to {method} from {rpc_url}"))?;
+    match (response.result, response.error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => bail!("{method} failed: {error}"),
+        (None, None) => bail!("{method} returned no result"),
+    }
+}
+
+/// The topic of the event with `signature`, i.e. the Keccak-256 hash of its signature
+fn event_topic(signature: &str) -> String {
+    format!("0x{}", hex::encode(Keccak256::digest(signature)))
+}
+
+/// Encodes `bytes` as a 32-byte EVM word, as topics are
+fn hex_word(bytes: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Parses a hex-encoded EVM quantity, e.g. a block number
+fn parse_quantity(quantity: &str) -> Result<u64> {
+    u64::from_str_radix(quantity.trim_start_matches("0x"), 16)
+        .with_context(|| format!("Malformed quantity {quantity}"))
+}
+
+fn parse_word(word: &str) -> Result<[u8; 32]> {
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(word.trim_start_matches("0x"), &mut bytes)
+        .with_context(|| format!("Malformed topic {word}"))?;
+    Ok(bytes)
+}
+
+#[derive(Deserialize)]
+struct EvmResponse<T> {
+    result: Option<T>,
+    error: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EvmLog {
+    topics: Vec<String>,
+    data: String,
+    block_number: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EsploraTransaction {
+    vin: Vec<EsploraInput>,
+    vout: Vec<EsploraOutput>,
+    status: EsploraStatus,
+}
+
+#[derive(Deserialize)]
+struct EsploraInput {
+    prevout: Option<EsploraOutput>,
+    witness: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct EsploraOutput {
+    scriptpubkey_address: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EsploraStatus {
+    confirmed: bool,
+    block_height: Option<u64>,
+}
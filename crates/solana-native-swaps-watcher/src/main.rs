@@ -0,0 +1,207 @@
+//! `swap-watcher` redeems swaps automatically once their secrets are revealed on the
+//! counterpart chain, as read from a configurable source.
+//!
+//! Revealed secrets are retained for a while, and matched against the secret hashes of the
+//! open swaps of the configured redeemers under every supported hash algorithm.
+//!
+//! Optionally, the watched swaps are periodically reconciled with their counterparty legs,
+//! queried on an EVM chain or through a Bitcoin Esplora API, flagging any mismatch between the
+//! legs, and redeeming the swaps whose secrets the counterparty legs' redeems revealed.
+//!
+//! Swaps whose secrets the watcher holds itself, e.g. as a resolver, draw them from an encrypted
+//! vault, populated by `swap-watcher generate` or `swap-watcher import`. A secret is only revealed,
+//! by redeeming its swap, once the swap's counterparty leg is confirmed to the configured depth,
+//! as found by reconciling the swaps.
+//!
+//! Configured with tenants, the watcher redeems the swaps of each tenant under the tenant's own
+//! keypair and vault, holding each tenant to its own limits, counting its own metrics, and
+//! notifying its own webhook.
+
+mod config;
+mod counterparty;
+mod reconcile;
+mod redeem;
+mod source;
+mod tenant;
+mod vault;
+
+use std::{collections::HashSet, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use log::{error, info};
+use solana_native_swaps_client::{
+    tenant::{serve_metrics, TenantMetrics},
+    HashAlgorithm,
+};
+
+use crate::{config::Config, source::SecretSource, tenant::Tenant, vault::Vault};
+
+/// Every hash algorithm a swap's secret hash may have been computed with
+const HASH_ALGORITHMS: [HashAlgorithm; 4] = [
+    HashAlgorithm::Sha256,
+    HashAlgorithm::Keccak256,
+    HashAlgorithm::Hash160,
+    HashAlgorithm::Blake3,
+];
+
+#[derive(Parser)]
+#[command(name = "swap-watcher", version, about)]
+struct Cli {
+    /// The path of the config file
+    #[arg(long, short, default_value = "config.toml")]
+    config: PathBuf,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Manages the vault instead of watching swaps
+#[derive(Subcommand)]
+enum Command {
+    /// Generates a secret into the vault, printing its hash under each algorithm
+    Generate {
+        /// The tenant whose vault to generate the secret into, when tenants are configured
+        #[arg(long)]
+        tenant: Option<String>,
+    },
+    /// Imports hex-encoded secrets into the vault, read from stdin one per line
+    Import {
+        /// The tenant whose vault to import the secrets into, when tenants are configured
+        #[arg(long)]
+        tenant: Option<String>,
+    },
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let cli = Cli::parse();
+    let config = Config::read(&cli.config)?;
+    let tenant_configs = config.tenants()?;
+    if let Some(command) = cli.command {
+        return manage_vault(&tenant_configs, command);
+    }
+    let has_vault = tenant_configs.iter().any(|tenant| tenant.vault.is_some());
+    if has_vault && config.reconcile.is_none() {
+        bail!("The vault reveals secrets by reconciling swaps, which must be configured");
+    }
+
+    let mut tenants = tenant_configs
+        .iter()
+        .map(|tenant| Tenant::open(tenant, &config))
+        .collect::<Result<Vec<_>>>()?;
+    // Each swap is redeemed on behalf of a single tenant, under that tenant's keypair
+    let mut redeemers = HashSet::new();
+    for tenant in &tenants {
+        if let Some(redeemer) = tenant.redeemers.iter().find(|r| !redeemers.insert(**r)) {
+            bail!("Redeemer {redeemer} belongs to several tenants");
+        }
+    }
+    let metrics = Arc::new(TenantMetrics::new("swap_watcher"));
+    if let Some(addr) = config.metrics_addr {
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_metrics(addr, metrics).await {
+                error!("Failed to serve metrics on {addr}: {e}");
+            }
+        });
+    }
+    let mut source = SecretSource::new(&config.source);
+    let mut reconcile_interval = config
+        .reconcile
+        .as_ref()
+        .map(|config| tokio::time::interval(Duration::from_secs(config.interval_secs.max(1))));
+
+    for tenant in &tenants {
+        info!(
+            "Watching for the swaps of {:?} for tenant {}",
+            tenant.redeemers, tenant.name
+        );
+    }
+    loop {
+        // Reconciles while waiting for secrets, the source being polled across reconciliations
+        let next = source.next();
+        tokio::pin!(next);
+        let revealed = loop {
+            tokio::select! {
+                revealed = &mut next => break revealed,
+                _ = tick(&mut reconcile_interval) => {
+                    for tenant in &mut tenants {
+                        match tenant.reconcile(&metrics).await {
+                            Ok(true) => tenant.redeem_all(&metrics).await,
+                            Ok(false) => {}
+                            Err(e) => error!(
+                                "Failed to reconcile the swaps of tenant {}: {e:#}",
+                                tenant.name
+                            ),
+                        }
+                    }
+                }
+            }
+        };
+        let revealed = match revealed {
+            Ok(Some(revealed)) => revealed,
+            Ok(None) => {
+                info!("Secret source exhausted");
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Failed to read secrets: {e:#}");
+                continue;
+            }
+        };
+        // Secrets revealed on the counterpart chain are public, and shared by every tenant
+        for tenant in &mut tenants {
+            tenant.reveal(&revealed);
+            tenant.redeem_all(&metrics).await;
+        }
+    }
+}
+
+fn manage_vault(tenants: &[config::TenantConfig], command: Command) -> Result<()> {
+    let (Command::Generate { tenant } | Command::Import { tenant }) = &command;
+    let tenant = match tenant {
+        Some(name) => tenants
+            .iter()
+            .find(|tenant| tenant.name == *name)
+            .with_context(|| format!("No tenant {name} is configured"))?,
+        None if tenants.len() == 1 => &tenants[0],
+        None => bail!("The tenant whose vault to manage must be given with --tenant"),
+    };
+    let Some(vault) = &tenant.vault else {
+        bail!("No vault is configured");
+    };
+    let mut vault = Vault::open(vault)?;
+    match command {
+        Command::Generate { .. } => {
+            let secret = vault.generate()?;
+            for hash_algorithm in HASH_ALGORITHMS {
+                println!(
+                    "{:<10} {}",
+                    format!("{hash_algorithm:?}:"),
+                    hex::encode(hash_algorithm.hash(&secret))
+                );
+            }
+        }
+        Command::Import { .. } => {
+            for line in std::io::stdin().lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    vault.insert(source::parse_secret(line.trim())?)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Waits for the next tick of `interval`, forever if there is none
+async fn tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
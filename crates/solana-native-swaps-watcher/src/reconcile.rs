@@ -0,0 +1,222 @@
+use std::{collections::HashMap, fmt};
+
+use anyhow::Result;
+use log::{info, warn};
+use solana_native_swaps_client::{
+    events::{parse_inner_instructions, parse_logs, SwapEvent},
+    fetch_swap_accounts_by_redeemer,
+    rpc::RpcClient,
+    Pubkey,
+};
+
+use crate::{
+    config::ReconcileConfig,
+    counterparty::{Counterparty, CounterpartyLeg},
+    vault::Vault,
+};
+
+/// The state of the Solana leg of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolanaLeg {
+    Open,
+    Redeemed,
+    Refunded,
+}
+
+/// A disagreement between the legs of a swap, requiring attention
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The Solana leg is open, but the counterparty leg was never funded
+    CounterpartyNotFunded,
+    /// The counterparty leg was refunded while the Solana leg is still open
+    CounterpartyRefunded,
+    /// The Solana leg was redeemed, revealing the secret, but the counterparty leg has not been
+    RedeemedUnmatched,
+    /// The Solana leg was refunded, but the counterparty leg was redeemed
+    RefundedWhileRedeemed,
+    /// The Solana leg was refunded, but the counterparty leg is still funded
+    RefundedWhileFunded,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Mismatch::CounterpartyNotFunded => "counterparty leg not funded",
+            Mismatch::CounterpartyRefunded => "counterparty leg refunded, Solana leg still open",
+            Mismatch::RedeemedUnmatched => "Solana leg redeemed, counterparty leg not redeemed",
+            Mismatch::RefundedWhileRedeemed => "Solana leg refunded, counterparty leg redeemed",
+            Mismatch::RefundedWhileFunded => "Solana leg refunded, counterparty leg still funded",
+        })
+    }
+}
+
+/// Correlates the legs of a swap, returning the mismatch between them if any, and whether
+/// both are settled, i.e. neither will change anymore
+pub fn correlate(solana: SolanaLeg, counterparty: CounterpartyLeg) -> (Option<Mismatch>, bool) {
+    match (solana, counterparty) {
+        (SolanaLeg::Open, CounterpartyLeg::NotFunded) => {
+            (Some(Mismatch::CounterpartyNotFunded), false)
+        }
+        (SolanaLeg::Open, CounterpartyLeg::Refunded) => {
+            (Some(Mismatch::CounterpartyRefunded), false)
+        }
+        // The secret revealed by the counterparty leg is yet to be used to redeem
+        (SolanaLeg::Open, _) => (None, false),
+        (SolanaLeg::Redeemed, CounterpartyLeg::Redeemed { .. }) => (None, true),
+        (SolanaLeg::Redeemed, CounterpartyLeg::Refunded) => {
+            (Some(Mismatch::RedeemedUnmatched), true)
+        }
+        (SolanaLeg::Redeemed, _) => (Some(Mismatch::RedeemedUnmatched), false),
+        (SolanaLeg::Refunded, CounterpartyLeg::Redeemed { .. }) => {
+            (Some(Mismatch::RefundedWhileRedeemed), true)
+        }
+        (SolanaLeg::Refunded, CounterpartyLeg::Funded { .. }) => {
+            (Some(Mismatch::RefundedWhileFunded), false)
+        }
+        (SolanaLeg::Refunded, _) => (None, true),
+    }
+}
+
+/// A swap whose legs are being reconciled
+struct Order {
+    secret_hash: [u8; 32],
+    solana: SolanaLeg,
+    /// The mismatch last flagged, so that each is flagged once
+    flagged: Option<Mismatch>,
+}
+
+/// The outcome of reconciling the watched swaps
+pub struct Reconciliation {
+    /// The secrets revealed by the redeems of the counterparty legs, and those of the vault its
+    /// policy releases
+    pub secrets: Vec<[u8; 32]>,
+    /// The mismatches flagged for the first time
+    pub mismatches: Vec<(Pubkey, Mismatch)>,
+}
+
+/// Periodically correlates the watched swaps with their counterparty legs, flagging mismatches
+pub struct Reconciler {
+    counterparty: Counterparty,
+    /// The swaps seen open, until both of their legs are settled
+    orders: HashMap<Pubkey, Order>,
+}
+
+impl Reconciler {
+    pub fn new(config: &ReconcileConfig) -> Self {
+        Reconciler {
+            counterparty: Counterparty::new(&config.counterparty),
+            orders: HashMap::new(),
+        }
+    }
+
+    /// Reconciles the swaps of `redeemers`, returning the secrets revealed by the redeems of
+    /// their counterparty legs, along with those of `vault` its policy releases
+    pub async fn reconcile(
+        &mut self,
+        rpc: &RpcClient,
+        redeemers: &[Pubkey],
+        vault: Option<&Vault>,
+    ) -> Result<Reconciliation> {
+        let mut open = HashMap::new();
+        for redeemer in redeemers {
+            open.extend(fetch_swap_accounts_by_redeemer(rpc, redeemer).await?);
+        }
+        for (swap_account, swap) in &open {
+            self.orders.entry(*swap_account).or_insert(Order {
+                secret_hash: swap.secret_hash,
+                solana: SolanaLeg::Open,
+                flagged: None,
+            });
+        }
+        for (swap_account, order) in &mut self.orders {
+            if order.solana == SolanaLeg::Open && !open.contains_key(swap_account) {
+                match fetch_outcome(rpc, swap_account).await? {
+                    Some(outcome) => order.solana = outcome,
+                    None => warn!("Swap {swap_account} was closed with no outcome found"),
+                }
+            }
+        }
+
+        let secret_hashes: Vec<[u8; 32]> = self
+            .orders
+            .values()
+            .map(|order| order.secret_hash)
+            .collect();
+        let legs = self.counterparty.legs(&secret_hashes).await?;
+        let mut secrets = vec![];
+        let mut mismatches = vec![];
+        self.orders.retain(|swap_account, order| {
+            let Some(counterparty) = legs.get(&order.secret_hash) else {
+                return true;
+            };
+            if order.solana == SolanaLeg::Open {
+                if let CounterpartyLeg::Redeemed {
+                    secret: Some(secret),
+                } = counterparty
+                {
+                    secrets.push(*secret);
+                } else if let Some(secret) =
+                    vault.and_then(|vault| vault.release(&order.secret_hash, *counterparty))
+                {
+                    info!("Revealing the secret of swap {swap_account} held in the vault");
+                    secrets.push(secret);
+                }
+            }
+            let (mismatch, settled) = correlate(order.solana, *counterparty);
+            if let Some(mismatch) = mismatch.filter(|mismatch| order.flagged != Some(*mismatch)) {
+                warn!(
+                    "Swap {swap_account} ({}): {mismatch}",
+                    hex::encode(order.secret_hash)
+                );
+                mismatches.push((*swap_account, mismatch));
+            }
+            order.flagged = mismatch;
+            if settled && mismatch.is_none() {
+                info!("Swap {swap_account} settled on both chains");
+            }
+            !settled
+        });
+        Ok(Reconciliation {
+            secrets,
+            mismatches,
+        })
+    }
+}
+
+/// Finds how the swap at `swap_account` was closed from the events of its latest transactions
+async fn fetch_outcome(rpc: &RpcClient, swap_account: &Pubkey) -> Result<Option<SolanaLeg>> {
+    for info in rpc
+        .get_signatures_for_address(swap_account, None, None)
+        .await?
+    {
+        if info.failed {
+            continue;
+        }
+        let Some(transaction) = rpc.get_transaction(&info.signature).await? else {
+            continue;
+        };
+        let mut events = parse_inner_instructions(
+            transaction
+                .inner_instructions
+                .iter()
+                .map(|(program_id, data)| (program_id, data.as_slice())),
+        )?;
+        // Earlier releases of the program logged their events instead
+        if events.is_empty() {
+            events = parse_logs(&transaction.log_messages)?;
+        }
+        for event in events.iter().rev() {
+            if event.swap_account() != *swap_account {
+                continue;
+            }
+            match event {
+                SwapEvent::Redeemed(_) => return Ok(Some(SolanaLeg::Redeemed)),
+                SwapEvent::Refunded(_) | SwapEvent::InstantRefunded(_) => {
+                    return Ok(Some(SolanaLeg::Refunded))
+                }
+                SwapEvent::Initiated(_) => {}
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use log::{info, warn};
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_keypair::Keypair;
+use solana_native_swaps_client::{
+    fetch_swap_account, rpc::RpcClient, Pubkey, RedeemBuilder, SwapAccount,
+};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::config::{PriorityFeeConfig, RetryConfig};
+
+/// Submits redeems, retrying failed attempts with exponential backoff
+/// and a bumped priority fee
+pub struct Redeemer {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub retry: RetryConfig,
+    pub priority_fee: PriorityFeeConfig,
+}
+
+impl Redeemer {
+    /// Redeems the entire remaining funds of the swap at `swap_account` with `secret`, returning
+    /// the signature of the redeem. Gives up once the swap is no longer open, e.g. having been
+    /// redeemed by someone else, returning `None`.
+    pub async fn redeem(
+        &self,
+        swap_account: &Pubkey,
+        swap: &SwapAccount,
+        secret: [u8; 32],
+    ) -> Result<Option<String>> {
+        let mut backoff = Duration::from_millis(self.retry.initial_backoff_ms);
+        let mut micro_lamports = self.priority_fee.initial_micro_lamports;
+
+        for attempt in 1..=self.retry.max_attempts {
+            if attempt > 1 && fetch_swap_account(&self.rpc, swap_account).await?.is_none() {
+                info!("Swap {swap_account} is no longer open");
+                return Ok(None);
+            }
+
+            match self.submit(swap, secret, micro_lamports).await {
+                Ok(signature) => {
+                    info!("Redeemed swap {swap_account} in {signature}");
+                    return Ok(Some(signature));
+                }
+                Err(e) => warn!(
+                    "Attempt {attempt} to redeem swap {swap_account} at {micro_lamports} \
+                     micro-lamports per CU failed: {e}"
+                ),
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_millis(self.retry.max_backoff_ms));
+            micro_lamports = micro_lamports
+                .saturating_mul(self.priority_fee.multiplier)
+                .min(self.priority_fee.max_micro_lamports);
+        }
+        bail!(
+            "Gave up redeeming swap {swap_account} after {} attempts",
+            self.retry.max_attempts
+        )
+    }
+
+    async fn submit(
+        &self,
+        swap: &SwapAccount,
+        secret: [u8; 32],
+        micro_lamports: u64,
+    ) -> Result<String> {
+        let mut redeem = RedeemBuilder::new(swap, secret);
+        // Claims the relayer fee when redeeming on the redeemer's behalf
+        if self.payer.pubkey() != swap.redeemer {
+            redeem = redeem.relayer(self.payer.pubkey());
+        }
+        let transaction = Transaction::new_signed_with_payer(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(
+                    self.priority_fee.compute_unit_limit,
+                ),
+                ComputeBudgetInstruction::set_compute_unit_price(micro_lamports),
+                redeem.instruction(),
+            ],
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            self.rpc.get_latest_blockhash().await?,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&transaction).await?;
+        Ok(signature.to_string())
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+
+use crate::config::SourceConfig;
+
+/// A source of secrets revealed on the counterpart chain
+pub enum SecretSource {
+    Http {
+        http: reqwest::Client,
+        url: String,
+        poll_interval: Duration,
+        polled: bool,
+    },
+    Stdin(Lines<BufReader<Stdin>>),
+}
+
+impl SecretSource {
+    pub fn new(config: &SourceConfig) -> Self {
+        match config {
+            SourceConfig::Http {
+                url,
+                poll_interval_secs,
+            } => SecretSource::Http {
+                http: reqwest::Client::new(),
+                url: url.clone(),
+                poll_interval: Duration::from_secs(*poll_interval_secs),
+                polled: false,
+            },
+            SourceConfig::Stdin => SecretSource::Stdin(BufReader::new(tokio::io::stdin()).lines()),
+        }
+    }
+
+    /// Waits for the next batch of revealed secrets. Secrets may be repeated across batches.
+    /// Returns `None` once the source is exhausted.
+    pub async fn next(&mut self) -> Result<Option<Vec<[u8; 32]>>> {
+        match self {
+            SecretSource::Http {
+                http,
+                url,
+                poll_interval,
+                polled,
+            } => {
+                if *polled {
+                    tokio::time::sleep(*poll_interval).await;
+                }
+                *polled = true;
+                let secrets: Vec<String> = http
+                    .get(url.as_str())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await
+                    .with_context(|| format!("Malformed response from {url}"))?;
+                secrets
+                    .iter()
+                    .map(|secret| parse_secret(secret))
+                    .collect::<Result<_>>()
+                    .map(Some)
+            }
+            SecretSource::Stdin(lines) => loop {
+                match lines.next_line().await? {
+                    Some(line) if line.trim().is_empty() => continue,
+                    Some(line) => break Ok(Some(vec![parse_secret(line.trim())?])),
+                    None => break Ok(None),
+                }
+            },
+        }
+    }
+}
+
+pub fn parse_secret(secret: &str) -> Result<[u8; 32]> {
+    let mut bytes = [0; 32];
+    if hex::decode_to_slice(secret.trim_start_matches("0x"), &mut bytes).is_err() {
+        bail!("Malformed secret {secret}, expected 32 bytes in hex");
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use solana_keypair::read_keypair_file;
+use solana_native_swaps_client::{
+    fetch_swap_accounts_by_redeemer,
+    rpc::RpcClient,
+    tenant::{RateLimiter, TenantMetrics, Webhook, WebhookEvent},
+    Pubkey,
+};
+use solana_signer::Signer;
+
+use crate::{
+    config::{Config, TenantConfig},
+    reconcile::Reconciler,
+    redeem::Redeemer,
+    vault::Vault,
+    HASH_ALGORITHMS,
+};
+
+/// A tenant whose swaps are redeemed under its own keypair, with the secrets of its own vault
+pub struct Tenant {
+    pub name: String,
+    pub redeemer: Redeemer,
+    /// The redeemers of the tenant's swaps
+    pub redeemers: Vec<Pubkey>,
+    vault: Option<Vault>,
+    reconciler: Option<Reconciler>,
+    /// The secrets revealed so far, keyed by their hash under each algorithm
+    secrets: HashMap<[u8; 32], ([u8; 32], Instant)>,
+    retention: Duration,
+    limiter: RateLimiter,
+    /// The swaps left unredeemed for exceeding the tenant's limits, notified once each
+    limited: HashSet<Pubkey>,
+    webhook: Option<Webhook>,
+}
+
+impl Tenant {
+    pub fn open(tenant: &TenantConfig, config: &Config) -> Result<Self> {
+        let keypair = match tenant.keypair.strip_prefix("~/") {
+            Some(relative) => PathBuf::from(std::env::var("HOME")?).join(relative),
+            None => PathBuf::from(&tenant.keypair),
+        };
+        let payer = read_keypair_file(&keypair)
+            .map_err(|e| anyhow!("Failed to read keypair {}: {e}", keypair.display()))?;
+        let redeemers = match tenant.redeemers.is_empty() {
+            true => vec![payer.pubkey()],
+            false => tenant.redeemers.clone(),
+        };
+        Ok(Tenant {
+            name: tenant.name.clone(),
+            redeemer: Redeemer {
+                rpc: RpcClient::new(&config.rpc_url),
+                payer,
+                retry: config.retry.clone(),
+                priority_fee: config.priority_fee.clone(),
+            },
+            redeemers,
+            vault: tenant.vault.as_ref().map(Vault::open).transpose()?,
+            reconciler: config.reconcile.as_ref().map(Reconciler::new),
+            secrets: HashMap::new(),
+            retention: Duration::from_secs(config.secret_retention_secs),
+            limiter: RateLimiter::new(&tenant.limits),
+            limited: HashSet::new(),
+            webhook: tenant.webhook_url.as_deref().map(Webhook::new),
+        })
+    }
+
+    /// Adds the `revealed` secrets to those retained, dropping those retained for too long
+    pub fn reveal(&mut self, revealed: &[[u8; 32]]) {
+        let now = Instant::now();
+        self.secrets
+            .retain(|_, (_, revealed_at)| now.duration_since(*revealed_at) < self.retention);
+        for secret in revealed {
+            for hash_algorithm in HASH_ALGORITHMS {
+                self.secrets
+                    .entry(hash_algorithm.hash(secret))
+                    .or_insert((*secret, now));
+            }
+        }
+    }
+
+    /// Reconciles the tenant's swaps, revealing the secrets reconciliation found, and returning
+    /// whether there were any
+    pub async fn reconcile(&mut self, metrics: &TenantMetrics) -> Result<bool> {
+        let Some(reconciler) = &mut self.reconciler else {
+            return Ok(false);
+        };
+        let reconciliation = reconciler
+            .reconcile(&self.redeemer.rpc, &self.redeemers, self.vault.as_ref())
+            .await?;
+        for (swap_account, mismatch) in reconciliation.mismatches {
+            metrics.add(&self.name, "mismatches", 1);
+            self.notify(
+                WebhookEvent::new(&self.name, "mismatch", &[swap_account]).detail(mismatch),
+            )
+            .await;
+        }
+        if reconciliation.secrets.is_empty() {
+            return Ok(false);
+        }
+        info!(
+            "Redeeming the swaps of tenant {} with {} secrets revealed by counterparty legs",
+            self.name,
+            reconciliation.secrets.len()
+        );
+        self.reveal(&reconciliation.secrets);
+        Ok(true)
+    }
+
+    /// Redeems the open swaps of every redeemer of the tenant whose secrets have been revealed
+    pub async fn redeem_all(&mut self, metrics: &TenantMetrics) {
+        for redeemer_address in self.redeemers.clone() {
+            if let Err(e) = self.redeem_revealed(&redeemer_address, metrics).await {
+                error!(
+                    "Failed to redeem the swaps of {redeemer_address} of tenant {}: {e:#}",
+                    self.name
+                );
+            }
+        }
+    }
+
+    /// Redeems the open swaps of `redeemer_address` whose secrets have been revealed
+    async fn redeem_revealed(
+        &mut self,
+        redeemer_address: &Pubkey,
+        metrics: &TenantMetrics,
+    ) -> Result<()> {
+        for (swap_account, swap) in
+            fetch_swap_accounts_by_redeemer(&self.redeemer.rpc, redeemer_address).await?
+        {
+            let Some((secret, _)) = self.secrets.get(&swap.secret_hash) else {
+                continue;
+            };
+            // The secret must hash to the swap's secret hash under the swap's own algorithm
+            if swap.hash_algorithm()?.hash(secret) != swap.secret_hash {
+                continue;
+            }
+            let secret = *secret;
+            if self.limiter.acquire(1) == 0 {
+                if self.limited.insert(swap_account) {
+                    warn!(
+                        "Tenant {} reached its limits, leaving swap {swap_account} unredeemed",
+                        self.name
+                    );
+                    metrics.add(&self.name, "swaps_rate_limited", 1);
+                    self.notify(WebhookEvent::new(
+                        &self.name,
+                        "rate_limited",
+                        &[swap_account],
+                    ))
+                    .await;
+                }
+                continue;
+            }
+            self.limited.remove(&swap_account);
+            let event = match self.redeemer.redeem(&swap_account, &swap, secret).await {
+                Ok(Some(signature)) => {
+                    metrics.add(&self.name, "swaps_redeemed", 1);
+                    WebhookEvent::new(&self.name, "redeemed", &[swap_account]).signature(signature)
+                }
+                Ok(None) => continue,
+                Err(e) => {
+                    error!("{e:#}");
+                    metrics.add(&self.name, "redeems_failed", 1);
+                    WebhookEvent::new(&self.name, "redeem_failed", &[swap_account])
+                        .detail(format!("{e:#}"))
+                }
+            };
+            self.notify(event).await;
+        }
+        Ok(())
+    }
+
+    /// Posts `event` to the tenant's webhook, if it has one
+    async fn notify(&self, event: WebhookEvent) {
+        if let Some(webhook) = &self.webhook {
+            if let Err(e) = webhook.notify(&event).await {
+                error!("Failed to notify the webhook of tenant {}: {e}", self.name);
+            }
+        }
+    }
+}
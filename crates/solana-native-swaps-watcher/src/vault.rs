@@ -0,0 +1,142 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::VaultConfig, counterparty::CounterpartyLeg, HASH_ALGORITHMS};
+
+/// The environment variable holding the hex-encoded 32-byte key the vault is encrypted with,
+/// unless configured otherwise, e.g. for each tenant to have its own key
+pub const VAULT_KEY_ENV: &str = "SWAP_WATCHER_VAULT_KEY";
+
+/// The preimages of the swaps the watcher holds the secrets of, encrypted at rest with
+/// ChaCha20-Poly1305, and released only as the reveal policy allows
+pub struct Vault {
+    path: PathBuf,
+    key_env: String,
+    min_confirmations: u64,
+    cipher: ChaCha20Poly1305,
+    /// The secrets held, keyed by their hash under each algorithm
+    secrets: HashMap<[u8; 32], [u8; 32]>,
+}
+
+/// A secret as stored in the vault file
+#[derive(Serialize, Deserialize)]
+struct SealedSecret {
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Vault {
+    /// Opens the vault, empty if its file does not exist, with the key of its `key_env`
+    pub fn open(config: &VaultConfig) -> Result<Self> {
+        let key_env = &config.key_env;
+        let key = std::env::var(key_env)
+            .with_context(|| format!("The vault key must be set in {key_env}"))?;
+        let mut key_bytes = [0; 32];
+        hex::decode_to_slice(key.trim(), &mut key_bytes)
+            .map_err(|_| anyhow!("Malformed {key_env}, expected 32 bytes in hex"))?;
+        let mut vault = Vault {
+            path: config.path.clone(),
+            key_env: key_env.clone(),
+            min_confirmations: config.min_confirmations,
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key_bytes)),
+            secrets: HashMap::new(),
+        };
+        for sealed in vault.read()? {
+            let secret = vault.unseal(&sealed)?;
+            vault.index(secret);
+        }
+        Ok(vault)
+    }
+
+    /// The secret hashing to `secret_hash`, if held and allowed to be revealed given the
+    /// `counterparty` leg of its swap, i.e. once the leg is funded with the minimum
+    /// confirmations, or redeemed, the secret then being public already
+    pub fn release(
+        &self,
+        secret_hash: &[u8; 32],
+        counterparty: CounterpartyLeg,
+    ) -> Option<[u8; 32]> {
+        match counterparty {
+            CounterpartyLeg::Funded { confirmations }
+                if confirmations >= self.min_confirmations =>
+            {
+                self.secrets.get(secret_hash).copied()
+            }
+            CounterpartyLeg::Redeemed { .. } => self.secrets.get(secret_hash).copied(),
+            _ => None,
+        }
+    }
+
+    /// Generates a secret into the vault, returning it
+    pub fn generate(&mut self) -> Result<[u8; 32]> {
+        let mut secret = [0; 32];
+        OsRng.fill_bytes(&mut secret);
+        self.insert(secret)?;
+        Ok(secret)
+    }
+
+    /// Adds `secret` to the vault, persisting it before returning
+    pub fn insert(&mut self, secret: [u8; 32]) -> Result<()> {
+        if self.secrets.values().any(|held| *held == secret) {
+            return Ok(());
+        }
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, secret.as_slice())
+            .map_err(|_| anyhow!("Failed to encrypt secret"))?;
+        let mut sealed = self.read()?;
+        sealed.push(SealedSecret {
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        });
+        let contents = serde_json::to_vec_pretty(&sealed)?;
+        // Written aside then renamed, so that a crash mid-write cannot lose the secrets
+        let temporary = self.path.with_extension("tmp");
+        std::fs::write(&temporary, contents)
+            .and_then(|()| std::fs::rename(&temporary, &self.path))
+            .with_context(|| format!("Failed to write vault {}", self.path.display()))?;
+        self.index(secret);
+        Ok(())
+    }
+
+    fn index(&mut self, secret: [u8; 32]) {
+        for hash_algorithm in HASH_ALGORITHMS {
+            self.secrets.insert(hash_algorithm.hash(&secret), secret);
+        }
+    }
+
+    fn read(&self) -> Result<Vec<SealedSecret>> {
+        match std::fs::read(&self.path) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .with_context(|| format!("Malformed vault {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => {
+                Err(e).with_context(|| format!("Failed to read vault {}", self.path.display()))
+            }
+        }
+    }
+
+    fn unseal(&self, sealed: &SealedSecret) -> Result<[u8; 32]> {
+        let nonce = hex::decode(&sealed.nonce)?;
+        if nonce.len() != 12 {
+            bail!("Malformed vault nonce {}", sealed.nonce);
+        }
+        let secret = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                hex::decode(&sealed.ciphertext)?.as_slice(),
+            )
+            .map_err(|_| anyhow!("Failed to decrypt the vault, is {} correct?", self.key_env))?;
+        secret
+            .try_into()
+            .map_err(|_| anyhow!("Malformed secret in vault {}", self.path.display()))
+    }
+}
@@ -0,0 +1,39 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { SolanaNativeSwaps } from "../target/types/solana_native_swaps";
+
+import { expect } from "chai";
+
+// Runs against the validator started by `fork/validator.sh`, which holds a snapshot of the
+// live program accounts alongside the locally built program.
+anchor.setProvider(anchor.AnchorProvider.env());
+const connection = anchor.getProvider().connection;
+const program = anchor.workspace.SolanaNativeSwaps as Program<SolanaNativeSwaps>;
+
+describe("Testing the local build against live swap accounts", () => {
+	const swapAccountDiscriminator = Buffer.from(
+		program.idl.accounts.find(account => account.name === "swapAccount").discriminator);
+	let liveSwapAccounts: Awaited<ReturnType<typeof connection.getProgramAccounts>>;
+
+	before(async () => {
+		const programAccounts = await connection.getProgramAccounts(program.programId);
+		liveSwapAccounts = programAccounts.filter(({ account }) =>
+			account.data.subarray(0, swapAccountDiscriminator.length).equals(swapAccountDiscriminator));
+		console.log(`Loaded ${liveSwapAccounts.length} live swap accounts of ${programAccounts.length} program accounts`);
+	});
+
+	it("Test every live swap account deserializes", () => {
+		for (const { pubkey, account } of liveSwapAccounts) {
+			expect(() => program.coder.accounts.decode("swapAccount", account.data), pubkey.toBase58())
+				.not.to.throw();
+		}
+	});
+
+	it("Test every live swap account escrows its swap amount", async () => {
+		for (const { pubkey, account } of liveSwapAccounts) {
+			const swap = program.coder.accounts.decode("swapAccount", account.data);
+			const rentAmount = await connection.getMinimumBalanceForRentExemption(account.data.length);
+			expect(account.lamports - rentAmount, pubkey.toBase58()).to.be.at.least(swap.amountLamports.toNumber());
+		}
+	});
+});
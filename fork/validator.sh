@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Starts a local validator loaded with a snapshot of the live program accounts,
+# with the locally built program deployed in place of the live one.
+# Usage: FORK_RPC_URL=<rpc url> ./fork/validator.sh
+set -euo pipefail
+
+FORK_RPC_URL="${FORK_RPC_URL:-https://api.mainnet-beta.solana.com}"
+PROGRAM_ID="$(grep -oP 'solana_native_swaps = "\K[^"]+' Anchor.toml)"
+SNAPSHOT_DIR="fork/snapshot"
+PROGRAM_SO="target/deploy/solana_native_swaps.so"
+
+mkdir -p "$SNAPSHOT_DIR"
+rm -f "$SNAPSHOT_DIR"/*.json
+
+echo "Snapshotting accounts of $PROGRAM_ID from $FORK_RPC_URL"
+pubkeys=$(curl -s "$FORK_RPC_URL" -X POST -H "Content-Type: application/json" -d "{
+	\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"getProgramAccounts\",
+	\"params\": [\"$PROGRAM_ID\", {\"encoding\": \"base64\", \"dataSlice\": {\"offset\": 0, \"length\": 0}}]
+}" | jq -r '.result[].pubkey')
+
+account_args=()
+for pubkey in $pubkeys; do
+	solana account "$pubkey" --url "$FORK_RPC_URL" --output json-compact \
+		--output-file "$SNAPSHOT_DIR/$pubkey.json" > /dev/null
+	account_args+=(--account "$pubkey" "$SNAPSHOT_DIR/$pubkey.json")
+done
+echo "Snapshotted $(ls "$SNAPSHOT_DIR" | wc -l) accounts"
+
+solana-test-validator --reset --ledger .anchor/fork-ledger \
+	--bpf-program "$PROGRAM_ID" "$PROGRAM_SO" \
+	"${account_args[@]}"
@@ -0,0 +1,425 @@
+//! A minimal variant of the native SOL atomic swap program, written against the raw Solana
+//! runtime rather than Anchor. It supports the core swap lifecycle only (`initiate`, `redeem`,
+//! `refund` and `instant_refund`) with SHA-256 secret hashes, and is meant for integrators
+//! bundling many swaps per transaction, for whom Anchor's per-instruction overhead is prohibitive.
+//! Every instruction targets a budget of under 5,000 compute units.
+//!
+//! Swap accounts are PDAs derived from the same seeds as the Anchor program's, namely
+//! `["swap_account", initiator, redeemer, secret_hash]`, under this program's ID.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    declare_id,
+    entrypoint::ProgramResult,
+    hash::hash,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::Sysvar,
+};
+
+declare_id!("9BLB4wNFHT7QxGE1zgnnQSiFrMAhC5caeKknARHMTXTN");
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_security_txt::security_txt! {
+    name: "Solana Native Swaps Lite",
+    project_url: "https://github.com/catalogfi/solana-native-swaps",
+    contacts: "link:https://github.com/catalogfi/solana-native-swaps/security/advisories/new",
+    policy: "https://github.com/catalogfi/solana-native-swaps/blob/main/SECURITY.md",
+    source_code: "https://github.com/catalogfi/solana-native-swaps"
+}
+
+/// The instructions of this program. Each is encoded as a single tag byte, followed by its
+/// fields in order, with integers in little-endian.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapInstruction {
+    /// Initiates the atomic swap, transferring `amount_lamports` from the initiator to the
+    /// swap account, which is created by this instruction. Refunds are allowed from
+    /// `expires_in_slots` slots after the current one. `bump` is the swap account's PDA bump.
+    ///
+    /// Accounts: `[writable, signer]` initiator, `[writable]` swap account, system program.
+    Initiate {
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        bump: u8,
+    },
+    /// Transfers the swap amount to the redeemer given the secret, and closes the swap account,
+    /// returning its rent to the initiator.
+    ///
+    /// Accounts: `[writable]` swap account, `[writable]` redeemer, `[writable]` initiator.
+    Redeem { secret: [u8; 32] },
+    /// Returns the swap amount to the initiator once the swap has expired, and closes the
+    /// swap account.
+    ///
+    /// Accounts: `[writable]` swap account, `[writable]` initiator.
+    Refund,
+    /// Returns the swap amount to the initiator before expiry with the redeemer's consent,
+    /// and closes the swap account.
+    ///
+    /// Accounts: `[writable]` swap account, `[writable]` initiator, `[signer]` redeemer.
+    InstantRefund,
+}
+
+impl SwapInstruction {
+    /// Decodes an instruction from its tag byte and fields
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, fields) = data
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let instruction = match tag {
+            0 => {
+                let fields: &[u8; 81] = fields
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+                SwapInstruction::Initiate {
+                    amount_lamports: u64::from_le_bytes(fields[0..8].try_into().unwrap()),
+                    expires_in_slots: u64::from_le_bytes(fields[8..16].try_into().unwrap()),
+                    redeemer: Pubkey::new_from_array(fields[16..48].try_into().unwrap()),
+                    secret_hash: fields[48..80].try_into().unwrap(),
+                    bump: fields[80],
+                }
+            }
+            1 => SwapInstruction::Redeem {
+                secret: fields
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?,
+            },
+            2 if fields.is_empty() => SwapInstruction::Refund,
+            3 if fields.is_empty() => SwapInstruction::InstantRefund,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        Ok(instruction)
+    }
+
+    /// Encodes this instruction into its tag byte and fields
+    pub fn pack(&self) -> Vec<u8> {
+        match self {
+            SwapInstruction::Initiate {
+                amount_lamports,
+                expires_in_slots,
+                redeemer,
+                secret_hash,
+                bump,
+            } => [
+                &[0][..],
+                &amount_lamports.to_le_bytes(),
+                &expires_in_slots.to_le_bytes(),
+                redeemer.as_ref(),
+                secret_hash,
+                &[*bump],
+            ]
+            .concat(),
+            SwapInstruction::Redeem { secret } => [&[1][..], secret].concat(),
+            SwapInstruction::Refund => vec![2],
+            SwapInstruction::InstantRefund => vec![3],
+        }
+    }
+}
+
+/// Stores the state information of the atomic swap on-chain, in a fixed layout of
+/// `SwapAccount::LEN` bytes with integers in little-endian
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapAccount {
+    /// The initiator of the atomic swap
+    pub initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    pub redeemer: Pubkey,
+    /// The SHA-256 hash of the secret
+    pub secret_hash: [u8; 32],
+    /// The quantity of native SOL to be transferred through this atomic swap in base units (aka lamports)
+    pub amount_lamports: u64,
+    /// The exact slot from which (non-instant) refunds are allowed
+    pub expiry_slot: u64,
+    /// The bump of this PDA
+    pub bump: u8,
+}
+
+impl SwapAccount {
+    /// The size of a swap account's data
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1;
+
+    /// Decodes the state of a swap account from its data
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let data: &[u8; Self::LEN] = data
+            .try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(SwapAccount {
+            initiator: Pubkey::new_from_array(data[0..32].try_into().unwrap()),
+            redeemer: Pubkey::new_from_array(data[32..64].try_into().unwrap()),
+            secret_hash: data[64..96].try_into().unwrap(),
+            amount_lamports: u64::from_le_bytes(data[96..104].try_into().unwrap()),
+            expiry_slot: u64::from_le_bytes(data[104..112].try_into().unwrap()),
+            bump: data[112],
+        })
+    }
+
+    /// Encodes the state of a swap account into its data
+    pub fn pack(&self, data: &mut [u8]) {
+        data[0..32].copy_from_slice(self.initiator.as_ref());
+        data[32..64].copy_from_slice(self.redeemer.as_ref());
+        data[64..96].copy_from_slice(&self.secret_hash);
+        data[96..104].copy_from_slice(&self.amount_lamports.to_le_bytes());
+        data[104..112].copy_from_slice(&self.expiry_slot.to_le_bytes());
+        data[112] = self.bump;
+    }
+}
+
+/// The errors of this program, returned as `ProgramError::Custom` with the variant's index
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapError {
+    /// The provided swap account is not the PDA of the initiator, redeemer and secret hash
+    InvalidSwapAccount,
+    /// The provided initiator is not the initiator of this swap
+    InvalidInitiator,
+    /// The provided redeemer is not the redeemer of this swap
+    InvalidRedeemer,
+    /// The provided secret does not correspond to the secret hash of this swap
+    InvalidSecret,
+    /// Attempt to perform a refund before expiry
+    RefundBeforeExpiry,
+    /// The swap amount and expiry must be non-zero
+    ZeroAmountOrExpiry,
+}
+
+impl From<SwapError> for ProgramError {
+    fn from(error: SwapError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+/// Returns the PDA of the swap from `initiator` to `redeemer` with `secret_hash`
+pub fn find_swap_account(
+    initiator: &Pubkey,
+    redeemer: &Pubkey,
+    secret_hash: &[u8; 32],
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"swap_account",
+            initiator.as_ref(),
+            redeemer.as_ref(),
+            secret_hash,
+        ],
+        &ID,
+    )
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match SwapInstruction::unpack(instruction_data)? {
+        SwapInstruction::Initiate {
+            amount_lamports,
+            expires_in_slots,
+            redeemer,
+            secret_hash,
+            bump,
+        } => initiate(
+            program_id,
+            accounts,
+            amount_lamports,
+            expires_in_slots,
+            redeemer,
+            secret_hash,
+            bump,
+        ),
+        SwapInstruction::Redeem { secret } => redeem(program_id, accounts, secret),
+        SwapInstruction::Refund => refund(program_id, accounts),
+        SwapInstruction::InstantRefund => instant_refund(program_id, accounts),
+    }
+}
+
+fn initiate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_lamports: u64,
+    expires_in_slots: u64,
+    redeemer: Pubkey,
+    secret_hash: [u8; 32],
+    bump: u8,
+) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let initiator = next_account_info(accounts)?;
+    let swap_account = next_account_info(accounts)?;
+    let system_program = next_account_info(accounts)?;
+    if !initiator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if amount_lamports == 0 || expires_in_slots == 0 {
+        return Err(SwapError::ZeroAmountOrExpiry.into());
+    }
+
+    // The bump is provided by the client, sparing the cost of deriving it on-chain
+    let seeds: &[&[u8]] = &[
+        b"swap_account",
+        initiator.key.as_ref(),
+        redeemer.as_ref(),
+        &secret_hash,
+        &[bump],
+    ];
+    let expected_swap_account = Pubkey::create_program_address(seeds, program_id)
+        .map_err(|_| SwapError::InvalidSwapAccount)?;
+    if *swap_account.key != expected_swap_account {
+        return Err(SwapError::InvalidSwapAccount.into());
+    }
+
+    let rent_amount = Rent::get()?.minimum_balance(SwapAccount::LEN);
+    let amount_with_rent = rent_amount
+        .checked_add(amount_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let deposited = swap_account.lamports();
+    if deposited == 0 {
+        invoke_signed(
+            &solana_system_interface::instruction::create_account(
+                initiator.key,
+                swap_account.key,
+                amount_with_rent,
+                SwapAccount::LEN as u64,
+                program_id,
+            ),
+            &[
+                initiator.clone(),
+                swap_account.clone(),
+                system_program.clone(),
+            ],
+            &[seeds],
+        )?;
+    } else {
+        // The account is allocated and assigned in place of being created, so that lamports
+        // sent to its address ahead of the initiate do not block it. They count towards its
+        // rent and the swap amount, with only the remainder, if any, transferred.
+        let remainder = amount_with_rent.saturating_sub(deposited);
+        if remainder > 0 {
+            invoke(
+                &solana_system_interface::instruction::transfer(
+                    initiator.key,
+                    swap_account.key,
+                    remainder,
+                ),
+                &[
+                    initiator.clone(),
+                    swap_account.clone(),
+                    system_program.clone(),
+                ],
+            )?;
+        }
+        invoke_signed(
+            &solana_system_interface::instruction::allocate(
+                swap_account.key,
+                SwapAccount::LEN as u64,
+            ),
+            &[swap_account.clone(), system_program.clone()],
+            &[seeds],
+        )?;
+        invoke_signed(
+            &solana_system_interface::instruction::assign(swap_account.key, program_id),
+            &[swap_account.clone(), system_program.clone()],
+            &[seeds],
+        )?;
+    }
+
+    let expiry_slot = Clock::get()?
+        .slot
+        .checked_add(expires_in_slots)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    SwapAccount {
+        initiator: *initiator.key,
+        redeemer,
+        secret_hash,
+        amount_lamports,
+        expiry_slot,
+        bump,
+    }
+    .pack(&mut swap_account.try_borrow_mut_data()?);
+
+    Ok(())
+}
+
+fn redeem(program_id: &Pubkey, accounts: &[AccountInfo], secret: [u8; 32]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let swap_account = next_account_info(accounts)?;
+    let redeemer = next_account_info(accounts)?;
+    let initiator = next_account_info(accounts)?;
+    let swap = load_swap(program_id, swap_account, initiator)?;
+    if *redeemer.key != swap.redeemer {
+        return Err(SwapError::InvalidRedeemer.into());
+    }
+    if hash(&secret).to_bytes() != swap.secret_hash {
+        return Err(SwapError::InvalidSecret.into());
+    }
+
+    close_swap(swap_account, &swap, redeemer, initiator)
+}
+
+fn refund(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let swap_account = next_account_info(accounts)?;
+    let initiator = next_account_info(accounts)?;
+    let swap = load_swap(program_id, swap_account, initiator)?;
+    if Clock::get()?.slot < swap.expiry_slot {
+        return Err(SwapError::RefundBeforeExpiry.into());
+    }
+
+    close_swap(swap_account, &swap, initiator, initiator)
+}
+
+fn instant_refund(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = &mut accounts.iter();
+    let swap_account = next_account_info(accounts)?;
+    let initiator = next_account_info(accounts)?;
+    let redeemer = next_account_info(accounts)?;
+    let swap = load_swap(program_id, swap_account, initiator)?;
+    if *redeemer.key != swap.redeemer {
+        return Err(SwapError::InvalidRedeemer.into());
+    }
+    if !redeemer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    close_swap(swap_account, &swap, initiator, initiator)
+}
+
+/// Decodes the state of a swap account owned by this program, verifying its initiator.
+/// Only this program can create accounts it owns, and it only does so at swap PDAs,
+/// so the ownership check alone rules out substituted accounts.
+fn load_swap(
+    program_id: &Pubkey,
+    swap_account: &AccountInfo,
+    initiator: &AccountInfo,
+) -> Result<SwapAccount, ProgramError> {
+    if swap_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let swap = SwapAccount::unpack(&swap_account.try_borrow_data()?)?;
+    if *initiator.key != swap.initiator {
+        return Err(SwapError::InvalidInitiator.into());
+    }
+    Ok(swap)
+}
+
+/// Transfers the swap amount to `destination` and the remaining rent to the initiator,
+/// then closes the swap account
+fn close_swap(
+    swap_account: &AccountInfo,
+    swap: &SwapAccount,
+    destination: &AccountInfo,
+    initiator: &AccountInfo,
+) -> ProgramResult {
+    let rent_amount = swap_account.lamports() - swap.amount_lamports;
+    **swap_account.try_borrow_mut_lamports()? = 0;
+    **destination.try_borrow_mut_lamports()? += swap.amount_lamports;
+    **initiator.try_borrow_mut_lamports()? += rent_amount;
+
+    swap_account.assign(&system_program::ID);
+    swap_account.realloc(0, false)
+}
@@ -0,0 +1,298 @@
+//! An example of a program composing the native SOL atomic swap program through CPI, using the
+//! typed interface its `cpi` feature provides. Its instructions forward to those of the swap
+//! program, as an order book or escrow would on settling, including initiating swaps from a
+//! vault owned by this program, with the vault's PDA as the initiator.
+//!
+//! Depend on the swap program with the `cpi` feature, which excludes its entrypoint:
+//!
+//! ```toml
+//! solana-native-swaps = { version = "1.0.0", features = ["cpi"] }
+//! ```
+//!
+//! Then invoke it through `solana_native_swaps::cpi`, whose account structs mirror those of each
+//! instruction. Instructions emitting events also take the swap program's event authority,
+//! derived by `solana_native_swaps::pda::event_authority`, and the swap program itself.
+
+use anchor_lang::prelude::*;
+use solana_native_swaps::{
+    cpi::{self as swaps, accounts as swaps_accounts},
+    program::SolanaNativeSwaps,
+    HashAlgorithm, RedeemWindow, SwapIncentives, SWAP_ACCOUNT_SPACE,
+};
+
+declare_id!("DYqyph2eJkXY9TNeGARFeFNf5yfXKrf6DTJckizYGQdn");
+
+#[program]
+pub mod solana_native_swaps_composer {
+    use super::*;
+
+    /// Initiates a swap from the initiator, with the swap program's defaults of SHA-256 secret
+    /// hashes, no incentives and no redeem window.
+    /// The initiator's signature is forwarded to the swap program, and as such is required
+    /// for this instruction.
+    pub fn initiate(
+        ctx: Context<Initiate>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        swaps::initiate(
+            CpiContext::new(
+                accounts.swaps_program.to_account_info(),
+                swaps_accounts::Initiate {
+                    swap_account: accounts.swap_account.to_account_info(),
+                    config: accounts.config.to_account_info(),
+                    initiator: accounts.initiator.to_account_info(),
+                    funder: None,
+                    refund_to: None,
+                    system_program: accounts.system_program.to_account_info(),
+                    event_authority: accounts.event_authority.to_account_info(),
+                    program: accounts.swaps_program.to_account_info(),
+                },
+            ),
+            amount_lamports,
+            expires_in_slots,
+            redeemer,
+            secret_hash,
+            HashAlgorithm::Sha256,
+            SwapIncentives::default(),
+            RedeemWindow::default(),
+        )
+    }
+
+    /// Creates the vault of `authority`, a PDA of this program holding lamports to initiate
+    /// swaps from. Lamports are deposited into it by plain transfers.
+    /// As such, the authority's signature is required for this instruction.
+    pub fn create_vault(ctx: Context<CreateVault>) -> Result<()> {
+        ctx.accounts.vault.set_inner(Vault {
+            authority: ctx.accounts.authority.key(),
+            bump: ctx.bumps.vault,
+        });
+        Ok(())
+    }
+
+    /// Initiates a swap from the vault, which is the swap's initiator and receives its refund
+    /// and rent. As the vault is owned by this program, the swap amount and the swap account's
+    /// rent are deposited into the swap account by this program, before the swap program is
+    /// invoked with the vault signing.
+    /// As such, the vault authority's signature is required for this instruction.
+    pub fn initiate_from_vault(
+        ctx: Context<InitiateFromVault>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let deposit = Rent::get()?
+            .minimum_balance(SWAP_ACCOUNT_SPACE)
+            .checked_add(amount_lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        accounts.vault.sub_lamports(deposit)?;
+        accounts.swap_account.add_lamports(deposit)?;
+
+        let authority = accounts.authority.key();
+        let seeds: &[&[u8]] = &[b"vault", authority.as_ref(), &[accounts.vault.bump]];
+        swaps::initiate(
+            CpiContext::new_with_signer(
+                accounts.swaps_program.to_account_info(),
+                swaps_accounts::Initiate {
+                    swap_account: accounts.swap_account.to_account_info(),
+                    config: accounts.config.to_account_info(),
+                    initiator: accounts.vault.to_account_info(),
+                    funder: None,
+                    refund_to: None,
+                    system_program: accounts.system_program.to_account_info(),
+                    event_authority: accounts.event_authority.to_account_info(),
+                    program: accounts.swaps_program.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount_lamports,
+            expires_in_slots,
+            redeemer,
+            secret_hash,
+            HashAlgorithm::Sha256,
+            SwapIncentives::default(),
+            RedeemWindow::default(),
+        )
+    }
+
+    /// Redeems the swap's entire remaining funds to the redeemer, as read through the swap
+    /// program's `get_swap_state`, rather than decoding the swap account.
+    /// This instruction does not require any signatures.
+    pub fn redeem(ctx: Context<Redeem>, secret: [u8; 32]) -> Result<()> {
+        let accounts = ctx.accounts;
+        let swap = swaps::get_swap_state(CpiContext::new(
+            accounts.swaps_program.to_account_info(),
+            swaps_accounts::GetSwapState {
+                swap_account: accounts.swap_account.to_account_info(),
+            },
+        ))?
+        .get();
+
+        swaps::redeem(
+            CpiContext::new(
+                accounts.swaps_program.to_account_info(),
+                swaps_accounts::Redeem {
+                    swap_account: accounts.swap_account.to_account_info(),
+                    initiator: accounts.initiator.to_account_info(),
+                    redeemer: accounts.redeemer.to_account_info(),
+                    destination: None,
+                    config: accounts.config.to_account_info(),
+                    treasury: accounts.treasury.to_account_info(),
+                    relayer: None,
+                    receipt: None,
+                    receipt_payer: None,
+                    system_program: accounts.system_program.to_account_info(),
+                    event_authority: accounts.event_authority.to_account_info(),
+                    program: accounts.swaps_program.to_account_info(),
+                },
+            ),
+            secret,
+            swap.amount_lamports,
+        )
+    }
+
+    /// Refunds the expired swap to the initiator.
+    /// This instruction does not require any signatures.
+    pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        let accounts = ctx.accounts;
+        swaps::refund(CpiContext::new(
+            accounts.swaps_program.to_account_info(),
+            swaps_accounts::Refund {
+                swap_account: accounts.swap_account.to_account_info(),
+                initiator: accounts.initiator.to_account_info(),
+                refund_to: None,
+                refunder: None,
+                event_authority: accounts.event_authority.to_account_info(),
+                program: accounts.swaps_program.to_account_info(),
+            },
+        ))
+    }
+}
+
+// The accounts forwarded to the swap program are verified by it, and left unchecked here
+
+#[derive(Accounts)]
+pub struct Initiate<'info> {
+    /// CHECK: The swap account, to be created by the swap program
+    #[account(mut)]
+    pub swap_account: UncheckedAccount<'info>,
+
+    /// CHECK: The swap program's config
+    pub config: UncheckedAccount<'info>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The swap program's event authority
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub swaps_program: Program<'info, SolanaNativeSwaps>,
+}
+
+/// A program-owned account holding lamports to initiate swaps from
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    /// The account authorized to initiate swaps from this vault
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct CreateVault<'info> {
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump,
+        space = 8 + Vault::INIT_SPACE,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// The vault's authority, paying its rent. They must sign this transaction.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateFromVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", authority.key().as_ref()],
+        bump = vault.bump,
+        has_one = authority,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// The vault's authority. They must sign this transaction.
+    pub authority: Signer<'info>,
+
+    /// CHECK: The swap account, to be created by the swap program
+    #[account(mut)]
+    pub swap_account: UncheckedAccount<'info>,
+
+    /// CHECK: The swap program's config
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The swap program's event authority
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub swaps_program: Program<'info, SolanaNativeSwaps>,
+}
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    /// CHECK: The swap account
+    #[account(mut)]
+    pub swap_account: UncheckedAccount<'info>,
+
+    /// CHECK: The initiator of the atomic swap
+    #[account(mut)]
+    pub initiator: UncheckedAccount<'info>,
+
+    /// CHECK: The redeemer of the atomic swap
+    #[account(mut)]
+    pub redeemer: UncheckedAccount<'info>,
+
+    /// CHECK: The swap program's config
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: The swap program's treasury
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: The swap program's event authority
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub swaps_program: Program<'info, SolanaNativeSwaps>,
+}
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    /// CHECK: The swap account
+    #[account(mut)]
+    pub swap_account: UncheckedAccount<'info>,
+
+    /// CHECK: The initiator of the atomic swap
+    #[account(mut)]
+    pub initiator: UncheckedAccount<'info>,
+
+    /// CHECK: The swap program's event authority
+    pub event_authority: UncheckedAccount<'info>,
+
+    pub swaps_program: Program<'info, SolanaNativeSwaps>,
+}
@@ -1,237 +1,8945 @@
-use anchor_lang::{prelude::*, solana_program::hash, system_program};
+// The CPI functions Anchor generates take every argument of their instruction
+#![cfg_attr(feature = "cpi", allow(clippy::too_many_arguments))]
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        bpf_loader_upgradeable, ed25519_program,
+        epoch_schedule::EpochSchedule,
+        hash,
+        instruction::Instruction,
+        keccak,
+        program::invoke_signed,
+        secp256k1_program,
+        sysvar::{
+            self,
+            instructions::{load_current_index_checked, load_instruction_at_checked},
+        },
+    },
+    system_program,
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, spl_token::native_mint, Token},
+    token_2022::{
+        spl_token_2022::{
+            extension::{
+                confidential_transfer::{self, DecryptableBalance},
+                metadata_pointer::MetadataPointer,
+                transfer_fee::TransferFeeConfig,
+                transfer_hook::TransferHook,
+                ExtensionType,
+            },
+            instruction as token_2022_instruction, onchain,
+        },
+        Token2022,
+    },
+    token_2022_extensions::transfer_fee,
+    token_interface::{self, get_mint_extension_data, Mint, TokenAccount, TokenInterface},
+};
+use ripemd::{Digest, Ripemd160};
+use solana_bn254::prelude::{alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing};
+use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
 
 declare_id!("6eksgdCnSjUaGQWZ6iYvauv1qzvYPF33RTGTM1ZuyENx");
 
+#[cfg(not(feature = "no-entrypoint"))]
+solana_security_txt::security_txt! {
+    name: "Solana Native Swaps",
+    project_url: "https://github.com/catalogfi/solana-native-swaps",
+    contacts: "link:https://github.com/catalogfi/solana-native-swaps/security/advisories/new",
+    policy: "https://github.com/catalogfi/solana-native-swaps/blob/main/SECURITY.md",
+    source_code: "https://github.com/catalogfi/solana-native-swaps"
+}
+
 /// The size of Anchor's internal discriminator in a PDA's memory
 const ANCHOR_DISCRIMINATOR: usize = 8;
 
+/// The current layout version of `SwapAccount`.
+/// Version 1 is Borsh-encoded, superseded by the zero-copy layout of version 2, to which
+/// version 3 appends the starting relayer fee, and version 4 the acceptance bond.
+#[constant]
+pub const SWAP_ACCOUNT_VERSION: u8 = 4;
+
+/// The current version of the scheme swap accounts' seeds follow, as recorded by each.
+/// Version 0 seeds are `[b"swap_account", initiator, secret_hash]`, to which version 1 adds the
+/// redeemer after the initiator. Either is followed by the swap's nonce, if any.
+/// Token and wSOL swap accounts, which record no seeds version, are derived from
+/// `[b"token_swap_account", initiator, redeemer, secret_hash]` alone; any opened under the earlier
+/// `[b"token_swap_account", initiator, secret_hash]` must be redeemed or refunded before upgrading.
+#[constant]
+pub const SWAP_SEEDS_VERSION: u8 = 1;
+
+/// The prefix of the preimage of `any_secret_hash`, followed by the secret hashes
+const ANY_SECRET_HASH_PREFIX: &[u8] = b"any_secret_hash";
+
+/// The maximum number of secret hashes a swap may commit to through `initiate_multi_secret`
+/// or `initiate_any_secret`
+#[constant]
+pub const MAX_SECRET_HASHES: u8 = 4;
+
+/// The size of a swap account, for the rent callers initiating swaps from program-owned
+/// accounts must deposit beforehand
+pub const SWAP_ACCOUNT_SPACE: usize = ANCHOR_DISCRIMINATOR + SwapAccount::INIT_SPACE;
+
+/// The semantic version of this program, as returned by `get_version`
+#[constant]
+pub const PROGRAM_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Session scope permitting `instant_refund_with_session` on behalf of the redeemer
+#[constant]
+pub const SESSION_SCOPE_INSTANT_REFUND: u8 = 1 << 0;
+
+/// Session scope permitting `lock_channel_payment_with_session` on behalf of the funder
+#[constant]
+pub const SESSION_SCOPE_LOCK_CHANNEL_PAYMENT: u8 = 1 << 1;
+
+/// The maximum number of slots any timelock may be set to expire in (~1 year of 400ms slots)
+#[constant]
+pub const MAX_EXPIRY_SLOTS: u64 = 78_840_000;
+
+/// The number of slots after its expiry from which a swap is considered abandoned, and may be
+/// swept through `sweep_abandoned` (~30 days of 400ms slots)
+#[constant]
+pub const ABANDONED_SWAP_GRACE_SLOTS: u64 = 6_480_000;
+
+/// The maximum number of SPL tokens a bundle swap may escrow alongside its native SOL
+#[constant]
+pub const MAX_BUNDLE_TOKENS: u8 = 4;
+
+/// The maximum number of transfer hook programs the config may permit
+#[constant]
+pub const MAX_TRANSFER_HOOK_PROGRAMS: u8 = 8;
+
+/// The Metaplex token metadata program, holding the metadata and master editions of NFTs
+/// minted without Token-2022's metadata pointer
+#[constant]
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// The Metaplex Bubblegum program, managing compressed NFTs as leaves of concurrent merkle trees
+#[constant]
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+/// The SPL account compression program, maintaining the merkle trees of compressed NFTs
+#[constant]
+pub const ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey =
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// The SPL noop program, through which changes to merkle trees are logged for indexers
+#[constant]
+pub const NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8ShW8aGWSKGiQsr5CYVV9nuW4PYfw");
+
+/// The discriminator of Bubblegum's `transfer` instruction
+const BUBBLEGUM_TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+/// The Pyth receiver program, owning the price update accounts oracle-priced swaps are
+/// redeemed at
+#[constant]
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MHGSAnYRFRMbSLDbR8QqRdRnfFvDyz3NA");
+
+/// The ID of Pyth's SOL/USD price feed
+#[constant]
+pub const PYTH_SOL_USD_FEED_ID: [u8; 32] = [
+    239, 13, 139, 111, 218, 44, 235, 164, 29, 161, 93, 64, 149, 209, 218, 57, 42, 13, 47, 142, 208,
+    198, 199, 188, 15, 76, 250, 200, 194, 128, 181, 109,
+];
+
+/// The discriminator of the Pyth receiver's `PriceUpdateV2` account
+const PYTH_PRICE_UPDATE_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// The Wormhole core bridge program, owning the posted VAAs Wormhole swaps are redeemed with
+#[constant]
+pub const WORMHOLE_CORE_BRIDGE_PROGRAM_ID: Pubkey =
+    pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+/// The denominator of fees expressed in basis points
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// The prefix of the message a redeemer signs to consent to an instant refund,
+/// followed by the swap account's address and the swap's instance as per
+/// `SwapAccount::signed_message`
+#[constant]
+pub const INSTANT_REFUND_CONSENT_PREFIX: &[u8] = b"instant_refund";
+
+/// The prefix of the message a redeemer signs to consent to a redeem to a destination of their
+/// choosing, followed by the swap account's address and the swap's instance as per
+/// `SwapAccount::signed_message`, then the destination's address
+#[constant]
+pub const REDEEM_CONSENT_PREFIX: &[u8] = b"redeem";
+
+/// The prefix of the message signed with the key of a PTLC swap's point to claim it,
+/// followed by the swap account's address and the swap's instance as per
+/// `PtlcSwapAccount::claim_message`
+#[constant]
+pub const PTLC_CLAIM_PREFIX: &[u8] = b"ptlc_claim";
+
+/// The prefix of the message signed with the committed secp256k1 key of a secp256k1 swap to claim
+/// it, followed by the swap account's address and the swap's instance as per
+/// `Secp256k1SwapAccount::claim_message`
+#[constant]
+pub const SECP256K1_CLAIM_PREFIX: &[u8] = b"secp256k1_claim";
+
+/// The maximum number of hash-locked payments that may be pending within a payment channel at once
+const MAX_PENDING_CHANNEL_PAYMENTS: usize = 16;
+
+/// Derivation of this program's PDAs, each returned along with its bump, for clients as well
+/// as programs invoking this one through CPI
+pub mod pda {
+    use super::*;
+
+    /// The swap account of the swap from `initiator` to `redeemer` with `secret_hash`
+    pub fn swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the swap from `initiator` to `redeemer` with `secret_hash`,
+    /// initiated through `initiate_with_nonce` with the nonzero `nonce`
+    pub fn swap_account_with_nonce(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+        nonce: u32,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+                &nonce.to_le_bytes(),
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of a swap between `initiator` and the redeemer with `secret_hash`,
+    /// initiated without a nonce before the redeemer was part of the seeds
+    pub fn legacy_swap_account(initiator: &Pubkey, secret_hash: &[u8; 32]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"swap_account", initiator.as_ref(), secret_hash], &ID)
+    }
+
+    /// The swap account holding the state of `swap`, derived from the seeds it records
+    pub fn swap_account_of(swap: &SwapAccount) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"swap_account",
+                swap.initiator.as_ref(),
+                swap.redeemer_seed(),
+                &swap.secret_hash,
+                swap.nonce_seed(),
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the streamed swap from `initiator` to `redeemer` with `secret_hash`
+    pub fn streamed_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"streamed_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the series of swaps from `initiator` to `redeemer` committed to the
+    /// hash chain ending at `chain_tip`
+    pub fn hash_chain_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        chain_tip: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"hash_chain_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                chain_tip,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the PTLC swap from `initiator` to `redeemer` locked to `point`
+    pub fn ptlc_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        point: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"ptlc_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                point.as_ref(),
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the secp256k1 swap from `initiator` to `redeemer` claimable by the
+    /// signer of `eth_address`
+    pub fn secp256k1_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        eth_address: &[u8; 20],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"secp256k1_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                eth_address,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the zero-knowledge swap from `initiator` to `redeemer` with `secret_hash`
+    pub fn zk_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"zk_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the oracle-priced swap from `initiator` to `redeemer` with
+    /// `secret_hash`
+    pub fn oracle_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"oracle_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the Wormhole swap from `initiator` to `redeemer` with `swap_id`
+    pub fn wormhole_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        swap_id: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"wormhole_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                swap_id,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the confidential swap from `initiator` to `redeemer` with `secret_hash`
+    pub fn confidential_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"confidential_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The vault confidentially escrowing the tokens of the confidential swap at `swap_account`
+    pub fn confidential_vault(swap_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"confidential_vault", swap_account.as_ref()], &ID)
+    }
+
+    /// The swap account of the token or wSOL swap from `initiator` to `redeemer` with `secret_hash`
+    pub fn token_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"token_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The swap account of the NFT swap from `initiator` to `redeemer` with `secret_hash`
+    pub fn nft_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"nft_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The vault escrowing the NFT of the NFT swap at `swap_account`
+    pub fn nft_vault(swap_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"nft_vault", swap_account.as_ref()], &ID)
+    }
+
+    /// The Metaplex metadata account of `mint`
+    pub fn metaplex_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"metadata",
+                TOKEN_METADATA_PROGRAM_ID.as_ref(),
+                mint.as_ref(),
+            ],
+            &TOKEN_METADATA_PROGRAM_ID,
+        )
+    }
+
+    /// The Metaplex master edition of `mint`, holding its mint authority
+    pub fn metaplex_master_edition(mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"metadata",
+                TOKEN_METADATA_PROGRAM_ID.as_ref(),
+                mint.as_ref(),
+                b"edition",
+            ],
+            &TOKEN_METADATA_PROGRAM_ID,
+        )
+    }
+
+    /// The swap account of the cNFT swap from `initiator` to `redeemer` with `secret_hash`
+    pub fn cnft_swap_account(
+        initiator: &Pubkey,
+        redeemer: &Pubkey,
+        secret_hash: &[u8; 32],
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"cnft_swap_account",
+                initiator.as_ref(),
+                redeemer.as_ref(),
+                secret_hash,
+            ],
+            &ID,
+        )
+    }
+
+    /// The asset id of the compressed NFT minted to `merkle_tree` with `nonce`
+    pub fn cnft_asset_id(merkle_tree: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+            &BUBBLEGUM_PROGRAM_ID,
+        )
+    }
+
+    /// The authority of `merkle_tree`, holding its Bubblegum configuration
+    pub fn cnft_tree_authority(merkle_tree: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[merkle_tree.as_ref()], &BUBBLEGUM_PROGRAM_ID)
+    }
+
+    /// The order posted by `maker` with `order_id`
+    pub fn order(maker: &Pubkey, order_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"order", maker.as_ref(), &order_id.to_le_bytes()], &ID)
+    }
+
+    /// The program-wide config
+    pub fn config() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"config"], &ID)
+    }
+
+    /// The treasury collecting protocol fees
+    pub fn treasury() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"treasury"], &ID)
+    }
+
+    /// The redeem receipt of the swap account at `swap_account`
+    pub fn redeem_receipt(swap_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"redeem_receipt", swap_account.as_ref()], &ID)
+    }
+
+    /// The authority the program emits events through, by invoking itself
+    pub fn event_authority() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"__event_authority"], &ID)
+    }
+}
+
 #[program]
 pub mod solana_native_swaps {
     use super::*;
 
-    /// Initiates the atomic swap. Funds are transferred from the initiator to the token vault.
+    /// Initiates the atomic swap. Funds are transferred from the initiator to the token vault,
+    /// or from a third-party funder on the initiator's behalf, if provided.
     /// As such, the initiator's signature is required for this instruction.
+    /// Initiators owned by other programs, e.g. their PDAs signing through CPI, are to deposit
+    /// the swap amount and the rent of `SWAP_ACCOUNT_SPACE` into the swap account beforehand,
+    /// as the system program cannot debit them.
     /// `amount_lamports` represents the quantity of native SOL to be transferred
     /// through this atomic swap in base units (aka lamports).  
     /// E.g: A quantity of 1 SOL must be provided as 1,000,000,000.
     /// `expires_in_slots` represents the number of slots (1 slot = 400ms) after
     /// which (non-instant) refunds are allowed.
+    /// `hash_algorithm` is the algorithm `secret_hash` was computed with, allowing the same
+    /// secret to be used against HTLCs on chains that standardize on a different algorithm.
+    /// `incentives` are the fees offered to third parties for submitting the swap's
+    /// lifecycle instructions on behalf of its parties, carved out of the swap amount.
+    /// `redeem_window` restricts when the swap may be redeemed, in addition to its secret.
+    #[allow(clippy::too_many_arguments)]
     pub fn initiate(
         ctx: Context<Initiate>,
         amount_lamports: u64,
         expires_in_slots: u64,
         redeemer: Pubkey,
         secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+    ) -> Result<()> {
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        let event = ctx.accounts.open_swap(
+            ctx.bumps.swap_account,
+            amount_lamports,
+            expiry_slot,
+            None,
+            redeemer,
+            secret_hash,
+            hash_algorithm,
+            incentives,
+            redeem_window,
+            0,
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Initiates the atomic swap with an expiry denominated in epochs, for swaps meant to stay
+    /// open for long durations. Refunds are allowed once `expires_in_epochs` epochs
+    /// have begun after the current one, as per the cluster's `EpochSchedule`.
+    /// `expires_in_epochs` may not exceed the number of whole epochs within the configured
+    /// maximum expiry. In all other respects, this is identical to `initiate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_with_epoch_expiry(
+        ctx: Context<Initiate>,
+        amount_lamports: u64,
+        expires_in_epochs: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+    ) -> Result<()> {
+        let epoch_schedule = EpochSchedule::get()?;
+        // Bounding the epochs first keeps the first slot of the expiry epoch from overflowing
+        require!(
+            expires_in_epochs > 0
+                && expires_in_epochs
+                    <= ctx.accounts.config.max_expiry_slots / epoch_schedule.slots_per_epoch,
+            SwapError::InvalidEpochExpiry
+        );
+        let expiry_epoch = Clock::get()?.epoch + expires_in_epochs;
+        let expiry_slot = epoch_schedule.get_first_slot_in_epoch(expiry_epoch);
+        let event = ctx.accounts.open_swap(
+            ctx.bumps.swap_account,
+            amount_lamports,
+            expiry_slot,
+            None,
+            redeemer,
+            secret_hash,
+            hash_algorithm,
+            incentives,
+            redeem_window,
+            0,
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Initiates the atomic swap with a second, wall-clock timelock for high-value swaps.
+    /// Refunds are allowed only once both `expires_in_slots` slots and `expires_in_seconds`
+    /// seconds have passed. `expires_in_seconds` must be positive and may not exceed the
+    /// configured maximum expiry at the cluster's default slot duration.
+    /// In all other respects, this is identical to `initiate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_with_dual_expiry(
+        ctx: Context<Initiate>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+        expires_in_seconds: i64,
+    ) -> Result<()> {
+        require!(expires_in_seconds > 0, SwapError::ZeroExpiry);
+        let max_expiry_seconds = ctx.accounts.config.max_expiry_slots
+            * anchor_lang::solana_program::clock::DEFAULT_MS_PER_SLOT
+            / 1_000;
+        require!(
+            expires_in_seconds as u64 <= max_expiry_seconds,
+            SwapError::ExpiryOutOfBounds
+        );
+        let clock = Clock::get()?;
+        let expiry_slot = expiry_slot_after(clock.slot, expires_in_slots)?;
+        let expiry_timestamp = clock
+            .unix_timestamp
+            .checked_add(expires_in_seconds)
+            .ok_or(SwapError::ExpiryTooLarge)?;
+        let event = ctx.accounts.open_swap(
+            ctx.bumps.swap_account,
+            amount_lamports,
+            expiry_slot,
+            Some(expiry_timestamp),
+            redeemer,
+            secret_hash,
+            hash_algorithm,
+            incentives,
+            redeem_window,
+            0,
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Initiates the atomic swap committed to several secret hashes, all of whose secrets must be
+    /// revealed through `redeem_multi_secret` to redeem it, e.g. for multi-party coordination
+    /// where each participant must release their share of the preimage.
+    /// `secret_hash` is the commitment to `secret_hashes` as per `combined_secret_hash`, which
+    /// takes its place in the PDA's seeds. Between 2 and `MAX_SECRET_HASHES` secret hashes,
+    /// computed with `hash_algorithm`, are supported.
+    /// In all other respects, this is identical to `initiate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_multi_secret(
+        ctx: Context<Initiate>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+        secret_hashes: Vec<[u8; 32]>,
     ) -> Result<()> {
+        require!(
+            (2..=MAX_SECRET_HASHES as usize).contains(&secret_hashes.len()),
+            SwapError::InvalidSecretCount
+        );
+        require!(
+            combined_secret_hash(&secret_hashes) == secret_hash,
+            SwapError::InvalidCombinedSecretHash
+        );
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        let event = ctx.accounts.open_swap(
+            ctx.bumps.swap_account,
+            amount_lamports,
+            expiry_slot,
+            None,
+            redeemer,
+            secret_hash,
+            hash_algorithm,
+            incentives,
+            redeem_window,
+            0,
+        )?;
+        let swap_account = event.swap_account;
+        emit_cpi!(event);
+        emit_cpi!(MultiSecretInitiated {
+            swap_account,
+            secret_hashes,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates the atomic swap in two phases, requiring the redeemer to accept it through
+    /// `accept` and post a bond of `acceptance_bond_lamports` before it may be redeemed.
+    /// Should the redeemer then never redeem, the bond is forfeited upon refund, paid along with
+    /// the swap amount to the refund destination, i.e. the account specified at initiate if any
+    /// and the initiator otherwise, compensating them for the counterparty walking away. The bond is otherwise returned to
+    /// the redeemer, along with the swap amount upon redeem or their share upon
+    /// `cooperative_close`, or by itself upon an instant refund.
+    /// In all other respects, this is identical to `initiate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_with_acceptance_bond(
+        ctx: Context<Initiate>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+        acceptance_bond_lamports: u64,
+    ) -> Result<()> {
+        require!(acceptance_bond_lamports > 0, SwapError::ZeroAcceptanceBond);
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        let event = ctx.accounts.open_swap(
+            ctx.bumps.swap_account,
+            amount_lamports,
+            expiry_slot,
+            None,
+            redeemer,
+            secret_hash,
+            hash_algorithm,
+            incentives,
+            redeem_window,
+            0,
+        )?;
+        ctx.accounts
+            .swap_account
+            .load_mut()?
+            .acceptance_bond_lamports = acceptance_bond_lamports;
+        let swap_account = event.swap_account;
+        emit_cpi!(event);
+        emit_cpi!(AcceptanceBondRequired {
+            swap_account,
+            acceptance_bond_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Accepts a swap initiated through `initiate_with_acceptance_bond`, posting its acceptance
+    /// bond into the swap account and making the swap live, i.e. redeemable.
+    /// Swaps may not be accepted once expired.
+    /// As such, the redeemer's signature is required for this instruction.
+    pub fn accept(ctx: Context<Accept>) -> Result<()> {
+        let swap = ctx.accounts.swap_account.load()?;
+        require!(
+            swap.acceptance_bond_lamports > 0,
+            SwapError::AcceptanceNotRequired
+        );
+        require!(
+            swap.posted_bond_lamports == 0,
+            SwapError::SwapAlreadyAccepted
+        );
+        require!(
+            !swap.has_expired(&Clock::get()?),
+            SwapError::AcceptAfterExpiry
+        );
+
+        let bond_lamports = swap.acceptance_bond_lamports;
+        let redeemer = swap.redeemer;
+        // The swap account's data may not be borrowed across the transfer's CPI
+        drop(swap);
         let transfer_context = CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
-                from: ctx.accounts.initiator.to_account_info(),
+                from: ctx.accounts.redeemer.to_account_info(),
                 to: ctx.accounts.swap_account.to_account_info(),
             },
         );
-        system_program::transfer(transfer_context, amount_lamports)?;
+        system_program::transfer(transfer_context, bond_lamports)?;
+        ctx.accounts.swap_account.load_mut()?.posted_bond_lamports = bond_lamports;
+
+        emit_cpi!(Accepted {
+            swap_account: ctx.accounts.swap_account.key(),
+            redeemer,
+            bond_lamports,
+        });
+
+        Ok(())
+    }
 
-        *ctx.accounts.swap_account = SwapAccount {
+    /// Initiates the atomic swap redeemable with the secret of any one of several secret hashes
+    /// through `redeem_any_secret`, e.g. when the redeemer has multiple acceptable settlement
+    /// paths on the counterpart chain, such as either of two BTC HTLC outputs.
+    /// `secret_hash` is the commitment to `secret_hashes` as per `any_secret_hash`, which
+    /// takes its place in the PDA's seeds. Between 2 and `MAX_SECRET_HASHES` secret hashes,
+    /// computed with `hash_algorithm`, are supported.
+    /// In all other respects, this is identical to `initiate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_any_secret(
+        ctx: Context<Initiate>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+        secret_hashes: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            (2..=MAX_SECRET_HASHES as usize).contains(&secret_hashes.len()),
+            SwapError::InvalidSecretCount
+        );
+        require!(
+            any_secret_hash(&secret_hashes) == secret_hash,
+            SwapError::InvalidCombinedSecretHash
+        );
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        let event = ctx.accounts.open_swap(
+            ctx.bumps.swap_account,
             amount_lamports,
-            expiry_slot: Clock::get()?.slot + expires_in_slots,
-            initiator: ctx.accounts.initiator.key(),
+            expiry_slot,
+            None,
             redeemer,
             secret_hash,
-        };
+            hash_algorithm,
+            incentives,
+            redeem_window,
+            0,
+        )?;
+        let swap_account = event.swap_account;
+        emit_cpi!(event);
+        emit_cpi!(AnySecretInitiated {
+            swap_account,
+            secret_hashes,
+        });
 
-        emit!(Initiated {
-            swap_amount: amount_lamports,
-            expires_in_slots,
-            initiator: ctx.accounts.initiator.key(),
+        Ok(())
+    }
+
+    /// Initiates the atomic swap with a nonzero `nonce` in its PDA's seeds, allowing multiple
+    /// swaps between the same parties and secret hash to be open at once, e.g. for market
+    /// makers deriving secrets deterministically across orders.
+    /// In all other respects, this is identical to `initiate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_with_nonce(
+        ctx: Context<InitiateWithNonce>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+        nonce: u32,
+    ) -> Result<()> {
+        require!(nonce != 0, SwapError::ZeroNonce);
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        let event = ctx.accounts.initiate().open_swap(
+            ctx.bumps.swap_account,
+            amount_lamports,
+            expiry_slot,
+            None,
             redeemer,
             secret_hash,
-        });
+            hash_algorithm,
+            incentives,
+            redeem_window,
+            nonce,
+        )?;
+        emit_cpi!(event);
 
         Ok(())
     }
 
-    /// Funds are transferred to the redeemer. This instruction does not require any signatures.
-    pub fn redeem(ctx: Context<Redeem>, secret: [u8; 32]) -> Result<()> {
+    /// Initiates multiple atomic swaps in a single transaction, one per entry of `swaps`.
+    /// The swap accounts are to be provided as remaining accounts, in the same order as `swaps`.
+    /// In all other respects, each swap is identical to one created through `initiate`.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitiateBatch<'info>>,
+        swaps: Vec<BatchedSwap>,
+    ) -> Result<()> {
         require!(
-            hash::hash(&secret).to_bytes() == ctx.accounts.swap_account.secret_hash,
-            SwapError::InvalidSecret
+            swaps.len() == ctx.remaining_accounts.len(),
+            SwapError::BatchAccountsMismatch
         );
+        let clock = Clock::get()?;
+        let space = SWAP_ACCOUNT_SPACE;
+        let rent_amount = Rent::get()?.minimum_balance(space);
+        let initiator = ctx.accounts.initiator.key();
 
-        let swap_amount = ctx.accounts.swap_account.amount_lamports;
-        ctx.accounts.swap_account.sub_lamports(swap_amount)?;
-        ctx.accounts.redeemer.add_lamports(swap_amount)?;
+        for (swap, swap_account) in swaps.iter().zip(ctx.remaining_accounts) {
+            require!(swap.amount_lamports > 0, SwapError::ZeroAmount);
+            require!(swap.expires_in_slots > 0, SwapError::ZeroExpiry);
+            require_keys_neq!(swap.redeemer, initiator, SwapError::SelfSwap);
+            ctx.accounts.config.validate_expiry(swap.expires_in_slots)?;
+            swap.incentives.validate(swap.amount_lamports)?;
+            let expiry_slot = expiry_slot_after(clock.slot, swap.expires_in_slots)?;
+            swap.redeem_window.validate(expiry_slot)?;
+            let (expected_swap_account, bump) = Pubkey::find_program_address(
+                &[
+                    b"swap_account",
+                    initiator.as_ref(),
+                    swap.redeemer.as_ref(),
+                    &swap.secret_hash,
+                ],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                swap_account.key(),
+                expected_swap_account,
+                SwapError::InvalidSwapAccount
+            );
 
-        emit!(Redeemed {
-            initiator: ctx.accounts.swap_account.initiator,
-            secret,
+            let seeds: &[&[u8]] = &[
+                b"swap_account",
+                initiator.as_ref(),
+                swap.redeemer.as_ref(),
+                &swap.secret_hash,
+                &[bump],
+            ];
+            let signer_seeds = &[seeds];
+            let system_program = ctx.accounts.system_program.to_account_info();
+            let deposited = swap_account.lamports();
+            if deposited == 0 {
+                let create_context = CpiContext::new_with_signer(
+                    system_program,
+                    system_program::CreateAccount {
+                        from: ctx.accounts.initiator.to_account_info(),
+                        to: swap_account.clone(),
+                    },
+                    signer_seeds,
+                );
+                system_program::create_account(
+                    create_context,
+                    rent_amount + swap.amount_lamports,
+                    space as u64,
+                    ctx.program_id,
+                )?;
+            } else {
+                // As `init` does for `initiate`, the account is allocated and assigned in place
+                // of being created, so that lamports sent to its address ahead of the batch do
+                // not block it. They count towards its rent and the swap amount, as they do for
+                // `initiate`, with only the remainder, if any, transferred.
+                let remainder = (rent_amount + swap.amount_lamports).saturating_sub(deposited);
+                if remainder > 0 {
+                    let transfer_context = CpiContext::new(
+                        system_program.clone(),
+                        system_program::Transfer {
+                            from: ctx.accounts.initiator.to_account_info(),
+                            to: swap_account.clone(),
+                        },
+                    );
+                    system_program::transfer(transfer_context, remainder)?;
+                }
+                let allocate_context = CpiContext::new_with_signer(
+                    system_program.clone(),
+                    system_program::Allocate {
+                        account_to_allocate: swap_account.clone(),
+                    },
+                    signer_seeds,
+                );
+                system_program::allocate(allocate_context, space as u64)?;
+                let assign_context = CpiContext::new_with_signer(
+                    system_program,
+                    system_program::Assign {
+                        account_to_assign: swap_account.clone(),
+                    },
+                    signer_seeds,
+                );
+                system_program::assign(assign_context, ctx.program_id)?;
+            }
+
+            let swap_account =
+                AccountLoader::<SwapAccount>::try_from_unchecked(ctx.program_id, swap_account)?;
+            *swap_account.load_init()? = SwapAccount {
+                version: SWAP_ACCOUNT_VERSION,
+                bump,
+                hash_algorithm: swap.hash_algorithm as u8,
+                nonce: [0; 4],
+                seeds_version: SWAP_SEEDS_VERSION,
+                amount_lamports: swap.amount_lamports,
+                expiry_slot,
+                expiry_timestamp: 0,
+                relayer_fee_lamports: swap.incentives.relayer_fee_lamports,
+                refund_tip_lamports: swap.incentives.refund_tip_lamports,
+                redeem_after_slot: swap.redeem_window.redeem_after_slot.unwrap_or_default(),
+                redeem_deadline_slot: swap.redeem_window.redeem_deadline_slot.unwrap_or_default(),
+                created_at_slot: clock.slot,
+                created_at_unix: clock.unix_timestamp,
+                initiator,
+                redeemer: swap.redeemer,
+                secret_hash: swap.secret_hash,
+                refund_to: swap.refund_to.unwrap_or_default(),
+                relayer_fee_start_lamports: swap.incentives.relayer_fee_start(),
+                acceptance_bond_lamports: 0,
+                posted_bond_lamports: 0,
+            };
+            // Writes the account discriminator
+            swap_account.exit(ctx.program_id)?;
+        }
+
+        emit_cpi!(BatchInitiated {
+            initiator,
+            swaps,
+            created_at_slot: clock.slot,
+            created_at_unix: clock.unix_timestamp,
         });
 
         Ok(())
     }
 
-    /// Funds are returned to the initiator, given that no redeems have occured
-    /// and the expiry slot has been reached.
-    /// This instruction does not require any signatures.
-    pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        let expiry_slot = ctx.accounts.swap_account.expiry_slot;
-        let current_slot = Clock::get()?.slot;
-        require!(current_slot > expiry_slot, SwapError::RefundBeforeExpiry);
-
-        let swap_amount = ctx.accounts.swap_account.amount_lamports;
-        ctx.accounts.swap_account.sub_lamports(swap_amount)?;
-        ctx.accounts.initiator.add_lamports(swap_amount)?;
+    /// Initializes the program's config, bounding the expiry of swaps initiated hereafter
+    /// to between `min_expiry_slots` and `max_expiry_slots` slots (inclusive),
+    /// along with the treasury protocol fees are paid to. The protocol fee is initially zero.
+    /// As such, the signature of the program's upgrade authority is required for this instruction,
+    /// who becomes the admin of the config.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        min_expiry_slots: u64,
+        max_expiry_slots: u64,
+    ) -> Result<()> {
+        require!(
+            min_expiry_slots <= max_expiry_slots && max_expiry_slots <= MAX_EXPIRY_SLOTS,
+            SwapError::InvalidExpiryBounds
+        );
+        *ctx.accounts.config = Config {
+            admin: ctx.accounts.admin.key(),
+            min_expiry_slots,
+            max_expiry_slots,
+            protocol_fee_bps: 0,
+            initiate_paused: false,
+            transfer_hook_programs: vec![],
+        };
 
-        emit!(Refunded {
-            initiator: ctx.accounts.swap_account.initiator,
-            secret_hash: ctx.accounts.swap_account.secret_hash,
+        emit_cpi!(ExpiryBoundsUpdated {
+            min_expiry_slots,
+            max_expiry_slots,
         });
 
         Ok(())
     }
 
-    /// Funds are returned to the initiator, with the redeemer's consent.
-    /// As such, the redeemer's signature is required for this instruction.
-    /// This allows for refunds before the expiry slot.
-    pub fn instant_refund(ctx: Context<InstantRefund>) -> Result<()> {
-        let swap_amount = ctx.accounts.swap_account.amount_lamports;
-        ctx.accounts.swap_account.sub_lamports(swap_amount)?;
-        ctx.accounts.initiator.add_lamports(swap_amount)?;
+    /// Updates the bounds on the expiry of swaps initiated hereafter.
+    /// Existing swaps are unaffected.
+    /// As such, the admin's signature is required for this instruction.
+    pub fn update_expiry_bounds(
+        ctx: Context<UpdateConfig>,
+        min_expiry_slots: u64,
+        max_expiry_slots: u64,
+    ) -> Result<()> {
+        require!(
+            min_expiry_slots <= max_expiry_slots && max_expiry_slots <= MAX_EXPIRY_SLOTS,
+            SwapError::InvalidExpiryBounds
+        );
+        let config = &mut ctx.accounts.config;
+        config.min_expiry_slots = min_expiry_slots;
+        config.max_expiry_slots = max_expiry_slots;
 
-        emit!(InstantRefunded {
-            initiator: ctx.accounts.swap_account.initiator,
-            secret_hash: ctx.accounts.swap_account.secret_hash,
+        emit_cpi!(ExpiryBoundsUpdated {
+            min_expiry_slots,
+            max_expiry_slots,
         });
 
         Ok(())
     }
-}
 
-/// Stores the state information of the atomic swap on-chain
-#[account]
-#[derive(InitSpace)]
-pub struct SwapAccount {
-    /// The quantity of native SOL to be transferred through this atomic swap in base units (aka lamports)
-    amount_lamports: u64,
-    /// The exact slot after which (non-instant) refunds are allowed
-    expiry_slot: u64,
-    /// The initiator of the atomic swap
-    initiator: Pubkey,
-    /// The redeemer of the atomic swap
-    redeemer: Pubkey,
-    /// The secret hash associated with the atomic swap
-    secret_hash: [u8; 32],
-}
+    /// Sets the protocol fee deducted from swaps upon redeem, in basis points of the swap amount.
+    /// As such, the admin's signature is required for this instruction.
+    pub fn update_protocol_fee(ctx: Context<UpdateConfig>, protocol_fee_bps: u16) -> Result<()> {
+        require!(
+            u64::from(protocol_fee_bps) <= BPS_DENOMINATOR,
+            SwapError::InvalidProtocolFee
+        );
+        ctx.accounts.config.protocol_fee_bps = protocol_fee_bps;
 
-#[derive(Accounts)]
-// The parameters must have the exact name and order as specified in the underlying function
-// to avoid "seed constraint violation" errors.
-// Refer: https://www.anchor-lang.com/docs/references/account-constraints#instruction-attribute
-#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
-pub struct Initiate<'info> {
-    /// A PDA that maintains the on-chain state of the atomic swap throughout its lifecycle.
-    /// It also serves as the "vault" for this swap, by escrowing the SOL involved in this swap.
-    /// The choice of seeds ensures that any swap with equal `initiator` and
-    /// `secret_hash` cannot be created until an existing one completes.
-    /// This PDA will be deleted upon completion of the swap.
-    #[account(
-        init,
-        payer = initiator,
-        seeds = [b"swap_account", initiator.key().as_ref(), &secret_hash],
-        bump,
-        space = ANCHOR_DISCRIMINATOR + SwapAccount::INIT_SPACE,
-    )]
-    pub swap_account: Account<'info, SwapAccount>,
+        emit_cpi!(ProtocolFeeUpdated { protocol_fee_bps });
 
-    /// The initiator of the atomic swap. They must sign this transaction.
-    #[account(mut)]
-    pub initiator: Signer<'info>,
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Pauses or unpauses the initiation of new swaps.
+    /// Redeems and refunds of existing swaps are always allowed, so that funds are never locked.
+    /// As such, the admin's signature is required for this instruction.
+    pub fn set_initiate_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        ctx.accounts.config.initiate_paused = paused;
 
-#[derive(Accounts)]
-pub struct Redeem<'info> {
-    /// The PDA holding the state information of the atomic swap.
+        emit_cpi!(InitiatePauseUpdated { paused });
+
+        Ok(())
+    }
+
+    /// Permits or forbids `program_id` as the transfer hook program of the mints of token swaps.
+    /// Token swaps of mints with a transfer hook may only be initiated if it is permitted, as a
+    /// malicious hook could fail the transfers out of the vault, holding the swap hostage.
+    /// Existing swaps are unaffected, so that funds are never locked by forbidding a program.
+    /// As such, the admin's signature is required for this instruction.
+    pub fn set_transfer_hook_permitted(
+        ctx: Context<UpdateConfig>,
+        program_id: Pubkey,
+        permitted: bool,
+    ) -> Result<()> {
+        let programs = &mut ctx.accounts.config.transfer_hook_programs;
+        programs.retain(|program| *program != program_id);
+        if permitted {
+            require!(
+                programs.len() < usize::from(MAX_TRANSFER_HOOK_PROGRAMS),
+                SwapError::TooManyTransferHookPrograms
+            );
+            programs.push(program_id);
+        }
+
+        emit_cpi!(TransferHookPermissionUpdated {
+            program_id,
+            permitted,
+        });
+
+        Ok(())
+    }
+
+    /// Transfers `amount_lamports` of the collected protocol fees from the treasury to the admin.
+    /// The treasury's rent-exempt reserve cannot be withdrawn.
+    /// As such, the admin's signature is required for this instruction.
+    pub fn withdraw_protocol_fees(
+        ctx: Context<WithdrawProtocolFees>,
+        amount_lamports: u64,
+    ) -> Result<()> {
+        let treasury = ctx.accounts.treasury.to_account_info();
+        let reserve = Rent::get()?.minimum_balance(treasury.data_len());
+        let available = treasury.lamports().saturating_sub(reserve);
+        require!(
+            amount_lamports <= available,
+            SwapError::InsufficientTreasuryBalance
+        );
+
+        ctx.accounts.treasury.sub_lamports(amount_lamports)?;
+        ctx.accounts.admin.add_lamports(amount_lamports)?;
+
+        Ok(())
+    }
+
+    /// `amount_lamports` of the swap's remaining funds, less the protocol fee, are transferred
+    /// to the redeemer. The protocol fee is transferred to the treasury.
+    /// If submitted by a relayer, the swap's relayer fee is also carved out and paid to the relayer.
+    /// The redeemer may direct the funds to a destination of their choosing instead, in which case
+    /// their signature is required.
+    /// A swap may be redeemed in multiple tranches, and is closed once fully redeemed.
+    /// If `receipt` is provided, the revealed secret is also recorded in it, funded by the
+    /// receipt payer. Only one receipt may be created per swap.
+    /// This instruction does not require any signatures, other than the relayer's
+    /// and the receipt payer's if present.
+    pub fn redeem(ctx: Context<Redeem>, secret: [u8; 32], amount_lamports: u64) -> Result<()> {
+        let destination = match &ctx.accounts.destination {
+            Some(destination) => {
+                require!(
+                    ctx.accounts.redeemer.is_signer,
+                    SwapError::RedeemerSignatureRequired
+                );
+                destination
+            }
+            None => &ctx.accounts.redeemer,
+        };
+        // Anchor ensures the receipt payer is present whenever the receipt is
+        if let (Some(receipt), Some(receipt_payer)) =
+            (&mut ctx.accounts.receipt, &ctx.accounts.receipt_payer)
+        {
+            record_redeem_receipt(receipt, &ctx.accounts.swap_account, secret, receipt_payer)?;
+        }
+        let event = redeem_swap(
+            &ctx.accounts.swap_account,
+            RevealedSecrets::All(std::slice::from_ref(&secret)),
+            amount_lamports,
+            destination,
+            &ctx.accounts.initiator,
+            &ctx.accounts.config,
+            &ctx.accounts.treasury,
+            ctx.accounts.relayer.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Identical to `redeem`, but for swaps initiated through `initiate_multi_secret`, revealing
+    /// the secrets of all of its secret hashes, in the same order. The `Redeemed` event and the
+    /// receipt, if any, record the first of `secrets`, while all are emitted through
+    /// `MultiSecretRedeemed`.
+    /// This instruction does not require any signatures, other than the relayer's
+    /// and the receipt payer's if present.
+    pub fn redeem_multi_secret(
+        ctx: Context<Redeem>,
+        secrets: Vec<[u8; 32]>,
+        amount_lamports: u64,
+    ) -> Result<()> {
+        require!(
+            (2..=MAX_SECRET_HASHES as usize).contains(&secrets.len()),
+            SwapError::InvalidSecretCount
+        );
+        let destination = match &ctx.accounts.destination {
+            Some(destination) => {
+                require!(
+                    ctx.accounts.redeemer.is_signer,
+                    SwapError::RedeemerSignatureRequired
+                );
+                destination
+            }
+            None => &ctx.accounts.redeemer,
+        };
+        // Anchor ensures the receipt payer is present whenever the receipt is
+        if let (Some(receipt), Some(receipt_payer)) =
+            (&mut ctx.accounts.receipt, &ctx.accounts.receipt_payer)
+        {
+            record_redeem_receipt(
+                receipt,
+                &ctx.accounts.swap_account,
+                secrets[0],
+                receipt_payer,
+            )?;
+        }
+        let event = redeem_swap(
+            &ctx.accounts.swap_account,
+            RevealedSecrets::All(&secrets),
+            amount_lamports,
+            destination,
+            &ctx.accounts.initiator,
+            &ctx.accounts.config,
+            &ctx.accounts.treasury,
+            ctx.accounts.relayer.as_ref(),
+        )?;
+        let swap_account = event.swap_account;
+        emit_cpi!(event);
+        emit_cpi!(MultiSecretRedeemed {
+            swap_account,
+            secrets,
+        });
+
+        Ok(())
+    }
+
+    /// Identical to `redeem`, but for swaps initiated through `initiate_any_secret`, revealing
+    /// the secret of any one of its `secret_hashes`, all of which are provided, in the same order.
+    /// This instruction does not require any signatures, other than the relayer's
+    /// and the receipt payer's if present.
+    pub fn redeem_any_secret(
+        ctx: Context<Redeem>,
+        secret: [u8; 32],
+        secret_hashes: Vec<[u8; 32]>,
+        amount_lamports: u64,
+    ) -> Result<()> {
+        let destination = match &ctx.accounts.destination {
+            Some(destination) => {
+                require!(
+                    ctx.accounts.redeemer.is_signer,
+                    SwapError::RedeemerSignatureRequired
+                );
+                destination
+            }
+            None => &ctx.accounts.redeemer,
+        };
+        // Anchor ensures the receipt payer is present whenever the receipt is
+        if let (Some(receipt), Some(receipt_payer)) =
+            (&mut ctx.accounts.receipt, &ctx.accounts.receipt_payer)
+        {
+            record_redeem_receipt(receipt, &ctx.accounts.swap_account, secret, receipt_payer)?;
+        }
+        let event = redeem_swap(
+            &ctx.accounts.swap_account,
+            RevealedSecrets::AnyOf {
+                secret,
+                secret_hashes: &secret_hashes,
+            },
+            amount_lamports,
+            destination,
+            &ctx.accounts.initiator,
+            &ctx.accounts.config,
+            &ctx.accounts.treasury,
+            ctx.accounts.relayer.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Identical to `redeem` of the swap's entire remaining funds, but transferring them to
+    /// `destination` with the redeemer's consent, given as an ed25519 signature over
+    /// `REDEEM_CONSENT_PREFIX` followed by the swap account's address, the swap's instance and
+    /// the address of `destination`, as per `SwapAccount::redeem_consent_message`.
+    /// This allows redeemers to pre-sign their consent and go offline, for any relayer to submit.
+    /// The signature must be verified by an ed25519 program instruction immediately preceding
+    /// this one in the same transaction.
+    /// This instruction does not require any signatures, other than the relayer's
+    /// and the receipt payer's if present.
+    pub fn redeem_with_signature(
+        ctx: Context<RedeemWithSignature>,
+        secret: [u8; 32],
+    ) -> Result<()> {
+        let swap = ctx.accounts.swap_account.load()?;
+        let message = swap.redeem_consent_message(
+            &ctx.accounts.swap_account.key(),
+            ctx.accounts.destination.key,
+        );
+        verify_ed25519_signature(&ctx.accounts.instructions_sysvar, &swap.redeemer, &message)?;
+        let amount_lamports = swap.amount_lamports;
+        drop(swap);
+
+        // Anchor ensures the receipt payer is present whenever the receipt is
+        if let (Some(receipt), Some(receipt_payer)) =
+            (&mut ctx.accounts.receipt, &ctx.accounts.receipt_payer)
+        {
+            record_redeem_receipt(receipt, &ctx.accounts.swap_account, secret, receipt_payer)?;
+        }
+        let event = redeem_swap(
+            &ctx.accounts.swap_account,
+            RevealedSecrets::All(std::slice::from_ref(&secret)),
+            amount_lamports,
+            &ctx.accounts.destination,
+            &ctx.accounts.initiator,
+            &ctx.accounts.config,
+            &ctx.accounts.treasury,
+            ctx.accounts.relayer.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Closes a redeem receipt once it is no longer needed, returning its rent to the payer.
+    /// As such, the payer's signature is required for this instruction.
+    pub fn close_redeem_receipt(_ctx: Context<CloseRedeemReceipt>) -> Result<()> {
+        Ok(())
+    }
+
+    /// The swap's remaining funds are returned to the initiator, given that it has not been
+    /// fully redeemed and the expiry slot, along with the expiry timestamp if any, has been reached.
+    /// If submitted by a third party, the swap's refund tip is carved out and paid to them.
+    /// This instruction does not require any signatures, other than the refunder's if present.
+    pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        let swap = ctx.accounts.swap_account.load()?;
+        require!(
+            swap.has_expired(&Clock::get()?),
+            SwapError::RefundBeforeExpiry
+        );
+
+        let destination =
+            swap.refund_destination(&ctx.accounts.initiator, ctx.accounts.refund_to.as_ref())?;
+        drop(swap);
+        let event = refund_swap(
+            &ctx.accounts.swap_account,
+            destination,
+            ctx.accounts.refunder.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Refunds a swap abandoned past its expiry, once `ABANDONED_SWAP_GRACE_SLOTS` slots have
+    /// passed since, closing its swap account so as to reclaim its rent for the initiator.
+    /// Emits `Refunded`, and is in all other respects identical to `refund`.
+    /// This instruction does not require any signatures, other than the refunder's if present.
+    pub fn sweep_abandoned(ctx: Context<Refund>) -> Result<()> {
+        let swap = ctx.accounts.swap_account.load()?;
+        let clock = Clock::get()?;
+        require!(swap.has_expired(&clock), SwapError::RefundBeforeExpiry);
+        require!(
+            has_expired(
+                clock.slot,
+                swap.expiry_slot.saturating_add(ABANDONED_SWAP_GRACE_SLOTS)
+            ),
+            SwapError::SweepBeforeGracePeriod
+        );
+
+        let destination =
+            swap.refund_destination(&ctx.accounts.initiator, ctx.accounts.refund_to.as_ref())?;
+        drop(swap);
+        let event = refund_swap(
+            &ctx.accounts.swap_account,
+            destination,
+            ctx.accounts.refunder.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Refunds multiple expired swaps in a single transaction, allowing anyone to clean up
+    /// expired swaps. Each swap is subject to the same conditions as `refund`.
+    /// The swap accounts are to be provided as remaining accounts, each followed by its initiator
+    /// and its refund destination, i.e. the one specified at initiate if any, and its initiator
+    /// otherwise. As with `refund`, the rent of the swap account is returned to the initiator.
+    /// The refund tips of the swaps are paid to the refunder, if present.
+    /// This instruction does not require any signatures, other than the refunder's if present.
+    pub fn refund_expired_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundExpiredBatch<'info>>,
+    ) -> Result<()> {
+        let batch = ctx.remaining_accounts.chunks_exact(3);
+        require!(
+            batch.remainder().is_empty(),
+            SwapError::BatchAccountsMismatch
+        );
+        let clock = Clock::get()?;
+
+        for accounts in batch {
+            let (swap_account, initiator, destination) = (&accounts[0], &accounts[1], &accounts[2]);
+            let swap_account = AccountLoader::<SwapAccount>::try_from(swap_account)?;
+            {
+                let swap = swap_account.load()?;
+                require_keys_eq!(initiator.key(), swap.initiator, SwapError::InvalidInitiator);
+                require_keys_eq!(
+                    destination.key(),
+                    swap.refund_recipient(),
+                    SwapError::InvalidRefundDestination
+                );
+                require!(swap.has_expired(&clock), SwapError::RefundBeforeExpiry);
+            }
+
+            let event = refund_swap(&swap_account, destination, ctx.accounts.refunder.as_ref())?;
+            emit_cpi!(event);
+            swap_account.close(initiator.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Funds are returned to the initiator, with the redeemer's consent.
+    /// As such, the redeemer's signature is required for this instruction.
+    /// This allows for refunds before the expiry slot.
+    pub fn instant_refund(ctx: Context<InstantRefund>) -> Result<()> {
+        let destination = ctx
+            .accounts
+            .swap_account
+            .load()?
+            .refund_destination(&ctx.accounts.initiator, ctx.accounts.refund_to.as_ref())?;
+        let event = instant_refund_swap(
+            &ctx.accounts.swap_account,
+            destination,
+            Some(&ctx.accounts.redeemer),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// The swap's funds are split between its parties as they agree, with `redeemer_share_bps`
+    /// basis points going to the redeemer and the remainder to the initiator, rather than
+    /// all-or-nothing as with `redeem` and `instant_refund`. This allows for partial settlements.
+    /// The protocol fee is charged on the redeemer's share, as with `redeem`.
+    /// As such, the signatures of both the initiator and the redeemer are required for this
+    /// instruction, which may be made at any time.
+    pub fn cooperative_close(
+        ctx: Context<CooperativeClose>,
+        redeemer_share_bps: u16,
+    ) -> Result<()> {
+        require!(
+            u64::from(redeemer_share_bps) <= BPS_DENOMINATOR,
+            SwapError::InvalidCloseShare
+        );
+        let swap_account = &ctx.accounts.swap_account;
+        let mut swap = swap_account.load_mut()?;
+        let destination =
+            swap.refund_destination(&ctx.accounts.initiator, ctx.accounts.refund_to.as_ref())?;
+        return_acceptance_bond(swap_account, &mut swap, Some(&ctx.accounts.redeemer))?;
+
+        let swap_amount = swap.amount_lamports;
+        let redeemer_amount = (u128::from(swap_amount) * u128::from(redeemer_share_bps)
+            / u128::from(BPS_DENOMINATOR)) as u64;
+        let protocol_fee = ctx.accounts.config.protocol_fee(redeemer_amount);
+        swap_account.sub_lamports(swap_amount)?;
+        ctx.accounts.treasury.add_lamports(protocol_fee)?;
+        ctx.accounts
+            .redeemer
+            .add_lamports(redeemer_amount - protocol_fee)?;
+        destination.add_lamports(swap_amount - redeemer_amount)?;
+
+        emit_cpi!(CooperativelyClosed {
+            swap_account: swap_account.key(),
+            initiator: swap.initiator,
+            redeemer: swap.redeemer,
+            secret_hash: swap.secret_hash,
+            redeemer_amount_lamports: redeemer_amount,
+            initiator_amount_lamports: swap_amount - redeemer_amount,
+            protocol_fee_lamports: protocol_fee,
+        });
+
+        Ok(())
+    }
+
+    /// Extends the swap's expiry to `new_expiry_slot`, which must be later than both the current
+    /// one and the current slot, and within the bounds configured for the program.
+    /// As such, the signatures of both the initiator and the redeemer are required for this instruction.
+    pub fn extend_expiry(ctx: Context<ExtendExpiry>, new_expiry_slot: u64) -> Result<()> {
+        let mut swap = ctx.accounts.swap_account.load_mut()?;
+        require!(
+            new_expiry_slot > swap.expiry_slot,
+            SwapError::InvalidExpiryExtension
+        );
+        let expires_in_slots = new_expiry_slot
+            .checked_sub(Clock::get()?.slot)
+            .ok_or(SwapError::InvalidExpiryExtension)?;
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+        swap.expiry_slot = new_expiry_slot;
+
+        let event = ExpiryExtended {
+            swap_account: ctx.accounts.swap_account.key(),
+            initiator: swap.initiator,
+            redeemer: swap.redeemer,
+            secret_hash: swap.secret_hash,
+            expiry_slot: new_expiry_slot,
+        };
+        drop(swap);
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Upgrades a swap account created under a previous layout version, or before the layout
+    /// was versioned, to the current one, reallocating it if required, with any additional rent
+    /// paid by the payer. Swaps must be migrated before any other instruction can use them.
+    /// Migrations are deterministic, and as such this instruction does not require any
+    /// signatures other than the payer's.
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        let data = swap_account.try_borrow_data()?;
+        require!(
+            data.len() > ANCHOR_DISCRIMINATOR
+                && data[..ANCHOR_DISCRIMINATOR] == *SwapAccount::DISCRIMINATOR,
+            SwapError::InvalidSwapAccount
+        );
+        let (version, layout) = (data[ANCHOR_DISCRIMINATOR], &data[ANCHOR_DISCRIMINATOR..]);
+        let data_len = data.len();
+
+        // Each future layout change extends the upgrades below, setting its appended fields for
+        // swaps of every previous version
+        let mut swap = match version {
+            // Swaps initiated before the layout was versioned have no version byte, the first
+            // byte being that of their amount instead, and are told apart by their size
+            _ if layout.len() == UnversionedSwapAccount::INIT_SPACE => {
+                UnversionedSwapAccount::deserialize(&mut &layout[..])?
+                    .upgrade(swap_account.key, ctx.program_id)?
+            }
+            SWAP_ACCOUNT_VERSION => return err!(SwapError::SwapAlreadyMigrated),
+            // Version 1 shares its version byte with no zero-copy layout, but is told apart by
+            // its size all the same, being the only Borsh-encoded one
+            1 if layout.len() == SwapAccountV1::INIT_SPACE => {
+                SwapAccountV1::deserialize(&mut &layout[..])?.upgrade()
+            }
+            2 | 3 if layout.len() < SwapAccount::INIT_SPACE => {
+                let mut swap: SwapAccount = bytemuck::Zeroable::zeroed();
+                bytemuck::bytes_of_mut(&mut swap)[..layout.len()].copy_from_slice(layout);
+                // Version 3 appends the starting relayer fee, being the full fee for swaps
+                // initiated before relayer fees could be auctioned
+                if version < 3 {
+                    swap.relayer_fee_start_lamports = swap.relayer_fee_lamports;
+                }
+                // Version 4 appends the acceptance bond, left as zero for swaps initiated
+                // before acceptance could be required
+                swap
+            }
+            _ => return err!(SwapError::UnsupportedSwapVersion),
+        };
+        swap.version = SWAP_ACCOUNT_VERSION;
+        drop(data);
+
+        let rent = Rent::get()?;
+        let additional_rent = rent
+            .minimum_balance(SWAP_ACCOUNT_SPACE)
+            .saturating_sub(rent.minimum_balance(data_len));
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, additional_rent)?;
+        swap_account.realloc(SWAP_ACCOUNT_SPACE, true)?;
+
+        let mut data = swap_account.try_borrow_mut_data()?;
+        data[ANCHOR_DISCRIMINATOR..SWAP_ACCOUNT_SPACE].copy_from_slice(bytemuck::bytes_of(&swap));
+        Ok(())
+    }
+
+    /// Sets the return data to the version of this program and of the swap account layout,
+    /// for CPI callers and simulations to check their compatibility with this deployment.
+    /// This instruction does not require any signatures.
+    pub fn get_version(_ctx: Context<GetVersion>) -> Result<ProgramVersion> {
+        Ok(ProgramVersion {
+            version: PROGRAM_VERSION.to_string(),
+            swap_account_version: SWAP_ACCOUNT_VERSION,
+        })
+    }
+
+    /// Sets the return data to the decoded state of the swap, including whether it has
+    /// expired as of the current slot and unix timestamp, for CPI callers and simulations
+    /// to introspect swaps without knowledge of the swap account layout.
+    /// This instruction does not require any signatures, nor modify any account.
+    pub fn get_swap_state(ctx: Context<GetSwapState>) -> Result<SwapInfo> {
+        let swap = ctx.accounts.swap_account.load()?;
+        Ok(SwapInfo {
+            version: swap.version,
+            initiator: swap.initiator,
+            redeemer: swap.redeemer,
+            secret_hash: swap.secret_hash,
+            nonce: swap.nonce(),
+            hash_algorithm: swap.hash_algorithm()?,
+            amount_lamports: swap.amount_lamports,
+            expiry_slot: swap.expiry_slot,
+            expiry_timestamp: swap.expiry_timestamp(),
+            incentives: SwapIncentives {
+                relayer_fee_lamports: swap.relayer_fee_lamports,
+                refund_tip_lamports: swap.refund_tip_lamports,
+                relayer_fee_start_lamports: Some(swap.relayer_fee_start_lamports)
+                    .filter(|&start| start != swap.relayer_fee_lamports),
+            },
+            redeem_window: swap.redeem_window(),
+            refund_recipient: swap.refund_recipient(),
+            created_at_slot: swap.created_at_slot,
+            created_at_unix: swap.created_at_unix,
+            has_expired: swap.has_expired(&Clock::get()?),
+        })
+    }
+
+    /// Identical to `instant_refund`, but with the redeemer's consent given as an ed25519 signature
+    /// over `SwapAccount::signed_message` under `INSTANT_REFUND_CONSENT_PREFIX`, rather than
+    /// the redeemer co-signing. This allows redeemers to pre-sign their consent and go offline,
+    /// without the consent applying to any later swap initiated at the same address.
+    /// The signature must be verified by an ed25519 program instruction immediately preceding
+    /// this one in the same transaction.
+    /// This instruction does not require any signatures.
+    pub fn instant_refund_with_signature(ctx: Context<InstantRefundWithSignature>) -> Result<()> {
+        let swap = ctx.accounts.swap_account.load()?;
+        let message = swap.signed_message(
+            INSTANT_REFUND_CONSENT_PREFIX,
+            &ctx.accounts.swap_account.key(),
+        );
+        verify_ed25519_signature(&ctx.accounts.instructions_sysvar, &swap.redeemer, &message)?;
+
+        let destination =
+            swap.refund_destination(&ctx.accounts.initiator, ctx.accounts.refund_to.as_ref())?;
+        drop(swap);
+        let event = instant_refund_swap(
+            &ctx.accounts.swap_account,
+            destination,
+            ctx.accounts.redeemer.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Identical to `instant_refund`, but signed by a session key the redeemer has
+    /// authorized with the `SESSION_SCOPE_INSTANT_REFUND` scope.
+    pub fn instant_refund_with_session(ctx: Context<InstantRefundWithSession>) -> Result<()> {
+        ctx.accounts
+            .session
+            .authorize(SESSION_SCOPE_INSTANT_REFUND)?;
+
+        let destination = ctx
+            .accounts
+            .swap_account
+            .load()?
+            .refund_destination(&ctx.accounts.initiator, ctx.accounts.refund_to.as_ref())?;
+        let event = instant_refund_swap(
+            &ctx.accounts.swap_account,
+            destination,
+            ctx.accounts.redeemer.as_ref(),
+        )?;
+        emit_cpi!(event);
+
+        Ok(())
+    }
+
+    /// Authorizes a short-lived `session_key` to sign routine lifecycle instructions on behalf
+    /// of the authority, limited to the instructions permitted by `scope` (a bitmask of
+    /// `SESSION_SCOPE_*` values) and until `expires_in_slots` slots have passed.
+    /// As such, the authority's signature is required for this instruction.
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_key: Pubkey,
+        scope: u8,
+        expires_in_slots: u64,
+    ) -> Result<()> {
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        *ctx.accounts.session = SessionToken {
+            authority: ctx.accounts.authority.key(),
+            session_key,
+            scope,
+            expiry_slot,
+        };
+
+        emit_cpi!(SessionCreated {
+            authority: ctx.accounts.authority.key(),
+            session_key,
+            scope,
+            expiry_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Replaces an existing session with one for `new_session_key`, invalidating the old one.
+    /// As such, the authority's signature is required for this instruction.
+    pub fn rotate_session(
+        ctx: Context<RotateSession>,
+        new_session_key: Pubkey,
+        scope: u8,
+        expires_in_slots: u64,
+    ) -> Result<()> {
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        *ctx.accounts.new_session = SessionToken {
+            authority: ctx.accounts.authority.key(),
+            session_key: new_session_key,
+            scope,
+            expiry_slot,
+        };
+
+        emit_cpi!(SessionRevoked {
+            authority: ctx.accounts.authority.key(),
+            session_key: ctx.accounts.session.session_key,
+        });
+        emit_cpi!(SessionCreated {
+            authority: ctx.accounts.authority.key(),
+            session_key: new_session_key,
+            scope,
+            expiry_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Invalidates a session before its expiry.
+    /// As such, the authority's signature is required for this instruction.
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        emit_cpi!(SessionRevoked {
+            authority: ctx.accounts.authority.key(),
+            session_key: ctx.accounts.session.session_key,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of SPL tokens, supporting both the Token and Token-2022 programs.
+    /// Tokens are transferred from the initiator's token account to a token vault owned by the swap's PDA.
+    /// For mints charging transfer fees, the swap records both the gross `amount` sent and the
+    /// net amount received by the vault, the latter being what the redeemer will be sent.
+    /// As such, the initiator's signature is required for this instruction.
+    /// `amount` represents the quantity of tokens to be transferred through this atomic swap
+    /// in base units of the mint. `expires_in_slots` follows the same semantics as `initiate`.
+    pub fn initiate_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitiateToken<'info>>,
+        amount: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(amount > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+        ctx.accounts
+            .config
+            .validate_transfer_hook(&ctx.accounts.mint.to_account_info())?;
+
+        transfer_with_hook(
+            &ctx.accounts.token_program,
+            &ctx.accounts.initiator_token_account.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_vault.to_account_info(),
+            &ctx.accounts.initiator.to_account_info(),
+            ctx.remaining_accounts,
+            amount,
+            &[],
+        )?;
+        ctx.accounts.token_vault.reload()?;
+        let net_amount = ctx.accounts.token_vault.amount;
+
+        *ctx.accounts.swap_account = TokenSwapAccount {
+            amount,
+            net_amount,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+            mint: ctx.accounts.mint.key(),
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(TokenInitiated {
+            mint: ctx.accounts.mint.key(),
+            swap_amount: amount,
+            net_swap_amount: net_amount,
+            expires_in_slots,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Tokens are transferred to the redeemer's token account.
+    /// This instruction does not require any signatures.
+    pub fn redeem_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemToken<'info>>,
+        secret: [u8; 32],
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        release_token_vault(
+            swap_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.redeemer_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+        )?;
+
+        emit_cpi!(TokenRedeemed {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// Tokens are transferred to the redeemer's associated token account, which is created
+    /// if it does not exist yet, with its rent paid by `payer`, e.g. a relayer or the redeemer.
+    /// This allows redeems to wallets that have never held the mint, as the secret would
+    /// otherwise already be revealed by a redeem failing for the lack of a token account.
+    /// This instruction requires the payer's signature.
+    pub fn redeem_token_to_ata<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemTokenToAta<'info>>,
+        secret: [u8; 32],
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        release_token_vault(
+            swap_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.redeemer_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+        )?;
+
+        emit_cpi!(TokenRedeemed {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// Tokens are returned to the initiator's token account, given that no redeems
+    /// have occured and the expiry slot has been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundToken<'info>>,
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        release_token_vault(
+            swap_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.initiator_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+        )?;
+
+        emit_cpi!(TokenRefunded {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of wrapped SOL from the initiator's native SOL, wrapping
+    /// `amount_lamports` into a wSOL token vault owned by the swap's PDA. The resulting swap is a
+    /// token swap of the native mint like any other, so counterparties handling token accounts
+    /// may redeem it through `redeem_token`, while `redeem_wsol` and `refund_wsol` unwrap it.
+    /// `expires_in_slots` follows the same semantics as `initiate`.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_wsol(
+        ctx: Context<InitiateWsol>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.token_vault.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+        let sync_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::SyncNative {
+                account: ctx.accounts.token_vault.to_account_info(),
+            },
+        );
+        token::sync_native(sync_context)?;
+
+        *ctx.accounts.swap_account = TokenSwapAccount {
+            amount: amount_lamports,
+            net_amount: amount_lamports,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+            mint: native_mint::ID,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(TokenInitiated {
+            mint: native_mint::ID,
+            swap_amount: amount_lamports,
+            net_swap_amount: amount_lamports,
+            expires_in_slots,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// The wrapped SOL of a token swap of the native mint is unwrapped to the redeemer
+    /// as native SOL.
+    /// This instruction does not require any signatures.
+    pub fn redeem_wsol(ctx: Context<RedeemWsol>, secret: [u8; 32]) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        unwrap_token_vault(
+            swap_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.redeemer,
+            &ctx.accounts.token_program,
+        )?;
+
+        emit_cpi!(TokenRedeemed {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// The wrapped SOL of a token swap of the native mint is unwrapped back to the initiator
+    /// as native SOL, given that no redeems have occured and the expiry slot has been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_wsol(ctx: Context<RefundWsol>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        unwrap_token_vault(
+            swap_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+        )?;
+
+        emit_cpi!(TokenRefunded {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of Token-2022 tokens through confidential transfers, such that the
+    /// amount escrowed is only known to the counterparties while the hashlock and timelock remain
+    /// public, e.g. for OTC trades whose sizes must not leak.
+    /// The vault owned by the swap's PDA is configured for confidential transfers with an
+    /// ElGamal keypair and AES key chosen by the initiator and shared with the redeemer off-chain,
+    /// allowing both to decrypt the escrowed amount and prove transfers out of the vault, which
+    /// only the program may authorize. The initiator then confidentially transfers the swap amount
+    /// into the vault, which is applied to its available balance, after which the vault accepts no
+    /// further credits. The mint must automatically approve new confidential accounts.
+    /// The proofs required by Token-2022 are provided as context state accounts verified
+    /// beforehand: the validity of the vault's ElGamal public key, and the equality, ciphertext
+    /// validity and range proofs of the transfer. `vault_decryptable_zero_balance` and
+    /// `vault_decryptable_balance` are the AES ciphertexts of the vault's available balance,
+    /// being zero before and the swap amount after the transfer, while
+    /// `initiator_decryptable_balance` is that of the initiator's token account after the
+    /// transfer. Only SHA-256 secret hashes are supported.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_confidential(
+        ctx: Context<InitiateConfidential>,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        vault_decryptable_zero_balance: [u8; 36],
+        vault_decryptable_balance: [u8; 36],
+        initiator_decryptable_balance: [u8; 36],
+    ) -> Result<()> {
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let accounts = &ctx.accounts;
+        let token_program = accounts.token_program.key();
+        let (swap_account, vault, mint) = (
+            accounts.swap_account.key(),
+            accounts.token_vault.key(),
+            accounts.mint.key(),
+        );
+        let initiator = accounts.initiator.key();
+        let seeds: &[&[u8]] = &[
+            b"confidential_swap_account",
+            initiator.as_ref(),
+            redeemer.as_ref(),
+            &secret_hash,
+            &[ctx.bumps.swap_account],
+        ];
+        let signer_seeds = &[seeds];
+
+        let reallocate = token_2022_instruction::reallocate(
+            &token_program,
+            &vault,
+            &initiator,
+            &swap_account,
+            &[],
+            &[ExtensionType::ConfidentialTransferAccount],
+        )?;
+        invoke_signed(
+            &reallocate,
+            &[
+                accounts.token_vault.to_account_info(),
+                accounts.initiator.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.swap_account.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        // Credits are limited to the single transfer funding the swap
+        let configure = confidential_transfer::instruction::inner_configure_account(
+            &token_program,
+            &vault,
+            &mint,
+            decryptable_balance(vault_decryptable_zero_balance),
+            1,
+            &swap_account,
+            &[],
+            ProofLocation::ContextStateAccount(&accounts.pubkey_validity_proof.key()),
+        )?;
+        invoke_signed(
+            &configure,
+            &[
+                accounts.token_vault.to_account_info(),
+                accounts.mint.to_account_info(),
+                accounts.pubkey_validity_proof.to_account_info(),
+                accounts.swap_account.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        confidential_transfer_cpi(
+            &token_program,
+            &accounts.initiator_token_account.to_account_info(),
+            &accounts.mint.to_account_info(),
+            &accounts.token_vault.to_account_info(),
+            &accounts.initiator.to_account_info(),
+            [
+                &accounts.equality_proof,
+                &accounts.ciphertext_validity_proof,
+                &accounts.range_proof,
+            ],
+            initiator_decryptable_balance,
+            &[],
+        )?;
+
+        let apply = confidential_transfer::instruction::inner_apply_pending_balance(
+            &token_program,
+            &vault,
+            1,
+            decryptable_balance(vault_decryptable_balance),
+            &swap_account,
+            &[],
+        )?;
+        let disable_credits = [
+            confidential_transfer::instruction::disable_confidential_credits(
+                &token_program,
+                &vault,
+                &swap_account,
+                &[],
+            )?,
+            confidential_transfer::instruction::disable_non_confidential_credits(
+                &token_program,
+                &vault,
+                &swap_account,
+                &[],
+            )?,
+        ];
+        for instruction in [apply].iter().chain(&disable_credits) {
+            invoke_signed(
+                instruction,
+                &[
+                    accounts.token_vault.to_account_info(),
+                    accounts.swap_account.to_account_info(),
+                ],
+                signer_seeds,
+            )?;
+        }
+
+        *ctx.accounts.swap_account = ConfidentialSwapAccount {
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator,
+            redeemer,
+            secret_hash,
+            mint,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(ConfidentialInitiated {
+            mint,
+            expires_in_slots,
+            initiator,
+            redeemer,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// The escrowed tokens are confidentially transferred to the redeemer's token account.
+    /// The proofs of the transfer out of the vault are provided as with `initiate_confidential`,
+    /// along with a proof that the vault's resulting balance is zero, allowing it to be closed.
+    /// `vault_decryptable_zero_balance` is the AES ciphertext of the vault's available balance
+    /// after the transfer, being zero.
+    /// This instruction does not require any signatures.
+    pub fn redeem_confidential(
+        ctx: Context<RedeemConfidential>,
+        secret: [u8; 32],
+        vault_decryptable_zero_balance: [u8; 36],
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        release_confidential_vault(
+            swap_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.redeemer_token_account.to_account_info(),
+            &ctx.accounts.mint,
+            [
+                &ctx.accounts.equality_proof,
+                &ctx.accounts.ciphertext_validity_proof,
+                &ctx.accounts.range_proof,
+            ],
+            &ctx.accounts.zero_balance_proof,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+            vault_decryptable_zero_balance,
+        )?;
+
+        emit_cpi!(ConfidentialRedeemed {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// The escrowed tokens are confidentially returned to the initiator's token account, given
+    /// that no redeems have occured and the expiry slot has been reached. The proofs and
+    /// `vault_decryptable_zero_balance` follow the same semantics as `redeem_confidential`.
+    /// This instruction does not require any signatures.
+    pub fn refund_confidential(
+        ctx: Context<RefundConfidential>,
+        vault_decryptable_zero_balance: [u8; 36],
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        release_confidential_vault(
+            swap_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.initiator_token_account.to_account_info(),
+            &ctx.accounts.mint,
+            [
+                &ctx.accounts.equality_proof,
+                &ctx.accounts.ciphertext_validity_proof,
+                &ctx.accounts.range_proof,
+            ],
+            &ctx.accounts.zero_balance_proof,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+            vault_decryptable_zero_balance,
+        )?;
+
+        emit_cpi!(ConfidentialRefunded {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of a non-fungible token, i.e. a single token of a mint with a
+    /// supply of one and zero decimals that can no longer be minted, e.g. for swapping an NFT
+    /// for coins on another chain. The mint authority must either be revoked or, for Metaplex
+    /// NFTs, be held by their master edition. `metadata` must be the mint's metadata, being the
+    /// account its metadata pointer refers to for Token-2022 mints carrying one, or its Metaplex
+    /// metadata account otherwise, which is recorded so the redeemer can verify the collection
+    /// and attributes of the NFT before revealing the secret.
+    /// The token is transferred from the initiator's token account to a vault owned by the
+    /// swap's PDA. Only SHA-256 secret hashes are supported.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_nft(
+        ctx: Context<InitiateNft>,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+        let metadata = validate_nft(&ctx.accounts.mint, &ctx.accounts.metadata)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.initiator_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.nft_vault.to_account_info(),
+                authority: ctx.accounts.initiator.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(transfer_context, 1, 0)?;
+
+        *ctx.accounts.swap_account = NftSwapAccount {
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+            mint: ctx.accounts.mint.key(),
+            metadata,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(NftInitiated {
+            mint: ctx.accounts.mint.key(),
+            metadata,
+            expires_in_slots,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// The NFT is transferred to the redeemer's token account.
+    /// This instruction does not require any signatures.
+    pub fn redeem_nft(ctx: Context<RedeemNft>, secret: [u8; 32]) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        release_nft_vault(
+            swap_account,
+            &ctx.accounts.nft_vault,
+            &ctx.accounts.redeemer_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+        )?;
+
+        emit_cpi!(NftRedeemed {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// The NFT is returned to the initiator's token account, given that no redeems
+    /// have occured and the expiry slot has been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_nft(ctx: Context<RefundNft>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        release_nft_vault(
+            swap_account,
+            &ctx.accounts.nft_vault,
+            &ctx.accounts.initiator_token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+        )?;
+
+        emit_cpi!(NftRefunded {
+            mint: swap_account.mint,
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of a compressed NFT, transferring its leaf in `merkle_tree` from
+    /// the initiator to the swap's PDA through Bubblegum, e.g. for swapping cNFTs of a collection
+    /// for coins on another chain. `leaf` describes the leaf as it is in the tree, and `root` is
+    /// the root the proof of the leaf, provided as remaining accounts, is verified against, as
+    /// fetched from an indexer. The leaf's delegate must be provided as `leaf_delegate`, being
+    /// the initiator if none is set. The asset id of the cNFT is recorded and emitted for the
+    /// redeemer to verify before revealing the secret. Only SHA-256 secret hashes are supported.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_cnft<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitiateCnft<'info>>,
+        leaf: CompressedLeaf,
+        root: [u8; 32],
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let accounts = &ctx.accounts;
+        transfer_cnft(
+            &accounts.bubblegum_program,
+            [
+                accounts.tree_authority.to_account_info(),
+                accounts.initiator.to_account_info(),
+                accounts.leaf_delegate.to_account_info(),
+                accounts.swap_account.to_account_info(),
+                accounts.merkle_tree.to_account_info(),
+                accounts.log_wrapper.to_account_info(),
+                accounts.compression_program.to_account_info(),
+                accounts.system_program.to_account_info(),
+            ],
+            ctx.remaining_accounts,
+            &leaf,
+            root,
+            &[],
+        )?;
+
+        let merkle_tree = ctx.accounts.merkle_tree.key();
+        let (asset_id, _) = pda::cnft_asset_id(&merkle_tree, leaf.nonce);
+        *ctx.accounts.swap_account = CnftSwapAccount {
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+            asset_id,
+            merkle_tree,
+            leaf,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(CnftInitiated {
+            asset_id,
+            merkle_tree,
+            expires_in_slots,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// The cNFT is transferred to the redeemer, with `root` and the proof of its leaf
+    /// following the same semantics as `initiate_cnft`.
+    /// This instruction does not require any signatures.
+    pub fn redeem_cnft<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemCnft<'info>>,
+        secret: [u8; 32],
+        root: [u8; 32],
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        release_cnft(
+            swap_account,
+            &ctx.accounts.redeemer,
+            &ctx.accounts.tree_authority,
+            &ctx.accounts.merkle_tree,
+            &ctx.accounts.log_wrapper,
+            &ctx.accounts.compression_program,
+            &ctx.accounts.bubblegum_program,
+            &ctx.accounts.system_program,
+            ctx.remaining_accounts,
+            root,
+        )?;
+
+        emit_cpi!(CnftRedeemed {
+            asset_id: swap_account.asset_id,
+            initiator: swap_account.initiator,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// The cNFT is returned to the initiator, given that no redeems have occured and the
+    /// expiry slot has been reached. `root` and the proof of its leaf follow the same
+    /// semantics as `initiate_cnft`.
+    /// This instruction does not require any signatures.
+    pub fn refund_cnft<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundCnft<'info>>,
+        root: [u8; 32],
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        release_cnft(
+            swap_account,
+            &ctx.accounts.initiator,
+            &ctx.accounts.tree_authority,
+            &ctx.accounts.merkle_tree,
+            &ctx.accounts.log_wrapper,
+            &ctx.accounts.compression_program,
+            &ctx.accounts.bubblegum_program,
+            &ctx.accounts.system_program,
+            ctx.remaining_accounts,
+            root,
+        )?;
+
+        emit_cpi!(CnftRefunded {
+            asset_id: swap_account.asset_id,
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of native SOL bundled with one or more SPL tokens under a single
+    /// secret hash, all of which are released together, e.g. for OTC deals of a token along
+    /// with gas money. `amount_lamports` is escrowed in the bundle swap account itself, and may
+    /// be zero as long as tokens are bundled.
+    /// Each of `token_amounts` is transferred from the initiator into a vault, provided as
+    /// remaining accounts as a triple of the mint, the initiator's token account and the vault,
+    /// in the same order as `token_amounts`. Vaults are empty token accounts owned by the bundle
+    /// swap account, without a delegate or close authority, e.g. its associated token accounts
+    /// created beforehand. All mints must belong to `token_program`, and transfer fees are
+    /// accounted for as in `initiate_token`. Only SHA-256 secret hashes are supported.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, InitiateBundle<'info>>,
+        amount_lamports: u64,
+        token_amounts: Vec<u64>,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            amount_lamports > 0 || !token_amounts.is_empty(),
+            SwapError::ZeroAmount
+        );
+        require!(
+            token_amounts.len() <= MAX_BUNDLE_TOKENS as usize,
+            SwapError::TooManyBundledTokens
+        );
+        require!(
+            token_amounts.len() * 3 == ctx.remaining_accounts.len(),
+            SwapError::BatchAccountsMismatch
+        );
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        if amount_lamports > 0 {
+            let transfer_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.initiator.to_account_info(),
+                    to: ctx.accounts.swap_account.to_account_info(),
+                },
+            );
+            system_program::transfer(transfer_context, amount_lamports)?;
+        }
+
+        let swap_account = ctx.accounts.swap_account.key();
+        let mut tokens: Vec<BundledToken> = Vec::with_capacity(token_amounts.len());
+        for (&amount, accounts) in token_amounts
+            .iter()
+            .zip(ctx.remaining_accounts.chunks_exact(3))
+        {
+            require!(amount > 0, SwapError::ZeroAmount);
+            let mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+            let mut vault = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+            require_keys_eq!(
+                *accounts[0].owner,
+                ctx.accounts.token_program.key(),
+                SwapError::InvalidMint
+            );
+            require!(
+                tokens.iter().all(|token| token.mint != mint.key()),
+                SwapError::DuplicateBundledMint
+            );
+            require_keys_eq!(vault.mint, mint.key(), SwapError::InvalidBundleVault);
+            require_keys_eq!(vault.owner, swap_account, SwapError::InvalidBundleVault);
+            require!(
+                vault.amount == 0 && vault.delegate.is_none() && vault.close_authority.is_none(),
+                SwapError::InvalidBundleVault
+            );
+
+            let transfer_context = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: accounts[1].clone(),
+                    mint: mint.to_account_info(),
+                    to: vault.to_account_info(),
+                    authority: ctx.accounts.initiator.to_account_info(),
+                },
+            );
+            token_interface::transfer_checked(transfer_context, amount, mint.decimals)?;
+            vault.reload()?;
+
+            tokens.push(BundledToken {
+                mint: mint.key(),
+                vault: vault.key(),
+                amount,
+                net_amount: vault.amount,
+            });
+        }
+
+        *ctx.accounts.swap_account = BundleSwapAccount {
+            amount_lamports,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+            tokens: tokens.clone(),
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(BundleInitiated {
+            swap_account,
+            amount_lamports,
+            tokens,
+            expires_in_slots,
+            initiator: ctx.accounts.initiator.key(),
+            redeemer,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// The bundle's native SOL and tokens are transferred to the redeemer, the latter to the
+    /// redeemer's token accounts provided as remaining accounts as a triple of the mint,
+    /// the vault and the token account, in the order the swap records its tokens.
+    /// This instruction does not require any signatures.
+    pub fn redeem_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemBundle<'info>>,
+        secret: [u8; 32],
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        release_bundle(
+            swap_account,
+            ctx.remaining_accounts,
+            &ctx.accounts.redeemer,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+        )?;
+
+        emit_cpi!(BundleRedeemed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// The bundle's native SOL and tokens are returned to the initiator, given that the expiry
+    /// slot has been reached, the latter to the initiator's token accounts provided as
+    /// remaining accounts as in `redeem_bundle`.
+    /// This instruction does not require any signatures.
+    pub fn refund_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundBundle<'info>>,
+    ) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        release_bundle(
+            swap_account,
+            ctx.remaining_accounts,
+            &ctx.accounts.initiator,
+            &ctx.accounts.initiator,
+            &ctx.accounts.token_program,
+        )?;
+
+        emit_cpi!(BundleRefunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of native SOL released to the redeemer linearly over
+    /// `stream_slots` slots once the secret is revealed through `reveal_streamed`, rather than
+    /// instantly. Once revealed, the swap can no longer be refunded, and the redeemer claims the
+    /// unlocked funds in increments through `claim_streamed`. This gives the initiator a dispute
+    /// window for payment-channel-like flows: until the funds are fully released, the initiator
+    /// may halt the stream through `dispute_streamed`, reclaiming the funds yet to unlock.
+    /// Redeemers must therefore only accept streamed swaps whose counterpart leg they are willing
+    /// to see released no faster than this one. `amount_lamports` is escrowed in the streamed swap
+    /// account itself.
+    /// Only SHA-256 secret hashes are supported.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_streamed(
+        ctx: Context<InitiateStreamed>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        stream_slots: u64,
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require!(stream_slots > 0, SwapError::ZeroStreamDuration);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+
+        let initiator = ctx.accounts.initiator.key();
+        *ctx.accounts.swap_account = StreamedSwapAccount {
+            amount_lamports,
+            claimed_lamports: 0,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            stream_slots,
+            revealed_at_slot: None,
+            initiator,
+            redeemer,
+            secret_hash,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(StreamedInitiated {
+            swap_account: ctx.accounts.swap_account.key(),
+            amount_lamports,
+            expires_in_slots,
+            stream_slots,
+            initiator,
+            redeemer,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Starts the release of the streamed swap's funds to the redeemer, given `secret`
+    /// corresponds to its secret hash. The funds unlock linearly from the current slot
+    /// over the swap's stream duration, to be claimed through `claim_streamed`.
+    /// This instruction does not require any signatures.
+    pub fn reveal_streamed(ctx: Context<RevealStreamed>, secret: [u8; 32]) -> Result<()> {
+        let swap_account = &mut ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+        require!(
+            swap_account.revealed_at_slot.is_none(),
+            SwapError::StreamAlreadyRevealed
+        );
+        let slot = Clock::get()?.slot;
+        swap_account.revealed_at_slot = Some(slot);
+
+        emit_cpi!(StreamedRevealed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            secret,
+            stream_ends_at_slot: slot.saturating_add(swap_account.stream_slots),
+        });
+
+        Ok(())
+    }
+
+    /// Transfers the funds of the streamed swap unlocked so far, less those already claimed,
+    /// to the redeemer. The swap account is closed once its funds are claimed in full,
+    /// transferring its rent to the initiator.
+    /// This instruction does not require any signatures.
+    pub fn claim_streamed(ctx: Context<ClaimStreamed>) -> Result<()> {
+        let swap_account = &mut ctx.accounts.swap_account;
+        let unlocked = swap_account.unlocked_lamports(Clock::get()?.slot)?;
+        let claimable = unlocked - swap_account.claimed_lamports;
+        require!(claimable > 0, SwapError::NothingToClaim);
+
+        swap_account.sub_lamports(claimable)?;
+        ctx.accounts.redeemer.add_lamports(claimable)?;
+        swap_account.claimed_lamports += claimable;
+
+        let remaining_lamports = swap_account.amount_lamports - swap_account.claimed_lamports;
+        emit_cpi!(StreamedClaimed {
+            swap_account: swap_account.key(),
+            redeemer: swap_account.redeemer,
+            amount_lamports: claimable,
+            remaining_lamports,
+        });
+
+        if remaining_lamports == 0 {
+            swap_account.close(ctx.accounts.initiator.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    /// Halts the release of the streamed swap's funds within its dispute window, i.e. after the
+    /// secret is revealed and before the funds are fully unlocked. The funds unlocked so far, less
+    /// those already claimed, are transferred to the redeemer, with the funds yet to unlock and
+    /// the rent of the swap account returned to the initiator.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn dispute_streamed(ctx: Context<DisputeStreamed>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        let unlocked = swap_account.unlocked_lamports(Clock::get()?.slot)?;
+        require!(
+            unlocked < swap_account.amount_lamports,
+            SwapError::StreamFullyUnlocked
+        );
+        let claimable = unlocked - swap_account.claimed_lamports;
+        swap_account.sub_lamports(claimable)?;
+        ctx.accounts.redeemer.add_lamports(claimable)?;
+
+        emit_cpi!(StreamedDisputed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            redeemer_amount_lamports: claimable,
+            initiator_amount_lamports: swap_account.amount_lamports - unlocked,
+        });
+
+        Ok(())
+    }
+
+    /// The streamed swap's funds are returned to the initiator, given that its secret has not
+    /// been revealed and the expiry slot has been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_streamed(ctx: Context<RefundStreamed>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            swap_account.revealed_at_slot.is_none(),
+            SwapError::StreamAlreadyRevealed
+        );
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(StreamedRefunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates a series of `swap_count` recurring swaps of `amount_lamports` each, committed to
+    /// a hash chain so that no new setup is required between swaps. `chain_tip` is H^n(s), the
+    /// SHA-256 hash applied `swap_count` times to a secret `s` known to the redeemer.
+    /// Each swap of the series is redeemed by revealing the preimage of the previous swap's hash,
+    /// i.e. H^(n-1)(s), H^(n-2)(s), …, and finally `s` itself. The amount of every swap is
+    /// escrowed in the swap account upfront.
+    /// `expires_in_slots` represents the number of slots after which the remaining swaps may be
+    /// refunded, counted from initiation and again from every redeem.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_hash_chain(
+        ctx: Context<InitiateHashChain>,
+        amount_lamports: u64,
+        swap_count: u32,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        chain_tip: [u8; 32],
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(swap_count > 0, SwapError::EmptyHashChain);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+        let total_lamports = amount_lamports
+            .checked_mul(swap_count.into())
+            .ok_or(error!(SwapError::HashChainAmountTooLarge))?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, total_lamports)?;
+
+        let initiator = ctx.accounts.initiator.key();
+        *ctx.accounts.swap_account = HashChainSwapAccount {
+            amount_lamports,
+            remaining_swaps: swap_count,
+            expires_in_slots,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator,
+            redeemer,
+            chain_tip,
+            chain_hash: chain_tip,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(HashChainInitiated {
+            swap_account: ctx.accounts.swap_account.key(),
+            amount_lamports,
+            swap_count,
+            expires_in_slots,
+            initiator,
+            redeemer,
+            chain_tip,
+        });
+
+        Ok(())
+    }
+
+    /// Redeems the next swap of the series, transferring its amount to the redeemer, given
+    /// `secret` is the preimage of the hash the series is currently at. The revealed `secret`
+    /// then becomes the hash the following swap is committed to, and the expiry is renewed.
+    /// The swap account is closed once every swap of the series has been redeemed,
+    /// transferring its rent to the initiator.
+    /// This instruction does not require any signatures.
+    pub fn redeem_hash_chain(ctx: Context<RedeemHashChain>, secret: [u8; 32]) -> Result<()> {
+        let swap_account = &mut ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.chain_hash,
+            SwapError::InvalidSecret
+        );
+
+        let amount_lamports = swap_account.amount_lamports;
+        swap_account.sub_lamports(amount_lamports)?;
+        ctx.accounts.redeemer.add_lamports(amount_lamports)?;
+        swap_account.chain_hash = secret;
+        swap_account.remaining_swaps -= 1;
+        swap_account.expiry_slot =
+            expiry_slot_after(Clock::get()?.slot, swap_account.expires_in_slots)?;
+
+        emit_cpi!(HashChainRedeemed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            secret,
+            remaining_swaps: swap_account.remaining_swaps,
+        });
+
+        if swap_account.remaining_swaps == 0 {
+            swap_account.close(ctx.accounts.initiator.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    /// The amounts of the remaining swaps of the series are returned to the initiator, given
+    /// that the expiry slot has been reached without the next swap being redeemed.
+    /// This instruction does not require any signatures.
+    pub fn refund_hash_chain(ctx: Context<RefundHashChain>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(HashChainRefunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            chain_hash: swap_account.chain_hash,
+            remaining_swaps: swap_account.remaining_swaps,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates a point time-locked contract (PTLC) swap of native SOL, locked to the ed25519
+    /// public `point` rather than a secret hash. Its secret scalar takes the place of the secret:
+    /// the swap is claimed through `redeem_ptlc` with a signature under `point` over
+    /// `PtlcSwapAccount::claim_message`. Having been handed an adaptor
+    /// signature for that message beforehand, the initiator learns the secret scalar from the
+    /// completed signature published by the claim, for use on the counterpart chain.
+    /// As no hash is shared across chains, the legs of the swap cannot be correlated through it.
+    /// `amount_lamports` is escrowed in the PTLC swap account itself.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_ptlc(
+        ctx: Context<InitiatePtlc>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        point: Pubkey,
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+
+        let initiator = ctx.accounts.initiator.key();
+        let slot = Clock::get()?.slot;
+        *ctx.accounts.swap_account = PtlcSwapAccount {
+            amount_lamports,
+            expiry_slot: expiry_slot_after(slot, expires_in_slots)?,
+            initiator,
+            redeemer,
+            point,
+            bump: ctx.bumps.swap_account,
+            created_at_slot: slot,
+        };
+
+        emit_cpi!(PtlcInitiated {
+            swap_account: ctx.accounts.swap_account.key(),
+            amount_lamports,
+            expires_in_slots,
+            initiator,
+            redeemer,
+            point,
+        });
+
+        Ok(())
+    }
+
+    /// The PTLC swap's funds are transferred to the redeemer, given a signature under its point
+    /// over `PtlcSwapAccount::claim_message`. The signature must be
+    /// verified by an ed25519 program instruction immediately preceding this one in the same
+    /// transaction, and is published in `PtlcRedeemed`.
+    /// This instruction does not require any signatures.
+    pub fn redeem_ptlc(ctx: Context<RedeemPtlc>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        let message = swap_account.claim_message(&swap_account.key());
+        let signature = verify_ed25519_signature(
+            &ctx.accounts.instructions_sysvar,
+            &swap_account.point,
+            &message,
+        )
+        .map_err(|_| error!(SwapError::InvalidPtlcSignature))?;
+
+        swap_account.sub_lamports(swap_account.amount_lamports)?;
+        ctx.accounts
+            .redeemer
+            .add_lamports(swap_account.amount_lamports)?;
+
+        emit_cpi!(PtlcRedeemed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            point: swap_account.point,
+            signature,
+        });
+
+        Ok(())
+    }
+
+    /// The PTLC swap's funds are returned to the initiator, given that the expiry slot has
+    /// been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_ptlc(ctx: Context<RefundPtlc>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(PtlcRefunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            point: swap_account.point,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of native SOL claimed with a secp256k1 signature rather than a
+    /// secret, so that Bitcoin and EVM-native signers can claim it without managing a separate
+    /// secret. The committed key is identified by `eth_address`, the Ethereum address of its
+    /// public key, i.e. the last 20 bytes of the keccak-256 hash of the uncompressed public key.
+    /// The swap is claimed through `redeem_secp256k1` with a signature by that key over
+    /// `Secp256k1SwapAccount::claim_message`.
+    /// `amount_lamports` is escrowed in the secp256k1 swap account itself.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_secp256k1(
+        ctx: Context<InitiateSecp256k1>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        eth_address: [u8; 20],
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+
+        let initiator = ctx.accounts.initiator.key();
+        let slot = Clock::get()?.slot;
+        *ctx.accounts.swap_account = Secp256k1SwapAccount {
+            amount_lamports,
+            expiry_slot: expiry_slot_after(slot, expires_in_slots)?,
+            initiator,
+            redeemer,
+            eth_address,
+            bump: ctx.bumps.swap_account,
+            created_at_slot: slot,
+        };
+
+        emit_cpi!(Secp256k1Initiated {
+            swap_account: ctx.accounts.swap_account.key(),
+            amount_lamports,
+            expires_in_slots,
+            initiator,
+            redeemer,
+            eth_address,
+        });
+
+        Ok(())
+    }
+
+    /// The secp256k1 swap's funds are transferred to the redeemer, given a signature by its
+    /// committed key over `Secp256k1SwapAccount::claim_message`.
+    /// The signature must be verified by a secp256k1 program instruction immediately preceding
+    /// this one in the same transaction.
+    /// This instruction does not require any signatures.
+    pub fn redeem_secp256k1(ctx: Context<RedeemSecp256k1>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        let message = swap_account.claim_message(&swap_account.key());
+        verify_secp256k1_signature(
+            &ctx.accounts.instructions_sysvar,
+            &swap_account.eth_address,
+            &message,
+        )?;
+
+        swap_account.sub_lamports(swap_account.amount_lamports)?;
+        ctx.accounts
+            .redeemer
+            .add_lamports(swap_account.amount_lamports)?;
+
+        emit_cpi!(Secp256k1Redeemed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            eth_address: swap_account.eth_address,
+        });
+
+        Ok(())
+    }
+
+    /// The secp256k1 swap's funds are returned to the initiator, given that the expiry slot has
+    /// been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_secp256k1(ctx: Context<RefundSecp256k1>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(Secp256k1Refunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            eth_address: swap_account.eth_address,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of native SOL redeemed with a zero-knowledge proof of knowledge of
+    /// the preimage of `secret_hash`, rather than the preimage itself. The secret is thus never
+    /// published on Solana, with the counterpart chain learning it out-of-band.
+    /// `verifying_key` is the Groth16 verifying key over BN254 of the circuit proving knowledge
+    /// of the preimage, whose public inputs are the upper and lower 16 bytes of `secret_hash`.
+    /// It must be checked by the redeemer against that circuit before locking funds on the
+    /// counterpart chain, as any key other than the circuit's may accept proofs without the secret.
+    /// `amount_lamports` is escrowed in the zero-knowledge swap account itself.
+    /// As such, the initiator's signature is required for this instruction.
+    pub fn initiate_zk(
+        ctx: Context<InitiateZk>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        verifying_key: Groth16VerifyingKey,
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+
+        let initiator = ctx.accounts.initiator.key();
+        *ctx.accounts.swap_account = ZkSwapAccount {
+            amount_lamports,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator,
+            redeemer,
+            secret_hash,
+            verifying_key,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(ZkInitiated {
+            swap_account: ctx.accounts.swap_account.key(),
+            amount_lamports,
+            expires_in_slots,
+            initiator,
+            redeemer,
+            secret_hash,
+            verifying_key,
+        });
+
+        Ok(())
+    }
+
+    /// The zero-knowledge swap's funds are transferred to the redeemer, given a Groth16 `proof`
+    /// of knowledge of the preimage of its secret hash under its verifying key.
+    /// The pairing check makes this instruction expensive, so its transaction should request
+    /// additional compute units.
+    /// This instruction does not require any signatures.
+    pub fn redeem_zk(ctx: Context<RedeemZk>, proof: Groth16Proof) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            swap_account
+                .verifying_key
+                .verify(&proof, &swap_account.secret_hash),
+            SwapError::InvalidPreimageProof
+        );
+
+        swap_account.sub_lamports(swap_account.amount_lamports)?;
+        ctx.accounts
+            .redeemer
+            .add_lamports(swap_account.amount_lamports)?;
+
+        emit_cpi!(ZkRedeemed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// The zero-knowledge swap's funds are returned to the initiator, given that the expiry slot
+    /// has been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_zk(ctx: Context<RefundZk>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(ZkRefunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Posts an order offering `amount_lamports` of native SOL in exchange for
+    /// `counterpart_amount` of `counterpart_asset` on `counterpart_chain`, to be filled by any
+    /// taker through `fill_order`. Each counterpart identifier is free-form, zero-padded UTF-8,
+    /// e.g. `bitcoin` and `BTC`. The swap filling the order expires in between
+    /// `min_expires_in_slots` and `max_expires_in_slots`, as chosen by the taker, while the order
+    /// itself may be filled for `fillable_for_slots`.
+    /// The order may be filled partially, by fills of at least `min_fill_lamports` each, except
+    /// for a final fill of its remainder.
+    /// `amount_lamports` and the rent of the swap accounts of as many fills as the order allows
+    /// are escrowed in the order account, such that it may be filled without the maker's
+    /// involvement. The rent of any swap accounts left unfilled is returned once the order is
+    /// fully filled or cancelled.
+    /// As such, the maker's signature is required for this instruction.
+    #[allow(clippy::too_many_arguments)]
+    pub fn post_order(
+        ctx: Context<PostOrder>,
+        order_id: u64,
+        amount_lamports: u64,
+        counterpart_chain: [u8; 32],
+        counterpart_asset: [u8; 32],
+        counterpart_amount: u64,
+        min_expires_in_slots: u64,
+        max_expires_in_slots: u64,
+        fillable_for_slots: u64,
+        min_fill_lamports: u64,
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(fillable_for_slots > 0, SwapError::ZeroExpiry);
+        require!(
+            min_fill_lamports > 0 && min_fill_lamports <= amount_lamports,
+            SwapError::InvalidMinFill
+        );
+        require!(
+            min_expires_in_slots <= max_expires_in_slots,
+            SwapError::InvalidOrderExpiry
+        );
+        let config = &ctx.accounts.config;
+        config.validate_expiry(min_expires_in_slots)?;
+        config.validate_expiry(max_expires_in_slots)?;
+
+        let max_fills = amount_lamports.div_ceil(min_fill_lamports);
+        let swaps_rent = Rent::get()?
+            .minimum_balance(SWAP_ACCOUNT_SPACE)
+            .checked_mul(max_fills)
+            .ok_or(error!(SwapError::InvalidMinFill))?;
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.maker.to_account_info(),
+                to: ctx.accounts.order.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports + swaps_rent)?;
+
+        let maker = ctx.accounts.maker.key();
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, fillable_for_slots)?;
+        *ctx.accounts.order = Order {
+            maker,
+            order_id,
+            amount_lamports,
+            remaining_lamports: amount_lamports,
+            min_fill_lamports,
+            counterpart_chain,
+            counterpart_asset,
+            counterpart_amount,
+            min_expires_in_slots,
+            max_expires_in_slots,
+            expiry_slot,
+            bump: ctx.bumps.order,
+        };
+
+        emit_cpi!(OrderPosted {
+            order: ctx.accounts.order.key(),
+            maker,
+            order_id,
+            amount_lamports,
+            counterpart_chain,
+            counterpart_asset,
+            counterpart_amount,
+            min_expires_in_slots,
+            max_expires_in_slots,
+            expiry_slot,
+            min_fill_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Fills `amount_lamports` of the order, atomically initiating a swap of as much of its
+    /// escrowed native SOL from its maker to the taker, locked to `secret_hash` and expiring in
+    /// `expires_in_slots`, which must be within the order's bounds. `amount_lamports` must be at
+    /// least the order's minimum fill, unless filling its remainder.
+    /// The taker pays the rent of the swap account, which is reimbursed from the order's escrow.
+    /// Once fully filled, the order is closed, returning its remaining escrow to the maker.
+    /// In all other respects, the swap is identical to one initiated through `initiate` with
+    /// the defaults for its optional terms.
+    /// As such, the taker's signature is required for this instruction.
+    pub fn fill_order(
+        ctx: Context<FillOrder>,
+        expires_in_slots: u64,
+        secret_hash: [u8; 32],
+        amount_lamports: u64,
+    ) -> Result<()> {
+        let order = &mut ctx.accounts.order;
+        let clock = Clock::get()?;
+        require!(
+            !has_expired(clock.slot, order.expiry_slot),
+            SwapError::OrderExpired
+        );
+        require!(
+            (order.min_expires_in_slots..=order.max_expires_in_slots).contains(&expires_in_slots),
+            SwapError::InvalidOrderExpiry
+        );
+        require!(
+            amount_lamports <= order.remaining_lamports
+                && (amount_lamports >= order.min_fill_lamports
+                    || amount_lamports == order.remaining_lamports),
+            SwapError::InvalidFillAmount
+        );
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+        let (maker, taker) = (order.maker, ctx.accounts.taker.key());
+        require_keys_neq!(taker, maker, SwapError::SelfSwap);
+
+        let swap_rent = Rent::get()?.minimum_balance(SWAP_ACCOUNT_SPACE);
+        order.sub_lamports(amount_lamports + swap_rent)?;
+        ctx.accounts.swap_account.add_lamports(amount_lamports)?;
+        ctx.accounts.taker.add_lamports(swap_rent)?;
+        order.remaining_lamports -= amount_lamports;
+
+        let expiry_slot = expiry_slot_after(clock.slot, expires_in_slots)?;
+        *ctx.accounts.swap_account.load_init()? = SwapAccount {
+            version: SWAP_ACCOUNT_VERSION,
+            bump: ctx.bumps.swap_account,
+            hash_algorithm: HashAlgorithm::Sha256 as u8,
+            nonce: [0; 4],
+            seeds_version: SWAP_SEEDS_VERSION,
+            amount_lamports,
+            expiry_slot,
+            expiry_timestamp: 0,
+            relayer_fee_lamports: 0,
+            refund_tip_lamports: 0,
+            redeem_after_slot: 0,
+            redeem_deadline_slot: 0,
+            created_at_slot: clock.slot,
+            created_at_unix: clock.unix_timestamp,
+            initiator: maker,
+            redeemer: taker,
+            secret_hash,
+            refund_to: Pubkey::default(),
+            relayer_fee_start_lamports: 0,
+            acceptance_bond_lamports: 0,
+            posted_bond_lamports: 0,
+        };
+
+        let swap_account = ctx.accounts.swap_account.key();
+        emit_cpi!(Initiated {
+            swap_account,
+            swap_amount: amount_lamports,
+            expires_in_slots,
+            expiry_slot,
+            expiry_timestamp: None,
+            initiator: maker,
+            redeemer: taker,
+            secret_hash,
+            hash_algorithm: HashAlgorithm::Sha256,
+            incentives: SwapIncentives::default(),
+            created_at_slot: clock.slot,
+            created_at_unix: clock.unix_timestamp,
+        });
+        emit_cpi!(OrderFilled {
+            order: order.key(),
+            swap_account,
+            maker,
+            taker,
+            secret_hash,
+            amount_lamports,
+            remaining_lamports: order.remaining_lamports,
+        });
+
+        if order.remaining_lamports == 0 {
+            order.close(ctx.accounts.maker.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    /// Cancels the order, returning its escrowed native SOL to the maker.
+    /// As such, the maker's signature is required for this instruction.
+    pub fn cancel_order(ctx: Context<CancelOrder>) -> Result<()> {
+        emit_cpi!(OrderCancelled {
+            order: ctx.accounts.order.key(),
+            maker: ctx.accounts.order.maker,
+            order_id: ctx.accounts.order.order_id,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of native SOL worth `notional_usd_micros` (in millionths of
+    /// a USD) upon redeem, as priced by Pyth's SOL/USD feed at that time, rather than a fixed
+    /// amount. Up to `amount_lamports` is escrowed in the oracle swap account to cover the
+    /// notional, with whatever is left after redeem returned to the initiator.
+    /// Redeems require a price published within `max_staleness_secs` whose confidence interval
+    /// is within `max_confidence_bps` basis points of the price, and releasing between
+    /// `min_lamports_out` and `max_lamports_out` so that neither party is exposed to a price
+    /// spike. Swaps whose bounds are violated until expiry are left to be refunded.
+    /// As such, the initiator's signature is required for this instruction.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_oracle_priced(
+        ctx: Context<InitiateOraclePriced>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        notional_usd_micros: u64,
+        max_staleness_secs: u64,
+        max_confidence_bps: u16,
+        min_lamports_out: u64,
+        max_lamports_out: u64,
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(notional_usd_micros > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require!(
+            max_staleness_secs > 0 && u64::from(max_confidence_bps) <= BPS_DENOMINATOR,
+            SwapError::InvalidPriceBounds
+        );
+        require!(
+            min_lamports_out <= max_lamports_out && min_lamports_out <= amount_lamports,
+            SwapError::InvalidSlippageBounds
+        );
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+
+        let initiator = ctx.accounts.initiator.key();
+        *ctx.accounts.swap_account = OracleSwapAccount {
+            amount_lamports,
+            notional_usd_micros,
+            max_staleness_secs,
+            max_confidence_bps,
+            min_lamports_out,
+            max_lamports_out,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator,
+            redeemer,
+            secret_hash,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(OraclePricedInitiated {
+            swap_account: ctx.accounts.swap_account.key(),
+            amount_lamports,
+            expires_in_slots,
+            initiator,
+            redeemer,
+            secret_hash,
+            notional_usd_micros,
+            max_staleness_secs,
+            max_confidence_bps,
+            min_lamports_out,
+            max_lamports_out,
+        });
+
+        Ok(())
+    }
+
+    /// The native SOL worth the oracle-priced swap's notional, as priced by `price_update`, is
+    /// transferred to the redeemer, given the secret and that it lies within the swap's slippage
+    /// bounds. The remainder of its escrow is returned to the initiator along with the rent.
+    /// This instruction does not require any signatures.
+    pub fn redeem_oracle_priced(ctx: Context<RedeemOraclePriced>, secret: [u8; 32]) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            hash::hash(&secret).to_bytes() == swap_account.secret_hash,
+            SwapError::InvalidSecret
+        );
+
+        let price = PythPrice::load(&ctx.accounts.price_update)?;
+        price.validate(
+            Clock::get()?.unix_timestamp,
+            swap_account.max_staleness_secs,
+            swap_account.max_confidence_bps,
+        )?;
+        let amount_lamports = price
+            .lamports_for(swap_account.notional_usd_micros)
+            .ok_or(error!(SwapError::InvalidPriceUpdate))?;
+        require!(
+            (swap_account.min_lamports_out..=swap_account.max_lamports_out)
+                .contains(&amount_lamports),
+            SwapError::SlippageExceeded
+        );
+        require!(
+            amount_lamports <= swap_account.amount_lamports,
+            SwapError::InsufficientOracleEscrow
+        );
+
+        swap_account.sub_lamports(amount_lamports)?;
+        ctx.accounts.redeemer.add_lamports(amount_lamports)?;
+
+        emit_cpi!(OraclePricedRedeemed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            secret,
+            amount_lamports,
+            price: price.price,
+            exponent: price.exponent,
+        });
+
+        Ok(())
+    }
+
+    /// The oracle-priced swap's escrow is returned to the initiator, given that the expiry slot
+    /// has been reached, as is the case for swaps whose slippage bounds were violated throughout.
+    /// This instruction does not require any signatures.
+    pub fn refund_oracle_priced(ctx: Context<RefundOraclePriced>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(OraclePricedRefunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            secret_hash: swap_account.secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Initiates an atomic swap of native SOL settled by a message rather than a secret: it is
+    /// redeemed with a Wormhole VAA from `emitter_address` on `emitter_chain` (in Wormhole's
+    /// encoding) attesting that the counterpart leg settled on that chain.
+    /// `swap_id` distinguishes the swaps between the same parties, taking the place of the
+    /// secret hash in the seeds of the swap account.
+    /// The emitter must be checked by the redeemer to only attest to settlements they made.
+    /// `amount_lamports` is escrowed in the Wormhole swap account itself.
+    /// As such, the initiator's signature is required for this instruction.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initiate_wormhole(
+        ctx: Context<InitiateWormhole>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        redeemer: Pubkey,
+        swap_id: [u8; 32],
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+    ) -> Result<()> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, ctx.accounts.initiator.key(), SwapError::SelfSwap);
+        ctx.accounts.config.validate_expiry(expires_in_slots)?;
+
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap_account.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+
+        let initiator = ctx.accounts.initiator.key();
+        *ctx.accounts.swap_account = WormholeSwapAccount {
+            amount_lamports,
+            expiry_slot: expiry_slot_after(Clock::get()?.slot, expires_in_slots)?,
+            initiator,
+            redeemer,
+            swap_id,
+            emitter_chain,
+            emitter_address,
+            bump: ctx.bumps.swap_account,
+        };
+
+        emit_cpi!(WormholeInitiated {
+            swap_account: ctx.accounts.swap_account.key(),
+            amount_lamports,
+            expires_in_slots,
+            initiator,
+            redeemer,
+            swap_id,
+            emitter_chain,
+            emitter_address,
+        });
+
+        Ok(())
+    }
+
+    /// The Wormhole swap's funds are transferred to the redeemer, given a VAA posted to the core
+    /// bridge from the swap's emitter whose payload begins with the address of its swap account.
+    /// The core bridge has verified the guardian signatures of every posted VAA.
+    /// This instruction does not require any signatures.
+    pub fn redeem_with_vaa(ctx: Context<RedeemWithVaa>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        let vaa = PostedVaa::load(&ctx.accounts.posted_vaa)?;
+        require!(
+            vaa.emitter_chain == swap_account.emitter_chain
+                && vaa.emitter_address == swap_account.emitter_address,
+            SwapError::InvalidVaaEmitter
+        );
+        require!(
+            vaa.swap_account == swap_account.key(),
+            SwapError::InvalidVaaPayload
+        );
+
+        swap_account.sub_lamports(swap_account.amount_lamports)?;
+        ctx.accounts
+            .redeemer
+            .add_lamports(swap_account.amount_lamports)?;
+
+        emit_cpi!(WormholeRedeemed {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            redeemer: swap_account.redeemer,
+            swap_id: swap_account.swap_id,
+            posted_vaa: ctx.accounts.posted_vaa.key(),
+            sequence: vaa.sequence,
+        });
+
+        Ok(())
+    }
+
+    /// The Wormhole swap's funds are returned to the initiator, given that the expiry slot
+    /// has been reached.
+    /// This instruction does not require any signatures.
+    pub fn refund_wormhole(ctx: Context<RefundWormhole>) -> Result<()> {
+        let swap_account = &ctx.accounts.swap_account;
+        require!(
+            has_expired(Clock::get()?.slot, swap_account.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(WormholeRefunded {
+            swap_account: swap_account.key(),
+            initiator: swap_account.initiator,
+            swap_id: swap_account.swap_id,
+        });
+
+        Ok(())
+    }
+
+    /// Carves off an allowance from the initiator into a per-swap fee escrow that an authorized
+    /// relayer can draw from to pay transaction fees for subsequent lifecycle instructions.
+    /// Meant to be bundled with `initiate` in the same transaction for fully-sponsored flows.
+    /// As such, the initiator's signature is required for this instruction.
+    /// `allowance_lamports` is denominated in base units (aka lamports).
+    pub fn provision_relayer_fees(
+        ctx: Context<ProvisionRelayerFees>,
+        allowance_lamports: u64,
+        relayer: Pubkey,
+    ) -> Result<()> {
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.fee_escrow.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, allowance_lamports)?;
+
+        *ctx.accounts.fee_escrow = FeeEscrow {
+            swap_account: ctx.accounts.swap_account.key(),
+            initiator: ctx.accounts.initiator.key(),
+            relayer,
+        };
+
+        emit_cpi!(RelayerFeesProvisioned {
+            swap_account: ctx.accounts.swap_account.key(),
+            relayer,
+            allowance_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Transfers `amount_lamports` from the fee escrow to the relayer.
+    /// The escrow's rent-exempt reserve cannot be drawn.
+    /// As such, the relayer's signature is required for this instruction.
+    pub fn draw_relayer_fees(ctx: Context<DrawRelayerFees>, amount_lamports: u64) -> Result<()> {
+        let fee_escrow = ctx.accounts.fee_escrow.to_account_info();
+        let reserve = Rent::get()?.minimum_balance(fee_escrow.data_len());
+        let available = fee_escrow.lamports().saturating_sub(reserve);
+        require!(
+            amount_lamports <= available,
+            SwapError::InsufficientFeeAllowance
+        );
+
+        ctx.accounts.fee_escrow.sub_lamports(amount_lamports)?;
+        ctx.accounts.relayer.add_lamports(amount_lamports)?;
+
+        emit_cpi!(RelayerFeesDrawn {
+            swap_account: ctx.accounts.fee_escrow.swap_account,
+            relayer: ctx.accounts.relayer.key(),
+            amount_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Closes the fee escrow once its swap has completed, returning the unused
+    /// allowance along with the rent to the initiator.
+    /// This instruction does not require any signatures.
+    pub fn close_fee_escrow(_ctx: Context<CloseFeeEscrow>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Settles two opposing swaps between the same pair of parties (A→B and B→A)
+    /// by transferring only the difference between their amounts.
+    /// The larger swap pays the difference to its redeemer and both PDAs are closed,
+    /// returning the remaining funds to their respective initiators.
+    /// The protocol fee is charged on the difference, as with `redeem`.
+    /// As such, the signatures of both parties are required for this instruction.
+    pub fn net_settle(ctx: Context<NetSettle>) -> Result<()> {
+        let swap_a = *ctx.accounts.swap_a.load()?;
+        let swap_b = *ctx.accounts.swap_b.load()?;
+        let (amount_a, amount_b) = (swap_a.amount_lamports, swap_b.amount_lamports);
+
+        let net_amount = amount_a.abs_diff(amount_b);
+        let protocol_fee = ctx.accounts.config.protocol_fee(net_amount);
+        if amount_a > amount_b {
+            ctx.accounts.swap_a.sub_lamports(net_amount)?;
+            ctx.accounts
+                .party_b
+                .add_lamports(net_amount - protocol_fee)?;
+        } else if amount_b > amount_a {
+            ctx.accounts.swap_b.sub_lamports(net_amount)?;
+            ctx.accounts
+                .party_a
+                .add_lamports(net_amount - protocol_fee)?;
+        }
+        ctx.accounts.treasury.add_lamports(protocol_fee)?;
+
+        emit_cpi!(NetSettled {
+            party_a: ctx.accounts.party_a.key(),
+            party_b: ctx.accounts.party_b.key(),
+            secret_hash_a: swap_a.secret_hash,
+            secret_hash_b: swap_b.secret_hash,
+            net_amount,
+            protocol_fee_lamports: protocol_fee,
+        });
+
+        Ok(())
+    }
+
+    /// Opens a reusable payment channel from the funder to the payee, supporting repeated
+    /// hash-locked payments without a fresh PDA per payment.
+    /// `deposit_lamports` is escrowed in the channel and `expires_in_slots` represents the
+    /// number of slots after which the funder may unilaterally close the channel.
+    /// As such, the funder's signature is required for this instruction.
+    pub fn open_channel(
+        ctx: Context<OpenChannel>,
+        deposit_lamports: u64,
+        expires_in_slots: u64,
+        payee: Pubkey,
+    ) -> Result<()> {
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.channel.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, deposit_lamports)?;
+
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        *ctx.accounts.channel = PaymentChannel {
+            funder: ctx.accounts.funder.key(),
+            payee,
+            expiry_slot,
+            deposit_lamports,
+            pending_payments: Vec::new(),
+        };
+
+        emit_cpi!(ChannelOpened {
+            funder: ctx.accounts.funder.key(),
+            payee,
+            deposit_lamports,
+            expiry_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Tops up the channel's deposit by `amount_lamports`.
+    /// As such, the funder's signature is required for this instruction.
+    pub fn deposit_to_channel(ctx: Context<DepositToChannel>, amount_lamports: u64) -> Result<()> {
+        let transfer_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.channel.to_account_info(),
+            },
+        );
+        system_program::transfer(transfer_context, amount_lamports)?;
+        let channel = &mut ctx.accounts.channel;
+        channel.deposit_lamports = channel
+            .deposit_lamports
+            .checked_add(amount_lamports)
+            .ok_or(SwapError::ChannelDepositTooLarge)?;
+
+        Ok(())
+    }
+
+    /// Locks `amount_lamports` of the channel's unlocked deposit behind `secret_hash`.
+    /// Unlike swaps, channel payments are always locked to the SHA-256 hash of the secret.
+    /// The payee may claim it with the secret until `expires_in_slots` slots have passed,
+    /// after which it may be cancelled, returning it to the unlocked deposit.
+    /// As such, the funder's signature is required for this instruction.
+    pub fn lock_channel_payment(
+        ctx: Context<LockChannelPayment>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        let expiry_slot =
+            ctx.accounts
+                .channel
+                .lock_payment(amount_lamports, expires_in_slots, secret_hash)?;
+
+        emit_cpi!(ChannelPaymentLocked {
+            channel: ctx.accounts.channel.key(),
+            amount_lamports,
+            expiry_slot,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Identical to `lock_channel_payment`, but signed by a session key the funder has
+    /// authorized with the `SESSION_SCOPE_LOCK_CHANNEL_PAYMENT` scope.
+    pub fn lock_channel_payment_with_session(
+        ctx: Context<LockChannelPaymentWithSession>,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts
+            .session
+            .authorize(SESSION_SCOPE_LOCK_CHANNEL_PAYMENT)?;
+        let expiry_slot =
+            ctx.accounts
+                .channel
+                .lock_payment(amount_lamports, expires_in_slots, secret_hash)?;
+
+        emit_cpi!(ChannelPaymentLocked {
+            channel: ctx.accounts.channel.key(),
+            amount_lamports,
+            expiry_slot,
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Pays a pending payment out of the channel to the payee.
+    /// This instruction does not require any signatures.
+    pub fn claim_channel_payment(
+        ctx: Context<ClaimChannelPayment>,
+        secret: [u8; 32],
+    ) -> Result<()> {
+        let secret_hash = hash::hash(&secret).to_bytes();
+        let channel = &mut ctx.accounts.channel;
+        let index = channel
+            .pending_payment(&secret_hash)
+            .ok_or(SwapError::InvalidSecret)?;
+        let payment = channel.pending_payments.swap_remove(index);
+        require!(
+            !has_expired(Clock::get()?.slot, payment.expiry_slot),
+            SwapError::ChannelPaymentExpired
+        );
+        channel.deposit_lamports -= payment.amount_lamports;
+
+        ctx.accounts.channel.sub_lamports(payment.amount_lamports)?;
+        ctx.accounts.payee.add_lamports(payment.amount_lamports)?;
+
+        emit_cpi!(ChannelPaymentClaimed {
+            channel: ctx.accounts.channel.key(),
+            amount_lamports: payment.amount_lamports,
+            secret,
+        });
+
+        Ok(())
+    }
+
+    /// Cancels an expired pending payment, returning its amount to the channel's unlocked deposit.
+    /// This instruction does not require any signatures.
+    pub fn cancel_channel_payment(
+        ctx: Context<CancelChannelPayment>,
+        secret_hash: [u8; 32],
+    ) -> Result<()> {
+        let channel = &mut ctx.accounts.channel;
+        let index = channel
+            .pending_payment(&secret_hash)
+            .ok_or(SwapError::UnknownChannelPayment)?;
+        require!(
+            has_expired(
+                Clock::get()?.slot,
+                channel.pending_payments[index].expiry_slot
+            ),
+            SwapError::RefundBeforeExpiry
+        );
+        channel.pending_payments.swap_remove(index);
+
+        emit_cpi!(ChannelPaymentCancelled {
+            channel: channel.key(),
+            secret_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Closes the channel with both parties' consent, paying `payee_amount_lamports` of the
+    /// remaining deposit to the payee and returning the rest to the funder.
+    /// As such, the signatures of both parties are required for this instruction.
+    pub fn cooperative_close_channel(
+        ctx: Context<CooperativeCloseChannel>,
+        payee_amount_lamports: u64,
+    ) -> Result<()> {
+        require!(
+            payee_amount_lamports <= ctx.accounts.channel.deposit_lamports,
+            SwapError::InsufficientChannelDeposit
+        );
+        ctx.accounts.channel.sub_lamports(payee_amount_lamports)?;
+        ctx.accounts.payee.add_lamports(payee_amount_lamports)?;
+
+        emit_cpi!(ChannelClosed {
+            channel: ctx.accounts.channel.key(),
+            payee_amount_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Closes the channel after its expiry slot, returning the remaining deposit,
+    /// including any pending payments, to the funder.
+    /// This instruction does not require any signatures.
+    pub fn close_expired_channel(ctx: Context<CloseExpiredChannel>) -> Result<()> {
+        require!(
+            has_expired(Clock::get()?.slot, ctx.accounts.channel.expiry_slot),
+            SwapError::RefundBeforeExpiry
+        );
+
+        emit_cpi!(ChannelClosed {
+            channel: ctx.accounts.channel.key(),
+            payee_amount_lamports: 0,
+        });
+
+        Ok(())
+    }
+}
+
+/// Stores the state information of the atomic swap on-chain.
+/// The layout is zero-copy and fixed, with fields ordered so as to require no implicit padding,
+/// allowing off-chain systems to filter swaps by memcmp at stable offsets.
+/// Optional fields are stored as zero when absent.
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct SwapAccount {
+    /// The layout version of this account, always stored first so as to be readable
+    /// regardless of the layout
+    pub version: u8,
+    /// The bump of this PDA, allowing its seeds to be verified without re-derivation
+    pub bump: u8,
+    /// The discriminant of the `HashAlgorithm` the secret hash was computed with
+    pub hash_algorithm: u8,
+    /// The nonce distinguishing this swap from others between the same parties and secret
+    /// hash, in little-endian, or zero if none. Nonzero nonces are part of the PDA's seeds.
+    pub nonce: [u8; 4],
+    /// The version of the scheme the PDA's seeds follow, see `SWAP_SEEDS_VERSION`
+    pub seeds_version: u8,
+    /// The quantity of native SOL to be transferred through this atomic swap in base units (aka lamports)
+    pub amount_lamports: u64,
+    /// The exact slot from which (non-instant) refunds are allowed
+    pub expiry_slot: u64,
+    /// The exact unix timestamp from which (non-instant) refunds are allowed, if any.
+    /// When present, refunds require both this and `expiry_slot` to have been reached.
+    pub expiry_timestamp: i64,
+    /// The relayer fee yet to be paid, as per the swap's `SwapIncentives`
+    pub relayer_fee_lamports: u64,
+    /// The refund tip yet to be paid, as per the swap's `SwapIncentives`
+    pub refund_tip_lamports: u64,
+    /// The exact slot after which redeems are allowed, if any, as per the swap's `RedeemWindow`
+    pub redeem_after_slot: u64,
+    /// The exact slot after which redeems are no longer allowed, if any,
+    /// as per the swap's `RedeemWindow`
+    pub redeem_deadline_slot: u64,
+    /// The slot the swap was initiated at
+    pub created_at_slot: u64,
+    /// The unix timestamp the swap was initiated at
+    pub created_at_unix: i64,
+    /// The initiator of the atomic swap
+    pub initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    pub redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    pub secret_hash: [u8; 32],
+    /// The account refunds are paid to in place of the initiator, if any
+    pub refund_to: Pubkey,
+    /// The relayer fee yet to be paid as of initiation, rising linearly to `relayer_fee_lamports`
+    /// at the expiry slot, as per the swap's `SwapIncentives`
+    pub relayer_fee_start_lamports: u64,
+    /// The bond the redeemer must post through `accept` before the swap may be redeemed,
+    /// or zero if the swap requires no acceptance
+    pub acceptance_bond_lamports: u64,
+    /// The bond the redeemer has posted through `accept`, escrowed in this account on top of the
+    /// swap amount, or zero if not (yet) accepted
+    pub posted_bond_lamports: u64,
+}
+
+impl SwapAccount {
+    /// The algorithm the secret hash was computed with
+    pub fn hash_algorithm(&self) -> Result<HashAlgorithm> {
+        HashAlgorithm::try_from_slice(&[self.hash_algorithm])
+            .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+    }
+
+    /// The nonce distinguishing this swap from others between the same parties and secret
+    /// hash, or zero if none
+    pub fn nonce(&self) -> u32 {
+        u32::from_le_bytes(self.nonce)
+    }
+
+    /// The seed the nonce contributes to the PDA's seeds, empty for swaps without one so as
+    /// to derive the same address as their seeds without it
+    pub fn nonce_seed(&self) -> &[u8] {
+        match self.nonce() {
+            0 => &[],
+            _ => &self.nonce,
+        }
+    }
+
+    /// The seed the redeemer contributes to the PDA's seeds, empty for swaps initiated
+    /// before it was part of them
+    pub fn redeemer_seed(&self) -> &[u8] {
+        match self.seeds_version {
+            0 => &[],
+            _ => self.redeemer.as_ref(),
+        }
+    }
+
+    /// The exact unix timestamp from which (non-instant) refunds are allowed, if any
+    pub fn expiry_timestamp(&self) -> Option<i64> {
+        Some(self.expiry_timestamp).filter(|&expiry_timestamp| expiry_timestamp != 0)
+    }
+
+    /// The account refunds are paid to in place of the initiator, if any
+    pub fn refund_to(&self) -> Option<Pubkey> {
+        Some(self.refund_to).filter(|&refund_to| refund_to != Pubkey::default())
+    }
+
+    /// The window within which the swap may be redeemed
+    pub fn redeem_window(&self) -> RedeemWindow {
+        RedeemWindow {
+            redeem_after_slot: Some(self.redeem_after_slot).filter(|&slot| slot != 0),
+            redeem_deadline_slot: Some(self.redeem_deadline_slot).filter(|&slot| slot != 0),
+        }
+    }
+
+    /// The relayer fee a relayer submitting `redeem` at `slot` is owed, rising linearly from
+    /// `relayer_fee_start_lamports` at initiation to `relayer_fee_lamports` at the expiry slot
+    pub fn relayer_fee_at(&self, slot: u64) -> u64 {
+        let duration = self.expiry_slot.saturating_sub(self.created_at_slot);
+        let elapsed = slot.clamp(self.created_at_slot, self.expiry_slot) - self.created_at_slot;
+        let start = self
+            .relayer_fee_start_lamports
+            .min(self.relayer_fee_lamports);
+        if duration == 0 {
+            return self.relayer_fee_lamports;
+        }
+        let rise = u128::from(self.relayer_fee_lamports - start) * u128::from(elapsed)
+            / u128::from(duration);
+        start + rise as u64
+    }
+
+    /// Whether `secrets` correspond to the swap's secret hash: a single secret hashed with the
+    /// swap's algorithm, or several whose hashes combine into it as per `combined_secret_hash`
+    pub fn verify_secrets(&self, secrets: &[[u8; 32]]) -> Result<bool> {
+        let hash_algorithm = self.hash_algorithm()?;
+        let secret_hash = match secrets {
+            [secret] => hash_algorithm.hash(secret),
+            _ => combined_secret_hash(
+                &secrets
+                    .iter()
+                    .map(|secret| hash_algorithm.hash(secret))
+                    .collect::<Vec<_>>(),
+            ),
+        };
+        Ok(secret_hash == self.secret_hash)
+    }
+
+    /// Whether `secret` corresponds to one of `secret_hashes`, given they are the secret hashes
+    /// the swap's secret hash commits to as per `any_secret_hash`
+    pub fn verify_any_secret(&self, secret: &[u8; 32], secret_hashes: &[[u8; 32]]) -> Result<bool> {
+        let secret_hash = self.hash_algorithm()?.hash(secret);
+        Ok(secret_hashes.len() <= MAX_SECRET_HASHES as usize
+            && any_secret_hash(secret_hashes) == self.secret_hash
+            && secret_hashes.contains(&secret_hash))
+    }
+
+    /// The message signed under `prefix` to authorize an instruction on the swap at
+    /// `swap_account`, bound to this instance of the swap as per `instance_message`
+    pub fn signed_message(&self, prefix: &[u8], swap_account: &Pubkey) -> Vec<u8> {
+        instance_message(
+            prefix,
+            swap_account,
+            self.created_at_slot,
+            self.expiry_slot,
+            self.amount_lamports,
+        )
+    }
+
+    /// The message the redeemer signs to consent to a redeem of the swap at `swap_account`
+    /// to `destination`, bound to this instance of the swap as per `signed_message`
+    pub fn redeem_consent_message(&self, swap_account: &Pubkey, destination: &Pubkey) -> Vec<u8> {
+        [
+            self.signed_message(REDEEM_CONSENT_PREFIX, swap_account),
+            destination.to_bytes().to_vec(),
+        ]
+        .concat()
+    }
+
+    /// The address refunds are paid to
+    pub fn refund_recipient(&self) -> Pubkey {
+        self.refund_to().unwrap_or(self.initiator)
+    }
+
+    /// Resolves the account refunds are paid to, out of the initiator and the provided
+    /// refund destination, if any
+    fn refund_destination<'a, 'info>(
+        &self,
+        initiator: &'a AccountInfo<'info>,
+        refund_to: Option<&'a AccountInfo<'info>>,
+    ) -> Result<&'a AccountInfo<'info>> {
+        let destination = match self.refund_to() {
+            Some(_) => refund_to.ok_or(error!(SwapError::InvalidRefundDestination))?,
+            None => initiator,
+        };
+        require_keys_eq!(
+            destination.key(),
+            self.refund_recipient(),
+            SwapError::InvalidRefundDestination
+        );
+        Ok(destination)
+    }
+
+    /// Whether the swap may be redeemed, i.e. it requires no acceptance or has been accepted
+    pub fn is_live(&self) -> bool {
+        self.acceptance_bond_lamports == 0 || self.posted_bond_lamports > 0
+    }
+
+    /// Whether (non-instant) refunds are allowed, i.e. every timelock of the swap has expired
+    pub fn has_expired(&self, clock: &Clock) -> bool {
+        let timestamp_expired = match self.expiry_timestamp() {
+            Some(expiry_timestamp) => clock.unix_timestamp >= expiry_timestamp,
+            None => true,
+        };
+        has_expired(clock.slot, self.expiry_slot) && timestamp_expired
+    }
+}
+
+/// The Borsh-encoded layout of `SwapAccount` as initiated before it was versioned, holding
+/// neither its bump nor when it was initiated, as read by `migrate`
+#[derive(AnchorDeserialize, InitSpace)]
+struct UnversionedSwapAccount {
+    amount_lamports: u64,
+    expiry_slot: u64,
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    secret_hash: [u8; 32],
+}
+
+impl UnversionedSwapAccount {
+    /// The swap held by `swap_account` in the current layout. These swaps were all derived from
+    /// seeds without the redeemer, and refundable only after their expiry slot rather than from
+    /// it. When they were initiated is unknown, and recorded as zero.
+    fn upgrade(self, swap_account: &Pubkey, program_id: &Pubkey) -> Result<SwapAccount> {
+        let (expected_swap_account, bump) = Pubkey::find_program_address(
+            &[b"swap_account", self.initiator.as_ref(), &self.secret_hash],
+            program_id,
+        );
+        require_keys_eq!(
+            *swap_account,
+            expected_swap_account,
+            SwapError::InvalidSwapAccount
+        );
+        Ok(SwapAccount {
+            version: SWAP_ACCOUNT_VERSION,
+            bump,
+            hash_algorithm: HashAlgorithm::Sha256 as u8,
+            nonce: [0; 4],
+            seeds_version: 0,
+            amount_lamports: self.amount_lamports,
+            expiry_slot: self.expiry_slot.saturating_add(1),
+            expiry_timestamp: 0,
+            relayer_fee_lamports: 0,
+            refund_tip_lamports: 0,
+            redeem_after_slot: 0,
+            redeem_deadline_slot: 0,
+            created_at_slot: 0,
+            created_at_unix: 0,
+            initiator: self.initiator,
+            redeemer: self.redeemer,
+            secret_hash: self.secret_hash,
+            refund_to: Pubkey::default(),
+            relayer_fee_start_lamports: 0,
+            acceptance_bond_lamports: 0,
+            posted_bond_lamports: 0,
+        })
+    }
+}
+
+/// The Borsh-encoded layout of `SwapAccount` at version 1, superseded by the zero-copy layout,
+/// as read by `migrate`
+#[derive(AnchorDeserialize, InitSpace)]
+struct SwapAccountV1 {
+    version: u8,
+    amount_lamports: u64,
+    expiry_slot: u64,
+    expiry_timestamp: Option<i64>,
+    initiator: Pubkey,
+    redeemer: Pubkey,
+    secret_hash: [u8; 32],
+    hash_algorithm: HashAlgorithm,
+    relayer_fee_lamports: u64,
+    refund_tip_lamports: u64,
+    refund_to: Option<Pubkey>,
+    redeem_after_slot: Option<u64>,
+    redeem_deadline_slot: Option<u64>,
+    bump: u8,
+    created_at_slot: u64,
+    created_at_unix: i64,
+}
+
+impl SwapAccountV1 {
+    /// The swap in the current layout. Version 1 swaps were all derived from seeds without
+    /// the redeemer, and without a nonce.
+    fn upgrade(self) -> SwapAccount {
+        SwapAccount {
+            version: self.version,
+            bump: self.bump,
+            hash_algorithm: self.hash_algorithm as u8,
+            nonce: [0; 4],
+            seeds_version: 0,
+            amount_lamports: self.amount_lamports,
+            expiry_slot: self.expiry_slot,
+            expiry_timestamp: self.expiry_timestamp.unwrap_or_default(),
+            relayer_fee_lamports: self.relayer_fee_lamports,
+            refund_tip_lamports: self.refund_tip_lamports,
+            redeem_after_slot: self.redeem_after_slot.unwrap_or_default(),
+            redeem_deadline_slot: self.redeem_deadline_slot.unwrap_or_default(),
+            created_at_slot: self.created_at_slot,
+            created_at_unix: self.created_at_unix,
+            initiator: self.initiator,
+            redeemer: self.redeemer,
+            secret_hash: self.secret_hash,
+            refund_to: self.refund_to.unwrap_or_default(),
+            relayer_fee_start_lamports: self.relayer_fee_lamports,
+            acceptance_bond_lamports: 0,
+            posted_bond_lamports: 0,
+        }
+    }
+}
+
+/// The version of the program, as returned by `get_version`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProgramVersion {
+    /// The semantic version of the program, i.e. `PROGRAM_VERSION`
+    pub version: String,
+    /// The layout version of the swap accounts created by the program
+    pub swap_account_version: u8,
+}
+
+/// The decoded state of a swap, as returned by `get_swap_state`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapInfo {
+    /// The layout version of the swap account
+    pub version: u8,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    /// The nonce in the swap account's seeds, or zero if none
+    pub nonce: u32,
+    pub hash_algorithm: HashAlgorithm,
+    /// The funds yet to be redeemed or refunded
+    pub amount_lamports: u64,
+    pub expiry_slot: u64,
+    pub expiry_timestamp: Option<i64>,
+    /// The incentives yet to be paid
+    pub incentives: SwapIncentives,
+    pub redeem_window: RedeemWindow,
+    /// The address refunds are paid to
+    pub refund_recipient: Pubkey,
+    pub created_at_slot: u64,
+    pub created_at_unix: i64,
+    /// Whether refunds are allowed, as of the slot and unix timestamp of the query
+    pub has_expired: bool,
+}
+
+/// The parameters of a single swap within `initiate_batch`, following the semantics of `initiate`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchedSwap {
+    pub amount_lamports: u64,
+    pub expires_in_slots: u64,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    pub hash_algorithm: HashAlgorithm,
+    pub incentives: SwapIncentives,
+    pub refund_to: Option<Pubkey>,
+    pub redeem_window: RedeemWindow,
+}
+
+/// The fees an initiator offers third parties for submitting a swap's lifecycle instructions
+/// on behalf of its parties. Each fee is carved out of the swap amount, in base units (aka lamports).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct SwapIncentives {
+    /// Paid to a relayer submitting `redeem`, with the remainder going to the redeemer
+    pub relayer_fee_lamports: u64,
+    /// Paid to a third party submitting `refund` after expiry, with the remainder going to the initiator
+    pub refund_tip_lamports: u64,
+    /// If set, the relayer fee is offered as a Dutch auction, starting at this fee upon initiation
+    /// and rising linearly to `relayer_fee_lamports` at the expiry slot. Relayers are thus paid
+    /// the least fee they are willing to settle for, rather than racing the refund boundary.
+    pub relayer_fee_start_lamports: Option<u64>,
+}
+
+impl SwapIncentives {
+    /// Ensures the fees can be carved out of a swap of `amount_lamports`
+    fn validate(&self, amount_lamports: u64) -> Result<()> {
+        require!(
+            self.relayer_fee_lamports <= amount_lamports
+                && self.refund_tip_lamports <= amount_lamports,
+            SwapError::IncentivesExceedSwapAmount
+        );
+        require!(
+            self.relayer_fee_start() <= self.relayer_fee_lamports,
+            SwapError::InvalidRelayerFeeAuction
+        );
+        Ok(())
+    }
+
+    /// The relayer fee offered upon initiation, being the full fee unless auctioned
+    fn relayer_fee_start(&self) -> u64 {
+        self.relayer_fee_start_lamports
+            .unwrap_or(self.relayer_fee_lamports)
+    }
+}
+
+/// Restricts when a swap may be redeemed, in addition to its secret being revealed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct RedeemWindow {
+    /// The exact slot after which redeems are allowed, if any. This gives the initiator an
+    /// initial window to verify the counterpart chain's leg before the secret can be used
+    /// against them.
+    pub redeem_after_slot: Option<u64>,
+    /// The exact slot after which redeems are no longer allowed, if any, even if the swap
+    /// has not been refunded. This eliminates the race where the redeemer claims right after
+    /// the initiator has refunded on the counterpart chain.
+    pub redeem_deadline_slot: Option<u64>,
+}
+
+impl RedeemWindow {
+    /// Ensures the window opens no later than the swap's expiry slot, and before it closes
+    fn validate(&self, expiry_slot: u64) -> Result<()> {
+        // A deadline of slot zero is indistinguishable from none once stored
+        require!(
+            self.redeem_deadline_slot != Some(0),
+            SwapError::InvalidRedeemWindow
+        );
+        if let Some(redeem_after_slot) = self.redeem_after_slot {
+            require!(
+                redeem_after_slot < expiry_slot,
+                SwapError::InvalidRedeemWindow
+            );
+            if let Some(redeem_deadline_slot) = self.redeem_deadline_slot {
+                require!(
+                    redeem_after_slot < redeem_deadline_slot,
+                    SwapError::InvalidRedeemWindow
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether redeems are allowed at `slot`, i.e. whether
+    /// `redeem_after_slot < slot <= redeem_deadline_slot`. Unlike the timelocks checked through
+    /// `has_expired`, neither bound takes effect until the slot after it.
+    fn is_open(&self, slot: u64) -> bool {
+        let opened = match self.redeem_after_slot {
+            Some(redeem_after_slot) => slot > redeem_after_slot,
+            None => true,
+        };
+        let closed = match self.redeem_deadline_slot {
+            Some(redeem_deadline_slot) => slot > redeem_deadline_slot,
+            None => false,
+        };
+        opened && !closed
+    }
+}
+
+/// The algorithm used to compute a swap's secret hash from its secret
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    /// Used by EVM HTLCs
+    Keccak256,
+    /// RIPEMD160(SHA256(secret)), used by Bitcoin script HTLCs.
+    /// The 20-byte digest occupies the leading bytes of the secret hash, followed by zeroes.
+    Hash160,
+    Blake3,
+}
+
+impl HashAlgorithm {
+    /// Computes the secret hash of `secret` using this algorithm
+    pub fn hash(&self, secret: &[u8; 32]) -> [u8; 32] {
+        match self {
+            HashAlgorithm::Sha256 => hash::hash(secret).to_bytes(),
+            HashAlgorithm::Keccak256 => keccak::hash(secret).to_bytes(),
+            HashAlgorithm::Hash160 => {
+                let digest = Ripemd160::digest(hash::hash(secret).to_bytes());
+                let mut secret_hash = [0; 32];
+                secret_hash[..digest.len()].copy_from_slice(&digest);
+                secret_hash
+            }
+            HashAlgorithm::Blake3 => solana_blake3_hasher::hash(secret).to_bytes(),
+        }
+    }
+}
+
+/// The commitment of a swap to several secret hashes, being the SHA-256 hash of their
+/// concatenation, irrespective of the algorithm the secret hashes were computed with
+pub fn combined_secret_hash(secret_hashes: &[[u8; 32]]) -> [u8; 32] {
+    hash::hashv(&secret_hashes.iter().map(AsRef::as_ref).collect::<Vec<_>>()).to_bytes()
+}
+
+/// The commitment of a swap to any one of several secret hashes, being the SHA-256 hash of
+/// `ANY_SECRET_HASH_PREFIX` followed by their concatenation. The prefix keeps it distinct
+/// from the `combined_secret_hash` of the same secret hashes, which requires every secret.
+pub fn any_secret_hash(secret_hashes: &[[u8; 32]]) -> [u8; 32] {
+    let mut preimage = vec![ANY_SECRET_HASH_PREFIX];
+    preimage.extend(secret_hashes.iter().map(AsRef::as_ref));
+    hash::hashv(&preimage).to_bytes()
+}
+
+/// The secrets revealed to redeem a swap
+#[derive(Clone, Copy)]
+enum RevealedSecrets<'a> {
+    /// The swap's secret, or every secret of a swap committed to several secret hashes,
+    /// as per `SwapAccount::verify_secrets`
+    All(&'a [[u8; 32]]),
+    /// The secret of one of the secret hashes of a swap redeemable with any of them,
+    /// as per `SwapAccount::verify_any_secret`
+    AnyOf {
+        secret: [u8; 32],
+        secret_hashes: &'a [[u8; 32]],
+    },
+}
+
+impl RevealedSecrets<'_> {
+    /// Whether the secrets correspond to `swap`'s secret hash
+    fn verify(&self, swap: &SwapAccount) -> Result<bool> {
+        match self {
+            RevealedSecrets::All(secrets) => swap.verify_secrets(secrets),
+            RevealedSecrets::AnyOf {
+                secret,
+                secret_hashes,
+            } => swap.verify_any_secret(secret, secret_hashes),
+        }
+    }
+
+    /// The secret the `Redeemed` event records, being the first revealed
+    fn first(&self) -> [u8; 32] {
+        match self {
+            RevealedSecrets::All(secrets) => secrets[0],
+            RevealedSecrets::AnyOf { secret, .. } => *secret,
+        }
+    }
+}
+
+/// Transfers `amount_lamports` of the funds escrowed by a swap to `destination`, given `secrets`
+/// correspond to the swap's secret hash. The protocol fee is carved out and paid to the treasury,
+/// as is the swap's relayer fee, to the relayer if present.
+/// The swap account is closed once fully redeemed, transferring its rent to the initiator.
+/// Returns the resulting event, for the caller to emit.
+#[allow(clippy::too_many_arguments)]
+fn redeem_swap<'info>(
+    swap_account: &AccountLoader<'info, SwapAccount>,
+    secrets: RevealedSecrets,
+    amount_lamports: u64,
+    destination: &AccountInfo<'info>,
+    initiator: &AccountInfo<'info>,
+    config: &Config,
+    treasury: &Account<'info, Treasury>,
+    relayer: Option<&Signer<'info>>,
+) -> Result<Redeemed> {
+    let mut swap = swap_account.load_mut()?;
+    require!(swap.is_live(), SwapError::SwapNotAccepted);
+    require!(secrets.verify(&swap)?, SwapError::InvalidSecret);
+    require!(
+        amount_lamports > 0 && amount_lamports <= swap.amount_lamports,
+        SwapError::InvalidRedeemAmount
+    );
+    let slot = Clock::get()?.slot;
+    require!(
+        swap.redeem_window().is_open(slot),
+        SwapError::RedeemOutsideWindow
+    );
+
+    let protocol_fee = config.protocol_fee(amount_lamports);
+    // The relayer fee is consumed across tranches until paid in full
+    let relayer_fee = match relayer {
+        Some(_) => swap
+            .relayer_fee_at(slot)
+            .min(amount_lamports - protocol_fee),
+        None => 0,
+    };
+    swap_account.sub_lamports(amount_lamports)?;
+    treasury.add_lamports(protocol_fee)?;
+    if let Some(relayer) = relayer {
+        relayer.add_lamports(relayer_fee)?;
+    }
+    destination.add_lamports(amount_lamports - protocol_fee - relayer_fee)?;
+    swap.amount_lamports -= amount_lamports;
+    swap.relayer_fee_lamports -= relayer_fee;
+    swap.relayer_fee_start_lamports = swap.relayer_fee_start_lamports.saturating_sub(relayer_fee);
+
+    let event = Redeemed {
+        swap_account: swap_account.key(),
+        initiator: swap.initiator,
+        redeemer: swap.redeemer,
+        secret: secrets.first(),
+        expiry_slot: swap.expiry_slot,
+        amount_lamports,
+        protocol_fee_lamports: protocol_fee,
+        relayer_fee_lamports: relayer_fee,
+    };
+
+    let fully_redeemed = swap.amount_lamports == 0;
+    if fully_redeemed {
+        return_acceptance_bond(swap_account, &mut swap, Some(destination))?;
+    }
+    drop(swap);
+    if fully_redeemed {
+        swap_account.close(initiator.clone())?;
+    }
+
+    Ok(event)
+}
+
+/// Records the secret revealed by a redeem of `swap_account` in `receipt`
+fn record_redeem_receipt(
+    receipt: &mut Account<RedeemReceipt>,
+    swap_account: &AccountLoader<SwapAccount>,
+    secret: [u8; 32],
+    payer: &Signer,
+) -> Result<()> {
+    receipt.set_inner(RedeemReceipt {
+        swap_account: swap_account.key(),
+        secret_hash: swap_account.load()?.secret_hash,
+        secret,
+        slot: Clock::get()?.slot,
+        payer: payer.key(),
+    });
+    Ok(())
+}
+
+/// Returns the remaining funds escrowed by a swap to its refund destination, less the swap's
+/// refund tip, which is paid to the refunder if present, along with the acceptance bond the
+/// redeemer forfeited by not redeeming, if any. Returns the resulting event, for the caller
+/// to emit.
+fn refund_swap<'info>(
+    swap_account: &AccountLoader<'info, SwapAccount>,
+    destination: &AccountInfo<'info>,
+    refunder: Option<&Signer<'info>>,
+) -> Result<Refunded> {
+    let mut swap = swap_account.load_mut()?;
+    let swap_amount = swap.amount_lamports;
+    let refund_tip = match refunder {
+        Some(_) => swap.refund_tip_lamports.min(swap_amount),
+        None => 0,
+    };
+    let forfeited_bond = std::mem::take(&mut swap.posted_bond_lamports);
+    swap_account.sub_lamports(swap_amount + forfeited_bond)?;
+    if let Some(refunder) = refunder {
+        refunder.add_lamports(refund_tip)?;
+    }
+    destination.add_lamports(swap_amount - refund_tip + forfeited_bond)?;
+
+    Ok(Refunded {
+        swap_account: swap_account.key(),
+        initiator: swap.initiator,
+        redeemer: swap.redeemer,
+        secret_hash: swap.secret_hash,
+        amount_lamports: swap_amount,
+        expiry_slot: swap.expiry_slot,
+        refund_tip_lamports: refund_tip,
+    })
+}
+
+/// Returns the acceptance bond posted by the swap's redeemer, if any, to `redeemer`, which is
+/// required if one was posted
+fn return_acceptance_bond<'info>(
+    swap_account: &AccountLoader<'info, SwapAccount>,
+    swap: &mut SwapAccount,
+    redeemer: Option<&AccountInfo<'info>>,
+) -> Result<()> {
+    if swap.posted_bond_lamports == 0 {
+        return Ok(());
+    }
+    let redeemer = redeemer.ok_or(error!(SwapError::InvalidRedeemer))?;
+    swap_account.sub_lamports(swap.posted_bond_lamports)?;
+    redeemer.add_lamports(swap.posted_bond_lamports)?;
+    swap.posted_bond_lamports = 0;
+    Ok(())
+}
+
+/// Returns the remaining funds escrowed by a swap to its refund destination in full.
+/// Returns the resulting event, for the caller to emit.
+fn instant_refund_swap<'info>(
+    swap_account: &AccountLoader<'info, SwapAccount>,
+    destination: &AccountInfo<'info>,
+    redeemer: Option<&AccountInfo<'info>>,
+) -> Result<InstantRefunded> {
+    let mut swap = swap_account.load_mut()?;
+    return_acceptance_bond(swap_account, &mut swap, redeemer)?;
+    let swap_amount = swap.amount_lamports;
+    swap_account.sub_lamports(swap_amount)?;
+    destination.add_lamports(swap_amount)?;
+
+    Ok(InstantRefunded {
+        swap_account: swap_account.key(),
+        initiator: swap.initiator,
+        redeemer: swap.redeemer,
+        secret_hash: swap.secret_hash,
+        amount_lamports: swap_amount,
+        expiry_slot: swap.expiry_slot,
+    })
+}
+
+/// Returns the slot at which a timelock set at `slot` to expire in `expires_in_slots` slots expires
+fn expiry_slot_after(slot: u64, expires_in_slots: u64) -> Result<u64> {
+    require!(
+        expires_in_slots <= MAX_EXPIRY_SLOTS,
+        SwapError::ExpiryTooLarge
+    );
+    slot.checked_add(expires_in_slots)
+        .ok_or(error!(SwapError::ExpiryTooLarge))
+}
+
+/// Whether a timelock ending at `expiry` (a slot or a unix timestamp) has expired at `now`.
+/// Timelocks are inclusive of their expiry: at `now == expiry` refunds and cancellations
+/// are already allowed, while accepts, order fills, channel payment claims and session use
+/// are no longer allowed. A swap's expiry does not end redeems, which remain allowed until it
+/// is refunded, unless its `RedeemWindow` closes first as per `RedeemWindow::is_open`.
+fn has_expired(now: u64, expiry: u64) -> bool {
+    now >= expiry
+}
+
+/// The message signed under `prefix` to authorize an instruction on the swap at `swap_account`:
+/// the prefix, the address, then the slot the swap was initiated at, its expiry slot and its
+/// amount, each as a little-endian u64. Binding the signature to this instance of the swap keeps
+/// it from being replayed against a swap later initiated at the same address
+fn instance_message(
+    prefix: &[u8],
+    swap_account: &Pubkey,
+    created_at_slot: u64,
+    expiry_slot: u64,
+    amount_lamports: u64,
+) -> Vec<u8> {
+    [
+        prefix,
+        swap_account.as_ref(),
+        &created_at_slot.to_le_bytes(),
+        &expiry_slot.to_le_bytes(),
+        &amount_lamports.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// Ensures the instruction preceding the current one is an ed25519 program instruction
+/// verifying a single signature by `signer` over `message`, with the signature, public key
+/// and message all contained within that instruction. Returns the verified signature.
+fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<[u8; 64]> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, SwapError::InvalidConsentSignature);
+    let instruction =
+        load_instruction_at_checked(usize::from(current_index - 1), instructions_sysvar)?;
+    require_keys_eq!(
+        instruction.program_id,
+        ed25519_program::ID,
+        SwapError::InvalidConsentSignature
+    );
+
+    // Refer: https://docs.anza.xyz/runtime/programs#ed25519-program
+    let data = &instruction.data;
+    let read_u16 = |offset: usize| -> Result<u16> {
+        data.get(offset..offset + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(|| error!(SwapError::InvalidConsentSignature))
+    };
+    let read_slice = |offset: u16, len: usize| -> Result<&[u8]> {
+        data.get(usize::from(offset)..usize::from(offset) + len)
+            .ok_or_else(|| error!(SwapError::InvalidConsentSignature))
+    };
+    require!(data.first() == Some(&1), SwapError::InvalidConsentSignature);
+    let signature_offset = read_u16(2)?;
+    let signature_instruction_index = read_u16(4)?;
+    let public_key_offset = read_u16(6)?;
+    let public_key_instruction_index = read_u16(8)?;
+    let message_offset = read_u16(10)?;
+    let message_size = read_u16(12)?;
+    let message_instruction_index = read_u16(14)?;
+
+    // An index of u16::MAX refers to the ed25519 program instruction itself
+    require!(
+        [
+            signature_instruction_index,
+            public_key_instruction_index,
+            message_instruction_index
+        ]
+        .iter()
+        .all(|&index| index == u16::MAX),
+        SwapError::InvalidConsentSignature
+    );
+    require!(
+        read_slice(public_key_offset, 32)? == signer.as_ref(),
+        SwapError::InvalidConsentSignature
+    );
+    require!(
+        read_slice(message_offset, usize::from(message_size))? == message,
+        SwapError::InvalidConsentSignature
+    );
+
+    let mut signature = [0; 64];
+    signature.copy_from_slice(read_slice(signature_offset, 64)?);
+    Ok(signature)
+}
+
+/// Ensures the instruction preceding the current one is a secp256k1 program instruction
+/// verifying a single signature by the key of `eth_address` over `message`, with the signature,
+/// address and message all contained within that instruction
+fn verify_secp256k1_signature(
+    instructions_sysvar: &AccountInfo,
+    eth_address: &[u8; 20],
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, SwapError::InvalidSecp256k1Signature);
+    let instruction_index = current_index - 1;
+    let instruction =
+        load_instruction_at_checked(usize::from(instruction_index), instructions_sysvar)?;
+    require_keys_eq!(
+        instruction.program_id,
+        secp256k1_program::ID,
+        SwapError::InvalidSecp256k1Signature
+    );
+
+    // Refer: https://docs.anza.xyz/runtime/programs#secp256k1-program
+    let data = &instruction.data;
+    let read_u16 = |offset: usize| -> Result<u16> {
+        data.get(offset..offset + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(|| error!(SwapError::InvalidSecp256k1Signature))
+    };
+    let read_slice = |offset: u16, len: usize| -> Result<&[u8]> {
+        data.get(usize::from(offset)..usize::from(offset) + len)
+            .ok_or_else(|| error!(SwapError::InvalidSecp256k1Signature))
+    };
+    require!(
+        data.first() == Some(&1) && data.len() > 11,
+        SwapError::InvalidSecp256k1Signature
+    );
+    let eth_address_offset = read_u16(4)?;
+    let message_offset = read_u16(7)?;
+    let message_size = read_u16(9)?;
+
+    // Unlike the ed25519 program's, these index the instructions of the transaction
+    require!(
+        [data[3], data[6], data[11]]
+            .iter()
+            .all(|&index| u16::from(index) == instruction_index),
+        SwapError::InvalidSecp256k1Signature
+    );
+    require!(
+        read_slice(eth_address_offset, 20)? == eth_address,
+        SwapError::InvalidSecp256k1Signature
+    );
+    require!(
+        read_slice(message_offset, usize::from(message_size))? == message,
+        SwapError::InvalidSecp256k1Signature
+    );
+
+    Ok(())
+}
+
+/// Stores the state information of an SPL token atomic swap on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct TokenSwapAccount {
+    /// The quantity of tokens sent by the initiator through this atomic swap in base units of the mint
+    amount: u64,
+    /// The quantity of tokens escrowed in the vault after any transfer fees, in base units of the mint
+    net_amount: u64,
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    secret_hash: [u8; 32],
+    /// The mint of the tokens being swapped
+    mint: Pubkey,
+    /// The bump of this PDA, required for signing transfers out of the token vault
+    bump: u8,
+}
+
+/// Stores the state information of a bundle swap of native SOL and SPL tokens on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct BundleSwapAccount {
+    /// The quantity of native SOL escrowed in this account on top of its rent, in lamports
+    amount_lamports: u64,
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    secret_hash: [u8; 32],
+    /// The tokens escrowed in vaults owned by this account
+    #[max_len(MAX_BUNDLE_TOKENS)]
+    tokens: Vec<BundledToken>,
+    /// The bump of this PDA, required for signing transfers out of the vaults
+    bump: u8,
+}
+
+/// A token escrowed by a bundle swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct BundledToken {
+    pub mint: Pubkey,
+    /// The token account owned by the bundle swap account escrowing the tokens
+    pub vault: Pubkey,
+    /// The quantity of tokens sent by the initiator in base units of the mint
+    pub amount: u64,
+    /// The quantity of tokens escrowed after any transfer fees in base units of the mint
+    pub net_amount: u64,
+}
+
+/// Stores the state information of a streamed atomic swap on-chain, whose native SOL is
+/// released to the redeemer linearly once the secret is revealed
+#[account]
+#[derive(InitSpace)]
+pub struct StreamedSwapAccount {
+    /// The quantity of native SOL escrowed in this account on top of its rent upon initiation,
+    /// in lamports
+    amount_lamports: u64,
+    /// The quantity of native SOL claimed by the redeemer so far, in lamports
+    claimed_lamports: u64,
+    /// The exact slot after which refunds are allowed, unless the secret has been revealed
+    expiry_slot: u64,
+    /// The number of slots over which the funds unlock once the secret is revealed
+    stream_slots: u64,
+    /// The slot the secret was revealed at, if it has been
+    revealed_at_slot: Option<u64>,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    secret_hash: [u8; 32],
+    /// The bump of this PDA
+    bump: u8,
+}
+
+impl StreamedSwapAccount {
+    /// The quantity of native SOL unlocked as of `slot`, including those already claimed
+    fn unlocked_lamports(&self, slot: u64) -> Result<u64> {
+        let revealed_at_slot = self
+            .revealed_at_slot
+            .ok_or(error!(SwapError::StreamNotRevealed))?;
+        let elapsed = slot.saturating_sub(revealed_at_slot).min(self.stream_slots);
+        let unlocked =
+            u128::from(self.amount_lamports) * u128::from(elapsed) / u128::from(self.stream_slots);
+        Ok(unlocked as u64)
+    }
+}
+
+/// Stores the state information of a series of recurring atomic swaps committed to a hash chain
+/// on-chain, along with the position within the chain
+#[account]
+#[derive(InitSpace)]
+pub struct HashChainSwapAccount {
+    /// The quantity of native SOL transferred by each swap of the series, in lamports
+    amount_lamports: u64,
+    /// The number of swaps of the series yet to be redeemed, i.e. the position within the chain
+    remaining_swaps: u32,
+    /// The number of slots after which the remaining swaps may be refunded, counted from
+    /// initiation and from every redeem
+    expires_in_slots: u64,
+    /// The exact slot after which the remaining swaps may be refunded
+    expiry_slot: u64,
+    /// The initiator of the series
+    initiator: Pubkey,
+    /// The redeemer of the series
+    redeemer: Pubkey,
+    /// The hash the series was initiated with, being part of this PDA's seeds
+    chain_tip: [u8; 32],
+    /// The hash the next swap of the series is committed to
+    chain_hash: [u8; 32],
+    /// The bump of this PDA
+    bump: u8,
+}
+
+/// Stores the state information of a PTLC atomic swap on-chain, locked to a public point rather
+/// than a secret hash
+#[account]
+#[derive(InitSpace)]
+pub struct PtlcSwapAccount {
+    /// The quantity of native SOL escrowed in this account on top of its rent, in lamports
+    amount_lamports: u64,
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The ed25519 public point whose secret scalar unlocks the atomic swap
+    point: Pubkey,
+    /// The bump of this PDA
+    bump: u8,
+    /// The slot the atomic swap was initiated at
+    created_at_slot: u64,
+}
+
+impl PtlcSwapAccount {
+    /// The message signed under the swap's point to claim the swap at `swap_account`, bound to
+    /// this instance of the swap as per `instance_message`
+    pub fn claim_message(&self, swap_account: &Pubkey) -> Vec<u8> {
+        instance_message(
+            PTLC_CLAIM_PREFIX,
+            swap_account,
+            self.created_at_slot,
+            self.expiry_slot,
+            self.amount_lamports,
+        )
+    }
+}
+
+/// Stores the state information of a secp256k1 atomic swap on-chain, claimed with a signature by
+/// a committed secp256k1 key rather than a secret
+#[account]
+#[derive(InitSpace)]
+pub struct Secp256k1SwapAccount {
+    /// The quantity of native SOL escrowed in this account on top of its rent, in lamports
+    amount_lamports: u64,
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The Ethereum address of the secp256k1 key whose signature claims the atomic swap
+    eth_address: [u8; 20],
+    /// The bump of this PDA
+    bump: u8,
+    /// The slot the atomic swap was initiated at
+    created_at_slot: u64,
+}
+
+impl Secp256k1SwapAccount {
+    /// The message signed by the committed key to claim the swap at `swap_account`, bound to
+    /// this instance of the swap as per `instance_message`
+    pub fn claim_message(&self, swap_account: &Pubkey) -> Vec<u8> {
+        instance_message(
+            SECP256K1_CLAIM_PREFIX,
+            swap_account,
+            self.created_at_slot,
+            self.expiry_slot,
+            self.amount_lamports,
+        )
+    }
+}
+
+/// Stores the state information of a zero-knowledge atomic swap on-chain, redeemed with a proof
+/// of knowledge of the secret rather than the secret itself
+#[account]
+#[derive(InitSpace)]
+pub struct ZkSwapAccount {
+    /// The quantity of native SOL escrowed in this account on top of its rent, in lamports
+    amount_lamports: u64,
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The SHA-256 hash of the secret, whose knowledge is proven to redeem the atomic swap
+    secret_hash: [u8; 32],
+    /// The verifying key of the circuit proving knowledge of the secret
+    verifying_key: Groth16VerifyingKey,
+    /// The bump of this PDA
+    bump: u8,
+}
+
+/// The number of public inputs of a preimage proof: the upper and lower 16 bytes of the secret
+/// hash, each of which fits in a BN254 scalar unlike the whole hash
+const PREIMAGE_PROOF_PUBLIC_INPUTS: usize = 2;
+
+/// The base field modulus of BN254, in big-endian
+const BN254_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// A Groth16 verifying key over BN254, with points encoded as by the `alt_bn128` syscalls,
+/// i.e. big-endian coordinates with the imaginary part of G2 coordinates first
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    /// The G1 points the public inputs are combined over, the first being the constant term
+    pub ic: [[u8; 64]; PREIMAGE_PROOF_PUBLIC_INPUTS + 1],
+}
+
+/// A Groth16 proof over BN254, encoded as `Groth16VerifyingKey`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Groth16Proof {
+    pub a: [u8; 64],
+    pub b: [u8; 128],
+    pub c: [u8; 64],
+}
+
+impl Groth16VerifyingKey {
+    /// Whether `proof` proves knowledge of the preimage of `secret_hash` under this key,
+    /// i.e. e(-A, B) · e(alpha, beta) · e(vk_x, gamma) · e(C, delta) = 1
+    pub fn verify(&self, proof: &Groth16Proof, secret_hash: &[u8; 32]) -> bool {
+        self.try_verify(proof, secret_hash).unwrap_or(false)
+    }
+
+    fn try_verify(
+        &self,
+        proof: &Groth16Proof,
+        secret_hash: &[u8; 32],
+    ) -> std::result::Result<bool, solana_bn254::AltBn128Error> {
+        let mut vk_x = self.ic[0].to_vec();
+        for (ic, half) in self.ic[1..].iter().zip(secret_hash.chunks(16)) {
+            let mut scalar = [0; 32];
+            scalar[16..].copy_from_slice(half);
+            let term = alt_bn128_multiplication(&[ic.as_slice(), &scalar].concat())?;
+            vk_x = alt_bn128_addition(&[vk_x, term].concat())?;
+        }
+
+        let pairing = alt_bn128_pairing(
+            &[
+                &negate_g1(&proof.a)[..],
+                &proof.b,
+                &self.alpha_g1,
+                &self.beta_g2,
+                &vk_x,
+                &self.gamma_g2,
+                &proof.c,
+                &self.delta_g2,
+            ]
+            .concat(),
+        )?;
+        Ok(pairing.last() == Some(&1) && pairing[..31].iter().all(|&byte| byte == 0))
+    }
+}
+
+/// Negates the BN254 G1 `point` by replacing its y coordinate with its additive inverse
+fn negate_g1(point: &[u8; 64]) -> [u8; 64] {
+    let mut negated = *point;
+    if point[32..].iter().all(|&byte| byte == 0) {
+        // The point at infinity is its own negation
+        return negated;
+    }
+    let mut borrow = false;
+    for i in (0..32).rev() {
+        let (difference, underflow) = BN254_FIELD_MODULUS[i].overflowing_sub(point[32 + i]);
+        let (difference, borrowed) = difference.overflowing_sub(u8::from(borrow));
+        negated[32 + i] = difference;
+        borrow = underflow || borrowed;
+    }
+    negated
+}
+
+/// Stores the terms of an order posted on-chain, escrowing the native SOL it offers until it is
+/// filled by a taker or cancelled
+#[account]
+#[derive(InitSpace)]
+pub struct Order {
+    /// The maker of the order, who becomes the initiator of the swap filling it
+    maker: Pubkey,
+    /// The identifier distinguishing this order from the maker's others
+    order_id: u64,
+    /// The quantity of native SOL offered, in lamports
+    amount_lamports: u64,
+    /// The quantity of native SOL offered yet to be filled, escrowed in this account on top of
+    /// its rent and the rent of the swap accounts of the remaining fills, in lamports
+    remaining_lamports: u64,
+    /// The minimum quantity of native SOL each fill but the last must take, in lamports
+    min_fill_lamports: u64,
+    /// The chain the maker is to receive the counterpart asset on, in zero-padded UTF-8
+    counterpart_chain: [u8; 32],
+    /// The counterpart asset the maker asks for, in zero-padded UTF-8
+    counterpart_asset: [u8; 32],
+    /// The quantity of the counterpart asset the maker asks for, in its base units
+    counterpart_amount: u64,
+    /// The minimum number of slots the swap filling the order may expire in
+    min_expires_in_slots: u64,
+    /// The maximum number of slots the swap filling the order may expire in
+    max_expires_in_slots: u64,
+    /// The exact slot from which the order may no longer be filled
+    expiry_slot: u64,
+    /// The bump of this PDA
+    bump: u8,
+}
+
+/// Stores the state information of an oracle-priced atomic swap on-chain, releasing native SOL
+/// worth a USD notional upon redeem
+#[account]
+#[derive(InitSpace)]
+pub struct OracleSwapAccount {
+    /// The maximum quantity of native SOL released upon redeem, escrowed in this account on top
+    /// of its rent, in lamports
+    amount_lamports: u64,
+    /// The USD value of the native SOL released upon redeem, in millionths of a USD
+    notional_usd_micros: u64,
+    /// The maximum age of the price redeems are priced at, in seconds
+    max_staleness_secs: u64,
+    /// The maximum confidence interval of the price redeems are priced at, in basis points of
+    /// the price
+    max_confidence_bps: u16,
+    /// The minimum quantity of native SOL released upon redeem, in lamports
+    min_lamports_out: u64,
+    /// The maximum quantity of native SOL released upon redeem, in lamports
+    max_lamports_out: u64,
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    secret_hash: [u8; 32],
+    /// The bump of this PDA
+    bump: u8,
+}
+
+/// A SOL/USD price read from a Pyth `PriceUpdateV2` account, being `price` × 10^`exponent`
+/// USD per SOL, give or take `conf` × 10^`exponent`
+struct PythPrice {
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+}
+
+impl PythPrice {
+    /// Reads the fully verified SOL/USD price of the `price_update` account
+    fn load(price_update: &AccountInfo) -> Result<Self> {
+        // Refer: https://github.com/pyth-network/pyth-crosschain/blob/main/target_chains/solana/pyth_solana_receiver_sdk/src/price_update.rs
+        // The discriminator is followed by the write authority, then the verification level,
+        // of which only the `Full` variant, encoded as a single byte, is accepted
+        const VERIFICATION_LEVEL: usize = ANCHOR_DISCRIMINATOR + 32;
+        const VERIFICATION_LEVEL_FULL: u8 = 1;
+        const FEED_ID: usize = VERIFICATION_LEVEL + 1;
+        const PRICE: usize = FEED_ID + 32;
+
+        require_keys_eq!(
+            *price_update.owner,
+            PYTH_RECEIVER_PROGRAM_ID,
+            SwapError::InvalidPriceUpdate
+        );
+        let data = price_update.try_borrow_data()?;
+        let read = |offset: usize| -> Result<[u8; 8]> {
+            data.get(offset..offset + 8)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| error!(SwapError::InvalidPriceUpdate))
+        };
+        require!(
+            data.get(..ANCHOR_DISCRIMINATOR) == Some(&PYTH_PRICE_UPDATE_DISCRIMINATOR[..])
+                && data.get(VERIFICATION_LEVEL) == Some(&VERIFICATION_LEVEL_FULL)
+                && data.get(FEED_ID..PRICE) == Some(&PYTH_SOL_USD_FEED_ID[..]),
+            SwapError::InvalidPriceUpdate
+        );
+
+        let exponent = read(PRICE + 16)?;
+        Ok(PythPrice {
+            price: i64::from_le_bytes(read(PRICE)?),
+            conf: u64::from_le_bytes(read(PRICE + 8)?),
+            exponent: i32::from_le_bytes([exponent[0], exponent[1], exponent[2], exponent[3]]),
+            publish_time: i64::from_le_bytes(read(PRICE + 20)?),
+        })
+    }
+
+    /// Ensures the price was published within `max_staleness_secs` of `now`, with its confidence
+    /// interval within `max_confidence_bps` basis points of it
+    fn validate(&self, now: i64, max_staleness_secs: u64, max_confidence_bps: u16) -> Result<()> {
+        require!(
+            i128::from(now) - i128::from(self.publish_time) <= i128::from(max_staleness_secs),
+            SwapError::StalePrice
+        );
+        require!(
+            self.price > 0
+                && u128::from(self.conf) * u128::from(BPS_DENOMINATOR)
+                    <= self.price as u128 * u128::from(max_confidence_bps),
+            SwapError::PriceTooUncertain
+        );
+        Ok(())
+    }
+
+    /// The lamports worth `notional_usd_micros` at this price, if representable
+    fn lamports_for(&self, notional_usd_micros: u64) -> Option<u64> {
+        // Lamports are 10^-9 SOL, whereas micros are 10^-6 USD
+        let notional = u128::from(notional_usd_micros) * 1_000;
+        let price = u128::try_from(self.price).ok().filter(|&price| price > 0)?;
+        let scale = 10u128.checked_pow(self.exponent.unsigned_abs())?;
+        let lamports = match self.exponent {
+            ..0 => notional.checked_mul(scale)? / price,
+            _ => notional / price.checked_mul(scale)?,
+        };
+        u64::try_from(lamports).ok()
+    }
+}
+
+/// Stores the state information of a Wormhole atomic swap on-chain, redeemed with a VAA attesting
+/// to the settlement of the counterpart leg rather than the secret
+#[account]
+#[derive(InitSpace)]
+pub struct WormholeSwapAccount {
+    /// The quantity of native SOL escrowed in this account on top of its rent, in lamports
+    amount_lamports: u64,
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The identifier of the atomic swap between its parties
+    swap_id: [u8; 32],
+    /// The Wormhole chain ID of the emitter attesting to the counterpart leg's settlement
+    emitter_chain: u16,
+    /// The address of the emitter attesting to the counterpart leg's settlement
+    emitter_address: [u8; 32],
+    /// The bump of this PDA
+    bump: u8,
+}
+
+/// The fields of a VAA posted to the Wormhole core bridge a Wormhole swap is redeemed with
+struct PostedVaa {
+    sequence: u64,
+    emitter_chain: u16,
+    emitter_address: [u8; 32],
+    /// The swap account attested to, being the first 32 bytes of the payload
+    swap_account: Pubkey,
+}
+
+impl PostedVaa {
+    /// Reads the VAA posted to the `posted_vaa` account
+    fn load(posted_vaa: &AccountInfo) -> Result<Self> {
+        // Refer: https://github.com/wormhole-foundation/wormhole/blob/main/solana/bridge/program/src/accounts/posted_vaa.rs
+        // The magic is followed by the version, consistency level, VAA time, signature set,
+        // submission time and nonce, then the fields below
+        const MAGIC: &[u8] = b"vaa";
+        const SEQUENCE: usize = 49;
+        const EMITTER_CHAIN: usize = SEQUENCE + 8;
+        const EMITTER_ADDRESS: usize = EMITTER_CHAIN + 2;
+        const PAYLOAD: usize = EMITTER_ADDRESS + 32 + 4;
+
+        require_keys_eq!(
+            *posted_vaa.owner,
+            WORMHOLE_CORE_BRIDGE_PROGRAM_ID,
+            SwapError::InvalidPostedVaa
+        );
+        let data = posted_vaa.try_borrow_data()?;
+        require!(
+            data.get(..MAGIC.len()) == Some(MAGIC),
+            SwapError::InvalidPostedVaa
+        );
+        let field = |offset: usize, len: usize| {
+            data.get(offset..offset + len)
+                .ok_or_else(|| error!(SwapError::InvalidPostedVaa))
+        };
+        let payload_len = u32::from_le_bytes(field(PAYLOAD - 4, 4)?.try_into().unwrap());
+        require!(payload_len >= 32, SwapError::InvalidVaaPayload);
+
+        Ok(PostedVaa {
+            sequence: u64::from_le_bytes(field(SEQUENCE, 8)?.try_into().unwrap()),
+            emitter_chain: u16::from_le_bytes(field(EMITTER_CHAIN, 2)?.try_into().unwrap()),
+            emitter_address: field(EMITTER_ADDRESS, 32)?.try_into().unwrap(),
+            swap_account: Pubkey::try_from(field(PAYLOAD, 32)?).unwrap(),
+        })
+    }
+}
+
+/// Stores the state information of a confidential token atomic swap on-chain, which, unlike
+/// `TokenSwapAccount`, does not record the amount escrowed
+#[account]
+#[derive(InitSpace)]
+pub struct ConfidentialSwapAccount {
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    secret_hash: [u8; 32],
+    /// The mint of the tokens being swapped
+    mint: Pubkey,
+    /// The bump of this PDA, required for signing transfers out of the token vault
+    bump: u8,
+}
+
+/// Stores the state information of an NFT atomic swap on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct NftSwapAccount {
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    secret_hash: [u8; 32],
+    /// The mint of the NFT being swapped
+    mint: Pubkey,
+    /// The metadata account of the NFT, verified upon initiation
+    metadata: Pubkey,
+    /// The bump of this PDA, required for signing transfers out of the NFT vault
+    bump: u8,
+}
+
+/// Stores the state information of a compressed NFT atomic swap on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct CnftSwapAccount {
+    /// The exact slot after which refunds are allowed
+    expiry_slot: u64,
+    /// The initiator of the atomic swap
+    initiator: Pubkey,
+    /// The redeemer of the atomic swap
+    redeemer: Pubkey,
+    /// The secret hash associated with the atomic swap
+    secret_hash: [u8; 32],
+    /// The asset id of the cNFT being swapped
+    asset_id: Pubkey,
+    /// The merkle tree holding the leaf of the cNFT
+    merkle_tree: Pubkey,
+    /// The leaf of the cNFT, owned by this PDA while escrowed
+    leaf: CompressedLeaf,
+    /// The bump of this PDA, required for signing the transfer of the cNFT
+    bump: u8,
+}
+
+/// The contents of a compressed NFT's leaf, besides its owner and delegate, as hashed by Bubblegum
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct CompressedLeaf {
+    /// The hash of the cNFT's metadata
+    pub data_hash: [u8; 32],
+    /// The hash of the cNFT's creators
+    pub creator_hash: [u8; 32],
+    /// The nonce the cNFT was minted with, from which its asset id is derived
+    pub nonce: u64,
+    /// The index of the leaf in the merkle tree
+    pub index: u32,
+}
+
+/// Stores the program-wide configuration on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    /// The key permitted to update this config
+    admin: Pubkey,
+    /// The minimum number of slots a swap may be initiated to expire in
+    min_expiry_slots: u64,
+    /// The maximum number of slots a swap may be initiated to expire in
+    max_expiry_slots: u64,
+    /// The fee deducted from swaps upon redeem and paid to the treasury, in basis points
+    protocol_fee_bps: u16,
+    /// Whether the initiation of new swaps is paused
+    initiate_paused: bool,
+    /// The transfer hook programs permitted for the mints of token swaps initiated hereafter
+    #[max_len(MAX_TRANSFER_HOOK_PROGRAMS)]
+    transfer_hook_programs: Vec<Pubkey>,
+}
+
+impl Config {
+    /// Ensures a swap expiring in `expires_in_slots` slots is within the configured bounds
+    fn validate_expiry(&self, expires_in_slots: u64) -> Result<()> {
+        require!(
+            (self.min_expiry_slots..=self.max_expiry_slots).contains(&expires_in_slots),
+            SwapError::ExpiryOutOfBounds
+        );
+        Ok(())
+    }
+
+    /// Ensures any transfer hook of `mint` invokes a permitted program
+    fn validate_transfer_hook(&self, mint: &AccountInfo) -> Result<()> {
+        let hook_program = get_mint_extension_data::<TransferHook>(mint)
+            .ok()
+            .and_then(|hook| Option::<Pubkey>::from(hook.program_id));
+        if let Some(hook_program) = hook_program {
+            require!(
+                self.transfer_hook_programs.contains(&hook_program),
+                SwapError::TransferHookNotPermitted
+            );
+        }
+        Ok(())
+    }
+
+    /// The protocol fee due on redeeming a swap of `amount_lamports`
+    fn protocol_fee(&self, amount_lamports: u64) -> u64 {
+        (u128::from(amount_lamports) * u128::from(self.protocol_fee_bps)
+            / u128::from(BPS_DENOMINATOR)) as u64
+    }
+}
+
+/// Collects the protocol fees paid upon redeems
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {}
+
+/// Retains the secret revealed by a redeem, allowing it to be fetched from account state
+/// after the swap account has been closed
+#[account]
+#[derive(InitSpace)]
+pub struct RedeemReceipt {
+    /// The swap account that was redeemed
+    swap_account: Pubkey,
+    /// The secret hash of the swap
+    secret_hash: [u8; 32],
+    /// The secret revealed by the redeem
+    secret: [u8; 32],
+    /// The slot the secret was revealed at
+    slot: u64,
+    /// The payer of the receipt's rent, to whom it is returned upon closure
+    payer: Pubkey,
+}
+
+/// Transfers the tokens escrowed by a token swap from its vault to `destination` and closes the
+/// vault, transferring the vault's rent to the initiator.
+/// Any transfer fees withheld in the vault are harvested to the mint beforehand,
+/// as Token-2022 does not allow closing accounts with withheld fees.
+fn release_token_vault<'info>(
+    swap_account: &Account<'info, TokenSwapAccount>,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    initiator: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    extra_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[
+        b"token_swap_account",
+        swap_account.initiator.as_ref(),
+        swap_account.redeemer.as_ref(),
+        &swap_account.secret_hash,
+        &[swap_account.bump],
+    ];
+    release_vault(
+        &token_vault.to_account_info(),
+        &destination.to_account_info(),
+        mint,
+        swap_account.net_amount,
+        initiator,
+        token_program,
+        &swap_account.to_account_info(),
+        extra_accounts,
+        &[seeds],
+    )
+}
+
+/// Transfers `amount` of `mint` from `from` to `to` through `transfer_checked`, with the extra
+/// accounts of any transfer hook of the mint resolved from `extra_accounts` as per the transfer
+/// hook interface, e.g. its validation account, its program and the accounts it lists
+#[allow(clippy::too_many_arguments)]
+fn transfer_with_hook<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    from: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    onchain::invoke_transfer_checked(
+        &token_program.key(),
+        from.clone(),
+        mint.to_account_info(),
+        to.clone(),
+        authority.clone(),
+        extra_accounts,
+        amount,
+        mint.decimals,
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Unwraps the wrapped SOL escrowed by a token swap of the native mint to `recipient`, by closing
+/// the vault into the swap's PDA and paying out the escrowed lamports from there, leaving the
+/// rent of the vault to be returned to the initiator along with that of the PDA
+fn unwrap_token_vault<'info>(
+    swap_account: &Account<'info, TokenSwapAccount>,
+    token_vault: &Account<'info, token::TokenAccount>,
+    recipient: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[
+        b"token_swap_account",
+        swap_account.initiator.as_ref(),
+        swap_account.redeemer.as_ref(),
+        &swap_account.secret_hash,
+        &[swap_account.bump],
+    ];
+    let signer_seeds = &[seeds];
+    let close_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::CloseAccount {
+            account: token_vault.to_account_info(),
+            destination: swap_account.to_account_info(),
+            authority: swap_account.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::close_account(close_context)?;
+
+    swap_account.sub_lamports(swap_account.net_amount)?;
+    recipient.add_lamports(swap_account.net_amount)?;
+    Ok(())
+}
+
+/// Confidentially transfers the escrowed tokens of a confidential swap to `destination`, then
+/// empties and closes the vault, returning its rent to the initiator
+#[allow(clippy::too_many_arguments)]
+fn release_confidential_vault<'info>(
+    swap_account: &Account<'info, ConfidentialSwapAccount>,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    transfer_proofs: [&UncheckedAccount<'info>; 3],
+    zero_balance_proof: &UncheckedAccount<'info>,
+    initiator: &AccountInfo<'info>,
+    token_program: &Program<'info, Token2022>,
+    vault_decryptable_zero_balance: [u8; 36],
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[
+        b"confidential_swap_account",
+        swap_account.initiator.as_ref(),
+        swap_account.redeemer.as_ref(),
+        &swap_account.secret_hash,
+        &[swap_account.bump],
+    ];
+    let signer_seeds = &[seeds];
+
+    confidential_transfer_cpi(
+        &token_program.key(),
+        &token_vault.to_account_info(),
+        &mint.to_account_info(),
+        destination,
+        &swap_account.to_account_info(),
+        transfer_proofs,
+        vault_decryptable_zero_balance,
+        signer_seeds,
+    )?;
+
+    let empty = confidential_transfer::instruction::inner_empty_account(
+        &token_program.key(),
+        &token_vault.key(),
+        &swap_account.key(),
+        &[],
+        ProofLocation::ContextStateAccount(&zero_balance_proof.key()),
+    )?;
+    invoke_signed(
+        &empty,
+        &[
+            token_vault.to_account_info(),
+            zero_balance_proof.to_account_info(),
+            swap_account.to_account_info(),
+        ],
+        signer_seeds,
+    )?;
+
+    let close_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: token_vault.to_account_info(),
+            destination: initiator.to_account_info(),
+            authority: swap_account.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token_interface::close_account(close_context)
+}
+
+/// Invokes Token-2022's confidential `transfer` from `source` to `destination`, with its equality,
+/// ciphertext validity and range proofs verified beforehand into the context state accounts
+/// `proofs`, and `source_decryptable_balance` the AES ciphertext of the source's resulting
+/// available balance
+#[allow(clippy::too_many_arguments)]
+fn confidential_transfer_cpi<'info>(
+    token_program: &Pubkey,
+    source: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    proofs: [&UncheckedAccount<'info>; 3],
+    source_decryptable_balance: [u8; 36],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let [equality_proof, ciphertext_validity_proof, range_proof] = proofs;
+    let transfer = confidential_transfer::instruction::inner_transfer(
+        token_program,
+        source.key,
+        mint.key,
+        destination.key,
+        decryptable_balance(source_decryptable_balance),
+        authority.key,
+        &[],
+        ProofLocation::ContextStateAccount(&equality_proof.key()),
+        ProofLocation::ContextStateAccount(&ciphertext_validity_proof.key()),
+        ProofLocation::ContextStateAccount(&range_proof.key()),
+    )?;
+    invoke_signed(
+        &transfer,
+        &[
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            equality_proof.to_account_info(),
+            ciphertext_validity_proof.to_account_info(),
+            range_proof.to_account_info(),
+            authority.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Reads the AES ciphertext of a decryptable balance from its bytes
+fn decryptable_balance(bytes: [u8; 36]) -> DecryptableBalance {
+    bytemuck::cast(bytes)
+}
+
+/// Transfers the NFT escrowed by an NFT swap to `destination`, then closes the vault,
+/// returning its rent to the initiator
+fn release_nft_vault<'info>(
+    swap_account: &Account<'info, NftSwapAccount>,
+    nft_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    initiator: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[
+        b"nft_swap_account",
+        swap_account.initiator.as_ref(),
+        swap_account.redeemer.as_ref(),
+        &swap_account.secret_hash,
+        &[swap_account.bump],
+    ];
+    release_vault(
+        &nft_vault.to_account_info(),
+        &destination.to_account_info(),
+        mint,
+        1,
+        initiator,
+        token_program,
+        &swap_account.to_account_info(),
+        &[],
+        &[seeds],
+    )
+}
+
+/// Transfers the cNFT escrowed by a cNFT swap to `recipient` through Bubblegum, signing as the
+/// owner of its leaf
+#[allow(clippy::too_many_arguments)]
+fn release_cnft<'info>(
+    swap_account: &Account<'info, CnftSwapAccount>,
+    recipient: &AccountInfo<'info>,
+    tree_authority: &AccountInfo<'info>,
+    merkle_tree: &AccountInfo<'info>,
+    log_wrapper: &AccountInfo<'info>,
+    compression_program: &AccountInfo<'info>,
+    bubblegum_program: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    proof: &[AccountInfo<'info>],
+    root: [u8; 32],
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[
+        b"cnft_swap_account",
+        swap_account.initiator.as_ref(),
+        swap_account.redeemer.as_ref(),
+        &swap_account.secret_hash,
+        &[swap_account.bump],
+    ];
+    // Transfers reset the delegate of a leaf to its new owner
+    let swap_account_info = swap_account.to_account_info();
+    transfer_cnft(
+        bubblegum_program,
+        [
+            tree_authority.clone(),
+            swap_account_info.clone(),
+            swap_account_info,
+            recipient.clone(),
+            merkle_tree.clone(),
+            log_wrapper.clone(),
+            compression_program.clone(),
+            system_program.to_account_info(),
+        ],
+        proof,
+        &swap_account.leaf,
+        root,
+        &[seeds],
+    )
+}
+
+/// Invokes Bubblegum's `transfer` of `leaf`, verified against `root` with `proof`. `accounts`
+/// are those of the instruction in its order: the tree authority, the leaf owner, the leaf
+/// delegate, the new leaf owner, the merkle tree, the noop program, the account compression
+/// program and the system program.
+fn transfer_cnft<'info>(
+    bubblegum_program: &AccountInfo<'info>,
+    accounts: [AccountInfo<'info>; 8],
+    proof: &[AccountInfo<'info>],
+    leaf: &CompressedLeaf,
+    root: [u8; 32],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = BUBBLEGUM_TRANSFER_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&root);
+    data.extend_from_slice(&leaf.data_hash);
+    data.extend_from_slice(&leaf.creator_hash);
+    data.extend_from_slice(&leaf.nonce.to_le_bytes());
+    data.extend_from_slice(&leaf.index.to_le_bytes());
+
+    let mut account_metas: Vec<_> = accounts
+        .iter()
+        .map(|account| AccountMeta::new_readonly(account.key(), false))
+        .collect();
+    // The leaf owner signs, and the merkle tree is updated
+    account_metas[1].is_signer = true;
+    account_metas[4].is_writable = true;
+    account_metas.extend(
+        proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(node.key(), false)),
+    );
+    let instruction = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: account_metas,
+        data,
+    };
+
+    let account_infos: Vec<_> = accounts
+        .iter()
+        .chain(proof)
+        .chain([bubblegum_program])
+        .cloned()
+        .collect();
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+    Ok(())
+}
+
+/// Ensures `mint` is that of an NFT, as described by `initiate_nft`, and `metadata` is its
+/// metadata account, returning the address of the latter
+fn validate_nft(mint: &InterfaceAccount<Mint>, metadata: &AccountInfo) -> Result<Pubkey> {
+    require!(mint.supply == 1 && mint.decimals == 0, SwapError::NotAnNft);
+    let mint_info = mint.to_account_info();
+    // Transfer fees would leave nothing of a single token for the redeemer
+    require!(
+        get_mint_extension_data::<TransferFeeConfig>(&mint_info).is_err(),
+        SwapError::NotAnNft
+    );
+
+    let metadata_pointer = get_mint_extension_data::<MetadataPointer>(&mint_info)
+        .ok()
+        .and_then(|pointer| Option::<Pubkey>::from(pointer.metadata_address));
+    let master_edition = match metadata_pointer {
+        Some(metadata_address) => {
+            require_keys_eq!(
+                metadata.key(),
+                metadata_address,
+                SwapError::InvalidNftMetadata
+            );
+            None
+        }
+        None => {
+            require_keys_eq!(
+                metadata.key(),
+                pda::metaplex_metadata(&mint.key()).0,
+                SwapError::InvalidNftMetadata
+            );
+            require_keys_eq!(
+                *metadata.owner,
+                TOKEN_METADATA_PROGRAM_ID,
+                SwapError::InvalidNftMetadata
+            );
+            Some(pda::metaplex_master_edition(&mint.key()).0)
+        }
+    };
+
+    // Either way, no further tokens of the mint can be minted
+    let mint_authority = Option::<Pubkey>::from(mint.mint_authority);
+    require!(
+        mint_authority.is_none() || mint_authority == master_edition,
+        SwapError::NotAnNft
+    );
+
+    Ok(metadata.key())
+}
+
+/// Transfers `amount` out of a vault owned by `authority`, a PDA signing with `signer_seeds`,
+/// harvesting any withheld transfer fees to the mint, then closes the vault, returning its rent
+/// to the initiator. `extra_accounts` are those required by any transfer hook of the mint.
+#[allow(clippy::too_many_arguments)]
+fn release_vault<'info>(
+    token_vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+    initiator: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    authority: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    transfer_with_hook(
+        token_program,
+        token_vault,
+        mint,
+        destination,
+        authority,
+        extra_accounts,
+        amount,
+        signer_seeds,
+    )?;
+
+    if get_mint_extension_data::<TransferFeeConfig>(&mint.to_account_info()).is_ok() {
+        let harvest_context = CpiContext::new(
+            token_program.to_account_info(),
+            transfer_fee::HarvestWithheldTokensToMint {
+                token_program_id: token_program.to_account_info(),
+                mint: mint.to_account_info(),
+            },
+        );
+        transfer_fee::harvest_withheld_tokens_to_mint(harvest_context, vec![token_vault.clone()])?;
+    }
+
+    let close_context = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: token_vault.clone(),
+            destination: initiator.to_account_info(),
+            authority: authority.clone(),
+        },
+        signer_seeds,
+    );
+    token_interface::close_account(close_context)
+}
+
+/// Releases the native SOL and tokens escrowed by a bundle swap to `recipient`, closing the
+/// vaults of the tokens to the initiator. The accounts of each token are provided as
+/// `remaining_accounts`, as a triple of the mint, the vault and the recipient's token account,
+/// in the order the swap records its tokens.
+fn release_bundle<'info>(
+    swap_account: &Account<'info, BundleSwapAccount>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    recipient: &AccountInfo<'info>,
+    initiator: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    require!(
+        remaining_accounts.len() == swap_account.tokens.len() * 3,
+        SwapError::BatchAccountsMismatch
+    );
+    let seeds: &[&[u8]] = &[
+        b"bundle_swap_account",
+        swap_account.initiator.as_ref(),
+        swap_account.redeemer.as_ref(),
+        &swap_account.secret_hash,
+        &[swap_account.bump],
+    ];
+
+    for (token, accounts) in swap_account
+        .tokens
+        .iter()
+        .zip(remaining_accounts.chunks_exact(3))
+    {
+        let mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        require_keys_eq!(mint.key(), token.mint, SwapError::InvalidMint);
+        require_keys_eq!(
+            accounts[1].key(),
+            token.vault,
+            SwapError::InvalidBundleVault
+        );
+        let destination = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+        require_keys_eq!(destination.mint, token.mint, SwapError::InvalidMint);
+        require_keys_eq!(
+            destination.owner,
+            recipient.key(),
+            SwapError::InvalidBundleDestination
+        );
+
+        release_vault(
+            &accounts[1],
+            &accounts[2],
+            &mint,
+            token.net_amount,
+            initiator,
+            token_program,
+            &swap_account.to_account_info(),
+            &[],
+            &[seeds],
+        )?;
+    }
+
+    swap_account.sub_lamports(swap_account.amount_lamports)?;
+    recipient.add_lamports(swap_account.amount_lamports)?;
+    Ok(())
+}
+
+/// Escrows a relayer's allowance for paying the transaction fees of a swap's lifecycle instructions
+#[account]
+#[derive(InitSpace)]
+pub struct FeeEscrow {
+    /// The swap account whose lifecycle instructions this allowance pays for
+    swap_account: Pubkey,
+    /// The initiator of the atomic swap, who funded this allowance
+    initiator: Pubkey,
+    /// The relayer authorized to draw from this allowance
+    relayer: Pubkey,
+}
+
+/// Delegates a subset of an authority's lifecycle permissions to a short-lived session key
+#[account]
+#[derive(InitSpace)]
+pub struct SessionToken {
+    /// The key whose permissions are delegated
+    authority: Pubkey,
+    /// The short-lived key permitted to sign on behalf of the authority
+    session_key: Pubkey,
+    /// A bitmask of the `SESSION_SCOPE_*` values the session key is permitted to perform
+    scope: u8,
+    /// The exact slot after which the session is no longer valid
+    expiry_slot: u64,
+}
+
+impl SessionToken {
+    /// Ensures the session is unexpired and permitted to perform `scope`
+    fn authorize(&self, scope: u8) -> Result<()> {
+        require!(
+            self.scope & scope == scope,
+            SwapError::SessionScopeNotPermitted
+        );
+        require!(
+            !has_expired(Clock::get()?.slot, self.expiry_slot),
+            SwapError::SessionExpired
+        );
+        Ok(())
+    }
+}
+
+/// A hash-locked payment pending within a payment channel
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct HashLockedPayment {
+    /// The quantity of native SOL locked in this payment in base units (aka lamports)
+    amount_lamports: u64,
+    /// The exact slot after which this payment can no longer be claimed and may be cancelled
+    expiry_slot: u64,
+    /// The SHA-256 secret hash the payee must reveal the preimage of to claim this payment
+    secret_hash: [u8; 32],
+}
+
+/// Stores the state of a reusable payment channel between a funder and a payee on-chain
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentChannel {
+    /// The party funding the channel's payments
+    funder: Pubkey,
+    /// The party receiving the channel's payments
+    payee: Pubkey,
+    /// The exact slot after which the funder may unilaterally close the channel
+    expiry_slot: u64,
+    /// The quantity of native SOL deposited and not yet paid out in base units (aka lamports)
+    deposit_lamports: u64,
+    /// The hash-locked payments yet to be claimed or cancelled
+    #[max_len(MAX_PENDING_CHANNEL_PAYMENTS)]
+    pending_payments: Vec<HashLockedPayment>,
+}
+
+impl PaymentChannel {
+    /// Locks `amount_lamports` of the unlocked deposit behind `secret_hash`,
+    /// returning the slot after which the payment expires
+    fn lock_payment(
+        &mut self,
+        amount_lamports: u64,
+        expires_in_slots: u64,
+        secret_hash: [u8; 32],
+    ) -> Result<u64> {
+        let expiry_slot = expiry_slot_after(Clock::get()?.slot, expires_in_slots)?;
+        require!(
+            expiry_slot <= self.expiry_slot,
+            SwapError::PaymentOutlivesChannel
+        );
+        require!(
+            amount_lamports <= self.unlocked_lamports(),
+            SwapError::InsufficientChannelDeposit
+        );
+        require!(
+            self.pending_payments.len() < MAX_PENDING_CHANNEL_PAYMENTS,
+            SwapError::TooManyPendingPayments
+        );
+        require!(
+            self.pending_payment(&secret_hash).is_none(),
+            SwapError::DuplicateSecretHash
+        );
+
+        self.pending_payments.push(HashLockedPayment {
+            amount_lamports,
+            expiry_slot,
+            secret_hash,
+        });
+
+        Ok(expiry_slot)
+    }
+
+    /// The portion of the deposit that is not locked behind pending payments
+    fn unlocked_lamports(&self) -> u64 {
+        let locked: u64 = self
+            .pending_payments
+            .iter()
+            .map(|p| p.amount_lamports)
+            .sum();
+        self.deposit_lamports - locked
+    }
+
+    /// The index of the pending payment locked behind `secret_hash`, if any
+    fn pending_payment(&self, secret_hash: &[u8; 32]) -> Option<usize> {
+        self.pending_payments
+            .iter()
+            .position(|p| &p.secret_hash == secret_hash)
+    }
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+// The parameters must have the exact name and order as specified in the underlying function
+// to avoid "seed constraint violation" errors.
+// Refer: https://www.anchor-lang.com/docs/references/account-constraints#instruction-attribute
+// `initiate_with_epoch_expiry` shares this layout, with `expires_in_epochs` in place of `expires_in_slots`.
+// `initiate_with_dual_expiry` shares this layout, with a trailing `expires_in_seconds`.
+// `initiate_multi_secret` and `initiate_any_secret` share this layout, with a trailing `secret_hashes`.
+// `initiate_with_acceptance_bond` shares this layout, with a trailing `acceptance_bond_lamports`.
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32], hash_algorithm: HashAlgorithm)]
+pub struct Initiate<'info> {
+    /// A PDA that maintains the on-chain state of the atomic swap throughout its lifecycle.
+    /// It also serves as the "vault" for this swap, by escrowing the SOL involved in this swap.
+    /// The choice of seeds ensures that any swap with equal `initiator`, `redeemer` and
+    /// `secret_hash` cannot be created until an existing one completes,
+    /// unless initiated with distinct nonces through `initiate_with_nonce`,
+    /// and allows the redeemer to locate the swap without scanning events.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = SWAP_ACCOUNT_SPACE,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    /// Pays the rent of the swap account, which is returned to them upon completion.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// The third party providing the swap amount on behalf of the initiator, if any,
+    /// e.g. a custodian. The initiator remains the beneficiary of refunds.
+    /// They must sign this transaction.
+    #[account(mut)]
+    pub funder: Option<Signer<'info>>,
+
+    /// CHECK: The account refunds are to be paid to in place of the initiator, if any,
+    /// e.g. a cold wallet. Only its address is recorded.
+    pub refund_to: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl Initiate<'_> {
+    /// Escrows the swap amount in the swap account and records the swap's state.
+    /// Returns the resulting event, for the caller to emit.
+    #[allow(clippy::too_many_arguments)]
+    fn open_swap(
+        &mut self,
+        bump: u8,
+        amount_lamports: u64,
+        expiry_slot: u64,
+        expiry_timestamp: Option<i64>,
+        redeemer: Pubkey,
+        secret_hash: [u8; 32],
+        hash_algorithm: HashAlgorithm,
+        incentives: SwapIncentives,
+        redeem_window: RedeemWindow,
+        nonce: u32,
+    ) -> Result<Initiated> {
+        require!(amount_lamports > 0, SwapError::ZeroAmount);
+        let clock = Clock::get()?;
+        let expires_in_slots = expiry_slot - clock.slot;
+        require!(expires_in_slots > 0, SwapError::ZeroExpiry);
+        require_keys_neq!(redeemer, self.initiator.key(), SwapError::SelfSwap);
+        self.config.validate_expiry(expires_in_slots)?;
+        incentives.validate(amount_lamports)?;
+        redeem_window.validate(expiry_slot)?;
+
+        // Initiators the system program cannot debit, i.e. PDAs of other programs initiating
+        // swaps from vaults they own, deposit the swap amount into the swap account on top of
+        // its rent before invoking this program. Only the remainder, if any, is transferred.
+        let rent = Rent::get()?.minimum_balance(SWAP_ACCOUNT_SPACE);
+        let deposited = self.swap_account.get_lamports().saturating_sub(rent);
+        let remainder = amount_lamports.saturating_sub(deposited);
+        if remainder > 0 {
+            let funder = match &self.funder {
+                Some(funder) => funder.to_account_info(),
+                None => self.initiator.to_account_info(),
+            };
+            let transfer_context = CpiContext::new(
+                self.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: funder,
+                    to: self.swap_account.to_account_info(),
+                },
+            );
+            system_program::transfer(transfer_context, remainder)?;
+        }
+
+        *self.swap_account.load_init()? = SwapAccount {
+            version: SWAP_ACCOUNT_VERSION,
+            bump,
+            hash_algorithm: hash_algorithm as u8,
+            nonce: nonce.to_le_bytes(),
+            seeds_version: SWAP_SEEDS_VERSION,
+            amount_lamports,
+            expiry_slot,
+            expiry_timestamp: expiry_timestamp.unwrap_or_default(),
+            relayer_fee_lamports: incentives.relayer_fee_lamports,
+            refund_tip_lamports: incentives.refund_tip_lamports,
+            redeem_after_slot: redeem_window.redeem_after_slot.unwrap_or_default(),
+            redeem_deadline_slot: redeem_window.redeem_deadline_slot.unwrap_or_default(),
+            created_at_slot: clock.slot,
+            created_at_unix: clock.unix_timestamp,
+            initiator: self.initiator.key(),
+            redeemer,
+            secret_hash,
+            refund_to: self
+                .refund_to
+                .as_ref()
+                .map(|refund_to| refund_to.key())
+                .unwrap_or_default(),
+            relayer_fee_start_lamports: incentives.relayer_fee_start(),
+            acceptance_bond_lamports: 0,
+            posted_bond_lamports: 0,
+        };
+
+        Ok(Initiated {
+            swap_account: self.swap_account.key(),
+            swap_amount: amount_lamports,
+            expires_in_slots,
+            expiry_slot,
+            expiry_timestamp,
+            initiator: self.initiator.key(),
+            redeemer,
+            secret_hash,
+            hash_algorithm,
+            incentives,
+            created_at_slot: clock.slot,
+            created_at_unix: clock.unix_timestamp,
+        })
+    }
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32], hash_algorithm: HashAlgorithm, incentives: SwapIncentives, redeem_window: RedeemWindow, nonce: u32)]
+pub struct InitiateWithNonce<'info> {
+    /// A PDA that maintains the on-chain state of the atomic swap throughout its lifecycle,
+    /// as in `Initiate`, with the nonce distinguishing it from other swaps with equal
+    /// `initiator`, `redeemer` and `secret_hash`.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash, &nonce.to_le_bytes()],
+        bump,
+        space = SWAP_ACCOUNT_SPACE,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    /// Pays the rent of the swap account, which is returned to them upon completion.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// The third party providing the swap amount on behalf of the initiator, if any.
+    /// They must sign this transaction.
+    #[account(mut)]
+    pub funder: Option<Signer<'info>>,
+
+    /// CHECK: The account refunds are to be paid to in place of the initiator, if any.
+    /// Only its address is recorded.
+    pub refund_to: Option<AccountInfo<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitiateWithNonce<'info> {
+    /// The accounts as those of `Initiate`, sharing the underlying accounts
+    fn initiate(&self) -> Initiate<'info> {
+        Initiate {
+            swap_account: self.swap_account.clone(),
+            config: self.config.clone(),
+            initiator: self.initiator.clone(),
+            funder: self.funder.clone(),
+            refund_to: self.refund_to.clone(),
+            system_program: self.system_program.clone(),
+            event_authority: self.event_authority.clone(),
+            program: self.program.clone(),
+        }
+    }
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitiateBatch<'info> {
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swaps. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// A PDA holding the program-wide configuration.
+    /// The choice of seeds ensures a single config exists for the program.
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"config"],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + Config::INIT_SPACE,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// A PDA collecting the protocol fees
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"treasury"],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + Treasury::INIT_SPACE,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Verifying the admin is the program's upgrade authority
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ SwapError::InvalidAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The program's upgrade authority. They must sign this transaction.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"config"], bump, has_one = admin @ SwapError::InvalidAdmin)]
+    pub config: Account<'info, Config>,
+
+    /// The admin of the config. They must sign this transaction.
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    #[account(seeds = [b"config"], bump, has_one = admin @ SwapError::InvalidAdmin)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The admin of the config. They must sign this transaction.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    /// The PDA holding the state information of the atomic swap.
+    /// Will be closed once fully redeemed and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(
+        mut,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// CHECK: Verifying the initiator.  
+    /// This is included here for the PDA rent refund once the swap is fully redeemed.
+    #[account(mut, address = swap_account.load()?.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer.
+    /// They must sign this transaction if `destination` is provided.
+    #[account(mut, address = swap_account.load()?.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    /// CHECK: The account the redeemer directs the funds to in place of themselves, if any
+    #[account(mut)]
+    pub destination: Option<AccountInfo<'info>>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// The PDA the protocol fee is transferred to
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The relayer submitting this transaction on the redeemer's behalf, if any.
+    /// They must sign this transaction.
+    #[account(mut)]
+    pub relayer: Option<Signer<'info>>,
+
+    /// A PDA retaining the secret revealed by this redeem, if requested.
+    /// The choice of seeds ties at most one receipt to each swap.
+    #[account(
+        init,
+        payer = receipt_payer,
+        seeds = [b"redeem_receipt", swap_account.key().as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + RedeemReceipt::INIT_SPACE,
+    )]
+    pub receipt: Option<Account<'info, RedeemReceipt>>,
+
+    /// The payer of the receipt's rent. Required if `receipt` is provided,
+    /// in which case they must sign this transaction.
+    #[account(mut)]
+    pub receipt_payer: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemWithSignature<'info> {
+    /// The PDA holding the state information of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(
+        mut,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the PDA rent refund upon closure.
+    #[account(mut, address = swap_account.load()?.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: The destination the redeemer has consented to, verified against their signature
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// The PDA the protocol fee is transferred to
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The relayer submitting this transaction on the redeemer's behalf, if any.
+    /// They must sign this transaction.
+    #[account(mut)]
+    pub relayer: Option<Signer<'info>>,
+
+    /// CHECK: Verifying the instructions sysvar, used to introspect the ed25519 program instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    /// A PDA retaining the secret revealed by this redeem, if requested.
+    /// The choice of seeds ties at most one receipt to each swap.
+    #[account(
+        init,
+        payer = receipt_payer,
+        seeds = [b"redeem_receipt", swap_account.key().as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + RedeemReceipt::INIT_SPACE,
+    )]
+    pub receipt: Option<Account<'info, RedeemReceipt>>,
+
+    /// The payer of the receipt's rent. Required if `receipt` is provided,
+    /// in which case they must sign this transaction.
+    #[account(mut)]
+    pub receipt_payer: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRedeemReceipt<'info> {
+    /// The redeem receipt. Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the payer.
+    #[account(mut, close = payer, has_one = payer @ SwapError::InvalidReceiptPayer)]
+    pub receipt: Account<'info, RedeemReceipt>,
+
+    /// The payer of the receipt's rent. They must sign this transaction.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    /// The PDA holding the state information of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the PDA rent refund using the `close` attribute above.
+    #[account(mut, address = swap_account.load()?.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the refund destination specified at initiate, if any.
+    /// Required if one was specified, in which case it receives the funds in place of the initiator.
+    #[account(mut)]
+    pub refund_to: Option<AccountInfo<'info>>,
+
+    /// The third party submitting this transaction, if any. They must sign this transaction.
+    #[account(mut)]
+    pub refunder: Option<Signer<'info>>,
+}
+
+/// The swap accounts and their refund destinations are provided as remaining accounts
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundExpiredBatch<'info> {
+    /// The third party submitting this transaction, if any. They must sign this transaction.
+    #[account(mut)]
+    pub refunder: Option<Signer<'info>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InstantRefund<'info> {
+    /// The PDA holding the state information of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the PDA rent refund using the `close` attribute above.
+    #[account(mut, address = swap_account.load()?.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the refund destination specified at initiate, if any.
+    /// Required if one was specified, in which case it receives the funds in place of the initiator.
+    #[account(mut)]
+    pub refund_to: Option<AccountInfo<'info>>,
+
+    /// CHECK: Verifying the redeemer. Redeemer must sign this transaction.
+    /// Receives their acceptance bond back, if any.
+    #[account(mut, address = swap_account.load()?.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Accept<'info> {
+    /// The PDA holding the state information of the atomic swap, escrowing the acceptance bond
+    #[account(
+        mut,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// The redeemer of the atomic swap, posting the acceptance bond. They must sign this transaction.
+    #[account(mut, address = swap_account.load()?.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CooperativeClose<'info> {
+    /// The PDA holding the state information of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// The initiator of the atomic swap, receiving their share unless a refund destination was
+    /// specified at initiate. They must sign this transaction.
+    #[account(mut, address = swap_account.load()?.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: Signer<'info>,
+
+    /// CHECK: Verifying the refund destination specified at initiate, if any.
+    /// Required if one was specified, in which case it receives the initiator's share.
+    #[account(mut)]
+    pub refund_to: Option<AccountInfo<'info>>,
+
+    /// The redeemer of the atomic swap, receiving their share. They must sign this transaction.
+    #[account(mut, address = swap_account.load()?.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// The PDA the protocol fee is transferred to
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateToken<'info> {
+    /// A PDA that maintains the on-chain state of the token swap throughout its lifecycle.
+    /// The choice of seeds mirrors that of `Initiate`, see `SWAP_SEEDS_VERSION`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"token_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + TokenSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, TokenSwapAccount>,
+
+    /// A token account owned by the swap's PDA that escrows the tokens involved in this swap.
+    /// This account will be closed upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"token_vault", swap_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = swap_account,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The initiator's token account the swap is funded from
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub initiator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemToken<'info> {
+    /// The PDA holding the state information of the token swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, TokenSwapAccount>,
+
+    #[account(mut, seeds = [b"token_vault", swap_account.key().as_ref()], bump)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The redeemer's token account the swapped tokens are transferred to
+    #[account(
+        mut,
+        token::mint = swap_account.mint,
+        token::authority = swap_account.redeemer,
+        token::token_program = token_program,
+    )]
+    pub redeemer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the swapped tokens, mutable for harvesting any withheld transfer fees
+    #[account(mut, address = swap_account.mint @ SwapError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the token vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemTokenToAta<'info> {
+    /// The PDA holding the state information of the token swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, TokenSwapAccount>,
+
+    #[account(mut, seeds = [b"token_vault", swap_account.key().as_ref()], bump)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The redeemer's associated token account the swapped tokens are transferred to,
+    /// created if it does not exist yet
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = redeemer,
+        associated_token::token_program = token_program,
+    )]
+    pub redeemer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the swapped tokens, mutable for harvesting any withheld transfer fees
+    #[account(mut, address = swap_account.mint @ SwapError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the token vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, the authority of the associated token account
+    #[account(address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    /// Pays the rent of the associated token account if it is created. They must sign this transaction.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundToken<'info> {
+    /// The PDA holding the state information of the token swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, TokenSwapAccount>,
+
+    #[account(mut, seeds = [b"token_vault", swap_account.key().as_ref()], bump)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The initiator's token account the escrowed tokens are returned to
+    #[account(
+        mut,
+        token::mint = swap_account.mint,
+        token::authority = swap_account.initiator,
+        token::token_program = token_program,
+    )]
+    pub initiator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The mint of the swapped tokens, mutable for harvesting any withheld transfer fees
+    #[account(mut, address = swap_account.mint @ SwapError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the token vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, token_amounts: Vec<u64>, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateBundle<'info> {
+    /// A PDA that maintains the on-chain state of the bundle swap throughout its lifecycle,
+    /// escrowing its native SOL and owning the vaults of its tokens.
+    /// The choice of seeds mirrors that of `Initiate`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"bundle_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + BundleSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, BundleSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemBundle<'info> {
+    /// The PDA holding the state information of the bundle swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, BundleSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the vaults.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the escrowed native SOL
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundBundle<'info> {
+    /// The PDA holding the state information of the bundle swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, BundleSwapAccount>,
+
+    /// CHECK: Verifying the initiator, who receives the escrowed native SOL.
+    /// This is included here for the rent refunds of the PDA and the vaults.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateStreamed<'info> {
+    /// A PDA that maintains the on-chain state of the streamed swap throughout its lifecycle,
+    /// escrowing its native SOL. The choice of seeds mirrors that of `Initiate`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"streamed_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + StreamedSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, StreamedSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevealStreamed<'info> {
+    /// The PDA holding the state information of the streamed swap
+    #[account(mut)]
+    pub swap_account: Account<'info, StreamedSwapAccount>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimStreamed<'info> {
+    /// The PDA holding the state information of the streamed swap.
+    /// Will be closed once its funds are claimed in full and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut)]
+    pub swap_account: Account<'info, StreamedSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the unlocked native SOL
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DisputeStreamed<'info> {
+    /// The PDA holding the state information of the streamed swap.
+    /// Will be closed upon successful execution and the native SOL yet to unlock along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, StreamedSwapAccount>,
+
+    /// The initiator of the streamed swap. They must sign this transaction.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: Signer<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the native SOL unlocked but not yet claimed
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundStreamed<'info> {
+    /// The PDA holding the state information of the streamed swap.
+    /// Will be closed upon successful execution and its escrowed native SOL along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, StreamedSwapAccount>,
+
+    /// CHECK: Verifying the initiator
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, swap_count: u32, expires_in_slots: u64, redeemer: Pubkey, chain_tip: [u8; 32])]
+pub struct InitiateHashChain<'info> {
+    /// A PDA that maintains the on-chain state of the series throughout its lifecycle,
+    /// escrowing the native SOL of all its swaps. The choice of seeds mirrors that of `Initiate`,
+    /// with the chain's tip in place of the secret hash.
+    /// This PDA will be deleted upon completion of the series.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"hash_chain_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &chain_tip],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + HashChainSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, HashChainSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the series. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemHashChain<'info> {
+    /// The PDA holding the state information of the series.
+    /// Will be closed once every swap of the series is redeemed and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut)]
+    pub swap_account: Account<'info, HashChainSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the amount of each swap
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundHashChain<'info> {
+    /// The PDA holding the state information of the series.
+    /// Will be closed upon successful execution and the native SOL of its remaining swaps along
+    /// with the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, HashChainSwapAccount>,
+
+    /// CHECK: Verifying the initiator
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, point: Pubkey)]
+pub struct InitiatePtlc<'info> {
+    /// A PDA that maintains the on-chain state of the PTLC swap throughout its lifecycle,
+    /// escrowing its native SOL. The choice of seeds mirrors that of `Initiate`,
+    /// with the point in place of the secret hash.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"ptlc_swap_account", initiator.key().as_ref(), redeemer.as_ref(), point.as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + PtlcSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, PtlcSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemPtlc<'info> {
+    /// The PDA holding the state information of the PTLC swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, PtlcSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the escrowed native SOL
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    /// CHECK: Verifying the instructions sysvar, used to introspect the ed25519 program instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundPtlc<'info> {
+    /// The PDA holding the state information of the PTLC swap.
+    /// Will be closed upon successful execution and its escrowed native SOL along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, PtlcSwapAccount>,
+
+    /// CHECK: Verifying the initiator
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, eth_address: [u8; 20])]
+pub struct InitiateSecp256k1<'info> {
+    /// A PDA that maintains the on-chain state of the secp256k1 swap throughout its lifecycle,
+    /// escrowing its native SOL. The choice of seeds mirrors that of `Initiate`,
+    /// with the committed key's address in place of the secret hash.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"secp256k1_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &eth_address],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + Secp256k1SwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, Secp256k1SwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemSecp256k1<'info> {
+    /// The PDA holding the state information of the secp256k1 swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, Secp256k1SwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the escrowed native SOL
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    /// CHECK: Verifying the instructions sysvar, used to introspect the secp256k1 program instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundSecp256k1<'info> {
+    /// The PDA holding the state information of the secp256k1 swap.
+    /// Will be closed upon successful execution and its escrowed native SOL along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, Secp256k1SwapAccount>,
+
+    /// CHECK: Verifying the initiator
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateZk<'info> {
+    /// A PDA that maintains the on-chain state of the zero-knowledge swap throughout its
+    /// lifecycle, escrowing its native SOL. The choice of seeds mirrors that of `Initiate`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"zk_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + ZkSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, ZkSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemZk<'info> {
+    /// The PDA holding the state information of the zero-knowledge swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, ZkSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the escrowed native SOL
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundZk<'info> {
+    /// The PDA holding the state information of the zero-knowledge swap.
+    /// Will be closed upon successful execution and its escrowed native SOL along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, ZkSwapAccount>,
+
+    /// CHECK: Verifying the initiator
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct PostOrder<'info> {
+    /// A PDA holding the terms of the order and escrowing the native SOL it offers.
+    /// This PDA will be deleted once the order is filled or cancelled.
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"order", maker.key().as_ref(), &order_id.to_le_bytes()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + Order::INIT_SPACE,
+    )]
+    pub order: Account<'info, Order>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The maker of the order. They must sign this transaction.
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(expires_in_slots: u64, secret_hash: [u8; 32])]
+pub struct FillOrder<'info> {
+    /// The order being filled.
+    /// Will be closed once fully filled and its remaining escrow along with the resulting rent
+    /// will be transferred to the maker.
+    #[account(mut)]
+    pub order: Account<'info, Order>,
+
+    /// A PDA that maintains the on-chain state of the atomic swap filling the order, as in
+    /// `Initiate`, with the maker as its initiator and the taker as its redeemer.
+    #[account(
+        init,
+        payer = taker,
+        seeds = [b"swap_account", order.maker.as_ref(), taker.key().as_ref(), &secret_hash],
+        bump,
+        space = SWAP_ACCOUNT_SPACE,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Verifying the maker.
+    /// This is included here for the rent refund of the order once fully filled.
+    #[account(mut, address = order.maker @ SwapError::InvalidInitiator)]
+    pub maker: AccountInfo<'info>,
+
+    /// The taker filling the order, who becomes the redeemer of the swap.
+    /// They must sign this transaction.
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    /// The order being cancelled.
+    /// Will be closed upon successful execution and its escrowed native SOL along with
+    /// the resulting rent will be transferred to the maker.
+    #[account(mut, close = maker)]
+    pub order: Account<'info, Order>,
+
+    /// The maker of the order. They must sign this transaction.
+    #[account(mut, address = order.maker @ SwapError::InvalidInitiator)]
+    pub maker: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateOraclePriced<'info> {
+    /// A PDA that maintains the on-chain state of the oracle-priced swap throughout its
+    /// lifecycle, escrowing its native SOL. The choice of seeds mirrors that of `Initiate`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"oracle_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + OracleSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, OracleSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemOraclePriced<'info> {
+    /// The PDA holding the state information of the oracle-priced swap.
+    /// Will be closed upon successful execution and the remainder of its escrow along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, OracleSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the refund of the remaining escrow and the rent of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the native SOL worth the notional
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    /// CHECK: The Pyth SOL/USD price update the swap is redeemed at, verified when read
+    pub price_update: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundOraclePriced<'info> {
+    /// The PDA holding the state information of the oracle-priced swap.
+    /// Will be closed upon successful execution and its escrowed native SOL along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, OracleSwapAccount>,
+
+    /// CHECK: Verifying the initiator
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, swap_id: [u8; 32])]
+pub struct InitiateWormhole<'info> {
+    /// A PDA that maintains the on-chain state of the Wormhole swap throughout its lifecycle,
+    /// escrowing its native SOL. The choice of seeds mirrors that of `Initiate`, with the swap ID
+    /// in place of the secret hash.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"wormhole_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &swap_id],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + WormholeSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, WormholeSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemWithVaa<'info> {
+    /// The PDA holding the state information of the Wormhole swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, WormholeSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the escrowed native SOL
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    /// CHECK: The VAA posted to the Wormhole core bridge, verified when read
+    pub posted_vaa: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundWormhole<'info> {
+    /// The PDA holding the state information of the Wormhole swap.
+    /// Will be closed upon successful execution and its escrowed native SOL along with
+    /// the resulting rent will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, WormholeSwapAccount>,
+
+    /// CHECK: Verifying the initiator
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount_lamports: u64, expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateWsol<'info> {
+    /// A PDA that maintains the on-chain state of the token swap throughout its lifecycle,
+    /// as in `InitiateToken`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"token_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + TokenSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, TokenSwapAccount>,
+
+    /// A wSOL token account owned by the swap's PDA that escrows the wrapped SOL.
+    /// This account will be closed upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"token_vault", swap_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = swap_account,
+        token::token_program = token_program,
+    )]
+    pub token_vault: Account<'info, token::TokenAccount>,
+
+    #[account(address = native_mint::ID @ SwapError::InvalidMint)]
+    pub mint: Account<'info, token::Mint>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap, whose native SOL is wrapped. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemWsol<'info> {
+    /// The PDA holding the state information of the token swap of the native mint.
+    /// Will be closed upon successful execution and the resulting rent, along with that of
+    /// the vault, will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        constraint = swap_account.mint == native_mint::ID @ SwapError::InvalidMint,
+    )]
+    pub swap_account: Account<'info, TokenSwapAccount>,
+
+    #[account(mut, seeds = [b"token_vault", swap_account.key().as_ref()], bump)]
+    pub token_vault: Account<'info, token::TokenAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the token vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the unwrapped SOL
+    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundWsol<'info> {
+    /// The PDA holding the state information of the token swap of the native mint.
+    /// Will be closed upon successful execution and the resulting rent, along with that of
+    /// the vault, will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        constraint = swap_account.mint == native_mint::ID @ SwapError::InvalidMint,
+    )]
+    pub swap_account: Account<'info, TokenSwapAccount>,
+
+    #[account(mut, seeds = [b"token_vault", swap_account.key().as_ref()], bump)]
+    pub token_vault: Account<'info, token::TokenAccount>,
+
+    /// CHECK: Verifying the initiator, who receives the unwrapped SOL.
+    /// This is included here for the rent refunds of the PDA and the token vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateConfidential<'info> {
+    /// A PDA that maintains the on-chain state of the confidential swap throughout its lifecycle.
+    /// The choice of seeds mirrors that of `Initiate`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"confidential_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + ConfidentialSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, ConfidentialSwapAccount>,
+
+    /// A token account owned by the swap's PDA that confidentially escrows the tokens involved
+    /// in this swap. This account will be closed upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"confidential_vault", swap_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = swap_account,
+        token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The initiator's token account the swap is confidentially funded from
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub initiator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the vault's public key validity proof
+    pub pubkey_validity_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's equality proof
+    pub equality_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's ciphertext validity proof
+    pub ciphertext_validity_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's range proof
+    pub range_proof: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemConfidential<'info> {
+    /// The PDA holding the state information of the confidential swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, ConfidentialSwapAccount>,
+
+    #[account(mut, seeds = [b"confidential_vault", swap_account.key().as_ref()], bump)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The redeemer's token account the escrowed tokens are confidentially transferred to
+    #[account(
+        mut,
+        token::mint = swap_account.mint,
+        token::authority = swap_account.redeemer,
+        token::token_program = token_program,
+    )]
+    pub redeemer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = swap_account.mint @ SwapError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's equality proof
+    pub equality_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's ciphertext validity proof
+    pub ciphertext_validity_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's range proof
+    pub range_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the proof of the vault's zero balance
+    pub zero_balance_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the token vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundConfidential<'info> {
+    /// The PDA holding the state information of the confidential swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, ConfidentialSwapAccount>,
+
+    #[account(mut, seeds = [b"confidential_vault", swap_account.key().as_ref()], bump)]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The initiator's token account the escrowed tokens are confidentially returned to
+    #[account(
+        mut,
+        token::mint = swap_account.mint,
+        token::authority = swap_account.initiator,
+        token::token_program = token_program,
+    )]
+    pub initiator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = swap_account.mint @ SwapError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's equality proof
+    pub equality_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's ciphertext validity proof
+    pub ciphertext_validity_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the transfer's range proof
+    pub range_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Token-2022 as the context state of the proof of the vault's zero balance
+    pub zero_balance_proof: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the token vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateNft<'info> {
+    /// A PDA that maintains the on-chain state of the NFT swap throughout its lifecycle.
+    /// The choice of seeds mirrors that of `Initiate`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"nft_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + NftSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, NftSwapAccount>,
+
+    /// A token account owned by the swap's PDA that escrows the NFT involved in this swap.
+    /// This account will be closed upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"nft_vault", swap_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = swap_account,
+        token::token_program = token_program,
+    )]
+    pub nft_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The initiator's token account holding the NFT
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub initiator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verified to be the metadata account of the mint by `initiate_nft`
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemNft<'info> {
+    /// The PDA holding the state information of the NFT swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, NftSwapAccount>,
+
+    #[account(mut, seeds = [b"nft_vault", swap_account.key().as_ref()], bump)]
+    pub nft_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The redeemer's token account the NFT is transferred to
+    #[account(
+        mut,
+        token::mint = swap_account.mint,
+        token::authority = swap_account.redeemer,
+        token::token_program = token_program,
+    )]
+    pub redeemer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = swap_account.mint @ SwapError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the NFT vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundNft<'info> {
+    /// The PDA holding the state information of the NFT swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, NftSwapAccount>,
+
+    #[account(mut, seeds = [b"nft_vault", swap_account.key().as_ref()], bump)]
+    pub nft_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// The initiator's token account the NFT is returned to
+    #[account(
+        mut,
+        token::mint = swap_account.mint,
+        token::authority = swap_account.initiator,
+        token::token_program = token_program,
+    )]
+    pub initiator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = swap_account.mint @ SwapError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refunds of the PDA and the NFT vault.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(leaf: CompressedLeaf, root: [u8; 32], expires_in_slots: u64, redeemer: Pubkey, secret_hash: [u8; 32])]
+pub struct InitiateCnft<'info> {
+    /// A PDA that maintains the on-chain state of the cNFT swap throughout its lifecycle,
+    /// owning the leaf of the cNFT while it is escrowed.
+    /// The choice of seeds mirrors that of `Initiate`.
+    /// This PDA will be deleted upon completion of the swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"cnft_swap_account", initiator.key().as_ref(), redeemer.as_ref(), &secret_hash],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + CnftSwapAccount::INIT_SPACE,
+    )]
+    pub swap_account: Account<'info, CnftSwapAccount>,
+
+    #[account(seeds = [b"config"], bump, constraint = !config.initiate_paused @ SwapError::InitiatePaused)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap, owning the leaf of the cNFT. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// CHECK: Verified by Bubblegum as the delegate of the leaf
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Bubblegum as the authority of the merkle tree
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by Bubblegum and the account compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the noop program
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the account compression program
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the Bubblegum program
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemCnft<'info> {
+    /// The PDA holding the state information of the cNFT swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, CnftSwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the redeemer, who receives the cNFT
+    #[account(address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: AccountInfo<'info>,
+
+    /// CHECK: Verified by Bubblegum as the authority of the merkle tree
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the merkle tree holding the leaf of the cNFT
+    #[account(mut, address = swap_account.merkle_tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the noop program
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the account compression program
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the Bubblegum program
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundCnft<'info> {
+    /// The PDA holding the state information of the cNFT swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(mut, close = initiator)]
+    pub swap_account: Account<'info, CnftSwapAccount>,
+
+    /// CHECK: Verifying the initiator, who the cNFT is returned to.
+    /// This is included here for the rent refund of the PDA.
+    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verified by Bubblegum as the authority of the merkle tree
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the merkle tree holding the leaf of the cNFT
+    #[account(mut, address = swap_account.merkle_tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the noop program
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the account compression program
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: Verifying the Bubblegum program
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    /// CHECK: The PDA holding the state information of the atomic swap, under any layout version.
+    /// Its discriminator is verified by the instruction.
+    #[account(mut, owner = crate::ID @ SwapError::InvalidSwapAccount)]
+    pub swap_account: AccountInfo<'info>,
+
+    /// The payer of any additional rent required by the migration. They must sign this transaction.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetVersion<'info> {
+    /// This program. Anchor's CPI interface requires every instruction to take an account.
+    pub program: Program<'info, crate::program::SolanaNativeSwaps>,
+}
+
+#[derive(Accounts)]
+pub struct GetSwapState<'info> {
+    /// The PDA holding the state information of the atomic swap
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExtendExpiry<'info> {
+    #[account(
+        mut,
+        has_one = initiator @ SwapError::InvalidInitiator,
+        has_one = redeemer @ SwapError::InvalidRedeemer,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    pub initiator: Signer<'info>,
+
+    /// The redeemer of the atomic swap. They must sign this transaction.
+    pub redeemer: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InstantRefundWithSignature<'info> {
+    /// The PDA holding the state information of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the PDA rent refund using the `close` attribute above.
+    #[account(mut, address = swap_account.load()?.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the refund destination specified at initiate, if any.
+    /// Required if one was specified, in which case it receives the funds in place of the initiator.
+    #[account(mut)]
+    pub refund_to: Option<AccountInfo<'info>>,
+
+    /// CHECK: Verifying the redeemer, who receives their acceptance bond back.
+    /// Required if they posted one.
+    #[account(mut, address = swap_account.load()?.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Option<AccountInfo<'info>>,
+
+    /// CHECK: Verifying the instructions sysvar, used to introspect the ed25519 program instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InstantRefundWithSession<'info> {
+    /// The PDA holding the state information of the atomic swap.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"swap_account", swap_account.load()?.initiator.as_ref(), swap_account.load()?.redeemer_seed(), &swap_account.load()?.secret_hash, swap_account.load()?.nonce_seed()],
+        bump = swap_account.load()?.bump,
+    )]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// CHECK: Verifying the initiator.
+    /// This is included here for the PDA rent refund using the `close` attribute above.
+    #[account(mut, address = swap_account.load()?.initiator @ SwapError::InvalidInitiator)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Verifying the refund destination specified at initiate, if any.
+    /// Required if one was specified, in which case it receives the funds in place of the initiator.
+    #[account(mut)]
+    pub refund_to: Option<AccountInfo<'info>>,
+
+    /// CHECK: Verifying the redeemer, who receives their acceptance bond back.
+    /// Required if they posted one.
+    #[account(mut, address = swap_account.load()?.redeemer @ SwapError::InvalidRedeemer)]
+    pub redeemer: Option<AccountInfo<'info>>,
+
+    /// The session delegated by the redeemer
+    #[account(
+        constraint = session.authority == swap_account.load()?.redeemer @ SwapError::InvalidRedeemer,
+        has_one = session_key @ SwapError::InvalidSessionKey,
+    )]
+    pub session: Account<'info, SessionToken>,
+
+    /// The session key. It must sign this transaction.
+    pub session_key: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct CreateSession<'info> {
+    /// A PDA recording the session's delegation.
+    /// The choice of seeds allows a single session per authority and session key.
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"session", authority.key().as_ref(), session_key.as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + SessionToken::INIT_SPACE,
+    )]
+    pub session: Account<'info, SessionToken>,
+
+    /// The authority delegating its permissions. They must sign this transaction.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(new_session_key: Pubkey)]
+pub struct RotateSession<'info> {
+    /// The session being replaced.
     /// Will be closed upon successful execution and the resulting rent
-    /// will be transferred to the initiator.
-    #[account(mut, close = initiator)]
-    pub swap_account: Account<'info, SwapAccount>,
+    /// will be transferred to the authority.
+    #[account(mut, close = authority, has_one = authority @ SwapError::InvalidSessionAuthority)]
+    pub session: Account<'info, SessionToken>,
 
-    /// CHECK: Verifying the initiator.  
+    /// A PDA recording the replacement session's delegation
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"session", authority.key().as_ref(), new_session_key.as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + SessionToken::INIT_SPACE,
+    )]
+    pub new_session: Account<'info, SessionToken>,
+
+    /// The authority delegating its permissions. They must sign this transaction.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    /// The session being revoked.
+    /// Will be closed upon successful execution and the resulting rent
+    /// will be transferred to the authority.
+    #[account(mut, close = authority, has_one = authority @ SwapError::InvalidSessionAuthority)]
+    pub session: Account<'info, SessionToken>,
+
+    /// The authority that delegated its permissions. They must sign this transaction.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProvisionRelayerFees<'info> {
+    /// The PDA of the swap whose lifecycle instructions the relayer will be paying for
+    #[account(has_one = initiator @ SwapError::InvalidInitiator)]
+    pub swap_account: AccountLoader<'info, SwapAccount>,
+
+    /// A PDA escrowing the relayer's fee allowance for this swap.
+    /// The choice of seeds ties exactly one fee escrow to each swap.
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [b"fee_escrow", swap_account.key().as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + FeeEscrow::INIT_SPACE,
+    )]
+    pub fee_escrow: Account<'info, FeeEscrow>,
+
+    /// The initiator of the atomic swap. They must sign this transaction.
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DrawRelayerFees<'info> {
+    /// The PDA escrowing the relayer's fee allowance
+    #[account(mut, has_one = relayer @ SwapError::InvalidRelayer)]
+    pub fee_escrow: Account<'info, FeeEscrow>,
+
+    /// The relayer authorized to draw from the fee escrow. They must sign this transaction.
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseFeeEscrow<'info> {
+    /// The PDA escrowing the relayer's fee allowance.
+    /// Will be closed upon successful execution and the remaining allowance,
+    /// along with the rent, will be transferred to the initiator.
+    #[account(
+        mut,
+        close = initiator,
+        has_one = initiator @ SwapError::InvalidInitiator,
+        has_one = swap_account @ SwapError::InvalidSwapAccount,
+    )]
+    pub fee_escrow: Account<'info, FeeEscrow>,
+
+    /// CHECK: Verifying the swap account has been closed, i.e. the swap has completed
+    #[account(constraint = swap_account.lamports() == 0 @ SwapError::SwapNotCompleted)]
+    pub swap_account: AccountInfo<'info>,
+
+    /// CHECK: Verifying the initiator.
     /// This is included here for the PDA rent refund using the `close` attribute above.
-    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
+    #[account(mut)]
     pub initiator: AccountInfo<'info>,
+}
 
-    /// CHECK: Verifying the redeemer
-    #[account(mut, address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
-    pub redeemer: AccountInfo<'info>,
+#[event_cpi]
+#[derive(Accounts)]
+pub struct NetSettle<'info> {
+    /// The PDA of the swap initiated by `party_a` in favour of `party_b`.
+    /// Will be closed upon successful execution and the remaining funds,
+    /// along with the rent, will be transferred to `party_a`.
+    #[account(
+        mut,
+        close = party_a,
+        constraint = swap_a.key() != swap_b.key() @ SwapError::IdenticalSwaps,
+    )]
+    pub swap_a: AccountLoader<'info, SwapAccount>,
+
+    /// The PDA of the swap initiated by `party_b` in favour of `party_a`.
+    /// Will be closed upon successful execution and the remaining funds,
+    /// along with the rent, will be transferred to `party_b`.
+    #[account(mut, close = party_b)]
+    pub swap_b: AccountLoader<'info, SwapAccount>,
+
+    /// The initiator of `swap_a` and the redeemer of `swap_b`. They must sign this transaction.
+    #[account(
+        mut,
+        address = swap_a.load()?.initiator @ SwapError::InvalidInitiator,
+        constraint = swap_b.load()?.redeemer == party_a.key() @ SwapError::InvalidRedeemer,
+    )]
+    pub party_a: Signer<'info>,
+
+    /// The initiator of `swap_b` and the redeemer of `swap_a`. They must sign this transaction.
+    #[account(
+        mut,
+        address = swap_b.load()?.initiator @ SwapError::InvalidInitiator,
+        constraint = swap_a.load()?.redeemer == party_b.key() @ SwapError::InvalidRedeemer,
+    )]
+    pub party_b: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// The PDA the protocol fee is transferred to
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(deposit_lamports: u64, expires_in_slots: u64, payee: Pubkey)]
+pub struct OpenChannel<'info> {
+    /// A PDA that maintains the state of the payment channel and escrows its deposit.
+    /// The choice of seeds allows a single open channel from a funder to a payee.
+    #[account(
+        init,
+        payer = funder,
+        seeds = [b"payment_channel", funder.key().as_ref(), payee.as_ref()],
+        bump,
+        space = ANCHOR_DISCRIMINATOR + PaymentChannel::INIT_SPACE,
+    )]
+    pub channel: Account<'info, PaymentChannel>,
+
+    /// The funder of the payment channel. They must sign this transaction.
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositToChannel<'info> {
+    #[account(mut, has_one = funder @ SwapError::InvalidInitiator)]
+    pub channel: Account<'info, PaymentChannel>,
+
+    /// The funder of the payment channel. They must sign this transaction.
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LockChannelPayment<'info> {
+    #[account(mut, has_one = funder @ SwapError::InvalidInitiator)]
+    pub channel: Account<'info, PaymentChannel>,
+
+    /// The funder of the payment channel. They must sign this transaction.
+    pub funder: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct LockChannelPaymentWithSession<'info> {
+    #[account(mut)]
+    pub channel: Account<'info, PaymentChannel>,
+
+    /// The session delegated by the funder
+    #[account(
+        constraint = session.authority == channel.funder @ SwapError::InvalidInitiator,
+        has_one = session_key @ SwapError::InvalidSessionKey,
+    )]
+    pub session: Account<'info, SessionToken>,
+
+    /// The session key. It must sign this transaction.
+    pub session_key: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimChannelPayment<'info> {
+    #[account(mut, has_one = payee @ SwapError::InvalidRedeemer)]
+    pub channel: Account<'info, PaymentChannel>,
+
+    /// CHECK: Verifying the payee
+    #[account(mut)]
+    pub payee: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelChannelPayment<'info> {
+    #[account(mut)]
+    pub channel: Account<'info, PaymentChannel>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CooperativeCloseChannel<'info> {
+    /// The PDA of the payment channel.
+    /// Will be closed upon successful execution and the remaining deposit,
+    /// along with the rent, will be transferred to the funder.
+    #[account(
+        mut,
+        close = funder,
+        has_one = funder @ SwapError::InvalidInitiator,
+        has_one = payee @ SwapError::InvalidRedeemer,
+    )]
+    pub channel: Account<'info, PaymentChannel>,
+
+    /// The funder of the payment channel. They must sign this transaction.
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// The payee of the payment channel. They must sign this transaction.
+    #[account(mut)]
+    pub payee: Signer<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseExpiredChannel<'info> {
+    /// The PDA of the payment channel.
+    /// Will be closed upon successful execution and the remaining deposit,
+    /// along with the rent, will be transferred to the funder.
+    #[account(mut, close = funder, has_one = funder @ SwapError::InvalidInitiator)]
+    pub channel: Account<'info, PaymentChannel>,
+
+    /// CHECK: Verifying the funder.
+    /// This is included here for the PDA rent refund using the `close` attribute above.
+    #[account(mut)]
+    pub funder: AccountInfo<'info>,
+}
+
+/// Represents the initiated state of the swap where the initiator has deposited funds into the vault
+#[event]
+pub struct Initiated {
+    /// The PDA holding the state information of the atomic swap
+    pub swap_account: Pubkey,
+    /// The quantity of native SOL transferred through this atomic swap in base units (aka lamports).  
+    /// E.g: A quantity of 1 SOL will be represented as 1,000,000,000.
+    pub swap_amount: u64,
+    /// `expires_in_slots` represents the number of slots (1 slot = 400ms) after which
+    /// (non-instant) refunds are allowed
+    pub expires_in_slots: u64,
+    /// The exact slot from which (non-instant) refunds are allowed
+    pub expiry_slot: u64,
+    /// The exact unix timestamp after which (non-instant) refunds are allowed, if any
+    pub expiry_timestamp: Option<i64>,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    pub hash_algorithm: HashAlgorithm,
+    pub incentives: SwapIncentives,
+    /// The slot the swap was initiated at
+    pub created_at_slot: u64,
+    /// The unix timestamp the swap was initiated at
+    pub created_at_unix: i64,
+}
+/// Represents the initiated state of multiple swaps created in a single batch
+#[event]
+pub struct BatchInitiated {
+    pub initiator: Pubkey,
+    pub swaps: Vec<BatchedSwap>,
+    /// The slot the swaps were initiated at
+    pub created_at_slot: u64,
+    /// The unix timestamp the swaps were initiated at
+    pub created_at_unix: i64,
+}
+/// Represents the secret hashes a swap initiated through `initiate_multi_secret` commits to,
+/// emitted along with `Initiated`
+#[event]
+pub struct MultiSecretInitiated {
+    pub swap_account: Pubkey,
+    pub secret_hashes: Vec<[u8; 32]>,
+}
+/// Represents the secret hashes a swap initiated through `initiate_any_secret` is redeemable
+/// with, emitted along with `Initiated`
+#[event]
+pub struct AnySecretInitiated {
+    pub swap_account: Pubkey,
+    pub secret_hashes: Vec<[u8; 32]>,
+}
+/// Represents the acceptance bond a swap initiated through `initiate_with_acceptance_bond`
+/// requires of its redeemer, emitted along with `Initiated`
+#[event]
+pub struct AcceptanceBondRequired {
+    pub swap_account: Pubkey,
+    /// The bond the redeemer must post through `accept` in base units (aka lamports)
+    pub acceptance_bond_lamports: u64,
+}
+/// Represents the accepted state of the swap, where the redeemer has posted its acceptance bond
+#[event]
+pub struct Accepted {
+    pub swap_account: Pubkey,
+    pub redeemer: Pubkey,
+    /// The bond posted in base units (aka lamports)
+    pub bond_lamports: u64,
+}
+/// Represents the redeemed state of the swap, where the redeemer has withdrawn funds from the vault
+#[event]
+pub struct Redeemed {
+    /// The PDA holding the state information of the atomic swap
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    /// The secret revealed, or the first of them for swaps committed to several secret hashes,
+    /// see `MultiSecretRedeemed`
+    pub secret: [u8; 32],
+    /// The exact slot from which (non-instant) refunds are allowed
+    pub expiry_slot: u64,
+    /// The quantity of native SOL redeemed from the swap in base units (aka lamports),
+    /// inclusive of the protocol fee
+    pub amount_lamports: u64,
+    /// The quantity of native SOL deducted from the swap amount and paid to the treasury
+    /// in base units (aka lamports)
+    pub protocol_fee_lamports: u64,
+    /// The quantity of native SOL paid to the relayer in base units (aka lamports), if any
+    pub relayer_fee_lamports: u64,
+}
+/// Represents the secrets revealed by `redeem_multi_secret`, emitted along with `Redeemed`
+#[event]
+pub struct MultiSecretRedeemed {
+    pub swap_account: Pubkey,
+    pub secrets: Vec<[u8; 32]>,
+}
+/// Represents the refund state of the swap, where the initiator has withdrawn funds from the vault past expiry
+#[event]
+pub struct Refunded {
+    /// The PDA holding the state information of the atomic swap
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    /// The quantity of native SOL refunded from the swap in base units (aka lamports),
+    /// inclusive of the refund tip
+    pub amount_lamports: u64,
+    /// The exact slot from which (non-instant) refunds are allowed
+    pub expiry_slot: u64,
+    /// The quantity of native SOL paid to the refunder in base units (aka lamports), if any
+    pub refund_tip_lamports: u64,
+}
+/// Represents the instant refund state of the swap, where the initiator has withdrawn funds the vault
+/// with the redeemer's consent
+#[event]
+pub struct InstantRefunded {
+    /// The PDA holding the state information of the atomic swap
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    /// The quantity of native SOL refunded from the swap in base units (aka lamports)
+    pub amount_lamports: u64,
+    /// The exact slot from which (non-instant) refunds would have been allowed
+    pub expiry_slot: u64,
+}
+/// Represents the cooperatively closed state of the swap, where its funds have been split between
+/// its parties as they agreed
+#[event]
+pub struct CooperativelyClosed {
+    /// The PDA holding the state information of the atomic swap
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    /// The redeemer's share of the swap in base units (aka lamports), including the protocol fee
+    pub redeemer_amount_lamports: u64,
+    /// The initiator's share of the swap in base units (aka lamports)
+    pub initiator_amount_lamports: u64,
+    /// The protocol fee charged on the redeemer's share in base units (aka lamports)
+    pub protocol_fee_lamports: u64,
+}
+
+/// Represents the expiry of a swap being extended with the consent of both parties
+#[event]
+pub struct ExpiryExtended {
+    /// The PDA holding the state information of the atomic swap
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    /// The exact slot after which (non-instant) refunds are now allowed
+    pub expiry_slot: u64,
+}
+
+/// Represents the initiated state of a token swap where the initiator has deposited tokens into the vault
+#[event]
+pub struct TokenInitiated {
+    pub mint: Pubkey,
+    /// The quantity of tokens sent by the initiator in base units of the mint
+    pub swap_amount: u64,
+    /// The quantity of tokens escrowed after any transfer fees in base units of the mint
+    pub net_swap_amount: u64,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the redeemed state of a token swap, where the redeemer has withdrawn tokens from the vault
+#[event]
+pub struct TokenRedeemed {
+    pub mint: Pubkey,
+    pub initiator: Pubkey,
+    pub secret: [u8; 32],
+}
+/// Represents the refund state of a token swap, where the initiator has withdrawn tokens from the vault
+/// past expiry
+#[event]
+pub struct TokenRefunded {
+    pub mint: Pubkey,
+    pub initiator: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the initiated state of a bundle swap, where the initiator has deposited native SOL
+/// and tokens
+#[event]
+pub struct BundleInitiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
+    pub tokens: Vec<BundledToken>,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the redeemed state of a bundle swap, where the redeemer has withdrawn its native
+/// SOL and tokens
+#[event]
+pub struct BundleRedeemed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret: [u8; 32],
+}
+/// Represents the refund state of a bundle swap, where the initiator has withdrawn its native
+/// SOL and tokens past expiry
+#[event]
+pub struct BundleRefunded {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the initiated state of a streamed swap, where the initiator has deposited native SOL
+#[event]
+pub struct StreamedInitiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
+    pub expires_in_slots: u64,
+    pub stream_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the revealed state of a streamed swap, from which its native SOL unlocks to the
+/// redeemer until `stream_ends_at_slot`
+#[event]
+pub struct StreamedRevealed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret: [u8; 32],
+    pub stream_ends_at_slot: u64,
+}
+/// Represents a claim of a streamed swap, where the redeemer has withdrawn the native SOL
+/// unlocked so far
+#[event]
+pub struct StreamedClaimed {
+    pub swap_account: Pubkey,
+    pub redeemer: Pubkey,
+    pub amount_lamports: u64,
+    pub remaining_lamports: u64,
+}
+/// Represents the disputed state of a streamed swap, where the initiator has halted its release,
+/// with the native SOL unlocked but not yet claimed going to the redeemer and the rest to the
+/// initiator
+#[event]
+pub struct StreamedDisputed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub redeemer_amount_lamports: u64,
+    pub initiator_amount_lamports: u64,
+}
+/// Represents the refund state of a streamed swap, where the initiator has withdrawn its native
+/// SOL past expiry
+#[event]
+pub struct StreamedRefunded {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the initiated state of a series of swaps committed to a hash chain, where the
+/// initiator has deposited the native SOL of every swap
+#[event]
+pub struct HashChainInitiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
+    pub swap_count: u32,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub chain_tip: [u8; 32],
+}
+/// Represents the redeem of a swap of a series, revealing the next preimage of the hash chain
+#[event]
+pub struct HashChainRedeemed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret: [u8; 32],
+    pub remaining_swaps: u32,
+}
+/// Represents the refund state of a series, where the initiator has withdrawn the native SOL of
+/// its remaining swaps past expiry
+#[event]
+pub struct HashChainRefunded {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub chain_hash: [u8; 32],
+    pub remaining_swaps: u32,
 }
-
-#[derive(Accounts)]
-pub struct Refund<'info> {
-    /// The PDA holding the state information of the atomic swap.
-    /// Will be closed upon successful execution and the resulting rent
-    /// will be transferred to the initiator.
-    #[account(mut, close = initiator)]
-    pub swap_account: Account<'info, SwapAccount>,
-
-    /// CHECK: Verifying the initiator.
-    /// This is included here for the PDA rent refund using the `close` attribute above.
-    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
-    pub initiator: AccountInfo<'info>,
+/// Represents the initiated state of a PTLC swap, where the initiator has deposited native SOL
+#[event]
+pub struct PtlcInitiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub point: Pubkey,
 }
-
-#[derive(Accounts)]
-pub struct InstantRefund<'info> {
-    /// The PDA holding the state information of the atomic swap.
-    /// Will be closed upon successful execution and the resulting rent
-    /// will be transferred to the initiator.
-    #[account(mut, close = initiator)]
-    pub swap_account: Account<'info, SwapAccount>,
-
-    /// CHECK: Verifying the initiator.
-    /// This is included here for the PDA rent refund using the `close` attribute above.
-    #[account(mut, address = swap_account.initiator @ SwapError::InvalidInitiator)]
-    pub initiator: AccountInfo<'info>,
-
-    /// CHECK: Verifying the redeemer. Redeemer must sign this transaction.
-    #[account(address = swap_account.redeemer @ SwapError::InvalidRedeemer)]
-    pub redeemer: Signer<'info>,
+/// Represents the redeemed state of a PTLC swap, publishing the signature under its point
+/// from which the initiator learns the point's secret scalar
+#[event]
+pub struct PtlcRedeemed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub point: Pubkey,
+    pub signature: [u8; 64],
 }
-
-/// Represents the initiated state of the swap where the initiator has deposited funds into the vault
+/// Represents the refund state of a PTLC swap, where the initiator has withdrawn its native SOL
+/// past expiry
 #[event]
-pub struct Initiated {
-    /// The quantity of native SOL transferred through this atomic swap in base units (aka lamports).  
-    /// E.g: A quantity of 1 SOL will be represented as 1,000,000,000.
-    pub swap_amount: u64,
-    /// `expires_in_slots` represents the number of slots (1 slot = 400ms) after which
-    /// (non-instant) refunds are allowed
+pub struct PtlcRefunded {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub point: Pubkey,
+}
+/// Represents the initiated state of a secp256k1 swap, where the initiator has deposited native SOL
+#[event]
+pub struct Secp256k1Initiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub eth_address: [u8; 20],
+}
+/// Represents the redeemed state of a secp256k1 swap, where the redeemer has withdrawn its
+/// native SOL with a signature by the committed key
+#[event]
+pub struct Secp256k1Redeemed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub eth_address: [u8; 20],
+}
+/// Represents the refund state of a secp256k1 swap, where the initiator has withdrawn its native
+/// SOL past expiry
+#[event]
+pub struct Secp256k1Refunded {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub eth_address: [u8; 20],
+}
+/// Represents the initiated state of a zero-knowledge swap, where the initiator has deposited
+/// native SOL
+#[event]
+pub struct ZkInitiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
     pub expires_in_slots: u64,
     pub initiator: Pubkey,
     pub redeemer: Pubkey,
     pub secret_hash: [u8; 32],
+    pub verifying_key: Groth16VerifyingKey,
 }
-/// Represents the redeemed state of the swap, where the redeemer has withdrawn funds from the vault
+/// Represents the redeemed state of a zero-knowledge swap, where the redeemer has withdrawn its
+/// native SOL by proving knowledge of the secret, which remains unpublished
 #[event]
-pub struct Redeemed {
+pub struct ZkRedeemed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the refund state of a zero-knowledge swap, where the initiator has withdrawn its
+/// native SOL past expiry
+#[event]
+pub struct ZkRefunded {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the posted state of an order, escrowing the native SOL it offers
+#[event]
+pub struct OrderPosted {
+    pub order: Pubkey,
+    pub maker: Pubkey,
+    pub order_id: u64,
+    pub amount_lamports: u64,
+    pub counterpart_chain: [u8; 32],
+    pub counterpart_asset: [u8; 32],
+    pub counterpart_amount: u64,
+    pub min_expires_in_slots: u64,
+    pub max_expires_in_slots: u64,
+    /// The exact slot from which the order may no longer be filled
+    pub expiry_slot: u64,
+    pub min_fill_lamports: u64,
+}
+/// Represents a fill of an order, whose swap has been initiated, emitted along with `Initiated`
+#[event]
+pub struct OrderFilled {
+    pub order: Pubkey,
+    pub swap_account: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub secret_hash: [u8; 32],
+    /// The quantity of native SOL filled, in lamports
+    pub amount_lamports: u64,
+    /// The quantity of native SOL of the order yet to be filled, in lamports
+    pub remaining_lamports: u64,
+}
+/// Represents the cancelled state of an order, whose native SOL has been returned to the maker
+#[event]
+pub struct OrderCancelled {
+    pub order: Pubkey,
+    pub maker: Pubkey,
+    pub order_id: u64,
+}
+/// Represents the initiated state of an oracle-priced swap, where the initiator has deposited
+/// native SOL to cover its notional
+#[event]
+pub struct OraclePricedInitiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+    pub notional_usd_micros: u64,
+    pub max_staleness_secs: u64,
+    pub max_confidence_bps: u16,
+    pub min_lamports_out: u64,
+    pub max_lamports_out: u64,
+}
+/// Represents the redeemed state of an oracle-priced swap, where the redeemer has withdrawn the
+/// native SOL worth its notional at the price of `price` × 10^`exponent` USD per SOL
+#[event]
+pub struct OraclePricedRedeemed {
+    pub swap_account: Pubkey,
     pub initiator: Pubkey,
+    pub redeemer: Pubkey,
     pub secret: [u8; 32],
+    pub amount_lamports: u64,
+    pub price: i64,
+    pub exponent: i32,
 }
-/// Represents the refund state of the swap, where the initiator has withdrawn funds from the vault past expiry
+/// Represents the refund state of an oracle-priced swap, where the initiator has withdrawn its
+/// native SOL past expiry
 #[event]
-pub struct Refunded {
+pub struct OraclePricedRefunded {
+    pub swap_account: Pubkey,
     pub initiator: Pubkey,
     pub secret_hash: [u8; 32],
 }
-/// Represents the instant refund state of the swap, where the initiator has withdrawn funds the vault
-/// with the redeemer's consent
+/// Represents the initiated state of a Wormhole swap, where the initiator has deposited
+/// native SOL
 #[event]
-pub struct InstantRefunded {
+pub struct WormholeInitiated {
+    pub swap_account: Pubkey,
+    pub amount_lamports: u64,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub swap_id: [u8; 32],
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+}
+/// Represents the redeemed state of a Wormhole swap, where the redeemer has withdrawn its
+/// native SOL with the VAA of sequence `sequence` posted to `posted_vaa`
+#[event]
+pub struct WormholeRedeemed {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub swap_id: [u8; 32],
+    pub posted_vaa: Pubkey,
+    pub sequence: u64,
+}
+/// Represents the refund state of a Wormhole swap, where the initiator has withdrawn its
+/// native SOL past expiry
+#[event]
+pub struct WormholeRefunded {
+    pub swap_account: Pubkey,
+    pub initiator: Pubkey,
+    pub swap_id: [u8; 32],
+}
+/// Represents the initiated state of a confidential swap, where the initiator has confidentially
+/// deposited tokens into the vault, of an amount only known to the counterparties
+#[event]
+pub struct ConfidentialInitiated {
+    pub mint: Pubkey,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the redeemed state of a confidential swap, where the redeemer has withdrawn the
+/// tokens from the vault
+#[event]
+pub struct ConfidentialRedeemed {
+    pub mint: Pubkey,
+    pub initiator: Pubkey,
+    pub secret: [u8; 32],
+}
+/// Represents the refund state of a confidential swap, where the initiator has withdrawn the
+/// tokens from the vault past expiry
+#[event]
+pub struct ConfidentialRefunded {
+    pub mint: Pubkey,
+    pub initiator: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the initiated state of an NFT swap, where the initiator has deposited the NFT into
+/// the vault
+#[event]
+pub struct NftInitiated {
+    pub mint: Pubkey,
+    /// The metadata account of the NFT, verified upon initiation
+    pub metadata: Pubkey,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the redeemed state of an NFT swap, where the redeemer has withdrawn the NFT from the
+/// vault
+#[event]
+pub struct NftRedeemed {
+    pub mint: Pubkey,
+    pub initiator: Pubkey,
+    pub secret: [u8; 32],
+}
+/// Represents the refund state of an NFT swap, where the initiator has withdrawn the NFT from the
+/// vault past expiry
+#[event]
+pub struct NftRefunded {
+    pub mint: Pubkey,
+    pub initiator: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the initiated state of a cNFT swap, where the initiator has transferred the cNFT to
+/// the swap account
+#[event]
+pub struct CnftInitiated {
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub expires_in_slots: u64,
+    pub initiator: Pubkey,
+    pub redeemer: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the redeemed state of a cNFT swap, where the cNFT has been transferred to the redeemer
+#[event]
+pub struct CnftRedeemed {
+    pub asset_id: Pubkey,
     pub initiator: Pubkey,
+    pub secret: [u8; 32],
+}
+/// Represents the refund state of a cNFT swap, where the cNFT has been returned to the initiator
+/// past expiry
+#[event]
+pub struct CnftRefunded {
+    pub asset_id: Pubkey,
+    pub initiator: Pubkey,
+    pub secret_hash: [u8; 32],
+}
+/// Represents the provisioning of a relayer's fee allowance for a swap
+#[event]
+pub struct RelayerFeesProvisioned {
+    pub swap_account: Pubkey,
+    pub relayer: Pubkey,
+    pub allowance_lamports: u64,
+}
+/// Represents a relayer drawing from its fee allowance for a swap
+#[event]
+pub struct RelayerFeesDrawn {
+    pub swap_account: Pubkey,
+    pub relayer: Pubkey,
+    pub amount_lamports: u64,
+}
+/// Represents the net-settled state of two opposing swaps, where only the difference
+/// between their amounts has been transferred to the party owed
+#[event]
+pub struct NetSettled {
+    pub party_a: Pubkey,
+    pub party_b: Pubkey,
+    pub secret_hash_a: [u8; 32],
+    pub secret_hash_b: [u8; 32],
+    /// The quantity of native SOL transferred between the parties in base units (aka lamports),
+    /// inclusive of the protocol fee
+    pub net_amount: u64,
+    /// The protocol fee charged on the net amount in base units (aka lamports)
+    pub protocol_fee_lamports: u64,
+}
+/// Represents the bounds on the expiry of swaps being set
+#[event]
+pub struct ExpiryBoundsUpdated {
+    pub min_expiry_slots: u64,
+    pub max_expiry_slots: u64,
+}
+/// Represents the protocol fee deducted upon redeems being set
+#[event]
+pub struct ProtocolFeeUpdated {
+    pub protocol_fee_bps: u16,
+}
+/// Represents the initiation of new swaps being paused or unpaused
+#[event]
+pub struct InitiatePauseUpdated {
+    pub paused: bool,
+}
+/// Represents the admin permitting or forbidding a transfer hook program
+#[event]
+pub struct TransferHookPermissionUpdated {
+    pub program_id: Pubkey,
+    pub permitted: bool,
+}
+
+/// Represents an authority delegating its permissions to a session key
+#[event]
+pub struct SessionCreated {
+    pub authority: Pubkey,
+    pub session_key: Pubkey,
+    pub scope: u8,
+    pub expiry_slot: u64,
+}
+/// Represents a session key being invalidated, either by revocation or rotation
+#[event]
+pub struct SessionRevoked {
+    pub authority: Pubkey,
+    pub session_key: Pubkey,
+}
+/// Represents the opened state of a payment channel, where the funder has deposited funds into it
+#[event]
+pub struct ChannelOpened {
+    pub funder: Pubkey,
+    pub payee: Pubkey,
+    pub deposit_lamports: u64,
+    pub expiry_slot: u64,
+}
+/// Represents a hash-locked payment being locked within a payment channel
+#[event]
+pub struct ChannelPaymentLocked {
+    pub channel: Pubkey,
+    pub amount_lamports: u64,
+    pub expiry_slot: u64,
+    pub secret_hash: [u8; 32],
+}
+/// Represents a hash-locked payment being claimed by the payee of a payment channel
+#[event]
+pub struct ChannelPaymentClaimed {
+    pub channel: Pubkey,
+    pub amount_lamports: u64,
+    pub secret: [u8; 32],
+}
+/// Represents an expired hash-locked payment being cancelled within a payment channel
+#[event]
+pub struct ChannelPaymentCancelled {
+    pub channel: Pubkey,
     pub secret_hash: [u8; 32],
 }
+/// Represents the closed state of a payment channel, where the remaining deposit has been
+/// returned to the funder
+#[event]
+pub struct ChannelClosed {
+    pub channel: Pubkey,
+    pub payee_amount_lamports: u64,
+}
 
 #[error_code]
 pub enum SwapError {
@@ -246,4 +8954,255 @@ pub enum SwapError {
 
     #[msg("Attempt to perform a refund before expiry time")]
     RefundBeforeExpiry,
+
+    #[msg("The provided swaps must be two distinct swaps")]
+    IdenticalSwaps,
+
+    #[msg("The epoch-denominated expiry must be at least one epoch and within the maximum expiry")]
+    InvalidEpochExpiry,
+
+    #[msg("The provided relayer is not the authorized relayer of this fee escrow")]
+    InvalidRelayer,
+
+    #[msg("The provided swap account is not the one associated with this fee escrow")]
+    InvalidSwapAccount,
+
+    #[msg("The requested amount exceeds the remaining fee allowance")]
+    InsufficientFeeAllowance,
+
+    #[msg("Attempt to close a fee escrow before its swap has completed")]
+    SwapNotCompleted,
+
+    #[msg("A hash-locked payment cannot outlive its payment channel")]
+    PaymentOutlivesChannel,
+
+    #[msg("The requested amount exceeds the channel's unlocked deposit")]
+    InsufficientChannelDeposit,
+
+    #[msg("The payment channel has reached its maximum number of pending payments")]
+    TooManyPendingPayments,
+
+    #[msg("A pending payment with this secret hash already exists in the payment channel")]
+    DuplicateSecretHash,
+
+    #[msg("The hash-locked payment has expired")]
+    ChannelPaymentExpired,
+
+    #[msg("No pending payment with this secret hash exists in the payment channel")]
+    UnknownChannelPayment,
+
+    #[msg("The provided authority is not the authority of this session")]
+    InvalidSessionAuthority,
+
+    #[msg("The provided signer is not the session key of this session")]
+    InvalidSessionKey,
+
+    #[msg("The session is not permitted to perform this instruction")]
+    SessionScopeNotPermitted,
+
+    #[msg("The session has expired")]
+    SessionExpired,
+
+    #[msg("The provided mint is not the mint of this token swap")]
+    InvalidMint,
+
+    #[msg("The provided admin is not the admin of this program")]
+    InvalidAdmin,
+
+    #[msg("The minimum expiry must not exceed the maximum expiry, which must not exceed MAX_EXPIRY_SLOTS")]
+    InvalidExpiryBounds,
+
+    #[msg("The swap's expiry is outside the bounds configured for this program")]
+    ExpiryOutOfBounds,
+
+    #[msg("The protocol fee must not exceed 10,000 basis points")]
+    InvalidProtocolFee,
+
+    #[msg("The requested amount exceeds the treasury's withdrawable balance")]
+    InsufficientTreasuryBalance,
+
+    #[msg("The initiation of new swaps is paused")]
+    InitiatePaused,
+
+    #[msg("The redeem amount must be non-zero and not exceed the swap's remaining amount")]
+    InvalidRedeemAmount,
+
+    #[msg("The number of accounts provided does not match the number of swaps in the batch")]
+    BatchAccountsMismatch,
+
+    #[msg("The incentives offered must not exceed the swap amount")]
+    IncentivesExceedSwapAmount,
+
+    #[msg("The transaction does not carry a valid ed25519 signature of the redeemer's consent")]
+    InvalidConsentSignature,
+
+    #[msg("The new expiry must be later than the swap's current expiry")]
+    InvalidExpiryExtension,
+
+    #[msg("The redeemer must sign to direct the funds to another destination")]
+    RedeemerSignatureRequired,
+
+    #[msg("The provided account is not the refund destination of this swap")]
+    InvalidRefundDestination,
+
+    #[msg("The redeem window must open before the swap's expiry and before its deadline")]
+    InvalidRedeemWindow,
+
+    #[msg("Attempt to perform a redeem outside the swap's redeem window")]
+    RedeemOutsideWindow,
+
+    #[msg("The expiry exceeds MAX_EXPIRY_SLOTS or overflows")]
+    ExpiryTooLarge,
+
+    #[msg("The swap amount must be non-zero")]
+    ZeroAmount,
+
+    #[msg("The swap expiry must be non-zero")]
+    ZeroExpiry,
+
+    #[msg("The redeemer of a swap must not be its initiator")]
+    SelfSwap,
+
+    #[msg("The provided payer is not the payer of this redeem receipt")]
+    InvalidReceiptPayer,
+
+    #[msg("The swap account is already at the current layout version")]
+    SwapAlreadyMigrated,
+
+    #[msg("The swap account's layout version is not supported by this program")]
+    UnsupportedSwapVersion,
+
+    #[msg("The swap nonce must be non-zero")]
+    ZeroNonce,
+
+    #[msg("Abandoned swaps may only be swept once the grace period after their expiry has passed")]
+    SweepBeforeGracePeriod,
+
+    #[msg("A bundle swap may escrow at most MAX_BUNDLE_TOKENS tokens")]
+    TooManyBundledTokens,
+
+    #[msg("A bundle swap may escrow each mint only once")]
+    DuplicateBundledMint,
+
+    #[msg("The vault must be an empty token account of the mint owned by the bundle swap account, without a delegate or close authority")]
+    InvalidBundleVault,
+
+    #[msg("The token account must be owned by the recipient of the bundle swap")]
+    InvalidBundleDestination,
+
+    #[msg("The mint must have a supply of one with zero decimals, no transfer fees, and no mint authority other than its master edition")]
+    NotAnNft,
+
+    #[msg("The provided account is not the metadata account of the NFT")]
+    InvalidNftMetadata,
+
+    #[msg("The config may permit at most MAX_TRANSFER_HOOK_PROGRAMS transfer hook programs")]
+    TooManyTransferHookPrograms,
+
+    #[msg("The transfer hook program of the mint is not permitted by the config")]
+    TransferHookNotPermitted,
+
+    #[msg("The starting relayer fee must not exceed the relayer fee")]
+    InvalidRelayerFeeAuction,
+
+    #[msg("The stream duration must be greater than zero")]
+    ZeroStreamDuration,
+
+    #[msg("The secret of the streamed swap has not been revealed")]
+    StreamNotRevealed,
+
+    #[msg("The secret of the streamed swap has already been revealed")]
+    StreamAlreadyRevealed,
+
+    #[msg("No funds of the streamed swap have unlocked since the last claim")]
+    NothingToClaim,
+
+    #[msg("A hash chain must commit to at least one swap")]
+    EmptyHashChain,
+
+    #[msg("The total amount of the swaps committed to the hash chain overflows")]
+    HashChainAmountTooLarge,
+
+    #[msg("Multi-secret and any-secret swaps must commit to between 2 and MAX_SECRET_HASHES secret hashes")]
+    InvalidSecretCount,
+
+    #[msg("The secret hash must be the commitment to the secret hashes")]
+    InvalidCombinedSecretHash,
+
+    #[msg(
+        "The transaction does not carry a valid ed25519 signature under the point of the PTLC swap"
+    )]
+    InvalidPtlcSignature,
+
+    #[msg("The transaction does not carry a valid secp256k1 signature by the committed key")]
+    InvalidSecp256k1Signature,
+
+    #[msg("The proof of knowledge of the secret is invalid")]
+    InvalidPreimageProof,
+
+    #[msg("The redeemer's share of a cooperative close cannot exceed 10000 basis points")]
+    InvalidCloseShare,
+
+    #[msg("The acceptance bond must be non-zero")]
+    ZeroAcceptanceBond,
+
+    #[msg("The swap does not require acceptance")]
+    AcceptanceNotRequired,
+
+    #[msg("The swap has already been accepted")]
+    SwapAlreadyAccepted,
+
+    #[msg("The swap may not be accepted once expired")]
+    AcceptAfterExpiry,
+
+    #[msg("The swap must be accepted by the redeemer before it may be redeemed")]
+    SwapNotAccepted,
+
+    #[msg("The swap expiry must be within the order's bounds, with its minimum not exceeding its maximum")]
+    InvalidOrderExpiry,
+
+    #[msg("The order may no longer be filled")]
+    OrderExpired,
+
+    #[msg("The minimum fill must be non-zero and not exceed the order's amount")]
+    InvalidMinFill,
+
+    #[msg("The fill must not exceed the order's remainder, and must be at least its minimum fill unless filling its remainder")]
+    InvalidFillAmount,
+
+    #[msg("The maximum price staleness must be non-zero and the maximum confidence interval must not exceed 10000 basis points")]
+    InvalidPriceBounds,
+
+    #[msg("The account is not a fully verified Pyth SOL/USD price update")]
+    InvalidPriceUpdate,
+
+    #[msg("The price was published too long ago")]
+    StalePrice,
+
+    #[msg("The price's confidence interval is too wide")]
+    PriceTooUncertain,
+
+    #[msg("The swap's escrow does not cover its notional at the current price")]
+    InsufficientOracleEscrow,
+
+    #[msg("The minimum lamports out must not exceed the maximum or the escrowed amount")]
+    InvalidSlippageBounds,
+
+    #[msg("The lamports released at the current price lie outside the swap's slippage bounds")]
+    SlippageExceeded,
+
+    #[msg("The account is not a VAA posted to the Wormhole core bridge")]
+    InvalidPostedVaa,
+
+    #[msg("The VAA was not emitted by the swap's emitter")]
+    InvalidVaaEmitter,
+
+    #[msg("The VAA does not attest to the settlement of this swap")]
+    InvalidVaaPayload,
+
+    #[msg("The funds of the streamed swap have fully unlocked, closing its dispute window")]
+    StreamFullyUnlocked,
+
+    #[msg("The channel's deposit overflows")]
+    ChannelDepositTooLarge,
 }
@@ -0,0 +1,60 @@
+// Generates the typed sources of the SDK from the IDL and types emitted by `anchor build`:
+// - src/generated/idl.json and src/generated/types.ts, copied as is
+// - src/generated/seeds.ts, the seeds of every PDA of the program, as declared by its instructions
+const fs = require("fs");
+const path = require("path");
+
+const root = path.resolve(__dirname, "..", "..");
+const idlPath = path.join(root, "target", "idl", "solana_native_swaps.json");
+const typesPath = path.join(root, "target", "types", "solana_native_swaps.ts");
+const outDir = path.resolve(__dirname, "..", "src", "generated");
+
+if (!fs.existsSync(idlPath) || !fs.existsSync(typesPath)) {
+	console.error(`${idlPath} not found, run \`anchor build\` first`);
+	process.exit(1);
+}
+
+const camelCase = (name) => name.replace(/_([a-z0-9])/g, (_, c) => c.toUpperCase());
+
+// Account seeds may reference a field of another account, e.g. `swap_account.initiator`,
+// which is named after the field alone, as arguments and accounts of the same name are
+// interchangeable as seeds
+const seedInput = (seed) => camelCase(seed.path.split(".").pop());
+
+const seeds = {};
+const collect = (accounts) => {
+	for (const account of accounts) {
+		if (account.accounts) {
+			collect(account.accounts);
+			continue;
+		}
+		const name = camelCase(account.name);
+		// Seeds referencing other programs' PDAs are not supported, nor needed
+		if (!account.pda || account.pda.program || seeds[name]) {
+			continue;
+		}
+		seeds[name] = account.pda.seeds.map((seed) =>
+			seed.kind === "const"
+				? { kind: "const", value: seed.value }
+				: { kind: "input", name: seedInput(seed) }
+		);
+	}
+};
+const idl = JSON.parse(fs.readFileSync(idlPath, "utf8"));
+for (const instruction of idl.instructions) {
+	collect(instruction.accounts);
+}
+
+fs.mkdirSync(outDir, { recursive: true });
+fs.copyFileSync(idlPath, path.join(outDir, "idl.json"));
+fs.copyFileSync(typesPath, path.join(outDir, "types.ts"));
+fs.writeFileSync(
+	path.join(outDir, "seeds.ts"),
+	`// Generated by scripts/generate.js from the program's IDL. Do not edit.
+
+export type Seed = { kind: "const"; value: number[] } | { kind: "input"; name: string };
+
+export const PDA_SEEDS = ${JSON.stringify(seeds, null, "\t")} satisfies Record<string, Seed[]>;
+`
+);
+console.log(`Generated the SDK sources of ${idl.metadata.name} ${idl.metadata.version}`);
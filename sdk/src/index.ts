@@ -0,0 +1,259 @@
+import { BN, Program, Provider, utils, web3 } from "@coral-xyz/anchor";
+import type { IdlAccounts, IdlEvents, IdlTypes } from "@coral-xyz/anchor";
+
+import idl from "./generated/idl.json";
+import { PDA_SEEDS, Seed } from "./generated/seeds";
+import type { SolanaNativeSwaps } from "./generated/types";
+
+export type { SolanaNativeSwaps };
+export { PDA_SEEDS };
+
+export const IDL = idl as SolanaNativeSwaps;
+export const PROGRAM_ID = new web3.PublicKey(IDL.address);
+
+export type HashAlgorithm = IdlTypes<SolanaNativeSwaps>["hashAlgorithm"];
+export type SwapIncentives = IdlTypes<SolanaNativeSwaps>["swapIncentives"];
+export type RedeemWindow = IdlTypes<SolanaNativeSwaps>["redeemWindow"];
+export type SwapAccount = IdlAccounts<SolanaNativeSwaps>["swapAccount"];
+export type SwapEvents = IdlEvents<SolanaNativeSwaps>;
+/** An event of the program, discriminated by its name */
+export type SwapEvent = { [N in keyof SwapEvents]: { name: N; data: SwapEvents[N] } }[keyof SwapEvents];
+
+/** The tag prefixing the data of the self-CPIs the program emits events through */
+const EVENT_IX_TAG = Buffer.from([0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d]);
+
+type SeedInput = web3.PublicKey | Uint8Array | number[];
+
+function findPda(seeds: readonly Seed[], inputs: Record<string, SeedInput>): web3.PublicKey {
+	const buffers = seeds.map((seed) => {
+		if (seed.kind === "const") {
+			return Buffer.from(seed.value);
+		}
+		const input = inputs[seed.name];
+		if (input === undefined) {
+			throw new Error(`Missing seed ${seed.name}`);
+		}
+		return input instanceof web3.PublicKey ? input.toBuffer() : Buffer.from(input);
+	});
+	return web3.PublicKey.findProgramAddressSync(buffers, PROGRAM_ID)[0];
+}
+
+/** Derivation of the program's PDAs, following the seeds declared in the IDL */
+export const pda = {
+	/** The swap account of the swap from `initiator` to `redeemer` with `secretHash` */
+	swapAccount: (initiator: web3.PublicKey, redeemer: web3.PublicKey, secretHash: Uint8Array | number[]) =>
+		findPda(PDA_SEEDS.swapAccount, { initiator, redeemer, secretHash }),
+	/** The swap account of a swap initiated through `initiateWithNonce` with the nonzero `nonce` */
+	swapAccountWithNonce: (
+		initiator: web3.PublicKey,
+		redeemer: web3.PublicKey,
+		secretHash: Uint8Array | number[],
+		nonce: number
+	) => {
+		const nonceSeed = Buffer.alloc(4);
+		nonceSeed.writeUInt32LE(nonce);
+		return findPda([...PDA_SEEDS.swapAccount, { kind: "input", name: "nonce" }], {
+			initiator,
+			redeemer,
+			secretHash,
+			nonce: nonceSeed,
+		});
+	},
+	/** The program-wide config */
+	config: () => findPda(PDA_SEEDS.config, {}),
+	/** The treasury collecting protocol fees */
+	treasury: () => findPda(PDA_SEEDS.treasury, {}),
+	/** The redeem receipt of the swap account at `swapAccount` */
+	redeemReceipt: (swapAccount: web3.PublicKey) => findPda(PDA_SEEDS.receipt, { swapAccount }),
+	/** The authority the program emits events through, by invoking itself */
+	eventAuthority: () => findPda(PDA_SEEDS.eventAuthority, {}),
+};
+
+/**
+ * Conversions between slots and epochs as per the cluster's `EpochSchedule`, fetched through
+ * `connection.getEpochSchedule()`, for swaps initiated through `initiateWithEpochExpiry`,
+ * whose expiry is the first slot of an epoch
+ */
+export const epochs = {
+	/** The most epochs a swap may be initiated to expire in, given the configured maximum expiry */
+	maxExpiresInEpochs: (schedule: web3.EpochSchedule, maxExpirySlots: BN) =>
+		maxExpirySlots.divn(schedule.slotsPerEpoch).toNumber(),
+	/** The expiry slot of a swap initiated at `slot` to expire in `expiresInEpochs` epochs */
+	expirySlot: (schedule: web3.EpochSchedule, slot: number, expiresInEpochs: number) =>
+		schedule.getFirstSlotInEpoch(schedule.getEpoch(slot) + expiresInEpochs),
+	/**
+	 * The fewest epochs a swap initiated at `slot` must be initiated to expire in for it to remain
+	 * unrefundable for at least `expiresInSlots` slots
+	 */
+	expiresInEpochsForSlots: (schedule: web3.EpochSchedule, slot: number, expiresInSlots: number) => {
+		const expirySlot = slot + expiresInSlots;
+		let expiryEpoch = schedule.getEpoch(expirySlot);
+		if (schedule.getFirstSlotInEpoch(expiryEpoch) < expirySlot) {
+			expiryEpoch += 1;
+		}
+		return Math.max(expiryEpoch - schedule.getEpoch(slot), 1);
+	},
+};
+
+export interface InitiateArgs {
+	initiator: web3.PublicKey;
+	redeemer: web3.PublicKey;
+	secretHash: Uint8Array | number[];
+	amountLamports: BN;
+	expiresInSlots: BN;
+	/** Defaults to SHA-256 */
+	hashAlgorithm?: HashAlgorithm;
+	/** Defaults to no incentives */
+	incentives?: SwapIncentives;
+	/** Defaults to no redeem window */
+	redeemWindow?: RedeemWindow;
+	/** Provides the swap amount on behalf of the initiator, and must then sign too */
+	funder?: web3.PublicKey;
+	/** Receives refunds in place of the initiator */
+	refundTo?: web3.PublicKey;
+}
+
+/** Identifies a swap by the seeds of its swap account */
+export interface SwapRef {
+	initiator: web3.PublicKey;
+	redeemer: web3.PublicKey;
+	secretHash: Uint8Array | number[];
+}
+
+export interface RedeemArgs extends SwapRef {
+	secret: Uint8Array | number[];
+	/** The funds redeemed, the entirety of the swap's remaining funds for a full redeem */
+	amountLamports: BN;
+	/** Receives the funds in place of the redeemer, who must then sign */
+	destination?: web3.PublicKey;
+	/** Submits the redeem on the redeemer's behalf claiming the relayer fee, and must then sign */
+	relayer?: web3.PublicKey;
+	/** Funds a receipt retaining the secret, and must then sign */
+	receiptPayer?: web3.PublicKey;
+}
+
+export interface RefundArgs extends SwapRef {
+	/** The refund destination specified at initiate, if any */
+	refundTo?: web3.PublicKey;
+	/** Submits the refund claiming the refund tip, and must then sign */
+	refunder?: web3.PublicKey;
+}
+
+/** The redeemer must sign the instruction */
+export interface InstantRefundArgs extends SwapRef {
+	/** The refund destination specified at initiate, if any */
+	refundTo?: web3.PublicKey;
+}
+
+/**
+ * Builds the instructions of the swap lifecycle and decodes the program's events.
+ * Only the provider's connection is used, so `{ connection }` suffices as a provider.
+ */
+export class SwapsClient {
+	readonly program: Program<SolanaNativeSwaps>;
+
+	constructor(provider: Provider) {
+		this.program = new Program(IDL, provider);
+	}
+
+	initiate(args: InitiateArgs): Promise<web3.TransactionInstruction> {
+		return this.program.methods
+			.initiate(
+				args.amountLamports,
+				args.expiresInSlots,
+				args.redeemer,
+				[...args.secretHash],
+				args.hashAlgorithm ?? { sha256: {} },
+				args.incentives ?? { relayerFeeLamports: new BN(0), refundTipLamports: new BN(0), relayerFeeStartLamports: null },
+				args.redeemWindow ?? { redeemAfterSlot: null, redeemDeadlineSlot: null }
+			)
+			.accountsPartial({
+				swapAccount: pda.swapAccount(args.initiator, args.redeemer, args.secretHash),
+				initiator: args.initiator,
+				funder: args.funder ?? null,
+				refundTo: args.refundTo ?? null,
+			})
+			.instruction();
+	}
+
+	redeem(args: RedeemArgs): Promise<web3.TransactionInstruction> {
+		const swapAccount = pda.swapAccount(args.initiator, args.redeemer, args.secretHash);
+		return this.program.methods
+			.redeem([...args.secret], args.amountLamports)
+			.accountsPartial({
+				swapAccount,
+				initiator: args.initiator,
+				redeemer: args.redeemer,
+				destination: args.destination ?? null,
+				relayer: args.relayer ?? null,
+				receipt: args.receiptPayer ? pda.redeemReceipt(swapAccount) : null,
+				receiptPayer: args.receiptPayer ?? null,
+			})
+			.instruction();
+	}
+
+	refund(args: RefundArgs): Promise<web3.TransactionInstruction> {
+		return this.program.methods
+			.refund()
+			.accountsPartial({
+				swapAccount: pda.swapAccount(args.initiator, args.redeemer, args.secretHash),
+				initiator: args.initiator,
+				refundTo: args.refundTo ?? null,
+				refunder: args.refunder ?? null,
+			})
+			.instruction();
+	}
+
+	instantRefund(args: InstantRefundArgs): Promise<web3.TransactionInstruction> {
+		return this.program.methods
+			.instantRefund()
+			.accountsPartial({
+				swapAccount: pda.swapAccount(args.initiator, args.redeemer, args.secretHash),
+				initiator: args.initiator,
+				refundTo: args.refundTo ?? null,
+				redeemer: args.redeemer,
+			})
+			.instruction();
+	}
+
+	/** Fetches the open swap from `initiator` to `redeemer` with `secretHash`, if any */
+	fetchSwap(
+		initiator: web3.PublicKey,
+		redeemer: web3.PublicKey,
+		secretHash: Uint8Array | number[]
+	): Promise<SwapAccount | null> {
+		return this.program.account.swapAccount.fetchNullable(pda.swapAccount(initiator, redeemer, secretHash));
+	}
+
+	/**
+	 * Decodes an event from the data of a self-CPI of the program.
+	 * Returns null for any other instruction data.
+	 */
+	decodeEvent(data: Uint8Array): SwapEvent | null {
+		const buffer = Buffer.from(data);
+		if (buffer.length < EVENT_IX_TAG.length || !buffer.subarray(0, EVENT_IX_TAG.length).equals(EVENT_IX_TAG)) {
+			return null;
+		}
+		return this.program.coder.events.decode(buffer.subarray(EVENT_IX_TAG.length).toString("base64")) as SwapEvent | null;
+	}
+
+	/** Decodes the events emitted by the program in a transaction, in the order they were emitted */
+	parseEvents(transaction: web3.VersionedTransactionResponse): SwapEvent[] {
+		const accountKeys = transaction.transaction.message.getAccountKeys({
+			accountKeysFromLookups: transaction.meta?.loadedAddresses,
+		});
+		const events: SwapEvent[] = [];
+		for (const inner of transaction.meta?.innerInstructions ?? []) {
+			for (const instruction of inner.instructions) {
+				if (!accountKeys.get(instruction.programIdIndex)?.equals(PROGRAM_ID)) {
+					continue;
+				}
+				const event = this.decodeEvent(utils.bytes.bs58.decode(instruction.data));
+				if (event) {
+					events.push(event);
+				}
+			}
+		}
+		return events;
+	}
+}
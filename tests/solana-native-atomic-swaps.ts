@@ -11,6 +11,17 @@ const connection = anchor.getProvider().connection;
 const program = anchor.workspace.SolanaNativeSwaps as Program<SolanaNativeSwaps>;
 const LAMPORTS_PER_SOL = anchor.web3.LAMPORTS_PER_SOL;
 const MILLIS_PER_SLOT = 400;
+const NO_INCENTIVES = { relayerFeeLamports: new anchor.BN(0), refundTipLamports: new anchor.BN(0) };
+const NO_REDEEM_WINDOW = { redeemAfterSlot: null, redeemDeadlineSlot: null };
+
+before(async () => {
+	// The provider's wallet deploys the program, and is thus its upgrade authority
+	const [programData,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[program.programId.toBuffer()], new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111"));
+	await program.methods.initializeConfig(new anchor.BN(1), new anchor.BN(10_000_000))
+		.accounts({ programData, admin: anchor.getProvider().publicKey })
+		.rpc();
+});
 
 describe("Testing one way swap between Alice and Bob", () => {
 	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
@@ -26,7 +37,7 @@ describe("Testing one way swap between Alice and Bob", () => {
 	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
 
 	// PDA
-	const pdaSeeds = [Buffer.from("swap_account"), alice.publicKey.toBuffer(), Buffer.from(secretHash)];
+	const pdaSeeds = [Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)];
 	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(pdaSeeds, program.programId);
 	const size = program.account.swapAccount.size;
 	let rentAmount: number;
@@ -35,7 +46,7 @@ describe("Testing one way swap between Alice and Bob", () => {
 
 	const aliceInitiate = () => new Promise<void>(async resolve => {
 		console.log("alice is initiating");
-		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash)
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
 			.accounts({
 				initiator: alice.publicKey,
 			}).signers([alice]).rpc()
@@ -61,11 +72,14 @@ describe("Testing one way swap between Alice and Bob", () => {
 		await aliceInitiate();
 		const pdaBalance = await connection.getBalance(swapAccount);
 		expect(pdaBalance - rentAmount).to.equal(swapAmount.toNumber());
+		const swap = await program.account.swapAccount.fetch(swapAccount);
+		expect(swap.createdAtSlot.toNumber()).to.be.at.most(await connection.getSlot());
+		expect(swap.createdAtUnix.toNumber()).to.be.greaterThan(0);
 	});
 
 	it("Test redeem", async () => {
 		// The previous test has already initiated the swap
-		await program.methods.redeem([...secret])
+		await program.methods.redeem([...secret], swapAmount)
 			.accounts({
 				swapAccount,
 				initiator: alice.publicKey,
@@ -116,4 +130,935 @@ describe("Testing one way swap between Alice and Bob", () => {
 		const pdaBalance = await connection.getBalance(swapAccount);
 		expect(pdaBalance).to.equal(0);
 	});
+
+	it("Test instant refund with the redeemer's pre-signed consent", async () => {
+		await aliceInitiate();  // Re-initiating for the sake of testcase
+		// Bob signs his consent off-chain, and need not be present when Alice refunds. The consent
+		// is bound to this instance of the swap, so it cannot be replayed against a later one
+		const swap = await program.account.swapAccount.fetch(swapAccount);
+		const consent = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+			privateKey: bob.secretKey,
+			message: Buffer.concat([
+				Buffer.from("instant_refund"),
+				swapAccount.toBuffer(),
+				swap.createdAtSlot.toArrayLike(Buffer, "le", 8),
+				swap.expirySlot.toArrayLike(Buffer, "le", 8),
+				swap.amountLamports.toArrayLike(Buffer, "le", 8),
+			]),
+		});
+		await program.methods.instantRefundWithSignature()
+			.accounts({
+				swapAccount,
+				initiator: alice.publicKey,
+			}).preInstructions([consent])
+			.rpc()
+			.then(async signature => {
+				console.log("Alice instant-refunded with Bob's pre-signed consent with Signature:", signature);
+				await connection.confirmTransaction({signature, ...(await connection.getLatestBlockhash())});
+			});
+
+		const pdaBalance = await connection.getBalance(swapAccount);
+		expect(pdaBalance).to.equal(0);
+	});
+});
+
+describe("Testing expiry boundary inclusivity", () => {
+	const swapAmount = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+	});
+
+	// Refunds are rejected strictly before the expiry slot, and allowed from the expiry slot onwards
+	for (const expiresInSlots of [4, 8, 16]) {
+		it(`Test refund boundary with an expiry of ${expiresInSlots} slots`, async () => {
+			const secretHash = [...crypto.randomBytes(32)];
+			const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+				[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+			await program.methods.initiate(swapAmount, new anchor.BN(expiresInSlots), bob.publicKey, secretHash, { sha256: {} },
+				NO_INCENTIVES, NO_REDEEM_WINDOW)
+				.accounts({ initiator: alice.publicKey }).signers([alice]).rpc({ commitment: "confirmed" });
+			const expirySlot = (await program.account.swapAccount.fetch(swapAccount, "confirmed")).expirySlot.toNumber();
+			const refund = program.methods.refund().accounts({ swapAccount, initiator: alice.publicKey });
+
+			// Every slot well before the expiry slot must be rejected
+			while (await connection.getSlot() < expirySlot - 1) {
+				const simulation = await refund.simulate().then(() => null, err => err);
+				expect(simulation?.toString()).to.contain("RefundBeforeExpiry");
+			}
+
+			let signature: string;
+			while (!signature) {
+				signature = await refund.rpc({ commitment: "confirmed" }).catch(() => null);
+			}
+			const refundSlot = (await connection.getTransaction(signature, { commitment: "confirmed" })).slot;
+			expect(refundSlot).to.be.at.least(expirySlot);
+			expect(await connection.getBalance(swapAccount)).to.equal(0);
+		});
+	}
+});
+
+describe("Testing initiation parameter validation", () => {
+	const swapAmount = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+	});
+
+	it("Test initiation with an overflowing expiry fails", async () => {
+		const maxU64 = new anchor.BN("18446744073709551615");
+		const error = await program.methods.initiate(swapAmount, maxU64, bob.publicKey, [...crypto.randomBytes(32)], { sha256: {} },
+			NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc()
+			.then(() => null, err => err);
+		expect(error?.toString()).to.contain("ExpiryTooLarge");
+	});
+
+	it("Test initiation with a zero amount fails", async () => {
+		const error = await program.methods.initiate(new anchor.BN(0), new anchor.BN(10), bob.publicKey, [...crypto.randomBytes(32)],
+			{ sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc()
+			.then(() => null, err => err);
+		expect(error?.toString()).to.contain("ZeroAmount");
+	});
+
+	it("Test initiation with a zero expiry fails", async () => {
+		const error = await program.methods.initiate(swapAmount, new anchor.BN(0), bob.publicKey, [...crypto.randomBytes(32)],
+			{ sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc()
+			.then(() => null, err => err);
+		expect(error?.toString()).to.contain("ZeroExpiry");
+	});
+
+	it("Test initiation with the initiator as redeemer fails", async () => {
+		const error = await program.methods.initiate(swapAmount, new anchor.BN(10), alice.publicKey, [...crypto.randomBytes(32)],
+			{ sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc()
+			.then(() => null, err => err);
+		expect(error?.toString()).to.contain("SelfSwap");
+	});
+});
+
+describe("Testing events emitted through self-CPI", () => {
+	const swapAmount = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(10);
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...crypto.randomBytes(32)];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+	});
+
+	it("Test the initiated event is recorded as an inner instruction", async () => {
+		const signature = await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} },
+			NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc({ commitment: "confirmed" });
+		const tx = await connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+		const events = tx.meta.innerInstructions
+			.flatMap(inner => inner.instructions)
+			.map(ix => anchor.utils.bytes.bs58.decode(ix.data))
+			// Skip the 8-byte event instruction tag preceding the event itself
+			.map(data => program.coder.events.decode(anchor.utils.bytes.base64.encode(data.subarray(8))))
+			.filter(event => event !== null);
+		expect(events.map(event => event.name)).to.deep.equal(["initiated"]);
+		expect(events[0].data.swapAccount.toBase58()).to.equal(swapAccount.toBase58());
+	});
+});
+
+describe("Testing the swap account layout", () => {
+	const swapAmount = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...crypto.randomBytes(32)];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc({ commitment: "confirmed" });
+	});
+
+	it("Test swaps are initiated at the current version", async () => {
+		const swap = await program.account.swapAccount.fetch(swapAccount, "confirmed");
+		expect(swap.version).to.equal(4);
+	});
+
+	it("Test migrating a swap at the current version fails", async () => {
+		const error = await program.methods.migrate()
+			.accounts({ swapAccount, payer: alice.publicKey }).signers([alice]).rpc()
+			.then(() => null, err => err);
+		expect(error?.toString()).to.contain("SwapAlreadyMigrated");
+	});
+
+	it("Test swaps can be filtered by initiator at a fixed offset", async () => {
+		// The discriminator, followed by 8 bytes of small fields and 9 u64 fields, precede the initiator
+		const initiatorOffset = 8 + 8 + 9 * 8;
+		const swaps = await program.account.swapAccount.all([
+			{ memcmp: { offset: initiatorOffset, bytes: alice.publicKey.toBase58() } },
+		]);
+		expect(swaps.map(swap => swap.publicKey.toBase58())).to.deep.equal([swapAccount.toBase58()]);
+	});
+});
+
+describe("Testing refund destination specified at initiate", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const aliceColdWallet = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey, refundTo: aliceColdWallet.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test instant refund to the refund destination", async () => {
+		await program.methods.instantRefund()
+			.accounts({
+				swapAccount,
+				initiator: alice.publicKey,
+				refundTo: aliceColdWallet.publicKey,
+				redeemer: bob.publicKey,
+			}).signers([bob])
+			.rpc();
+
+		expect(await connection.getBalance(aliceColdWallet.publicKey)).to.equal(swapAmount.toNumber());
+		expect(await connection.getBalance(swapAccount)).to.equal(0);
+	});
+});
+
+describe("Testing initiation funded by a third party", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const custodian = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		for (const party of [alice, custodian]) {
+			const airdropSig = await connection.requestAirdrop(party.publicKey, 1 * LAMPORTS_PER_SOL);
+			await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		}
+	});
+
+	it("Test the funder provides the swap amount", async () => {
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey, funder: custodian.publicKey }).signers([alice, custodian]).rpc();
+
+		expect(await connection.getBalance(custodian.publicKey)).to.equal(1 * LAMPORTS_PER_SOL - swapAmount.toNumber());
+		const swap = await program.account.swapAccount.fetch(swapAccount);
+		expect(swap.initiator.equals(alice.publicKey)).to.equal(true);
+	});
+});
+
+describe("Testing net settlement of opposing swaps between Alice and Bob", () => {
+	const aliceSwapAmount = new anchor.BN(0.3 * LAMPORTS_PER_SOL);
+	const bobSwapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const airdropAmount = 1 * LAMPORTS_PER_SOL;
+
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const aliceSecretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bobSecretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+
+	const [aliceSwap,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(aliceSecretHash)], program.programId);
+	const [bobSwap,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), bob.publicKey.toBuffer(), alice.publicKey.toBuffer(), Buffer.from(bobSecretHash)], program.programId);
+
+	before(async () => {
+		for (const party of [alice, bob]) {
+			const airdropSig = await connection.requestAirdrop(party.publicKey, airdropAmount);
+			await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		}
+		await program.methods.initiate(aliceSwapAmount, expiresInSlots, bob.publicKey, aliceSecretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+		await program.methods.initiate(bobSwapAmount, expiresInSlots, alice.publicKey, bobSecretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: bob.publicKey }).signers([bob]).rpc();
+	});
+
+	it("Test net settle", async () => {
+		const bobBalanceBefore = await connection.getBalance(bob.publicKey);
+		const bobSwapBalance = await connection.getBalance(bobSwap);
+
+		await program.methods.netSettle()
+			.accounts({
+				swapA: aliceSwap,
+				swapB: bobSwap,
+				partyA: alice.publicKey,
+				partyB: bob.publicKey,
+			}).signers([alice, bob])
+			.rpc()
+			.then(async signature => {
+				console.log("Alice and Bob net-settled with Signature:", signature);
+				await connection.confirmTransaction({signature, ...(await connection.getLatestBlockhash())});
+			});
+
+		expect(await connection.getBalance(aliceSwap)).to.equal(0);
+		expect(await connection.getBalance(bobSwap)).to.equal(0);
+		// Bob receives the difference on top of his own swap account's balance
+		const bobBalanceAfter = await connection.getBalance(bob.publicKey);
+		expect(bobBalanceAfter - bobBalanceBefore)
+			.to.equal(bobSwapBalance + aliceSwapAmount.sub(bobSwapAmount).toNumber());
+	});
+});
+
+describe("Testing epoch-denominated expiry", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInEpochs = new anchor.BN(2);
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+	});
+
+	it("Test initiation with epoch expiry", async () => {
+		await program.methods.initiateWithEpochExpiry(swapAmount, expiresInEpochs, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc({ commitment: "confirmed" });
+
+		const { epoch } = await connection.getEpochInfo("confirmed");
+		const epochSchedule = await connection.getEpochSchedule();
+		const swap = await program.account.swapAccount.fetch(swapAccount, "confirmed");
+		// Refunds are allowed from the first slot of the expiry epoch onwards
+		expect(swap.expirySlot.toNumber())
+			.to.equal(epochSchedule.getFirstSlotInEpoch(epoch + expiresInEpochs.toNumber()));
+	});
+});
+
+describe("Testing dual slot and timestamp expiry", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const expiresInSeconds = new anchor.BN(60); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.initiateWithDualExpiry(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW, expiresInSeconds)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test refund before the expiry timestamp fails", async () => {
+		await new Promise(r => setTimeout(r, (expiresInSlots.toNumber() + 1) * MILLIS_PER_SLOT));
+		let refunded = true;
+		await program.methods.refund()
+			.accounts({ swapAccount, initiator: alice.publicKey }).rpc()
+			.catch(() => { refunded = false; });
+		expect(refunded).to.equal(false);
+		expect(await connection.getBalance(swapAccount)).to.be.greaterThan(swapAmount.toNumber());
+	});
+});
+
+describe("Testing expiry extension with mutual consent", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const extensionSlots = 100;
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc({ commitment: "confirmed" });
+	});
+
+	it("Test extending the expiry", async () => {
+		const { expirySlot } = await program.account.swapAccount.fetch(swapAccount, "confirmed");
+		const newExpirySlot = expirySlot.addn(extensionSlots);
+		await program.methods.extendExpiry(newExpirySlot)
+			.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey })
+			.signers([alice, bob])
+			.rpc({ commitment: "confirmed" });
+
+		const swap = await program.account.swapAccount.fetch(swapAccount, "confirmed");
+		expect(swap.expirySlot.eq(newExpirySlot)).to.equal(true);
+	});
+});
+
+describe("Testing redeem grace period", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const gracePeriodSlots = 5_000 / MILLIS_PER_SLOT; // 5 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+	const bobRedeem = () => program.methods.redeem([...secret], swapAmount)
+		.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey }).rpc();
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		const redeemAfterSlot = new anchor.BN(await connection.getSlot() + gracePeriodSlots);
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES,
+			{ ...NO_REDEEM_WINDOW, redeemAfterSlot })
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test redeem during the grace period fails", async () => {
+		let redeemed = true;
+		await bobRedeem().catch(() => { redeemed = false; });
+		expect(redeemed).to.equal(false);
+	});
+
+	it("Test redeem after the grace period", async () => {
+		console.log("Awaiting grace period for redeem");
+		await new Promise(r => setTimeout(r, (gracePeriodSlots + 1) * MILLIS_PER_SLOT));
+		await bobRedeem();
+		expect(await connection.getBalance(bob.publicKey)).to.equal(swapAmount.toNumber());
+	});
+});
+
+describe("Testing redeem deadline", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const deadlineSlots = 800 / MILLIS_PER_SLOT; // 0.8 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		const redeemDeadlineSlot = new anchor.BN(await connection.getSlot() + deadlineSlots);
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES,
+			{ ...NO_REDEEM_WINDOW, redeemDeadlineSlot })
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test redeem after the deadline fails", async () => {
+		console.log("Awaiting redeem deadline");
+		await new Promise(r => setTimeout(r, (deadlineSlots + 1) * MILLIS_PER_SLOT));
+		let redeemed = true;
+		await program.methods.redeem([...secret], swapAmount)
+			.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey }).rpc()
+			.catch(() => { redeemed = false; });
+		expect(redeemed).to.equal(false);
+		expect(await connection.getBalance(bob.publicKey)).to.equal(0);
+	});
+});
+
+describe("Testing protocol fee on redeem", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const protocolFeeBps = 30;
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+	const [treasury,] = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId);
+	const admin = anchor.getProvider().publicKey;
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.updateProtocolFee(protocolFeeBps).accounts({ admin }).rpc();
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	after(async () => {
+		await program.methods.updateProtocolFee(0).accounts({ admin }).rpc();
+	});
+
+	it("Test redeem pays the protocol fee to the treasury", async () => {
+		const treasuryBalanceBefore = await connection.getBalance(treasury);
+		await program.methods.redeem([...secret], swapAmount)
+			.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey }).rpc();
+
+		const protocolFee = swapAmount.muln(protocolFeeBps).divn(10_000).toNumber();
+		expect(await connection.getBalance(treasury) - treasuryBalanceBefore).to.equal(protocolFee);
+		expect(await connection.getBalance(bob.publicKey)).to.equal(swapAmount.toNumber() - protocolFee);
+	});
+});
+
+describe("Testing the initiate pause switch", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+	const admin = anchor.getProvider().publicKey;
+	const aliceInitiate = () => program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+		.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+	});
+
+	it("Test initiation is blocked while paused", async () => {
+		await program.methods.setInitiatePaused(true).accounts({ admin }).rpc();
+		let initiated = true;
+		await aliceInitiate().catch(() => { initiated = false; });
+		expect(initiated).to.equal(false);
+		expect(await connection.getBalance(swapAccount)).to.equal(0);
+	});
+
+	it("Test initiation resumes once unpaused", async () => {
+		await program.methods.setInitiatePaused(false).accounts({ admin }).rpc();
+		await aliceInitiate();
+		expect(await connection.getBalance(swapAccount)).to.be.greaterThan(swapAmount.toNumber());
+	});
+});
+
+describe("Testing partial redeems", () => {
+	const swapAmount = new anchor.BN(0.3 * LAMPORTS_PER_SOL);
+	const trancheAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test redeeming in tranches", async () => {
+		for (let i = 1; i <= 3; i++) {
+			await program.methods.redeem([...secret], trancheAmount)
+				.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey })
+				.rpc({ commitment: "confirmed" });
+			expect(await connection.getBalance(bob.publicKey, "confirmed")).to.equal(trancheAmount.muln(i).toNumber());
+		}
+		// The swap account is closed once fully redeemed
+		expect(await connection.getBalance(swapAccount, "confirmed")).to.equal(0);
+	});
+});
+
+describe("Testing batch initiation", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHashes = [0, 1, 2].map(() => [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())]);
+	const swapAccounts = secretHashes.map(secretHash => anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId)[0]);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+	});
+
+	it("Test initiating multiple swaps in one transaction", async () => {
+		const swaps = secretHashes.map(secretHash => ({
+			amountLamports: swapAmount,
+			expiresInSlots,
+			redeemer: bob.publicKey,
+			secretHash,
+			hashAlgorithm: { sha256: {} },
+			incentives: NO_INCENTIVES,
+			refundTo: null,
+			redeemWindow: NO_REDEEM_WINDOW,
+		}));
+		await program.methods.initiateBatch(swaps)
+			.accounts({ initiator: alice.publicKey })
+			.remainingAccounts(swapAccounts.map(pubkey => ({ pubkey, isWritable: true, isSigner: false })))
+			.signers([alice]).rpc();
+
+		const rentAmount = await connection.getMinimumBalanceForRentExemption(program.account.swapAccount.size);
+		for (const swapAccount of swapAccounts) {
+			expect(await connection.getBalance(swapAccount) - rentAmount).to.equal(swapAmount.toNumber());
+			const swap = await program.account.swapAccount.fetch(swapAccount);
+			expect(swap.redeemer.equals(bob.publicKey)).to.equal(true);
+		}
+	});
+});
+
+describe("Testing the batch-refund crank", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHashes = [0, 1].map(() => [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())]);
+	const swapAccounts = secretHashes.map(secretHash => anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId)[0]);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		for (const secretHash of secretHashes) {
+			await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+				.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+		}
+	});
+
+	it("Test refunding expired swaps in one transaction", async () => {
+		console.log("Awaiting timelock for refund");
+		await new Promise(r => setTimeout(r, (expiresInSlots.toNumber() + 1) * MILLIS_PER_SLOT));
+		await program.methods.refundExpiredBatch()
+			.remainingAccounts(swapAccounts.flatMap(swapAccount => [
+				{ pubkey: swapAccount, isWritable: true, isSigner: false },
+				{ pubkey: alice.publicKey, isWritable: true, isSigner: false },
+				{ pubkey: alice.publicKey, isWritable: true, isSigner: false },
+			]))
+			.rpc();
+
+		for (const swapAccount of swapAccounts) {
+			expect(await connection.getBalance(swapAccount)).to.equal(0);
+		}
+	});
+});
+
+describe("Testing relayer fee carved out on redeem", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const relayerFee = new anchor.BN(0.001 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const relayer = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		for (const party of [alice, relayer]) {
+			const airdropSig = await connection.requestAirdrop(party.publicKey, 1 * LAMPORTS_PER_SOL);
+			await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		}
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} },
+			{ ...NO_INCENTIVES, relayerFeeLamports: relayerFee }, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test redeem submitted by a relayer", async () => {
+		await program.methods.redeem([...secret], swapAmount)
+			.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey, relayer: relayer.publicKey })
+			.signers([relayer])
+			.transaction()
+			.then(async tx => {
+				tx.feePayer = relayer.publicKey;
+				await anchor.web3.sendAndConfirmTransaction(connection, tx, [relayer]);
+			});
+
+		expect(await connection.getBalance(bob.publicKey)).to.equal(swapAmount.sub(relayerFee).toNumber());
+		// The relayer pays the transaction fee, and is compensated by the relayer fee
+		expect(await connection.getBalance(relayer.publicKey))
+			.to.be.greaterThan(1 * LAMPORTS_PER_SOL + relayerFee.toNumber() - 10_000);
+	});
+});
+
+describe("Testing redeem to a destination chosen by the redeemer", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const omnibusWallet = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test redeem to the destination", async () => {
+		const redeem = await program.methods.redeem([...secret], swapAmount)
+			.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey, destination: omnibusWallet.publicKey })
+			.instruction();
+		// The redeemer only signs when directing the funds elsewhere
+		redeem.keys.find(key => key.pubkey.equals(bob.publicKey)).isSigner = true;
+		await anchor.getProvider().sendAndConfirm(new anchor.web3.Transaction().add(redeem), [bob]);
+
+		expect(await connection.getBalance(omnibusWallet.publicKey)).to.equal(swapAmount.toNumber());
+		expect(await connection.getBalance(bob.publicKey)).to.equal(0);
+	});
+});
+
+describe("Testing redeem receipts retaining the secret", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const relayer = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+	const [receipt,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("redeem_receipt"), swapAccount.toBuffer()], program.programId);
+
+	before(async () => {
+		for (const account of [alice, relayer]) {
+			const airdropSig = await connection.requestAirdrop(account.publicKey, 1 * LAMPORTS_PER_SOL);
+			await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		}
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test redeem records the secret in the receipt", async () => {
+		await program.methods.redeem([...secret], swapAmount)
+			.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey, receipt, receiptPayer: relayer.publicKey })
+			.signers([relayer]).rpc();
+
+		expect(await connection.getBalance(swapAccount)).to.equal(0);
+		const state = await program.account.redeemReceipt.fetch(receipt);
+		expect(state.swapAccount.toBase58()).to.equal(swapAccount.toBase58());
+		expect(Buffer.from(state.secret).equals(secret)).to.equal(true);
+	});
+
+	it("Test closing the receipt returns its rent to the payer", async () => {
+		await program.methods.closeRedeemReceipt()
+			.accounts({ receipt, payer: relayer.publicKey }).signers([relayer]).rpc();
+		expect(await connection.getBalance(receipt)).to.equal(0);
+	});
+});
+
+describe("Testing redeem with the redeemer's pre-signed consent", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bobColdWallet = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test redeem to the consented destination", async () => {
+		// Bob signs his consent off-chain, and need not be present when the secret is revealed
+		const swap = await program.account.swapAccount.fetch(swapAccount);
+		const consent = anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+			privateKey: bob.secretKey,
+			message: Buffer.concat([
+				Buffer.from("redeem"),
+				swapAccount.toBuffer(),
+				swap.createdAtSlot.toArrayLike(Buffer, "le", 8),
+				swap.expirySlot.toArrayLike(Buffer, "le", 8),
+				swap.amountLamports.toArrayLike(Buffer, "le", 8),
+				bobColdWallet.publicKey.toBuffer(),
+			]),
+		});
+		await program.methods.redeemWithSignature([...secret])
+			.accounts({ swapAccount, initiator: alice.publicKey, destination: bobColdWallet.publicKey })
+			.preInstructions([consent])
+			.rpc();
+
+		expect(await connection.getBalance(bobColdWallet.publicKey)).to.equal(swapAmount.toNumber());
+		expect(await connection.getBalance(swapAccount)).to.equal(0);
+	});
+});
+
+describe("Testing refund tip for third-party refunds", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const refundTip = new anchor.BN(0.001 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const refunder = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+
+	before(async () => {
+		for (const party of [alice, refunder]) {
+			const airdropSig = await connection.requestAirdrop(party.publicKey, 1 * LAMPORTS_PER_SOL);
+			await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		}
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} },
+			{ ...NO_INCENTIVES, refundTipLamports: refundTip }, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test refund submitted by a third party", async () => {
+		console.log("Awaiting timelock for refund");
+		await new Promise(r => setTimeout(r, (expiresInSlots.toNumber() + 1) * MILLIS_PER_SLOT));
+		const refunderBalanceBefore = await connection.getBalance(refunder.publicKey);
+		await program.methods.refund()
+			.accounts({ swapAccount, initiator: alice.publicKey, refunder: refunder.publicKey })
+			.signers([refunder])
+			.rpc();
+
+		// The provider's wallet pays the transaction fee here
+		expect(await connection.getBalance(refunder.publicKey) - refunderBalanceBefore).to.equal(refundTip.toNumber());
+	});
+});
+
+describe("Testing relayer fee provisioning", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const allowance = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(800 / MILLIS_PER_SLOT); // 0.8 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const relayer = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secret = crypto.randomBytes(32);
+	const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+	const [feeEscrow,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("fee_escrow"), swapAccount.toBuffer()], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+	});
+
+	it("Test provisioning alongside initiation", async () => {
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey })
+			.postInstructions([
+				await program.methods.provisionRelayerFees(allowance, relayer.publicKey)
+					.accounts({ swapAccount, initiator: alice.publicKey }).instruction(),
+			])
+			.signers([alice]).rpc();
+
+		const rentAmount = await connection.getMinimumBalanceForRentExemption(program.account.feeEscrow.size);
+		expect(await connection.getBalance(feeEscrow) - rentAmount).to.equal(allowance.toNumber());
+	});
+
+	it("Test relayer draw and escrow closure", async () => {
+		const drawAmount = allowance.divn(2);
+		await program.methods.drawRelayerFees(drawAmount)
+			.accounts({ feeEscrow, relayer: relayer.publicKey }).signers([relayer]).rpc();
+		expect(await connection.getBalance(relayer.publicKey)).to.equal(drawAmount.toNumber());
+
+		await program.methods.redeem([...secret], swapAmount)
+			.accounts({ swapAccount, initiator: alice.publicKey, redeemer: bob.publicKey }).rpc();
+		await program.methods.closeFeeEscrow()
+			.accounts({ feeEscrow, swapAccount, initiator: alice.publicKey }).rpc();
+		expect(await connection.getBalance(feeEscrow)).to.equal(0);
+	});
+});
+
+describe("Testing hash-locked payment channel between Alice and Bob", () => {
+	const depositAmount = new anchor.BN(0.5 * LAMPORTS_PER_SOL);
+	const paymentAmount = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
+	const channelExpiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const paymentExpiresInSlots = new anchor.BN(10_000 / MILLIS_PER_SLOT); // 10 secs
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const [channel,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("payment_channel"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer()], program.programId);
+
+	before(async () => {
+		const airdropSig = await connection.requestAirdrop(alice.publicKey, 1 * LAMPORTS_PER_SOL);
+		await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		await program.methods.openChannel(depositAmount, channelExpiresInSlots, bob.publicKey)
+			.accounts({ funder: alice.publicKey }).signers([alice]).rpc();
+	});
+
+	it("Test repeated payments over a single channel", async () => {
+		for (let i = 0; i < 3; i++) {
+			const secret = crypto.randomBytes(32);
+			const secretHash = [...(crypto.createHash('sha256').update(secret).digest())];
+			await program.methods.lockChannelPayment(paymentAmount, paymentExpiresInSlots, secretHash)
+				.accounts({ channel, funder: alice.publicKey }).signers([alice]).rpc();
+			await program.methods.claimChannelPayment([...secret])
+				.accounts({ channel, payee: bob.publicKey }).rpc();
+		}
+		expect(await connection.getBalance(bob.publicKey)).to.equal(paymentAmount.muln(3).toNumber());
+	});
+
+	it("Test cooperative close", async () => {
+		await program.methods.cooperativeCloseChannel(new anchor.BN(0))
+			.accounts({ channel, funder: alice.publicKey, payee: bob.publicKey }).signers([alice, bob]).rpc();
+		expect(await connection.getBalance(channel)).to.equal(0);
+	});
+});
+
+describe("Testing session keys", () => {
+	const swapAmount = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+	const expiresInSlots = new anchor.BN(60_000 / MILLIS_PER_SLOT); // 1 min
+	const alice = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bob = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const bobSessionKey = anchor.web3.Keypair.fromSeed(crypto.randomBytes(32));
+	const secretHash = [...(crypto.createHash('sha256').update(crypto.randomBytes(32)).digest())];
+	const [swapAccount,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("swap_account"), alice.publicKey.toBuffer(), bob.publicKey.toBuffer(), Buffer.from(secretHash)], program.programId);
+	const [session,] = anchor.web3.PublicKey.findProgramAddressSync(
+		[Buffer.from("session"), bob.publicKey.toBuffer(), bobSessionKey.publicKey.toBuffer()], program.programId);
+	const SESSION_SCOPE_INSTANT_REFUND = 1;
+
+	before(async () => {
+		for (const party of [alice, bob]) {
+			const airdropSig = await connection.requestAirdrop(party.publicKey, 1 * LAMPORTS_PER_SOL);
+			await connection.confirmTransaction({signature: airdropSig, ...(await connection.getLatestBlockhash())});
+		}
+		await program.methods.initiate(swapAmount, expiresInSlots, bob.publicKey, secretHash, { sha256: {} }, NO_INCENTIVES, NO_REDEEM_WINDOW)
+			.accounts({ initiator: alice.publicKey }).signers([alice]).rpc();
+		await program.methods.createSession(bobSessionKey.publicKey, SESSION_SCOPE_INSTANT_REFUND, expiresInSlots)
+			.accounts({ authority: bob.publicKey }).signers([bob]).rpc();
+	});
+
+	it("Test instant refund signed by a session key", async () => {
+		await program.methods.instantRefundWithSession()
+			.accounts({
+				swapAccount,
+				initiator: alice.publicKey,
+				session,
+				sessionKey: bobSessionKey.publicKey,
+			}).signers([bobSessionKey])
+			.rpc();
+
+		expect(await connection.getBalance(swapAccount)).to.equal(0);
+	});
+
+	it("Test session revocation", async () => {
+		await program.methods.revokeSession()
+			.accounts({ session, authority: bob.publicKey }).signers([bob]).rpc();
+		expect(await connection.getBalance(session)).to.equal(0);
+	});
 });